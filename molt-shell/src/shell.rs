@@ -1,10 +1,13 @@
+use molt::HistoryStore;
 use molt::Interp;
 use molt::ResultCode;
 use molt::Value;
 use molt::MoltList;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
+use std::cell::RefCell;
 use std::fs;
+use std::rc::Rc;
 
 /// Invokes an interactive REPL for the given interpreter, using `rustlyline` line editing.
 ///
@@ -12,19 +15,29 @@ use std::fs;
 /// the REPL, returning control to the caller.  Entering `exit` will usually cause the
 /// application to terminate (but the `exit` command can be removed or redefined by the
 /// application).
+///
+/// Each line entered is added to the interpreter's command history, which is shared with
+/// the line editor's history: the `history` command sees the lines that can be recalled.
 pub fn repl(interp: &mut Interp, prompt: &str) {
-    let mut rl = Editor::<()>::new();
+    let rl = Rc::new(RefCell::new(Editor::<()>::new()));
+    interp.set_history_store(Box::new(EditorHistory(Rc::clone(&rl))));
 
     loop {
-        let readline = rl.readline(prompt);
+        let readline = rl.borrow_mut().readline(prompt);
         match readline {
             Ok(line) => {
                 let line = line.trim();
                 if !line.is_empty() {
-                    match interp.eval(line) {
-                        Ok(value) | Err(ResultCode::Return(value)) => {
-                            rl.add_history_entry(line);
+                    interp.add_history(line);
+
+                    // A return from the top level just returns the value.
+                    let result = match interp.eval(line) {
+                        Err(ResultCode::Return(data)) => Ok(data.value().clone()),
+                        result => result,
+                    };
 
+                    match result {
+                        Ok(value) => {
                             // Don't output empty values.
                             if !value.as_string().is_empty() {
                                 println!("{}", value);
@@ -35,8 +48,8 @@ pub fn repl(interp: &mut Interp, prompt: &str) {
                         }
                         result => {
                             // Must be Break or Continue, which should have been caught
-                            // by eval(), so this should never happen.  But panicking would
-                            // be rude.
+                            // by eval() unless returned explicitly, so this should rarely
+                            // happen.  But panicking would be rude.
                             println!("Unexpected eval return: {:?}", result);
                         }
                     }
@@ -55,6 +68,31 @@ pub fn repl(interp: &mut Interp, prompt: &str) {
     }
 }
 
+/// The interpreter's command history, as stored in the REPL's line editor.
+struct EditorHistory(Rc<RefCell<Editor<()>>>);
+
+impl HistoryStore for EditorHistory {
+    fn add(&mut self, event: &str) -> bool {
+        self.0.borrow_mut().add_history_entry(event)
+    }
+
+    fn get(&self, index: usize) -> Option<String> {
+        self.0.borrow().history().get(index).cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.0.borrow().history().len()
+    }
+
+    fn set_max_len(&mut self, len: usize) {
+        self.0.borrow_mut().history_mut().set_max_len(len);
+    }
+
+    fn clear(&mut self) {
+        self.0.borrow_mut().clear_history();
+    }
+}
+
 /// Executes a script from a set of command line arguments.
 ///
 /// `args[0]` is presumed to be the name of a Molt script file, with any subsequent
@@ -69,6 +107,9 @@ pub fn repl(interp: &mut Interp, prompt: &str) {
 /// * The Molt variable `arg0` will be set to the `arg0` value.
 /// * The Molt variable `argv` will be set to the content of the `argv` array,
 ///   formatted as a Molt list.
+///
+/// If the script throws an error, its error info is written to standard error and the
+/// process exits with status 1.
 pub fn script(interp: &mut Interp, args: &[String]) {
     let arg0 = &args[0];
     let argv = &args[1..];
@@ -96,19 +137,21 @@ fn execute_script(interp: &mut Interp, script: String, arg0: &str, argv: &[Strin
     let argv: MoltList = argv.iter().map(Value::from).collect();
     let argv = molt::list_to_string(&argv);
 
-    interp.set_var("arg0", arg0);
-    interp.set_var("argv", &argv);
+    interp.set_var("arg0", arg0).expect("arg0 predefined as array!");
+    interp.set_var("argv", &argv).expect("argv predefined as array!");
+    interp.set_script_file(arg0);
 
     match interp.eval(&script) {
         Ok(_) => (),
         Err(ResultCode::Return(_)) => (),
-        Err(ResultCode::Error(msg)) => {
-            eprintln!("{}", msg);
+        Err(ResultCode::Error(data)) => {
+            eprintln!("{}", data.error_info());
+            eprintln!("    (file \"{}\" line {})", arg0, data.error_line());
             std::process::exit(1);
         }
         result => {
             // Break or Continue; should never happen, since eval() is supposed to convert
-            // these to errors.
+            // these to errors at the top level.
             panic!("Unexpected eval return: {:?}", result)
         }
     }
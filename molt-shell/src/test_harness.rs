@@ -65,6 +65,7 @@ pub fn test_harness(interp: &mut Interp, args: &[String]) {
             if parent.is_some() {
                 let _ = env::set_current_dir(parent.unwrap());
             }
+            interp.set_script_file(&args[0]);
             match interp.eval(&script) {
                 Ok(_) => (),
                 Err(ResultCode::Error(msg)) => {
@@ -155,9 +156,11 @@ impl TestInfo {
         match result {
             Ok(val) => println!("Received -ok <{}>", val),
             Err(ResultCode::Error(msg)) => println!("Received -error <{}>", msg),
-            Err(ResultCode::Return(val)) => println!("Received -return <{}>", val),
+            Err(ResultCode::Return(data)) => println!("Received -return <{}>", data.value()),
             Err(ResultCode::Break) => println!("Received -break <>"),
             Err(ResultCode::Continue) => println!("Received -continue <>"),
+            Err(ResultCode::TailCall(_)) => println!("Received -return <>"),
+            Err(ResultCode::Other(code, value)) => println!("Received -code {} <{}>", code, value),
         }
     }
 
@@ -301,7 +304,7 @@ impl TestCommand {
             }
             Err(ResultCode::Error(out)) => {
                 if info.code == Code::Error {
-                    if *out == Value::from(&info.expect) {
+                    if *out.message() == Value::from(&info.expect) {
                         ctx.num_passed += 1;
                     } else {
                         ctx.num_failed += 1;
@@ -0,0 +1,436 @@
+//! The Binary Command's Formatter and Scanner
+//!
+//! This module implements the `binary format` and `binary scan` commands, which convert
+//! between Molt values and binary data, i.e., byte arrays, according to a template.  The
+//! template is a sequence of field specifiers, each a field type optionally followed by
+//! `u`, for integer fields, and then by a count, which is a number or `*`; whitespace
+//! between specifiers is ignored.  The field types are:
+//!
+//! * `a`, `A`: a string of bytes, padded with nulls or spaces respectively.
+//! * `c`: 8-bit integers.
+//! * `s`, `S`: 16-bit integers, little-endian and big-endian respectively.
+//! * `i`, `I`: 32-bit integers, little-endian and big-endian respectively.
+//! * `w`, `W`: 64-bit integers, little-endian and big-endian respectively.
+//! * `f`, `d`: single- and double-precision floating-point numbers, in the machine's
+//!   native byte order.
+//! * `x`: null bytes, or in a scan, bytes to skip.
+//! * `X`: moves the cursor back.
+//! * `@`: moves the cursor to an absolute position.
+
+use crate::util::value_size;
+use crate::*;
+
+//--------------------------------------------------------------------------
+// Templates
+
+/// A parsed field specifier.
+struct Field {
+    /// The field type, e.g., `i`.
+    kind: char,
+
+    /// Whether integers are scanned as unsigned.
+    unsigned: bool,
+
+    /// The field's count.
+    count: Count,
+}
+
+/// The count of a field specifier.
+#[derive(Copy, Clone)]
+enum Count {
+    /// No count was given.
+    Default,
+
+    /// A numeric count.
+    Num(usize),
+
+    /// The count `*`.
+    All,
+}
+
+/// Parses the template into a list of field specifiers.
+fn parse_template(template: &str) -> Result<Vec<Field>, ResultCode> {
+    let chars: Vec<char> = template.chars().collect();
+    let mut fields = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let kind = chars[i];
+        i += 1;
+
+        if kind.is_whitespace() {
+            continue;
+        }
+
+        if !"aAcsSiIwWfdxX@".contains(kind) {
+            return molt_err!("bad field specifier \"{}\"", kind);
+        }
+
+        // NEXT, integer fields may be flagged as unsigned.
+        let unsigned = int_layout(kind).is_some() && chars.get(i) == Some(&'u');
+
+        if unsigned {
+            i += 1;
+        }
+
+        // NEXT, get the count, if any.
+        let count = if chars.get(i) == Some(&'*') {
+            i += 1;
+            Count::All
+        } else {
+            let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+            if len == 0 {
+                Count::Default
+            } else {
+                let digits: String = chars[i..i + len].iter().collect();
+                i += len;
+
+                match digits.parse() {
+                    Ok(num) => Count::Num(num),
+                    Err(_) => return molt_err!("count \"{}\" is too large", digits),
+                }
+            }
+        };
+
+        fields.push(Field { kind, unsigned, count });
+    }
+
+    Ok(fields)
+}
+
+/// Gets the width in bytes of an integer field type, and whether it's big-endian, or None
+/// if it isn't an integer field type.
+fn int_layout(kind: char) -> Option<(usize, bool)> {
+    match kind {
+        'c' => Some((1, false)),
+        's' => Some((2, false)),
+        'S' => Some((2, true)),
+        'i' => Some((4, false)),
+        'I' => Some((4, true)),
+        'w' => Some((8, false)),
+        'W' => Some((8, true)),
+        _ => None,
+    }
+}
+
+/// Gets the width in bytes of a numeric field type.
+fn num_width(kind: char) -> usize {
+    match kind {
+        'f' => 4,
+        'd' => 8,
+        _ => int_layout(kind).expect("numeric field type").0,
+    }
+}
+
+//--------------------------------------------------------------------------
+// Formatting
+
+/// Formats the arguments as binary data according to the template, as for `binary
+/// format`.  A string field takes one argument, as does a numeric field with no count; a
+/// numeric field with a count takes a list of at least that many numbers.  Fields and
+/// positions that would make the result larger than a Tcl value can be are errors.
+pub(crate) fn format(template: &str, args: &[Value]) -> Result<Vec<u8>, ResultCode> {
+    let fields = parse_template(template)?;
+    let mut args = args.iter();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut cursor = 0;
+
+    for field in &fields {
+        let bytes = match field.kind {
+            'a' | 'A' => {
+                let string = next_arg(&mut args)?.as_bytes();
+                let len = match field.count {
+                    Count::Default => 1,
+                    Count::Num(num) => value_size(num)?,
+                    Count::All => string.len(),
+                };
+                let pad = if field.kind == 'a' { 0 } else { b' ' };
+
+                let mut bytes: Vec<u8> = string.iter().copied().take(len).collect();
+                bytes.resize(len, pad);
+                bytes
+            }
+            'x' => match field.count {
+                Count::Default => vec![0],
+                Count::Num(num) => vec![0; value_size(num)?],
+                Count::All => return molt_err!("cannot use \"*\" in format string with \"x\""),
+            },
+            'X' => {
+                cursor -= match field.count {
+                    Count::Default => cursor.min(1),
+                    Count::Num(num) => num.min(cursor),
+                    Count::All => cursor,
+                };
+                continue;
+            }
+            '@' => {
+                cursor = match field.count {
+                    Count::Default => return molt_err!("missing count for \"@\" field specifier"),
+                    Count::Num(num) => value_size(num)?,
+                    Count::All => buf.len(),
+                };
+
+                if cursor > buf.len() {
+                    buf.resize(cursor, 0);
+                }
+                continue;
+            }
+            kind => {
+                let arg = next_arg(&mut args)?;
+                let mut bytes = Vec::new();
+
+                match field.count {
+                    Count::Default => encode_number(kind, arg, &mut bytes)?,
+                    count => {
+                        let list = arg.as_list()?;
+                        let num = match count {
+                            Count::Num(num) => num,
+                            _ => list.len(),
+                        };
+
+                        if num > list.len() {
+                            return molt_err!("number of elements in list does not match count");
+                        }
+
+                        for value in &list[..num] {
+                            encode_number(kind, value, &mut bytes)?;
+                        }
+                    }
+                }
+                bytes
+            }
+        };
+
+        // NEXT, write the field's bytes at the cursor, overwriting any bytes already there.
+        let end = value_size(cursor + bytes.len())?;
+
+        if end > buf.len() {
+            buf.resize(end, 0);
+        }
+        buf[cursor..end].copy_from_slice(&bytes);
+        cursor = end;
+    }
+
+    Ok(buf)
+}
+
+/// Gets the next argument for a field, or the usual error.
+fn next_arg<'a>(args: &mut std::slice::Iter<'a, Value>) -> Result<&'a Value, ResultCode> {
+    match args.next() {
+        Some(arg) => Ok(arg),
+        None => molt_err!("not enough arguments for all format specifiers"),
+    }
+}
+
+/// Appends the number to the bytes as a field of the given numeric type.  Integers too
+/// large for the field are truncated to its width.
+fn encode_number(kind: char, value: &Value, bytes: &mut Vec<u8>) -> Result<(), ResultCode> {
+    match kind {
+        'f' => bytes.extend_from_slice(&(value.as_float()? as f32).to_ne_bytes()),
+        'd' => bytes.extend_from_slice(&value.as_float()?.to_ne_bytes()),
+        _ => {
+            let (width, big_endian) = int_layout(kind).expect("integer field type");
+            let le_bytes = value.as_int()?.to_le_bytes();
+
+            if big_endian {
+                bytes.extend(le_bytes[..width].iter().rev());
+            } else {
+                bytes.extend_from_slice(&le_bytes[..width]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+//--------------------------------------------------------------------------
+// Scanning
+
+/// Scans the binary data according to the template, as for `binary scan`, returning the
+/// values of the fields, one per variable, until the data runs out.  A string field
+/// yields a byte array, as does a numeric field with no count; a numeric field with a
+/// count yields a list.  It's an error if there are more fields than variables.
+pub(crate) fn scan(data: &[u8], template: &str, num_vars: usize) -> Result<MoltList, ResultCode> {
+    let fields = parse_template(template)?;
+    let mut values = Vec::new();
+    let mut cursor = 0;
+
+    for field in &fields {
+        let remaining = data.len() - cursor;
+
+        match field.kind {
+            'x' => {
+                cursor += match field.count {
+                    Count::Default => remaining.min(1),
+                    Count::Num(num) => num.min(remaining),
+                    Count::All => remaining,
+                };
+                continue;
+            }
+            'X' => {
+                cursor -= match field.count {
+                    Count::Default => cursor.min(1),
+                    Count::Num(num) => num.min(cursor),
+                    Count::All => cursor,
+                };
+                continue;
+            }
+            '@' => {
+                cursor = match field.count {
+                    Count::Default => return molt_err!("missing count for \"@\" field specifier"),
+                    Count::Num(num) => num.min(data.len()),
+                    Count::All => data.len(),
+                };
+                continue;
+            }
+            _ => (),
+        }
+
+        if values.len() == num_vars {
+            return molt_err!("not enough arguments for all format specifiers");
+        }
+
+        let value = match field.kind {
+            'a' | 'A' => {
+                let len = match field.count {
+                    Count::Default => 1,
+                    Count::Num(num) => num,
+                    Count::All => remaining,
+                };
+
+                if len > remaining {
+                    break;
+                }
+
+                let mut bytes = &data[cursor..cursor + len];
+                cursor += len;
+
+                if field.kind == 'A' {
+                    while let Some((b' ' | 0, rest)) = bytes.split_last() {
+                        bytes = rest;
+                    }
+                }
+                Value::from_bytes(bytes.to_vec())
+            }
+            kind => {
+                let width = num_width(kind);
+                let num = match field.count {
+                    Count::Default => 1,
+                    Count::Num(num) => num,
+                    Count::All => remaining / width,
+                };
+
+                if num * width > remaining {
+                    break;
+                }
+
+                let mut numbers: MoltList = data[cursor..cursor + num * width]
+                    .chunks(width)
+                    .map(|chunk| decode_number(kind, field.unsigned, chunk))
+                    .collect();
+                cursor += num * width;
+
+                match field.count {
+                    Count::Default => numbers.remove(0),
+                    _ => Value::from(numbers),
+                }
+            }
+        };
+
+        values.push(value);
+    }
+
+    Ok(values)
+}
+
+/// Decodes the bytes of a field of the given numeric type.  Integers are sign-extended
+/// unless the field is unsigned.
+fn decode_number(kind: char, unsigned: bool, bytes: &[u8]) -> Value {
+    match kind {
+        'f' => {
+            let mut buf = [0; 4];
+            buf.copy_from_slice(bytes);
+            Value::from(f32::from_ne_bytes(buf) as MoltFloat)
+        }
+        'd' => {
+            let mut buf = [0; 8];
+            buf.copy_from_slice(bytes);
+            Value::from(f64::from_ne_bytes(buf))
+        }
+        _ => {
+            let (width, big_endian) = int_layout(kind).expect("integer field type");
+            let mut buf = [0; 8];
+            buf[..width].copy_from_slice(bytes);
+
+            if big_endian {
+                buf[..width].reverse();
+            }
+
+            let raw = u64::from_le_bytes(buf);
+            let shift = 64 - 8 * width as u32;
+
+            if unsigned {
+                Value::from(raw as MoltInt)
+            } else {
+                Value::from(((raw << shift) as MoltInt) >> shift)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(template: &str, args: &[&str]) -> Vec<u8> {
+        let args: Vec<Value> = args.iter().map(|a| Value::from(*a)).collect();
+        format(template, &args).unwrap()
+    }
+
+    fn sc(data: &[u8], template: &str) -> Vec<String> {
+        scan(data, template, 10).unwrap().iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(fmt("a", &["abc"]), b"a");
+        assert_eq!(fmt("a5A5", &["ab", "cd"]), b"ab\0\0\0cd   ");
+        assert_eq!(fmt("a*", &["abc"]), b"abc");
+        assert_eq!(fmt("c", &["200"]), vec![200]);
+        assert_eq!(fmt("s S", &["258", "258"]), vec![2, 1, 1, 2]);
+        assert_eq!(fmt("i", &["-2"]), vec![0xFE, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(fmt("I", &["16909060"]), vec![1, 2, 3, 4]);
+        assert_eq!(fmt("W", &["1"]), vec![0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(fmt("c2", &["1 2 3"]), vec![1, 2]);
+        assert_eq!(fmt("c*", &["1 2 3"]), vec![1, 2, 3]);
+        assert_eq!(fmt("d", &["1.5"]), 1.5f64.to_ne_bytes().to_vec());
+        assert_eq!(fmt("x2c", &["1"]), vec![0, 0, 1]);
+        assert_eq!(fmt("a3X2c", &["abc", "65"]), b"aAc");
+        assert_eq!(fmt("a3@5c", &["abc", "65"]), b"abc\0\0A");
+        assert_eq!(fmt("a3@1c@*c", &["abc", "65", "66"]), b"aAcB");
+
+        let args = [Value::from("1 2")];
+        assert_eq!(
+            format("c3", &args),
+            molt_err!("number of elements in list does not match count")
+        );
+        assert_eq!(format("q", &args), molt_err!("bad field specifier \"q\""));
+        assert_eq!(format("cc", &args[..0]), molt_err!("not enough arguments for all format specifiers"));
+    }
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(sc(b"abc", "a a2"), vec!["a", "bc"]);
+        assert_eq!(sc(b"ab  \0", "A*"), vec!["ab"]);
+        assert_eq!(sc(&[0xFE, 0xFF, 0xFE], "c cu"), vec!["-2", "255"]);
+        assert_eq!(sc(&[2, 1, 1, 2], "s S"), vec!["258", "258"]);
+        assert_eq!(sc(&[0xFF, 0xFF, 0xFF, 0xFF], "iu"), vec!["4294967295"]);
+        assert_eq!(sc(&[1, 2, 3, 4], "c* c"), vec!["1 2 3 4"]);
+        assert_eq!(sc(&[1, 2, 3, 4], "c1 x2 c"), vec!["1", "4"]);
+        assert_eq!(sc(&[1, 2, 3], "c3 X2 c @0 c"), vec!["1 2 3", "2", "1"]);
+        assert_eq!(sc(&[1, 2, 3], "s s"), vec!["513"]);
+        assert_eq!(sc(&1.5f64.to_ne_bytes(), "d"), vec!["1.5"]);
+        assert_eq!(scan(b"ab", "a a", 1), molt_err!("not enough arguments for all format specifiers"));
+    }
+}
@@ -0,0 +1,1202 @@
+//! Channels
+//!
+//! This module defines the interpreter's channels: the open files and standard streams
+//! that scripts read and write with commands like `gets` and `puts`, named by handles
+//! like `stdout` or `file3`.  A channel buffers its input, so that `gets` can read a line
+//! at a time, and its output; the underlying stream provides only the capabilities it
+//! has, e.g., `stdin` can be read but not written.
+//!
+//! A channel converts between the bytes of its stream and the characters of Molt strings
+//! according to its encoding, and between the stream's line endings and Molt's newlines
+//! according to its translation modes; `fconfigure` sets these.  The input buffer holds
+//! the stream's raw bytes, so that the buffer's size is always the distance the channel
+//! has read ahead of the script.
+
+use crate::socket::Server;
+use crate::types::*;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpStream;
+
+/// The default number of bytes a channel reads at a time, and the number of bytes of output
+/// it buffers before writing them to the stream.
+const BUFFER_SIZE: usize = 4096;
+
+/// The largest buffer size `fconfigure -buffersize` accepts.
+pub(crate) const MAX_BUFFER_SIZE: usize = 1 << 20;
+
+/// When a channel writes its buffered output to its stream.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Buffering {
+    /// When the buffer is full.
+    Full,
+
+    /// When a newline is written.
+    Line,
+
+    /// Whenever anything is written.
+    None,
+}
+
+impl Buffering {
+    /// Gets the buffering mode with the given `fconfigure` name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(Buffering::Full),
+            "line" => Some(Buffering::Line),
+            "none" => Some(Buffering::None),
+            _ => None,
+        }
+    }
+
+    /// Gets the mode's `fconfigure` name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Buffering::Full => "full",
+            Buffering::Line => "line",
+            Buffering::None => "none",
+        }
+    }
+}
+
+/// How a channel, or the `encoding` command, converts between bytes and characters.
+/// Invalid input is decoded as U+FFFD.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Encoding {
+    /// UTF-8.
+    Utf8,
+
+    /// ISO 8859-1: each byte is the character with the same code; characters that
+    /// can't be encoded are written as `?`.
+    Iso8859_1,
+
+    /// 7-bit ASCII; characters that can't be encoded are written as `?`.
+    Ascii,
+
+    /// UTF-16, little-endian.
+    Utf16Le,
+
+    /// UTF-16, big-endian.
+    Utf16Be,
+
+    /// Binary data: each byte is the character with the same code; each character is
+    /// written as the low byte of its code.
+    Binary,
+}
+
+impl Encoding {
+    /// The character encodings, as listed by `encoding names`.  The `binary` encoding is
+    /// a channel mode rather than a character encoding, and so isn't listed.
+    pub const NAMES: [&'static str; 5] = ["utf-8", "iso8859-1", "ascii", "utf-16le", "utf-16be"];
+
+    /// Gets the encoding with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "utf-8" => Some(Encoding::Utf8),
+            "iso8859-1" => Some(Encoding::Iso8859_1),
+            "ascii" => Some(Encoding::Ascii),
+            "utf-16le" => Some(Encoding::Utf16Le),
+            "utf-16be" => Some(Encoding::Utf16Be),
+            "binary" => Some(Encoding::Binary),
+            _ => None,
+        }
+    }
+
+    /// Gets the encoding's name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Iso8859_1 => "iso8859-1",
+            Encoding::Ascii => "ascii",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Binary => "binary",
+        }
+    }
+
+    /// Appends the encoded string to the buffer.
+    pub fn encode(self, string: &str, buf: &mut Vec<u8>) {
+        match self {
+            Encoding::Utf8 => buf.extend_from_slice(string.as_bytes()),
+            Encoding::Iso8859_1 => {
+                buf.extend(string.chars().map(|c| if (c as u32) < 256 { c as u8 } else { b'?' }))
+            }
+            Encoding::Ascii => {
+                buf.extend(string.chars().map(|c| if c.is_ascii() { c as u8 } else { b'?' }))
+            }
+            Encoding::Utf16Le => string.encode_utf16().for_each(|u| buf.extend(&u.to_le_bytes())),
+            Encoding::Utf16Be => string.encode_utf16().for_each(|u| buf.extend(&u.to_be_bytes())),
+            Encoding::Binary => buf.extend(string.chars().map(|c| c as u32 as u8)),
+        }
+    }
+
+    /// Decodes all of the bytes as a string.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        let mut string = String::new();
+        let mut pos = 0;
+
+        while let Some((c, next)) = self.decode_char(bytes, pos, true) {
+            string.push(c);
+            pos = next;
+        }
+
+        string
+    }
+
+    /// Decodes the character at the given position in the bytes, and returns it and the
+    /// position of the following character.  Returns `None` if the bytes don't contain a
+    /// complete character at that position; but if they're the last of the input, an
+    /// incomplete character is decoded as U+FFFD.
+    fn decode_char(self, bytes: &[u8], pos: usize, last: bool) -> Option<(char, usize)> {
+        let byte = *bytes.get(pos)?;
+        let incomplete = || if last { Some(('\u{FFFD}', bytes.len())) } else { None };
+
+        match self {
+            Encoding::Iso8859_1 | Encoding::Binary => Some((byte as char, pos + 1)),
+            Encoding::Ascii if byte.is_ascii() => Some((byte as char, pos + 1)),
+            Encoding::Ascii => Some(('\u{FFFD}', pos + 1)),
+            Encoding::Utf8 => {
+                let len = match byte {
+                    0x00..=0x7F => 1,
+                    0xC0..=0xDF => 2,
+                    0xE0..=0xEF => 3,
+                    0xF0..=0xF7 => 4,
+                    _ => return Some(('\u{FFFD}', pos + 1)),
+                };
+
+                if pos + len > bytes.len() {
+                    return incomplete();
+                }
+
+                match std::str::from_utf8(&bytes[pos..pos + len]) {
+                    Ok(string) => string.chars().next().map(|c| (c, pos + len)),
+                    Err(_) => Some(('\u{FFFD}', pos + 1)),
+                }
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let unit = |pos: usize| {
+                    let pair = [*bytes.get(pos)?, *bytes.get(pos + 1)?];
+
+                    if self == Encoding::Utf16Le {
+                        Some(u16::from_le_bytes(pair))
+                    } else {
+                        Some(u16::from_be_bytes(pair))
+                    }
+                };
+
+                let first = match unit(pos) {
+                    Some(first) => first,
+                    None => return incomplete(),
+                };
+
+                // NEXT, a high surrogate must be followed by a low surrogate.
+                if !(0xD800..0xDC00).contains(&first) {
+                    let c = char::from_u32(first as u32).unwrap_or('\u{FFFD}');
+                    return Some((c, pos + 2));
+                }
+
+                match unit(pos + 2) {
+                    Some(second @ 0xDC00..=0xDFFF) => {
+                        let code = 0x10000 + ((first as u32 - 0xD800) << 10) + (second as u32 - 0xDC00);
+                        Some((char::from_u32(code).unwrap_or('\u{FFFD}'), pos + 4))
+                    }
+                    Some(_) => Some(('\u{FFFD}', pos + 2)),
+                    None => incomplete(),
+                }
+            }
+        }
+    }
+}
+
+/// How a channel translates line endings.  On input, the stream's line endings are
+/// translated to newlines; on output, newlines are translated to the stream's line
+/// endings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Translation {
+    /// On input, any of `\n`, `\r\n`, and `\r` ends a line.  On output, the same as the
+    /// platform's native line ending.
+    Auto,
+
+    /// Lines end with `\r`.
+    Cr,
+
+    /// Lines end with `\r\n`.
+    Crlf,
+
+    /// Lines end with `\n`, i.e., no translation.
+    Lf,
+}
+
+impl Translation {
+    /// The platform's native line ending.
+    #[cfg(windows)]
+    pub const NATIVE: Translation = Translation::Crlf;
+
+    /// The platform's native line ending.
+    #[cfg(not(windows))]
+    pub const NATIVE: Translation = Translation::Lf;
+
+    /// Gets the translation mode with the given `fconfigure` name.  The names `binary`
+    /// and `platform` are handled by the caller.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Translation::Auto),
+            "cr" => Some(Translation::Cr),
+            "crlf" => Some(Translation::Crlf),
+            "lf" => Some(Translation::Lf),
+            _ => None,
+        }
+    }
+
+    /// Gets the mode's `fconfigure` name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Translation::Auto => "auto",
+            Translation::Cr => "cr",
+            Translation::Crlf => "crlf",
+            Translation::Lf => "lf",
+        }
+    }
+}
+
+/// A condition of a channel for which a script can register a handler with `fileevent`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum FileEvent {
+    /// The channel has input available, or is at the end of its stream.
+    Readable,
+
+    /// The channel can accept output.
+    Writable,
+}
+
+impl FileEvent {
+    /// Gets the event with the given `fileevent` name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "readable" => Some(FileEvent::Readable),
+            "writable" => Some(FileEvent::Writable),
+            _ => None,
+        }
+    }
+}
+
+/// The underlying stream of a channel.  Each capability is optional; a stream that can't
+/// be read, e.g., returns `None` from `reader`.
+pub(crate) trait Stream {
+    /// Gets the stream as a reader, if it can be read.
+    fn reader(&mut self) -> Option<&mut dyn Read> {
+        None
+    }
+
+    /// Gets the stream as a writer, if it can be written.
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        None
+    }
+
+    /// Gets the stream as a seeker, if it supports random access.
+    fn seeker(&mut self) -> Option<&mut dyn Seek> {
+        None
+    }
+
+    /// Puts the stream into blocking or non-blocking mode, for streams that support
+    /// non-blocking I/O; others ignore the request.
+    fn set_blocking(&mut self, _blocking: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Whether the stream can be read or written without blocking.  Streams that are
+    /// always ready, e.g., files, needn't implement this.
+    fn is_ready(&mut self, _event: FileEvent) -> bool {
+        true
+    }
+
+    /// Gets the stream as a server socket, if it is one.
+    fn server(&mut self) -> Option<&mut Server> {
+        None
+    }
+
+    /// Gets the local address of the stream, if it's a socket.
+    fn sockname(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Gets the address of the stream's peer, if it's a connected socket.
+    fn peername(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Finishes the stream when its channel is closed, after the channel's output has been
+    /// written, returning the error `close` should report, if any.  A command pipeline,
+    /// e.g., waits for its programs to exit.  Most streams needn't implement this.
+    fn finish(&mut self) -> Result<(), ResultCode> {
+        Ok(())
+    }
+}
+
+impl Stream for File {
+    fn reader(&mut self) -> Option<&mut dyn Read> {
+        Some(self)
+    }
+
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        Some(self)
+    }
+
+    fn seeker(&mut self) -> Option<&mut dyn Seek> {
+        Some(self)
+    }
+}
+
+impl Stream for io::Stdin {
+    fn reader(&mut self) -> Option<&mut dyn Read> {
+        Some(self)
+    }
+
+    #[cfg(unix)]
+    fn is_ready(&mut self, event: FileEvent) -> bool {
+        use std::os::unix::io::AsRawFd;
+        poll_fd(self.as_raw_fd(), event)
+    }
+}
+
+/// Determines whether the file descriptor is ready for reading or writing, without
+/// waiting.  Errors are reported as ready, so that the script sees them when it reads
+/// or writes.
+#[cfg(unix)]
+pub(crate) fn poll_fd(fd: std::os::unix::io::RawFd, event: FileEvent) -> bool {
+    let events = match event {
+        FileEvent::Readable => libc::POLLIN,
+        FileEvent::Writable => libc::POLLOUT,
+    };
+    let mut pollfd = libc::pollfd { fd, events, revents: 0 };
+
+    unsafe { libc::poll(&mut pollfd, 1, 0) != 0 }
+}
+
+impl Stream for io::Stdout {
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        Some(self)
+    }
+}
+
+impl Stream for io::Stderr {
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        Some(self)
+    }
+}
+
+/// A writer supplied by the client, e.g., to capture the standard output.
+struct Output(Box<dyn Write>);
+
+impl Stream for Output {
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        Some(&mut self.0)
+    }
+}
+
+/// An open channel.
+pub(crate) struct Channel {
+    /// The underlying stream.
+    stream: Box<dyn Stream>,
+
+    /// Whether the channel was opened for reading.
+    readable: bool,
+
+    /// Whether the channel was opened for writing.
+    writable: bool,
+
+    /// Bytes read from the stream but not yet consumed.
+    input: Vec<u8>,
+
+    /// Bytes written to the channel but not yet to the stream.
+    output: Vec<u8>,
+
+    /// When output is written to the stream.
+    buffering: Buffering,
+
+    /// The number of bytes to read at a time, and to buffer before writing.
+    buffer_size: usize,
+
+    /// Whether the stream is in blocking mode.
+    blocking: bool,
+
+    /// The encoding of the stream's characters.
+    encoding: Encoding,
+
+    /// The line endings of the stream's input.
+    input_translation: Translation,
+
+    /// The line endings of the stream's output; never `Auto`.
+    output_translation: Translation,
+
+    /// Whether the most recent read reached the end of the stream.
+    eof: bool,
+
+    /// The `fileevent` handlers for when the channel is readable and writable.
+    readable_handler: Option<Value>,
+    writable_handler: Option<Value>,
+
+    /// The ids of the processes in the command pipeline the channel reads or writes, if
+    /// any, as returned by `pid`.
+    pids: Vec<u32>,
+}
+
+impl Channel {
+    /// Creates a channel on the given stream, which is readable, writable, or both.  Its
+    /// output is fully buffered.
+    pub fn new(stream: Box<dyn Stream>, readable: bool, writable: bool) -> Self {
+        Self {
+            stream,
+            readable,
+            writable,
+            input: Vec::new(),
+            output: Vec::new(),
+            buffering: Buffering::Full,
+            buffer_size: BUFFER_SIZE,
+            blocking: true,
+            encoding: Encoding::Utf8,
+            input_translation: Translation::Auto,
+            output_translation: Translation::NATIVE,
+            eof: false,
+            readable_handler: None,
+            writable_handler: None,
+            pids: Vec::new(),
+        }
+    }
+
+    /// Creates a channel with the given buffering, as for the standard streams.
+    fn with_buffering(
+        stream: Box<dyn Stream>,
+        readable: bool,
+        writable: bool,
+        buffering: Buffering,
+    ) -> Self {
+        let mut channel = Self::new(stream, readable, writable);
+        channel.buffering = buffering;
+        channel
+    }
+
+    /// Replaces the channel's stream, first writing any output buffered for the old one.
+    fn set_stream(&mut self, stream: Box<dyn Stream>) -> io::Result<()> {
+        let result = self.flush();
+        self.stream = stream;
+        result
+    }
+
+    /// Whether the channel was opened for reading.
+    pub fn is_readable(&self) -> bool {
+        self.readable
+    }
+
+    /// Whether the channel was opened for writing.
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Whether the most recent read reached the end of the stream.
+    pub fn eof(&self) -> bool {
+        self.eof
+    }
+
+    /// Gets the channel's buffering mode.
+    pub fn buffering(&self) -> Buffering {
+        self.buffering
+    }
+
+    /// Sets the channel's buffering mode.
+    pub fn set_buffering(&mut self, buffering: Buffering) {
+        self.buffering = buffering;
+    }
+
+    /// Gets the channel's buffer size in bytes.
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Sets the channel's buffer size in bytes, which must be at least 1.
+    pub fn set_buffer_size(&mut self, size: usize) {
+        self.buffer_size = size.clamp(1, MAX_BUFFER_SIZE);
+    }
+
+    /// Whether the channel is in blocking mode.
+    pub fn blocking(&self) -> bool {
+        self.blocking
+    }
+
+    /// Puts the channel into blocking or non-blocking mode.
+    pub fn set_blocking(&mut self, blocking: bool) -> io::Result<()> {
+        self.stream.set_blocking(blocking)?;
+        self.blocking = blocking;
+        Ok(())
+    }
+
+    /// Gets the channel's encoding.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Sets the channel's encoding.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// Gets the channel's input translation mode.
+    pub fn input_translation(&self) -> Translation {
+        self.input_translation
+    }
+
+    /// Gets the channel's output translation mode.
+    pub fn output_translation(&self) -> Translation {
+        self.output_translation
+    }
+
+    /// Sets the channel's translation modes for input and output; `Auto` output means
+    /// the platform's native line ending.
+    pub fn set_translation(&mut self, input: Translation, output: Translation) {
+        self.input_translation = input;
+        self.output_translation = match output {
+            Translation::Auto => Translation::NATIVE,
+            _ => output,
+        };
+    }
+
+    /// Reads the next line, without its newline.  Returns `None` if the end of the stream
+    /// has been reached and there is no more input, or if the stream is non-blocking and
+    /// a complete line isn't yet available.
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        self.flush()?;
+        let mut line = String::new();
+        let mut pos = 0;
+
+        loop {
+            match self.decode_char(pos) {
+                Some(('\n', next)) => {
+                    self.input.drain(..next);
+                    return Ok(Some(line));
+                }
+                Some((c, next)) => {
+                    line.push(c);
+                    pos = next;
+                }
+                None => {
+                    if !self.fill()? {
+                        // An incomplete line is left buffered until the rest of it is
+                        // available, unless there's no more to come.
+                        if !self.eof {
+                            return Ok(None);
+                        }
+
+                        self.input.drain(..pos);
+                        return Ok(if pos == 0 { None } else { Some(line) });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads the remainder of the stream, or, if it's non-blocking, all of the input that
+    /// is available.
+    pub fn read_to_end(&mut self) -> io::Result<String> {
+        self.flush()?;
+
+        while self.fill()? {}
+
+        Ok(self.decode_all())
+    }
+
+    /// Reads up to the given number of characters, fewer only if the end of the stream
+    /// is reached or, if it's non-blocking, no more input is available.
+    pub fn read_chars(&mut self, count: usize) -> io::Result<String> {
+        self.flush()?;
+        let mut string = String::new();
+        let mut pos = 0;
+        let mut n = 0;
+
+        while n < count {
+            match self.decode_char(pos) {
+                Some((c, next)) => {
+                    string.push(c);
+                    pos = next;
+                    n += 1;
+                }
+                None => {
+                    if !self.fill()? {
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.input.drain(..pos);
+        Ok(string)
+    }
+
+    /// Writes the string to the channel, translating its newlines.
+    pub fn write_str(&mut self, string: &str) -> io::Result<()> {
+        self.discard_input()?;
+
+        match self.output_translation {
+            Translation::Cr => self.encoding.encode(&string.replace('\n', "\r"), &mut self.output),
+            Translation::Crlf => self.encoding.encode(&string.replace('\n', "\r\n"), &mut self.output),
+            _ => self.encoding.encode(string, &mut self.output),
+        }
+
+        let flush = match self.buffering {
+            Buffering::Full => self.output.len() >= self.buffer_size,
+            Buffering::Line => string.contains('\n') || self.output.len() >= self.buffer_size,
+            Buffering::None => true,
+        };
+
+        if flush {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes any buffered output to the stream.  If the stream is non-blocking, output
+    /// that it can't yet accept remains buffered.  On error, the buffered output is
+    /// discarded.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.output.is_empty() {
+            return Ok(());
+        }
+
+        let writer = match self.stream.writer() {
+            Some(writer) => writer,
+            None => {
+                self.output.clear();
+                return Err(io::Error::from(io::ErrorKind::Unsupported));
+            }
+        };
+
+        while !self.output.is_empty() {
+            match writer.write(&self.output) {
+                Ok(0) => {
+                    self.output.clear();
+                    return Err(io::Error::from(io::ErrorKind::WriteZero));
+                }
+                Ok(n) => {
+                    self.output.drain(..n);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => {
+                    self.output.clear();
+                    return Err(e);
+                }
+            }
+        }
+
+        writer.flush()
+    }
+
+    /// Closes the channel, writing any buffered output; if the stream is non-blocking, it
+    /// is first put into blocking mode, so that none of the output is lost.
+    pub fn close(&mut self) -> io::Result<()> {
+        if !self.blocking {
+            self.set_blocking(true)?;
+        }
+
+        self.flush()
+    }
+
+    /// Finishes the channel's stream once the channel has been closed; see
+    /// `Stream::finish`.
+    pub fn finish(&mut self) -> Result<(), ResultCode> {
+        self.stream.finish()
+    }
+
+    /// Gets the channel's `fileevent` handler for the event, if any.
+    pub fn handler(&self, event: FileEvent) -> Option<&Value> {
+        match event {
+            FileEvent::Readable => self.readable_handler.as_ref(),
+            FileEvent::Writable => self.writable_handler.as_ref(),
+        }
+    }
+
+    /// Sets or, given `None`, removes the channel's `fileevent` handler for the event.
+    pub fn set_handler(&mut self, event: FileEvent, script: Option<Value>) {
+        match event {
+            FileEvent::Readable => self.readable_handler = script,
+            FileEvent::Writable => self.writable_handler = script,
+        }
+    }
+
+    /// Gets the ids of the processes in the channel's command pipeline; empty if the
+    /// channel isn't a command pipeline.
+    pub fn pids(&self) -> &[u32] {
+        &self.pids
+    }
+
+    /// Sets the ids of the processes in the channel's command pipeline.
+    pub fn set_pids(&mut self, pids: Vec<u32>) {
+        self.pids = pids;
+    }
+
+    /// Whether the channel can be read or written without blocking.  A channel is
+    /// readable if it has buffered input or is at the end of its stream.
+    pub fn is_ready(&mut self, event: FileEvent) -> bool {
+        match event {
+            FileEvent::Readable => {
+                self.readable && (!self.input.is_empty() || self.eof || self.stream.is_ready(event))
+            }
+            FileEvent::Writable => self.writable && self.stream.is_ready(event),
+        }
+    }
+
+    /// Gets the channel as a server socket, if it is one.
+    pub fn server(&mut self) -> Option<&mut Server> {
+        self.stream.server()
+    }
+
+    /// Gets the local address of the channel's stream, if it's a socket.
+    pub fn sockname(&self) -> Option<SocketAddr> {
+        self.stream.sockname()
+    }
+
+    /// Gets the address of the channel's peer, if its stream is a connected socket.
+    pub fn peername(&self) -> Option<SocketAddr> {
+        self.stream.peername()
+    }
+
+    /// Whether the channel's stream supports random access.
+    pub fn is_seekable(&mut self) -> bool {
+        self.stream.seeker().is_some()
+    }
+
+    /// Moves the channel's position, writing any buffered output and discarding any
+    /// buffered input; a `SeekFrom::Current` offset is relative to the script's position,
+    /// not the stream's.  Returns the new position.
+    pub fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if !self.is_seekable() {
+            return Err(illegal_seek());
+        }
+
+        self.flush()?;
+
+        let pos = match pos {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - self.input.len() as i64),
+            _ => pos,
+        };
+
+        let seeker = self.stream.seeker().ok_or_else(illegal_seek)?;
+        let result = seeker.seek(pos)?;
+        self.input.clear();
+        self.eof = false;
+        Ok(result)
+    }
+
+    /// Gets the channel's position as a byte offset from the start of the stream,
+    /// accounting for buffered input and output, or `None` if the stream isn't seekable.
+    pub fn tell(&mut self) -> io::Result<Option<u64>> {
+        let buffered = self.output.len() as i64 - self.input.len() as i64;
+
+        match self.stream.seeker() {
+            Some(seeker) => Ok(Some((seeker.stream_position()? as i64 + buffered) as u64)),
+            None => Ok(None),
+        }
+    }
+
+    /// Removes and decodes the complete characters in the input buffer.
+    fn decode_all(&mut self) -> String {
+        let mut string = String::new();
+        let mut pos = 0;
+
+        while let Some((c, next)) = self.decode_char(pos) {
+            string.push(c);
+            pos = next;
+        }
+
+        self.input.drain(..pos);
+        string
+    }
+
+    /// Decodes the character at the given position in the input buffer, translating line
+    /// endings, and returns it and the position of the following character.  Returns `None`
+    /// if the buffer doesn't contain a complete character at that position; at the end of
+    /// the stream, an incomplete character is decoded as U+FFFD.
+    fn decode_char(&self, pos: usize) -> Option<(char, usize)> {
+        let (c, next) = self.encoding.decode_char(&self.input, pos, self.eof)?;
+
+        if c != '\r' {
+            return Some((c, next));
+        }
+
+        let following = self.encoding.decode_char(&self.input, next, self.eof);
+
+        match self.input_translation {
+            Translation::Lf => Some(('\r', next)),
+            Translation::Cr => Some(('\n', next)),
+            _ => match following {
+                Some(('\n', after)) => Some(('\n', after)),
+                // A "\r" at the end of the buffer might be followed by "\n".
+                None if !self.eof => None,
+                _ if self.input_translation == Translation::Crlf => Some(('\r', next)),
+                _ => Some(('\n', next)),
+            },
+        }
+    }
+
+    /// Reads more input from the stream into the buffer, returning false at the end of
+    /// the stream or, if it's non-blocking, when no input is available.
+    fn fill(&mut self) -> io::Result<bool> {
+        let reader = match self.stream.reader() {
+            Some(reader) => reader,
+            None => return Err(io::Error::from(io::ErrorKind::Unsupported)),
+        };
+
+        let mut buf = vec![0; self.buffer_size];
+
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => {
+                    self.eof = true;
+                    return Ok(false);
+                }
+                Ok(n) => {
+                    self.eof = false;
+                    self.input.extend_from_slice(&buf[..n]);
+                    return Ok(true);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    return Ok(false);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Before writing, discards any input that has been read ahead, moving the stream's
+    /// position back to the first unread byte so that the output goes where the script
+    /// expects.  Streams that aren't seekable, e.g., sockets, have separate input and
+    /// output, and so keep their input.
+    fn discard_input(&mut self) -> io::Result<()> {
+        if self.input.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(seeker) = self.stream.seeker() {
+            seeker.seek(SeekFrom::Current(-(self.input.len() as i64)))?;
+            self.input.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// The error for seeking on a stream that doesn't support it.
+fn illegal_seek() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "illegal seek")
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// The interpreter's open channels, by name.
+pub(crate) struct ChannelTable {
+    /// The number of the next channel name.
+    next_id: usize,
+
+    /// The open channels.
+    channels: HashMap<String, Channel>,
+
+    /// The channels whose `fileevent` handlers were ready when last polled, and have yet
+    /// to be called.
+    ready: VecDeque<(String, FileEvent)>,
+}
+
+impl Default for ChannelTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelTable {
+    /// Creates a channel table containing the standard channels, `stdin`, `stdout`, and
+    /// `stderr`.
+    pub fn new() -> Self {
+        let mut channels = HashMap::new();
+
+        let stdin = Channel::with_buffering(Box::new(io::stdin()), true, false, Buffering::Line);
+        let stdout = Channel::with_buffering(Box::new(io::stdout()), false, true, Buffering::Line);
+        let stderr = Channel::with_buffering(Box::new(io::stderr()), false, true, Buffering::None);
+
+        channels.insert("stdin".into(), stdin);
+        channels.insert("stdout".into(), stdout);
+        channels.insert("stderr".into(), stderr);
+
+        // The standard channels are conventionally file descriptors 0 through 2.
+        Self { next_id: 3, channels, ready: VecDeque::new() }
+    }
+
+    /// Adds the channel to the table, returning its new name, the prefix followed by a
+    /// unique number, e.g., "file3".
+    pub fn add(&mut self, prefix: &str, channel: Channel) -> String {
+        let name = format!("{}{}", prefix, self.next_id);
+        self.next_id += 1;
+        self.channels.insert(name.clone(), channel);
+        name
+    }
+
+    /// Directs the named standard output channel, `stdout` or `stderr`, to the writer,
+    /// first writing any output buffered for its old stream.  The channel keeps its
+    /// configuration; if it has been closed, it's reopened with the standard one.  Errors
+    /// writing the old stream's output are ignored.
+    pub fn set_output(&mut self, name: &str, writer: Box<dyn Write>) {
+        let stream = Box::new(Output(writer));
+
+        match self.channels.get_mut(name) {
+            Some(channel) => {
+                let _ = channel.set_stream(stream);
+            }
+            None => {
+                let buffering = if name == "stderr" { Buffering::None } else { Buffering::Line };
+                let channel = Channel::with_buffering(stream, false, true, buffering);
+                self.channels.insert(name.into(), channel);
+            }
+        }
+    }
+
+    /// Gets the named channel, if it's open.
+    pub fn get(&mut self, name: &str) -> Option<&mut Channel> {
+        self.channels.get_mut(name)
+    }
+
+    /// Removes the named channel from the table, returning it.
+    pub fn remove(&mut self, name: &str) -> Option<Channel> {
+        self.channels.remove(name)
+    }
+
+    /// Accepts a pending connection on any of the server sockets, returning the server's
+    /// command prefix, the new connection, and the address of its peer.
+    pub fn accept(&mut self) -> Option<(MoltList, TcpStream, SocketAddr)> {
+        self.channels.values_mut().find_map(|channel| {
+            let server = channel.server()?;
+            let (stream, addr) = server.accept()?;
+            Some((server.command().clone(), stream, addr))
+        })
+    }
+
+    /// Gets the next `fileevent` handler that is ready to be called, and the name of its
+    /// channel and its event.  The channels are polled only once all of the handlers found
+    /// ready by the previous poll have been called, so that each gets its turn.
+    pub fn ready_handler(&mut self) -> Option<(String, FileEvent, Value)> {
+        // If all of the handlers still pending have been removed, poll again.
+        for _ in 0..2 {
+            if self.ready.is_empty() {
+                self.poll_handlers();
+            }
+
+            // A handler may have been removed, or its channel closed, by an earlier handler.
+            while let Some((name, event)) = self.ready.pop_front() {
+                if let Some(script) = self.channels.get(&name).and_then(|c| c.handler(event)) {
+                    return Some((name, event, script.clone()));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Gets the number of `fileevent` handlers that are ready to be called, polling the
+    /// channels if none are pending.
+    pub fn ready_len(&mut self) -> usize {
+        if self.ready.is_empty() {
+            self.poll_handlers();
+        }
+
+        self.ready.len()
+    }
+
+    /// Finds the channels whose handlers are ready, in order by name.
+    fn poll_handlers(&mut self) {
+        let mut names: Vec<&String> = self.channels.keys().collect();
+        names.sort();
+
+        for name in names {
+            for event in &[FileEvent::Readable, FileEvent::Writable] {
+                self.ready.push_back((name.clone(), *event));
+            }
+        }
+
+        let channels = &mut self.channels;
+        self.ready.retain(|(name, event)| {
+            let channel = channels.get_mut(name).unwrap();
+            channel.handler(*event).is_some() && channel.is_ready(*event)
+        });
+    }
+
+    /// Whether any of the channels awaits events: a server socket awaiting connections,
+    /// or a channel with a `fileevent` handler.
+    pub fn awaits_events(&mut self) -> bool {
+        self.channels.values_mut().any(|channel| {
+            channel.server().is_some()
+                || channel.readable_handler.is_some()
+                || channel.writable_handler.is_some()
+        })
+    }
+
+    /// Writes any buffered output of all channels to their streams, ignoring errors.
+    pub fn flush_all(&mut self) {
+        for channel in self.channels.values_mut() {
+            let _ = channel.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-memory stream for testing.
+    struct Bytes(io::Cursor<Vec<u8>>);
+
+    impl Stream for Bytes {
+        fn reader(&mut self) -> Option<&mut dyn Read> {
+            Some(&mut self.0)
+        }
+
+        fn seeker(&mut self) -> Option<&mut dyn Seek> {
+            Some(&mut self.0)
+        }
+    }
+
+    fn channel(input: &str) -> Channel {
+        Channel::new(Box::new(Bytes(io::Cursor::new(input.as_bytes().to_vec()))), true, false)
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut chan = channel("abc\n\ndef");
+
+        assert_eq!(chan.read_line().unwrap(), Some("abc".into()));
+        assert_eq!(chan.read_line().unwrap(), Some("".into()));
+        assert!(!chan.eof());
+        assert_eq!(chan.read_line().unwrap(), Some("def".into()));
+        assert!(chan.eof());
+        assert_eq!(chan.read_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_chars() {
+        let mut chan = channel("h\u{e9}llo w\u{f6}rld");
+
+        assert_eq!(chan.read_chars(2).unwrap(), "h\u{e9}");
+        assert_eq!(chan.read_chars(0).unwrap(), "");
+        assert_eq!(chan.read_to_end().unwrap(), "llo w\u{f6}rld");
+        assert!(chan.eof());
+        assert_eq!(chan.read_chars(5).unwrap(), "");
+    }
+
+    #[test]
+    fn test_utf16() {
+        let mut bytes = Vec::new();
+        Encoding::Utf16Be.encode("a\r\nb\u{1F600}\n", &mut bytes);
+        let mut chan = Channel::new(Box::new(Bytes(io::Cursor::new(bytes))), true, false);
+        chan.set_encoding(Encoding::Utf16Be);
+
+        assert_eq!(chan.read_line().unwrap(), Some("a".into()));
+        assert_eq!(chan.read_line().unwrap(), Some("b\u{1F600}".into()));
+        assert_eq!(chan.read_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut bytes = Vec::new();
+        Encoding::Utf16Le.encode("h\u{e9}\u{1F600}", &mut bytes);
+        assert_eq!(bytes, vec![0x68, 0, 0xE9, 0, 0x3D, 0xD8, 0x00, 0xDE]);
+        assert_eq!(Encoding::Utf16Le.decode(&bytes), "h\u{e9}\u{1F600}");
+
+        // Invalid and incomplete input is decoded as U+FFFD.
+        assert_eq!(Encoding::Utf8.decode(&[0x61, 0xFF, 0xC3]), "a\u{FFFD}\u{FFFD}");
+        assert_eq!(Encoding::Utf16Le.decode(&[0x00, 0xDC, 0x61]), "\u{FFFD}\u{FFFD}");
+        assert_eq!(Encoding::Ascii.decode(&[0x61, 0xE9]), "a\u{FFFD}");
+    }
+
+    #[test]
+    fn test_write_unsupported() {
+        let mut chan = channel("");
+
+        assert!(chan.write_str("abc").is_ok());
+        assert!(chan.flush().is_err());
+    }
+
+    #[test]
+    fn test_seek_tell() {
+        let mut chan = channel("a\nbc\nd\n");
+
+        assert_eq!(chan.read_line().unwrap(), Some("a".into()));
+        assert_eq!(chan.tell().unwrap(), Some(2));
+        assert_eq!(chan.seek(SeekFrom::Current(1)).unwrap(), 3);
+        assert_eq!(chan.read_line().unwrap(), Some("c".into()));
+        assert_eq!(chan.seek(SeekFrom::End(-2)).unwrap(), 5);
+        assert_eq!(chan.read_to_end().unwrap(), "d\n");
+        assert!(chan.eof());
+        assert_eq!(chan.seek(SeekFrom::Start(0)).unwrap(), 0);
+        assert!(!chan.eof());
+        assert_eq!(chan.read_line().unwrap(), Some("a".into()));
+
+        let mut table = ChannelTable::new();
+        let stdin = table.get("stdin").unwrap();
+        assert!(!stdin.is_seekable());
+        assert!(stdin.seek(SeekFrom::Start(0)).is_err());
+        assert_eq!(stdin.tell().unwrap(), None);
+    }
+
+    #[test]
+    fn test_table() {
+        let mut table = ChannelTable::new();
+
+        assert!(table.get("stdout").unwrap().is_writable());
+        assert!(!table.get("stdin").unwrap().is_writable());
+
+        let name = table.add("file", channel(""));
+        assert_eq!(name, "file3");
+        assert!(table.get(&name).is_some());
+        assert!(table.remove(&name).is_some());
+        assert!(table.get(&name).is_none());
+    }
+
+    #[test]
+    fn test_ready_handler() {
+        let mut table = ChannelTable::new();
+        assert!(table.ready_handler().is_none());
+        assert!(!table.awaits_events());
+
+        let a = table.add("file", channel("a"));
+        let b = table.add("file", channel("b"));
+        table.get(&a).unwrap().set_handler(FileEvent::Readable, Some(Value::from("read a")));
+        table.get(&b).unwrap().set_handler(FileEvent::Readable, Some(Value::from("read b")));
+        assert!(table.awaits_events());
+
+        // Each ready handler gets its turn before the channels are polled again.
+        let (name, event, script) = table.ready_handler().unwrap();
+        assert_eq!(name, a);
+        assert_eq!(event, FileEvent::Readable);
+        assert_eq!(script, Value::from("read a"));
+        assert_eq!(table.ready_handler().unwrap().0, b);
+        assert_eq!(table.ready_handler().unwrap().0, a);
+
+        // A handler removed while it's pending isn't called.
+        table.get(&b).unwrap().set_handler(FileEvent::Readable, None);
+        assert_eq!(table.ready_handler().unwrap().0, a);
+        assert_eq!(table.ready_handler().unwrap().0, a);
+
+        // The channel isn't writable, so it's never ready for writing.
+        table.get(&a).unwrap().set_handler(FileEvent::Readable, None);
+        table.get(&a).unwrap().set_handler(FileEvent::Writable, Some(Value::from("write a")));
+        assert!(table.ready_handler().is_none());
+    }
+}
@@ -0,0 +1,769 @@
+//! Clock Formatting and Scanning
+//!
+//! This module implements the time conversions for the `clock` command: breaking a time,
+//! expressed as a number of seconds since the epoch, into its calendar and clock fields,
+//! in UTC or in the local time zone, and formatting those fields according to a format
+//! string of `%` field codes; and scanning a date-time string according to a format
+//! string using the same field codes, converting it back to seconds since the epoch.
+
+use crate::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The format used by `clock format` when no `-format` is given.
+pub(crate) const DEFAULT_FORMAT: &str = "%a %b %d %H:%M:%S %Z %Y";
+
+const SECONDS_PER_DAY: MoltInt = 86_400;
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday",
+];
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+/// The date-time formats tried, in order, by `clock scan` when no format is given.
+const FREE_FORMATS: [&str; 10] = [
+    "%Y-%m-%dT%H:%M:%S%z",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d",
+    "%Y%m%dT%H%M%S",
+    "%Y%m%d",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y",
+];
+
+//--------------------------------------------------------------------------
+// The Current Time
+
+/// Gets the current time as a duration since the epoch.  A system clock set before the
+/// epoch is treated as the epoch.
+fn now() -> std::time::Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+/// Gets the current time in seconds since the epoch.
+pub(crate) fn seconds() -> MoltInt {
+    now().as_secs() as MoltInt
+}
+
+/// Gets the current time in milliseconds since the epoch.
+pub(crate) fn milliseconds() -> MoltInt {
+    now().as_millis() as MoltInt
+}
+
+/// Gets the current time in microseconds since the epoch.
+pub(crate) fn microseconds() -> MoltInt {
+    now().as_micros() as MoltInt
+}
+
+//--------------------------------------------------------------------------
+// Broken-down Times
+
+/// A time broken into its calendar and clock fields in a particular time zone.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Time {
+    /// The time in seconds since the epoch.
+    pub seconds: MoltInt,
+    /// The year, e.g., 2019.
+    pub year: MoltInt,
+    /// The month of the year, 1 to 12.
+    pub month: MoltInt,
+    /// The day of the month, 1 to 31.
+    pub day: MoltInt,
+    /// The hour of the day, 0 to 23.
+    pub hour: MoltInt,
+    /// The minute of the hour, 0 to 59.
+    pub minute: MoltInt,
+    /// The second of the minute, 0 to 59.
+    pub second: MoltInt,
+    /// The day of the year, 1 to 366.
+    pub yday: MoltInt,
+    /// The day of the week, 0 (Sunday) to 6 (Saturday).
+    pub wday: MoltInt,
+    /// The time zone's offset from UTC, in seconds east of Greenwich.
+    pub offset: MoltInt,
+    /// The time zone's abbreviated name, e.g., "GMT".
+    pub zone: String,
+}
+
+impl Time {
+    /// Breaks the time into its fields, in UTC if `gmt` is true and in the local time zone
+    /// otherwise.
+    pub fn new(seconds: MoltInt, gmt: bool) -> Result<Self, ResultCode> {
+        let (offset, zone) = if gmt {
+            (0, "GMT".to_string())
+        } else {
+            match local_zone(seconds) {
+                Some(zone) => zone,
+                None => return molt_err!("clock value \"{}\" is out of range", seconds),
+            }
+        };
+
+        let local = match seconds.checked_add(offset) {
+            Some(local) => local,
+            None => return molt_err!("clock value \"{}\" is out of range", seconds),
+        };
+
+        let days = local.div_euclid(SECONDS_PER_DAY);
+        let secs = local.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+
+        Ok(Self {
+            seconds,
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+            yday: days - days_from_civil(year, 1, 1) + 1,
+            // The epoch was a Thursday.
+            wday: (days + 4).rem_euclid(7),
+            offset,
+            zone,
+        })
+    }
+}
+
+/// Gets the number of days from the epoch to the given date in the proleptic Gregorian
+/// calendar.  (This is Howard Hinnant's `days_from_civil` algorithm.)
+pub(crate) fn days_from_civil(year: MoltInt, month: MoltInt, day: MoltInt) -> MoltInt {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+/// Gets the date, as year, month, and day, that is the given number of days from the epoch.
+/// (This is Howard Hinnant's `civil_from_days` algorithm.)
+pub(crate) fn civil_from_days(days: MoltInt) -> (MoltInt, MoltInt, MoltInt) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Determines whether the year is a leap year in the proleptic Gregorian calendar.
+fn is_leap_year(year: MoltInt) -> bool {
+    year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+}
+
+/// Gets the number of days in the given month of the given year.
+fn days_in_month(year: MoltInt, month: MoltInt) -> MoltInt {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Gets the local time zone's offset from UTC, in seconds east of Greenwich, and its
+/// abbreviated name, at the given time, or None if the time can't be represented.
+#[cfg(unix)]
+pub(crate) fn local_zone(seconds: MoltInt) -> Option<(MoltInt, String)> {
+    use std::convert::TryFrom;
+    use std::ffi::CStr;
+
+    let time = libc::time_t::try_from(seconds).ok()?;
+
+    // SAFETY: localtime_r fills in the tm struct, or returns null on failure; tm_zone,
+    // if not null, points to a static, NUL-terminated string.
+    unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return None;
+        }
+
+        let zone = if tm.tm_zone.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(tm.tm_zone).to_string_lossy().into_owned()
+        };
+
+        Some((tm.tm_gmtoff as MoltInt, zone))
+    }
+}
+
+/// Gets the local time zone's offset from UTC and its abbreviated name.  On this platform
+/// Molt doesn't know the local time zone, and so uses UTC.
+#[cfg(not(unix))]
+pub(crate) fn local_zone(_seconds: MoltInt) -> Option<(MoltInt, String)> {
+    Some((0, "UTC".to_string()))
+}
+
+//--------------------------------------------------------------------------
+// Formatting
+
+/// Formats the time according to the format string, in UTC if `gmt` is true and in the
+/// local time zone otherwise.
+///
+/// The format string may contain the following field codes; any other character is
+/// copied to the result as is.  An unrecognized field code is an error.
+///
+/// * `%a`, `%A`: the abbreviated or full weekday name, e.g., "Mon" or "Monday".
+/// * `%b`, `%h`, `%B`: the abbreviated or full month name, e.g., "Jan" or "January".
+/// * `%c`: the date and time, as `%a %b %e %H:%M:%S %Y`.
+/// * `%C`: the century, 00 to 99.
+/// * `%d`, `%e`: the day of the month, zero-padded or space-padded to two characters.
+/// * `%D`: the date, as `%m/%d/%y`.
+/// * `%H`, `%k`: the hour on the 24-hour clock, zero-padded or space-padded.
+/// * `%I`, `%l`: the hour on the 12-hour clock, zero-padded or space-padded.
+/// * `%j`: the day of the year, 001 to 366.
+/// * `%m`: the month, 01 to 12.
+/// * `%M`: the minute, 00 to 59.
+/// * `%n`, `%t`: a newline or a tab.
+/// * `%p`: AM or PM.
+/// * `%R`: the time, as `%H:%M`.
+/// * `%s`: the time in seconds since the epoch.
+/// * `%S`: the second, 00 to 59.
+/// * `%T`: the time, as `%H:%M:%S`.
+/// * `%u`, `%w`: the day of the week, 1 (Monday) to 7, or 0 (Sunday) to 6.
+/// * `%y`, `%Y`: the year, without or with its century.
+/// * `%z`: the time zone's offset from UTC, e.g., "-0500".
+/// * `%Z`: the time zone's abbreviated name, e.g., "EST".
+/// * `%%`: a single "%".
+pub(crate) fn format(seconds: MoltInt, fmt: &str, gmt: bool) -> Result<String, ResultCode> {
+    let time = Time::new(seconds, gmt)?;
+    let mut result = String::new();
+    let mut chars = fmt.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(field) => format_field(&time, field, &mut result)?,
+            None => return molt_err!("bad format string \"{}\": ends with \"%\"", fmt),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Formats a single field of the time, appending it to the result.
+fn format_field(time: &Time, field: char, result: &mut String) -> Result<(), ResultCode> {
+    let hour12 = if time.hour % 12 == 0 { 12 } else { time.hour % 12 };
+
+    let text = match field {
+        'a' => WEEKDAYS[time.wday as usize][..3].to_string(),
+        'A' => WEEKDAYS[time.wday as usize].to_string(),
+        'b' | 'h' => MONTHS[time.month as usize - 1][..3].to_string(),
+        'B' => MONTHS[time.month as usize - 1].to_string(),
+        'c' => {
+            let mut text = String::new();
+            for field in "a b e H:M:S Y".chars() {
+                match field {
+                    ' ' | ':' => text.push(field),
+                    _ => format_field(time, field, &mut text)?,
+                }
+            }
+            text
+        }
+        'C' => format!("{:02}", time.year.div_euclid(100)),
+        'd' => format!("{:02}", time.day),
+        'D' => format!("{:02}/{:02}/{:02}", time.month, time.day, time.year.rem_euclid(100)),
+        'e' => format!("{:2}", time.day),
+        'H' => format!("{:02}", time.hour),
+        'I' => format!("{:02}", hour12),
+        'j' => format!("{:03}", time.yday),
+        'k' => format!("{:2}", time.hour),
+        'l' => format!("{:2}", hour12),
+        'm' => format!("{:02}", time.month),
+        'M' => format!("{:02}", time.minute),
+        'n' => "\n".to_string(),
+        'p' => if time.hour < 12 { "AM" } else { "PM" }.to_string(),
+        'R' => format!("{:02}:{:02}", time.hour, time.minute),
+        's' => time.seconds.to_string(),
+        'S' => format!("{:02}", time.second),
+        't' => "\t".to_string(),
+        'T' => format!("{:02}:{:02}:{:02}", time.hour, time.minute, time.second),
+        'u' => (if time.wday == 0 { 7 } else { time.wday }).to_string(),
+        'w' => time.wday.to_string(),
+        'y' => format!("{:02}", time.year.rem_euclid(100)),
+        'Y' => format!("{:04}", time.year),
+        'z' => {
+            let sign = if time.offset < 0 { '-' } else { '+' };
+            let minutes = time.offset.abs() / 60;
+            format!("{}{:02}{:02}", sign, minutes / 60, minutes % 60)
+        }
+        'Z' => time.zone.clone(),
+        '%' => "%".to_string(),
+        _ => return molt_err!("bad format field \"%{}\"", field),
+    };
+
+    result.push_str(&text);
+    Ok(())
+}
+
+//--------------------------------------------------------------------------
+// Scanning
+
+/// The fields of a date-time string, as scanned by `scan_format`, prior to validation.
+#[derive(Default)]
+struct Fields {
+    year: Option<MoltInt>,
+    century: Option<MoltInt>,
+    year_of_century: Option<MoltInt>,
+    month: Option<MoltInt>,
+    day: Option<MoltInt>,
+    yday: Option<MoltInt>,
+    hour: Option<MoltInt>,
+    hour12: Option<MoltInt>,
+    pm: Option<bool>,
+    minute: Option<MoltInt>,
+    second: Option<MoltInt>,
+    seconds: Option<MoltInt>,
+    offset: Option<MoltInt>,
+}
+
+/// Scans the date-time string according to the format string, returning the time in
+/// seconds since the epoch.  The format string may contain the field codes accepted by
+/// `format`; whitespace in the format string matches any amount of whitespace, including
+/// none, and any other character must match exactly.  The fields are interpreted as UTC
+/// if `gmt` is true or the string contains a `%z` offset, and in the local time zone
+/// otherwise.
+///
+/// Fields missing from the string take default values: if no date is given, the current
+/// date; if no year, the current year; if no month or day, the first; and if no hour,
+/// minute, or second, zero.  A `%s` field gives the time directly, and overrides all
+/// other fields.  Fields that are out of range, e.g., a month of 13 or an hour of 25, are
+/// errors.
+pub(crate) fn scan(input: &str, fmt: &str, gmt: bool) -> Result<MoltInt, ResultCode> {
+    let chars: Vec<char> = input.chars().collect();
+
+    match scan_fields(&chars, fmt) {
+        Some(fields) => to_seconds(&fields, gmt),
+        None => molt_err!("input string does not match supplied format"),
+    }
+}
+
+/// Scans a date-time string in one of a number of common formats, including ISO-8601,
+/// returning the time in seconds since the epoch.  The fields are interpreted as for
+/// `scan`.
+pub(crate) fn scan_free(input: &str, gmt: bool) -> Result<MoltInt, ResultCode> {
+    let chars: Vec<char> = input.trim().chars().collect();
+
+    for fmt in &FREE_FORMATS {
+        if let Some(fields) = scan_fields(&chars, fmt) {
+            return to_seconds(&fields, gmt);
+        }
+    }
+
+    molt_err!("unable to convert date-time string \"{}\"", input)
+}
+
+/// Scans the entire input according to the format, returning the fields, or None if the
+/// input doesn't match.
+fn scan_fields(chars: &[char], fmt: &str) -> Option<Fields> {
+    let mut fields = Fields::default();
+    let mut pos = 0;
+
+    if scan_format(chars, &mut pos, fmt, &mut fields) && pos == chars.len() {
+        Some(fields)
+    } else {
+        None
+    }
+}
+
+/// Scans the input from the given position according to the format, saving the fields
+/// and updating the position.  Returns false if the input doesn't match.
+fn scan_format(chars: &[char], pos: &mut usize, fmt: &str, fields: &mut Fields) -> bool {
+    let mut fmt_chars = fmt.chars();
+
+    while let Some(c) = fmt_chars.next() {
+        if c.is_whitespace() {
+            skip_white(chars, pos);
+            continue;
+        }
+
+        if c != '%' {
+            if chars.get(*pos) != Some(&c) {
+                return false;
+            }
+            *pos += 1;
+            continue;
+        }
+
+        let field = match fmt_chars.next() {
+            Some(field) => field,
+            None => return false,
+        };
+
+        let matched = match field {
+            'a' | 'A' => scan_name(chars, pos, &WEEKDAYS).is_some(),
+            'b' | 'B' | 'h' => set(&mut fields.month, scan_name(chars, pos, &MONTHS).map(|m| m + 1)),
+            'c' => scan_format(chars, pos, "%a %b %e %H:%M:%S %Y", fields),
+            'C' => set(&mut fields.century, scan_digits(chars, pos, 2)),
+            'd' | 'e' => {
+                skip_white(chars, pos);
+                set(&mut fields.day, scan_digits(chars, pos, 2))
+            }
+            'D' => scan_format(chars, pos, "%m/%d/%y", fields),
+            'H' | 'k' => {
+                skip_white(chars, pos);
+                set(&mut fields.hour, scan_digits(chars, pos, 2))
+            }
+            'I' | 'l' => {
+                skip_white(chars, pos);
+                set(&mut fields.hour12, scan_digits(chars, pos, 2))
+            }
+            'j' => set(&mut fields.yday, scan_digits(chars, pos, 3)),
+            'm' => set(&mut fields.month, scan_digits(chars, pos, 2)),
+            'M' => set(&mut fields.minute, scan_digits(chars, pos, 2)),
+            'n' | 't' => {
+                skip_white(chars, pos);
+                true
+            }
+            'p' => {
+                let pm = scan_name(chars, pos, &["AM", "PM"]).map(|i| i == 1);
+                fields.pm = pm;
+                pm.is_some()
+            }
+            'R' => scan_format(chars, pos, "%H:%M", fields),
+            's' => set(&mut fields.seconds, scan_signed(chars, pos)),
+            'S' => set(&mut fields.second, scan_digits(chars, pos, 2)),
+            'T' => scan_format(chars, pos, "%H:%M:%S", fields),
+            'u' | 'w' => scan_digits(chars, pos, 1).is_some(),
+            'y' => set(&mut fields.year_of_century, scan_digits(chars, pos, 2)),
+            'Y' => set(&mut fields.year, scan_digits(chars, pos, 4)),
+            'z' => set(&mut fields.offset, scan_offset(chars, pos)),
+            'Z' => scan_zone(chars, pos, fields),
+            '%' => {
+                let matched = chars.get(*pos) == Some(&'%');
+                *pos += 1;
+                matched
+            }
+            _ => false,
+        };
+
+        if !matched {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Saves a scanned field, returning whether there was one.
+fn set(field: &mut Option<MoltInt>, value: Option<MoltInt>) -> bool {
+    *field = value;
+    value.is_some()
+}
+
+/// Skips any whitespace at the position.
+fn skip_white(chars: &[char], pos: &mut usize) {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+}
+
+/// Scans one to `max` decimal digits.
+fn scan_digits(chars: &[char], pos: &mut usize, max: usize) -> Option<MoltInt> {
+    let mut value: MoltInt = 0;
+    let mut count = 0;
+
+    while count < max {
+        match chars.get(*pos).and_then(|c| c.to_digit(10)) {
+            Some(digit) => value = value.checked_mul(10)?.checked_add(digit as MoltInt)?,
+            None => break,
+        }
+        *pos += 1;
+        count += 1;
+    }
+
+    if count > 0 {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Scans a decimal integer with an optional sign.
+fn scan_signed(chars: &[char], pos: &mut usize) -> Option<MoltInt> {
+    let negative = chars.get(*pos) == Some(&'-');
+
+    if negative || chars.get(*pos) == Some(&'+') {
+        *pos += 1;
+    }
+
+    let value = scan_digits(chars, pos, 19)?;
+
+    Some(if negative { -value } else { value })
+}
+
+/// Scans one of the names, or its first three letters, ignoring case, returning the
+/// name's index.
+fn scan_name(chars: &[char], pos: &mut usize, names: &[&str]) -> Option<MoltInt> {
+    for (i, name) in names.iter().enumerate() {
+        for len in &[name.len(), name.len().min(3)] {
+            let end = *pos + len;
+
+            if end <= chars.len() {
+                let word: String = chars[*pos..end].iter().collect();
+
+                if word.eq_ignore_ascii_case(&name[..*len]) {
+                    *pos = end;
+                    return Some(i as MoltInt);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Scans a time zone offset from UTC, `Z`, `+hhmm`, or `+hh:mm`, in seconds east of
+/// Greenwich.
+fn scan_offset(chars: &[char], pos: &mut usize) -> Option<MoltInt> {
+    let sign = match chars.get(*pos) {
+        Some('Z') => {
+            *pos += 1;
+            return Some(0);
+        }
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return None,
+    };
+    *pos += 1;
+
+    let start = *pos;
+    let hours = scan_digits(chars, pos, 2)?;
+
+    if *pos - start != 2 {
+        return None;
+    }
+
+    if chars.get(*pos) == Some(&':') {
+        *pos += 1;
+    }
+
+    let start = *pos;
+    let minutes = scan_digits(chars, pos, 2)?;
+
+    if *pos - start != 2 || hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Scans a time zone name.  `GMT`, `UTC`, and `Z` are UTC; any other name is taken to
+/// be the local time zone.
+fn scan_zone(chars: &[char], pos: &mut usize, fields: &mut Fields) -> bool {
+    let start = *pos;
+
+    while *pos < chars.len() && chars[*pos].is_ascii_alphabetic() {
+        *pos += 1;
+    }
+
+    let zone: String = chars[start..*pos].iter().collect();
+
+    if zone == "GMT" || zone == "UTC" || zone == "Z" {
+        fields.offset = Some(0);
+    }
+
+    !zone.is_empty()
+}
+
+/// Checks that the field, if given, is within the range.
+fn check_range(name: &str, value: Option<MoltInt>, min: MoltInt, max: MoltInt) -> Result<(), ResultCode> {
+    match value {
+        Some(value) if value < min || value > max => {
+            molt_err!("bad {} \"{}\": must be from {} to {}", name, value, min, max)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates the scanned fields and converts them to seconds since the epoch.
+fn to_seconds(fields: &Fields, gmt: bool) -> Result<MoltInt, ResultCode> {
+    if let Some(seconds) = fields.seconds {
+        return Ok(seconds);
+    }
+
+    // FIRST, validate the time of day.
+    check_range("hour", fields.hour, 0, 23)?;
+    check_range("hour", fields.hour12, 1, 12)?;
+    check_range("minute", fields.minute, 0, 59)?;
+    check_range("second", fields.second, 0, 59)?;
+
+    let hour = match (fields.hour12, fields.pm) {
+        (Some(hour), pm) => hour % 12 + if pm == Some(true) { 12 } else { 0 },
+        (None, _) => fields.hour.unwrap_or(0),
+    };
+    let secs = hour * 3600 + fields.minute.unwrap_or(0) * 60 + fields.second.unwrap_or(0);
+
+    // NEXT, get the date, defaulting to the current date.
+    let has_date = fields.year.is_some()
+        || fields.year_of_century.is_some()
+        || fields.month.is_some()
+        || fields.day.is_some()
+        || fields.yday.is_some();
+    let today = if has_date { None } else { Some(Time::new(seconds(), gmt)?) };
+
+    let year = match (fields.year, fields.century, fields.year_of_century) {
+        (Some(year), _, _) => year,
+        (None, Some(century), yy) => century * 100 + yy.unwrap_or(0),
+        (None, None, Some(yy)) if yy < 69 => 2000 + yy,
+        (None, None, Some(yy)) => 1900 + yy,
+        (None, None, None) => match &today {
+            Some(today) => today.year,
+            None => Time::new(seconds(), gmt)?.year,
+        },
+    };
+
+    let days = if fields.yday.is_some() && fields.month.is_none() && fields.day.is_none() {
+        let max = if is_leap_year(year) { 366 } else { 365 };
+        check_range("day of year", fields.yday, 1, max)?;
+        days_from_civil(year, 1, 1) + fields.yday.unwrap_or(1) - 1
+    } else {
+        let (month, day) = match &today {
+            Some(today) => (today.month, today.day),
+            None => (fields.month.unwrap_or(1), fields.day.unwrap_or(1)),
+        };
+        check_range("month", Some(month), 1, 12)?;
+        check_range("day", Some(day), 1, days_in_month(year, month))?;
+        days_from_civil(year, month, day)
+    };
+
+    let local = days * SECONDS_PER_DAY + secs;
+
+    // NEXT, convert to UTC.
+    if let Some(offset) = fields.offset {
+        Ok(local - offset)
+    } else if gmt {
+        Ok(local)
+    } else {
+        local_to_utc(local)
+    }
+}
+
+/// Converts a local time, in seconds since the epoch as though the local time zone were
+/// UTC, to the actual time in seconds since the epoch.
+fn local_to_utc(local: MoltInt) -> Result<MoltInt, ResultCode> {
+    // The offset depends on the time being converted, which isn't yet known; so guess the
+    // time using the offset at the local time, and then use the offset at the guess.
+    let offset = |time: MoltInt| match local_zone(time) {
+        Some((offset, _)) => Ok(offset),
+        None => molt_err!("clock value \"{}\" is out of range", time),
+    };
+
+    let guess = local - offset(local)?;
+
+    Ok(local - offset(guess)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+
+        for days in -800_000..800_000 {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn test_time() {
+        let time = Time::new(951_827_696, true).unwrap();
+        assert_eq!(time.year, 2000);
+        assert_eq!(time.month, 2);
+        assert_eq!(time.day, 29);
+        assert_eq!(time.hour, 12);
+        assert_eq!(time.minute, 34);
+        assert_eq!(time.second, 56);
+        assert_eq!(time.yday, 60);
+        assert_eq!(time.wday, 2);
+        assert_eq!(time.offset, 0);
+        assert_eq!(time.zone, "GMT");
+
+        let time = Time::new(-1, true).unwrap();
+        assert_eq!((time.year, time.month, time.day), (1969, 12, 31));
+        assert_eq!((time.hour, time.minute, time.second), (23, 59, 59));
+        assert_eq!(time.wday, 3);
+    }
+
+    #[test]
+    fn test_format() {
+        let secs = 951_827_696;
+        assert_eq!(format(secs, DEFAULT_FORMAT, true), Ok("Tue Feb 29 12:34:56 GMT 2000".into()));
+        assert_eq!(format(secs, "%Y-%m-%dT%H:%M:%S", true), Ok("2000-02-29T12:34:56".into()));
+        assert_eq!(format(secs, "%A %B %e %j", true), Ok("Tuesday February 29 060".into()));
+        assert_eq!(format(secs, "%I %l %p %k", true), Ok("12 12 PM 12".into()));
+        assert_eq!(format(secs, "%c", true), Ok("Tue Feb 29 12:34:56 2000".into()));
+        assert_eq!(format(secs, "%D %R %T", true), Ok("02/29/00 12:34 12:34:56".into()));
+        assert_eq!(format(secs, "%C %y %u %w %s", true), Ok("20 00 2 2 951827696".into()));
+        assert_eq!(format(secs, "%z %Z %%", true), Ok("+0000 GMT %".into()));
+        assert_eq!(format(0, "%e %k %l %p", true), Ok(" 1  0 12 AM".into()));
+
+        assert_eq!(format(0, "%q", true), molt_err!("bad format field \"%q\""));
+        assert_eq!(format(0, "abc%", true), molt_err!("bad format string \"abc%\": ends with \"%\""));
+    }
+
+    #[test]
+    fn test_scan() {
+        let secs = 951_827_696;
+        assert_eq!(scan("2000-02-29T12:34:56", "%Y-%m-%dT%H:%M:%S", true), Ok(secs));
+        assert_eq!(scan("Tue Feb 29 12:34:56 GMT 2000", DEFAULT_FORMAT, false), Ok(secs));
+        assert_eq!(scan("29 february 00 12:34:56 pm", "%d %B %y %I:%M:%S %p", true), Ok(secs));
+        assert_eq!(scan("2000 060  12:34:56", "%Y %j %T", true), Ok(secs));
+        assert_eq!(scan("2000-02-29T07:34:56-05:00", "%Y-%m-%dT%T%z", false), Ok(secs));
+        assert_eq!(scan("951827696", "%s", false), Ok(secs));
+        assert_eq!(scan("1970", "%Y", true), Ok(0));
+        assert_eq!(scan("12 AM", "%I %p", true).map(|s| s % 86_400), Ok(0));
+
+        assert_eq!(scan("2000-02-29", "%Y-%m-%dT%H", true),
+            molt_err!("input string does not match supplied format"));
+        assert_eq!(scan("2000-13-01", "%Y-%m-%d", true),
+            molt_err!("bad month \"13\": must be from 1 to 12"));
+        assert_eq!(scan("1900-02-29", "%Y-%m-%d", true),
+            molt_err!("bad day \"29\": must be from 1 to 28"));
+        assert_eq!(scan("25:00", "%H:%M", true),
+            molt_err!("bad hour \"25\": must be from 0 to 23"));
+        assert_eq!(scan("1999 366", "%Y %j", true),
+            molt_err!("bad day of year \"366\": must be from 1 to 365"));
+    }
+
+    #[test]
+    fn test_scan_free() {
+        let secs = 951_827_696;
+        assert_eq!(scan_free("2000-02-29T12:34:56", true), Ok(secs));
+        assert_eq!(scan_free("2000-02-29T12:34:56Z", false), Ok(secs));
+        assert_eq!(scan_free(" 2000-02-29 12:34:56 ", true), Ok(secs));
+        assert_eq!(scan_free("20000229T123456", true), Ok(secs));
+        assert_eq!(scan_free("02/29/2000", true), Ok(secs - 45_296));
+        assert_eq!(scan_free("2000-13-01", true),
+            molt_err!("bad month \"13\": must be from 1 to 12"));
+        assert_eq!(scan_free("yesterday", true),
+            molt_err!("unable to convert date-time string \"yesterday\""));
+    }
+}
@@ -2,13 +2,117 @@
 //!
 //! This module defines the standard Molt commands.
 
+use crate::binary;
+use crate::channel::Buffering;
+use crate::channel::Channel;
+use crate::channel::Encoding;
+use crate::channel::FileEvent;
+use crate::channel::Translation;
+use crate::char_ptr::CharPtr;
+use crate::clock;
+use crate::dict::dict_path_get;
+use crate::dict::dict_path_insert;
+use crate::dict::dict_path_remove;
+use crate::dict::list_to_dict;
+use crate::ensemble::Ensemble;
 use crate::expr::expr;
 use crate::expr::expr_test;
+use crate::format::format;
+use crate::interp::check_arg_specs;
+use crate::interp::parse_var_name;
+use crate::interp::ExecOp;
 use crate::interp::Interp;
+use crate::package;
+use crate::scan::scan;
+use crate::scope::TraceOp;
+use crate::socket;
 use crate::types::*;
+use crate::util::get_index;
+use crate::util::glob_match;
+use crate::util::glob_match_nocase;
+use crate::util::posix_error;
+use crate::util::read_float;
+use crate::util::read_int;
+use crate::util::repeated_size;
 use crate::*;
+use regex::Captures;
+use regex::Match;
+use regex::Regex;
+use regex::RegexBuilder;
+use std::cmp::Ordering;
+use std::thread;
+use std::time::Duration;
 use std::time::Instant;
-use std::fs;
+use std::io::SeekFrom;
+use std::rc::Rc;
+
+/// # after *ms* ?*script* ...?
+/// # after cancel *id*|*script* ...
+/// # after idle *script* ?*script* ...?
+/// # after info ?*id*?
+///
+/// Sleeps for the given number of milliseconds, or schedules a script to be evaluated at
+/// the global scope by the event loop after the given number of milliseconds or when
+/// the event loop is idle.  The other subcommands cancel and query scheduled scripts.
+/// See molt-book for full semantics.
+pub fn cmd_after(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "option ?arg ...?")?;
+
+    if let Ok(ms) = argv[1].as_int() {
+        let delay = Duration::from_millis(ms.max(0) as u64);
+
+        if argv.len() == 2 {
+            thread::sleep(delay);
+            return molt_ok!();
+        }
+
+        return molt_ok!(interp.after(delay, after_script(&argv[2..])));
+    }
+
+    match argv[1].as_string().as_str() {
+        "cancel" => {
+            check_args(2, argv, 3, 0, "id|command")?;
+
+            let arg = argv[2].as_string();
+            if argv.len() == 3 && interp.event_info(&arg).is_some() {
+                interp.cancel_event(&arg);
+            } else {
+                interp.cancel_event_script(&after_script(&argv[2..]).as_string());
+            }
+
+            molt_ok!()
+        }
+        "idle" => {
+            check_args(2, argv, 3, 0, "script ?script ...?")?;
+
+            molt_ok!(interp.after_idle(after_script(&argv[2..])))
+        }
+        "info" => {
+            check_args(2, argv, 2, 3, "?id?")?;
+
+            if argv.len() == 2 {
+                return molt_ok!(interp.event_ids());
+            }
+
+            let id = argv[2].as_string();
+            match interp.event_info(&id) {
+                Some((script, kind)) => molt_ok!(vec![script, Value::from(kind)]),
+                None => molt_err!("event \"{}\" doesn't exist", id),
+            }
+        }
+        arg => molt_err!("bad argument \"{}\": must be cancel, idle, info, or an integer", arg),
+    }
+}
+
+/// Gets the script to be scheduled by `after` from its arguments, which are concatenated
+/// as by `concat`.
+fn after_script(args: &[Value]) -> Value {
+    if args.len() == 1 {
+        args[0].clone()
+    } else {
+        Value::from(concat_values(args))
+    }
+}
 
 /// # append *varName* ?*value* ...?
 ///
@@ -19,25 +123,145 @@ pub fn cmd_append(interp: &mut Interp, argv: &[Value]) -> MoltResult {
 
     // FIRST, get the value of the variable.  If the variable is undefined,
     // start with the empty string.
-
-    // TODO: Can we make this conversion simpler, but still as efficient?
     let var_name = &*argv[1].as_string();
-    let var_result = interp.var(var_name);
+    let old_value = interp.var(var_name).unwrap_or_else(|_| Value::empty());
 
-    let mut new_string = if var_result.is_ok() {
-        // Use to_string() because we need a mutable owned string.
-        var_result.unwrap().to_string()
-    } else {
-        String::new()
-    };
+    if argv.len() == 2 {
+        return interp.set_var2(var_name, old_value);
+    }
+
+    // NEXT, release the variable's reference to its value, so that the string can be
+    // extended in place rather than copied if nothing else shares it.  Traced variables
+    // are left alone, as the traces would see the empty value.
+    if !interp.has_var_traces() {
+        interp.set_var2(var_name, Value::empty())?;
+    }
+    let mut new_string = old_value.into_string();
 
     // NEXT, append the remaining values to the string.
     for item in &argv[2..] {
-        new_string.push_str(&*item.as_string());
+        new_string.push_str(&item.as_string());
     }
 
     // NEXT, save and return the new value.
-    molt_ok!(interp.set_var2(var_name, new_string.into()))
+    interp.set_var2(var_name, new_string.into())
+}
+
+/// # apply *lambdaExpr* ?*arg* ...?
+///
+/// Applies an anonymous procedure to the arguments.  The lambda expression is a list
+/// of two or three elements, `{args body ?namespace?}`, where *args* and *body* are as
+/// for `proc`.
+pub fn cmd_apply(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "lambdaExpr ?arg ...?")?;
+
+    interp.apply(argv)
+}
+
+/// # array *subcommand* ?*arg*...?
+pub fn cmd_array(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&ARRAY_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const ARRAY_SUBCOMMANDS: [Subcommand; 6] = [
+    Subcommand("exists", cmd_array_exists),
+    Subcommand("get", cmd_array_get),
+    Subcommand("names", cmd_array_names),
+    Subcommand("set", cmd_array_set),
+    Subcommand("size", cmd_array_size),
+    Subcommand("unset", cmd_array_unset),
+];
+
+/// # array exists *arrayName*
+pub fn cmd_array_exists(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "arrayName")?;
+
+    molt_ok!(interp.array_exists(&argv[2].as_string()))
+}
+
+/// # array get *arrayName* ?*pattern*?
+pub fn cmd_array_get(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "arrayName ?pattern?")?;
+
+    let list = interp.array_get(&argv[2].as_string());
+
+    if argv.len() == 3 {
+        return molt_ok!(list);
+    }
+
+    let pattern = &*argv[3].as_string();
+    let mut result = Vec::new();
+
+    for pair in list.chunks(2) {
+        if glob_match(pattern, &pair[0].as_string()) {
+            result.push(pair[0].clone());
+            result.push(pair[1].clone());
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # array names *arrayName* ?*pattern*?
+pub fn cmd_array_names(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "arrayName ?pattern?")?;
+
+    let names = interp.array_names(&argv[2].as_string());
+
+    if argv.len() == 3 {
+        return molt_ok!(names);
+    }
+
+    let pattern = &*argv[3].as_string();
+    let names: MoltList = names
+        .into_iter()
+        .filter(|name| glob_match(pattern, &name.as_string()))
+        .collect();
+
+    molt_ok!(names)
+}
+
+/// # array set *arrayName* *list*
+pub fn cmd_array_set(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "arrayName list")?;
+
+    interp.array_set(&argv[2].as_string(), &argv[3].as_list()?)
+}
+
+/// # array size *arrayName*
+pub fn cmd_array_size(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "arrayName")?;
+
+    molt_ok!(interp.array_names(&argv[2].as_string()).len() as MoltInt)
+}
+
+/// # array unset *arrayName* ?*pattern*?
+pub fn cmd_array_unset(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "arrayName ?pattern?")?;
+
+    let array_name = &*argv[2].as_string();
+
+    if !interp.array_exists(array_name) {
+        return molt_ok!();
+    }
+
+    if argv.len() == 3 {
+        interp.unset_var(array_name);
+    } else {
+        let pattern = &*argv[3].as_string();
+
+        for name in interp.array_names(array_name) {
+            let name = &*name.as_string();
+            if glob_match(pattern, name) {
+                interp.unset_var(&format!("{}({})", array_name, name));
+            }
+        }
+    }
+
+    molt_ok!()
 }
 
 /// assert_eq received, expected
@@ -54,6 +278,48 @@ pub fn cmd_assert_eq(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
     }
 }
 
+/// # binary *subcommand* ?*arg* ...?
+pub fn cmd_binary(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&BINARY_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const BINARY_SUBCOMMANDS: [Subcommand; 2] = [
+    Subcommand("format", cmd_binary_format),
+    Subcommand("scan", cmd_binary_scan),
+];
+
+/// # binary format *template* ?*arg* ...?
+///
+/// Returns the binary data, a byte array, made by formatting the arguments according to
+/// the template.  See molt-book for the field specifiers.
+pub fn cmd_binary_format(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "formatString ?arg ...?")?;
+
+    let bytes = binary::format(&argv[2].as_string(), &argv[3..])?;
+    molt_ok!(Value::from_bytes(bytes))
+}
+
+/// # binary scan *data* *template* ?*varName* ...?
+///
+/// Parses fields from the binary data according to the template and assigns them to the
+/// variables, returning the number of variables assigned.  Scanning stops when the data
+/// runs out.
+pub fn cmd_binary_scan(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "value formatString ?varName ...?")?;
+
+    let var_names = &argv[4..];
+    let values = binary::scan(&argv[2].as_bytes(), &argv[3].as_string(), var_names.len())?;
+
+    for (var_name, value) in var_names.iter().zip(&values) {
+        interp.set_var2(&var_name.as_string(), value.clone())?;
+    }
+
+    molt_ok!(values.len() as MoltInt)
+}
+
 /// # break
 ///
 /// Breaks a loops.
@@ -64,618 +330,5015 @@ pub fn cmd_break(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
     Err(ResultCode::Break)
 }
 
-/// catch script ?resultVarName?
+/// catch script ?resultVarName? ?optionsVarName?
 ///
 /// Executes a script, returning the result code.  If the resultVarName is given, the result
-/// of executing the script is returned in it.  The result code is returned as an integer,
-/// 0=Ok, 1=Error, 2=Return, 3=Break, 4=Continue.
+/// of executing the script is returned in it.  If the optionsVarName is given, the
+/// script's return options are returned in it as a dictionary.  The result code is returned
+/// as an integer, 0=Ok, 1=Error, 2=Return, 3=Break, 4=Continue.  On error, the global
+/// `errorInfo` and `errorCode` variables are set.
 pub fn cmd_catch(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 3, "script ?resultVarName")?;
+    check_args(1, argv, 2, 4, "script ?resultVarName? ?optionsVarName?")?;
+
+    let result = interp.eval_body(&argv[1].as_string());
+
+    // A tail call ends the enclosing proc, whatever the catch would do.
+    if let Err(ResultCode::TailCall(_)) = result {
+        return result;
+    }
+
+    if let Err(ResultCode::Error(data)) = &result {
+        interp.set_error_vars(data);
+    }
+
+    let (code, value) = caught_result(&result);
+
+    if argv.len() >= 3 {
+        interp.set_var2(&argv[2].as_string(), value)?;
+    }
 
-    let result = interp.eval_body(&*argv[1].as_string());
-    let code: MoltInt;
-    let mut value = Value::empty();
+    if argv.len() == 4 {
+        interp.set_var2(&argv[3].as_string(), Value::from(return_options(&result)))?;
+    }
+
+    Ok(Value::from(code))
+}
 
+/// Gets the result code and value of a caught result, as returned by `catch`: the
+/// error message for errors, and the returned value for `return`.
+pub(crate) fn caught_result(result: &MoltResult) -> (MoltInt, Value) {
     match result {
-        Ok(val) => {
-            code = 0;
-            value = val;
-        }
-        Err(ResultCode::Error(val)) => {
-            code = 1;
-            value = val;
-        }
-        Err(ResultCode::Return(val)) => {
-            code = 2;
-            value = val;
-        }
-        Err(ResultCode::Break) => {
-            code = 3;
-        }
-        Err(ResultCode::Continue) => {
-            code = 4;
+        Ok(val) => (0, val.clone()),
+        Err(ResultCode::Error(data)) => (1, data.message().clone()),
+        Err(ResultCode::Return(data)) => (2, data.value().clone()),
+        Err(ResultCode::Other(code, value)) => (*code, value.clone()),
+        Err(code) => (code.as_int(), Value::empty()),
+    }
+}
+
+/// Gets the return options dictionary for a result, as returned by `catch`: the `-code` and
+/// `-level`, plus the `-errorcode`, `-errorinfo`, and `-errorline` for errors.  For an explicit
+/// `return`, the `-code` and `-level` are those given to the `return` command.
+fn return_options(result: &MoltResult) -> MoltDict {
+    let mut opts = MoltDict::new();
+
+    let (code, level, error) = match result {
+        Ok(_) => (0, 0, None),
+        Err(ResultCode::Error(data)) => (1, 0, Some(data)),
+        Err(ResultCode::Return(data)) => match data.code() {
+            Some(ResultCode::Error(error)) => (1, data.level(), Some(error)),
+            Some(code) => (code.as_int(), data.level(), None),
+            None => (0, data.level(), None),
+        },
+        Err(code) => (code.as_int(), 0, None),
+    };
+
+    opts.insert(Value::from("-code"), Value::from(code));
+    opts.insert(Value::from("-level"), Value::from(level as MoltInt));
+
+    if let Some(data) = error {
+        opts.insert(Value::from("-errorcode"), data.error_code().clone());
+        opts.insert(Value::from("-errorinfo"), Value::from(data.error_info()));
+
+        if level == 0 {
+            opts.insert(Value::from("-errorline"), Value::from(data.error_line() as MoltInt));
         }
     }
 
-    if argv.len() == 3 {
-        interp.set_var2(&*argv[2].as_string(), value);
+    opts
+}
+
+/// Parses a completion code as given to `return -code`: one of `ok`, `error`, `return`,
+/// `break`, or `continue`, or an integer code: 0 to 4 for those, or an application-defined
+/// code.
+fn parse_completion_code(value: &Value) -> Result<MoltInt, ResultCode> {
+    match value.as_string().as_str() {
+        "ok" => Ok(0),
+        "error" => Ok(1),
+        "return" => Ok(2),
+        "break" => Ok(3),
+        "continue" => Ok(4),
+        _ => match value.as_int() {
+            Ok(code) => Ok(code),
+            _ => molt_err!(
+                "bad completion code \"{}\": must be ok, error, return, break, continue, or an integer",
+                value
+            ),
+        },
     }
+}
 
-    Ok(Value::from(code))
+/// # clock *subcommand* ?*arg* ...?
+pub fn cmd_clock(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&CLOCK_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
 }
 
-/// # continue
+const CLOCK_SUBCOMMANDS: [Subcommand; 5] = [
+    Subcommand("format", cmd_clock_format),
+    Subcommand("microseconds", cmd_clock_microseconds),
+    Subcommand("milliseconds", cmd_clock_milliseconds),
+    Subcommand("scan", cmd_clock_scan),
+    Subcommand("seconds", cmd_clock_seconds),
+];
+
+/// # clock format *seconds* ?-format *format*? ?-gmt *boolean*?
 ///
-/// Continues with the next iteration of the inmost loop.
-pub fn cmd_continue(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 1, 1, "")?;
+/// Formats the time, in seconds since the epoch, according to the format string, in
+/// local time or, if `-gmt` is true, in UTC.
+pub fn cmd_clock_format(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "clockValue ?-format string? ?-gmt boolean?")?;
 
-    Err(ResultCode::Continue)
+    let seconds = argv[2].as_int()?;
+    let (fmt, gmt) = clock_options(&argv[3..])?;
+    let fmt = fmt.unwrap_or_else(|| Value::from(clock::DEFAULT_FORMAT));
+
+    molt_ok!(clock::format(seconds, &fmt.as_string(), gmt)?)
 }
 
-/// error *message*
-///
-/// Returns an error with the given message.
+/// Parses the `-format` and `-gmt` options of `clock format` and `clock scan`.
+fn clock_options(opts: &[Value]) -> Result<(Option<Value>, bool), ResultCode> {
+    let mut fmt = None;
+    let mut gmt = false;
+
+    let mut i = 0;
+
+    while i < opts.len() {
+        let opt = opts[i].as_string();
+
+        if i + 1 == opts.len() {
+            return molt_err!("missing value for option \"{}\"", opt);
+        }
+
+        match opt.as_str() {
+            "-format" => fmt = Some(opts[i + 1].clone()),
+            "-gmt" => gmt = opts[i + 1].as_bool()?,
+            _ => return molt_err!("bad option \"{}\": must be -format or -gmt", opt),
+        }
+
+        i += 2;
+    }
+
+    Ok((fmt, gmt))
+}
+
+/// # clock microseconds
 ///
-/// ## TCL Liens
+/// Returns the current time in microseconds since the epoch.
+pub fn cmd_clock_microseconds(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(clock::microseconds())
+}
+
+/// # clock milliseconds
 ///
-/// * In Standard TCL, `error` can optionally set the stack trace and an error code.
-pub fn cmd_error(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "message")?;
+/// Returns the current time in milliseconds since the epoch.
+pub fn cmd_clock_milliseconds(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
 
-    molt_err!(argv[1].clone())
+    molt_ok!(clock::milliseconds())
 }
 
-/// # exit ?*returnCode*?
+/// # clock scan *dateString* ?-format *format*? ?-gmt *boolean*?
 ///
-/// Terminates the application by calling `std::process::exit()`.
-/// If given, _returnCode_ must be an integer return code; if absent, it
-/// defaults to 0.
-pub fn cmd_exit(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 1, 2, "?returnCode?")?;
+/// Scans the date-time string according to the format string, or, if none is given,
+/// as one of a number of common formats, and returns the time in seconds since the epoch.
+/// The time is taken to be local time unless `-gmt` is true or the string includes a time
+/// zone.
+pub fn cmd_clock_scan(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "dateString ?-format string? ?-gmt boolean?")?;
 
-    let return_code: MoltInt = if argv.len() == 1 {
-        0
-    } else {
-        argv[1].as_int()?
+    let input = argv[2].as_string();
+    let (fmt, gmt) = clock_options(&argv[3..])?;
+
+    let seconds = match fmt {
+        Some(fmt) => clock::scan(&input, &fmt.as_string(), gmt)?,
+        None => clock::scan_free(&input, gmt)?,
     };
 
-    std::process::exit(return_code as i32)
+    molt_ok!(seconds)
 }
 
-/// # expr expr
-///
-/// Evaluates an expression and returns its result.
+/// # clock seconds
 ///
-/// ## TCL Liens
+/// Returns the current time in seconds since the epoch.
+pub fn cmd_clock_seconds(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(clock::seconds())
+}
+
+/// # close *channelId*
 ///
-/// See the Molt Book.
+/// Closes the channel, writing any buffered output.  For a command pipeline, waits for its
+/// programs to exit, and reports their failure as `exec` does.
+pub fn cmd_close(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "channelId")?;
 
-pub fn cmd_expr(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "expr")?;
+    let name = argv[1].as_string();
+    let mut channel = interp.remove_channel(&name)?;
+    let written = channel.close();
+    let finished = channel.finish();
 
-    expr(interp, &argv[1])
+    match written {
+        Ok(_) => finished.and(molt_ok!()),
+        Err(e) => molt_err!("error writing \"{}\": {}", name, posix_error(&e)),
+    }
 }
 
-/// # for *start* *test* *next* *command*
-///
-/// A standard "for" loop.  start, next, and command are scripts; test is an expression
-///
-pub fn cmd_for(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 5, 5, "start test next command")?;
+/// Gets the named channel, which must have been opened for reading.
+fn input_channel<'a>(interp: &'a mut Interp, name: &str) -> Result<&'a mut Channel, ResultCode> {
+    let channel = interp.channel(name)?;
 
-    let start = &*argv[1].as_string();
-    let test = &argv[2];
-    let next = &*argv[3].as_string();
-    let command = &*argv[4].as_string();
+    if channel.is_readable() {
+        Ok(channel)
+    } else {
+        molt_err!("channel \"{}\" wasn't opened for reading", name)
+    }
+}
 
-    // Start
-    interp.eval(start)?;
+/// Gets the named channel, which must have been opened for writing.
+pub(crate) fn output_channel<'a>(interp: &'a mut Interp, name: &str) -> Result<&'a mut Channel, ResultCode> {
+    let channel = interp.channel(name)?;
 
-    while expr_test(interp, test)? {
-        let result = interp.eval_body(command);
+    if channel.is_writable() {
+        Ok(channel)
+    } else {
+        molt_err!("channel \"{}\" wasn't opened for writing", name)
+    }
+}
 
-        match result {
-            Ok(_) => (),
-            Err(ResultCode::Break) => break,
-            Err(ResultCode::Continue) => (),
-            _ => return result,
-        }
+/// # concat ?*arg* ...?
+///
+/// Trims leading and trailing whitespace from each argument, and joins the non-empty
+/// results with single spaces.  No list quoting is done.
+pub fn cmd_concat(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // No arg check needed; can take any number.
+    molt_ok!(concat_values(&argv[1..]))
+}
 
-        // Execute next script.  Break is allowed, but continue is not.
-        let result = interp.eval_body(next);
+/// Concatenates the values as for `concat`.
+fn concat_values(values: &[Value]) -> String {
+    let mut words: Vec<String> = Vec::new();
 
-        match result {
-            Ok(_) => (),
-            Err(ResultCode::Break) => break,
-            Err(ResultCode::Continue) => {
-                return molt_err!("invoked \"continue\" outside of a loop");
-            },
-            _ => return result,
+    for arg in values {
+        let arg = arg.as_string();
+        let trimmed = arg.trim();
+
+        if !trimmed.is_empty() {
+            words.push(trimmed.to_string());
         }
     }
 
-    molt_ok!()
+    words.join(" ")
 }
 
-/// # foreach *varList* *list* *body*
-///
-/// Loops over the items the list, assigning successive items to the variables in the
-/// *varList* and calling the *body* as a script once for each set of assignments.
-/// On the last iteration, the second and subsequents variables in the *varList* will
-/// be assigned the empty string if there are not enough list elements to fill them.
+/// # continue
 ///
-/// ## TCL Liens
+/// Continues with the next iteration of the inmost loop.
+pub fn cmd_continue(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 1, "")?;
+
+    Err(ResultCode::Continue)
+}
+
+/// # coroutine *name* *command* ?*arg* ...?
 ///
-/// * In Standard TCL, `foreach` can loop over several lists at the same time.
-pub fn cmd_foreach(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 4, 4, "varList list body")?;
+/// Creates a coroutine command with the given name, which executes the command, resolved
+/// in the current namespace, at the global level until it calls `yield`, and returns the
+/// yielded value.  Each call of the coroutine command resumes the command where it left
+/// off, with `yield` returning the call's argument, if any, and returns the next yielded
+/// value.  When the command returns, the coroutine command is deleted, and the command's
+/// result is the result of the call.
+pub fn cmd_coroutine(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "name command ?arg ...?")?;
 
-    let var_list = &*argv[1].as_list()?;
-    let list = &*argv[2].as_list()?;
-    let body = &*argv[3].as_string();
+    interp.add_coroutine(&argv[1].as_string(), &argv[2..])
+}
 
-    let mut i = 0;
+/// # dict *subcommand* ?*arg*...?
+pub fn cmd_dict(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&DICT_SUBCOMMANDS, &argv[1].as_string())?;
 
-    while i < list.len() {
-        for var_name in var_list {
-            if i < list.len() {
-                interp.set_var2(&*var_name.as_string(), list[i].clone());
-                i += 1;
-            } else {
-                interp.set_var2(&*var_name.as_string(), Value::empty());
-            }
-        }
+    (subc.1)(interp, argv)
+}
 
-        let result = interp.eval_body(body);
+const DICT_SUBCOMMANDS: [Subcommand; 14] = [
+    Subcommand("create", cmd_dict_create),
+    Subcommand("exists", cmd_dict_exists),
+    Subcommand("filter", cmd_dict_filter),
+    Subcommand("for", cmd_dict_for),
+    Subcommand("get", cmd_dict_get),
+    Subcommand("keys", cmd_dict_keys),
+    Subcommand("map", cmd_dict_map),
+    Subcommand("merge", cmd_dict_merge),
+    Subcommand("set", cmd_dict_set),
+    Subcommand("size", cmd_dict_size),
+    Subcommand("unset", cmd_dict_unset),
+    Subcommand("update", cmd_dict_update),
+    Subcommand("values", cmd_dict_values),
+    Subcommand("with", cmd_dict_with),
+];
 
-        match result {
-            Ok(_) => (),
-            Err(ResultCode::Break) => break,
-            Err(ResultCode::Continue) => (),
-            _ => return result,
-        }
+/// # dict create ?*key* *value* ...?
+pub fn cmd_dict_create(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    if argv.len() % 2 == 1 {
+        return molt_err!("wrong # args: should be \"dict create ?key value ...?\"");
     }
 
-    molt_ok!()
+    molt_ok!(list_to_dict(&argv[2..])?)
 }
 
+/// # dict exists *dictionary* *key* ?*key* ...?
+pub fn cmd_dict_exists(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "dictionary key ?key ...?")?;
 
-/// # global ?*varName* ...?
-///
+    let mut value = argv[2].clone();
+
+    for key in &argv[3..] {
+        // An intermediate value that isn't a dictionary doesn't contain the key.
+        let dict = match value.as_dict() {
+            Ok(dict) => dict,
+            Err(_) => return molt_ok!(false),
+        };
+
+        value = match dict.get(key) {
+            Some(val) => val.clone(),
+            None => return molt_ok!(false),
+        };
+    }
+
+    molt_ok!(true)
+}
+
+/// # dict filter *dictionary* *filterType* ?*arg* ...?
+///
+/// Returns a new dictionary containing the entries that match the filter.  The
+/// filter types are `key ?pattern ...?`, `value ?pattern ...?`, and
+/// `script {keyVar valueVar} script`.
+pub fn cmd_dict_filter(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "dictionary filterType ?arg ...?")?;
+
+    let dict = &*argv[2].as_dict()?;
+    let filter_type = argv[3].as_string();
+    let mut result = MoltDict::new();
+
+    match filter_type.as_str() {
+        "key" | "value" => {
+            let by_key = filter_type.as_str() == "key";
+
+            for (key, value) in dict {
+                let item = if by_key { key } else { value };
+                let item = &*item.as_string();
+
+                if argv[4..].iter().any(|pat| glob_match(&pat.as_string(), item)) {
+                    result.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        "script" => {
+            if argv.len() != 6 {
+                return molt_err!("wrong # args: should be \"dict filter dictionary script {keyVarName valueVarName} filterScript\"");
+            }
+
+            let var_list = &*argv[4].as_list()?;
+
+            if var_list.len() != 2 {
+                return molt_err!("must have exactly two variable names");
+            }
+
+            let body = &*argv[5].as_string();
+
+            for (key, value) in dict {
+                interp.set_var2(&var_list[0].as_string(), key.clone())?;
+                interp.set_var2(&var_list[1].as_string(), value.clone())?;
+
+                match interp.eval_body(body) {
+                    Ok(flag) => {
+                        if flag.as_bool()? {
+                            result.insert(key.clone(), value.clone());
+                        }
+                    }
+                    Err(ResultCode::Break) => break,
+                    Err(ResultCode::Continue) => (),
+                    result => return result,
+                }
+            }
+        }
+        filter_type => {
+            return molt_err!("bad filterType \"{}\": must be key, script, or value", filter_type);
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # dict for {*keyVar* *valueVar*} *dictionary* *body*
+///
+/// Loops over the entries in the dictionary, in order, assigning each key and value to the
+/// variables and calling the body as a script once for each entry.
+pub fn cmd_dict_for(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 5, "{keyVar valueVar} dictionary script")?;
+
+    let var_list = &*argv[2].as_list()?;
+
+    if var_list.len() != 2 {
+        return molt_err!("must have exactly two variable names");
+    }
+
+    let dict = &*argv[3].as_dict()?;
+    let body = &*argv[4].as_string();
+
+    for (key, value) in dict {
+        interp.set_var2(&var_list[0].as_string(), key.clone())?;
+        interp.set_var2(&var_list[1].as_string(), value.clone())?;
+
+        let result = interp.eval_body(body);
+
+        match result {
+            Ok(_) => (),
+            Err(ResultCode::Break) => break,
+            Err(ResultCode::Continue) => (),
+            _ => return result,
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # dict get *dictionary* ?*key* ...?
+pub fn cmd_dict_get(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "dictionary ?key ...?")?;
+
+    if argv.len() == 3 {
+        // Validate the dictionary.
+        argv[2].as_dict()?;
+    }
+
+    dict_path_get(&argv[2], &argv[3..])
+}
+
+/// # dict keys *dictionary* ?*pattern*?
+pub fn cmd_dict_keys(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "dictionary ?pattern?")?;
+
+    let dict = argv[2].as_dict()?;
+    let keys: MoltList = if argv.len() == 4 {
+        let pattern = &*argv[3].as_string();
+        dict.keys().filter(|k| glob_match(pattern, &k.as_string())).cloned().collect()
+    } else {
+        dict.keys().cloned().collect()
+    };
+
+    molt_ok!(keys)
+}
+
+/// # dict map {*keyVar* *valueVar*} *dictionary* *body*
+///
+/// Loops over the entries in the dictionary as for `dict for`, building a new dictionary
+/// that maps the value of *keyVar* to the result of the body on each iteration.
+pub fn cmd_dict_map(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 5, "{keyVar valueVar} dictionary script")?;
+
+    let var_list = &*argv[2].as_list()?;
+
+    if var_list.len() != 2 {
+        return molt_err!("must have exactly two variable names");
+    }
+
+    let dict = &*argv[3].as_dict()?;
+    let body = &*argv[4].as_string();
+    let mut result = MoltDict::new();
+
+    for (key, value) in dict {
+        let key_var = &*var_list[0].as_string();
+        interp.set_var2(key_var, key.clone())?;
+        interp.set_var2(&var_list[1].as_string(), value.clone())?;
+
+        match interp.eval_body(body) {
+            Ok(new_value) => {
+                result.insert(interp.var(key_var)?, new_value);
+            }
+            Err(ResultCode::Break) => break,
+            Err(ResultCode::Continue) => (),
+            result => return result,
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # dict merge ?*dictionary* ...?
+///
+/// Merges the dictionaries into a new dictionary; if a key appears in more than one of
+/// them, the value from the last one wins.
+pub fn cmd_dict_merge(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut result = MoltDict::new();
+
+    for dict_val in &argv[2..] {
+        for (key, value) in &*dict_val.as_dict()? {
+            result.insert(key.clone(), value.clone());
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # dict set *dictVarName* *key* ?*key* ...? *value*
+pub fn cmd_dict_set(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 0, "dictVarName key ?key ...? value")?;
+
+    let var_name = &*argv[2].as_string();
+    let dict_val = interp.var(var_name).unwrap_or_else(|_| Value::empty());
+    let last = argv.len() - 1;
+    let new_val = dict_path_insert(&dict_val, &argv[3..last], argv[last].clone())?;
+
+    interp.set_var2(var_name, new_val)
+}
+
+/// # dict size *dictionary*
+pub fn cmd_dict_size(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "dictionary")?;
+
+    molt_ok!(argv[2].as_dict()?.len() as MoltInt)
+}
+
+/// # dict unset *dictVarName* *key* ?*key* ...?
+pub fn cmd_dict_unset(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "dictVarName key ?key ...?")?;
+
+    let var_name = &*argv[2].as_string();
+    let dict_val = interp.var(var_name).unwrap_or_else(|_| Value::empty());
+    let new_val = dict_path_remove(&dict_val, &argv[3..])?;
+
+    interp.set_var2(var_name, new_val)
+}
+
+/// # dict update *dictVarName* *key* *varName* ?*key* *varName* ...? *body*
+///
+/// Sets each *varName* to the value of the matching *key*, if any, and executes the
+/// body.  Afterwards, the variables are written back into the dictionary, whatever the
+/// result of the body: a variable's key is removed if the variable was unset.
+pub fn cmd_dict_update(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    if argv.len() < 6 || argv.len() % 2 == 1 {
+        return molt_err!("wrong # args: should be \"dict update dictVarName key varName ?key varName ...? script\"");
+    }
+
+    let var_name = &*argv[2].as_string();
+    let dict = interp.var(var_name)?.as_dict()?;
+    let last = argv.len() - 1;
+    let mut vars: Vec<(Value, Value)> = Vec::new();
+
+    for pair in argv[3..last].chunks(2) {
+        let name = &*pair[1].as_string();
+
+        match dict.get(&pair[0]) {
+            Some(value) => {
+                interp.set_var2(name, value.clone())?;
+            }
+            None => interp.unset_var(name),
+        }
+
+        vars.push((pair[0].clone(), pair[1].clone()));
+    }
+
+    let result = interp.eval_body(&argv[last].as_string());
+    dict_write_back(interp, var_name, &[], &vars)?;
+
+    result
+}
+
+/// # dict values *dictionary* ?*pattern*?
+pub fn cmd_dict_values(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "dictionary ?pattern?")?;
+
+    let dict = argv[2].as_dict()?;
+    let values: MoltList = if argv.len() == 4 {
+        let pattern = &*argv[3].as_string();
+        dict.values().filter(|v| glob_match(pattern, &v.as_string())).cloned().collect()
+    } else {
+        dict.values().cloned().collect()
+    };
+
+    molt_ok!(values)
+}
+
+/// # dict with *dictVarName* ?*key* ...? *body*
+///
+/// Sets a variable for each key in the dictionary (or in the nested dictionary at the
+/// path of keys) and executes the body.  Afterwards, the variables are written back into
+/// the dictionary, whatever the result of the body: a key is removed if its variable was
+/// unset.
+pub fn cmd_dict_with(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "dictVarName ?key ...? script")?;
+
+    let var_name = &*argv[2].as_string();
+    let last = argv.len() - 1;
+    let path = &argv[3..last];
+    let dict = dict_path_get(&interp.var(var_name)?, path)?.as_dict()?;
+    let mut vars: Vec<(Value, Value)> = Vec::new();
+
+    for (key, value) in &*dict {
+        interp.set_var2(&key.as_string(), value.clone())?;
+        vars.push((key.clone(), key.clone()));
+    }
+
+    let result = interp.eval_body(&argv[last].as_string());
+    dict_write_back(interp, var_name, path, &vars)?;
+
+    result
+}
+
+/// Writes variables back into the (possibly nested) dictionary in the named variable, for
+/// `dict update` and `dict with`.  The `vars` are pairs of dictionary keys and variable
+/// names; if a variable no longer exists, its key is removed.  If the dictionary variable
+/// itself no longer exists there's nothing to do.
+fn dict_write_back(
+    interp: &mut Interp,
+    var_name: &str,
+    path: &[Value],
+    vars: &[(Value, Value)],
+) -> Result<(), ResultCode> {
+    let dict_val = match interp.var(var_name) {
+        Ok(val) => val,
+        Err(_) => return Ok(()),
+    };
+
+    let mut dict = (*dict_path_get(&dict_val, path)?.as_dict()?).clone();
+
+    for (key, name) in vars {
+        match interp.var(&name.as_string()) {
+            Ok(value) => {
+                dict.insert(key.clone(), value);
+            }
+            Err(_) => {
+                dict.shift_remove(key);
+            }
+        }
+    }
+
+    let new_val = if path.is_empty() {
+        Value::from(dict)
+    } else {
+        dict_path_insert(&dict_val, path, Value::from(dict))?
+    };
+
+    interp.set_var2(var_name, new_val)?;
+    Ok(())
+}
+
+/// # encoding *subcommand* ?*arg* ...?
+pub fn cmd_encoding(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&ENCODING_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const ENCODING_SUBCOMMANDS: [Subcommand; 4] = [
+    Subcommand("convertfrom", cmd_encoding_convertfrom),
+    Subcommand("convertto", cmd_encoding_convertto),
+    Subcommand("names", cmd_encoding_names),
+    Subcommand("system", cmd_encoding_system),
+];
+
+/// # encoding convertfrom ?*encoding*? *data*
+///
+/// Decodes the binary data, a byte array, as a string in the encoding, which defaults to
+/// the system encoding.
+pub fn cmd_encoding_convertfrom(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "?encoding? data")?;
+
+    let encoding = encoding_arg(argv)?;
+    molt_ok!(encoding.decode(&argv[argv.len() - 1].as_bytes()))
+}
+
+/// # encoding convertto ?*encoding*? *string*
+///
+/// Encodes the string in the encoding, which defaults to the system encoding, returning
+/// binary data, a byte array.
+pub fn cmd_encoding_convertto(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "?encoding? string")?;
+
+    let encoding = encoding_arg(argv)?;
+    let mut bytes = Vec::new();
+    encoding.encode(&argv[argv.len() - 1].as_string(), &mut bytes);
+    molt_ok!(Value::from_bytes(bytes))
+}
+
+/// Gets the optional encoding argument of `encoding convertfrom` or `convertto`, or the
+/// system encoding.
+fn encoding_arg(argv: &[Value]) -> Result<Encoding, ResultCode> {
+    if argv.len() == 3 {
+        return Ok(Encoding::Utf8);
+    }
+
+    match Encoding::from_name(&argv[2].as_string()) {
+        Some(encoding) => Ok(encoding),
+        None => molt_err!("unknown encoding \"{}\"", argv[2]),
+    }
+}
+
+/// # encoding names
+///
+/// Returns a list of the names of the available encodings.
+pub fn cmd_encoding_names(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(Encoding::NAMES.iter().map(|name| Value::from(*name)).collect::<MoltList>())
+}
+
+/// # encoding system
+///
+/// Returns the name of the system encoding, which is always `utf-8`.
+pub fn cmd_encoding_system(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(Encoding::Utf8.name())
+}
+
+/// # eof *channelId*
+///
+/// Returns 1 if the most recent read from the channel reached the end of its input, and
+/// 0 otherwise.
+pub fn cmd_eof(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "channelId")?;
+
+    molt_ok!(interp.channel(&argv[1].as_string())?.eof())
+}
+
+/// error *message* ?*errorInfo*? ?*errorCode*?
+///
+/// Returns an error with the given message.  If given and non-empty, the errorInfo is the
+/// initial error info, which will be extended as the error propagates; if given, the
+/// errorCode is the error code.
+pub fn cmd_error(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 4, "message ?errorInfo? ?errorCode?")?;
+
+    let mut data = ErrorData::new(argv[1].clone());
+
+    if argv.len() >= 3 && !argv[2].as_string().is_empty() {
+        data.set_error_info(&argv[2].as_string());
+        data.set_logged();
+    }
+
+    if argv.len() == 4 {
+        data.set_error_code(argv[3].clone());
+    }
+
+    Err(ResultCode::Error(data))
+}
+
+/// # exit ?*returnCode*?
+///
+/// Terminates the application by calling `std::process::exit()`.
+/// If given, _returnCode_ must be an integer return code; if absent, it
+/// defaults to 0.
+pub fn cmd_exit(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 2, "?returnCode?")?;
+
+    let return_code: MoltInt = if argv.len() == 1 {
+        0
+    } else {
+        argv[1].as_int()?
+    };
+
+    interp.flush_channels();
+    std::process::exit(return_code as i32)
+}
+
+/// # expr expr
+///
+/// Evaluates an expression and returns its result.
+///
+/// ## TCL Liens
+///
+/// See the Molt Book.
+
+pub fn cmd_expr(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "expr")?;
+
+    expr(interp, &argv[1])
+}
+
+/// # fconfigure *channelId* ?*optionName*? ?*value* *optionName* *value* ...?
+///
+/// Queries or sets the channel's options.  With no options, returns a dictionary of all
+/// of the options and their values; with one option, returns its value; otherwise, sets
+/// the options to the values.  Sockets have the additional read-only options `-peername`
+/// and `-sockname`.
+pub fn cmd_fconfigure(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "channelId ?optionName? ?value optionName value ...?")?;
+
+    let channel = interp.channel(&argv[1].as_string())?;
+    let mut options = FCONFIGURE_OPTIONS.to_vec();
+
+    if channel.peername().is_some() {
+        options.push("-peername");
+    }
+
+    if channel.sockname().is_some() {
+        options.push("-sockname");
+    }
+
+    if argv.len() == 2 {
+        let mut list = MoltList::new();
+
+        for opt in &options {
+            list.push(Value::from(*opt));
+            list.push(fconfigure_get(channel, opt));
+        }
+
+        return molt_ok!(list);
+    }
+
+    if argv.len() == 3 {
+        let opt = fconfigure_option(&options, &argv[2])?;
+        return molt_ok!(fconfigure_get(channel, opt));
+    }
+
+    for pair in argv[2..].chunks(2) {
+        let opt = fconfigure_option(&FCONFIGURE_OPTIONS, &pair[0])?;
+
+        match pair.get(1) {
+            Some(value) => fconfigure_set(channel, opt, value)?,
+            None => return molt_err!("value for \"{}\" missing", opt),
+        }
+    }
+
+    molt_ok!()
+}
+
+const FCONFIGURE_OPTIONS: [&str; 5] =
+    ["-blocking", "-buffering", "-buffersize", "-encoding", "-translation"];
+
+/// Looks up an `fconfigure` option by name among the given options.
+fn fconfigure_option(options: &[&'static str], opt: &Value) -> Result<&'static str, ResultCode> {
+    let opt = opt.as_string();
+
+    match options.iter().find(|o| **o == *opt) {
+        Some(o) => Ok(o),
+        None => {
+            let (last, rest) = options.split_last().unwrap();
+            molt_err!("bad option \"{}\": should be one of {}, or {}", opt, rest.join(", "), last)
+        }
+    }
+}
+
+/// Gets the value of a channel option.
+fn fconfigure_get(channel: &Channel, opt: &str) -> Value {
+    match opt {
+        "-blocking" => Value::from(channel.blocking()),
+        "-buffering" => Value::from(channel.buffering().name()),
+        "-buffersize" => Value::from(channel.buffer_size() as MoltInt),
+        "-encoding" => Value::from(channel.encoding().name()),
+        "-peername" => channel.peername().map_or_else(Value::empty, socket::addr_list),
+        "-sockname" => channel.sockname().map_or_else(Value::empty, socket::addr_list),
+        _ => {
+            // The translation is given for each direction in which the channel is open.
+            let input = Value::from(channel.input_translation().name());
+            let output = Value::from(channel.output_translation().name());
+
+            match (channel.is_readable(), channel.is_writable()) {
+                (true, true) => Value::from(vec![input, output]),
+                (true, false) => input,
+                _ => output,
+            }
+        }
+    }
+}
+
+/// Sets the value of a channel option.
+fn fconfigure_set(channel: &mut Channel, opt: &str, value: &Value) -> Result<(), ResultCode> {
+    match opt {
+        "-blocking" => {
+            if let Err(e) = channel.set_blocking(value.as_bool()?) {
+                return molt_err!("error setting blocking mode: {}", posix_error(&e));
+            }
+        }
+        "-buffering" => match Buffering::from_name(&value.as_string()) {
+            Some(buffering) => channel.set_buffering(buffering),
+            None => return molt_err!("bad value for -buffering: must be one of full, line, or none"),
+        },
+        "-buffersize" => channel.set_buffer_size(value.as_int()?.max(1) as usize),
+        "-encoding" => match Encoding::from_name(&value.as_string()) {
+            Some(encoding) => channel.set_encoding(encoding),
+            None => return molt_err!("unknown encoding \"{}\"", value),
+        },
+        _ => {
+            let modes = &*value.as_list()?;
+            let mut translations = Vec::new();
+
+            for mode in modes {
+                let mode = mode.as_string();
+
+                // Binary mode is shorthand for no translation and binary encoding.
+                let translation = match mode.as_str() {
+                    "binary" => {
+                        channel.set_encoding(Encoding::Binary);
+                        Some(Translation::Lf)
+                    }
+                    "platform" => Some(Translation::NATIVE),
+                    _ => Translation::from_name(&mode),
+                };
+
+                match translation {
+                    Some(translation) => translations.push(translation),
+                    None => break,
+                }
+            }
+
+            match translations.as_slice() {
+                [both] if modes.len() == 1 => channel.set_translation(*both, *both),
+                [input, output] if modes.len() == 2 => channel.set_translation(*input, *output),
+                _ => {
+                    return molt_err!(
+                        "bad value for -translation: must be one of auto, binary, cr, lf, \
+                         crlf, or platform"
+                    )
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// # fileevent *channelId* readable|writable ?*script*?
+///
+/// Registers the script as the channel's handler for when it is readable or writable, to
+/// be evaluated at the global scope by the event loop; an empty script removes the
+/// handler.  With no script, returns the current handler, or the empty string if none.
+pub fn cmd_fileevent(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 4, "channelId event ?script?")?;
+
+    let channel = interp.channel(&argv[1].as_string())?;
+    let name = argv[2].as_string();
+
+    let event = match FileEvent::from_name(&name) {
+        Some(event) => event,
+        None => return molt_err!("bad event name \"{}\": must be readable or writable", name),
+    };
+
+    let allowed = match event {
+        FileEvent::Readable => channel.is_readable(),
+        FileEvent::Writable => channel.is_writable(),
+    };
+
+    if !allowed {
+        return molt_err!("channel is not {}", name);
+    }
+
+    if argv.len() == 3 {
+        return molt_ok!(channel.handler(event).cloned().unwrap_or_else(Value::empty));
+    }
+
+    let script = &argv[3];
+    channel.set_handler(event, if script.as_string().is_empty() { None } else { Some(script.clone()) });
+    molt_ok!()
+}
+
+/// # flush *channelId*
+///
+/// Writes any buffered output to the channel's stream.
+pub fn cmd_flush(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "channelId")?;
+
+    let name = argv[1].as_string();
+    let channel = output_channel(interp, &name)?;
+
+    match channel.flush() {
+        Ok(_) => molt_ok!(),
+        Err(e) => molt_err!("error flushing \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # for *start* *test* *next* *command*
+///
+/// A standard "for" loop.  start, next, and command are scripts; test is an expression
+///
+pub fn cmd_for(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 5, 5, "start test next command")?;
+
+    let start = &*argv[1].as_string();
+    let test = &argv[2];
+    let next = &*argv[3].as_string();
+    let command = &*argv[4].as_string();
+
+    // Start
+    interp.eval(start)?;
+
+    while expr_test(interp, test)? {
+        let result = interp.eval_body(command);
+
+        match result {
+            Ok(_) => (),
+            Err(ResultCode::Break) => break,
+            Err(ResultCode::Continue) => (),
+            _ => return result,
+        }
+
+        // Execute next script.  Break is allowed, but continue is not.
+        let result = interp.eval_body(next);
+
+        match result {
+            Ok(_) => (),
+            Err(ResultCode::Break) => break,
+            Err(ResultCode::Continue) => {
+                return molt_err!("invoked \"continue\" outside of a loop");
+            },
+            _ => return result,
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # foreach *varList* *list* ?*varList* *list* ...? *body*
+///
+/// Loops over the items of one or more lists, assigning successive items of each list to
+/// the variables in its *varList* and calling the *body* as a script once for each set of
+/// assignments.  The loop continues until all of the lists are exhausted; variables for
+/// which a list has no more elements are assigned the empty string.
+pub fn cmd_foreach(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 0, "varList list ?varList list ...? body")?;
+
+    if argv.len() % 2 == 1 {
+        return molt_err!("wrong # args: should be \"foreach varList list ?varList list ...? body\"");
+    }
+
+    foreach_loop(interp, argv, None)?;
+    molt_ok!()
+}
+
+/// The loop machinery shared by `foreach` and `lmap`.  The arguments are one or more
+/// *varList* *list* pairs followed by the body.  On each iteration, each *varList* takes
+/// the next elements from its *list*, or the empty string once its list is exhausted;
+/// the loop continues until all of the lists are exhausted.  If `collect` is given, the
+/// result of each iteration of the body is pushed onto it, unless the body calls
+/// `continue`.
+fn foreach_loop(
+    interp: &mut Interp,
+    argv: &[Value],
+    mut collect: Option<&mut MoltList>,
+) -> MoltResult {
+    let cmd = &*argv[0].as_string();
+    let body = &*argv[argv.len() - 1].as_string();
+
+    // FIRST, get the varLists and lists, and the number of iterations.
+    let mut pairs = Vec::new();
+    let mut iterations = 0;
+
+    for pair in argv[1..argv.len() - 1].chunks(2) {
+        let var_list = pair[0].as_list()?;
+        let list = pair[1].as_list()?;
+
+        if var_list.is_empty() {
+            return molt_err!("{} varlist is empty", cmd);
+        }
+
+        iterations = iterations.max(list.len().div_ceil(var_list.len()));
+        pairs.push((var_list, list));
+    }
+
+    // NEXT, run the loop.
+    for n in 0..iterations {
+        for (var_list, list) in &pairs {
+            for (j, var_name) in var_list.iter().enumerate() {
+                let value = list.get(n * var_list.len() + j).cloned().unwrap_or_else(Value::empty);
+                interp.set_var2(&var_name.as_string(), value)?;
+            }
+        }
+
+        let result = interp.eval_body(body);
+
+        match result {
+            Ok(value) => {
+                if let Some(list) = collect.as_mut() {
+                    list.push(value);
+                }
+            }
+            Err(ResultCode::Break) => break,
+            Err(ResultCode::Continue) => (),
+            _ => return result,
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # format *formatString* ?*arg* ...?
+///
+/// Formats the arguments according to the format string, in the style of the C
+/// `sprintf` function.  See molt-book for the details.
+pub fn cmd_format(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "formatString ?arg ...?")?;
+
+    format(&argv[1].as_string(), &argv[2..])
+}
+
+/// # gets *channelId* ?*varName*?
+///
+/// Reads the next line from the channel, without its newline.  If *varName* is given,
+/// assigns the line to the variable and returns its length in characters, or -1 at the
+/// end of the input; otherwise, returns the line, or the empty string at the end of the
+/// input.
+pub fn cmd_gets(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "channelId ?varName?")?;
+
+    let name = argv[1].as_string();
+    let line = match input_channel(interp, &name)?.read_line() {
+        Ok(line) => line,
+        Err(e) => return molt_err!("error reading \"{}\": {}", name, posix_error(&e)),
+    };
+
+    if argv.len() == 2 {
+        return molt_ok!(line.unwrap_or_default());
+    }
+
+    match line {
+        Some(line) => {
+            let len = line.chars().count() as MoltInt;
+            interp.set_var2(&argv[2].as_string(), Value::from(line))?;
+            molt_ok!(len)
+        }
+        None => {
+            interp.set_var2(&argv[2].as_string(), Value::empty())?;
+            molt_ok!(-1)
+        }
+    }
+}
+
+/// # global ?*varName* ...?
+///
 /// Appends any number of values to a variable's value, which need not
 /// initially exist.
-pub fn cmd_global(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    // Accepts any number of arguments
+pub fn cmd_global(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // Accepts any number of arguments
+
+    // FIRST, if we're at the global scope this is a no-op.
+    if interp.scope_level() > 0 {
+        for name in &argv[1..] {
+            // TODO: Should upvar take the name as a Value?
+            interp.upvar(0, &*name.as_string());
+        }
+    }
+    molt_ok!()
+}
+
+/// # history ?*subcommand*? ?*arg*...?
+///
+/// Manages the command history.  With no subcommand, it's `history info`.
+pub fn cmd_history(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    if argv.len() == 1 {
+        return history_info(interp, None);
+    }
+
+    let subc = Subcommand::find(&HISTORY_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const HISTORY_SUBCOMMANDS: [Subcommand; 7] = [
+    Subcommand("add", cmd_history_add),
+    Subcommand("clear", cmd_history_clear),
+    Subcommand("event", cmd_history_event),
+    Subcommand("info", cmd_history_info),
+    Subcommand("keep", cmd_history_keep),
+    Subcommand("nextid", cmd_history_nextid),
+    Subcommand("redo", cmd_history_redo),
+];
+
+/// # history add *command* ?exec?
+///
+/// Adds the command to the history as its most recent event.  With `exec`, also evaluates
+/// it at the global scope and returns its result.
+pub fn cmd_history_add(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "command ?exec?")?;
+
+    let command = argv[2].as_string();
+
+    if argv.len() == 4 && *argv[3].as_string() != "exec" {
+        return molt_err!("bad argument \"{}\": should be \"exec\"", argv[3]);
+    }
+
+    interp.add_history(&command);
+
+    if argv.len() == 4 {
+        interp.eval_global(&command)
+    } else {
+        molt_ok!()
+    }
+}
+
+/// # history clear
+///
+/// Drops all of the events; the next event is numbered 1.
+pub fn cmd_history_clear(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    interp.clear_history();
+    molt_ok!()
+}
+
+/// # history event ?*event*?
+///
+/// Returns the event, which defaults to -1, the event before the most recent.
+pub fn cmd_history_event(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?event?")?;
+
+    molt_ok!(history_event(interp, argv.get(2))?)
+}
+
+/// # history info ?*count*?
+///
+/// Returns the most recent *count* events, or all retained events, one per line, each
+/// preceded by its number.
+pub fn cmd_history_info(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?count?")?;
+
+    let count = match argv.get(2) {
+        Some(count) => Some(count.as_int()?),
+        None => None,
+    };
+
+    history_info(interp, count)
+}
+
+/// Formats the most recent events, as for `history info`.
+fn history_info(interp: &Interp, count: Option<MoltInt>) -> MoltResult {
+    let last = interp.history_id();
+    let mut first = interp.history_first_id();
+
+    if let Some(count) = count {
+        first = first.max((last as MoltInt - count + 1).max(0) as usize);
+    }
+
+    let lines: Vec<String> = (first..=last)
+        .filter_map(|id| Some(format!("{:6}  {}", id, interp.history_event(id)?.replace('\n', "\n\t"))))
+        .collect();
+
+    molt_ok!(lines.join("\n"))
+}
+
+/// # history keep ?*count*?
+///
+/// Sets the maximum number of events retained, dropping the oldest events if there are
+/// more than that.  Without a count, returns the current limit.
+pub fn cmd_history_keep(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?count?")?;
+
+    if argv.len() == 2 {
+        return molt_ok!(interp.history_keep() as MoltInt);
+    }
+
+    match argv[2].as_int() {
+        Ok(count) if count >= 0 => {
+            interp.set_history_keep(count as usize);
+            molt_ok!()
+        }
+        _ => molt_err!("illegal keep count \"{}\"", argv[2]),
+    }
+}
+
+/// # history nextid
+///
+/// Returns the number the next event will have.
+pub fn cmd_history_nextid(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(interp.history_id() as MoltInt + 1)
+}
+
+/// # history redo ?*event*?
+///
+/// Evaluates the event, which defaults to -1, at the global scope, and returns its result.
+pub fn cmd_history_redo(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?event?")?;
+
+    let command = history_event(interp, argv.get(2))?;
+    interp.eval_global(&command)
+}
+
+/// Gets the event given to `history event` or `history redo`: an event number, counting
+/// back from the most recent event if it's 0 or negative, or else the most recent event
+/// that matches the string as a glob pattern or a prefix.  The event defaults to -1.
+fn history_event(interp: &Interp, event: Option<&Value>) -> Result<String, ResultCode> {
+    let spec = event.map_or_else(|| "-1".to_string(), |event| event.to_string());
+    let last = interp.history_id();
+
+    if let Ok(id) = spec.parse::<MoltInt>() {
+        let id = if id <= 0 { last as MoltInt + id } else { id };
+
+        if id > last as MoltInt {
+            return molt_err!("event \"{}\" hasn't occurred yet", spec);
+        }
+
+        return match interp.history_event(id.max(0) as usize) {
+            Some(command) => Ok(command),
+            None => molt_err!("event \"{}\" is too far in the past", spec),
+        };
+    }
+
+    let pattern = format!("{}*", spec);
+
+    (interp.history_first_id()..=last)
+        .rev()
+        .filter_map(|id| interp.history_event(id))
+        .find(|command| glob_match(&pattern, command))
+        .map_or_else(|| molt_err!("no event matches \"{}\"", spec), Ok)
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum IfWants {
+    Expr,
+    ThenBody,
+    SkipThenClause,
+    ElseClause,
+    ElseBody,
+}
+
+/// # if *expr* ?then? *script* elseif *expr* ?then? *script* ... ?else? ?*script*?
+///
+/// Standard conditional.  Returns the value of the selected script (or
+/// "" if there is no else body and the none of the previous branches were selected).
+///
+/// # TCL Liens
+///
+/// * Because we don't yet have an expression parser, the *expr* arguments are evaluated as
+///   scripts that must return a boolean value.
+pub fn cmd_if(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut argi = 1;
+    let mut wants = IfWants::Expr;
+
+    while argi < argv.len() {
+        match wants {
+            IfWants::Expr => {
+                wants = if expr_test(interp, &argv[argi])? {
+                    IfWants::ThenBody
+                } else {
+                    IfWants::SkipThenClause
+                };
+            },
+            IfWants::ThenBody => {
+                if &*argv[argi].as_string() == "then" {
+                    argi += 1;
+                }
+
+                if argi < argv.len() {
+                    return interp.eval_body(&*argv[argi].as_string());
+                } else {
+                    break;
+                }
+            },
+            IfWants::SkipThenClause => {
+                if &*argv[argi].as_string() == "then" {
+                    argi += 1;
+                }
+
+                if argi < argv.len() {
+                    argi += 1;
+                    wants = IfWants::ElseClause;
+                }
+                continue;
+            }
+            IfWants::ElseClause => {
+                if &*argv[argi].as_string() == "elseif" {
+                    wants = IfWants::Expr;
+                } else {
+                    wants = IfWants::ElseBody;
+                    continue;
+                }
+            }
+            IfWants::ElseBody => {
+                if &*argv[argi].as_string() == "else" {
+                    argi += 1;
+
+                    // If "else" appears, then the else body is required.
+                    if argi == argv.len() {
+                        return molt_err!("wrong # args: no script following after \"{}\" argument",
+                            argv[argi - 1]);
+                    }
+                }
+
+                if argi < argv.len() {
+                    return interp.eval_body(&*argv[argi].as_string());
+                } else {
+                    break;
+                }
+            }
+        }
+
+        argi += 1;
+    }
+
+    if argi < argv.len() {
+        return molt_err!(
+            "wrong # args: extra words after \"else\" clause in \"if\" command");
+    } else if wants == IfWants::Expr {
+        return molt_err!("wrong # args: no expression after \"{}\" argument",
+            argv[argi-1]);
+    } else if wants == IfWants::ThenBody || wants == IfWants::SkipThenClause {
+        return molt_err!("wrong # args: no script following after \"{}\" argument",
+            argv[argi - 1]);
+    } else {
+        // Looking for ElseBody, but there doesn't need to be one.
+        molt_ok!() // temp
+    }
+}
+
+/// # incr *varName* ?*increment* ...?
+///
+/// Increments an integer variable by a value.
+pub fn cmd_incr(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "varName ?increment?")?;
+
+    let increment: MoltInt = if argv.len() == 3 {
+        argv[2].as_int()?
+    } else {
+        1
+    };
+
+    let var_name = &*argv[1].as_string();
+    let var_value = interp.var(var_name);
+
+    let new_value = if var_value.is_ok() {
+        var_value.unwrap().as_int()? + increment
+    } else {
+        increment
+    };
+
+    interp.set_var2(var_name, new_value.into())
+}
+
+
+/// # info *subcommand* ?*arg*...?
+pub fn cmd_info(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&INFO_SUBCOMMANDS, &*argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const INFO_SUBCOMMANDS: [Subcommand; 17] = [
+    Subcommand("args", cmd_info_args),
+    Subcommand("body", cmd_info_body),
+    Subcommand("cmdcount", cmd_info_cmdcount),
+    Subcommand("commands", cmd_info_commands),
+    Subcommand("complete", cmd_info_complete),
+    Subcommand("coroutine", cmd_info_coroutine),
+    Subcommand("default", cmd_info_default),
+    Subcommand("exists", cmd_info_exists),
+    Subcommand("globals", cmd_info_globals),
+    Subcommand("level", cmd_info_level),
+    Subcommand("locals", cmd_info_locals),
+    Subcommand("nameofexecutable", cmd_info_nameofexecutable),
+    Subcommand("patchlevel", cmd_info_patchlevel),
+    Subcommand("procs", cmd_info_procs),
+    Subcommand("script", cmd_info_script),
+    Subcommand("tclversion", cmd_info_tclversion),
+    Subcommand("vars", cmd_info_vars),
+];
+
+/// # info args *procName*
+///
+/// Returns a list of the names of the proc's arguments.
+pub fn cmd_info_args(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "procname")?;
+
+    interp.proc_args(&argv[2].as_string())
+}
+
+/// # info body *procName*
+///
+/// Returns the proc's body.
+pub fn cmd_info_body(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "procname")?;
+
+    interp.proc_body(&argv[2].as_string())
+}
+
+/// # info cmdcount
+///
+/// Returns the total number of commands the interpreter has executed since it was created.
+pub fn cmd_info_cmdcount(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(interp.cmd_count() as MoltInt)
+}
+
+/// # info commands ?*pattern*?
+///
+/// Returns a list of the names of the commands defined in the interpreter, including
+/// procs, that match the optional glob pattern.
+pub fn cmd_info_commands(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?pattern?")?;
+
+    info_names(argv, interp.command_names())
+}
+
+/// # info complete *command*
+pub fn cmd_info_complete(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "command")?;
+
+    if interp.complete(&*argv[2].as_string()) {
+        molt_ok!(true)
+    } else {
+        molt_ok!(false)
+    }
+}
+
+/// # info coroutine
+///
+/// Returns the fully qualified name of the current coroutine's command, or the empty
+/// string if there's no current coroutine.
+pub fn cmd_info_coroutine(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(interp.coroutine_name().unwrap_or_default())
+}
+
+/// # info default *procName* *arg* *varName*
+///
+/// Returns 1 if the proc's argument has a default value, assigning the default value to
+/// the variable, and 0 otherwise, assigning the empty string to the variable.
+pub fn cmd_info_default(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 5, "procname arg varname")?;
+
+    let default = interp.proc_default(&argv[2].as_string(), &argv[3].as_string())?;
+    let var_name = &*argv[4].as_string();
+
+    match default {
+        Some(value) => {
+            interp.set_var2(var_name, value)?;
+            molt_ok!(true)
+        }
+        None => {
+            interp.set_var(var_name, "")?;
+            molt_ok!(false)
+        }
+    }
+}
+
+/// # info exists *varName*
+///
+/// Returns 1 if the named variable, array, or array element is visible in the current
+/// scope, and 0 otherwise.
+pub fn cmd_info_exists(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "varName")?;
+
+    molt_ok!(interp.var_exists(&argv[2].as_string()))
+}
+
+/// # info globals ?*pattern*?
+///
+/// Returns a list of the names of the global variables that match the optional glob
+/// pattern.
+pub fn cmd_info_globals(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?pattern?")?;
+
+    info_names(argv, interp.vars_global())
+}
+
+/// # info level ?*number*?
+///
+/// Returns the current proc call depth, 0 at the global level.  If the number is given,
+/// returns the command being executed at that level, as a list; if the number is zero
+/// or negative, it's relative to the current level.
+pub fn cmd_info_level(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?number?")?;
+
+    let current = interp.scope_level() as MoltInt;
+
+    if argv.len() == 2 {
+        return molt_ok!(current);
+    }
+
+    let number = argv[2].as_int()?;
+    let level = if number <= 0 { current + number } else { number };
+
+    match interp.scope_argv(level.max(0) as usize) {
+        Some(cmd) if level <= current => molt_ok!(cmd),
+        _ => molt_err!("bad level \"{}\"", argv[2]),
+    }
+}
+
+/// # info locals ?*pattern*?
+///
+/// Returns a list of the names of the variables local to the current proc that match
+/// the optional glob pattern.  Variables linked to other scopes by `global` aren't
+/// local.
+pub fn cmd_info_locals(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?pattern?")?;
+
+    info_names(argv, interp.vars_local())
+}
+
+/// # info nameofexecutable
+///
+/// Returns the full path of the running executable, or the empty string if it can't
+/// be determined.
+pub fn cmd_info_nameofexecutable(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    match std::env::current_exe() {
+        Ok(path) => molt_ok!(path.to_string_lossy().to_string()),
+        Err(_) => molt_ok!(),
+    }
+}
+
+/// # info patchlevel
+///
+/// Returns the full version of Molt, e.g., "0.1.0".
+pub fn cmd_info_patchlevel(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(env!("CARGO_PKG_VERSION"))
+}
+
+/// # info procs ?*pattern*?
+///
+/// Returns a list of the names of the procs defined in the interpreter that match the
+/// optional glob pattern.
+pub fn cmd_info_procs(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?pattern?")?;
+
+    info_names(argv, interp.proc_names())
+}
+
+/// # info script ?*filename*?
+///
+/// Returns the name of the script file currently being evaluated, or the empty string
+/// if none.  If the file name is given, it replaces the current name.
+pub fn cmd_info_script(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?filename?")?;
+
+    if argv.len() == 3 {
+        interp.set_script_file(&argv[2].as_string());
+    }
+
+    molt_ok!(interp.script_file())
+}
+
+/// # info tclversion
+///
+/// Returns the major and minor version of Molt, e.g., "0.1".
+pub fn cmd_info_tclversion(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(concat!(env!("CARGO_PKG_VERSION_MAJOR"), ".", env!("CARGO_PKG_VERSION_MINOR")))
+}
+
+/// # info vars ?*pattern*?
+///
+/// Returns a list of the names of the variables visible in the current scope that match
+/// the optional glob pattern.
+pub fn cmd_info_vars(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?pattern?")?;
+
+    info_names(argv, interp.vars_in_scope())
+}
+
+/// Filters a list of names for an `info` subcommand by the optional glob pattern in
+/// `argv[2]`.
+fn info_names(argv: &[Value], mut names: MoltList) -> MoltResult {
+    if argv.len() == 3 {
+        let pattern = &*argv[2].as_string();
+        names.retain(|name| glob_match(pattern, &name.as_string()));
+    }
+
+    molt_ok!(names)
+}
+
+/// # interp *subcommand* ?*arg* ...?
+pub fn cmd_interp(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&INTERP_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const INTERP_SUBCOMMANDS: [Subcommand; 12] = [
+    Subcommand("alias", cmd_interp_alias),
+    Subcommand("aliases", cmd_interp_aliases),
+    Subcommand("create", cmd_interp_create),
+    Subcommand("delete", cmd_interp_delete),
+    Subcommand("eval", cmd_interp_eval),
+    Subcommand("exists", cmd_interp_exists),
+    Subcommand("expose", cmd_interp_expose),
+    Subcommand("hidden", cmd_interp_hidden),
+    Subcommand("hide", cmd_interp_hide),
+    Subcommand("invokehidden", cmd_interp_invokehidden),
+    Subcommand("issafe", cmd_interp_issafe),
+    Subcommand("slaves", cmd_interp_slaves),
+];
+
+/// # interp alias *srcPath* *srcCmd* ?*targetPath* *targetCmd* ?*arg* ...??
+///
+/// Creates the alias *srcCmd* in the interpreter *srcPath*, which calls *targetCmd* with
+/// the given leading arguments followed by its own arguments, and returns its name.  Given
+/// only *srcCmd*, returns the alias's target command prefix; given an empty *targetPath*
+/// and no *targetCmd*, deletes the alias.  The alias and its target must be in the same
+/// interpreter.
+pub fn cmd_interp_alias(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "slavePath slaveCmd ?masterPath masterCmd? ?arg ...?")?;
+
+    if argv.len() >= 6 && argv[2].as_list()? != argv[4].as_list()? {
+        slave_path(interp, &argv[2])?;
+        slave_path(interp, &argv[4])?;
+        return molt_err!("cross-interpreter aliases are not supported");
+    }
+
+    let src = slave_path(interp, &argv[2])?;
+    let name = argv[3].as_string();
+
+    if argv.len() >= 6 {
+        src.add_alias(&name, &argv[5..])?;
+        return molt_ok!(argv[3].clone());
+    }
+
+    if argv.len() == 5 && !argv[4].as_string().is_empty() {
+        return molt_err!(
+            "wrong # args: should be \"interp alias slavePath slaveCmd ?masterPath masterCmd? \
+             ?arg ...?\""
+        );
+    }
+
+    let target = match src.alias_target(&name) {
+        Some(target) => target,
+        None => return molt_err!("alias \"{}\" not found", name),
+    };
+
+    if argv.len() == 4 {
+        molt_ok!(target)
+    } else {
+        src.remove_command(&name);
+        molt_ok!()
+    }
+}
+
+/// # interp aliases ?*path*?
+///
+/// Returns a list of the names of the aliases in the interpreter.
+pub fn cmd_interp_aliases(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?path?")?;
+
+    match argv.get(2) {
+        Some(path) => molt_ok!(slave_path(interp, path)?.alias_names()),
+        None => molt_ok!(interp.alias_names()),
+    }
+}
+
+/// # interp create ?-safe? ?--? ?*path*?
+///
+/// Creates a slave interpreter with the standard commands, and returns its path.  With
+/// `-safe`, or if the parent is safe, the slave is a safe interpreter.  If the path is
+/// omitted, the slave is a child of the current interpreter with a name of the form
+/// `interpN`.
+pub fn cmd_interp_create(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut safe = false;
+    let mut i = 2;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        i += 1;
+
+        match opt.as_str() {
+            "-safe" => safe = true,
+            "--" => break,
+            _ => return molt_err!("bad option \"{}\": must be -safe or --", opt),
+        }
+    }
+
+    if argv.len() > i + 1 {
+        return molt_err!("wrong # args: should be \"interp create ?-safe? ?--? ?path?\"");
+    }
+
+    let (parent, name) = match argv.get(i) {
+        Some(path) => match parent_path(interp, path)? {
+            Some(found) => found,
+            None => {
+                return molt_err!("interpreter named \"{}\" already exists, cannot create", path)
+            }
+        },
+        None => {
+            let name = (0..)
+                .map(|n| format!("interp{}", n))
+                .find(|name| !interp.has_slave(name))
+                .expect("unused slave name");
+            (interp, name)
+        }
+    };
+
+    if safe {
+        parent.create_safe_slave(&name)?;
+    } else {
+        parent.create_slave(&name)?;
+    }
+
+    molt_ok!(argv.get(i).cloned().unwrap_or_else(|| Value::from(name)))
+}
+
+/// # interp delete ?*path* ...?
+///
+/// Deletes the slave interpreters, and all of their slaves.
+pub fn cmd_interp_delete(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    for path in &argv[2..] {
+        let deleted = match parent_path(interp, path)? {
+            Some((parent, name)) => parent.delete_slave(&name),
+            None => return molt_err!("cannot delete the current interpreter"),
+        };
+
+        if !deleted {
+            return molt_err!("could not find interpreter \"{}\"", path);
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # interp eval *path* *arg* ?*arg* ...?
+///
+/// Concatenates the arguments as for `concat`, and evaluates the result as a script at
+/// the global scope of the interpreter, returning its result.
+pub fn cmd_interp_eval(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "path arg ?arg ...?")?;
+
+    let script = concat_values(&argv[3..]);
+
+    slave_path(interp, &argv[2])?.eval_global(&script)
+}
+
+/// # interp exists ?*path*?
+///
+/// Returns 1 if the interpreter exists, and 0 otherwise.
+pub fn cmd_interp_exists(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?path?")?;
+
+    match argv.get(2) {
+        Some(path) => {
+            path.as_list()?;
+            molt_ok!(slave_path(interp, path).is_ok())
+        }
+        None => molt_ok!(true),
+    }
+}
+
+/// # interp expose *path* *hiddenCmd* ?*exposedCmd*?
+///
+/// Exposes the interpreter's hidden command *hiddenCmd* as the command *exposedCmd*, which
+/// defaults to *hiddenCmd*.  A safe interpreter can't expose commands.
+pub fn cmd_interp_expose(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "path hiddenCmdName ?cmdName?")?;
+
+    if interp.is_safe() {
+        return molt_err!("permission denied: safe interpreter cannot expose commands");
+    }
+
+    let hidden_name = argv[3].as_string();
+    let name = argv.get(4).unwrap_or(&argv[3]).as_string();
+
+    slave_path(interp, &argv[2])?.expose_command(&hidden_name, &name)
+}
+
+/// # interp hidden ?*path*?
+///
+/// Returns a sorted list of the names of the interpreter's hidden commands.
+pub fn cmd_interp_hidden(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?path?")?;
+
+    let target = match argv.get(2) {
+        Some(path) => slave_path(interp, path)?,
+        None => interp,
+    };
+
+    let mut names = target.hidden_names();
+    names.sort_by_key(|name| name.to_string());
+    molt_ok!(names)
+}
+
+/// # interp hide *path* *exposedCmd* ?*hiddenCmd*?
+///
+/// Hides the interpreter's command *exposedCmd*, as the hidden command *hiddenCmd*, which
+/// defaults to *exposedCmd*.  A safe interpreter can't hide commands.
+pub fn cmd_interp_hide(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "path cmdName ?hiddenCmdName?")?;
+
+    if interp.is_safe() {
+        return molt_err!("permission denied: safe interpreter cannot hide commands");
+    }
+
+    let name = argv[3].as_string();
+    let hidden_name = argv.get(4).unwrap_or(&argv[3]).as_string();
+
+    slave_path(interp, &argv[2])?.hide_command(&name, &hidden_name)
+}
+
+/// # interp invokehidden *path* ?--? *hiddenCmd* ?*arg* ...?
+///
+/// Invokes the interpreter's hidden command with the arguments, returning its result.  A
+/// safe interpreter can't invoke hidden commands.
+pub fn cmd_interp_invokehidden(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "path ?--? cmd ?arg ...?")?;
+
+    if interp.is_safe() {
+        return molt_err!("not allowed to invoke hidden commands from safe interpreter");
+    }
+
+    let first = if *argv[3].as_string() == "--" { 4 } else { 3 };
+
+    if first == argv.len() {
+        return molt_err!("wrong # args: should be \"interp invokehidden path ?--? cmd ?arg ...?\"");
+    }
+
+    slave_path(interp, &argv[2])?.invoke_hidden(&argv[first..])
+}
+
+/// # interp issafe ?*path*?
+///
+/// Returns 1 if the interpreter is safe, and 0 otherwise.
+pub fn cmd_interp_issafe(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?path?")?;
+
+    match argv.get(2) {
+        Some(path) => molt_ok!(slave_path(interp, path)?.is_safe()),
+        None => molt_ok!(interp.is_safe()),
+    }
+}
+
+/// # interp slaves ?*path*?
+///
+/// Returns a list of the names of the slaves of the interpreter.
+pub fn cmd_interp_slaves(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 3, "?path?")?;
+
+    let target = match argv.get(2) {
+        Some(path) => slave_path(interp, path)?,
+        None => interp,
+    };
+
+    let mut names = target.slave_names();
+    names.sort_by_key(|name| name.to_string());
+    molt_ok!(names)
+}
+
+/// Finds the interpreter with the given path: a list of slave names, each a slave of
+/// the interpreter before it, beginning with the current interpreter.
+fn slave_path<'a>(interp: &'a mut Interp, path: &Value) -> Result<&'a mut Interp, ResultCode> {
+    let mut target = interp;
+
+    for name in path.as_list()?.iter() {
+        target = match target.slave(&name.as_string()) {
+            Some(slave) => slave,
+            None => return molt_err!("could not find interpreter \"{}\"", path),
+        };
+    }
+
+    Ok(target)
+}
+
+/// Finds the parent of the slave interpreter with the given path, returning it with the
+/// slave's name, or None if the path is empty, i.e., the current interpreter.
+fn parent_path<'a>(
+    interp: &'a mut Interp,
+    path: &Value,
+) -> Result<Option<(&'a mut Interp, String)>, ResultCode> {
+    let names = path.as_list()?;
+
+    let (name, parent) = match names.split_last() {
+        Some(split) => split,
+        None => return Ok(None),
+    };
+
+    let mut target = interp;
+
+    for parent_name in parent {
+        target = match target.slave(&parent_name.as_string()) {
+            Some(slave) => slave,
+            None => return molt_err!("could not find interpreter \"{}\"", path),
+        };
+    }
+
+    Ok(Some((target, name.to_string())))
+}
+
+/// # join *list* ?*joinString*?
+///
+/// Joins the elements of a list with a string.  The join string defaults to " ".
+pub fn cmd_join(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "list ?joinString?")?;
+
+    let list = &argv[1].as_list()?;
+
+    let join_string = if argv.len() == 3 {
+        argv[2].to_string()
+    } else {
+        " ".to_string()
+    };
+
+    // Note: the elements are joined as is; there is no list quoting.
+    let list: Vec<String> = list.iter().map(|v| v.to_string()).collect();
+
+    molt_ok!(list.join(&join_string))
+}
+
+/// # lappend *varName* ?*value* ...?
+///
+/// Appends any number of values to a variable's list value, which need not
+/// initially exist.
+pub fn cmd_lappend(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "varName ?value ...?")?;
+
+    let var_name = &*argv[1].as_string();
+
+    let mut list: MoltList = match interp.var(var_name) {
+        Ok(old_value) => old_value.as_list()?.to_vec(),
+        Err(_) => Vec::new(),
+    };
+
+    list.extend_from_slice(&argv[2..]);
+
+    interp.set_var2(var_name, Value::from(list))
+}
+
+/// # lassign *list* *varName* ?*varName* ...?
+///
+/// Assigns the elements of the list to the named variables, in order, setting any
+/// variables left over to the empty string.  Returns the elements that weren't assigned.
+pub fn cmd_lassign(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "list varName ?varName ...?")?;
+
+    let list = &*argv[1].as_list()?;
+    let var_names = &argv[2..];
+
+    for (i, var_name) in var_names.iter().enumerate() {
+        let value = list.get(i).cloned().unwrap_or_else(Value::empty);
+        interp.set_var2(&var_name.as_string(), value)?;
+    }
+
+    if list.len() > var_names.len() {
+        molt_ok!(list_to_string(&list[var_names.len()..]))
+    } else {
+        molt_ok!()
+    }
+}
+
+/// # lindex *list* ?*index* ...?
+///
+/// Returns an element from the list, indexing into nested lists.  A single *index*
+/// argument may be a list of indices.
+pub fn cmd_lindex(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "list ?index ...?")?;
+
+    let indices: MoltList = if argv.len() == 3 {
+        argv[2].as_list()?.to_vec()
+    } else {
+        argv[2..].to_vec()
+    };
+
+    let mut value = argv[1].clone();
+
+    for index_val in &indices {
+        let list = value.as_list()?;
+        let index = get_index(index_val, list.len())?;
+
+        value = if index < 0 || index as usize >= list.len() {
+            Value::empty()
+        }  else {
+            list[index as usize].clone()
+        };
+    }
+
+    molt_ok!(value)
+}
+
+/// # linsert *list* *index* *value* ?*value* ...?
+///
+/// Returns a new list with the values inserted just before the element at the given
+/// index.  Here `end` refers to the position after the last element, so that
+/// `linsert $list end $value` appends the value.
+pub fn cmd_linsert(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 0, "list index value ?value ...?")?;
+
+    let list = &*argv[1].as_list()?;
+    let index = get_index(&argv[2], list.len() + 1)?.max(0).min(list.len() as MoltInt) as usize;
+
+    let mut result: MoltList = Vec::with_capacity(list.len() + argv.len() - 3);
+    result.extend_from_slice(&list[..index]);
+    result.extend_from_slice(&argv[3..]);
+    result.extend_from_slice(&list[index..]);
+
+    molt_ok!(result)
+}
+
+/// # list ?*arg*...?
+///
+/// Converts its arguments into a canonical list.
+pub fn cmd_list(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // // No arg check needed; can take any number.
+    molt_ok!(list_to_string(&argv[1..]))
+}
+
+/// # llength *list*
+///
+/// Returns the length of the list.
+pub fn cmd_llength(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "list")?;
+
+    // let list = interp.get_list(argv[1])?;
+
+    molt_ok!(Value::from(argv[1].as_list()?.len() as MoltInt))
+}
+
+/// # lmap *varList* *list* ?*varList* *list* ...? *body*
+///
+/// Loops like `foreach`, returning a list of the results of each iteration of the body.
+/// Iterations that end with `continue` contribute nothing to the result.
+pub fn cmd_lmap(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 0, "varList list ?varList list ...? body")?;
+
+    if argv.len() % 2 == 1 {
+        return molt_err!("wrong # args: should be \"lmap varList list ?varList list ...? body\"");
+    }
+
+    let mut result: MoltList = Vec::new();
+    foreach_loop(interp, argv, Some(&mut result))?;
+
+    molt_ok!(result)
+}
+
+/// # lrange *list* *first* *last*
+///
+/// Returns the elements of the list from index *first* through index *last*, inclusive.
+/// Indices outside the list are clamped to the ends of the list; if *first* is greater
+/// than *last*, the result is the empty list.
+pub fn cmd_lrange(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 4, "list first last")?;
+
+    let list = &*argv[1].as_list()?;
+    let first = get_index(&argv[2], list.len())?.max(0);
+    let last = get_index(&argv[3], list.len())?.min(list.len() as MoltInt - 1);
+
+    if first > last {
+        return molt_ok!();
+    }
+
+    molt_ok!(list_to_string(&list[first as usize..=last as usize]))
+}
+
+/// # lrepeat *count* ?*value* ...?
+///
+/// Returns a list containing the values repeated *count* times.
+pub fn cmd_lrepeat(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "count ?value ...?")?;
+
+    let count = argv[1].as_int()?;
+
+    if count < 0 {
+        return molt_err!("bad count \"{}\": must be integer >= 0", count);
+    }
+
+    let values = &argv[2..];
+    repeated_size(values.len(), count as usize)?;
+
+    // The list grows as needed, so that an excessive count fails cleanly rather than
+    // aborting on a single huge allocation.
+    let mut result: MoltList = Vec::new();
+
+    if !values.is_empty() {
+        for _ in 0..count {
+            result.extend_from_slice(values);
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # lreplace *list* *first* *last* ?*value* ...?
+///
+/// Returns a new list in which the elements from index *first* through index *last* have
+/// been replaced by the given values, if any.  If *last* is less than *first*, no elements
+/// are deleted and the values are inserted at *first*.
+pub fn cmd_lreplace(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 0, "list first last ?value ...?")?;
+
+    let list = &*argv[1].as_list()?;
+    let len = list.len() as MoltInt;
+    let first = get_index(&argv[2], list.len())?.max(0).min(len) as usize;
+    let last = get_index(&argv[3], list.len())?.min(len - 1);
+
+    // The index just past the last element to delete.
+    let rest = if last < first as MoltInt { first } else { last as usize + 1 };
+
+    let mut result: MoltList = Vec::with_capacity(list.len() + argv.len() - 4);
+    result.extend_from_slice(&list[..first]);
+    result.extend_from_slice(&argv[4..]);
+    result.extend_from_slice(&list[rest..]);
+
+    molt_ok!(result)
+}
+
+/// # lreverse *list*
+///
+/// Returns the list with its elements in reverse order.
+pub fn cmd_lreverse(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "list")?;
+
+    let mut result: MoltList = argv[1].as_list()?.to_vec();
+    result.reverse();
+
+    molt_ok!(result)
+}
+
+/// # lsearch ?*option* ...? *list* *pattern*
+///
+/// Searches the list for elements that match the pattern, returning the index of the
+/// first match, or -1 if there is none.  See molt-book for the options.
+pub fn cmd_lsearch(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "?-option value ...? list pattern")?;
+
+    // FIRST, get the options.
+    let mut mode = "-glob";
+    let mut all = false;
+    let mut inline = false;
+    let mut not = false;
+    let mut start: Option<&Value> = None;
+
+    let last = argv.len() - 2;
+    let mut i = 1;
+
+    while i < last {
+        let opt = argv[i].as_string();
+
+        match opt.as_str() {
+            "-all" => all = true,
+            "-exact" => mode = "-exact",
+            "-glob" => mode = "-glob",
+            "-inline" => inline = true,
+            "-not" => not = true,
+            "-regexp" => mode = "-regexp",
+            "-start" => {
+                if i + 1 == last {
+                    return molt_err!("missing starting index");
+                }
+                i += 1;
+                start = Some(&argv[i]);
+            }
+            _ => {
+                return molt_err!("bad option \"{}\": must be -all, -exact, -glob, -inline, -not, -regexp, or -start", opt);
+            }
+        }
+
+        i += 1;
+    }
+
+    // NEXT, get the list, pattern, and starting index.
+    let list = &*argv[last].as_list()?;
+    let pattern = &*argv[last + 1].as_string();
+
+    let start = match start {
+        Some(index) => get_index(index, list.len())?.max(0) as usize,
+        None => 0,
+    };
+
+    let regex = if mode == "-regexp" {
+        match Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => return molt_err!("couldn't compile regular expression pattern: {}", e),
+        }
+    } else {
+        None
+    };
+
+    // NEXT, search the list.
+    let mut result: MoltList = Vec::new();
+
+    for (i, item) in list.iter().enumerate().skip(start) {
+        let item_str = &*item.as_string();
+
+        let matched = match &regex {
+            Some(re) => re.is_match(item_str),
+            None if mode == "-exact" => item_str == pattern,
+            None => glob_match(pattern, item_str),
+        };
+
+        if matched != not {
+            let found = if inline { item.clone() } else { Value::from(i as MoltInt) };
+
+            if !all {
+                return molt_ok!(found);
+            }
+            result.push(found);
+        }
+    }
+
+    if all {
+        molt_ok!(result)
+    } else if inline {
+        molt_ok!()
+    } else {
+        molt_ok!(-1)
+    }
+}
+
+/// # lset *varName* ?*index* ...? *value*
+///
+/// Replaces the element of the list in the variable at the given index path with the
+/// value, saves the new list in the variable, and returns it.  As with `lindex`, a
+/// single *index* argument may be a list of indices.
+pub fn cmd_lset(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "listVar ?index? ?index ...? value")?;
+
+    let var_name = &*argv[1].as_string();
+    let list_val = interp.var(var_name)?;
+    let value = argv[argv.len() - 1].clone();
+
+    let indices: MoltList = if argv.len() == 4 {
+        argv[2].as_list()?.to_vec()
+    } else {
+        argv[2..argv.len() - 1].to_vec()
+    };
+
+    let new_val = list_path_set(&list_val, &indices, value)?;
+    interp.set_var2(var_name, new_val)
+}
+
+/// Replaces the element at the given index path in a nested list, returning the new list.
+/// An index equal to the length of its list appends the value to that list.
+fn list_path_set(list_val: &Value, indices: &[Value], value: Value) -> MoltResult {
+    if indices.is_empty() {
+        return Ok(value);
+    }
+
+    let mut list = list_val.as_list()?.to_vec();
+    let index = get_index(&indices[0], list.len())?;
+
+    if index < 0 || index as usize > list.len() {
+        return molt_err!("list index out of range");
+    }
+
+    let index = index as usize;
+
+    if index == list.len() {
+        if indices.len() > 1 {
+            return molt_err!("list index out of range");
+        }
+        list.push(value);
+    } else {
+        list[index] = list_path_set(&list[index], &indices[1..], value)?;
+    }
+
+    molt_ok!(list)
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+enum SortMode {
+    Ascii,
+    Dictionary,
+    Integer,
+    Real,
+}
+
+/// A parsed `lsort` sort key.
+enum SortKey {
+    Str(String),
+    Int(MoltInt),
+    Flt(MoltFloat),
+    Val(Value),
+}
+
+/// The `lsort` options, which determine how two sort keys are compared.
+struct SortOptions {
+    mode: SortMode,
+    decreasing: bool,
+    nocase: bool,
+    unique: bool,
+    index: Option<Value>,
+    command: Option<Value>,
+}
+
+/// # lsort ?*option* ...? *list*
+///
+/// Sorts the list, returning a new list.  The sort is stable.
+/// See molt-book for the options.
+pub fn cmd_lsort(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "?-option value ...? list")?;
+
+    // FIRST, get the options.
+    let mut opts = SortOptions {
+        mode: SortMode::Ascii,
+        decreasing: false,
+        nocase: false,
+        unique: false,
+        index: None,
+        command: None,
+    };
+
+    let last = argv.len() - 1;
+    let mut i = 1;
+
+    while i < last {
+        let opt = argv[i].as_string();
+
+        match opt.as_str() {
+            "-ascii" => opts.mode = SortMode::Ascii,
+            "-command" => {
+                if i + 1 == last {
+                    return molt_err!("\"-command\" option must be followed by comparison command");
+                }
+                i += 1;
+                opts.command = Some(argv[i].clone());
+            }
+            "-decreasing" => opts.decreasing = true,
+            "-dictionary" => opts.mode = SortMode::Dictionary,
+            "-increasing" => opts.decreasing = false,
+            "-index" => {
+                if i + 1 == last {
+                    return molt_err!("\"-index\" option must be followed by list index");
+                }
+                i += 1;
+                // Validate the index now, before sorting.
+                get_index(&argv[i], 0)?;
+                opts.index = Some(argv[i].clone());
+            }
+            "-integer" => opts.mode = SortMode::Integer,
+            "-nocase" => opts.nocase = true,
+            "-real" => opts.mode = SortMode::Real,
+            "-unique" => opts.unique = true,
+            _ => {
+                return molt_err!("bad option \"{}\": must be -ascii, -command, -decreasing, -dictionary, -increasing, -index, -integer, -nocase, -real, or -unique", opt);
+            }
+        }
+
+        i += 1;
+    }
+
+    // NEXT, get the sort keys.  This also validates the elements.
+    let list = &*argv[last].as_list()?;
+    let mut keyed: Vec<(SortKey, Value)> = Vec::with_capacity(list.len());
+
+    for item in list {
+        keyed.push((sort_key(&opts, item)?, item.clone()));
+    }
+
+    // NEXT, sort the list.
+    let keyed = merge_sort(keyed, &mut |a, b| sort_compare(interp, &opts, &a.0, &b.0))?;
+
+    // NEXT, if -unique, keep only the last of each run of equal elements.
+    let mut result: MoltList = Vec::with_capacity(keyed.len());
+
+    for i in 0..keyed.len() {
+        if opts.unique && i + 1 < keyed.len()
+            && sort_compare(interp, &opts, &keyed[i].0, &keyed[i + 1].0)? == Ordering::Equal
+        {
+            continue;
+        }
+        result.push(keyed[i].1.clone());
+    }
+
+    molt_ok!(result)
+}
+
+/// Computes the sort key for an `lsort` list item, given the options.
+fn sort_key(opts: &SortOptions, item: &Value) -> Result<SortKey, ResultCode> {
+    // FIRST, if there's an index, extract the element to compare.
+    let item = if let Some(index) = &opts.index {
+        let sublist = item.as_list()?;
+        let i = get_index(index, sublist.len())?;
+
+        if i < 0 || i as usize >= sublist.len() {
+            return molt_err!("element {} missing from sublist \"{}\"", i, item);
+        }
+        sublist[i as usize].clone()
+    } else {
+        item.clone()
+    };
+
+    // NEXT, get the key.
+    if opts.command.is_some() {
+        return Ok(SortKey::Val(item));
+    }
+
+    Ok(match opts.mode {
+        SortMode::Ascii if opts.nocase => SortKey::Str(item.as_string().to_lowercase()),
+        SortMode::Ascii | SortMode::Dictionary => SortKey::Str(item.to_string()),
+        SortMode::Integer => SortKey::Int(item.as_int()?),
+        SortMode::Real => SortKey::Flt(item.as_float()?),
+    })
+}
+
+/// Compares two `lsort` sort keys, given the options.  A `-command` comparison calls the
+/// command with the two items, and so can return an error.
+fn sort_compare(
+    interp: &mut Interp,
+    opts: &SortOptions,
+    a: &SortKey,
+    b: &SortKey,
+) -> Result<Ordering, ResultCode> {
+    let order = match (a, b) {
+        (SortKey::Val(a), SortKey::Val(b)) => {
+            // opts.command is always set when the keys are SortKey::Val.
+            let mut cmd = (*opts.command.as_ref().unwrap().as_list()?).clone();
+            cmd.push(a.clone());
+            cmd.push(b.clone());
+
+            let result = interp.eval(&list_to_string(&cmd))?;
+
+            match result.as_int() {
+                Ok(int) => int.cmp(&0),
+                Err(_) => return molt_err!("-compare command returned non-integer result"),
+            }
+        }
+        (SortKey::Int(a), SortKey::Int(b)) => a.cmp(b),
+        (SortKey::Flt(a), SortKey::Flt(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (SortKey::Str(a), SortKey::Str(b)) if opts.mode == SortMode::Dictionary => {
+            dictionary_compare(a, b)
+        }
+        (SortKey::Str(a), SortKey::Str(b)) => a.cmp(b),
+        _ => unreachable!(),
+    };
+
+    Ok(if opts.decreasing { order.reverse() } else { order })
+}
+
+/// A stable merge sort with a comparison function that can fail.  The standard library's
+/// sorts can't propagate errors, and may panic if a user-defined comparison (e.g., an
+/// `lsort -command` script) isn't a total order.
+fn merge_sort<T, F>(mut items: Vec<T>, cmp: &mut F) -> Result<Vec<T>, ResultCode>
+where
+    F: FnMut(&T, &T) -> Result<Ordering, ResultCode>,
+{
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, cmp)?;
+    let right = merge_sort(right, cmp)?;
+
+    let mut result = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // Take from the left on ties, to keep the sort stable.
+        if cmp(l, r)? == Ordering::Greater {
+            result.push(right.next().unwrap());
+        } else {
+            result.push(left.next().unwrap());
+        }
+    }
+
+    result.extend(left);
+    result.extend(right);
+
+    Ok(result)
+}
+
+/// Compares two strings as `lsort -dictionary` does: case is ignored except as a
+/// tie-breaker, and embedded runs of digits are compared as integers.
+fn dictionary_compare(a: &str, b: &str) -> Ordering {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut i = 0;
+    let mut j = 0;
+    let mut tie_breaker = Ordering::Equal;
+
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            // FIRST, skip leading zeros; more leading zeros sort later on a tie.
+            let (zi, zj) = (i, j);
+            while i < a.len() - 1 && a[i] == '0' && a[i + 1].is_ascii_digit() {
+                i += 1;
+            }
+            while j < b.len() - 1 && b[j] == '0' && b[j + 1].is_ascii_digit() {
+                j += 1;
+            }
+            if tie_breaker == Ordering::Equal {
+                tie_breaker = (i - zi).cmp(&(j - zj));
+            }
+
+            // NEXT, compare the digit runs: the longer run is the larger number.
+            let (si, sj) = (i, j);
+            while i < a.len() && a[i].is_ascii_digit() {
+                i += 1;
+            }
+            while j < b.len() && b[j].is_ascii_digit() {
+                j += 1;
+            }
+
+            let order = (i - si).cmp(&(j - sj)).then_with(|| a[si..i].cmp(&b[sj..j]));
+
+            if order != Ordering::Equal {
+                return order;
+            }
+            continue;
+        }
+
+        if a[i] != b[j] {
+            let la = a[i].to_lowercase().next().unwrap_or(a[i]);
+            let lb = b[j].to_lowercase().next().unwrap_or(b[j]);
+
+            if la != lb {
+                return la.cmp(&lb);
+            }
+
+            // Upper case sorts before lower case on a tie.
+            if tie_breaker == Ordering::Equal {
+                tie_breaker = a[i].cmp(&b[j]);
+            }
+        }
+
+        i += 1;
+        j += 1;
+    }
+
+    (a.len() - i).cmp(&(b.len() - j)).then(tie_breaker)
+}
+
+/// # namespace *subcommand* ?*arg*...?
+pub fn cmd_namespace(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&NAMESPACE_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const NAMESPACE_SUBCOMMANDS: [Subcommand; 6] = [
+    Subcommand("children", cmd_namespace_children),
+    Subcommand("current", cmd_namespace_current),
+    Subcommand("delete", cmd_namespace_delete),
+    Subcommand("ensemble", cmd_namespace_ensemble),
+    Subcommand("eval", cmd_namespace_eval),
+    Subcommand("exists", cmd_namespace_exists),
+];
+
+/// # namespace children ?*namespace*? ?*pattern*?
+///
+/// Returns a list of the fully qualified names of the children of the namespace, which
+/// defaults to the current namespace, that match the optional glob pattern.  An
+/// unqualified pattern is relative to the namespace.
+pub fn cmd_namespace_children(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 4, "?name? ?pattern?")?;
+
+    let namespace = if argv.len() >= 3 {
+        interp.qualify_namespace(&argv[2].as_string())
+    } else {
+        interp.current_namespace().to_string()
+    };
+
+    let children = interp.namespace_children(&namespace)?;
+
+    if argv.len() == 4 {
+        let pattern = argv[3].as_string();
+        let pattern = if pattern.starts_with("::") {
+            pattern.to_string()
+        } else if namespace == "::" {
+            format!("::{}", pattern)
+        } else {
+            format!("{}::{}", namespace, pattern)
+        };
+
+        let children: MoltList = children
+            .into_iter()
+            .filter(|name| glob_match(&pattern, &name.as_string()))
+            .collect();
+        molt_ok!(children)
+    } else {
+        molt_ok!(children)
+    }
+}
+
+/// # namespace current
+///
+/// Returns the fully qualified name of the current namespace.
+pub fn cmd_namespace_current(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(interp.current_namespace())
+}
+
+/// # namespace delete ?*namespace* ...?
+///
+/// Deletes the namespaces, along with their child namespaces, commands, and variables.
+pub fn cmd_namespace_delete(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    for name in &argv[2..] {
+        interp.delete_namespace(&name.as_string())?;
+    }
+
+    molt_ok!()
+}
+
+/// # namespace ensemble *subcommand* ?*arg*...?
+pub fn cmd_namespace_ensemble(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&NAMESPACE_ENSEMBLE_SUBCOMMANDS, &argv[2].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const NAMESPACE_ENSEMBLE_SUBCOMMANDS: [Subcommand; 1] = [
+    Subcommand("create", cmd_namespace_ensemble_create),
+];
+
+/// # namespace ensemble create ?*option* *value* ...?
+///
+/// Creates an ensemble command for the current namespace, and returns its fully qualified
+/// name.  The options are `-command`, the name of the command, which defaults to the
+/// namespace's name; `-map`, a dictionary of subcommand names and command prefixes; and
+/// `-subcommands`, a list of the valid subcommand names.
+pub fn cmd_namespace_ensemble_create(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let namespace = interp.current_namespace().to_string();
+    let mut name = namespace.clone();
+    let mut map = None;
+    let mut subcommands = None;
+
+    for pair in argv[3..].chunks(2) {
+        let opt = pair[0].as_string();
+
+        match opt.as_str() {
+            "-command" | "-map" | "-subcommands" if pair.len() == 1 => {
+                return molt_err!("missing value for option \"{}\"", opt);
+            }
+            "-command" => name = interp.qualify_namespace(&pair[1].as_string()),
+            "-map" => map = Some((*pair[1].as_dict()?).clone()),
+            "-subcommands" => subcommands = Some(pair[1].as_list()?.to_vec()),
+            _ => {
+                return molt_err!(
+                    "bad option \"{}\": must be -command, -map, or -subcommands", opt);
+            }
+        }
+    }
+
+    if name == "::" {
+        return molt_err!("can't create an ensemble for the global namespace");
+    }
+
+    let ensemble = Ensemble::new(&namespace, map, subcommands);
+    interp.add_command_object(&name, Rc::new(ensemble));
+
+    molt_ok!(name)
+}
+
+/// # namespace eval *namespace* *arg* ?*arg* ...?
+///
+/// Concatenates the arguments as for `concat`, and evaluates the result in the namespace,
+/// creating the namespace if it doesn't exist.
+pub fn cmd_namespace_eval(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "name arg ?arg...?")?;
+
+    let script = if argv.len() == 4 {
+        argv[3].to_string()
+    } else {
+        concat_values(&argv[3..])
+    };
+
+    interp.namespace_eval(&argv[2].as_string(), &script)
+}
+
+/// # namespace exists *namespace*
+///
+/// Returns 1 if the namespace exists, and 0 otherwise.
+pub fn cmd_namespace_exists(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(interp.namespace_exists(&argv[2].as_string()))
+}
+
+/// # package *subcommand* ?*arg*...?
+pub fn cmd_package(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&PACKAGE_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const PACKAGE_SUBCOMMANDS: [Subcommand; 9] = [
+    Subcommand("forget", cmd_package_forget),
+    Subcommand("ifneeded", cmd_package_ifneeded),
+    Subcommand("names", cmd_package_names),
+    Subcommand("present", cmd_package_present),
+    Subcommand("provide", cmd_package_provide),
+    Subcommand("require", cmd_package_require),
+    Subcommand("vcompare", cmd_package_vcompare),
+    Subcommand("versions", cmd_package_versions),
+    Subcommand("vsatisfies", cmd_package_vsatisfies),
+];
+
+/// # package forget ?*package* ...?
+///
+/// Forgets the packages, so that they are neither provided nor available.
+pub fn cmd_package_forget(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    for name in &argv[2..] {
+        interp.forget_package(&name.as_string());
+    }
+
+    molt_ok!()
+}
+
+/// # package ifneeded *package* *version* ?*script*?
+///
+/// Makes the version of the package available, to be loaded by evaluating the script at
+/// the global scope.  Without a script, returns the script registered for the version,
+/// or the empty string.
+pub fn cmd_package_ifneeded(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "package version ?script?")?;
+
+    let name = argv[2].as_string();
+    let version = argv[3].as_string();
+
+    if argv.len() == 5 {
+        interp.add_package_script(&name, &version, argv[4].clone())?;
+        return molt_ok!();
+    }
+
+    package::parse_version(&version)?;
+
+    match interp.package_script(&name, &version) {
+        Some(script) => molt_ok!(script),
+        None => molt_ok!(),
+    }
+}
+
+/// # package names
+///
+/// Returns the names of the packages that are provided or available, in sorted order.
+pub fn cmd_package_names(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 2, 2, "")?;
+
+    molt_ok!(interp.package_names())
+}
+
+/// # package present ?-exact? *package* ?*requirement*?
+///
+/// Returns the provided version of the package, which must satisfy the requirement,
+/// if any, as for `package require`, but doesn't load the package.
+pub fn cmd_package_present(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let (name, requirement, exact) = package_requirement(argv)?;
+
+    if interp.package_provided(&name).is_none() {
+        return molt_err!("package {} is not present", name);
+    }
+
+    interp.require_package(&name, requirement.as_deref(), exact)
+}
+
+/// # package provide *package* ?*version*?
+///
+/// Notes that the version of the package is provided.  Without a version, returns the
+/// provided version of the package, or the empty string.
+pub fn cmd_package_provide(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 4, "package ?version?")?;
+
+    let name = argv[2].as_string();
+
+    if argv.len() == 4 {
+        return interp.provide_package(&name, &argv[3].as_string());
+    }
+
+    match interp.package_provided(&name) {
+        Some(version) => molt_ok!(version),
+        None => molt_ok!(),
+    }
+}
+
+/// # package require ?-exact? *package* ?*requirement*?
+///
+/// Loads the package, unless it's already provided, and returns its version.  With
+/// `-exact`, the requirement is a version, which must be provided; otherwise, it's a
+/// requirement as for `package vsatisfies`, and the latest version that satisfies it is
+/// loaded.
+pub fn cmd_package_require(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let (name, requirement, exact) = package_requirement(argv)?;
+
+    interp.require_package(&name, requirement.as_deref(), exact)
+}
+
+/// Parses the arguments to `package present` and `package require`: the package name,
+/// the requirement, if any, and whether it's `-exact`.
+fn package_requirement(argv: &[Value]) -> Result<(String, Option<String>, bool), ResultCode> {
+    let exact = argv.len() > 2 && *argv[2].as_string() == "-exact";
+
+    match if exact { &argv[3..] } else { &argv[2..] } {
+        [name] if !exact => Ok((name.to_string(), None, false)),
+        [name, requirement] => Ok((name.to_string(), Some(requirement.to_string()), exact)),
+        _ => molt_err!(
+            "wrong # args: should be \"{} {} ?-exact? package ?requirement?\"",
+            argv[0],
+            argv[1]
+        ),
+    }
+}
+
+/// # package vcompare *version1* *version2*
+///
+/// Compares the versions, returning -1, 0, or 1 as the first is earlier than, the same
+/// as, or later than the second.
+pub fn cmd_package_vcompare(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "version1 version2")?;
+
+    let a = argv[2].as_string();
+    let b = argv[3].as_string();
+    package::parse_version(&a)?;
+    package::parse_version(&b)?;
+
+    let result: MoltInt = match package::compare(&a, &b) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    };
+
+    molt_ok!(result)
+}
+
+/// # package versions *package*
+///
+/// Returns the available versions of the package.
+pub fn cmd_package_versions(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "package")?;
+
+    molt_ok!(interp.package_versions(&argv[2].as_string()))
+}
+
+/// # package vsatisfies *version* *requirement*
+///
+/// Returns 1 if the version satisfies the requirement, and 0 otherwise.  The requirement
+/// *min* is satisfied by versions no earlier than *min* with the same major version;
+/// *min*- by versions no earlier than *min*; and *min*-*max* by versions no earlier than
+/// *min* and earlier than *max*.
+pub fn cmd_package_vsatisfies(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "version requirement")?;
+
+    let version = argv[2].as_string();
+    let requirement = argv[3].as_string();
+    package::parse_version(&version)?;
+    package::check_requirement(&requirement)?;
+
+    molt_ok!(package::satisfies(&version, &requirement))
+}
+
+/// # pid ?*channelId*?
+///
+/// Returns the id of the current process.  Given a channel, returns the list of the ids
+/// of the processes in its command pipeline, or the empty list if it isn't one.
+pub fn cmd_pid(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 2, "?channelId?")?;
+
+    if argv.len() == 1 {
+        return molt_ok!(std::process::id() as MoltInt);
+    }
+
+    let pids = interp.channel(&argv[1].as_string())?.pids();
+
+    molt_ok!(Value::from(pids.iter().map(|&pid| Value::from(pid as MoltInt)).collect::<MoltList>()))
+}
+
+pub fn cmd_proc(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 4, 4, "name args body")?;
+
+    // FIRST, get the arguments
+    let name = &*argv[1].as_string();
+    let args = &*argv[2].as_list()?;
+    let body = &*argv[3].as_string();
+
+    // NEXT, validate the argument specs
+    check_arg_specs(args)?;
+
+    // NEXT, add the command.
+    interp.add_proc(name, args, body)
+}
+
+/// # puts ?-nonewline? ?*channelId*? *string*
+///
+/// Writes the string to the channel, `stdout` by default, followed by a newline unless
+/// `-nonewline` is given.
+pub fn cmd_puts(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 4, "?-nonewline? ?channelId? string")?;
+
+    let nonewline = argv.len() > 2 && *argv[1].as_string() == "-nonewline";
+
+    let (name, string, newline) = match argv.len() {
+        2 => (Value::from("stdout"), &argv[1], true),
+        3 if nonewline => (Value::from("stdout"), &argv[2], false),
+        3 => (argv[1].clone(), &argv[2], true),
+        _ if nonewline => (argv[2].clone(), &argv[3], false),
+        // The obsolete form: puts channelId string nonewline
+        _ if *argv[3].as_string() == "nonewline" => (argv[1].clone(), &argv[2], false),
+        _ => return molt_err!("bad argument \"{}\": should be \"nonewline\"", argv[3]),
+    };
+
+    let name = name.as_string();
+    let channel = output_channel(interp, &name)?;
+    let result = if newline {
+        channel.write_str(&format!("{}\n", string))
+    } else {
+        channel.write_str(&string.as_string())
+    };
+
+    match result {
+        Ok(_) => molt_ok!(),
+        Err(e) => molt_err!("error writing \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # read ?-nonewline? *channelId*
+/// # read *channelId* *numChars*
+///
+/// Reads the remaining input from the channel, less the final newline if `-nonewline` is
+/// given, or at most *numChars* characters.
+pub fn cmd_read(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let nonewline = argv.len() == 3 && *argv[1].as_string() == "-nonewline";
+
+    if argv.len() != 2 && argv.len() != 3 {
+        return molt_err!(
+            "wrong # args: should be \"read channelId ?numChars?\" or \"read ?-nonewline? channelId\""
+        );
+    }
+
+    let name = if nonewline { argv[2].as_string() } else { argv[1].as_string() };
+
+    let count = if argv.len() == 3 && !nonewline {
+        match argv[2].as_int() {
+            Ok(count) if count >= 0 => Some(count as usize),
+            _ => return molt_err!("expected non-negative integer but got \"{}\"", argv[2]),
+        }
+    } else {
+        None
+    };
+
+    let channel = input_channel(interp, &name)?;
+    let result = match count {
+        Some(count) => channel.read_chars(count),
+        None => channel.read_to_end(),
+    };
+
+    match result {
+        Ok(mut string) => {
+            if nonewline && string.ends_with('\n') {
+                string.pop();
+            }
+            molt_ok!(string)
+        }
+        Err(e) => molt_err!("error reading \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # regexp ?*switches*? *exp* *string* ?*matchVar*? ?*subMatchVar* ...?
+///
+/// Matches the regular expression against the string, assigning the match and its
+/// capture groups to the variables, and returns the number of matches: 1 or 0, or the
+/// number of matches if `-all` is given.  See molt-book for the switches.
+pub fn cmd_regexp(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let (switches, i) = regexp_switches(argv, &REGEXP_SWITCHES)?;
+    check_args(1, argv, i + 2, 0, "?-option ...? exp string ?matchVar? ?subMatchVar ...?")?;
+
+    let re = regexp_compile(&argv[i].as_string(), &switches)?;
+    let string = &*argv[i + 1].as_string();
+    let var_names = &argv[i + 2..];
+
+    if switches.inline && !var_names.is_empty() {
+        return molt_err!("regexp match variables not allowed when using -inline");
+    }
+
+    // FIRST, find the matches, saving the values of the variables for the last one,
+    // or the values of all of them if -inline.
+    let mut offset = regexp_start(string, &switches)?;
+    let mut count: MoltInt = 0;
+    let mut values: MoltList = Vec::new();
+
+    while let Some(caps) = re.captures_at(string, offset) {
+        count += 1;
+
+        if !switches.inline {
+            values.clear();
+        }
+
+        let num_groups = if switches.inline { caps.len() } else { var_names.len() };
+
+        for group in 0..num_groups {
+            let value = match caps.get(group) {
+                m if switches.indices => regexp_indices(string, m),
+                Some(m) => Value::from(m.as_str()),
+                None => Value::empty(),
+            };
+            values.push(value);
+        }
+
+        // NEXT, continue after the match, or one character after an empty match.
+        let m = caps.get(0).unwrap();
+        offset = match string[m.end()..].chars().next() {
+            Some(ch) if m.start() == m.end() => m.end() + ch.len_utf8(),
+            _ => m.end(),
+        };
+
+        if !switches.all || offset >= string.len() {
+            break;
+        }
+    }
+
+    // NEXT, return the matches or assign the variables.
+    if switches.inline {
+        return molt_ok!(values);
+    }
+
+    for (var_name, value) in var_names.iter().zip(values) {
+        interp.set_var2(&var_name.as_string(), value)?;
+    }
+
+    molt_ok!(count)
+}
+
+/// The switches accepted by `regexp`.
+const REGEXP_SWITCHES: [&str; 10] = [
+    "-all",
+    "-indices",
+    "-inline",
+    "-expanded",
+    "-line",
+    "-linestop",
+    "-lineanchor",
+    "-nocase",
+    "-start",
+    "--",
+];
+
+/// The switches given to a `regexp` or `regsub` command.
+#[derive(Default)]
+struct RegexpSwitches<'a> {
+    all: bool,
+    indices: bool,
+    inline: bool,
+    expanded: bool,
+    linestop: bool,
+    lineanchor: bool,
+    nocase: bool,
+    start: Option<&'a Value>,
+}
+
+/// Parses the switches at the beginning of a `regexp` or `regsub` command, given the
+/// switches the command accepts.  Returns the switches and the index of the first
+/// argument following them.
+fn regexp_switches<'a>(
+    argv: &'a [Value],
+    valid: &[&str],
+) -> Result<(RegexpSwitches<'a>, usize), ResultCode> {
+    let mut switches = RegexpSwitches::default();
+    let mut i = 1;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        if !valid.contains(&opt.as_str()) {
+            let (last, rest) = valid.split_last().unwrap();
+            return molt_err!("bad option \"{}\": must be {}, or {}", opt, rest.join(", "), last);
+        }
+
+        i += 1;
+
+        match opt.as_str() {
+            "-all" => switches.all = true,
+            "-indices" => switches.indices = true,
+            "-inline" => switches.inline = true,
+            "-expanded" => switches.expanded = true,
+            "-line" => {
+                switches.linestop = true;
+                switches.lineanchor = true;
+            }
+            "-linestop" => switches.linestop = true,
+            "-lineanchor" => switches.lineanchor = true,
+            "-nocase" => switches.nocase = true,
+            "-start" => {
+                if i < argv.len() {
+                    switches.start = Some(&argv[i]);
+                    i += 1;
+                }
+            }
+            _ => break,
+        }
+    }
+
+    Ok((switches, i))
+}
+
+/// Compiles a regular expression according to the switches.  By default, as in
+/// standard TCL, `.` matches newlines and `^` and `$` match only at the ends of the
+/// string.
+fn regexp_compile(pattern: &str, switches: &RegexpSwitches) -> Result<Regex, ResultCode> {
+    let result = RegexBuilder::new(pattern)
+        .case_insensitive(switches.nocase)
+        .ignore_whitespace(switches.expanded)
+        .dot_matches_new_line(!switches.linestop)
+        .multi_line(switches.lineanchor)
+        .build();
+
+    match result {
+        Ok(re) => Ok(re),
+        Err(e) => molt_err!("couldn't compile regular expression pattern: {}", e),
+    }
+}
+
+/// Gets the `-indices` value for a match or capture group: the indices of its first and
+/// last characters in the string, or `-1 -1` if the group didn't participate.
+fn regexp_indices(string: &str, m: Option<Match>) -> Value {
+    let (first, last) = match m {
+        Some(m) => {
+            let first = string[..m.start()].chars().count() as MoltInt;
+            (first, first + m.as_str().chars().count() as MoltInt - 1)
+        }
+        None => (-1, -1),
+    };
+
+    Value::from(vec![Value::from(first), Value::from(last)])
+}
+
+/// Gets the byte offset in the string at which matching starts, given the switches'
+/// `-start` character index.
+fn regexp_start(string: &str, switches: &RegexpSwitches) -> Result<usize, ResultCode> {
+    let start = match switches.start {
+        Some(index) => get_index(index, string.chars().count())?.max(0) as usize,
+        None => return Ok(0),
+    };
+
+    Ok(string.char_indices().nth(start).map_or(string.len(), |(i, _)| i))
+}
+
+/// # regsub ?*switches*? *exp* *string* *subSpec* ?*varName*?
+///
+/// Replaces the first match of the regular expression in the string with the
+/// substitution spec, or all matches if `-all` is given.  Assigns the result to the
+/// variable and returns the number of substitutions, or returns the result if there's
+/// no variable.  See molt-book for the switches and the substitution syntax.
+pub fn cmd_regsub(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let (switches, i) = regexp_switches(argv, &REGSUB_SWITCHES)?;
+    check_args(1, argv, i + 3, i + 4, "?-option ...? exp string subSpec ?varName?")?;
+
+    let re = regexp_compile(&argv[i].as_string(), &switches)?;
+    let string = &*argv[i + 1].as_string();
+    let spec = &*argv[i + 2].as_string();
+
+    // FIRST, copy the string to the result, substituting the matches.
+    let mut offset = regexp_start(string, &switches)?;
+    let mut result = String::from(&string[..offset]);
+    let mut count: MoltInt = 0;
+
+    while offset <= string.len() {
+        let caps = match re.captures_at(string, offset) {
+            Some(caps) => caps,
+            None => break,
+        };
+
+        count += 1;
+
+        let m = caps.get(0).unwrap();
+        result.push_str(&string[offset..m.start()]);
+        regsub_spec(spec, &caps, &mut result);
+        offset = m.end();
+
+        // NEXT, always consume at least one character after an empty match, so that
+        // -all terminates.
+        if m.start() == m.end() {
+            match string[offset..].chars().next() {
+                Some(ch) => {
+                    result.push(ch);
+                    offset += ch.len_utf8();
+                }
+                None => offset += 1,
+            }
+        }
+
+        if !switches.all {
+            break;
+        }
+    }
+
+    if offset < string.len() {
+        result.push_str(&string[offset..]);
+    }
+
+    // NEXT, return the result or assign the variable.
+    if argv.len() == i + 4 {
+        interp.set_var(&argv[i + 3].as_string(), &result)?;
+        molt_ok!(count)
+    } else {
+        molt_ok!(result)
+    }
+}
+
+/// The switches accepted by `regsub`.
+const REGSUB_SWITCHES: [&str; 8] = [
+    "-all",
+    "-expanded",
+    "-line",
+    "-linestop",
+    "-lineanchor",
+    "-nocase",
+    "-start",
+    "--",
+];
+
+/// Appends a `regsub` substitution spec to the result, replacing `&` and `\0` with the
+/// match and `\1` through `\9` with the capture groups.  `\&` and `\\` are a literal
+/// `&` and `\`; any other backslash is copied as is.
+fn regsub_spec(spec: &str, caps: &Captures, result: &mut String) {
+    let mut chars = spec.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        let group = match ch {
+            '&' => 0,
+            '\\' => match chars.peek() {
+                Some(&next) if next == '&' || next == '\\' => {
+                    result.push(next);
+                    chars.next();
+                    continue;
+                }
+                Some(next) if next.is_ascii_digit() => {
+                    let group = next.to_digit(10).unwrap() as usize;
+                    chars.next();
+                    group
+                }
+                _ => {
+                    result.push('\\');
+                    continue;
+                }
+            },
+            _ => {
+                result.push(ch);
+                continue;
+            }
+        };
+
+        if let Some(m) = caps.get(group) {
+            result.push_str(m.as_str());
+        }
+    }
+}
+
+/// # rename *oldName* *newName*
+///
+/// Renames the command called *oldName* to have the *newName*.  If the
+/// *newName* is "", the command is destroyed.
+pub fn cmd_rename(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 3, "oldName newName")?;
+
+    // FIRST, get the arguments
+    let old_name = &*argv[1].as_string();
+    let new_name = &*argv[2].as_string();
+
+    if !interp.has_command(old_name) {
+        if new_name.is_empty() {
+            return molt_err!("can't delete \"{}\": command doesn't exist", old_name);
+        }
+        return molt_err!("can't rename \"{}\": command doesn't exist", old_name);
+    }
+
+    // NEXT, rename or remove the command.
+    if new_name.is_empty() {
+        interp.remove_command(old_name);
+    } else {
+        interp.rename_command(old_name, new_name)?;
+    }
+
+    molt_ok!()
+}
+
+/// # return ?-code *code*? ?-level *level*? ?-errorcode *list*? ?-errorinfo *info*? ?value?
+///
+/// Returns from a proc.  The proc will return the given value, or ""
+/// if no value is specified.  The options allow a proc to return any of the standard
+/// result codes, through any number of levels.  See molt-book for full semantics.
+///
+/// ## TCL Liens
+///
+/// * The `-options` option and user-defined options are not supported.
+pub fn cmd_return(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // FIRST, the options come in pairs; if there's an argument left over, it's the value.
+    let (opts, value) = if argv.len().is_multiple_of(2) {
+        (&argv[1..argv.len() - 1], argv[argv.len() - 1].clone())
+    } else {
+        (&argv[1..], Value::empty())
+    };
+
+    // NEXT, parse the options.
+    let mut code = 0;
+    let mut level = 1;
+    let mut error_code = None;
+    let mut error_info = None;
+
+    for pair in opts.chunks(2) {
+        let opt = pair[0].as_string();
+
+        match opt.as_str() {
+            "-code" => code = parse_completion_code(&pair[1])?,
+            "-errorcode" => error_code = Some(pair[1].clone()),
+            "-errorinfo" => error_info = Some(pair[1].to_string()),
+            "-level" => {
+                level = match pair[1].as_int() {
+                    Ok(level) if level >= 0 => level as usize,
+                    _ => {
+                        return molt_err!(
+                            "bad -level value: expected non-negative integer but got \"{}\"",
+                            pair[1]
+                        )
+                    }
+                }
+            }
+            _ => {
+                return molt_err!(
+                    "bad option \"{}\": must be -code, -errorcode, -errorinfo, or -level",
+                    opt
+                )
+            }
+        }
+    }
+
+    // NEXT, returning with "-code return" is the same as returning normally one more level.
+    if code == 2 {
+        code = 0;
+        level += 1;
+    }
+
+    // NEXT, get the result code to return at the requested level.
+    let pending = match code {
+        0 => None,
+        1 => {
+            let mut data = ErrorData::new(value.clone());
+
+            if let Some(error_code) = error_code {
+                data.set_error_code(error_code);
+            }
+
+            if let Some(error_info) = error_info {
+                data.set_error_info(&error_info);
+            }
+
+            Some(ResultCode::Error(data))
+        }
+        3 => Some(ResultCode::Break),
+        4 => Some(ResultCode::Continue),
+        _ => Some(ResultCode::Other(code, value.clone())),
+    };
+
+    // NEXT, at level 0 the result code is returned immediately.
+    if level == 0 {
+        return match pending {
+            None => Ok(value),
+            Some(code) => Err(code),
+        };
+    }
+
+    Err(ResultCode::Return(ReturnData::new(value, level, pending)))
+}
+
+/// # scan *string* *formatString* ?*varName* ...?
+///
+/// Parses fields from the string according to the format string, in the style of the
+/// C `sscanf` function, and assigns them to the variables, returning the number of
+/// conversions performed.  If there are no variables, returns the values as a list.
+/// See molt-book for the details.
+pub fn cmd_scan(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "string format ?varName ...?")?;
+
+    let var_names = &argv[3..];
+    let values = scan(&argv[1].as_string(), &argv[2].as_string(), var_names.len())?;
+
+    // FIRST, if the input ran out before the first conversion, there are no values.
+    let values = match values {
+        Some(values) => values,
+        None if var_names.is_empty() => return molt_ok!(),
+        None => return molt_ok!(-1),
+    };
+
+    // NEXT, return the values inline, or assign them to the variables.
+    if var_names.is_empty() {
+        let list: MoltList = values
+            .into_iter()
+            .map(|value| value.unwrap_or_else(Value::empty))
+            .collect();
+        return molt_ok!(list);
+    }
+
+    let mut count: MoltInt = 0;
+
+    for (var_name, value) in var_names.iter().zip(values) {
+        if let Some(value) = value {
+            interp.set_var2(&var_name.as_string(), value)?;
+            count += 1;
+        }
+    }
+
+    molt_ok!(count)
+}
+
+/// # seek *channelId* *offset* ?*origin*?
+///
+/// Moves the channel's position to *offset* bytes from the *origin*, which is `start`,
+/// `current`, or `end`, defaulting to `start`.  Buffered output is written first, and
+/// buffered input is discarded.
+pub fn cmd_seek(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 4, "channelId offset ?origin?")?;
+
+    let name = argv[1].as_string();
+    let offset = argv[2].as_int()?;
+
+    let pos = if argv.len() == 4 {
+        match argv[3].as_string().as_str() {
+            "start" if offset >= 0 => SeekFrom::Start(offset as u64),
+            "start" => return molt_err!("error during seek on \"{}\": invalid argument", name),
+            "current" => SeekFrom::Current(offset),
+            "end" => SeekFrom::End(offset),
+            origin => {
+                return molt_err!("bad origin \"{}\": must be start, current, or end", origin)
+            }
+        }
+    } else if offset >= 0 {
+        SeekFrom::Start(offset as u64)
+    } else {
+        return molt_err!("error during seek on \"{}\": invalid argument", name);
+    };
+
+    match interp.channel(&name)?.seek(pos) {
+        Ok(_) => molt_ok!(),
+        Err(e) => molt_err!("error during seek on \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # set *varName* ?*newValue*?
+///
+/// Sets variable *varName* to *newValue*, returning the value.
+/// If *newValue* is omitted, returns the variable's current value,
+/// returning an error if the variable is unknown.  The *varName* may name
+/// an array element, e.g., `a(x)`.
+pub fn cmd_set(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "varName ?newValue?")?;
+
+    let var_name = &*argv[1].as_string();
+
+    if argv.len() == 3 {
+        interp.set_var2(var_name, argv[2].clone())
+    } else {
+        molt_ok!(interp.var(var_name)?.clone())
+    }
+}
+
+/// # split *string* ?*splitChars*?
+///
+/// Splits the string into a list at each occurrence of any of the split characters,
+/// which default to the whitespace characters.  Adjacent split characters produce empty
+/// elements.  If *splitChars* is the empty string, the string is split into its
+/// individual characters.
+pub fn cmd_split(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "string ?splitChars?")?;
+
+    let string = &*argv[1].as_string();
+
+    let split_chars: Vec<char> = if argv.len() == 3 {
+        argv[2].as_string().chars().collect()
+    } else {
+        vec![' ', '\t', '\n', '\r']
+    };
+
+    let mut result: MoltList = Vec::new();
+
+    if string.is_empty() {
+        return molt_ok!(result);
+    }
+
+    if split_chars.is_empty() {
+        for ch in string.chars() {
+            result.push(Value::from(ch.to_string()));
+        }
+    } else {
+        for item in string.split(&split_chars[..]) {
+            result.push(Value::from(item));
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # string *subcommand* ?*arg*...?
+pub fn cmd_string(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&STRING_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const STRING_SUBCOMMANDS: [Subcommand; 22] = [
+    Subcommand("cat", cmd_string_cat),
+    Subcommand("compare", cmd_string_compare),
+    Subcommand("equal", cmd_string_equal),
+    Subcommand("first", cmd_string_first),
+    Subcommand("index", cmd_string_index),
+    Subcommand("is", cmd_string_is),
+    Subcommand("last", cmd_string_last),
+    Subcommand("length", cmd_string_length),
+    Subcommand("map", cmd_string_map),
+    Subcommand("match", cmd_string_match),
+    Subcommand("range", cmd_string_range),
+    Subcommand("repeat", cmd_string_repeat),
+    Subcommand("replace", cmd_string_replace),
+    Subcommand("reverse", cmd_string_reverse),
+    Subcommand("tolower", cmd_string_tolower),
+    Subcommand("totitle", cmd_string_totitle),
+    Subcommand("toupper", cmd_string_toupper),
+    Subcommand("trim", cmd_string_trim),
+    Subcommand("trimleft", cmd_string_trimleft),
+    Subcommand("trimright", cmd_string_trimright),
+    Subcommand("wordend", cmd_string_wordend),
+    Subcommand("wordstart", cmd_string_wordstart),
+];
+
+/// # string cat ?*arg* ...?
+///
+/// Concatenates the arguments, returning the result.
+pub fn cmd_string_cat(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // No arg check needed; can take any number.  The string reps are shared, not copied.
+    let args: Vec<_> = argv[2..].iter().map(|arg| arg.as_string()).collect();
+    let mut result = String::with_capacity(args.iter().map(|arg| arg.len()).sum());
+
+    for arg in &args {
+        result.push_str(arg);
+    }
+
+    molt_ok!(result)
+}
+
+/// # string compare ?-nocase? ?-length *length*? *string1* *string2*
+///
+/// Compares the two strings character by character, returning -1, 0, or 1 as the first
+/// is less than, equal to, or greater than the second.
+pub fn cmd_string_compare(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "?-nocase? ?-length int? string1 string2")?;
+
+    let ordering = string_compare(argv)?;
+
+    molt_ok!(match ordering {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    })
+}
+
+/// # string equal ?-nocase? ?-length *length*? *string1* *string2*
+///
+/// Returns 1 if the two strings are equal, and 0 otherwise.
+pub fn cmd_string_equal(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "?-nocase? ?-length int? string1 string2")?;
+
+    molt_ok!(string_compare(argv)? == Ordering::Equal)
+}
+
+/// The implementation of `string compare` and `string equal`.  A negative `-length`
+/// compares the whole strings.
+fn string_compare(argv: &[Value]) -> Result<Ordering, ResultCode> {
+    let mut nocase = false;
+    let mut length: Option<usize> = None;
+
+    let last = argv.len() - 2;
+    let mut i = 2;
+
+    while i < last {
+        let opt = argv[i].as_string();
+
+        match opt.as_str() {
+            "-nocase" => nocase = true,
+            "-length" => {
+                if i + 1 == last {
+                    return molt_err!(
+                        "wrong # args: should be \"{} {} ?-nocase? ?-length int? string1 string2\"",
+                        argv[0], argv[1]);
+                }
+                i += 1;
+                let len = argv[i].as_int()?;
+                length = if len < 0 { None } else { Some(len as usize) };
+            }
+            _ => return molt_err!("bad option \"{}\": must be -nocase or -length", opt),
+        }
+
+        i += 1;
+    }
+
+    let mut a: Vec<char> = argv[last].as_string().chars().collect();
+    let mut b: Vec<char> = argv[last + 1].as_string().chars().collect();
+
+    if let Some(len) = length {
+        a.truncate(len);
+        b.truncate(len);
+    }
+
+    if nocase {
+        let a: String = a.iter().collect();
+        let b: String = b.iter().collect();
+        Ok(a.to_lowercase().cmp(&b.to_lowercase()))
+    } else {
+        Ok(a.cmp(&b))
+    }
+}
+
+/// # string first *needleString* *haystackString* ?*startIndex*?
+///
+/// Returns the index of the first occurrence of the needle in the haystack at or after
+/// the start index, or -1 if there is none.
+pub fn cmd_string_first(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "needleString haystackString ?startIndex?")?;
+
+    let needle: Vec<char> = argv[2].as_string().chars().collect();
+    let haystack: Vec<char> = argv[3].as_string().chars().collect();
+
+    let start = if argv.len() == 5 {
+        get_index(&argv[4], haystack.len())?.max(0) as usize
+    } else {
+        0
+    };
+
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return molt_ok!(-1);
+    }
+
+    for i in start..=haystack.len() - needle.len() {
+        if haystack[i..i + needle.len()] == needle[..] {
+            return molt_ok!(i as MoltInt);
+        }
+    }
+
+    molt_ok!(-1)
+}
+
+/// # string index *string* *charIndex*
+///
+/// Returns the character at the given index, or the empty string if the index is out
+/// of range.
+pub fn cmd_string_index(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "string charIndex")?;
+
+    let string = &*argv[2].as_string();
+    let index = get_index(&argv[3], string.chars().count())?;
+
+    if index < 0 {
+        return molt_ok!();
+    }
+
+    match string.chars().nth(index as usize) {
+        Some(ch) => molt_ok!(ch.to_string()),
+        None => molt_ok!(),
+    }
+}
+
+/// # string is *class* ?-strict? ?-failindex *varName*? *string*
+///
+/// Returns 1 if the string is a member of the given character class, and 0 otherwise.
+/// See molt-book for the classes.
+pub fn cmd_string_is(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 0, "class ?-strict? ?-failindex var? str")?;
+
+    let class = argv[2].as_string();
+
+    if !STRING_CLASSES.contains(&class.as_str()) {
+        return molt_err!("bad class \"{}\": must be {}", class, STRING_CLASSES_LIST);
+    }
+
+    // FIRST, get the options.
+    let mut strict = false;
+    let mut fail_var: Option<&Value> = None;
+
+    let last = argv.len() - 1;
+    let mut i = 3;
+
+    while i < last {
+        let opt = argv[i].as_string();
+
+        match opt.as_str() {
+            "-strict" => strict = true,
+            "-failindex" => {
+                if i + 1 == last {
+                    return molt_err!(
+                        "wrong # args: should be \"string is class ?-strict? ?-failindex var? str\"");
+                }
+                i += 1;
+                fail_var = Some(&argv[i]);
+            }
+            _ => return molt_err!("bad option \"{}\": must be -strict or -failindex", opt),
+        }
+
+        i += 1;
+    }
+
+    // NEXT, test the string.  On failure, get the index of the first character that
+    // doesn't belong to the class, or -1 if the string fails as a whole.
+    let string = &*argv[last].as_string();
+
+    let fail_index: Option<MoltInt> = if string.is_empty() {
+        if strict { Some(0) } else { None }
+    } else {
+        match class.as_str() {
+            "alnum" => first_failure(string, |c| c.is_alphanumeric()),
+            "alpha" => first_failure(string, |c| c.is_alphabetic()),
+            "ascii" => first_failure(string, |c| c.is_ascii()),
+            "boolean" => Value::get_bool(string).err().map(|_| 0),
+            "digit" => first_failure(string, |c| c.is_numeric()),
+            "double" => {
+                if Value::get_int(string).is_ok() || Value::get_float(string).is_ok() {
+                    None
+                } else {
+                    Some(number_failure(string, read_float))
+                }
+            }
+            "false" => match Value::get_bool(string) {
+                Ok(false) => None,
+                _ => Some(0),
+            },
+            "integer" => Value::get_int(string).err().map(|_| number_failure(string, read_int)),
+            "list" => argv[last].as_list().err().map(|_| -1),
+            "lower" => first_failure(string, |c| c.is_lowercase()),
+            "space" => first_failure(string, |c| c.is_whitespace()),
+            "true" => match Value::get_bool(string) {
+                Ok(true) => None,
+                _ => Some(0),
+            },
+            "upper" => first_failure(string, |c| c.is_uppercase()),
+            _ => unreachable!(),
+        }
+    };
+
+    match fail_index {
+        None => molt_ok!(true),
+        Some(index) => {
+            if let Some(var_name) = fail_var {
+                interp.set_var2(&var_name.as_string(), Value::from(index))?;
+            }
+            molt_ok!(false)
+        }
+    }
+}
+
+// The `string is` classes.
+const STRING_CLASSES: [&str; 13] = [
+    "alnum", "alpha", "ascii", "boolean", "digit", "double", "false", "integer", "list",
+    "lower", "space", "true", "upper",
+];
+
+const STRING_CLASSES_LIST: &str = "alnum, alpha, ascii, boolean, digit, double, false, \
+    integer, list, lower, space, true, or upper";
+
+/// Returns the index of the first character in the string that fails the predicate, or
+/// `None` if there is none.
+fn first_failure<P>(string: &str, pred: P) -> Option<MoltInt>
+where
+    P: Fn(char) -> bool,
+{
+    string.chars().position(|c| !pred(c)).map(|i| i as MoltInt)
+}
+
+/// Given a string that doesn't parse as a number, finds the index of the first character
+/// that isn't part of the number as read by the given reader, or -1 if the whole string
+/// has the form of a number but is nevertheless invalid, e.g., out of range.
+fn number_failure(string: &str, reader: fn(&mut CharPtr) -> Option<String>) -> MoltInt {
+    let mut ptr = CharPtr::new(string);
+    let leading = string.chars().take_while(|c| c.is_whitespace()).count();
+    ptr.skip_over(leading);
+
+    match reader(&mut ptr) {
+        Some(number) => {
+            let index = leading + number.chars().count();
+
+            if string.chars().skip(index).all(|c| c.is_whitespace()) {
+                -1
+            } else {
+                index as MoltInt
+            }
+        }
+        None => leading as MoltInt,
+    }
+}
+
+/// # string last *needleString* *haystackString* ?*lastIndex*?
+///
+/// Returns the index of the last occurrence of the needle in the haystack that begins
+/// at or before the last index, or -1 if there is none.
+pub fn cmd_string_last(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "needleString haystackString ?lastIndex?")?;
+
+    let needle: Vec<char> = argv[2].as_string().chars().collect();
+    let haystack: Vec<char> = argv[3].as_string().chars().collect();
+
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return molt_ok!(-1);
+    }
+
+    let mut last = (haystack.len() - needle.len()) as MoltInt;
+
+    if argv.len() == 5 {
+        last = last.min(get_index(&argv[4], haystack.len())?);
+    }
+
+    if last < 0 {
+        return molt_ok!(-1);
+    }
+
+    for i in (0..=last as usize).rev() {
+        if haystack[i..i + needle.len()] == needle[..] {
+            return molt_ok!(i as MoltInt);
+        }
+    }
+
+    molt_ok!(-1)
+}
 
-    // FIRST, if we're at the global scope this is a no-op.
-    if interp.scope_level() > 0 {
-        for name in &argv[1..] {
-            // TODO: Should upvar take the name as a Value?
-            interp.upvar(0, &*name.as_string());
+/// # string length *string*
+///
+/// Returns the number of characters in the string.
+pub fn cmd_string_length(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "string")?;
+
+    molt_ok!(argv[2].as_string().chars().count() as MoltInt)
+}
+
+/// # string map ?-nocase? *charMap* *string*
+///
+/// Replaces each occurrence of a key in the *charMap* with its value, in a single
+/// left-to-right pass over the string.  When more than one key matches at the same
+/// position, the first one in the *charMap* wins.
+pub fn cmd_string_map(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "?-nocase? charMap string")?;
+
+    let nocase = argv.len() == 5 && nocase_option(&argv[2])?;
+
+    let map_list = &*argv[argv.len() - 2].as_list()?;
+
+    if map_list.len() % 2 == 1 {
+        return molt_err!("char map list unbalanced");
+    }
+
+    // FIRST, get the keys as characters, so that they can be compared in place.  Empty
+    // keys never match.
+    let mut pairs: Vec<(Vec<char>, String)> = Vec::new();
+
+    for pair in map_list.chunks(2) {
+        let key: Vec<char> = pair[0].as_string().chars().collect();
+
+        if !key.is_empty() {
+            pairs.push((key, pair[1].to_string()));
         }
     }
-    molt_ok!()
+
+    // NEXT, scan the string.
+    let chars: Vec<char> = argv[argv.len() - 1].as_string().chars().collect();
+    let mut result = String::with_capacity(chars.len());
+    let mut i = 0;
+
+    'scan: while i < chars.len() {
+        for (key, value) in &pairs {
+            if key.len() <= chars.len() - i && chars_match(&chars[i..i + key.len()], key, nocase) {
+                result.push_str(value);
+                i += key.len();
+                continue 'scan;
+            }
+        }
+
+        result.push(chars[i]);
+        i += 1;
+    }
+
+    molt_ok!(result)
 }
 
-#[derive(Eq, PartialEq, Debug)]
-enum IfWants {
-    Expr,
-    ThenBody,
-    SkipThenClause,
-    ElseClause,
-    ElseBody,
+/// # string match ?-nocase? *pattern* *string*
+///
+/// Returns 1 if the string matches the glob pattern, and 0 otherwise.
+pub fn cmd_string_match(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 5, "?-nocase? pattern string")?;
+
+    let nocase = argv.len() == 5 && nocase_option(&argv[2])?;
+
+    let pattern = &*argv[argv.len() - 2].as_string();
+    let string = &*argv[argv.len() - 1].as_string();
+
+    if nocase {
+        molt_ok!(glob_match_nocase(pattern, string))
+    } else {
+        molt_ok!(glob_match(pattern, string))
+    }
 }
 
-/// # if *expr* ?then? *script* elseif *expr* ?then? *script* ... ?else? ?*script*?
+/// # string range *string* *first* *last*
 ///
-/// Standard conditional.  Returns the value of the selected script (or
-/// "" if there is no else body and the none of the previous branches were selected).
+/// Returns the characters of the string from index *first* through index *last*.
+/// Indices outside the string are clamped to its ends; if *first* is greater than *last*,
+/// the result is the empty string.
+pub fn cmd_string_range(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 5, "string first last")?;
+
+    let chars: Vec<char> = argv[2].as_string().chars().collect();
+    let first = get_index(&argv[3], chars.len())?.max(0);
+    let last = get_index(&argv[4], chars.len())?.min(chars.len() as MoltInt - 1);
+
+    if first > last {
+        return molt_ok!();
+    }
+
+    let range: String = chars[first as usize..=last as usize].iter().collect();
+    molt_ok!(range)
+}
+
+/// # string repeat *string* *count*
 ///
-/// # TCL Liens
+/// Returns the string repeated *count* times.
+pub fn cmd_string_repeat(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "string count")?;
+
+    let count = argv[3].as_int()?;
+
+    if count < 0 {
+        return molt_err!("bad count \"{}\": must be integer >= 0", count);
+    }
+
+    // Note: str::repeat allocates the result just once.
+    let string = argv[2].as_string();
+    repeated_size(string.len(), count as usize)?;
+    molt_ok!(string.repeat(count as usize))
+}
+
+/// # string replace *string* *first* *last* ?*newString*?
 ///
-/// * Because we don't yet have an expression parser, the *expr* arguments are evaluated as
-///   scripts that must return a boolean value.
-pub fn cmd_if(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    let mut argi = 1;
-    let mut wants = IfWants::Expr;
+/// Replaces the characters from index *first* through index *last* with the new string,
+/// which defaults to the empty string.  If the range is empty or lies outside the string,
+/// the string is returned unchanged.
+pub fn cmd_string_replace(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 5, 6, "string first last ?string?")?;
 
-    while argi < argv.len() {
-        match wants {
-            IfWants::Expr => {
-                wants = if expr_test(interp, &argv[argi])? {
-                    IfWants::ThenBody
-                } else {
-                    IfWants::SkipThenClause
-                };
-            },
-            IfWants::ThenBody => {
-                if &*argv[argi].as_string() == "then" {
-                    argi += 1;
-                }
+    let chars: Vec<char> = argv[2].as_string().chars().collect();
+    let len = chars.len() as MoltInt;
+    let first = get_index(&argv[3], chars.len())?;
+    let last = get_index(&argv[4], chars.len())?;
 
-                if argi < argv.len() {
-                    return interp.eval_body(&*argv[argi].as_string());
-                } else {
-                    break;
-                }
-            },
-            IfWants::SkipThenClause => {
-                if &*argv[argi].as_string() == "then" {
-                    argi += 1;
-                }
+    if first > last || first >= len || last < 0 {
+        return molt_ok!(argv[2].clone());
+    }
 
-                if argi < argv.len() {
-                    argi += 1;
-                    wants = IfWants::ElseClause;
-                }
-                continue;
+    let first = first.max(0) as usize;
+    let last = last.min(len - 1) as usize;
+
+    let mut result: String = chars[..first].iter().collect();
+
+    if argv.len() == 6 {
+        result.push_str(&argv[5].as_string());
+    }
+
+    result.extend(&chars[last + 1..]);
+
+    molt_ok!(result)
+}
+
+/// # string reverse *string*
+///
+/// Returns the string with its characters in reverse order.
+pub fn cmd_string_reverse(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "string")?;
+
+    let reversed: String = argv[2].as_string().chars().rev().collect();
+    molt_ok!(reversed)
+}
+
+/// # string tolower *string* ?*first*? ?*last*?
+///
+/// Converts the string, or the characters from *first* through *last*, to lower case.
+pub fn cmd_string_tolower(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_case(argv, |chars| chars.iter().flat_map(|c| c.to_lowercase()).collect())
+}
+
+/// # string totitle *string* ?*first*? ?*last*?
+///
+/// Converts the first character of the string, or of the range from *first* through
+/// *last*, to upper case, and the remaining characters in the range to lower case.
+pub fn cmd_string_totitle(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_case(argv, |chars| {
+        let mut result: String = chars[..1].iter().flat_map(|c| c.to_uppercase()).collect();
+        result.extend(chars[1..].iter().flat_map(|c| c.to_lowercase()));
+        result
+    })
+}
+
+/// # string toupper *string* ?*first*? ?*last*?
+///
+/// Converts the string, or the characters from *first* through *last*, to upper case.
+pub fn cmd_string_toupper(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_case(argv, |chars| chars.iter().flat_map(|c| c.to_uppercase()).collect())
+}
+
+/// The implementation of `string tolower`, `string totitle`, and `string toupper`.
+/// Applies the conversion to the characters in the range given by the optional *first*
+/// and *last* indices; *last* defaults to *first*.  The conversion is never called with
+/// an empty range.
+fn string_case<F>(argv: &[Value], convert: F) -> MoltResult
+where
+    F: Fn(&[char]) -> String,
+{
+    check_args(2, argv, 3, 5, "string ?first? ?last?")?;
+
+    let chars: Vec<char> = argv[2].as_string().chars().collect();
+    let len = chars.len() as MoltInt;
+
+    let (first, last) = if argv.len() == 3 {
+        (0, len - 1)
+    } else {
+        let first = get_index(&argv[3], chars.len())?;
+        let last = if argv.len() == 5 { get_index(&argv[4], chars.len())? } else { first };
+        (first.max(0), last.min(len - 1))
+    };
+
+    if first > last {
+        return molt_ok!(argv[2].clone());
+    }
+
+    let (first, last) = (first as usize, last as usize);
+    let mut result: String = chars[..first].iter().collect();
+    result.push_str(&convert(&chars[first..=last]));
+    result.extend(&chars[last + 1..]);
+
+    molt_ok!(result)
+}
+
+/// # string trim *string* ?*chars*?
+///
+/// Trims the characters in *chars* from both ends of the string.  The *chars* default
+/// to the whitespace characters.
+pub fn cmd_string_trim(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_trim(argv, true, true)
+}
+
+/// # string trimleft *string* ?*chars*?
+///
+/// Trims the characters in *chars* from the beginning of the string.
+pub fn cmd_string_trimleft(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_trim(argv, true, false)
+}
+
+/// # string trimright *string* ?*chars*?
+///
+/// Trims the characters in *chars* from the end of the string.
+pub fn cmd_string_trimright(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    string_trim(argv, false, true)
+}
+
+/// The implementation of `string trim`, `string trimleft`, and `string trimright`.
+fn string_trim(argv: &[Value], left: bool, right: bool) -> MoltResult {
+    check_args(2, argv, 3, 4, "string ?chars?")?;
+
+    let chars: Vec<char> = if argv.len() == 4 {
+        argv[3].as_string().chars().collect()
+    } else {
+        vec![' ', '\t', '\n', '\r', '\x0B', '\x0C']
+    };
+
+    let string = &*argv[2].as_string();
+    let mut trimmed: &str = string;
+
+    if left {
+        trimmed = trimmed.trim_start_matches(&chars[..]);
+    }
+
+    if right {
+        trimmed = trimmed.trim_end_matches(&chars[..]);
+    }
+
+    molt_ok!(trimmed)
+}
+
+/// # string wordend *string* *charIndex*
+///
+/// Returns the index just past the end of the word containing the given index.
+pub fn cmd_string_wordend(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "string index")?;
+
+    let chars: Vec<char> = argv[2].as_string().chars().collect();
+    let index = get_index(&argv[3], chars.len())?.max(0) as usize;
+
+    if index >= chars.len() {
+        return molt_ok!(chars.len() as MoltInt);
+    }
+
+    let mut end = index;
+
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+
+    // A character that isn't a word character is a word by itself.
+    if end == index {
+        end += 1;
+    }
+
+    molt_ok!(end as MoltInt)
+}
+
+/// # string wordstart *string* *charIndex*
+///
+/// Returns the index of the first character of the word containing the given index.
+pub fn cmd_string_wordstart(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 4, 4, "string index")?;
+
+    let chars: Vec<char> = argv[2].as_string().chars().collect();
+
+    if chars.is_empty() {
+        return molt_ok!(0);
+    }
+
+    let index = get_index(&argv[3], chars.len())?.max(0).min(chars.len() as MoltInt - 1);
+    let mut start = index as usize;
+
+    if is_word_char(chars[start]) {
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+    }
+
+    molt_ok!(start as MoltInt)
+}
+
+/// Word characters, for `string wordend` and `string wordstart`.
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Checks an optional `-nocase` argument to a `string` subcommand.
+fn nocase_option(opt: &Value) -> Result<bool, ResultCode> {
+    if *opt.as_string() == "-nocase" {
+        Ok(true)
+    } else {
+        molt_err!("bad option \"{}\": must be -nocase", opt)
+    }
+}
+
+/// Compares two slices of characters for equality, optionally ignoring case.
+fn chars_match(a: &[char], b: &[char], nocase: bool) -> bool {
+    if nocase {
+        a.iter().zip(b).all(|(x, y)| x == y || x.to_lowercase().eq(y.to_lowercase()))
+    } else {
+        a == b
+    }
+}
+
+/// # subst ?-nobackslashes? ?-nocommands? ?-novariables? *string*
+///
+/// Performs backslash, command, and variable substitution on the string, returning the
+/// result.  The options disable the individual kinds of substitution.
+/// See molt-book for full semantics.
+pub fn cmd_subst(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(
+        1,
+        argv,
+        2,
+        0,
+        "?-nobackslashes? ?-nocommands? ?-novariables? string",
+    )?;
+
+    let mut backslashes = true;
+    let mut commands = true;
+    let mut variables = true;
+
+    for opt in &argv[1..argv.len() - 1] {
+        match opt.as_string().as_str() {
+            "-nobackslashes" => backslashes = false,
+            "-nocommands" => commands = false,
+            "-novariables" => variables = false,
+            _ => {
+                return molt_err!(
+                    "bad option \"{}\": must be -nobackslashes, -nocommands, or -novariables",
+                    opt
+                )
             }
-            IfWants::ElseClause => {
-                if &*argv[argi].as_string() == "elseif" {
-                    wants = IfWants::Expr;
+        }
+    }
+
+    interp.subst(
+        &argv[argv.len() - 1].as_string(),
+        backslashes,
+        commands,
+        variables,
+    )
+}
+
+/// # switch ?*options*? *string* *pattern* *body* ?*pattern* *body* ...?
+/// # switch ?*options*? *string* {*pattern* *body* ?*pattern* *body* ...?}
+///
+/// Matches the string against each pattern in turn, and evaluates the body of the
+/// first pattern that matches, returning its result.  A body of `-` falls through to
+/// the next body, and a final pattern of `default` matches anything.  See molt-book
+/// for the options.
+pub fn cmd_switch(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // FIRST, get the options.  There must always be a string and at least one more
+    // argument following them.
+    let mut mode = "-exact";
+    let mut nocase = false;
+    let mut match_var: Option<&Value> = None;
+    let mut index_var: Option<&Value> = None;
+    let mut i = 1;
+
+    while i + 2 < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        i += 1;
+
+        match opt.as_str() {
+            "-exact" => mode = "-exact",
+            "-glob" => mode = "-glob",
+            "-regexp" => mode = "-regexp",
+            "-nocase" => nocase = true,
+            "-matchvar" | "-indexvar" => {
+                let var_name = Some(&argv[i]);
+                i += 1;
+
+                if opt.as_str() == "-matchvar" {
+                    match_var = var_name;
                 } else {
-                    wants = IfWants::ElseBody;
-                    continue;
+                    index_var = var_name;
                 }
             }
-            IfWants::ElseBody => {
-                if &*argv[argi].as_string() == "else" {
-                    argi += 1;
+            "--" => break,
+            _ => {
+                return molt_err!("bad option \"{}\": must be -exact, -glob, -indexvar, -matchvar, -nocase, -regexp, or --", opt);
+            }
+        }
+    }
 
-                    // If "else" appears, then the else body is required.
-                    if argi == argv.len() {
-                        return molt_err!("wrong # args: no script following after \"{}\" argument",
-                            argv[argi - 1]);
-                    }
-                }
+    check_args(1, argv, i + 2, 0, "?-option ...? string ?pattern body ...? ?default body?")?;
 
-                if argi < argv.len() {
-                    return interp.eval_body(&*argv[argi].as_string());
-                } else {
-                    break;
+    if mode != "-regexp" {
+        if match_var.is_some() {
+            return molt_err!("-matchvar option requires -regexp option");
+        }
+        if index_var.is_some() {
+            return molt_err!("-indexvar option requires -regexp option");
+        }
+    }
+
+    // NEXT, get the string and the patterns and bodies.
+    let string = &*argv[i].as_string();
+
+    let clauses: MoltList = if argv.len() == i + 2 {
+        let list = argv[i + 1].as_list()?.to_vec();
+
+        if list.is_empty() {
+            return molt_err!("wrong # args: should be \"switch ?-option ...? string {?pattern body ...? ?default body?}\"");
+        }
+        list
+    } else {
+        argv[i + 1..].to_vec()
+    };
+
+    if clauses.len() % 2 == 1 {
+        return molt_err!("extra switch pattern with no body");
+    }
+
+    // NEXT, find the first matching pattern.
+    let last = clauses.len() - 2;
+    let mut found = None;
+
+    for j in (0..clauses.len()).step_by(2) {
+        let pattern = &*clauses[j].as_string();
+
+        let matched = if j == last && pattern == "default" {
+            true
+        } else {
+            match mode {
+                "-glob" if nocase => glob_match_nocase(pattern, string),
+                "-glob" => glob_match(pattern, string),
+                "-regexp" => {
+                    let switches = RegexpSwitches { nocase, ..Default::default() };
+                    let re = regexp_compile(pattern, &switches)?;
+
+                    match re.captures(string) {
+                        Some(caps) => {
+                            switch_regexp_vars(interp, string, &caps, match_var, index_var)?;
+                            true
+                        }
+                        None => false,
+                    }
                 }
+                _ if nocase => pattern.to_lowercase() == string.to_lowercase(),
+                _ => pattern == string,
             }
+        };
+
+        if matched {
+            found = Some(j + 1);
+            break;
         }
+    }
 
-        argi += 1;
+    // NEXT, evaluate the body, falling through any "-" bodies.
+    let mut j = match found {
+        Some(j) => j,
+        None => return molt_ok!(),
+    };
+
+    while &*clauses[j].as_string() == "-" {
+        j += 2;
+
+        if j >= clauses.len() {
+            return molt_err!("no body specified for pattern \"{}\"", clauses[j - 3]);
+        }
+    }
+
+    interp.eval_body(&clauses[j].as_string())
+}
+
+/// Assigns the `-matchvar` and `-indexvar` variables for a `switch -regexp` match.
+fn switch_regexp_vars(
+    interp: &mut Interp,
+    string: &str,
+    caps: &Captures,
+    match_var: Option<&Value>,
+    index_var: Option<&Value>,
+) -> Result<(), ResultCode> {
+    if let Some(var_name) = match_var {
+        let groups: MoltList = caps
+            .iter()
+            .map(|m| m.map_or_else(Value::empty, |m| Value::from(m.as_str())))
+            .collect();
+        interp.set_var2(&var_name.as_string(), Value::from(groups))?;
+    }
+
+    if let Some(var_name) = index_var {
+        let groups: MoltList = caps.iter().map(|m| regexp_indices(string, m)).collect();
+        interp.set_var2(&var_name.as_string(), Value::from(groups))?;
     }
 
-    if argi < argv.len() {
-        return molt_err!(
-            "wrong # args: extra words after \"else\" clause in \"if\" command");
-    } else if wants == IfWants::Expr {
-        return molt_err!("wrong # args: no expression after \"{}\" argument",
-            argv[argi-1]);
-    } else if wants == IfWants::ThenBody || wants == IfWants::SkipThenClause {
-        return molt_err!("wrong # args: no script following after \"{}\" argument",
-            argv[argi - 1]);
-    } else {
-        // Looking for ElseBody, but there doesn't need to be one.
-        molt_ok!() // temp
+    Ok(())
+}
+
+/// # tailcall *command* ?*arg* ...?
+///
+/// Replaces the current proc or lambda invocation with the given command, which is
+/// resolved in the current namespace and invoked in the caller's scope once the proc
+/// has returned.  The command's result is the proc's result.
+pub fn cmd_tailcall(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "command ?arg ...?")?;
+
+    if !interp.in_proc() {
+        return molt_err!("tailcall can only be called from a proc or lambda");
+    }
+
+    Err(ResultCode::TailCall(interp.tail_command(&argv[1..])))
+}
+
+/// # tell *channelId*
+///
+/// Returns the channel's position as a byte offset from the start of its stream, or -1
+/// if the stream doesn't support random access.
+pub fn cmd_tell(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "channelId")?;
+
+    let name = argv[1].as_string();
+
+    match interp.channel(&name)?.tell() {
+        Ok(Some(pos)) => molt_ok!(pos as MoltInt),
+        Ok(None) => molt_ok!(-1),
+        Err(e) => molt_err!("error during tell on \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # throw *type* *message*
+///
+/// Throws an error with the given message, whose error code is the given type, a
+/// non-empty list.  Equivalent to `return -code error -errorcode $type $message`.
+pub fn cmd_throw(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 3, "type message")?;
+
+    if argv[1].as_list()?.is_empty() {
+        return molt_err!("type must be non-empty list");
     }
+
+    let mut data = ErrorData::new(argv[2].clone());
+    data.set_error_code(argv[1].clone());
+
+    Err(ResultCode::Error(data))
 }
 
-/// # incr *varName* ?*increment* ...?
+/// # time *command* ?*count*?
 ///
-/// Increments an integer variable by a value.
-pub fn cmd_incr(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 3, "varName ?increment?")?;
+/// Executes the command the given number of times, and returns the average
+/// number of microseconds per iteration.  The *count* defaults to 1.  An error
+/// in the command is returned immediately.
+pub fn cmd_time(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 3, "command ?count?")?;
 
-    let increment: MoltInt = if argv.len() == 3 {
+    let command = &*argv[1].as_string();
+
+    let count = if argv.len() == 3 {
         argv[2].as_int()?
     } else {
         1
     };
 
-    let var_name = &*argv[1].as_string();
-    let var_value = interp.var(var_name);
+    // Only the evaluation of the command itself is timed.
+    let start = Instant::now();
 
-    let new_value = if var_value.is_ok() {
-        var_value.unwrap().as_int()? + increment
+    for _i in 0..count {
+        let result = interp.eval(command);
+        // Note: explicit returns will break the loop.
+        if result.is_err() {
+            return result;
+        }
+    }
+
+    let span = start.elapsed();
+
+    // A single iteration is timed to the whole microsecond, like `clock microseconds`;
+    // an average is a float.
+    let avg = if count <= 0 {
+        Value::from(0)
+    } else if count == 1 {
+        Value::from(span.as_micros() as MoltInt)
     } else {
-        increment
+        Value::from(span.as_micros() as MoltFloat / count as MoltFloat)
     };
 
-    molt_ok!(interp.set_var2(var_name, new_value.into()))
+    molt_ok!("{} microseconds per iteration", avg)
 }
 
-
-/// # info *subcommand* ?*arg*...?
-pub fn cmd_info(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+/// # trace *subcommand* ?*arg*...?
+pub fn cmd_trace(interp: &mut Interp, argv: &[Value]) -> MoltResult {
     check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
-    let subc = Subcommand::find(&INFO_SUBCOMMANDS, &*argv[1].as_string())?;
+    let subc = Subcommand::find(&TRACE_SUBCOMMANDS, &argv[1].as_string())?;
 
     (subc.1)(interp, argv)
 }
 
-const INFO_SUBCOMMANDS: [Subcommand; 3] = [
-    Subcommand("commands", cmd_info_commands),
-    Subcommand("complete", cmd_info_complete),
-    Subcommand("vars", cmd_info_vars),
+const TRACE_SUBCOMMANDS: [Subcommand; 3] = [
+    Subcommand("add", cmd_trace_add),
+    Subcommand("info", cmd_trace_info),
+    Subcommand("remove", cmd_trace_remove),
 ];
 
-/// # info commands ?*pattern*?
-pub fn cmd_info_commands(interp: &mut Interp, _argv: &[Value]) -> MoltResult {
-    // TODO: Return the list
-    molt_ok!(Value::from(interp.command_names()))
-}
-
-/// # info complete *command*
-pub fn cmd_info_complete(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(2, argv, 3, 3, "command")?;
+/// # trace add *type* *name* *ops* *command*
+///
+/// Adds a trace of the given type to the named entity.
+pub fn cmd_trace_add(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "type ?arg ...?")?;
 
-    if interp.complete(&*argv[2].as_string()) {
-        molt_ok!(true)
-    } else {
-        molt_ok!(false)
+    match trace_type(&argv[2])? {
+        TraceType::Execution => {
+            check_args(3, argv, 6, 6, "name opList command")?;
+            let ops = trace_ops(&argv[4], &ExecOp::ALL, |op| op.as_str())?;
+            interp.add_exec_trace(&argv[3].as_string(), ops, argv[5].clone())?;
+        }
+        TraceType::Variable => {
+            check_args(3, argv, 6, 6, "name opList command")?;
+            let ops = trace_ops(&argv[4], &TraceOp::ALL, |op| op.as_str())?;
+            interp.add_var_trace(&argv[3].as_string(), ops, argv[5].clone());
+        }
     }
-}
 
-/// # info vars ?*pattern*?
-/// TODO: Add glob matching as a feature, and provide optional pattern argument.
-pub fn cmd_info_vars(interp: &mut Interp, _argv: &[Value]) -> MoltResult {
-    molt_ok!(Value::from(interp.vars_in_scope()))
+    molt_ok!()
 }
 
-/// # join *list* ?*joinString*?
+/// # trace info *type* *name*
 ///
-/// Joins the elements of a list with a string.  The join string defaults to " ".
-pub fn cmd_join(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 3, "list ?joinString?")?;
-
+/// Returns the traces of the given type on the named entity, most recent first, as a
+/// list of pairs of operations and command.
+pub fn cmd_trace_info(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "type ?arg ...?")?;
 
-    let list = &argv[1].as_list()?;
+    check_args(3, argv, 4, 4, "name")?;
+    let name = argv[3].as_string();
 
-    let join_string = if argv.len() == 3 {
-        argv[2].to_string()
-    } else {
-        " ".to_string()
+    let info: MoltList = match trace_type(&argv[2])? {
+        TraceType::Execution => interp
+            .exec_trace_info(&name)?
+            .into_iter()
+            .map(|trace| trace_info_item(trace.ops.iter().map(|op| op.as_str()), trace.command))
+            .collect(),
+        TraceType::Variable => interp
+            .var_trace_info(&name)
+            .into_iter()
+            .map(|trace| trace_info_item(trace.ops.iter().map(|op| op.as_str()), trace.command))
+            .collect(),
     };
 
-    // TODO: Need to implement a standard join() method for MoltLists.
-    let list: Vec<String> = list.iter().map(|v| v.to_string()).collect();
+    molt_ok!(info)
+}
 
-    molt_ok!(list.join(&join_string))
+/// Gets an item of the list returned by `trace info`: a pair of operations and command.
+fn trace_info_item<'a>(ops: impl Iterator<Item = &'a str>, command: Value) -> Value {
+    let ops: MoltList = ops.map(Value::from).collect();
+    Value::from(vec![Value::from(ops), command])
 }
 
-/// # lappend *varName* ?*value* ...?
+/// # trace remove *type* *name* *ops* *command*
 ///
-/// Appends any number of values to a variable's list value, which need not
-/// initially exist.
-pub fn cmd_lappend(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 0, "varName ?value ...?")?;
+/// Removes the most recent trace of the given type with the given operations and command
+/// from the named entity, if there is one.
+pub fn cmd_trace_remove(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "type ?arg ...?")?;
 
-    let var_name = &*argv[1].as_string();
-    let var_result = interp.var(var_name);
+    match trace_type(&argv[2])? {
+        TraceType::Execution => {
+            check_args(3, argv, 6, 6, "name opList command")?;
+            let ops = trace_ops(&argv[4], &ExecOp::ALL, |op| op.as_str())?;
+            interp.remove_exec_trace(&argv[3].as_string(), &ops, &argv[5].as_string())?;
+        }
+        TraceType::Variable => {
+            check_args(3, argv, 6, 6, "name opList command")?;
+            let ops = trace_ops(&argv[4], &TraceOp::ALL, |op| op.as_str())?;
+            interp.remove_var_trace(&argv[3].as_string(), &ops, &argv[5].as_string());
+        }
+    }
+
+    molt_ok!()
+}
+
+/// The types of trace managed by `trace`.
+enum TraceType {
+    Execution,
+    Variable,
+}
 
-    let mut list: MoltList = Vec::new();
+/// Gets the type of trace named by a `trace` argument.
+fn trace_type(value: &Value) -> Result<TraceType, ResultCode> {
+    match value.as_string().as_str() {
+        "execution" => Ok(TraceType::Execution),
+        "variable" => Ok(TraceType::Variable),
+        other => molt_err!("bad option \"{}\": must be execution or variable", other),
+    }
+}
 
-    if var_result.is_ok() {
-        // TODO: MoltList needs a to_list() method.
-        let old_list = var_result.unwrap().as_list()?;
+/// Parses a list of trace operations, given the known operations and their names, returning
+/// them in the order in which `trace info` reports them.
+fn trace_ops<T: Copy>(value: &Value, known: &[T], name: fn(T) -> &'static str) -> Result<Vec<T>, ResultCode> {
+    let list = value.as_list()?;
+    let mut names: Vec<&str> = known.iter().map(|op| name(*op)).collect();
+    names.sort_unstable();
+    let names = match names.len() {
+        0 => String::new(),
+        1 => names[0].to_string(),
+        2 => format!("{} or {}", names[0], names[1]),
+        n => format!("{}, or {}", names[..n - 1].join(", "), names[n - 1]),
+    };
 
-        for value in &*old_list {
-            list.push(value.clone());
-        }
+    if list.is_empty() {
+        return molt_err!("bad operation list \"\": must be one or more of {}", names);
     }
 
-    for value in &argv[2..] {
-        list.push(value.clone());
+    for op in list.iter() {
+        let op = op.as_string();
+
+        if !known.iter().any(|known| name(*known) == *op) {
+            return molt_err!("bad operation \"{}\": must be {}", op, names);
+        }
     }
 
-    molt_ok!(interp.set_var2(var_name, Value::from(list)))
+    Ok(known
+        .iter()
+        .copied()
+        .filter(|known| list.iter().any(|op| *op.as_string() == name(*known)))
+        .collect())
 }
 
-/// # lindex *list* ?*index* ...?
-///
-/// Returns an element from the list, indexing into nested lists.
-pub fn cmd_lindex(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 0, "list ?index ...?")?;
+/// A `try` handler: the results it matches, the variables that receive the result and
+/// the return options, and the script to evaluate.
+struct TryHandler {
+    matcher: TryMatch,
+    vars: MoltList,
+    script: Value,
+}
 
-    let mut value = argv[1].clone();
+/// What a `try` handler matches: `on` a result code, or a `trap` of errors whose error
+/// code begins with a pattern.
+enum TryMatch {
+    Code(MoltInt),
+    Trap(MoltList),
+}
 
-    for index_val in &argv[2..] {
-        let list = value.as_list()?;
-        let index = index_val.as_int()?;
+impl TryHandler {
+    /// Determines whether the handler matches the result.
+    fn matches(&self, result: &MoltResult) -> bool {
+        // A tail call ends the enclosing proc; no handler can catch it.
+        if let Err(ResultCode::TailCall(_)) = result {
+            return false;
+        }
 
-        value = if index < 0 || index as usize >= list.len() {
-            Value::empty()
-        }  else {
-            list[index as usize].clone()
-        };
+        match &self.matcher {
+            TryMatch::Code(code) => *code == caught_result(result).0,
+            TryMatch::Trap(pattern) => match result {
+                Err(ResultCode::Error(data)) => match data.error_code().as_list() {
+                    Ok(error_code) => {
+                        error_code.len() >= pattern.len()
+                            && pattern.iter().zip(error_code.iter()).all(|(p, e)| p == e)
+                    }
+                    Err(_) => false,
+                },
+                _ => false,
+            },
+        }
     }
-
-    molt_ok!(value)
 }
 
-/// # list ?*arg*...?
+/// # try *body* ?*handler* ...? ?finally *script*?
 ///
-/// Converts its arguments into a canonical list.
-pub fn cmd_list(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    // // No arg check needed; can take any number.
-    molt_ok!(list_to_string(&argv[1..]))
-}
+/// Evaluates the body, and then the first handler that matches its result, if any.  A
+/// handler is either `on code varList script`, matching results with the given result code,
+/// or `trap pattern varList script`, matching errors whose error code begins with the
+/// pattern.  The finally script, if given, is always evaluated last.
+/// See molt-book for full semantics.
+pub fn cmd_try(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "body ?handler ...? ?finally script?")?;
 
-/// # llength *list*
-///
-/// Returns the length of the list.
-pub fn cmd_llength(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "list")?;
+    // FIRST, parse the handlers and the finally clause.
+    let mut handlers: Vec<TryHandler> = Vec::new();
+    let mut finally: Option<&Value> = None;
+    let mut i = 2;
 
-    // let list = interp.get_list(argv[1])?;
+    while i < argv.len() {
+        let clause = argv[i].as_string();
 
-    molt_ok!(Value::from(argv[1].as_list()?.len() as MoltInt))
-}
+        match clause.as_str() {
+            "on" | "trap" => {
+                if i + 3 >= argv.len() {
+                    let arg = if clause.as_str() == "on" { "code" } else { "pattern" };
+                    return molt_err!(
+                        "wrong # args to {} clause: must be \"... {} {} variableList script\"",
+                        clause,
+                        clause,
+                        arg
+                    );
+                }
 
-pub fn cmd_proc(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 4, 4, "name args body")?;
+                let matcher = if clause.as_str() == "on" {
+                    TryMatch::Code(parse_completion_code(&argv[i + 1])?)
+                } else {
+                    TryMatch::Trap(argv[i + 1].as_list()?.to_vec())
+                };
 
-    // FIRST, get the arguments
-    let name = &*argv[1].as_string();
-    let args = &*argv[2].as_list()?;
-    let body = &*argv[3].as_string();
+                let vars = argv[i + 2].as_list()?.to_vec();
 
-    // NEXT, validate the argument specs
-    for arg in args {
-        let vec = arg.as_list()?;
+                if vars.len() > 2 {
+                    return molt_err!(
+                        "bad variable list \"{}\": must name at most a result variable and an options variable",
+                        argv[i + 2]
+                    );
+                }
+
+                handlers.push(TryHandler {
+                    matcher,
+                    vars,
+                    script: argv[i + 3].clone(),
+                });
+                i += 4;
+            }
+            "finally" => {
+                if i + 2 != argv.len() {
+                    return molt_err!("wrong # args to finally clause: must be \"... finally script\"");
+                }
 
-        if vec.is_empty() {
-            return molt_err!("argument with no name");
-        } else if vec.len() > 2 {
-            return molt_err!("too many fields in argument specifier \"{}\"", arg);
+                finally = Some(&argv[i + 1]);
+                i += 2;
+            }
+            _ => {
+                return molt_err!("bad handler type \"{}\": must be finally, on, or trap", clause);
+            }
         }
     }
 
-    // NEXT, add the command.
-    interp.add_proc(name, args, body);
+    if let Some(handler) = handlers.last() {
+        if &*handler.script.as_string() == "-" {
+            return molt_err!("last non-finally clause must not have a body of \"-\"");
+        }
+    }
 
-    molt_ok!()
-}
+    // NEXT, evaluate the body.
+    let result = interp.eval_body(&argv[1].as_string());
 
-/// # puts *string*
-///
-/// Outputs the string to stdout.
-///
-/// ## TCL Liens
-///
-/// * Does not support `-nonewline`
-/// * Does not support `channelId`
-pub fn cmd_puts(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "string")?;
+    if let Err(ResultCode::Error(data)) = &result {
+        interp.set_error_vars(data);
+    }
 
-    println!("{}", argv[1]);
-    molt_ok!()
+    // NEXT, evaluate the first matching handler, if any; its result is the result of the
+    // try.  A handler whose script is "-" uses the script of the next handler.
+    let result = match handlers.iter().position(|handler| handler.matches(&result)) {
+        Some(index) => {
+            let handler = &handlers[index];
+            let script = handlers[index..]
+                .iter()
+                .map(|h| h.script.as_string())
+                .find(|script| script.as_str() != "-")
+                .expect("last handler has a script");
+
+            let (_, value) = caught_result(&result);
+            let bound = match handler.vars.len() {
+                0 => Ok(Value::empty()),
+                1 => interp.set_var2(&handler.vars[0].as_string(), value),
+                _ => interp
+                    .set_var2(&handler.vars[0].as_string(), value)
+                    .and_then(|_| {
+                        let opts = Value::from(return_options(&result));
+                        interp.set_var2(&handler.vars[1].as_string(), opts)
+                    }),
+            };
+
+            match bound {
+                Ok(_) => interp.eval_body(&script),
+                Err(code) => Err(code),
+            }
+        }
+        None => result,
+    };
+
+    // NEXT, evaluate the finally script.  Its result is discarded unless it's exceptional,
+    // in which case it takes precedence.
+    if let Some(script) = finally {
+        interp.eval_body(&script.as_string())?;
+    }
+
+    result
 }
 
-/// # rename *oldName* *newName*
+/// # unset ?-nocomplain? ?--? ?*varName* ...?
 ///
-/// Renames the command called *oldName* to have the *newName*.  If the
-/// *newName* is "", the command is destroyed.
-pub fn cmd_rename(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 3, 3, "oldName newName")?;
+/// Removes the variables from the interpreter.  The *varName* may name an array, in
+/// which case the whole array is removed, or an array element.  It's an error if a
+/// variable doesn't exist, unless `-nocomplain` is given.
+pub fn cmd_unset(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    // FIRST, get the options.
+    let mut i = 1;
+    let mut nocomplain = false;
 
-    // FIRST, get the arguments
-    let old_name = &*argv[1].as_string();
-    let new_name = &*argv[2].as_string();
+    if i < argv.len() && *argv[i].as_string() == "-nocomplain" {
+        nocomplain = true;
+        i += 1;
+    }
 
-    if !interp.has_command(old_name) {
-        return molt_err!("can't rename \"{}\": command doesn't exist", old_name);
+    if i < argv.len() && *argv[i].as_string() == "--" {
+        i += 1;
     }
 
-    // NEXT, rename or remove the command.
-    if new_name.is_empty() {
-        interp.remove_command(old_name);
-    } else {
-        interp.rename_command(old_name, new_name);
+    // NEXT, unset the variables, stopping at the first error.
+    for var_name in &argv[i..] {
+        if nocomplain {
+            interp.unset_var(&var_name.as_string());
+        } else {
+            interp.unset_var_checked(&var_name.as_string())?;
+        }
     }
 
     molt_ok!()
 }
 
-/// # return ?value?
+/// # update ?idletasks?
 ///
-/// Returns from a proc.  The proc will return the given value, or ""
-/// if no value is specified.
-///
-/// ## TCL Liens
-///
-/// * Doesn't support all of TCL's fancy return machinery.
-pub fn cmd_return(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 1, 2, "?value?")?;
+/// Processes the pending events without waiting: the timer events that are due and the
+/// idle events, or only the idle events if `idletasks` is given.
+pub fn cmd_update(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 2, "?idletasks?")?;
 
-    let value = if argv.len() == 1 {
-        Value::empty()
+    if argv.len() == 1 {
+        interp.update();
+    } else if &*argv[1].as_string() == "idletasks" {
+        interp.update_idletasks();
     } else {
-        argv[1].clone()
-    };
+        return molt_err!("bad option \"{}\": must be idletasks", argv[1]);
+    }
 
-    Err(ResultCode::Return(value))
+    molt_ok!()
 }
 
-/// # set *varName* ?*newValue*?
-///
-/// Sets variable *varName* to *newValue*, returning the value.
-/// If *newValue* is omitted, returns the variable's current value,
-/// returning an error if the variable is unknown.
-///
-/// ## TCL Liens
+/// # uplevel ?*level*? *arg* ?*arg* ...?
 ///
-/// * Does not support arrays
-pub fn cmd_set(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 3, "varName ?newValue?")?;
+/// Concatenates the arguments as for `concat` and evaluates the result in the variable
+/// scope of the given level, returning the result.  The *level* may be a relative level,
+/// e.g., `1`, the caller's scope, or an absolute level, e.g., `#0`, the global scope; it
+/// defaults to `1`.
+pub fn cmd_uplevel(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "?level? command ?arg ...?")?;
 
-    let var_name = &*argv[1].as_string();
+    let (level, first) = match parse_level(interp, &argv[1])? {
+        Some(level) if argv.len() > 2 => (level, 2),
+        _ => (relative_level(interp, 1, &Value::from("1"))?, 1),
+    };
 
-    if argv.len() == 3 {
-        molt_ok!(interp.set_var2(var_name, argv[2].clone()))
+    let script = if argv.len() == first + 1 {
+        argv[first].to_string()
     } else {
-        molt_ok!(interp.var(var_name)?.clone())
-    }
+        concat_values(&argv[first..])
+    };
+
+    interp.eval_at_level(level, &script)
 }
 
-/// # source *filename*
-///
-/// Sources the file, returning the result.
-pub fn cmd_source(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "filename")?;
+/// Parses a level specification as used by `uplevel` and `upvar`: `#N` is the absolute
+/// level `N`, and `N` is `N` levels above the current level.  Returns `None` if the value
+/// isn't a level specification; it's an error if the value looks like one but isn't a
+/// valid level.
+fn parse_level(interp: &Interp, spec: &Value) -> Result<Option<usize>, ResultCode> {
+    let text = spec.as_string();
 
-    let filename = &*argv[1].as_string();
+    if let Some(number) = text.strip_prefix('#') {
+        match number.parse::<usize>() {
+            Ok(level) if level <= interp.scope_level() => Ok(Some(level)),
+            _ => molt_err!("bad level \"{}\"", text),
+        }
+    } else if text.starts_with(|ch: char| ch.is_ascii_digit()) {
+        match text.parse::<usize>() {
+            Ok(number) => Ok(Some(relative_level(interp, number, spec)?)),
+            _ => molt_err!("bad level \"{}\"", text),
+        }
+    } else {
+        Ok(None)
+    }
+}
 
-    match fs::read_to_string(filename) {
-        Ok(script) => interp.eval(&script),
-        Err(e) => molt_err!("couldn't read file \"{}\": {}", filename, e),
+/// Computes the level `number` levels above the current level.  The `spec` is used in
+/// the error message if there's no such level.
+fn relative_level(interp: &Interp, number: usize, spec: &Value) -> Result<usize, ResultCode> {
+    match interp.scope_level().checked_sub(number) {
+        Some(level) => Ok(level),
+        None => molt_err!("bad level \"{}\"", spec),
     }
 }
 
-/// # time *command* ?*count*?
+/// # upvar ?*level*? *otherVar* *localVar* ?*otherVar* *localVar* ...?
 ///
-/// Executes the command the given number of times, and returns the average
-/// number of microseconds per iteration.  The *count* defaults to 1.
-pub fn cmd_time(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 3, "command ?count?")?;
-
-    let command = &*argv[1].as_string();
+/// Links each *localVar* in the current scope to the *otherVar* in the scope of the
+/// given level, which is a relative or absolute level as for `uplevel`.  The *level*
+/// defaults to `1`.
+pub fn cmd_upvar(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 3, 0, "?level? otherVar localVar ?otherVar localVar ...?")?;
 
-    let count = if argv.len() == 3 {
-        argv[2].as_int()?
-    } else {
-        1
+    let (level, first) = match parse_level(interp, &argv[1])? {
+        Some(level) => (level, 2),
+        None => (relative_level(interp, 1, &Value::from("1"))?, 1),
     };
 
-    let start = Instant::now();
+    let names = &argv[first..];
 
-    for _i in 0..count {
-        let result = interp.eval(command);
-        // Note: explicit returns will break the loop.
-        if result.is_err() {
-            return result;
-        }
+    if names.is_empty() || !names.len().is_multiple_of(2) {
+        return molt_err!(
+            "wrong # args: should be \"upvar ?level? otherVar localVar ?otherVar localVar ...?\"");
     }
 
-    let span = Instant::now().duration_since(start);
-
-    let avg = if count > 0 {
-        span.as_micros() as f64 / (count as f64)
-    } else {
-        0.0
-    };
+    for pair in names.chunks(2) {
+        interp.upvar2(level, &pair[0].as_string(), &pair[1].as_string())?;
+    }
 
-    molt_ok!("{} microseconds per iteration", avg)
+    molt_ok!()
 }
 
-/// # unset *varName*
+/// # variable ?*name* *value* ...? *name* ?*value*?
 ///
-/// Removes the variable from the interpreter.  This is a no op if
-/// there is no such variable.
-pub fn cmd_unset(interp: &mut Interp, argv: &[Value]) -> MoltResult {
-    check_args(1, argv, 2, 2, "varName")?;
+/// Declares variables in the current namespace.  In a proc, links each *name* to the
+/// namespace variable of the same name, much as `global` does for global variables.  If
+/// a *value* is given, the variable is set to it.
+pub fn cmd_variable(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "?name value...? name ?value?")?;
+
+    for pair in argv[1..].chunks(2) {
+        let name = pair[0].as_string();
 
-    interp.unset_var(&*argv[1].as_string());
+        if parse_var_name(&name).1.is_some() {
+            return molt_err!("can't define \"{}\": name refers to an element in an array", name);
+        }
+
+        let qualified = interp.namespace_var_name(&name);
+
+        if !interp.in_namespace_scope() {
+            interp.upvar2(0, &qualified, &name)?;
+        }
+
+        if pair.len() == 2 {
+            interp.set_var2(&qualified, pair[1].clone())?;
+        }
+    }
 
     molt_ok!()
 }
 
+/// # vwait *varName*
+///
+/// Processes events until the named global variable is written or unset.
+pub fn cmd_vwait(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "name")?;
+
+    interp.vwait(&argv[1].as_string())
+}
+
 /// # while *test* *command*
 ///
 /// A standard "while" loop.  *test* is a boolean expression; *command* is a script to
@@ -696,3 +5359,15 @@ pub fn cmd_while(interp: &mut Interp, argv: &[Value]) -> MoltResult {
 
     molt_ok!()
 }
+
+/// # yield ?*value*?
+///
+/// Suspends the current coroutine, returning the value, or the empty string, from the
+/// call that resumed it.  The result is the argument, if any, of the call that next
+/// resumes the coroutine.  It's an error if there's no current coroutine.
+pub fn cmd_yield(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 2, "?value?")?;
+
+    let value = argv.get(1).cloned().unwrap_or_else(Value::empty);
+    interp.yield_coroutine(value)
+}
@@ -11,6 +11,9 @@ use std::str::Chars;
 /// A struct that holds the editing context: the iterator over the input string, and
 /// any relevant flags.
 pub struct Context<'a> {
+    // The input string, if known
+    input: Option<&'a str>,
+
     // The input iterator
     chars: Peekable<Chars<'a>>,
 
@@ -22,24 +25,36 @@ pub struct Context<'a> {
 
     // Whether we're evaluating commands or just checking for completeness.
     no_eval: bool,
+
+    // The number of the line containing the next character, starting at 1.
+    line: usize,
+
+    // The byte offset of the next character.
+    pos: usize,
 }
 
 impl<'a> Context<'a> {
     pub fn new(input: &'a str) -> Self {
         Self {
+            input: Some(input),
             chars: input.chars().peekable(),
             bracket_term: false,
             term_char: None,
             no_eval: false,
+            line: 1,
+            pos: 0,
         }
     }
 
     pub fn from_peekable(peekable: Peekable<Chars<'a>>) -> Self {
         Self {
+            input: None,
             chars: peekable,
             bracket_term: false,
             term_char: None,
             no_eval: false,
+            line: 1,
+            pos: 0,
         }
     }
 
@@ -136,6 +151,12 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// Is the input at a namespace separator in a variable name, i.e., two or more colons?
+    pub fn next_is_namespace_sep(&mut self) -> bool {
+        let mut chars = self.chars.clone();
+        chars.next() == Some(':') && chars.next() == Some(':')
+    }
+
     /// Is the current character a valid octal digit?
     pub fn next_is_octal_digit(&mut self) -> bool {
         match self.chars.peek() {
@@ -159,7 +180,7 @@ impl<'a> Context<'a> {
     /// character.
     pub fn skip_block_white(&mut self) {
         while !self.at_end() && self.next_is_block_white() {
-            self.chars.next();
+            self.advance();
         }
     }
 
@@ -168,7 +189,7 @@ impl<'a> Context<'a> {
     /// current command, or on a non-white-space character.
     pub fn skip_line_white(&mut self) {
         while !self.at_end() && self.next_is_line_white() {
-            self.chars.next();
+            self.advance();
         }
     }
 
@@ -177,7 +198,7 @@ impl<'a> Context<'a> {
     /// the next list item.
     pub fn skip_list_white(&mut self) {
         while !self.at_end() && self.next_is_list_white() {
-            self.chars.next();
+            self.advance();
         }
     }
 
@@ -186,14 +207,14 @@ impl<'a> Context<'a> {
     pub fn skip_comment(&mut self) -> bool {
         if self.next_is('#') {
             while !self.at_end() {
-                let c = self.chars.next();
+                let c = self.advance();
                 if c == Some('\n') {
                     break;
                 } else if c == Some('\\') {
                     // Skip the following character. The intent is to skip
                     // backslashed newlines, but in
                     // this context it doesn't matter.
-                    self.chars.next();
+                    self.advance();
                 }
             }
             true
@@ -204,13 +225,44 @@ impl<'a> Context<'a> {
 
     /// Skip a specific character
     pub fn skip_char(&mut self, ch: char) {
-        let c = self.chars.next();
+        let c = self.advance();
         assert!(c == Some(ch), "expected '{:?}', got '{:?}' ", Some(ch), c);
     }
 
     /// Get the next character.
     pub fn next(&mut self) -> Option<char> {
-        self.chars.next()
+        self.advance()
+    }
+
+    /// Gets the number of the line containing the next character, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Gets the byte offset of the next character.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Gets the text of the input between two byte offsets, if the input string is
+    /// known; it isn't for a context created from a bare iterator.
+    pub fn text(&self, start: usize, end: usize) -> Option<&'a str> {
+        self.input.map(|input| &input[start..end])
+    }
+
+    // Consumes the next character, keeping track of the line number and position.
+    fn advance(&mut self) -> Option<char> {
+        let c = self.chars.next();
+
+        if let Some(ch) = c {
+            self.pos += ch.len_utf8();
+
+            if ch == '\n' {
+                self.line += 1;
+            }
+        }
+
+        c
     }
 }
 
@@ -227,6 +279,34 @@ mod tests {
         assert!(ctx.is_bracket_term());
     }
 
+    #[test]
+    fn test_line() {
+        let mut ctx = Context::new("a\n\nb c\nd");
+        assert_eq!(ctx.line(), 1);
+        ctx.next();
+        assert_eq!(ctx.line(), 1);
+        ctx.skip_block_white();
+        assert_eq!(ctx.line(), 3);
+        ctx.next();
+        ctx.skip_line_white();
+        ctx.next();
+        ctx.next();
+        assert_eq!(ctx.line(), 4);
+    }
+
+    #[test]
+    fn test_text() {
+        let mut ctx = Context::new("ab\u{e9}c");
+        ctx.next();
+        let start = ctx.pos();
+        ctx.next();
+        ctx.next();
+        assert_eq!(ctx.text(start, ctx.pos()), Some("b\u{e9}"));
+
+        let ctx = Context::from_peekable("abc".chars().peekable());
+        assert_eq!(ctx.text(0, 0), None);
+    }
+
     #[test]
     fn test_next_is() {
         let mut ctx = Context::new("123");
@@ -0,0 +1,224 @@
+//! Coroutines
+//!
+//! This module defines the coroutines created by the `coroutine` command.  A coroutine's
+//! command runs on a thread of its own, so that it can be suspended by `yield` at any depth
+//! of nested calls.  The coroutine's thread and the thread that resumes it hand control
+//! back and forth over a pair of channels, so that exactly one of them runs at a time: the
+//! resuming thread waits while the coroutine runs, and the coroutine's thread waits while
+//! the coroutine is suspended.  Thus the interpreter is never accessed by two threads at
+//! once, though it is accessed by more than one.
+//!
+//! The interpreter state that belongs to the call stack, e.g., the scopes of the procs
+//! being executed, is swapped in and out by `Interp` on each handoff; see
+//! `Interp::resume_coroutine`.
+//!
+//! The coroutine's thread refers to the interpreter by its address for as long as the
+//! coroutine exists, so the interpreter mustn't move while a coroutine is suspended: a
+//! coroutine whose interpreter has moved can't be resumed.  Slave interpreters are boxed,
+//! so that they stay put, and an interpreter kills its suspended coroutines when it's
+//! dropped, so that their threads run to completion.
+
+use crate::interp::Interp;
+use crate::interp::NsContext;
+use crate::scope::Frames;
+use crate::types::*;
+use crate::*;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::ptr;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+/// The stack size of a coroutine's thread, which is as large as a typical main thread's so
+/// that the interpreter's recursion limit applies within coroutines as well.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// What a coroutine is resumed with.
+pub(crate) enum Resume {
+    /// The value that `yield` returns in the coroutine.
+    Value(Value),
+
+    /// The coroutine's command has been deleted: `yield` returns an error, so that the
+    /// coroutine runs to completion.
+    Kill,
+}
+
+/// What a coroutine returns to the thread that resumed it.
+pub(crate) enum Outcome {
+    /// The coroutine yielded the value.
+    Yield(Value),
+
+    /// The coroutine's command returned the result.
+    Done(MoltResult),
+}
+
+/// A value handed from one thread to another.  Values aren't `Send`, since their reference
+/// counts aren't atomic, but they can be handed between a coroutine's thread and the
+/// thread that resumes it, as only one of the two runs at a time.
+struct Handoff<T>(T);
+
+// SAFETY: see above.  The receiving thread only runs once the sending thread has stopped
+// touching the value, and its references, until the next handoff.
+unsafe impl<T> Send for Handoff<T> {}
+
+impl<T> Handoff<T> {
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// The interpreter state that belongs to a suspended coroutine's call stack, or to the
+/// call stack of the caller of a running coroutine.
+#[derive(Default)]
+pub(crate) struct CallStack {
+    pub frames: Frames,
+    pub scope_argvs: Vec<MoltList>,
+    pub ns_stack: Vec<NsContext>,
+    pub num_levels: usize,
+}
+
+/// A coroutine, as created by the `coroutine` command.
+pub(crate) struct Coroutine {
+    /// The coroutine's command and arguments, until the coroutine is first resumed.
+    cmd: RefCell<Option<MoltList>>,
+
+    /// The coroutine's call stack while it's suspended.
+    stack: RefCell<CallStack>,
+
+    /// Whether the coroutine is running, possibly having resumed another coroutine.
+    running: Cell<bool>,
+
+    /// Whether the coroutine's command has been deleted.
+    killed: Cell<bool>,
+
+    /// The interpreter in which the coroutine's thread runs, once it has started.
+    interp: Cell<*mut Interp>,
+
+    resume_tx: Sender<Handoff<Resume>>,
+    resume_rx: Receiver<Handoff<Resume>>,
+    outcome_tx: Sender<Handoff<Outcome>>,
+    outcome_rx: Receiver<Handoff<Outcome>>,
+}
+
+impl Coroutine {
+    /// Creates a coroutine that will execute the command and arguments when it's first
+    /// resumed.
+    pub fn new(cmd: MoltList) -> Self {
+        let (resume_tx, resume_rx) = channel();
+        let (outcome_tx, outcome_rx) = channel();
+
+        Self {
+            cmd: RefCell::new(Some(cmd)),
+            stack: RefCell::new(CallStack::default()),
+            running: Cell::new(false),
+            killed: Cell::new(false),
+            interp: Cell::new(ptr::null_mut()),
+            resume_tx,
+            resume_rx,
+            outcome_tx,
+            outcome_rx,
+        }
+    }
+
+    /// Determines whether the coroutine is running.
+    pub fn is_running(&self) -> bool {
+        self.running.get()
+    }
+
+    /// Determines whether the coroutine's command has been deleted.
+    pub fn is_killed(&self) -> bool {
+        self.killed.get()
+    }
+
+    /// Notes that the coroutine's command has been deleted.
+    pub fn kill(&self) {
+        self.killed.set(true);
+    }
+
+    /// Takes the coroutine's saved call stack.
+    pub fn take_stack(&self) -> CallStack {
+        self.stack.take()
+    }
+
+    /// Saves the coroutine's call stack when it yields.
+    pub fn save_stack(&self, stack: CallStack) {
+        self.stack.replace(stack);
+    }
+
+    /// Runs the coroutine until it yields or its command returns, starting its thread if
+    /// this is its first resumption, and waits for the outcome.  The interpreter's state
+    /// must already be the coroutine's.  It's an error if the interpreter has moved since
+    /// the coroutine started; the coroutine's thread is left suspended.
+    pub fn transfer(&self, interp: &mut Interp, resume: Resume) -> Outcome {
+        let interp: *mut Interp = interp;
+        let cmd = self.cmd.borrow_mut().take();
+
+        if cmd.is_none() && self.interp.get() != interp {
+            return Outcome::Done(molt_err!("can't resume coroutine: its interpreter has moved"));
+        }
+
+        self.running.set(true);
+
+        let sent = match cmd {
+            Some(cmd) => {
+                self.interp.set(interp);
+                self.start(interp, cmd)
+            }
+            None => self.resume_tx.send(Handoff(resume)).is_ok(),
+        };
+
+        let outcome = if sent {
+            match self.outcome_rx.recv() {
+                Ok(outcome) => outcome.into_inner(),
+                Err(_) => Outcome::Done(molt_err!("coroutine terminated unexpectedly")),
+            }
+        } else {
+            Outcome::Done(molt_err!("can't resume coroutine"))
+        };
+
+        self.running.set(false);
+        outcome
+    }
+
+    /// Starts the coroutine's thread, which executes the command in the interpreter and
+    /// sends the result as the final outcome.
+    fn start(&self, interp: *mut Interp, cmd: MoltList) -> bool {
+        let start = Handoff((interp, cmd));
+        let outcome_tx = self.outcome_tx.clone();
+
+        let body = move || {
+            let (interp, cmd) = start.into_inner();
+
+            // SAFETY: the resuming thread waits, without touching the interpreter, until
+            // the coroutine yields or this thread sends its result.  The coroutine is only
+            // resumed at the same address, and the interpreter doesn't go away until this
+            // thread has sent its result.
+            let result = unsafe { (*interp).invoke(&cmd) };
+
+            // Nothing that refers to the interpreter's values may be dropped after the
+            // result is sent.
+            drop(cmd);
+            let _ = outcome_tx.send(Handoff(Outcome::Done(result)));
+        };
+
+        thread::Builder::new().stack_size(STACK_SIZE).spawn(body).is_ok()
+    }
+
+    /// Suspends the running coroutine, on its own thread, handing the value to the thread
+    /// that resumed it, and waits to be resumed.  The coroutine goes away while suspended
+    /// only if it can't be resumed because its interpreter has moved; as the thread can't
+    /// then touch the interpreter, it waits forever.
+    pub fn suspend(&self, value: Value) -> Resume {
+        if self.outcome_tx.send(Handoff(Outcome::Yield(value))).is_ok() {
+            if let Ok(resume) = self.resume_rx.recv() {
+                return resume.into_inner();
+            }
+        }
+
+        loop {
+            thread::park();
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! TCL Dictionary Parsing and Formatting
+//!
+//! A dictionary is an ordered mapping from keys to values; its string representation is a
+//! list of alternating keys and values.  Keys retain the order in which they were first
+//! added.
+
+use crate::list::get_list;
+use crate::list::list_to_string;
+use crate::molt_err;
+use crate::types::*;
+use crate::value::Value;
+
+//--------------------------------------------------------------------------
+// Dictionary Parsing
+
+/// Parses a dictionary-formatted string into a `MoltDict`, throwing a Molt error if the
+/// string cannot be parsed as a list with an even number of elements.
+pub(crate) fn get_dict(str: &str) -> Result<MoltDict, ResultCode> {
+    list_to_dict(&get_list(str)?)
+}
+
+/// Converts a flat list of keys and values into a `MoltDict`.  If a key appears more than
+/// once, the last value wins.
+pub(crate) fn list_to_dict(list: &[Value]) -> Result<MoltDict, ResultCode> {
+    if list.len() % 2 == 1 {
+        return molt_err!("missing value to go with key");
+    }
+
+    let mut dict = MoltDict::new();
+
+    for pair in list.chunks(2) {
+        dict.insert(pair[0].clone(), pair[1].clone());
+    }
+
+    Ok(dict)
+}
+
+//--------------------------------------------------------------------------
+// Dictionary Formatting
+
+/// Converts a `MoltDict` into its string representation, a list of alternating keys and
+/// values.
+pub(crate) fn dict_to_string(dict: &MoltDict) -> String {
+    let mut list: MoltList = Vec::with_capacity(2 * dict.len());
+
+    for (key, value) in dict {
+        list.push(key.clone());
+        list.push(value.clone());
+    }
+
+    list_to_string(&list)
+}
+
+//--------------------------------------------------------------------------
+// Nested Dictionaries
+
+/// Looks up a value in a nested dictionary, following the path of keys.  Returns the
+/// standard error if some key is missing.
+pub(crate) fn dict_path_get(dict_val: &Value, keys: &[Value]) -> MoltResult {
+    let mut value = dict_val.clone();
+
+    for key in keys {
+        let dict = value.as_dict()?;
+
+        value = match dict.get(key) {
+            Some(val) => val.clone(),
+            None => return molt_err!("key \"{}\" not known in dictionary", key),
+        };
+    }
+
+    Ok(value)
+}
+
+/// Inserts a value into a nested dictionary, following the path of keys, and returns the
+/// new dictionary.  Any missing levels in the path are created.
+pub(crate) fn dict_path_insert(dict_val: &Value, keys: &[Value], value: Value) -> MoltResult {
+    assert!(!keys.is_empty());
+
+    let mut dict = (*dict_val.as_dict()?).clone();
+
+    if keys.len() == 1 {
+        dict.insert(keys[0].clone(), value);
+    } else {
+        let child = dict.get(&keys[0]).cloned().unwrap_or_else(Value::empty);
+        dict.insert(keys[0].clone(), dict_path_insert(&child, &keys[1..], value)?);
+    }
+
+    Ok(Value::from(dict))
+}
+
+/// Removes a key from a nested dictionary, following the path of keys, and returns the new
+/// dictionary.  It's an error if any key but the last is missing.
+pub(crate) fn dict_path_remove(dict_val: &Value, keys: &[Value]) -> MoltResult {
+    assert!(!keys.is_empty());
+
+    let mut dict = (*dict_val.as_dict()?).clone();
+
+    if keys.len() == 1 {
+        dict.shift_remove(&keys[0]);
+    } else if let Some(child) = dict.get(&keys[0]).cloned() {
+        dict.insert(keys[0].clone(), dict_path_remove(&child, &keys[1..])?);
+    } else {
+        return molt_err!("key \"{}\" not known in dictionary", keys[0]);
+    }
+
+    Ok(Value::from(dict))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dict() {
+        let dict = get_dict("a 1 b 2").unwrap();
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(&Value::from("a")), Some(&Value::from("1")));
+        assert_eq!(dict.get(&Value::from("b")), Some(&Value::from("2")));
+
+        // Last value wins, but the key keeps its original place
+        let dict = get_dict("a 1 b 2 a 3").unwrap();
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict_to_string(&dict), "a 3 b 2");
+
+        assert_eq!(get_dict("a 1 b"), molt_err!("missing value to go with key"));
+    }
+
+    #[test]
+    fn test_dict_to_string() {
+        assert_eq!(dict_to_string(&MoltDict::new()), "");
+        assert_eq!(dict_to_string(&get_dict("a 1 b {x y}").unwrap()), "a 1 b {x y}");
+    }
+
+    #[test]
+    fn test_dict_path_get() {
+        let dict = Value::from("a 1 b {c 2}");
+        assert_eq!(dict_path_get(&dict, &[]), Ok(dict.clone()));
+        assert_eq!(dict_path_get(&dict, &[Value::from("a")]), Ok(Value::from("1")));
+        assert_eq!(dict_path_get(&dict, &[Value::from("b"), Value::from("c")]),
+            Ok(Value::from("2")));
+        assert_eq!(dict_path_get(&dict, &[Value::from("x")]),
+            molt_err!("key \"x\" not known in dictionary"));
+    }
+
+    #[test]
+    fn test_dict_path_insert() {
+        let dict = Value::from("a 1");
+        let keys = [Value::from("b"), Value::from("c")];
+        let result = dict_path_insert(&dict, &keys, Value::from("2")).unwrap();
+        assert_eq!(&*result.as_string(), "a 1 b {c 2}");
+
+        let result = dict_path_insert(&result, &keys[0..1], Value::from("3")).unwrap();
+        assert_eq!(&*result.as_string(), "a 1 b 3");
+    }
+
+    #[test]
+    fn test_dict_path_remove() {
+        let dict = Value::from("a 1 b {c 2 d 3}");
+        let keys = [Value::from("b"), Value::from("c")];
+        let result = dict_path_remove(&dict, &keys).unwrap();
+        assert_eq!(&*result.as_string(), "a 1 b {d 3}");
+
+        let result = dict_path_remove(&result, &[Value::from("a")]).unwrap();
+        assert_eq!(&*result.as_string(), "b {d 3}");
+
+        assert_eq!(dict_path_remove(&result, &[Value::from("x"), Value::from("y")]),
+            molt_err!("key \"x\" not known in dictionary"));
+    }
+}
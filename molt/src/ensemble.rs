@@ -0,0 +1,106 @@
+//! Namespace Ensembles
+//!
+//! An ensemble is a command whose subcommands are defined by the commands in a namespace,
+//! as created by `namespace ensemble create`.  By default, calling `mycmd sub args...`
+//! calls `::mycmd::sub args...`; any unambiguous prefix of a subcommand name may be used.
+//! The `-map` option maps subcommand names to arbitrary command prefixes, and the
+//! `-subcommands` option limits the subcommands to a given list.
+
+use crate::interp::Interp;
+use crate::types::*;
+use crate::util::find_subcommand;
+use crate::*;
+
+/// An ensemble command.
+pub(crate) struct Ensemble {
+    /// The fully qualified name of the ensemble's namespace.
+    namespace: String,
+
+    /// The mapping from subcommand names to command prefixes, if any.
+    map: Option<MoltDict>,
+
+    /// The valid subcommand names, if restricted.
+    subcommands: Option<MoltList>,
+}
+
+impl Ensemble {
+    /// Creates an ensemble for the given fully qualified namespace, with an optional
+    /// subcommand map and list of valid subcommands.
+    pub fn new(namespace: &str, map: Option<MoltDict>, subcommands: Option<MoltList>) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            map,
+            subcommands,
+        }
+    }
+
+    /// Gets the valid subcommand names, in sorted order: the -subcommands list, if given;
+    /// otherwise the keys of the -map, if given; otherwise the names of the commands
+    /// currently defined in the namespace.
+    fn names(&self, interp: &Interp) -> Vec<String> {
+        let list = if let Some(subcommands) = &self.subcommands {
+            subcommands.clone()
+        } else if let Some(map) = &self.map {
+            map.keys().cloned().collect()
+        } else {
+            interp.namespace_commands(&self.namespace)
+        };
+
+        let mut names: Vec<String> = list.iter().map(|name| name.to_string()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Gets the command prefix to which the named subcommand is dispatched.  An unqualified
+    /// command name in the map is resolved in the ensemble's namespace, if possible.
+    fn target(&self, interp: &Interp, sub: &str) -> Result<MoltList, ResultCode> {
+        let prefix = self.map.as_ref().and_then(|map| map.get(&Value::from(sub)));
+
+        let mut cmd = match prefix {
+            Some(prefix) => prefix.as_list()?.to_vec(),
+            None => return Ok(vec![Value::from(format!("{}::{}", self.namespace, sub))]),
+        };
+
+        if cmd.is_empty() {
+            return molt_err!("empty command prefix for subcommand \"{}\"", sub);
+        }
+
+        let name = cmd[0].to_string();
+
+        if !name.starts_with("::") {
+            let qualified = format!("{}::{}", self.namespace, name);
+
+            if interp.has_command(&qualified) {
+                cmd[0] = Value::from(qualified);
+            }
+        }
+
+        Ok(cmd)
+    }
+}
+
+impl Command for Ensemble {
+    fn execute(&self, interp: &mut Interp, argv: &[Value]) -> MoltResult {
+        check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+
+        let names = self.names(interp);
+        let sub = argv[1].as_string();
+
+        if names.is_empty() {
+            return molt_err!(
+                "unknown subcommand \"{}\": namespace {} does not export any commands",
+                sub,
+                self.namespace
+            );
+        }
+
+        let refs: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        let sub = find_subcommand(&refs, &sub, true)?;
+
+        let mut cmd = self.target(interp, sub)?;
+        cmd.extend_from_slice(&argv[2..]);
+
+        interp.invoke(&cmd)
+    }
+}
@@ -0,0 +1,183 @@
+//! The Event Queue
+//!
+//! This module defines the interpreter's queue of pending events: the scripts scheduled by
+//! the `after` command, to be evaluated when the application processes events by calling
+//! `Interp::do_one_event` or `Interp::run_event_loop`.  Timer events are due at a given
+//! time; idle events are evaluated when no timer event is due.
+
+use crate::types::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A scheduled script.
+pub(crate) struct Event {
+    /// The event's ID, e.g., "after#0".
+    pub id: String,
+
+    /// The script to evaluate.
+    pub script: Value,
+
+    /// When the script is due, for timer events, or None for idle events.
+    pub due: Option<Instant>,
+}
+
+/// The queue of pending events.
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    /// The number of the next event ID.
+    next_id: usize,
+
+    /// The timer events, by time due; events due at the same time are in the order in
+    /// which they were scheduled.
+    timers: Vec<Event>,
+
+    /// The idle events, in the order in which they were scheduled.
+    idle: VecDeque<Event>,
+}
+
+impl EventQueue {
+    /// Creates an empty event queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets a new, unique event ID.
+    fn new_id(&mut self) -> String {
+        let id = format!("after#{}", self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Schedules the script to be evaluated after the given delay, returning the event's ID.
+    pub fn add_timer(&mut self, delay: Duration, script: Value) -> String {
+        let id = self.new_id();
+        let due = Instant::now() + delay;
+        let index = self.timers.iter().position(|e| e.due > Some(due)).unwrap_or(self.timers.len());
+
+        self.timers.insert(index, Event { id: id.clone(), script, due: Some(due) });
+        id
+    }
+
+    /// Schedules the script to be evaluated when no timer events are due, returning the
+    /// event's ID.
+    pub fn add_idle(&mut self, script: Value) -> String {
+        let id = self.new_id();
+
+        self.idle.push_back(Event { id: id.clone(), script, due: None });
+        id
+    }
+
+    /// Cancels the event with the given ID, if any.
+    pub fn cancel(&mut self, id: &str) {
+        self.timers.retain(|e| e.id != id);
+        self.idle.retain(|e| e.id != id);
+    }
+
+    /// Cancels the first pending event whose script is the given script, if any; timer
+    /// events are considered before idle events.
+    pub fn cancel_script(&mut self, script: &str) {
+        if let Some(index) = self.timers.iter().position(|e| *e.script.as_string() == script) {
+            self.timers.remove(index);
+        } else if let Some(index) = self.idle.iter().position(|e| *e.script.as_string() == script) {
+            self.idle.remove(index);
+        }
+    }
+
+    /// Gets the event with the given ID, if it's pending.
+    pub fn get(&self, id: &str) -> Option<&Event> {
+        self.timers.iter().chain(self.idle.iter()).find(|e| e.id == id)
+    }
+
+    /// Gets the IDs of the pending events: the timer events in the order in which they're
+    /// due, followed by the idle events.
+    pub fn ids(&self) -> MoltList {
+        self.timers
+            .iter()
+            .chain(self.idle.iter())
+            .map(|e| Value::from(&e.id))
+            .collect()
+    }
+
+    /// Determines whether there are no pending events.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty() && self.idle.is_empty()
+    }
+
+    /// Gets the time at which the next timer event is due, if any.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.timers.first().and_then(|e| e.due)
+    }
+
+    /// Removes and returns the next timer event that is due as of the given time, if any.
+    pub fn pop_timer(&mut self, now: Instant) -> Option<Event> {
+        match self.next_due() {
+            Some(due) if due <= now => Some(self.timers.remove(0)),
+            _ => None,
+        }
+    }
+
+    /// Gets the number of pending idle events.
+    pub fn idle_len(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Removes and returns the next idle event, if any.
+    pub fn pop_idle(&mut self) -> Option<Event> {
+        self.idle.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timers() {
+        let mut events = EventQueue::new();
+        let later = events.add_timer(Duration::from_secs(60), Value::from("later"));
+        let now1 = events.add_timer(Duration::from_secs(0), Value::from("now1"));
+        let now2 = events.add_timer(Duration::from_secs(0), Value::from("now2"));
+
+        assert_eq!(later, "after#0");
+        assert_eq!(events.ids(), vec![Value::from(&now1), Value::from(&now2), Value::from(&later)]);
+
+        let now = Instant::now();
+        assert_eq!(events.pop_timer(now).unwrap().id, now1);
+        assert_eq!(events.pop_timer(now).unwrap().id, now2);
+        assert!(events.pop_timer(now).is_none());
+        assert!(!events.is_empty());
+        assert!(events.next_due().unwrap() > now);
+    }
+
+    #[test]
+    fn test_idle() {
+        let mut events = EventQueue::new();
+        let a = events.add_idle(Value::from("a"));
+        let b = events.add_idle(Value::from("b"));
+
+        assert!(events.get(&a).unwrap().due.is_none());
+        assert_eq!(events.pop_idle().unwrap().id, a);
+        assert_eq!(events.pop_idle().unwrap().id, b);
+        assert!(events.pop_idle().is_none());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut events = EventQueue::new();
+        let a = events.add_timer(Duration::from_secs(0), Value::from("a"));
+        let b = events.add_idle(Value::from("b"));
+        let c = events.add_idle(Value::from("a"));
+
+        events.cancel(&b);
+        assert!(events.get(&b).is_none());
+
+        events.cancel_script("a");
+        assert!(events.get(&a).is_none());
+        assert!(events.get(&c).is_some());
+
+        events.cancel("nonesuch");
+        assert_eq!(events.ids(), vec![Value::from(&c)]);
+    }
+}
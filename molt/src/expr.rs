@@ -8,6 +8,9 @@ use crate::context::Context;
 use crate::*;
 use crate::interp::Interp;
 use crate::list;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::convert::TryFrom;
 
 //------------------------------------------------------------------------------------------------
 // Datum Representation
@@ -37,6 +40,10 @@ pub(crate) struct Datum {
     int: MoltInt,
     flt: MoltFloat,
     str: String,
+
+    // The string representation of a numeric value that was given, rather than computed,
+    // e.g., "1.0" or "0x10", for the string operators.
+    rep: Option<Rc<String>>,
 }
 
 impl Datum {
@@ -46,6 +53,7 @@ impl Datum {
             int: 0,
             flt: 0.0,
             str: String::new(),
+            rep: None,
         }
     }
 
@@ -55,6 +63,7 @@ impl Datum {
             int,
             flt: 0.0,
             str: String::new(),
+            rep: None,
         }
     }
 
@@ -64,6 +73,7 @@ impl Datum {
             int: 0,
             flt,
             str: String::new(),
+            rep: None,
         }
     }
 
@@ -73,9 +83,16 @@ impl Datum {
             int: 0,
             flt: 0.0,
             str: string.to_string(),
+            rep: None,
         }
     }
 
+    // Gives a numeric value the string representation it was given as.
+    fn with_rep(mut self, rep: Rc<String>) -> Self {
+        self.rep = Some(rep);
+        self
+    }
+
     // Only for checking integers.
     fn is_true(&self) -> bool {
         match self.vtype {
@@ -101,40 +118,207 @@ enum ArgType {
     Number, // Either Type::Int or Type::Float is OK
 }
 
-type MathFunc = fn(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult;
+type MathFunc = fn(interp: &mut Interp, args: &[Datum]) -> DatumResult;
 
 struct BuiltinFunc {
     name: &'static str,
     num_args: usize,
+    // If true, the function takes num_args or more arguments, all of the last type.
+    variadic: bool,
     arg_types: [ArgType; MAX_MATH_ARGS],
     func: MathFunc,
 }
 
-const FUNC_TABLE: [BuiltinFunc;4] = [
+const FUNC_TABLE: [BuiltinFunc;27] = [
     BuiltinFunc {
         name: "abs",
         num_args: 1,
+        variadic: false,
         arg_types: [ArgType::Number, ArgType::None],
         func: expr_abs_func,
     },
+    BuiltinFunc {
+        name: "acos",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_acos_func,
+    },
+    BuiltinFunc {
+        name: "asin",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_asin_func,
+    },
+    BuiltinFunc {
+        name: "atan",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_atan_func,
+    },
+    BuiltinFunc {
+        name: "atan2",
+        num_args: 2,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::Float],
+        func: expr_atan2_func,
+    },
+    BuiltinFunc {
+        name: "ceil",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_ceil_func,
+    },
+    BuiltinFunc {
+        name: "cos",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_cos_func,
+    },
+    BuiltinFunc {
+        name: "cosh",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_cosh_func,
+    },
     BuiltinFunc {
         name: "double",
         num_args: 1,
+        variadic: false,
         arg_types: [ArgType::Number, ArgType::None],
         func: expr_double_func,
     },
+    BuiltinFunc {
+        name: "exp",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_exp_func,
+    },
+    BuiltinFunc {
+        name: "floor",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_floor_func,
+    },
+    BuiltinFunc {
+        name: "fmod",
+        num_args: 2,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::Float],
+        func: expr_fmod_func,
+    },
+    BuiltinFunc {
+        name: "hypot",
+        num_args: 2,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::Float],
+        func: expr_hypot_func,
+    },
     BuiltinFunc {
         name: "int",
         num_args: 1,
+        variadic: false,
         arg_types: [ArgType::Number, ArgType::None],
         func: expr_int_func,
     },
+    BuiltinFunc {
+        name: "log",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_log_func,
+    },
+    BuiltinFunc {
+        name: "log10",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_log10_func,
+    },
+    BuiltinFunc {
+        name: "max",
+        num_args: 1,
+        variadic: true,
+        arg_types: [ArgType::Number, ArgType::None],
+        func: expr_max_func,
+    },
+    BuiltinFunc {
+        name: "min",
+        num_args: 1,
+        variadic: true,
+        arg_types: [ArgType::Number, ArgType::None],
+        func: expr_min_func,
+    },
+    BuiltinFunc {
+        name: "pow",
+        num_args: 2,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::Float],
+        func: expr_pow_func,
+    },
+    BuiltinFunc {
+        name: "rand",
+        num_args: 0,
+        variadic: false,
+        arg_types: [ArgType::None, ArgType::None],
+        func: expr_rand_func,
+    },
     BuiltinFunc {
         name: "round",
         num_args: 1,
+        variadic: false,
         arg_types: [ArgType::Number, ArgType::None],
         func: expr_round_func,
     },
+    BuiltinFunc {
+        name: "sin",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_sin_func,
+    },
+    BuiltinFunc {
+        name: "sinh",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_sinh_func,
+    },
+    BuiltinFunc {
+        name: "sqrt",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_sqrt_func,
+    },
+    BuiltinFunc {
+        name: "srand",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Number, ArgType::None],
+        func: expr_srand_func,
+    },
+    BuiltinFunc {
+        name: "tan",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_tan_func,
+    },
+    BuiltinFunc {
+        name: "tanh",
+        num_args: 1,
+        variadic: false,
+        arg_types: [ArgType::Float, ArgType::None],
+        func: expr_tanh_func,
+    },
 ];
 
 //------------------------------------------------------------------------------------------------
@@ -216,16 +400,23 @@ const UNARY_PLUS: i32 = 33;
 const NOT: i32 = 34;
 const BIT_NOT: i32 = 35;
 
+// Binary operators added after the unary operators:
+const POW: i32 = 36;
+const STRING_LT: i32 = 37;
+const STRING_GT: i32 = 38;
+const STRING_LE: i32 = 39;
+const STRING_GE: i32 = 40;
+
 // Precedence table.  The values for non-operator token types are ignored.
 
-const PREC_TABLE: [i32; 36] = [
+const PREC_TABLE: [i32; 41] = [
     0, 0, 0, 0, 0, 0, 0, 0,
     14, 14, 14, // MULT, DIVIDE, MOD
     13, 13, // PLUS, MINUS
     12, 12, // LEFT_SHIFT, RIGHT_SHIFT
     11, 11, 11, 11, // LESS, GREATER, LEQ, GEQ
     10, 10, // EQUAL, NEQ
-    9, 9, // STRING_EQ, STRING_NE
+    10, 10, // STRING_EQ, STRING_NE
     8, 8, // IN, NI
     7, // BIT_AND
     6, // BIT_XOR
@@ -235,13 +426,15 @@ const PREC_TABLE: [i32; 36] = [
     2, // QUESTY
     1, // COLON
     13, 13, 13, 13, // UNARY_MINUS, UNARY_PLUS, NOT, BIT_NOT
+    15, // POW
+    11, 11, 11, 11, // STRING_LT, STRING_GT, STRING_LE, STRING_GE
 ];
 
-const OP_STRINGS: [&str; 36] = [
+const OP_STRINGS: [&str; 41] = [
     "VALUE", "(", ")", ",", "END", "UNKNOWN", "6", "7",
     "*", "/", "%", "+", "-", "<<", ">>", "<", ">", "<=",
     ">=", "==", "!=", "eq", "ne", "in", "ni", "&", "^", "|", "&&", "||", "?", ":",
-    "-", "+", "!", "~"
+    "-", "+", "!", "~", "**", "lt", "gt", "le", "ge"
 ];
 
 //------------------------------------------------------------------------------------------------
@@ -327,7 +520,7 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
             info.token = UNARY_PLUS;
         }
 
-        if info.token >= UNARY_MINUS {
+        if info.token >= UNARY_MINUS && info.token < POW {
             // Process unary operators
             operator = info.token;
             value = expr_get_value(interp, info, PREC_TABLE[info.token as usize])?;
@@ -387,6 +580,8 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
                         return molt_err!("unknown unary op: \"{}\"", operator);
                     }
                 }
+
+                value.rep = None;
             }
             got_op = true;
         } else if info.token != VALUE {
@@ -407,7 +602,7 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
         operator = info.token;
         // ??? value2.pv.next = value2.pv.buffer;
 
-        if operator < MULT || operator >= UNARY_MINUS {
+        if !(MULT..UNARY_MINUS).contains(&operator) && operator < POW {
             if operator == END || operator == CLOSE_PAREN || operator == COMMA {
                 return Ok(value);
             } else {
@@ -460,11 +655,13 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
                 // Special note: ?: operators must associate right to left.  To make
                 // this happen, use a precedence one lower than QUESTY when calling
                 // expr_get_value recursively.
+                let questy_pos = expr_position(info) - 1;
+
                 if value.int != 0 {
                     value = expr_get_value(interp, info, PREC_TABLE[QUESTY as usize] - 1)?;
 
                     if info.token != COLON {
-                        return syntax_error(info);
+                        return missing_colon(info, questy_pos);
                     }
 
                     info.no_eval += 1;
@@ -476,7 +673,7 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
                     info.no_eval -= 1;
 
                     if info.token != COLON {
-                        return syntax_error(info);
+                        return missing_colon(info, questy_pos);
                     }
 
                     value = expr_get_value(interp, info, PREC_TABLE[QUESTY as usize] -1)?;
@@ -484,6 +681,9 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
             } else {
                 value2 = expr_get_value(interp, info, PREC_TABLE[operator as usize])?;
             }
+        } else if operator == POW {
+            // Like ?:, ** associates right to left.
+            value2 = expr_get_value(interp, info, PREC_TABLE[POW as usize] - 1)?;
         } else {
             value2 = expr_get_value(interp, info, PREC_TABLE[operator as usize])?;
         }
@@ -508,7 +708,7 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
         match operator {
             // For the operators below, no strings are allowed and ints get converted to
             // floats if necessary.
-            MULT | DIVIDE | PLUS | MINUS => {
+            MULT | DIVIDE | PLUS | MINUS | POW => {
                 if value.vtype == Type::String || value2.vtype == Type::String {
                     return illegal_type(Type::String, operator);
                 }
@@ -561,7 +761,7 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
             // For the operators below, everything's treated as a string.
             // For IN and NI, the second value is a list, but we'll parse it as a list
             // as part of evaluation.
-            STRING_EQ | STRING_NE | IN | NI => {
+            STRING_EQ | STRING_NE | STRING_LT | STRING_GT | STRING_LE | STRING_GE | IN | NI => {
                 if value.vtype != Type::String {
                     value = expr_as_string(value);
                 }
@@ -592,6 +792,21 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
 
         // Carry out the function of the specified operator.
         match operator {
+            POW => {
+                if value.vtype == Type::Int {
+                    value.int = expr_int_pow(value.int, value2.int)?;
+                } else {
+                    if value.flt == 0.0 && value2.flt < 0.0 {
+                        return molt_err!("exponentiation of zero by negative power");
+                    }
+
+                    value.flt = value.flt.powf(value2.flt);
+
+                    if value.flt.is_nan() {
+                        return domain_error();
+                    }
+                }
+            }
             MULT => {
                 if value.vtype == Type::Int {
                     // value.int *= value2.int
@@ -750,6 +965,34 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
                     Datum::int(0)
                 };
             }
+            STRING_LT => {
+                value = if value.str < value2.str {
+                    Datum::int(1)
+                } else {
+                    Datum::int(0)
+                };
+            }
+            STRING_GT => {
+                value = if value.str > value2.str {
+                    Datum::int(1)
+                } else {
+                    Datum::int(0)
+                };
+            }
+            STRING_LE => {
+                value = if value.str <= value2.str {
+                    Datum::int(1)
+                } else {
+                    Datum::int(0)
+                };
+            }
+            STRING_GE => {
+                value = if value.str >= value2.str {
+                    Datum::int(1)
+                } else {
+                    Datum::int(0)
+                };
+            }
             IN => {
                 let list = list::get_list(&value2.str)?;
                 // TODO: Need a better MoltList contains() method.
@@ -812,6 +1055,12 @@ fn expr_get_value<'a>(interp: &mut Interp, info: &'a mut ExprInfo, prec: i32) ->
                 // Nothing to do.
             }
         }
+
+        // The result was computed, and so has no given string representation, unless
+        // the operator was ?:, whose result is one of its operands.
+        if operator != QUESTY {
+            value.rep = None;
+        }
     }
 }
 
@@ -849,11 +1098,12 @@ fn expr_lex(interp: &mut Interp, info: &mut ExprInfo) -> DatumResult {
             let int = Value::get_int(&token)?;
             info.token = VALUE;
             info.expr = p;
-            return Ok(Datum::int(int));
+            return Ok(Datum::int(int).with_rep(Rc::new(token)));
         } else if let Some(token) = util::read_float(&mut p) {
             info.token = VALUE;
             info.expr = p;
-            return Ok(Datum::float(Value::get_float(&token)?));
+            let flt = Value::get_float(&token)?;
+            return Ok(Datum::float(flt).with_rep(Rc::new(token)));
         }
     }
 
@@ -927,7 +1177,14 @@ fn expr_lex(interp: &mut Interp, info: &mut ExprInfo) -> DatumResult {
             Ok(Datum::none())
         }
         Some('*') => {
-            info.token = MULT;
+            p.skip();
+            if let Some('*') = p.peek() {
+                info.token = POW;
+                p.skip();
+                info.expr = p;
+            } else {
+                info.token = MULT;
+            }
             Ok(Datum::none())
         }
         Some('/') => {
@@ -1078,6 +1335,26 @@ fn expr_lex(interp: &mut Interp, info: &mut ExprInfo) -> DatumResult {
                         info.token = STRING_NE;
                         Ok(Datum::none())
                     }
+                    "lt" => {
+                        info.expr = p;
+                        info.token = STRING_LT;
+                        Ok(Datum::none())
+                    }
+                    "gt" => {
+                        info.expr = p;
+                        info.token = STRING_GT;
+                        Ok(Datum::none())
+                    }
+                    "le" => {
+                        info.expr = p;
+                        info.token = STRING_LE;
+                        Ok(Datum::none())
+                    }
+                    "ge" => {
+                        info.expr = p;
+                        info.token = STRING_GE;
+                        Ok(Datum::none())
+                    }
                     "in" => {
                         info.expr = p;
                         info.token = IN;
@@ -1110,7 +1387,6 @@ fn expr_lex(interp: &mut Interp, info: &mut ExprInfo) -> DatumResult {
 }
 
 /// Parses math functions, returning the evaluated value.
-#[allow(clippy::needless_range_loop)]
 fn expr_math_func(interp: &mut Interp, info: &mut ExprInfo, func_name: &str) -> DatumResult {
     // FIRST, is this actually a function?
     // TODO: this does a linear search of the FUNC_TABLE.  Ultimately, it should probably
@@ -1126,69 +1402,73 @@ fn expr_math_func(interp: &mut Interp, info: &mut ExprInfo, func_name: &str) ->
     }
 
     // NEXT, scan off the arguments for the function, if there are any.
-    let mut args: [Datum; MAX_MATH_ARGS] = [Datum::none(), Datum::none()];
+    let mut args: Vec<Datum> = Vec::with_capacity(bfunc.num_args);
+    let mut p = info.expr.clone();
+    p.skip_while(|c| c.is_whitespace());
 
-    if bfunc.num_args == 0 {
+    if p.is(')') {
         let _ = expr_lex(interp, info)?;
-        if info.token != OPEN_PAREN {
-            return syntax_error(info);
+        if bfunc.num_args > 0 {
+            return molt_err!("not enough arguments for math function \"{}\"", func_name);
         }
+    } else if bfunc.num_args == 0 {
+        return molt_err!("too many arguments for math function \"{}\"", func_name);
     } else {
-        for i in 0..bfunc.num_args {
+        loop {
             let arg = expr_get_value(interp, info, -1)?;
 
             // At present we have no string functions.
-            if arg.vtype == Type::String {
+            if arg.vtype == Type::String && info.no_eval == 0 {
                 return molt_err!("argument to math function didn't have numeric value");
             }
 
-            // Copy the value to the argument record, converting it if necessary.
+            // Copy the value to the argument list, converting it if necessary.  The
+            // arguments of a variadic function all have the type of its last argument.
+            let arg_type = bfunc.arg_types[args.len().min(bfunc.num_args - 1)];
+
             if arg.vtype == Type::Int {
-                if bfunc.arg_types[i] == ArgType::Float {
-                    args[i] = Datum::float(arg.int as MoltFloat);
+                if arg_type == ArgType::Float {
+                    args.push(Datum::float(arg.int as MoltFloat));
                 } else {
-                    args[i] = arg;
+                    args.push(arg);
                 }
             } else {  // Type::Float
-                if bfunc.arg_types[i] == ArgType::Int {
+                if arg_type == ArgType::Int {
                     // TODO: Need to handle overflow?
-                    args[i] = Datum::int(arg.flt as MoltInt);
+                    args.push(Datum::int(arg.flt as MoltInt));
                 } else {
-                    args[i] = arg;
+                    args.push(arg);
                 }
             }
 
             // Check for a comma separator between arguments or a close-paren to end
             // the argument list.
-            if i == bfunc.num_args - 1 {
-                if info.token == CLOSE_PAREN {
-                    break;
-                }
-                if info.token == COMMA {
-                    return molt_err!("too many arguments for math function");
-                } else {
-                    return syntax_error(info);
+            if info.token == CLOSE_PAREN {
+                if args.len() < bfunc.num_args {
+                    return molt_err!("not enough arguments for math function \"{}\"", func_name);
                 }
+                break;
             }
 
             if info.token != COMMA {
-                if info.token == CLOSE_PAREN {
-                    return molt_err!("too few arguments for math function");
-                } else {
-                    return syntax_error(info);
-                }
+                return syntax_error(info);
+            }
+
+            if args.len() == bfunc.num_args && !bfunc.variadic {
+                return molt_err!("too many arguments for math function \"{}\"", func_name);
             }
         }
     }
 
     // NEXT, if we aren't evaluating, return an empty value.
+    info.token = VALUE;
+
     if info.no_eval > 0 {
         return Ok(Datum::none());
     }
 
     // NEXT, invoke the math function.
-    info.token = VALUE;
-    (bfunc.func)(&args)
+    (bfunc.func)(interp, &args)
 }
 
 // Find the function in the table.
@@ -1210,7 +1490,7 @@ fn expr_find_func(func_name: &str) -> Result<&'static BuiltinFunc,ResultCode> {
 /// to parse strings with no extra whitespace.  (That may be a bug.)
 fn expr_parse_value(value: &Value) -> DatumResult {
     match value.already_number() {
-        Some(datum) => Ok(datum),
+        Some(datum) => Ok(datum.with_rep(value.as_string())),
         _ => expr_parse_string(&*value.as_string())
     }
 }
@@ -1239,7 +1519,7 @@ fn expr_parse_string(string: &str) -> DatumResult {
                 // Can return an error if the number is too long to represent as a
                 // MoltInt.  This is consistent with Tcl 7.6.  (Tcl 8 uses BigNums.)
                 let int = Value::get_int(&token)?;
-                return Ok(Datum::int(int));
+                return Ok(Datum::int(int).with_rep(Rc::new(string.to_string())));
             }
         } else {
             // FIRST, see if it's a double. Skip leading whitespace.
@@ -1255,7 +1535,7 @@ fn expr_parse_string(string: &str) -> DatumResult {
                     // Can theoretically return an error.  This is consistent with
                     // Tcl 7.6.  Molt and Tcl 8 return 0, Inf, or -Inf instead.
                     let flt = Value::get_float(&token)?;
-                    return Ok(Datum::float(flt));
+                    return Ok(Datum::float(flt).with_rep(Rc::new(string.to_string())));
                 }
             }
         }
@@ -1264,8 +1544,34 @@ fn expr_parse_string(string: &str) -> DatumResult {
     Ok(Datum::string(string))
 }
 
+// Raises an integer to an integer power.  As in Standard TCL, a negative power yields the
+// integer part of the result, which is 0 unless the base is 1 or -1.
+fn expr_int_pow(base: MoltInt, exp: MoltInt) -> Result<MoltInt, ResultCode> {
+    if exp < 0 {
+        return match base {
+            0 => molt_err!("exponentiation of zero by negative power"),
+            1 => Ok(1),
+            -1 => Ok(if exp % 2 == 0 { 1 } else { -1 }),
+            _ => Ok(0),
+        };
+    }
+
+    match base {
+        0 | 1 => Ok(if exp == 0 { 1 } else { base }),
+        -1 => Ok(if exp % 2 == 0 { 1 } else { -1 }),
+        _ => match u32::try_from(exp).ok().and_then(|exp| base.checked_pow(exp)) {
+            Some(int) => Ok(int),
+            None => molt_err!("integer overflow"),
+        },
+    }
+}
+
 // Converts values to strings for string comparisons.
 fn expr_as_string(value: Datum) -> Datum {
+    if let Some(rep) = &value.rep {
+        return Datum::string(rep);
+    }
+
     match value.vtype {
         Type::Int => Datum::string(&format!("{}", value.int)),
         Type::Float => Datum::string(&format!("{}", value.flt)),
@@ -1306,7 +1612,7 @@ impl Datum {
 }
 
 #[allow(clippy::collapsible_if)]
-fn expr_abs_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
+fn expr_abs_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
     let arg = &args[0];
     if arg.vtype == Type::Float {
         if arg.flt < 0.0 {
@@ -1315,16 +1621,42 @@ fn expr_abs_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
             Ok(Datum::float(arg.flt))
         }
     } else {
-        // TODO: need to handle integer overflow here.
-        if arg.int < 0 {
-            Ok(Datum::int(-arg.int))
-        } else {
-            Ok(Datum::int(arg.int))
+        match arg.int.checked_abs() {
+            Some(int) => Ok(Datum::int(int)),
+            None => molt_err!("integer overflow"),
         }
     }
 }
 
-fn expr_double_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
+fn expr_acos_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.acos())
+}
+
+fn expr_asin_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.asin())
+}
+
+fn expr_atan_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.atan())
+}
+
+fn expr_atan2_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.atan2(args[1].flt))
+}
+
+fn expr_ceil_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.ceil())
+}
+
+fn expr_cos_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.cos())
+}
+
+fn expr_cosh_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.cosh())
+}
+
+fn expr_double_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
     let arg = &args[0];
     if arg.vtype == Type::Float {
         Ok(Datum::float(arg.flt))
@@ -1333,7 +1665,23 @@ fn expr_double_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
     }
 }
 
-fn expr_int_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
+fn expr_exp_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.exp())
+}
+
+fn expr_floor_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.floor())
+}
+
+fn expr_fmod_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt % args[1].flt)
+}
+
+fn expr_hypot_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.hypot(args[1].flt))
+}
+
+fn expr_int_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
     let arg = &args[0];
     if arg.vtype == Type::Int {
         Ok(Datum::int(arg.int))
@@ -1343,7 +1691,66 @@ fn expr_int_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
     }
 }
 
-fn expr_round_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
+// The logarithm of zero is -Inf, but Tcl 7.6 treats it as a domain error.
+fn expr_log_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    if args[0].flt == 0.0 {
+        return domain_error();
+    }
+    float_result(args[0].flt.ln())
+}
+
+fn expr_log10_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    if args[0].flt == 0.0 {
+        return domain_error();
+    }
+    float_result(args[0].flt.log10())
+}
+
+fn expr_max_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    expr_extremum(args, Ordering::Greater)
+}
+
+fn expr_min_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    expr_extremum(args, Ordering::Less)
+}
+
+// Finds the argument that compares as wanted with all of the others.  The result is an
+// integer if all of the arguments are.
+fn expr_extremum(args: &[Datum], wanted: Ordering) -> DatumResult {
+    let as_float = |arg: &Datum| {
+        if arg.vtype == Type::Int { arg.int as MoltFloat } else { arg.flt }
+    };
+
+    let mut best = &args[0];
+
+    for arg in &args[1..] {
+        let order = if arg.vtype == Type::Int && best.vtype == Type::Int {
+            arg.int.cmp(&best.int)
+        } else {
+            as_float(arg).partial_cmp(&as_float(best)).unwrap_or(Ordering::Equal)
+        };
+
+        if order == wanted {
+            best = arg;
+        }
+    }
+
+    if args.iter().all(|arg| arg.vtype == Type::Int) {
+        Ok(Datum::int(best.int))
+    } else {
+        Ok(Datum::float(as_float(best)))
+    }
+}
+
+fn expr_pow_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.powf(args[1].flt))
+}
+
+fn expr_rand_func(interp: &mut Interp, _args: &[Datum]) -> DatumResult {
+    Ok(Datum::float(interp.rand()))
+}
+
+fn expr_round_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
     // TODO: need to handle integer overflow here.
     let arg = &args[0];
     if arg.vtype == Type::Int {
@@ -1355,12 +1762,70 @@ fn expr_round_func(args: &[Datum; MAX_MATH_ARGS]) -> DatumResult {
     }
 }
 
+fn expr_sin_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.sin())
+}
+
+fn expr_sinh_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.sinh())
+}
+
+fn expr_sqrt_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.sqrt())
+}
+
+fn expr_srand_func(interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    if args[0].vtype != Type::Int {
+        return molt_err!("can't use floating-point value as argument to srand");
+    }
+
+    interp.srand(args[0].int);
+    Ok(Datum::float(interp.rand()))
+}
+
+fn expr_tan_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.tan())
+}
+
+fn expr_tanh_func(_interp: &mut Interp, args: &[Datum]) -> DatumResult {
+    float_result(args[0].flt.tanh())
+}
+
+// Returns the result of a floating-point math function, which is NaN if an argument
+// was outside the function's domain.
+fn float_result(flt: MoltFloat) -> DatumResult {
+    if flt.is_nan() {
+        domain_error()
+    } else {
+        Ok(Datum::float(flt))
+    }
+}
+
+// Return standard domain error
+fn domain_error() -> DatumResult {
+    molt_err!("domain error: argument not in valid range")
+}
 
 // Return standard syntax error
 fn syntax_error(info: &mut ExprInfo) -> DatumResult {
     molt_err!("syntax error in expression \"{}\"", info.original_expr)
 }
 
+// Return the syntax error for a "?" with no matching ":", given the character index of the
+// "?" in the expression.
+fn missing_colon(info: &mut ExprInfo, questy_pos: usize) -> DatumResult {
+    molt_err!(
+        "syntax error in expression \"{}\": missing \":\" for \"?\" at character {}",
+        info.original_expr,
+        questy_pos
+    )
+}
+
+// Gets the character index of the next character to be lexed in the expression.
+fn expr_position(info: &ExprInfo) -> usize {
+    info.original_expr.chars().count() - info.expr.to_peekable().count()
+}
+
 // Return standard illegal type error
 fn illegal_type(bad_type: Type, op: i32) -> DatumResult {
     let type_str = if bad_type == Type::Float {
@@ -0,0 +1,1333 @@
+//! File System Commands
+//!
+//! This module defines the commands that query and manipulate the file system, e.g., the
+//! `file` ensemble.  They are kept apart from the standard commands so that an application
+//! can create an interpreter that has no access to the file system: `Interp::new` adds them
+//! by calling `Interp::add_filesystem_commands`, and an interpreter built up from
+//! `Interp::empty` need not.
+//!
+//! The `file` subcommands that only manipulate path names, e.g., `file join`, don't touch
+//! the file system, and are also available on their own as the standard command
+//! `cmd_safe_file`.  Path names may use either `/` or `\` as the separator, whatever
+//! the platform, so that scripts are portable; results always use `/`.
+
+use crate::channel::Channel;
+use crate::interp::Interp;
+use crate::process;
+use crate::types::*;
+use crate::util::glob_match;
+use crate::util::posix_error;
+use crate::*;
+use std::fs;
+use std::fs::Metadata;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::MAIN_SEPARATOR;
+use std::rc::Rc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// # cd ?*dirName*?
+///
+/// Changes the current working directory to *dirName*, or to the user's home directory,
+/// `$env(HOME)`, if *dirName* is omitted.
+pub fn cmd_cd(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 2, "?dirName?")?;
+
+    let dir = if argv.len() == 2 {
+        argv[1].clone()
+    } else {
+        match interp.element("::env", "HOME") {
+            Ok(home) => home,
+            Err(_) => return molt_err!("couldn't find HOME environment variable to expand path"),
+        }
+    };
+
+    match std::env::set_current_dir(&*dir.as_string()) {
+        Ok(_) => molt_ok!(),
+        Err(e) => molt_err!(
+            "couldn't change working directory to \"{}\": {}",
+            dir,
+            posix_error(&e)
+        ),
+    }
+}
+
+/// # file *subcommand* ?*arg* ...?
+pub fn cmd_file(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&FILE_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const FILE_SUBCOMMANDS: [Subcommand; 22] = [
+    Subcommand("atime", cmd_file_atime),
+    Subcommand("copy", cmd_file_copy),
+    Subcommand("delete", cmd_file_delete),
+    Subcommand("dirname", cmd_file_dirname),
+    Subcommand("executable", cmd_file_executable),
+    Subcommand("exists", cmd_file_exists),
+    Subcommand("extension", cmd_file_extension),
+    Subcommand("isdirectory", cmd_file_isdirectory),
+    Subcommand("isfile", cmd_file_isfile),
+    Subcommand("join", cmd_file_join),
+    Subcommand("mkdir", cmd_file_mkdir),
+    Subcommand("mtime", cmd_file_mtime),
+    Subcommand("nativename", cmd_file_nativename),
+    Subcommand("normalize", cmd_file_normalize),
+    Subcommand("readable", cmd_file_readable),
+    Subcommand("rename", cmd_file_rename),
+    Subcommand("rootname", cmd_file_rootname),
+    Subcommand("size", cmd_file_size),
+    Subcommand("split", cmd_file_split),
+    Subcommand("tail", cmd_file_tail),
+    Subcommand("type", cmd_file_type),
+    Subcommand("writable", cmd_file_writable),
+];
+
+/// # file *subcommand* ?*arg* ...?
+///
+/// A version of `file` that has only the subcommands that manipulate path names, for use
+/// in interpreters that have no access to the file system.
+pub fn cmd_safe_file(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 0, "subcommand ?arg ...?")?;
+    let subc = Subcommand::find(&SAFE_FILE_SUBCOMMANDS, &argv[1].as_string())?;
+
+    (subc.1)(interp, argv)
+}
+
+const SAFE_FILE_SUBCOMMANDS: [Subcommand; 8] = [
+    Subcommand("dirname", cmd_file_dirname),
+    Subcommand("extension", cmd_file_extension),
+    Subcommand("join", cmd_file_join),
+    Subcommand("nativename", cmd_file_nativename),
+    Subcommand("normalize", cmd_file_normalize),
+    Subcommand("rootname", cmd_file_rootname),
+    Subcommand("split", cmd_file_split),
+    Subcommand("tail", cmd_file_tail),
+];
+
+/// # file atime *name*
+///
+/// Returns the time the file was last accessed, in seconds since the epoch.
+pub fn cmd_file_atime(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name = argv[2].as_string();
+    let meta = metadata(&name)?;
+
+    match meta.accessed() {
+        Ok(time) => molt_ok!(epoch_seconds(time)),
+        Err(e) => molt_err!("could not get access time for file \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # file copy ?-force? ?--? *source* ?*source* ...? *target*
+///
+/// Copies the source file or directory to the target.  If the target is an existing
+/// directory, or if there are multiple sources, each source is copied into the target
+/// directory.  An existing target is overwritten only if `-force` is given.  Directories
+/// are copied recursively.
+pub fn cmd_file_copy(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    transfer_files(argv, Transfer::Copy)
+}
+
+/// # file delete ?-force? ?--? ?*name* ...?
+///
+/// Deletes the named files and directories.  A directory that isn't empty is deleted,
+/// along with its contents, only if `-force` is given.  Names of files that don't exist
+/// are ignored.
+pub fn cmd_file_delete(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let (force, first) = file_options(argv, 2)?;
+
+    for name in &argv[first..] {
+        let name = name.as_string();
+        let path = Path::new(&*name);
+
+        let result = match fs::symlink_metadata(path) {
+            Ok(meta) if meta.is_dir() && force => fs::remove_dir_all(path),
+            Ok(meta) if meta.is_dir() => fs::remove_dir(path),
+            Ok(_) => fs::remove_file(path),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            return molt_err!("error deleting \"{}\": {}", name, posix_error(&e));
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # file dirname *name*
+///
+/// Returns the name of the directory containing the named file: all but the last
+/// component of the path, or `.` for a relative name with one component.
+pub fn cmd_file_dirname(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let (root, parts) = split_path(&argv[2].as_string());
+
+    if parts.len() > 1 {
+        molt_ok!(join_path(&root, &parts[..parts.len() - 1]))
+    } else if !root.is_empty() {
+        molt_ok!(root)
+    } else {
+        molt_ok!(".")
+    }
+}
+
+/// # file executable *name*
+///
+/// Returns 1 if the file exists and the current user may execute it, and 0 otherwise.
+pub fn cmd_file_executable(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(is_executable(Path::new(&*argv[2].as_string())))
+}
+
+/// # file exists *name*
+///
+/// Returns 1 if the file exists, and 0 otherwise.
+pub fn cmd_file_exists(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(Path::new(&*argv[2].as_string()).exists())
+}
+
+/// # file extension *name*
+///
+/// Returns the extension of the last component of the name, i.e., everything from its
+/// last `.` onward, or the empty string if it has no `.`.
+pub fn cmd_file_extension(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name = argv[2].as_string();
+
+    match extension_index(&name) {
+        Some(index) => molt_ok!(&name[index..]),
+        None => molt_ok!(""),
+    }
+}
+
+/// # file isdirectory *name*
+///
+/// Returns 1 if the file exists and is a directory, and 0 otherwise.
+pub fn cmd_file_isdirectory(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(Path::new(&*argv[2].as_string()).is_dir())
+}
+
+/// # file isfile *name*
+///
+/// Returns 1 if the file exists and is a regular file, and 0 otherwise.
+pub fn cmd_file_isfile(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(Path::new(&*argv[2].as_string()).is_file())
+}
+
+/// # file join *name* ?*name* ...?
+///
+/// Joins the names into a single path name.  If a name is absolute, the preceding names
+/// are discarded.
+pub fn cmd_file_join(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 0, "name ?name ...?")?;
+
+    let mut root = String::new();
+    let mut parts = Vec::new();
+
+    for name in &argv[2..] {
+        let (name_root, name_parts) = split_path(&name.as_string());
+
+        if !name_root.is_empty() {
+            root = name_root;
+            parts.clear();
+        }
+
+        parts.extend(name_parts);
+    }
+
+    molt_ok!(join_path(&root, &parts))
+}
+
+/// # file mkdir ?*name* ...?
+///
+/// Creates the named directories, along with any missing parent directories.  It is not
+/// an error if a directory already exists.
+pub fn cmd_file_mkdir(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    for name in &argv[2..] {
+        let name = name.as_string();
+        let path = Path::new(&*name);
+
+        if path.exists() && !path.is_dir() {
+            return molt_err!("can't create directory \"{}\": file already exists", name);
+        }
+
+        if let Err(e) = fs::create_dir_all(path) {
+            return molt_err!("can't create directory \"{}\": {}", name, posix_error(&e));
+        }
+    }
+
+    molt_ok!()
+}
+
+/// # file mtime *name*
+///
+/// Returns the time the file was last modified, in seconds since the epoch.
+pub fn cmd_file_mtime(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name = argv[2].as_string();
+    let meta = metadata(&name)?;
+
+    match meta.modified() {
+        Ok(time) => molt_ok!(epoch_seconds(time)),
+        Err(e) => molt_err!("could not get modification time for file \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// # file nativename *name*
+///
+/// Returns the name with its separators converted to the platform's native separator.
+pub fn cmd_file_nativename(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name: String = argv[2]
+        .as_string()
+        .chars()
+        .map(|c| if is_separator(c) { MAIN_SEPARATOR } else { c })
+        .collect();
+
+    molt_ok!(name)
+}
+
+/// # file normalize *name*
+///
+/// Returns the absolute path name equivalent to the name, with `.` and `..` components
+/// resolved.  Relative names are relative to the current working directory.
+pub fn cmd_file_normalize(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let (mut root, mut parts) = split_path(&argv[2].as_string());
+
+    // A volume-relative name, e.g., "C:foo", is treated as relative to the working
+    // directory.
+    if root.is_empty() || !root.ends_with('/') && !root.starts_with("//") {
+        let cwd = match std::env::current_dir() {
+            Ok(cwd) => cwd,
+            Err(e) => return molt_err!("error getting working directory name: {}", posix_error(&e)),
+        };
+        let (cwd_root, mut cwd_parts) = split_path(&cwd.to_string_lossy());
+        cwd_parts.append(&mut parts);
+        root = cwd_root;
+        parts = cwd_parts;
+    }
+
+    let mut normal: Vec<String> = Vec::new();
+
+    for part in parts {
+        match part.as_str() {
+            "." => {}
+            ".." => {
+                normal.pop();
+            }
+            _ => normal.push(part),
+        }
+    }
+
+    molt_ok!(join_path(&root, &normal))
+}
+
+/// # file readable *name*
+///
+/// Returns 1 if the file exists and the current user may read it, and 0 otherwise.
+pub fn cmd_file_readable(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(is_readable(Path::new(&*argv[2].as_string())))
+}
+
+/// # file rename ?-force? ?--? *source* ?*source* ...? *target*
+///
+/// Renames the source file or directory to the target.  If the target is an existing
+/// directory, or if there are multiple sources, each source is moved into the target
+/// directory.  An existing target is overwritten only if `-force` is given.
+pub fn cmd_file_rename(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    transfer_files(argv, Transfer::Rename)
+}
+
+/// # file rootname *name*
+///
+/// Returns the name with the extension of its last component, if any, removed.
+pub fn cmd_file_rootname(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name = argv[2].as_string();
+
+    match extension_index(&name) {
+        Some(index) => molt_ok!(&name[..index]),
+        None => molt_ok!(argv[2].clone()),
+    }
+}
+
+/// # file size *name*
+///
+/// Returns the size of the file in bytes.
+pub fn cmd_file_size(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let meta = metadata(&argv[2].as_string())?;
+
+    molt_ok!(meta.len() as MoltInt)
+}
+
+/// # file split *name*
+///
+/// Returns a list of the components of the path name.  The first element is the root
+/// directory, e.g., `/` or `C:/`, if the name is absolute.
+pub fn cmd_file_split(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let (root, parts) = split_path(&argv[2].as_string());
+    let mut list = MoltList::new();
+
+    if !root.is_empty() {
+        list.push(Value::from(root));
+    }
+
+    list.extend(parts.into_iter().map(Value::from));
+
+    molt_ok!(list)
+}
+
+/// # file tail *name*
+///
+/// Returns the last component of the path name, or the empty string if it has none.
+pub fn cmd_file_tail(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let (_, parts) = split_path(&argv[2].as_string());
+
+    match parts.last() {
+        Some(tail) => molt_ok!(tail.as_str()),
+        None => molt_ok!(""),
+    }
+}
+
+/// # file type *name*
+///
+/// Returns the type of the file: `file`, `directory`, `characterSpecial`, `blockSpecial`,
+/// `fifo`, `link`, or `socket`.  Symbolic links are not followed.
+pub fn cmd_file_type(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    let name = argv[2].as_string();
+    let meta = match fs::symlink_metadata(&*name) {
+        Ok(meta) => meta,
+        Err(e) => return molt_err!("could not read \"{}\": {}", name, posix_error(&e)),
+    };
+
+    molt_ok!(file_type(&meta))
+}
+
+/// # file writable *name*
+///
+/// Returns 1 if the file exists and the current user may write to it, and 0 otherwise.
+pub fn cmd_file_writable(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(2, argv, 3, 3, "name")?;
+
+    molt_ok!(is_writable(Path::new(&*argv[2].as_string())))
+}
+
+/// # glob ?-directory *dir*? ?-nocomplain? ?-types *types*? ?--? *pattern* ?*pattern* ...?
+///
+/// Returns a list of the names of the files that match any of the glob patterns.  It is an
+/// error if no file matches, unless `-nocomplain` is given.
+pub fn cmd_glob(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut dir: Option<Rc<String>> = None;
+    let mut nocomplain = false;
+    let mut types = GlobTypes::default();
+    let mut i = 1;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        i += 1;
+
+        match opt.as_str() {
+            "-directory" | "-types" => {
+                if i == argv.len() {
+                    return molt_err!("missing argument to \"{}\"", opt);
+                }
+
+                if opt.as_str() == "-directory" {
+                    dir = Some(argv[i].as_string());
+                } else {
+                    types = GlobTypes::parse(&argv[i])?;
+                }
+
+                i += 1;
+            }
+            "-nocomplain" => nocomplain = true,
+            "--" => break,
+            _ => {
+                return molt_err!(
+                    "bad option \"{}\": must be -directory, -nocomplain, -types, or --",
+                    opt
+                )
+            }
+        }
+    }
+
+    if i == argv.len() {
+        return molt_err!("wrong # args: should be \"glob ?switches? name ?name ...?\"");
+    }
+
+    let mut result = MoltList::new();
+
+    for pattern in &argv[i..] {
+        for pattern in expand_braces(&pattern.as_string())? {
+            for name in glob_files(dir.as_ref().map(|d| d.as_str()), &pattern, &types) {
+                result.push(Value::from(name));
+            }
+        }
+    }
+
+    if result.is_empty() && !nocomplain {
+        let patterns = &argv[i..];
+        if patterns.len() == 1 {
+            return molt_err!("no files matched glob pattern \"{}\"", patterns[0]);
+        } else {
+            return molt_err!(
+                "no files matched glob patterns \"{}\"",
+                list_to_string(patterns)
+            );
+        }
+    }
+
+    molt_ok!(result)
+}
+
+/// # open *fileName* ?*access*? ?*permissions*?
+///
+/// Opens the file, returning the name of a new channel for reading and writing it.  The
+/// access mode is one of `r`, `r+`, `w`, `w+`, `a`, or `a+`, as for C's `fopen`; it
+/// defaults to `r`.  A new file is created with the given permissions, which default to
+/// `0666`, less the process's umask.  A *fileName* beginning with `|` is a command
+/// pipeline, which is run as for `exec`; see `process::open_pipeline`.
+pub fn cmd_open(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 4, "fileName ?access? ?permissions?")?;
+
+    let name = argv[1].as_string();
+    let access = if argv.len() > 2 { argv[2].as_string() } else { Rc::new("r".into()) };
+    let mut options = OpenOptions::new();
+
+    let (readable, writable) = match access.as_str() {
+        "r" => (true, false),
+        "r+" => (true, true),
+        "w" => (false, true),
+        "w+" => (true, true),
+        "a" => (false, true),
+        "a+" => (true, true),
+        _ => return molt_err!("illegal access mode \"{}\"", access),
+    };
+
+    if let Some(command) = name.strip_prefix('|') {
+        return process::open_pipeline(interp, command, readable, writable);
+    }
+
+    options.read(readable).write(writable);
+
+    if access.starts_with('w') {
+        options.create(true).truncate(true);
+    } else if access.starts_with('a') {
+        options.create(true).append(true);
+    }
+
+    if argv.len() == 4 {
+        set_permissions(&mut options, &argv[3])?;
+    }
+
+    match options.open(&*name) {
+        Ok(mut file) => {
+            // As in TCL, a file opened for appending starts at its end.
+            if access.starts_with('a') {
+                if let Err(e) = file.seek(SeekFrom::End(0)) {
+                    return molt_err!("couldn't open \"{}\": {}", name, posix_error(&e));
+                }
+            }
+
+            let channel = Channel::new(Box::new(file), readable, writable);
+            molt_ok!(interp.add_channel("file", channel))
+        }
+        Err(e) => molt_err!("couldn't open \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// Sets the permissions with which `open` creates a file.  By TCL convention, the
+/// permissions are usually written in octal with a leading zero, e.g., `0644`.
+fn set_permissions(options: &mut OpenOptions, value: &Value) -> Result<(), ResultCode> {
+    let string = value.as_string();
+    let octal = string.strip_prefix("0o").or_else(|| string.strip_prefix('0'));
+
+    let mode = match octal {
+        Some(digits) if !digits.is_empty() => match MoltInt::from_str_radix(digits, 8) {
+            Ok(mode) => mode,
+            Err(_) => return molt_err!("expected integer but got \"{}\"", string),
+        },
+        _ => value.as_int()?,
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode as u32);
+    }
+
+    #[cfg(not(unix))]
+    let _ = (options, mode);
+
+    Ok(())
+}
+
+/// # pwd
+///
+/// Returns the current working directory.
+pub fn cmd_pwd(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 1, 1, "")?;
+
+    match std::env::current_dir() {
+        Ok(dir) => molt_ok!(dir.to_string_lossy().to_string()),
+        Err(e) => molt_err!("error getting working directory name: {}", posix_error(&e)),
+    }
+}
+
+/// # source *filename*
+///
+/// Evaluates the file as a script at the current scope level, returning its result.  A
+/// `return` at the top level of the file returns from the file.
+pub fn cmd_source(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    check_args(1, argv, 2, 2, "filename")?;
+
+    let filename = &*argv[1].as_string();
+
+    let script = match fs::read_to_string(filename) {
+        Ok(script) => script,
+        Err(e) => return molt_err!("couldn't read file \"{}\": {}", filename, posix_error(&e)),
+    };
+
+    // NEXT, evaluate the script, saving and restoring the enclosing script's name.
+    let outer_file = interp.script_file().to_string();
+    interp.set_script_file(filename);
+    let result = interp.eval(&script);
+    interp.set_script_file(&outer_file);
+
+    // NEXT, if there was an error, note the file in the error info.
+    match result {
+        Err(ResultCode::Error(mut data)) => {
+            let line = data.error_line();
+            data.add_error_info(&format!("\n    (file \"{}\" line {})", filename, line));
+            Err(ResultCode::Error(data))
+        }
+        _ => result,
+    }
+}
+
+//--------------------------------------------------------------------------------------------
+// Path Names
+
+/// Determines whether the character is a path separator.  Both `/` and `\` are accepted
+/// on all platforms.
+fn is_separator(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+/// Splits a path name into its root, which is empty for a relative name, and its
+/// components, ignoring empty components.  The root is one of `/`, a drive such as `C:/`,
+/// a drive-relative prefix such as `C:`, or a UNC prefix such as `//server/share`, always
+/// written with `/`.
+fn split_path(name: &str) -> (String, Vec<String>) {
+    let (root, rest) = split_root(name);
+    let parts = rest
+        .split(is_separator)
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_string())
+        .collect();
+
+    (root, parts)
+}
+
+/// Splits the root off of a path name, returning the root and the rest of the name.
+fn split_root(name: &str) -> (String, &str) {
+    let mut chars = name.chars();
+    let first = chars.next();
+    let second = chars.next();
+    let third = chars.next();
+
+    match (first, second) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => {
+            if third.is_some_and(is_separator) {
+                (format!("{}:/", drive), &name[3..])
+            } else {
+                (format!("{}:", drive), &name[2..])
+            }
+        }
+        (Some(a), Some(b)) if is_separator(a) && is_separator(b) => {
+            // A UNC name, //server/share; if the share is missing, the name is simply
+            // absolute.
+            let rest = &name[2..];
+            if let Some(end) = rest.find(is_separator).filter(|&end| end > 0) {
+                let share = &rest[end + 1..];
+                let share_end = share.find(is_separator).unwrap_or(share.len());
+                if share_end > 0 {
+                    let root = format!("//{}/{}", &rest[..end], &share[..share_end]);
+                    return (root, &share[share_end..]);
+                }
+            }
+            ("/".into(), &name[1..])
+        }
+        (Some(a), _) if is_separator(a) => ("/".into(), &name[1..]),
+        _ => (String::new(), name),
+    }
+}
+
+/// Joins a root and a list of components into a path name.
+fn join_path(root: &str, parts: &[String]) -> String {
+    let mut name = root.to_string();
+
+    if !parts.is_empty() && !name.is_empty() && !name.ends_with('/') && !name.ends_with(':') {
+        name.push('/');
+    }
+
+    name.push_str(&parts.join("/"));
+    name
+}
+
+/// Gets the index of the `.` that begins the extension of the last component of the
+/// path name, if it has an extension.  The components `.` and `..` have no extension.
+fn extension_index(name: &str) -> Option<usize> {
+    let start = name.rfind(is_separator).map_or(0, |sep| sep + 1);
+    let tail = &name[start..];
+
+    if tail == "." || tail == ".." {
+        return None;
+    }
+
+    tail.rfind('.').map(|dot| start + dot)
+}
+
+//--------------------------------------------------------------------------------------------
+// Glob Patterns
+
+/// The file types and permissions given by `glob -types`.  A file matches if it is any
+/// one of the types, if any are given, and has all of the permissions.
+#[derive(Default)]
+struct GlobTypes {
+    /// The file types, as returned by `file type`.
+    types: Vec<&'static str>,
+
+    /// The permissions, `r`, `w`, and `x`.
+    perms: Vec<char>,
+}
+
+impl GlobTypes {
+    /// Parses the `-types` list.
+    fn parse(value: &Value) -> Result<Self, ResultCode> {
+        let mut types = GlobTypes::default();
+
+        for item in &*value.as_list()? {
+            match item.as_string().as_str() {
+                "b" => types.types.push("blockSpecial"),
+                "c" => types.types.push("characterSpecial"),
+                "d" => types.types.push("directory"),
+                "f" => types.types.push("file"),
+                "l" => types.types.push("link"),
+                "p" => types.types.push("fifo"),
+                "s" => types.types.push("socket"),
+                "r" => types.perms.push('r'),
+                "w" => types.perms.push('w'),
+                "x" => types.perms.push('x'),
+                _ => return molt_err!("bad argument to \"-types\": {}", item),
+            }
+        }
+
+        Ok(types)
+    }
+
+    /// Determines whether the file matches the types and permissions.
+    fn matches(&self, path: &Path) -> bool {
+        // Links are followed except when looking for links.
+        let is_type = |name: &str| {
+            let meta = if name == "link" {
+                fs::symlink_metadata(path)
+            } else {
+                fs::metadata(path)
+            };
+            meta.is_ok_and(|meta| file_type(&meta) == name)
+        };
+
+        let has_perm = |perm: &char| match perm {
+            'r' => is_readable(path),
+            'w' => is_writable(path),
+            _ => is_executable(path),
+        };
+
+        (self.types.is_empty() || self.types.iter().any(|t| is_type(t)))
+            && self.perms.iter().all(has_perm)
+    }
+}
+
+/// Expands the `{a,b,...}` alternations in a glob pattern, returning the list of patterns
+/// that result.
+fn expand_braces(pattern: &str) -> Result<Vec<String>, ResultCode> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut open = None;
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => {
+                if depth == 0 {
+                    open = Some(i);
+                }
+                depth += 1;
+            }
+            ',' if depth == 1 => commas.push(i),
+            '}' if depth > 0 => {
+                depth -= 1;
+
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let open = match open {
+        Some(open) => open,
+        None => return Ok(vec![pattern.to_string()]),
+    };
+
+    if depth > 0 {
+        return molt_err!("unmatched open-brace in file name");
+    }
+
+    let prefix: String = chars[..open].iter().collect();
+    let suffix: String = chars[i + 1..].iter().collect();
+    let mut bounds = vec![open];
+    bounds.extend(commas);
+    bounds.push(i);
+
+    let mut patterns = Vec::new();
+
+    for pair in bounds.windows(2) {
+        let alt: String = chars[pair[0] + 1..pair[1]].iter().collect();
+        patterns.extend(expand_braces(&format!("{}{}{}", prefix, alt, suffix))?);
+    }
+
+    Ok(patterns)
+}
+
+/// Gets the names of the files that match a glob pattern without alternations, relative
+/// to the given directory, if any.  The components of the pattern are separated by `/`;
+/// `\` quotes the following character.  Files whose names begin with `.` match only if
+/// the pattern component begins with `.`.
+fn glob_files(dir: Option<&str>, pattern: &str, types: &GlobTypes) -> Vec<String> {
+    // Only "/" is a separator in a pattern, as "\\" is the quote character.
+    let (root, rest) = match split_root(pattern) {
+        (root, rest) if !pattern[..pattern.len() - rest.len()].contains('\\') => (root, rest),
+        _ => (String::new(), pattern),
+    };
+    let parts: Vec<&str> = rest.split('/').filter(|p| !p.is_empty()).collect();
+
+    // A pattern ending in "/" matches only directories.
+    let dirs_only = !parts.is_empty() && pattern.ends_with('/');
+
+    let mut paths = vec![if root.is_empty() {
+        dir.unwrap_or("").to_string()
+    } else {
+        root
+    }];
+
+    for (i, part) in parts.iter().enumerate() {
+        let mut next = Vec::new();
+
+        for path in &paths {
+            if !part.contains(|c| "*?[\\".contains(c)) {
+                next.push(glob_join(path, part));
+                continue;
+            }
+
+            let entries = match fs::read_dir(if path.is_empty() { "." } else { path }) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            let mut names: Vec<String> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with('.') || part.starts_with('.'))
+                .filter(|name| glob_match(part, name))
+                .collect();
+            names.sort();
+
+            next.extend(names.iter().map(|name| glob_join(path, name)));
+        }
+
+        if i + 1 < parts.len() {
+            next.retain(|path| Path::new(path).is_dir());
+        }
+
+        paths = next;
+    }
+
+    paths
+        .into_iter()
+        .filter(|path| fs::symlink_metadata(path).is_ok())
+        .filter(|path| !dirs_only || Path::new(path).is_dir())
+        .filter(|path| types.matches(Path::new(path)))
+        .map(|path| if dirs_only { path + "/" } else { path })
+        .collect()
+}
+
+/// Appends a file name to a directory name, as returned by `glob`.
+fn glob_join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+//--------------------------------------------------------------------------------------------
+// Helpers
+
+/// Parses the `?-force? ?--?` options of `file copy`, `file delete`, and `file rename`,
+/// which begin at index `first` in the argument list.  Returns the value of `-force` and
+/// the index of the first argument following the options.
+fn file_options(argv: &[Value], first: usize) -> Result<(bool, usize), ResultCode> {
+    let mut force = false;
+    let mut i = first;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        i += 1;
+
+        match opt.as_str() {
+            "-force" => force = true,
+            "--" => break,
+            _ => return molt_err!("bad option \"{}\": must be -force or --", opt),
+        }
+    }
+
+    Ok((force, i))
+}
+
+/// The two ways `transfer_files` can move a file to its target.
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Transfer {
+    Copy,
+    Rename,
+}
+
+impl Transfer {
+    /// The verb used in error messages.
+    fn verb(self) -> &'static str {
+        match self {
+            Transfer::Copy => "copying",
+            Transfer::Rename => "renaming",
+        }
+    }
+}
+
+/// Implements `file copy` and `file rename`, which take the same arguments and differ
+/// only in what they do with each source file.
+fn transfer_files(argv: &[Value], how: Transfer) -> MoltResult {
+    let (force, first) = file_options(argv, 2)?;
+
+    if argv.len() - first < 2 {
+        return molt_err!(
+            "wrong # args: should be \"{} {} ?-force? ?--? source ?source ...? target\"",
+            argv[0].as_string(),
+            argv[1].as_string()
+        );
+    }
+
+    let target = argv[argv.len() - 1].as_string();
+    let target_path = Path::new(&*target);
+    let sources = &argv[first..argv.len() - 1];
+    let into_dir = target_path.is_dir();
+
+    if sources.len() > 1 && !into_dir {
+        return molt_err!("error {}: target \"{}\" is not a directory", how.verb(), target);
+    }
+
+    for source in sources {
+        let source = source.as_string();
+        let source_path = Path::new(&*source);
+
+        let dest = if into_dir {
+            match source_path.file_name() {
+                Some(tail) => target_path.join(tail),
+                None => {
+                    return molt_err!(
+                        "error {} \"{}\" to \"{}\": invalid source name",
+                        how.verb(),
+                        source,
+                        target
+                    )
+                }
+            }
+        } else {
+            target_path.to_path_buf()
+        };
+
+        transfer_file(source_path, &dest, force, how)?;
+    }
+
+    molt_ok!()
+}
+
+/// Copies or renames a single file or directory to the given destination, first removing
+/// an existing destination if `force` is set.
+fn transfer_file(source: &Path, dest: &Path, force: bool, how: Transfer) -> Result<(), ResultCode> {
+    let src_name = source.display();
+    let dest_name = dest.display();
+
+    let src_meta = match fs::symlink_metadata(source) {
+        Ok(meta) => meta,
+        Err(e) => return molt_err!("error {} \"{}\": {}", how.verb(), src_name, posix_error(&e)),
+    };
+
+    if let Ok(dest_meta) = fs::symlink_metadata(dest) {
+        if !force {
+            return molt_err!(
+                "error {} \"{}\" to \"{}\": file already exists",
+                how.verb(),
+                src_name,
+                dest_name
+            );
+        }
+
+        if dest_meta.is_dir() && !src_meta.is_dir() {
+            return molt_err!(
+                "can't overwrite directory \"{}\" with file \"{}\"",
+                dest_name,
+                src_name
+            );
+        }
+
+        if !dest_meta.is_dir() && src_meta.is_dir() {
+            return molt_err!(
+                "can't overwrite file \"{}\" with directory \"{}\"",
+                dest_name,
+                src_name
+            );
+        }
+
+        let removed = if dest_meta.is_dir() {
+            fs::remove_dir(dest)
+        } else {
+            fs::remove_file(dest)
+        };
+
+        // A directory that isn't empty can't be overwritten, even with -force.
+        if removed.is_err() {
+            return molt_err!(
+                "error {} \"{}\" to \"{}\": file already exists",
+                how.verb(),
+                src_name,
+                dest_name
+            );
+        }
+    }
+
+    let result = match how {
+        Transfer::Copy => copy_path(source, dest),
+        Transfer::Rename => match fs::rename(source, dest) {
+            // A file can't be renamed onto another file system; copy it and delete the
+            // original instead.
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                copy_path(source, dest).and_then(|_| {
+                    if src_meta.is_dir() {
+                        fs::remove_dir_all(source)
+                    } else {
+                        fs::remove_file(source)
+                    }
+                })
+            }
+            result => result,
+        },
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => molt_err!(
+            "error {} \"{}\" to \"{}\": {}",
+            how.verb(),
+            src_name,
+            dest_name,
+            posix_error(&e)
+        ),
+    }
+}
+
+/// Copies a file, symbolic link, or directory tree to a destination that doesn't exist.
+fn copy_path(source: &Path, dest: &Path) -> io::Result<()> {
+    let meta = fs::symlink_metadata(source)?;
+
+    if meta.is_dir() {
+        fs::create_dir(dest)?;
+
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+
+        Ok(())
+    } else if meta.file_type().is_symlink() {
+        copy_link(source, dest)
+    } else {
+        fs::copy(source, dest).map(|_| ())
+    }
+}
+
+/// Copies a symbolic link as a link.
+#[cfg(unix)]
+fn copy_link(source: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, dest)
+}
+
+/// Copies a symbolic link by copying the file it refers to.
+#[cfg(not(unix))]
+fn copy_link(source: &Path, dest: &Path) -> io::Result<()> {
+    fs::copy(source, dest).map(|_| ())
+}
+
+/// Gets the file's metadata, following symbolic links, or returns the standard error
+/// for a file that can't be read.
+fn metadata(name: &str) -> Result<Metadata, ResultCode> {
+    match fs::metadata(name) {
+        Ok(meta) => Ok(meta),
+        Err(e) => molt_err!("could not read \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// Converts a file time to seconds since the epoch.
+fn epoch_seconds(time: SystemTime) -> MoltInt {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as MoltInt,
+        Err(e) => -(e.duration().as_secs() as MoltInt),
+    }
+}
+
+/// Gets the TCL name for the file's type.
+#[cfg(unix)]
+fn file_type(meta: &Metadata) -> &'static str {
+    use std::os::unix::fs::FileTypeExt;
+
+    let ft = meta.file_type();
+
+    if ft.is_symlink() {
+        "link"
+    } else if ft.is_dir() {
+        "directory"
+    } else if ft.is_char_device() {
+        "characterSpecial"
+    } else if ft.is_block_device() {
+        "blockSpecial"
+    } else if ft.is_fifo() {
+        "fifo"
+    } else if ft.is_socket() {
+        "socket"
+    } else {
+        "file"
+    }
+}
+
+/// Gets the TCL name for the file's type.
+#[cfg(not(unix))]
+fn file_type(meta: &Metadata) -> &'static str {
+    let ft = meta.file_type();
+
+    if ft.is_symlink() {
+        "link"
+    } else if ft.is_dir() {
+        "directory"
+    } else {
+        "file"
+    }
+}
+
+/// Determines whether the current user has the given access to the file.
+#[cfg(unix)]
+fn access(path: &Path, mode: libc::c_int) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    match CString::new(path.as_os_str().as_bytes()) {
+        Ok(cpath) => unsafe { libc::access(cpath.as_ptr(), mode) == 0 },
+        Err(_) => false,
+    }
+}
+
+#[cfg(unix)]
+fn is_readable(path: &Path) -> bool {
+    access(path, libc::R_OK)
+}
+
+#[cfg(unix)]
+fn is_writable(path: &Path) -> bool {
+    access(path, libc::W_OK)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    access(path, libc::X_OK)
+}
+
+#[cfg(not(unix))]
+fn is_readable(path: &Path) -> bool {
+    path.exists()
+}
+
+#[cfg(not(unix))]
+fn is_writable(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(meta) => !meta.permissions().readonly(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    let exe = |ext: &std::ffi::OsStr| {
+        let ext = ext.to_string_lossy().to_lowercase();
+        ext == "exe" || ext == "com" || ext == "bat" || ext == "cmd"
+    };
+
+    path.is_dir() || path.extension().is_some_and(exe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A scratch directory in the system's temporary directory, deleted when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let name = format!(
+                "molt-file-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::SeqCst)
+            );
+            let path = std::env::temp_dir().join(name);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.0.join(name).to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_file_copy_rename_delete() {
+        let tmp = TempDir::new();
+        let mut interp = Interp::new();
+        let a = tmp.path("a.txt");
+        let b = tmp.path("b.txt");
+        let dir = tmp.path("x/y");
+        fs::write(&a, "hello").unwrap();
+
+        assert!(interp.eval(&format!("file mkdir {{{}}}", dir)).is_ok());
+        assert!(Path::new(&dir).is_dir());
+
+        assert!(interp.eval(&format!("file copy {{{}}} {{{}}}", a, b)).is_ok());
+        assert_eq!(fs::read_to_string(&b).unwrap(), "hello");
+
+        assert!(interp.eval(&format!("file rename {{{}}} {{{}}}", b, dir)).is_ok());
+        assert!(!Path::new(&b).exists());
+        assert!(Path::new(&dir).join("b.txt").is_file());
+
+        assert!(interp.eval(&format!("file copy {{{}}} {{{}}}", a, dir)).is_ok());
+        let result = interp.eval(&format!("file copy {{{}}} {{{}}}", a, dir));
+        assert_eq!(
+            result,
+            molt_err!(
+                "error copying \"{}\" to \"{}\": file already exists",
+                a,
+                Path::new(&dir).join("a.txt").display()
+            )
+        );
+
+        assert!(interp.eval(&format!("file delete -force {{{}}}", tmp.path("x"))).is_ok());
+        assert!(!Path::new(&tmp.path("x")).exists());
+    }
+
+    #[test]
+    fn test_safe_file() {
+        let mut interp = Interp::empty();
+        interp.add_command("file", cmd_safe_file);
+
+        assert_eq!(interp.eval("file join a b"), molt_ok!("a/b"));
+        assert_eq!(
+            interp.eval("file exists a"),
+            molt_err!(
+                "unknown or ambiguous subcommand \"exists\": must be dirname, extension, join, \
+                 nativename, normalize, rootname, split, or tail"
+            )
+        );
+    }
+
+    #[test]
+    fn test_split_path() {
+        assert_eq!(split_path("a/b"), ("".into(), vec!["a".into(), "b".into()]));
+        assert_eq!(split_path("\\\\srv\\share\\a"), ("//srv/share".into(), vec!["a".into()]));
+        assert_eq!(split_path("c:\\"), ("c:/".into(), vec![]));
+        assert_eq!(split_path("//"), ("/".into(), vec![]));
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("a*").unwrap(), vec!["a*"]);
+        assert_eq!(expand_braces("{a,b}.c").unwrap(), vec!["a.c", "b.c"]);
+        assert_eq!(expand_braces("x{a,{b,c}}{1,2}").unwrap(), vec!["xa1", "xa2", "xb1", "xb2", "xc1", "xc2"]);
+        assert_eq!(expand_braces("\\{a,b}").unwrap(), vec!["\\{a,b}"]);
+        assert!(expand_braces("{a,b").is_err());
+    }
+}
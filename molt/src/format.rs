@@ -0,0 +1,476 @@
+//! The Format Command's Formatter
+//!
+//! This module implements the formatting logic for the `format` command, which formats
+//! its arguments according to a format string in the style of the C `sprintf` function.
+
+use crate::*;
+
+//--------------------------------------------------------------------------
+// Field Specifiers
+
+/// A parsed field specifier: everything between the `%` and the conversion character.
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    zero: bool,
+    alt: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+//--------------------------------------------------------------------------
+// Formatting
+
+/// Formats the arguments according to the format string, returning the formatted string.
+///
+/// The format string may contain the conversions `%d`, `%i`, `%u`, `%o`, `%x`, `%X`,
+/// `%c`, `%s`, `%e`, `%E`, `%f`, `%g`, `%G`, and `%%`, with the usual flags, field
+/// widths, and precisions.  A width or precision of `*` is taken from the next argument.
+/// An XPG positional specifier, e.g., `%2$s`, takes the conversion's value from the
+/// given argument.
+pub(crate) fn format(fmt: &str, args: &[Value]) -> MoltResult {
+    let chars: Vec<char> = fmt.chars().collect();
+    let mut result = String::new();
+
+    // The index of the next argument to use, and whether positional specifiers are in use.
+    let mut next_arg = 0;
+    let mut xpg: Option<bool> = None;
+
+    let mut i = 0;
+
+    while i < chars.len() {
+        // FIRST, copy ordinary characters as is.
+        if chars[i] != '%' {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+
+        if i < chars.len() && chars[i] == '%' {
+            result.push('%');
+            i += 1;
+            continue;
+        }
+
+        // NEXT, look for an XPG positional specifier, which is a number followed by "$".
+        let digits = count_digits(&chars[i..]);
+        let is_xpg = digits > 0 && chars.get(i + digits) == Some(&'$');
+
+        if *xpg.get_or_insert(is_xpg) != is_xpg {
+            return molt_err!("cannot mix \"%\" and \"%n$\" conversion specifiers");
+        }
+
+        if is_xpg {
+            let n: usize = chars[i..i + digits].iter().collect::<String>().parse().unwrap_or(0);
+
+            if n == 0 || n > args.len() {
+                return molt_err!("\"%n$\" argument index out of range");
+            }
+
+            next_arg = n - 1;
+            i += digits + 1;
+        }
+
+        // NEXT, get the flags.
+        let mut spec = Spec::default();
+
+        while i < chars.len() {
+            match chars[i] {
+                '-' => spec.left = true,
+                '+' => spec.plus = true,
+                ' ' => spec.space = true,
+                '0' => spec.zero = true,
+                '#' => spec.alt = true,
+                _ => break,
+            }
+            i += 1;
+        }
+
+        // NEXT, get the field width.
+        if i < chars.len() && chars[i] == '*' {
+            let width = next_int(args, &mut next_arg)?;
+
+            if width < 0 {
+                spec.left = true;
+            }
+            spec.width = width.unsigned_abs() as usize;
+            i += 1;
+        } else {
+            let digits = count_digits(&chars[i..]);
+            spec.width = parse_digits(&chars[i..i + digits]);
+            i += digits;
+        }
+
+        // NEXT, get the precision.
+        if i < chars.len() && chars[i] == '.' {
+            i += 1;
+
+            if i < chars.len() && chars[i] == '*' {
+                spec.precision = Some(next_int(args, &mut next_arg)?.max(0) as usize);
+                i += 1;
+            } else {
+                let digits = count_digits(&chars[i..]);
+                spec.precision = Some(parse_digits(&chars[i..i + digits]));
+                i += digits;
+            }
+        }
+
+        // NEXT, skip any size modifiers; all integers are 64-bit.
+        while i < chars.len() && matches!(chars[i], 'h' | 'l' | 'L' | 'j' | 'z' | 't') {
+            i += 1;
+        }
+
+        // NEXT, get the conversion character and format the argument.
+        if i == chars.len() {
+            return molt_err!("format string ended in middle of field specifier");
+        }
+
+        let conv = chars[i];
+        i += 1;
+
+        if !"diuoxXcseEfgG".contains(conv) {
+            return molt_err!("bad field specifier \"{}\"", conv);
+        }
+
+        let arg = next_value(args, &mut next_arg)?;
+
+        let field = match conv {
+            'd' | 'i' => format_signed(&spec, arg.as_int()?),
+            'u' | 'o' | 'x' | 'X' => format_unsigned(&spec, conv, arg.as_int()? as u64),
+            'c' => match std::char::from_u32(arg.as_int()? as u32) {
+                Some(ch) => pad(&spec, String::new(), ch.to_string(), false),
+                None => return molt_err!("character code out of range: \"{}\"", arg),
+            },
+            's' => {
+                let string = arg.as_string();
+                let string = match spec.precision {
+                    Some(prec) => string.chars().take(prec).collect(),
+                    None => string.to_string(),
+                };
+                pad(&spec, String::new(), string, false)
+            }
+            _ => format_float(&spec, conv, arg.as_float()?),
+        };
+
+        result.push_str(&field);
+    }
+
+    molt_ok!(result)
+}
+
+/// Gets the next argument, or returns the standard error if there are none left.
+fn next_value<'a>(args: &'a [Value], next_arg: &mut usize) -> Result<&'a Value, ResultCode> {
+    match args.get(*next_arg) {
+        Some(arg) => {
+            *next_arg += 1;
+            Ok(arg)
+        }
+        None => molt_err!("not enough arguments for all format specifiers"),
+    }
+}
+
+/// Gets the next argument as an integer, for a `*` width or precision.
+fn next_int(args: &[Value], next_arg: &mut usize) -> Result<MoltInt, ResultCode> {
+    next_value(args, next_arg)?.as_int()
+}
+
+/// Counts the decimal digits at the head of the slice.
+fn count_digits(chars: &[char]) -> usize {
+    chars.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+/// Parses a slice of decimal digits; the empty slice is 0.
+fn parse_digits(chars: &[char]) -> usize {
+    chars.iter().fold(0, |n, c| n * 10 + c.to_digit(10).unwrap() as usize)
+}
+
+/// Pads a formatted field to the field width.  The prefix is the sign or radix prefix,
+/// which precedes any zero padding.  Zero padding is used only if `numeric`.
+fn pad(spec: &Spec, prefix: String, body: String, numeric: bool) -> String {
+    let len = prefix.chars().count() + body.chars().count();
+
+    if len >= spec.width {
+        return prefix + &body;
+    }
+
+    let fill = spec.width - len;
+
+    if spec.left {
+        prefix + &body + &" ".repeat(fill)
+    } else if spec.zero && numeric {
+        prefix + &"0".repeat(fill) + &body
+    } else {
+        " ".repeat(fill) + &prefix + &body
+    }
+}
+
+/// Returns the sign prefix for a number, given the flags.
+fn sign_prefix(spec: &Spec, negative: bool) -> String {
+    if negative {
+        "-".to_string()
+    } else if spec.plus {
+        "+".to_string()
+    } else if spec.space {
+        " ".to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Applies an integer precision, the minimum number of digits.
+fn apply_int_precision(spec: &Spec, digits: String) -> String {
+    match spec.precision {
+        // As in C, a zero value with a zero precision produces no digits.
+        Some(0) if digits == "0" => String::new(),
+        Some(prec) if prec > digits.len() => "0".repeat(prec - digits.len()) + &digits,
+        _ => digits,
+    }
+}
+
+/// Formats a signed decimal integer.
+fn format_signed(spec: &Spec, value: MoltInt) -> String {
+    let digits = apply_int_precision(spec, value.unsigned_abs().to_string());
+
+    // As in C, the zero flag is ignored when there's a precision.
+    let numeric = spec.precision.is_none();
+    pad(spec, sign_prefix(spec, value < 0), digits, numeric)
+}
+
+/// Formats an integer as unsigned decimal, octal, or hex.
+fn format_unsigned(spec: &Spec, conv: char, value: u64) -> String {
+    let digits = match conv {
+        'u' => value.to_string(),
+        'o' => format!("{:o}", value),
+        'x' => format!("{:x}", value),
+        _ => format!("{:X}", value),
+    };
+
+    let mut digits = apply_int_precision(spec, digits);
+    let mut prefix = String::new();
+
+    if spec.alt && value != 0 {
+        match conv {
+            'o' if !digits.starts_with('0') => digits.insert(0, '0'),
+            'x' => prefix.push_str("0x"),
+            'X' => prefix.push_str("0X"),
+            _ => (),
+        }
+    }
+
+    let numeric = spec.precision.is_none();
+    pad(spec, prefix, digits, numeric)
+}
+
+/// Formats a floating point number using `%e`, `%E`, `%f`, `%g`, or `%G`.
+fn format_float(spec: &Spec, conv: char, value: MoltFloat) -> String {
+    let prefix = sign_prefix(spec, value.is_sign_negative() && !value.is_nan());
+    let value = value.abs();
+
+    if value.is_infinite() {
+        return pad(spec, prefix, "Inf".to_string(), false);
+    } else if value.is_nan() {
+        return pad(spec, prefix, "NaN".to_string(), false);
+    }
+
+    let prec = spec.precision.unwrap_or(6);
+
+    let body = match conv {
+        'f' => fixed(value, prec, spec.alt),
+        'e' | 'E' => exponential(value, prec, spec.alt, conv == 'E'),
+        _ => general(value, prec, spec.alt, conv == 'G'),
+    };
+
+    pad(spec, prefix, body, true)
+}
+
+/// The most digits that Rust's formatter is asked for after the decimal point: more than
+/// there are in the exact decimal expansion of any `MoltFloat`, so that any further
+/// digits are zeros.
+const MAX_FLOAT_DIGITS: usize = 1100;
+
+/// Formats a non-negative number in fixed-point notation.
+fn fixed(value: MoltFloat, prec: usize, alt: bool) -> String {
+    let mut body = format!("{:.*}", prec.min(MAX_FLOAT_DIGITS), value);
+    zero_pad(&mut body, prec);
+
+    if alt && prec == 0 {
+        body.push('.');
+    }
+
+    body
+}
+
+/// Formats a non-negative number in exponential notation, C-style: the exponent has a
+/// sign and at least two digits.
+fn exponential(value: MoltFloat, prec: usize, alt: bool, upper: bool) -> String {
+    let (mantissa, exp) = split_exponential(value, prec);
+    let mut body = mantissa;
+
+    if alt && prec == 0 {
+        body.push('.');
+    }
+
+    let sign = if exp < 0 { '-' } else { '+' };
+    body.push_str(&format!("{}{}{:02}", if upper { 'E' } else { 'e' }, sign, exp.abs()));
+    body
+}
+
+/// Formats a non-negative number in exponential notation using Rust's formatter, and
+/// returns the mantissa and the exponent.
+fn split_exponential(value: MoltFloat, prec: usize) -> (String, i32) {
+    let formatted = format!("{:.*e}", prec.min(MAX_FLOAT_DIGITS), value);
+    let (mantissa, exp) = formatted.split_at(formatted.find('e').unwrap());
+    let mut mantissa = mantissa.to_string();
+    zero_pad(&mut mantissa, prec);
+    (mantissa, exp[1..].parse().unwrap())
+}
+
+/// Pads the digits formatted with at most `MAX_FLOAT_DIGITS` digits after the decimal
+/// point with zeros, to the given number of digits.
+fn zero_pad(digits: &mut String, prec: usize) {
+    if prec > MAX_FLOAT_DIGITS {
+        digits.push_str(&"0".repeat(prec - MAX_FLOAT_DIGITS));
+    }
+}
+
+/// Formats a non-negative number using `%g`: exponential notation if the exponent is
+/// small or large, and fixed-point notation otherwise, with trailing zeros removed unless
+/// `alt`.
+fn general(value: MoltFloat, prec: usize, alt: bool, upper: bool) -> String {
+    let prec = prec.max(1);
+    let (_, exp) = split_exponential(value, prec - 1);
+
+    let mut body = if exp < -4 || exp as i64 >= prec as i64 {
+        exponential(value, prec - 1, alt, upper)
+    } else {
+        fixed(value, (prec as i64 - 1 - exp as i64) as usize, alt)
+    };
+
+    if !alt && body.contains('.') {
+        // Remove trailing zeros from the mantissa, and the decimal point if need be.
+        let exp_start = body.find(['e', 'E']).unwrap_or(body.len());
+        let exp_part = body.split_off(exp_start);
+        let trimmed = body.trim_end_matches('0').trim_end_matches('.').len();
+        body.truncate(trimmed);
+        body.push_str(&exp_part);
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(fmt_str: &str, args: &[&str]) -> String {
+        let args: Vec<Value> = args.iter().map(|a| Value::from(*a)).collect();
+        format(fmt_str, &args).unwrap().to_string()
+    }
+
+    fn fmt_err(fmt_str: &str, args: &[&str]) -> MoltResult {
+        let args: Vec<Value> = args.iter().map(|a| Value::from(*a)).collect();
+        format(fmt_str, &args)
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(fmt("", &[]), "");
+        assert_eq!(fmt("abc", &[]), "abc");
+        assert_eq!(fmt("100%%", &[]), "100%");
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(fmt("%d", &["42"]), "42");
+        assert_eq!(fmt("%i", &["-42"]), "-42");
+        assert_eq!(fmt("%5d|%-5d|%05d", &["42", "42", "42"]), "   42|42   |00042");
+        assert_eq!(fmt("%+d %+d % d", &["5", "-5", "5"]), "+5 -5  5");
+        assert_eq!(fmt("%05d", &["-42"]), "-0042");
+        assert_eq!(fmt("%.3d|%6.3d|%06.3d", &["7", "7", "7"]), "007|   007|   007");
+        assert_eq!(fmt("%.0d|", &["0"]), "|");
+        assert_eq!(fmt("%ld %lld", &["1", "2"]), "1 2");
+    }
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!(fmt("%u", &["42"]), "42");
+        assert_eq!(fmt("%u", &["-1"]), "18446744073709551615");
+        assert_eq!(fmt("%o %#o %#o", &["8", "8", "0"]), "10 010 0");
+        assert_eq!(fmt("%x %X %#x %#X", &["255", "255", "255", "255"]), "ff FF 0xff 0XFF");
+        assert_eq!(fmt("%#06x|%08.4x", &["255", "255"]), "0x00ff|    00ff");
+    }
+
+    #[test]
+    fn test_chars_and_strings() {
+        assert_eq!(fmt("%c%c", &["65", "233"]), "Aé");
+        assert_eq!(fmt("%s|%5s|%-5s|", &["abc", "abc", "abc"]), "abc|  abc|abc  |");
+        assert_eq!(fmt("%.2s|%5.1s", &["abc", "abc"]), "ab|    a");
+        assert_eq!(fmt("%3s|", &["héé"]), "héé|");
+        assert_eq!(fmt("%4s|", &["héé"]), " héé|");
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(fmt("%f", &["1.5"]), "1.500000");
+        assert_eq!(fmt("%.2f|%8.3f|%-8.1f|%08.2f", &["3.14159", "3.14159", "2.5", "-2.5"]),
+            "3.14|   3.142|2.5     |-0002.50");
+        assert_eq!(fmt("%.0f|%#.0f", &["2.0", "2.0"]), "2|2.");
+        assert_eq!(fmt("%+.1f|% .1f", &["1", "1"]), "+1.0| 1.0");
+        assert_eq!(fmt("%e", &["12345.678"]), "1.234568e+04");
+        assert_eq!(fmt("%.2E", &["0.000123"]), "1.23E-04");
+        assert_eq!(fmt("%e", &["0"]), "0.000000e+00");
+        assert_eq!(fmt("%.1e", &["1e100"]), "1.0e+100");
+        assert_eq!(fmt("%g|%g|%g|%g", &["100000", "1000000", "0.0001", "0.00001"]),
+            "100000|1e+06|0.0001|1e-05");
+        assert_eq!(fmt("%g|%G|%.3g|%#g", &["1.5", "1e-10", "3.14159", "1.5"]),
+            "1.5|1E-10|3.14|1.50000");
+        assert_eq!(fmt("%g|%.0g", &["0", "123"]), "0|1e+02");
+        assert_eq!(fmt("%f %f %5.1f", &["Inf", "-Inf", "NaN"]), "Inf -Inf   NaN");
+        assert_eq!(fmt("%d", &["0x10"]), "16");
+    }
+
+    #[test]
+    fn test_star() {
+        assert_eq!(fmt("%*d|", &["5", "42"]), "   42|");
+        assert_eq!(fmt("%-*d|", &["5", "42"]), "42   |");
+        assert_eq!(fmt("%*d|", &["-5", "42"]), "42   |");
+        assert_eq!(fmt("%.*f", &["2", "3.14159"]), "3.14");
+        assert_eq!(fmt("%*.*s|", &["5", "2", "abc"]), "   ab|");
+    }
+
+    #[test]
+    fn test_xpg() {
+        assert_eq!(fmt("%2$s %1$s", &["a", "b"]), "b a");
+        assert_eq!(fmt("%1$s %1$s", &["a"]), "a a");
+        assert_eq!(fmt("%1$5d|", &["42"]), "   42|");
+        assert_eq!(fmt_err("%1$s %s", &["a", "b"]),
+            molt_err!("cannot mix \"%\" and \"%n$\" conversion specifiers"));
+        assert_eq!(fmt_err("%s %1$s", &["a", "b"]),
+            molt_err!("cannot mix \"%\" and \"%n$\" conversion specifiers"));
+        assert_eq!(fmt_err("%3$s", &["a", "b"]),
+            molt_err!("\"%n$\" argument index out of range"));
+        assert_eq!(fmt_err("%0$s", &["a"]),
+            molt_err!("\"%n$\" argument index out of range"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(fmt_err("%d %d", &["1"]),
+            molt_err!("not enough arguments for all format specifiers"));
+        assert_eq!(fmt_err("%*d", &["5"]),
+            molt_err!("not enough arguments for all format specifiers"));
+        assert_eq!(fmt_err("abc%", &[]),
+            molt_err!("format string ended in middle of field specifier"));
+        assert_eq!(fmt_err("%5", &[]),
+            molt_err!("format string ended in middle of field specifier"));
+        assert_eq!(fmt_err("%q", &["1"]), molt_err!("bad field specifier \"q\""));
+        assert_eq!(fmt_err("%y", &["1"]), molt_err!("bad field specifier \"y\""));
+        assert_eq!(fmt_err("%d", &["abc"]), molt_err!("expected integer but got \"abc\""));
+        assert_eq!(fmt_err("%f", &["abc"]),
+            molt_err!("expected floating-point number but got \"abc\""));
+    }
+}
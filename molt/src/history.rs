@@ -0,0 +1,189 @@
+//! Command History
+//!
+//! This module defines the interpreter's default store for its command history, as
+//! managed by the `history` command: a list of the most recent events, i.e., commands
+//! entered at the top level, trimmed to the `history keep` limit, along with the ids by
+//! which the events are numbered.
+
+use crate::types::HistoryStore;
+use std::collections::VecDeque;
+
+/// The number of events retained by default, as for standard TCL.
+const DEFAULT_KEEP: usize = 20;
+
+/// The interpreter's command history: the store of events, the maximum number of events
+/// it retains, and the id of the most recent event.  Events are numbered from 1 in the
+/// order in which they are added.
+pub(crate) struct History {
+    store: Box<dyn HistoryStore>,
+    keep: usize,
+    id: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            store: Box::new(EventList::new(DEFAULT_KEEP)),
+            keep: DEFAULT_KEEP,
+            id: 0,
+        }
+    }
+}
+
+impl History {
+    /// Replaces the store.  Any events already in it are numbered from 1, and it's trimmed
+    /// to the keep limit.
+    pub fn set_store(&mut self, mut store: Box<dyn HistoryStore>) {
+        store.set_max_len(self.keep);
+        self.id = store.len();
+        self.store = store;
+    }
+
+    /// Adds an event, if the store accepts it.
+    pub fn add(&mut self, event: &str) {
+        if self.store.add(event) {
+            self.id += 1;
+        }
+    }
+
+    /// Gets the event with the given id, if it's retained.
+    pub fn event(&self, id: usize) -> Option<String> {
+        if id < self.first_id() || id > self.id {
+            return None;
+        }
+
+        self.store.get(id - self.first_id())
+    }
+
+    /// Gets the id of the oldest event retained, or of the next event if there are none.
+    pub fn first_id(&self) -> usize {
+        self.id + 1 - self.store.len()
+    }
+
+    /// Gets the id of the most recent event, or 0 if there have been none.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Gets the maximum number of events retained.
+    pub fn keep(&self) -> usize {
+        self.keep
+    }
+
+    /// Sets the maximum number of events retained, dropping the oldest events if there are
+    /// more than that.
+    pub fn set_keep(&mut self, keep: usize) {
+        self.keep = keep;
+        self.store.set_max_len(keep);
+    }
+
+    /// Drops all of the events; the next event's id will be 1.
+    pub fn clear(&mut self) {
+        self.store.clear();
+        self.id = 0;
+    }
+}
+
+/// The interpreter's default history store.
+struct EventList {
+    events: VecDeque<String>,
+    max_len: usize,
+}
+
+impl EventList {
+    /// Creates an empty event list that retains at most the given number of events.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            max_len,
+        }
+    }
+}
+
+impl HistoryStore for EventList {
+    fn add(&mut self, event: &str) -> bool {
+        if self.max_len == 0 {
+            return false;
+        }
+
+        if self.events.len() == self.max_len {
+            self.events.pop_front();
+        }
+
+        self.events.push_back(event.to_string());
+        true
+    }
+
+    fn get(&self, index: usize) -> Option<String> {
+        self.events.get(index).cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    fn set_max_len(&mut self, len: usize) {
+        self.max_len = len;
+
+        while self.events.len() > len {
+            self.events.pop_front();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_list() {
+        let mut list = EventList::new(2);
+        assert!(list.is_empty());
+        assert!(list.add("a"));
+        assert!(list.add("b"));
+        assert!(list.add("c"));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.get(0), Some("b".to_string()));
+        assert_eq!(list.get(1), Some("c".to_string()));
+        assert_eq!(list.get(2), None);
+
+        list.set_max_len(1);
+        assert_eq!(list.get(0), Some("c".to_string()));
+
+        list.set_max_len(0);
+        assert!(list.is_empty());
+        assert!(!list.add("d"));
+
+        list.set_max_len(5);
+        list.add("e");
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::default();
+        assert_eq!(history.id(), 0);
+        assert_eq!(history.first_id(), 1);
+
+        history.set_keep(2);
+        history.add("a");
+        history.add("b");
+        history.add("c");
+        assert_eq!(history.id(), 3);
+        assert_eq!(history.first_id(), 2);
+        assert_eq!(history.event(1), None);
+        assert_eq!(history.event(2), Some("b".to_string()));
+        assert_eq!(history.event(3), Some("c".to_string()));
+        assert_eq!(history.event(4), None);
+
+        history.clear();
+        assert_eq!(history.id(), 0);
+        history.add("d");
+        assert_eq!(history.event(1), Some("d".to_string()));
+    }
+}
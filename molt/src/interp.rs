@@ -5,16 +5,160 @@
 //! [`Interp`]: struct.Interp.html
 
 use crate::list;
+use crate::channel::Channel;
+use crate::channel::ChannelTable;
 use crate::commands;
 use crate::context::Context;
+use crate::coroutine::CallStack;
+use crate::coroutine::Coroutine;
+use crate::coroutine::Outcome;
+use crate::coroutine::Resume;
+use crate::event::Event;
+use crate::event::EventQueue;
+use crate::file;
+use crate::history::History;
+use crate::process;
+use crate::socket;
 use crate::molt_ok;
 use crate::molt_err;
+use crate::package;
+use crate::package::Loader;
+use crate::package::PackageTable;
 use crate::scope::ScopeStack;
+use crate::scope::TraceOp;
+use crate::scope::VarTrace;
 use crate::types::Command;
 use crate::types::*;
 use crate::value::Value;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Write;
 use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// The standard commands, which are safe for untrusted scripts: they can't access the file
+/// system, run programs, open network connections, or exit the process.
+const STANDARD_COMMANDS: [(&str, CommandFunc); 78] = [
+    ("after", commands::cmd_after),
+    ("append", commands::cmd_append),
+    ("apply", commands::cmd_apply),
+    ("array", commands::cmd_array),
+    ("assert_eq", commands::cmd_assert_eq),
+    ("binary", commands::cmd_binary),
+    ("break", commands::cmd_break),
+    ("catch", commands::cmd_catch),
+    ("clock", commands::cmd_clock),
+    ("close", commands::cmd_close),
+    ("concat", commands::cmd_concat),
+    ("continue", commands::cmd_continue),
+    ("coroutine", commands::cmd_coroutine),
+    ("dict", commands::cmd_dict),
+    ("encoding", commands::cmd_encoding),
+    ("eof", commands::cmd_eof),
+    ("error", commands::cmd_error),
+    ("expr", commands::cmd_expr),
+    ("fconfigure", commands::cmd_fconfigure),
+    ("file", file::cmd_safe_file),
+    ("fileevent", commands::cmd_fileevent),
+    ("flush", commands::cmd_flush),
+    ("for", commands::cmd_for),
+    ("foreach", commands::cmd_foreach),
+    ("format", commands::cmd_format),
+    ("gets", commands::cmd_gets),
+    ("global", commands::cmd_global),
+    ("history", commands::cmd_history),
+    ("if", commands::cmd_if),
+    ("incr", commands::cmd_incr),
+    ("info", commands::cmd_info),
+    ("interp", commands::cmd_interp),
+    ("join", commands::cmd_join),
+    ("lappend", commands::cmd_lappend),
+    ("lassign", commands::cmd_lassign),
+    ("lindex", commands::cmd_lindex),
+    ("linsert", commands::cmd_linsert),
+    ("list", commands::cmd_list),
+    ("llength", commands::cmd_llength),
+    ("lmap", commands::cmd_lmap),
+    ("lrange", commands::cmd_lrange),
+    ("lrepeat", commands::cmd_lrepeat),
+    ("lreplace", commands::cmd_lreplace),
+    ("lreverse", commands::cmd_lreverse),
+    ("lsearch", commands::cmd_lsearch),
+    ("lset", commands::cmd_lset),
+    ("lsort", commands::cmd_lsort),
+    ("namespace", commands::cmd_namespace),
+    ("package", commands::cmd_package),
+    ("pid", commands::cmd_pid),
+    ("proc", commands::cmd_proc),
+    ("puts", commands::cmd_puts),
+    ("read", commands::cmd_read),
+    ("regexp", commands::cmd_regexp),
+    ("regsub", commands::cmd_regsub),
+    ("rename", commands::cmd_rename),
+    ("return", commands::cmd_return),
+    ("scan", commands::cmd_scan),
+    ("seek", commands::cmd_seek),
+    ("set", commands::cmd_set),
+    ("split", commands::cmd_split),
+    ("string", commands::cmd_string),
+    ("subst", commands::cmd_subst),
+    ("switch", commands::cmd_switch),
+    ("tailcall", commands::cmd_tailcall),
+    ("tell", commands::cmd_tell),
+    ("throw", commands::cmd_throw),
+    ("time", commands::cmd_time),
+    ("trace", commands::cmd_trace),
+    ("try", commands::cmd_try),
+    ("unset", commands::cmd_unset),
+    ("update", commands::cmd_update),
+    ("uplevel", commands::cmd_uplevel),
+    ("upvar", commands::cmd_upvar),
+    ("variable", commands::cmd_variable),
+    ("vwait", commands::cmd_vwait),
+    ("while", commands::cmd_while),
+    ("yield", commands::cmd_yield),
+];
+
+/// The commands that query and manipulate the file system.
+const FILESYSTEM_COMMANDS: [(&str, CommandFunc); 6] = [
+    ("cd", file::cmd_cd),
+    ("file", file::cmd_file),
+    ("glob", file::cmd_glob),
+    ("open", file::cmd_open),
+    ("pwd", file::cmd_pwd),
+    ("source", file::cmd_source),
+];
+
+/// The commands that run other programs and exit the process.
+const PROCESS_COMMANDS: [(&str, CommandFunc); 2] = [
+    ("exec", process::cmd_exec),
+    ("exit", commands::cmd_exit),
+];
+
+/// The commands that open network connections.
+const SOCKET_COMMANDS: [(&str, CommandFunc); 1] = [("socket", socket::cmd_socket)];
+
+/// The groups of commands that aren't safe for untrusted scripts.  Safe interpreters have
+/// them only as hidden commands.
+const UNSAFE_COMMANDS: [&[(&str, CommandFunc)]; 3] =
+    [&FILESYSTEM_COMMANDS, &PROCESS_COMMANDS, &SOCKET_COMMANDS];
+
+/// The constants of the pseudo-random number generator used by the `rand()` math function.
+const RAND_IA: MoltInt = 16807;
+const RAND_IM: MoltInt = 2_147_483_647;
+const RAND_IQ: MoltInt = 127_773;
+const RAND_IR: MoltInt = 2836;
+const RAND_MASK: MoltInt = 123_459_876;
 
 /// The Molt Interpreter.
 ///
@@ -44,17 +188,87 @@ use std::rc::Rc;
 #[derive(Default)]
 #[allow(dead_code)] // TEMP
 pub struct Interp {
-    // Command Table
-    commands: HashMap<String, Rc<dyn Command>>,
+    // Command Table.  Commands in the global namespace are keyed by their simple names;
+    // commands in other namespaces are keyed by their qualified names, less the leading
+    // "::", e.g., "foo::bar".
+    commands: HashMap<String, CommandEntry>,
+
+    // The hidden commands, by name, which scripts can't call directly.
+    hidden: HashMap<String, CommandEntry>,
+
+    // The fully qualified names of the namespaces other than the global namespace.
+    namespaces: HashSet<String>,
+
+    // The namespace contexts pushed by `namespace eval` and by procs, most recent last.
+    ns_stack: Vec<NsContext>,
 
     // Variable Table
     scopes: ScopeStack,
 
+    // The commands that pushed the scopes above the global scope, e.g., the argument
+    // lists of the procs currently being executed.
+    scope_argvs: Vec<MoltList>,
+
+    // The name of the script file currently being evaluated, as reported by `info script`.
+    script_file: String,
+
     // Defines the recursion limit for Interp::eval().
     recursion_limit: usize,
 
     // Current number of eval levels.
     num_levels: usize,
+
+    // Total number of commands executed since the interpreter was created.
+    cmd_count: u64,
+
+    // The scripts scheduled by `after`, pending evaluation by the event loop.
+    events: EventQueue,
+
+    // Set from outside the interpreter to interrupt waiting for events.
+    interrupted: Arc<AtomicBool>,
+
+    // The open channels, by name, e.g., "stdout".
+    channels: ChannelTable,
+
+    // The packages that are provided or available.
+    packages: PackageTable,
+
+    // The command history.
+    history: History,
+
+    // The slave interpreters, by name.
+    // They are boxed, so that their coroutines' threads can refer to them by address.
+    slaves: HashMap<String, Box<Interp>>,
+
+    // Whether this is a safe interpreter, as created by `Interp::new_safe`.
+    safe: bool,
+
+    // Whether the interpreter may run programs: whether `Interp::add_process_commands` has
+    // added its commands, so that `open` can open command pipelines.
+    runs_programs: bool,
+
+    // The function to call when a command isn't found, instead of the `unknown` command.
+    unknown_handler: Option<CommandFunc>,
+
+    // The number of calls to the unknown handler or command in progress.
+    unknown_depth: usize,
+
+    // The variables whose traces are being called, by scope level and name, so that a
+    // trace that accesses its own variable doesn't call itself again.
+    active_var_traces: Vec<(usize, String)>,
+
+    // The execution traces on commands, most recent first, by command table key.
+    exec_traces: HashMap<String, Vec<ExecTrace>>,
+
+    // The commands whose execution traces are being called.
+    active_exec_traces: Vec<String>,
+
+    // The coroutines that are running, most recent last.
+    coroutines: Vec<Rc<Coroutine>>,
+
+    // The state of the pseudo-random number generator used by the `rand()` math function,
+    // once it's been seeded.
+    rand_seed: Option<MoltInt>,
 }
 
 impl Interp {
@@ -68,49 +282,124 @@ impl Interp {
         Self {
             recursion_limit: 1000,
             commands: HashMap::new(),
+            hidden: HashMap::new(),
+            namespaces: HashSet::new(),
+            ns_stack: Vec::new(),
             scopes: ScopeStack::new(),
+            scope_argvs: Vec::new(),
+            script_file: String::new(),
             num_levels: 0,
+            cmd_count: 0,
+            events: EventQueue::new(),
+            interrupted: Arc::new(AtomicBool::new(false)),
+            channels: ChannelTable::new(),
+            packages: PackageTable::new(),
+            history: History::default(),
+            slaves: HashMap::new(),
+            safe: false,
+            runs_programs: false,
+            unknown_handler: None,
+            unknown_depth: 0,
+            active_var_traces: Vec::new(),
+            exec_traces: HashMap::new(),
+            active_exec_traces: Vec::new(),
+            coroutines: Vec::new(),
+            rand_seed: None,
         }
     }
 
-    /// Creates a new Molt interpreter, pre-populated with the standard Molt commands.
-    /// Use `info commands` to retrieve the full list.
-    /// TODO: Define command sets (sets of commands that go together, so that clients can
-    /// add or remove them in groups).
+    /// Creates a new Molt interpreter, pre-populated with the standard Molt commands and
+    /// the commands that access the file system, run programs, and open network
+    /// connections, and with the `env` array.  Use `info commands` to retrieve the full
+    /// list.
     pub fn new() -> Self {
         let mut interp = Interp::empty();
 
-        interp.add_command("append", commands::cmd_append);
-        interp.add_command("assert_eq", commands::cmd_assert_eq);
-        interp.add_command("break", commands::cmd_break);
-        interp.add_command("catch", commands::cmd_catch);
-        interp.add_command("continue", commands::cmd_continue);
-        interp.add_command("error", commands::cmd_error);
-        interp.add_command("exit", commands::cmd_exit);
-        interp.add_command("expr", commands::cmd_expr);
-        interp.add_command("for", commands::cmd_for);
-        interp.add_command("foreach", commands::cmd_foreach);
-        interp.add_command("global", commands::cmd_global);
-        interp.add_command("if", commands::cmd_if);
-        interp.add_command("incr", commands::cmd_incr);
-        interp.add_command("info", commands::cmd_info);
-        interp.add_command("join", commands::cmd_join);
-        interp.add_command("lappend", commands::cmd_lappend);
-        interp.add_command("lindex", commands::cmd_lindex);
-        interp.add_command("list", commands::cmd_list);
-        interp.add_command("llength", commands::cmd_llength);
-        interp.add_command("proc", commands::cmd_proc);
-        interp.add_command("puts", commands::cmd_puts);
-        interp.add_command("rename", commands::cmd_rename);
-        interp.add_command("return", commands::cmd_return);
-        interp.add_command("set", commands::cmd_set);
-        interp.add_command("source", commands::cmd_source);
-        interp.add_command("time", commands::cmd_time);
-        interp.add_command("unset", commands::cmd_unset);
-        interp.add_command("while", commands::cmd_while);
+        interp.add_standard_commands();
+        interp.add_filesystem_commands();
+        interp.add_process_commands();
+        interp.add_socket_commands();
+        interp.add_env_array();
+        interp
+    }
+
+    /// Creates a new safe interpreter, for evaluating untrusted scripts.  It has the
+    /// standard Molt commands, but the commands that access the file system, run programs,
+    /// open network connections, or exit the process are hidden, and it has no `env` array.
+    /// Its `file` command can only manipulate path names.  The application can expose or
+    /// invoke the hidden commands; see `Interp::expose_command` and `Interp::invoke_hidden`.
+    /// The slaves of a safe interpreter are safe as well.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    ///
+    /// let mut interp = Interp::new_safe();
+    /// assert!(interp.is_safe());
+    /// assert_eq!(interp.eval("llength {a b c}"), Ok(Value::from(3)));
+    /// assert!(interp.eval("open config.txt").is_err());
+    /// ```
+    pub fn new_safe() -> Self {
+        let mut interp = Interp::empty();
+
+        interp.add_standard_commands();
+
+        for (name, func) in UNSAFE_COMMANDS.iter().flat_map(|group| group.iter()) {
+            let command = Rc::new(CommandFuncWrapper::new(*func));
+            interp.hidden.insert(name.to_string(), CommandEntry::Native(command));
+        }
+
+        interp.safe = true;
         interp
     }
 
+    /// Adds the standard Molt commands, which are safe for untrusted scripts.  Their `file`
+    /// command can only manipulate path names.  `Interp::new` and `Interp::new_safe` add
+    /// them; an interpreter built up from `Interp::empty` need not.
+    pub fn add_standard_commands(&mut self) {
+        self.add_commands(&STANDARD_COMMANDS);
+    }
+
+    /// Adds the commands that query and manipulate the file system, e.g., `cd`, `file`,
+    /// `glob`, and `source`, replacing the standard `file` command, which can only
+    /// manipulate path names.  `Interp::new` adds them along with the standard commands;
+    /// an interpreter that should not have access to the file system can be built up from
+    /// `Interp::empty` without them.
+    pub fn add_filesystem_commands(&mut self) {
+        self.add_commands(&FILESYSTEM_COMMANDS);
+    }
+
+    /// Adds the commands that run other programs and exit the process, i.e., `exec` and
+    /// `exit`, and lets `open` open command pipelines.  `Interp::new` adds them along with
+    /// the standard commands; an interpreter that should not be able to run programs can be
+    /// built up from `Interp::empty` without them.
+    pub fn add_process_commands(&mut self) {
+        self.add_commands(&PROCESS_COMMANDS);
+        self.runs_programs = true;
+    }
+
+    /// Adds the commands that open network connections, i.e., `socket`.  `Interp::new` adds
+    /// them along with the standard commands.
+    pub fn add_socket_commands(&mut self) {
+        self.add_commands(&SOCKET_COMMANDS);
+    }
+
+    /// Adds a group of commands.
+    fn add_commands(&mut self, group: &[(&str, CommandFunc)]) {
+        for (name, func) in group {
+            self.add_command(name, *func);
+        }
+    }
+
+    /// Adds the global array `env`, which holds the process's environment variables.
+    /// Setting or unsetting an element of `env` sets or removes the environment variable,
+    /// so that programs run by `exec` see the change.  `Interp::new` adds it; an
+    /// interpreter built up from `Interp::empty` without it can't see the environment.
+    pub fn add_env_array(&mut self) {
+        self.scopes.link_env();
+    }
+
     //--------------------------------------------------------------------------------------------
     // Interpreter Configuration
 
@@ -141,6 +430,36 @@ impl Interp {
         self.recursion_limit = limit;
     }
 
+    /// Gets the total number of commands the interpreter has executed since it was created,
+    /// including commands executed within command substitutions and proc bodies.  This
+    /// is the value returned by `info cmdcount`.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::types::*;
+    /// # use molt::interp::Interp;
+    /// let mut interp = Interp::new();
+    /// let count = interp.cmd_count();
+    /// interp.eval("set a [expr {1 + 1}]").unwrap();
+    /// assert_eq!(interp.cmd_count(), count + 2);
+    /// ```
+    pub fn cmd_count(&self) -> u64 {
+        self.cmd_count
+    }
+
+    /// Gets the name of the script file currently being evaluated, or the empty string
+    /// if none.  This is the value returned by `info script`.
+    pub fn script_file(&self) -> &str {
+        &self.script_file
+    }
+
+    /// Sets the name of the script file currently being evaluated.  Commands and
+    /// applications that evaluate script files, e.g., `source`, should set this before
+    /// evaluating the file and restore the previous name afterwards.
+    pub fn set_script_file(&mut self, name: &str) {
+        self.script_file = name.to_string();
+    }
+
     //--------------------------------------------------------------------------------------------
     // Command Definition and Handling
 
@@ -158,197 +477,2123 @@ impl Interp {
     ///
     /// This is how to add a Molt `proc` to the interpreter.  The arguments are the same
     /// as for the `proc` command and the `commands::cmd_proc` function.
-    pub(crate) fn add_proc(&mut self, name: &str, args: &[Value], body: &str) {
+    ///
+    /// The proc is defined in the current namespace unless the name is qualified; it's an
+    /// error if the proc's namespace doesn't exist.
+    pub(crate) fn add_proc(&mut self, name: &str, args: &[Value], body: &str) -> MoltResult {
+        let key = match self.definition_key(name) {
+            Some(key) => key,
+            None => return molt_err!("can't create procedure \"{}\": unknown namespace", name),
+        };
+
         let command = Rc::new(CommandProc {
             args: args.to_owned(),
             body: body.to_string(),
+            namespace: namespace_of(&key).into(),
         });
 
-        self.add_command_object(name, command);
+        self.exec_traces.remove(&key);
+        if let Some(old) = self.commands.insert(key, CommandEntry::Proc(command)) {
+            self.discard_command(old);
+        }
+        molt_ok!()
+    }
+
+    /// Applies a lambda expression to arguments, as the `apply` command: `argv[1]` is the
+    /// lambda expression, and `argv[2..]` are its arguments.
+    pub(crate) fn apply(&mut self, argv: &[Value]) -> MoltResult {
+        let lambda = match argv[1].as_other::<Lambda>() {
+            Some(lambda) => lambda,
+            None => return Lambda::parse(&argv[1].as_string()).map(|_| Value::empty()),
+        };
+
+        if !self.namespace_exists(&lambda.0.namespace) {
+            return molt_err!("namespace \"{}\" not found", lambda.0.namespace);
+        }
+
+        lambda.0.call(self, argv, CallKind::Lambda)
     }
 
     /// Adds a command to the interpreter using a `Command` trait object.
     ///
     /// Use this when defining a command that requires application context.
     pub fn add_command_object(&mut self, name: &str, command: Rc<dyn Command>) {
-        self.commands.insert(name.into(), command);
+        let key = name.strip_prefix("::").unwrap_or(name);
+        self.exec_traces.remove(key);
+        if let Some(old) = self.commands.insert(key.into(), CommandEntry::Native(command)) {
+            self.discard_command(old);
+        }
+    }
+
+    /// Adds an alias: a command that invokes the target command prefix with the alias's
+    /// own arguments appended, as created by `interp alias`.  The target command is looked
+    /// up each time the alias is called, so a later redefinition of it is honored.  The
+    /// alias is defined in the current namespace unless the name is qualified; it's an error
+    /// if its namespace doesn't exist, or if the target is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.add_alias("shout", &[Value::from("string"), Value::from("toupper")]).unwrap();
+    /// assert_eq!(interp.eval("shout hello"), Ok(Value::from("HELLO")));
+    /// ```
+    pub fn add_alias(&mut self, name: &str, target: &[Value]) -> MoltResult {
+        let key = match self.definition_key(name) {
+            Some(key) => key,
+            None => return molt_err!("can't create alias \"{}\": unknown namespace", name),
+        };
+
+        if target.is_empty() {
+            return molt_err!("can't create alias \"{}\": empty target command", name);
+        }
+
+        self.exec_traces.remove(&key);
+        if let Some(old) = self.commands.insert(key, CommandEntry::Alias(Rc::new(target.to_vec()))) {
+            self.discard_command(old);
+        }
+        molt_ok!()
+    }
+
+    /// Gets the target command prefix of the named alias, or None if the command isn't
+    /// an alias.
+    pub fn alias_target(&self, name: &str) -> Option<MoltList> {
+        match self.find_command(name) {
+            Some(CommandEntry::Alias(target)) => Some(target.to_vec()),
+            _ => None,
+        }
+    }
+
+    /// Gets a vector of the names of the existing aliases in the global namespace.
+    pub fn alias_names(&self) -> MoltList {
+        self.commands
+            .iter()
+            .filter(|(name, cmd)| matches!(cmd, CommandEntry::Alias(_)) && !name.contains("::"))
+            .map(|(name, _)| Value::from(name))
+            .collect()
+    }
+
+    /// Calls an alias: invokes its target with the alias's arguments appended.  An alias
+    /// counts as a nesting level, so that an alias to itself is caught by the recursion
+    /// limit.
+    fn call_alias(&mut self, target: &[Value], argv: &[Value]) -> MoltResult {
+        let mut cmd = target.to_vec();
+        cmd.extend_from_slice(&argv[1..]);
+
+        self.num_levels += 1;
+
+        let result = if self.num_levels > self.recursion_limit {
+            molt_err!("too many nested calls to Interp::eval (infinite loop?)")
+        } else {
+            self.invoke(&cmd)
+        };
+
+        self.num_levels -= 1;
+        result
+    }
+
+    /// Gets the body of the named proc.
+    pub fn proc_body(&self, name: &str) -> MoltResult {
+        molt_ok!(self.get_proc(name)?.body.as_str())
+    }
+
+    /// Gets the list of the argument names of the named proc.
+    pub fn proc_args(&self, name: &str) -> MoltResult {
+        let mut names = MoltList::new();
+
+        for spec in &self.get_proc(name)?.args {
+            names.push(spec.as_list()?[0].clone());
+        }
+
+        molt_ok!(names)
+    }
+
+    /// Gets the default value of the named argument of the named proc, or None if the
+    /// argument has no default value.
+    pub fn proc_default(&self, name: &str, arg: &str) -> Result<Option<Value>, ResultCode> {
+        for spec in &self.get_proc(name)?.args {
+            let vec = spec.as_list()?;
+
+            if *vec[0].as_string() == arg {
+                return Ok(vec.get(1).cloned());
+            }
+        }
+
+        molt_err!("procedure \"{}\" doesn't have an argument \"{}\"", name, arg)
+    }
+
+    /// Gets the definition of the named proc.
+    fn get_proc(&self, name: &str) -> Result<Rc<CommandProc>, ResultCode> {
+        match self.find_command(name) {
+            Some(CommandEntry::Proc(cmd)) => Ok(Rc::clone(cmd)),
+            _ => molt_err!("\"{}\" isn't a procedure", name),
+        }
     }
 
     /// Determines whether the interpreter contains a command with the given
     /// name.
     pub fn has_command(&self, name: &str) -> bool {
-        self.commands.contains_key(name)
+        self.command_key(name).is_some()
     }
 
-    /// Renames the command.
+    /// Renames the command.  The new name is relative to the current namespace; it's an
+    /// error if its namespace doesn't exist, or if a command with the new name already
+    /// exists.
     ///
     /// **Note:** This does not update procedures that reference the command under the old
     /// name.  This is intentional: it is a common TCL programming technique to wrap an
     /// existing command by renaming it and defining a new command with the old name that
     /// calls the original command at its new name.
-    pub fn rename_command(&mut self, old_name: &str, new_name: &str) {
-        if let Some(cmd) = self.commands.get(old_name) {
-            let cmd = Rc::clone(cmd);
-            self.commands.remove(old_name);
-            self.commands.insert(new_name.into(), cmd);
+    pub fn rename_command(&mut self, old_name: &str, new_name: &str) -> MoltResult {
+        let new_key = match self.definition_key(new_name) {
+            Some(key) => key,
+            None => return molt_err!("can't rename to \"{}\": unknown namespace", new_name),
+        };
+
+        if self.commands.contains_key(&new_key) {
+            return molt_err!("can't rename to \"{}\": command already exists", new_name);
+        }
+
+        if let Some(old_key) = self.command_key(old_name).map(Cow::into_owned) {
+            if let Some(cmd) = self.commands.remove(&old_key) {
+                if let Some(traces) = self.exec_traces.remove(&old_key) {
+                    self.exec_traces.insert(new_key.clone(), traces);
+                }
+                self.commands.insert(new_key, cmd);
+            }
         }
+
+        molt_ok!()
     }
 
-    /// Removes the command with the given name.
+    /// Removes the command with the given name, along with its execution traces.  If the
+    /// command is a coroutine, the coroutine is run to completion, with `yield` returning
+    /// an error.
     pub fn remove_command(&mut self, name: &str) {
-        self.commands.remove(name);
+        if let Some(key) = self.command_key(name).map(Cow::into_owned) {
+            self.exec_traces.remove(&key);
+
+            if let Some(cmd) = self.commands.remove(&key) {
+                self.discard_command(cmd);
+            }
+        }
     }
 
-    /// Gets a vector of the names of the existing commands.
-    ///
-    pub fn command_names(&self) -> MoltList {
-        let vec: MoltList = self.commands.keys().cloned().map(|x| Value::from(&x)).collect();
+    /// Adds an execution trace to the named command, as for `trace add execution`: the
+    /// command prefix will be called before the command executes with the command, as a
+    /// list of its words, and `enter`, and after it executes with the command, the result
+    /// code, the result, and `leave`.  The trace stays with the command if it's renamed,
+    /// and goes away with it if it's deleted.  It's an error if there's no such command.
+    pub(crate) fn add_exec_trace(&mut self, name: &str, ops: Vec<ExecOp>, command: Value) -> MoltResult {
+        let key = self.traced_command_key(name)?;
+        self.exec_traces.entry(key).or_default().insert(0, ExecTrace { ops, command });
+        molt_ok!()
+    }
+
+    /// Removes the most recent execution trace with the given operations and command
+    /// prefix from the named command, if there is one.
+    pub(crate) fn remove_exec_trace(&mut self, name: &str, ops: &[ExecOp], command: &str) -> MoltResult {
+        let key = self.traced_command_key(name)?;
+
+        if let Some(traces) = self.exec_traces.get_mut(&key) {
+            if let Some(i) = traces.iter().position(|trace| trace.matches(ops, command)) {
+                traces.remove(i);
+            }
+
+            if traces.is_empty() {
+                self.exec_traces.remove(&key);
+            }
+        }
 
-        vec
+        molt_ok!()
     }
 
-    //--------------------------------------------------------------------------------------------
-    // Variable Handling
+    /// Gets the execution traces on the named command, most recent first, as for `trace
+    /// info execution`.
+    pub(crate) fn exec_trace_info(&self, name: &str) -> Result<Vec<ExecTrace>, ResultCode> {
+        let key = self.traced_command_key(name)?;
+        Ok(self.exec_traces.get(&key).cloned().unwrap_or_default())
+    }
 
-    /// Retrieves the value of the named variable in the current scope, if any.
-    /// TODO: Somehow this converts the MoltValue from the variable into a string automatically.
-    /// Maybe it's molt_ok! doing that?
-    pub fn var(&self, name: &str) -> MoltResult {
-        match self.scopes.get(name) {
-            Some(v) => molt_ok!(v.clone()),
-            None => molt_err!("can't read \"{}\": no such variable", name),
+    /// Gets the command table key of the named command to be traced, or the usual error.
+    fn traced_command_key(&self, name: &str) -> Result<String, ResultCode> {
+        match self.command_key(name) {
+            Some(key) => Ok(key.into_owned()),
+            None => molt_err!("unknown command \"{}\"", name),
         }
     }
 
-    /// Sets the value of the named variable in the current scope, creating the variable
-    /// if necessary.
-    pub fn set_var(&mut self, name: &str, value: &str) {
-        // TODO: Temporary fix while integrating MoltValue.
-        self.scopes.set(name, Value::from(value));
+    /// Determines whether the named command has execution traces that aren't already being
+    /// called.
+    fn is_exec_traced(&self, name: &str) -> bool {
+        if self.exec_traces.is_empty() {
+            return false;
+        }
+
+        match self.command_key(name) {
+            Some(key) => {
+                self.exec_traces.contains_key(&*key)
+                    && !self.active_exec_traces.iter().any(|active| *active == key)
+            }
+            None => false,
+        }
     }
 
-    /// Sets the value of the named variable in the current scope, creating the variable
-    /// if necessary.
-    ///
-    /// TODO: Ultimately, this should replace set_var.
-    pub fn set_var2(&mut self, name: &str, value: Value) -> Value {
-        self.scopes.set(name, value.clone());
+    /// Executes a command with execution traces, calling its enter traces, most recent
+    /// first, and then its leave traces, least recent first.  While they are being called,
+    /// the command's traces are disabled.  An error in an enter trace is returned without
+    /// executing the command; an error in a leave trace replaces the command's result.
+    fn execute_traced(&mut self, cmd: &CommandEntry, argv: &[Value]) -> MoltResult {
+        let key = self.traced_command_key(&argv[0].as_string())?;
+        let traces = self.exec_traces.get(&key).cloned().unwrap_or_default();
+        let command = Value::from(argv.to_vec());
+
+        for trace in traces.iter().filter(|trace| trace.ops.contains(&ExecOp::Enter)) {
+            self.call_exec_trace(&key, trace, &[command.clone(), Value::from("enter")])?;
+        }
+
+        let result = cmd.execute(self, argv);
+        let (code, value) = commands::caught_result(&result);
+
+        for trace in traces.iter().rev().filter(|trace| trace.ops.contains(&ExecOp::Leave)) {
+            let args = [command.clone(), Value::from(code), value.clone(), Value::from("leave")];
+            self.call_exec_trace(&key, trace, &args)?;
+        }
 
-        value
+        result
     }
 
-    /// Sets the value of the named variable in the current scope, creating the variable
-    /// if necessary.
-    pub fn set_var3(&mut self, name: &str, value: &Value) {
-        self.scopes.set(name, value.clone());
+    /// Calls an execution trace on the command with the given key, with its traces
+    /// disabled, returning any error.
+    fn call_exec_trace(&mut self, key: &str, trace: &ExecTrace, args: &[Value]) -> Result<(), ResultCode> {
+        let mut cmd = trace.command.as_list()?.to_vec();
+
+        if cmd.is_empty() {
+            return Ok(());
+        }
+
+        cmd.extend_from_slice(args);
+
+        self.active_exec_traces.push(key.to_string());
+        let result = self.invoke(&cmd);
+        self.active_exec_traces.pop();
+
+        match result {
+            Err(error @ ResultCode::Error(_)) => Err(error),
+            _ => Ok(()),
+        }
     }
 
-    /// Unsets the value of the named variable in the current scope
-    pub fn unset_var(&mut self, name: &str) {
-        self.scopes.unset(name);
+    /// Hides the named command: moves it to the hidden commands under the hidden name,
+    /// so that scripts can no longer call it, but it can still be invoked by
+    /// `invoke_hidden` or exposed again by `expose_command`.  It's an error if there's no
+    /// such command, if the hidden name is qualified, or if there's already a hidden
+    /// command with that name.
+    pub fn hide_command(&mut self, name: &str, hidden_name: &str) -> MoltResult {
+        if hidden_name.contains("::") {
+            return molt_err!("cannot use namespace qualifiers in hidden command token (rename)");
+        }
+
+        if self.hidden.contains_key(hidden_name) {
+            return molt_err!("hidden command named \"{}\" already exists", hidden_name);
+        }
+
+        match self.command_key(name).map(Cow::into_owned) {
+            Some(key) => {
+                let cmd = self.commands.remove(&key).expect("command exists");
+                self.hidden.insert(hidden_name.into(), cmd);
+                molt_ok!()
+            }
+            None => molt_err!("unknown command \"{}\"", name),
+        }
     }
 
-    /// Gets a vector of the visible var names.
-    pub fn vars_in_scope(&self) -> MoltList {
-        self.scopes.vars_in_scope()
+    /// Exposes the hidden command: moves it back to the command table, in the global
+    /// namespace, under the name.  It's an error if there's no such hidden command, if the
+    /// name is qualified, or if there's already a command with that name.
+    pub fn expose_command(&mut self, hidden_name: &str, name: &str) -> MoltResult {
+        if name.contains("::") {
+            return molt_err!("cannot expose to a namespace (use expose to toplevel, then rename)");
+        }
+
+        if !self.hidden.contains_key(hidden_name) {
+            return molt_err!("unknown hidden command \"{}\"", hidden_name);
+        }
+
+        if self.commands.contains_key(name) {
+            return molt_err!("exposed command \"{}\" already exists", name);
+        }
+
+        let cmd = self.hidden.remove(hidden_name).expect("hidden command exists");
+        self.commands.insert(name.into(), cmd);
+        molt_ok!()
     }
 
-    /// Pushes a variable scope on to the scope stack.
-    /// Procs use this to define their local scope.
-    pub fn push_scope(&mut self) {
-        self.scopes.push();
+    /// Invokes a hidden command: `argv[0]` is the hidden command's name, and the rest of
+    /// `argv` is its arguments.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    ///
+    /// let mut interp = Interp::new_safe();
+    /// let argv = [Value::from("file"), Value::from("isdirectory"), Value::from(".")];
+    /// assert_eq!(interp.invoke_hidden(&argv), Ok(Value::from(true)));
+    /// ```
+    pub fn invoke_hidden(&mut self, argv: &[Value]) -> MoltResult {
+        let name = &*argv[0].as_string();
+
+        if let Some(cmd) = self.hidden.get(name) {
+            let cmd = cmd.clone();
+            self.cmd_count += 1;
+            cmd.execute(self, argv)
+        } else {
+            molt_err!("invalid hidden command name \"{}\"", name)
+        }
     }
 
-    /// Pops a variable scope off of the scope stack.
-    pub fn pop_scope(&mut self) {
-        self.scopes.pop();
+    /// Gets a vector of the names of the hidden commands.
+    pub fn hidden_names(&self) -> MoltList {
+        self.hidden.keys().map(Value::from).collect()
     }
 
-    /// Return the current scope level
-    pub fn scope_level(&self) -> usize {
-        self.scopes.current()
+    /// Determines whether this is a safe interpreter, as created by `Interp::new_safe`.
+    pub fn is_safe(&self) -> bool {
+        self.safe
     }
 
-    /// Links the variable name in the current scope to the given scope.
-    pub fn upvar(&mut self, level: usize, name: &str) {
-        assert!(level <= self.scopes.current(), "Invalid scope level");
-        self.scopes.upvar(level, name);
+    /// Determines whether the interpreter may run programs, i.e., whether
+    /// `Interp::add_process_commands` has added its commands.  Only then can `open` open
+    /// command pipelines.
+    pub(crate) fn runs_programs(&self) -> bool {
+        self.runs_programs
     }
 
-    //--------------------------------------------------------------------------------------------
-    // Script and Expression Evaluation
+    /// Gets a vector of the names of the existing commands in the global namespace,
+    /// including procs.
+    pub fn command_names(&self) -> MoltList {
+        self.commands
+            .keys()
+            .filter(|name| !name.contains("::"))
+            .map(Value::from)
+            .collect()
+    }
 
-    /// Evaluates a script one command at a time, returning the
-    /// value of the last command in the script, the value of an explicit
-    /// `return` command, or an error.
-    ///
-    /// `break` and `continue` results are converted to errors.
-    ///
-    /// This is the method to use when evaluating an entire script.
-    pub fn eval(&mut self, script: &str) -> MoltResult {
-        // FIRST, check the number of nesting levels
-        self.num_levels += 1;
+    /// Gets a vector of the names of the existing procs in the global namespace.
+    pub fn proc_names(&self) -> MoltList {
+        self.commands
+            .iter()
+            .filter(|(name, cmd)| matches!(cmd, CommandEntry::Proc(_)) && !name.contains("::"))
+            .map(|(name, _)| Value::from(name))
+            .collect()
+    }
 
-        if self.num_levels > self.recursion_limit {
-            self.num_levels -= 1;
-            return molt_err!("too many nested calls to Interp::eval (infinite loop?)");
+    /// Finds the command with the given name.  A qualified name beginning with "::" is
+    /// looked up as is; any other name is looked up first in the current namespace and
+    /// then in the global namespace.
+    fn find_command(&self, name: &str) -> Option<&CommandEntry> {
+        if let Some(key) = name.strip_prefix("::") {
+            return self.commands.get(key);
         }
 
-        // NEXT, evaluate the script and translate the result to Ok or Error
-        let mut ctx = Context::new(script);
+        match self.ns_stack.last() {
+            Some(ctx) if &*ctx.name != "::" => {
+                let key = format!("{}::{}", &ctx.name[2..], name);
+                self.commands.get(&key).or_else(|| self.commands.get(name))
+            }
+            _ => self.commands.get(name),
+        }
+    }
 
-        let result = self.eval_context(&mut ctx);
+    /// Gets the command table key of the command with the given name, looking it up
+    /// as for `find_command`, or None if there's no such command.
+    fn command_key<'a>(&self, name: &'a str) -> Option<Cow<'a, str>> {
+        if let Some(key) = name.strip_prefix("::") {
+            return self.commands.get(key).map(|_| Cow::Borrowed(key));
+        }
 
-        // NEXT, decrement the number of nesting levels.
-        self.num_levels -= 1;
+        if let Some(ctx) = self.ns_stack.last() {
+            if &*ctx.name != "::" {
+                let key = format!("{}::{}", &ctx.name[2..], name);
 
-        // NEXT, translate and return the result.
-        match result {
-            Err(ResultCode::Return(value)) => {
-                molt_ok!(value)
-            }
-            Err(ResultCode::Break) => {
-                molt_err!("invoked \"break\" outside of a loop")
-            }
-            Err(ResultCode::Continue) => {
-                molt_err!("invoked \"continue\" outside of a loop")
+                if self.commands.contains_key(&key) {
+                    return Some(Cow::Owned(key));
+                }
             }
-            _ => result
         }
+
+        self.commands.get(name).map(|_| Cow::Borrowed(name))
     }
 
-    /// Evaluates a script one command at a time, returning whatever
-    /// MoltResult arises.
-    ///
-    /// This is the method to use when evaluating a control structure's
-    /// script body; the control structure must handle the special
-    /// result codes appropriately.
-    pub fn eval_body(&mut self, script: &str) -> MoltResult {
-        let mut ctx = Context::new(script);
+    /// Gets the command table key for defining a command with the given name in the
+    /// current namespace, or None if the command's namespace doesn't exist.
+    fn definition_key(&self, name: &str) -> Option<String> {
+        let qualified = qualify(self.current_namespace(), name);
+        let namespace = namespace_of(&qualified[2..]);
 
-        self.eval_context(&mut ctx)
+        if namespace == "::" || self.namespaces.contains(&namespace) {
+            Some(qualified[2..].to_string())
+        } else {
+            None
+        }
     }
 
-    /// Determines whether or not the script is syntactically complete,
-    /// e.g., has no unmatched quotes, brackets, or braces.
+    //--------------------------------------------------------------------------------------------
+    // Variable Handling
+
+    /// Retrieves the value of the named variable in the current scope, if any.  The
+    /// name may be a scalar variable name or an array element name of the form
+    /// "*arrayName*(*index*)".
     ///
-    /// REPLs use this to determine whether or not to ask for another line of
-    /// input.
+    /// It's an error to retrieve the value of an array variable as a whole; use
+    /// `array_get` for that.  Any read traces on the variable are called first.
     ///
-    /// # Example
-    /// ```
-    /// # use molt::types::*;
-    /// # use molt::interp::Interp;
-    /// let mut interp = Interp::new();
-    /// assert!(interp.complete("set a [expr {1+1}]"));
-    /// assert!(!interp.complete("set a [expr {1+1"));
-    /// ```
-
-    pub fn complete(&mut self, script: &str) -> bool {
+    /// TODO: Somehow this converts the MoltValue from the variable into a string automatically.
+    /// Maybe it's molt_ok! doing that?
+    pub fn var(&mut self, name: &str) -> MoltResult {
+        match parse_var_name(name) {
+            (array_name, Some(index)) => self.element(array_name, index),
+            (_, None) => {
+                self.var_traces(name, None, TraceOp::Read)?;
+                let key = self.read_key(name);
+
+                match self.scopes.get(&key) {
+                    Some(v) => molt_ok!(v.clone()),
+                    None if self.scopes.is_array(&key) => {
+                        molt_err!("can't read \"{}\": variable is array", name)
+                    }
+                    None => molt_err!("can't read \"{}\": no such variable", name),
+                }
+            }
+        }
+    }
+
+    /// Retrieves the value of the given element of the named array variable in the current
+    /// scope.  Any read traces on the array or the element are called first.
+    pub fn element(&mut self, name: &str, index: &str) -> MoltResult {
+        self.var_traces(name, Some(index), TraceOp::Read)?;
+        let key = self.read_key(name);
+
+        match self.scopes.get_elem(&key, index) {
+            Some(v) => molt_ok!(v),
+            None if self.scopes.is_array(&key) => {
+                molt_err!("can't read \"{}({})\": no such element in array", name, index)
+            }
+            None if self.scopes.exists(&key) => {
+                molt_err!("can't read \"{}({})\": variable isn't array", name, index)
+            }
+            None => molt_err!("can't read \"{}({})\": no such variable", name, index),
+        }
+    }
+
+    /// Sets the value of the named variable in the current scope, creating the variable
+    /// if necessary.
+    pub fn set_var(&mut self, name: &str, value: &str) -> MoltResult {
+        // TODO: Temporary fix while integrating MoltValue.
+        self.set_var2(name, Value::from(value))
+    }
+
+    /// Sets the value of the named variable in the current scope, creating the variable
+    /// if necessary, and returns the value.  The name may be a scalar variable name or
+    /// an array element name of the form "*arrayName*(*index*)".
+    ///
+    /// It's an error to set an array variable as a whole, or to set an element of a
+    /// scalar variable.  Any write traces on the variable are called after it is set; it's
+    /// an error if one of them is, and the value returned is the variable's value after
+    /// they are called.
+    ///
+    /// TODO: Ultimately, this should replace set_var.
+    pub fn set_var2(&mut self, name: &str, value: Value) -> MoltResult {
+        match parse_var_name(name) {
+            (array_name, Some(index)) => self.set_element(array_name, index, value),
+            (_, None) => {
+                let key = self.var_key(name).into_owned();
+
+                if self.scopes.is_array(&key) {
+                    return molt_err!("can't set \"{}\": variable is array", name);
+                }
+
+                let value = self.scopes.set(&key, value);
+
+                if !self.scopes.has_traces() {
+                    return molt_ok!(value);
+                }
+
+                self.var_traces(name, None, TraceOp::Write)?;
+                molt_ok!(self.scopes.get(&key).unwrap_or(value))
+            }
+        }
+    }
+
+    /// Sets the value of the named variable in the current scope, creating the variable
+    /// if necessary.
+    pub fn set_var3(&mut self, name: &str, value: &Value) -> MoltResult {
+        self.set_var2(name, value.clone())
+    }
+
+    /// Sets the value of the given element of the named array variable in the current
+    /// scope, creating the array variable if necessary, and returns the value.  Write
+    /// traces are called as for `set_var2`.
+    pub fn set_element(&mut self, name: &str, index: &str, value: Value) -> MoltResult {
+        let key = self.var_key(name).into_owned();
+
+        if self.scopes.exists(&key) && !self.scopes.is_array(&key) {
+            return molt_err!("can't set \"{}({})\": variable isn't array", name, index);
+        }
+
+        let value = self.scopes.set_elem(&key, index, value);
+
+        if !self.scopes.has_traces() {
+            return molt_ok!(value);
+        }
+
+        self.var_traces(name, Some(index), TraceOp::Write)?;
+        molt_ok!(self.scopes.get_elem(&key, index).unwrap_or(value))
+    }
+
+    /// Determines whether the named variable exists in the current scope, following any
+    /// links to other scopes.  The name may be a scalar variable name, an array variable
+    /// name, or an array element name of the form "*arrayName*(*index*)".
+    pub fn var_exists(&self, name: &str) -> bool {
+        match parse_var_name(name) {
+            (array_name, Some(index)) => {
+                self.scopes.get_elem(&self.read_key(array_name), index).is_some()
+            }
+            (_, None) => self.scopes.exists(&self.read_key(name)),
+        }
+    }
+
+    /// Unsets the value of the named variable in the current scope.  The name may be a
+    /// scalar variable name, an array variable name, or an array element name of the form
+    /// "*arrayName*(*index*)".  Any unset traces on the variable are called after it is
+    /// unset, ignoring errors, and are then removed along with the variable.
+    pub fn unset_var(&mut self, name: &str) {
+        let (name, index) = parse_var_name(name);
+        let key = self.var_key(name).into_owned();
+
+        match index {
+            Some(index) => self.scopes.unset_elem(&key, index),
+            None => self.scopes.unset(&key),
+        }
+
+        // Errors from unset traces are ignored.
+        let _ = self.var_traces(name, index, TraceOp::Unset);
+    }
+
+    /// Unsets the value of the named variable in the current scope, as for `unset_var`,
+    /// but returns an error if there is no such variable or array element.
+    pub fn unset_var_checked(&mut self, name: &str) -> MoltResult {
+        match parse_var_name(name) {
+            (array_name, Some(index)) => {
+                let key = self.var_key(array_name);
+
+                if self.scopes.get_elem(&key, index).is_none() {
+                    if self.scopes.is_array(&key) {
+                        return molt_err!("can't unset \"{}\": no such element in array", name);
+                    } else if self.scopes.exists(&key) {
+                        return molt_err!("can't unset \"{}\": variable isn't array", name);
+                    } else {
+                        return molt_err!("can't unset \"{}\": no such variable", name);
+                    }
+                }
+            }
+            (_, None) => {
+                if !self.scopes.exists(&self.var_key(name)) {
+                    return molt_err!("can't unset \"{}\": no such variable", name);
+                }
+            }
+        }
+
+        self.unset_var(name);
+        molt_ok!()
+    }
+
+    /// Determines whether the named variable is an array variable in the current scope.
+    pub fn array_exists(&self, name: &str) -> bool {
+        self.scopes.is_array(&self.read_key(name))
+    }
+
+    /// Gets a list of the element names of the named array variable.  The list is empty
+    /// if there is no such array variable.
+    pub fn array_names(&self, name: &str) -> MoltList {
+        self.scopes.array_names(&self.read_key(name))
+    }
+
+    /// Gets a flat list of the element names and values of the named array variable.
+    /// The list is empty if there is no such array variable.
+    pub fn array_get(&self, name: &str) -> MoltList {
+        self.scopes.array_get(&self.read_key(name))
+    }
+
+    /// Merges a flat list of element names and values into the named array variable,
+    /// creating it if necessary.  It's an error if the list has an odd number of
+    /// elements or if the variable exists as a scalar.
+    pub fn array_set(&mut self, name: &str, list: &[Value]) -> MoltResult {
+        if list.len() % 2 == 1 {
+            return molt_err!("list must have an even number of elements");
+        }
+
+        let key = self.var_key(name).into_owned();
+
+        if self.scopes.exists(&key) && !self.scopes.is_array(&key) {
+            return molt_err!("can't array set \"{}\": variable isn't array", name);
+        }
+
+        self.scopes.array_set(&key, list);
+
+        if self.scopes.has_traces() {
+            for pair in list.chunks(2) {
+                self.var_traces(name, Some(&pair[0].as_string()), TraceOp::Write)?;
+            }
+        }
+
+        molt_ok!()
+    }
+
+    /// Adds a trace to the named variable in the current scope, as for `trace add
+    /// variable`: the command prefix will be called with the variable's name, the element
+    /// index or the empty string, and the operation, whenever the variable is accessed by
+    /// one of the operations.  The name may be an array element name, to trace just that
+    /// element.  The variable needn't exist yet.
+    pub(crate) fn add_var_trace(&mut self, name: &str, ops: Vec<TraceOp>, command: Value) {
+        let (name, index) = parse_var_name(name);
+        let key = self.var_key(name).into_owned();
+        self.scopes.add_trace(&key, index, VarTrace { ops, command });
+    }
+
+    /// Removes the most recent trace with the given operations and command prefix from
+    /// the named variable, if there is one.
+    pub(crate) fn remove_var_trace(&mut self, name: &str, ops: &[TraceOp], command: &str) {
+        let (name, index) = parse_var_name(name);
+        let key = self.var_key(name).into_owned();
+        self.scopes.remove_trace(&key, index, ops, command);
+    }
+
+    /// Determines whether any variable has traces.
+    pub(crate) fn has_var_traces(&self) -> bool {
+        self.scopes.has_traces()
+    }
+
+    /// Gets the traces on the named variable, most recent first, as for `trace info
+    /// variable`.
+    pub(crate) fn var_trace_info(&self, name: &str) -> Vec<VarTrace> {
+        let (name, index) = parse_var_name(name);
+        self.scopes.trace_info(&self.var_key(name), index)
+    }
+
+    /// Calls the traces for the operation on the named variable, or on the given element of
+    /// the named array, passing each the name, the index or the empty string, and the
+    /// operation.  While they are being called, the variable's traces are disabled.  The
+    /// first error stops the calls, and is returned as the error for the access; other
+    /// results are ignored.  Unset traces are removed before they are called.
+    fn var_traces(&mut self, name: &str, index: Option<&str>, op: TraceOp) -> Result<(), ResultCode> {
+        if !self.scopes.has_traces() {
+            return Ok(());
+        }
+
+        let key = match op {
+            TraceOp::Read => self.read_key(name).into_owned(),
+            _ => self.var_key(name).into_owned(),
+        };
+        let id = self.scopes.var_id(&key);
+
+        if self.active_var_traces.contains(&id) {
+            return Ok(());
+        }
+
+        let traces = if op == TraceOp::Unset {
+            self.scopes.take_traces(&key, index)
+        } else {
+            self.scopes.traces(&key, index)
+        };
+
+        self.active_var_traces.push(id);
+        let mut result = Ok(());
+
+        for (elem, trace) in traces.into_iter().filter(|(_, trace)| trace.ops.contains(&op)) {
+            let mut cmd = match trace.command.as_list() {
+                Ok(list) => list.to_vec(),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+
+            if cmd.is_empty() {
+                continue;
+            }
+
+            cmd.push(Value::from(name));
+            cmd.push(Value::from(elem));
+            cmd.push(Value::from(op.as_str()));
+
+            if let Err(error @ ResultCode::Error(_)) = self.invoke(&cmd) {
+                result = Err(error);
+                break;
+            }
+        }
+
+        self.active_var_traces.pop();
+
+        match result {
+            Err(ResultCode::Error(data)) if op != TraceOp::Unset => {
+                let name = match index {
+                    Some(index) => format!("{}({})", name, index),
+                    None => name.to_string(),
+                };
+                let verb = if op == TraceOp::Read { "read" } else { "set" };
+
+                molt_err!("can't {} \"{}\": {}", verb, name, data.message())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets the key by which the named variable is known to the scope stack, given the
+    /// current namespace context.  Qualified names, e.g., "::foo::x" or "foo::x", refer
+    /// to namespace variables; "::x" is a global variable.  Unqualified names refer to
+    /// local variables in procs, and to the namespace's variables in the body of a
+    /// `namespace eval`.
+    fn var_key<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if name.starts_with("::") {
+            return Cow::Borrowed(name);
+        }
+
+        let namespace = self.current_namespace();
+
+        if name.contains("::") {
+            return Cow::Owned(qualify(namespace, name));
+        }
+
+        match self.ns_stack.last() {
+            Some(ctx) if ctx.var_level == Some(self.scopes.current()) => {
+                Cow::Owned(qualify(namespace, name))
+            }
+            _ => Cow::Borrowed(name),
+        }
+    }
+
+    /// Gets the key by which the named variable is read, as for `var_key`, except that in
+    /// the body of a `namespace eval` an unqualified name that isn't a variable of the
+    /// namespace refers to the global variable of that name, if there is one, as for
+    /// commands.  Variables are always set in the current namespace.
+    fn read_key<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let key = self.var_key(name);
+
+        if let Cow::Owned(ns_key) = &key {
+            if !name.contains("::") && !self.scopes.exists(ns_key) {
+                let global = format!("::{}", name);
+
+                if self.scopes.exists(&global) {
+                    return Cow::Owned(global);
+                }
+            }
+        }
+
+        key
+    }
+
+    /// Gets a vector of the visible var names.
+    pub fn vars_in_scope(&self) -> MoltList {
+        self.scopes.vars_in_scope()
+    }
+
+    /// Gets a vector of the names of the variables local to the current scope, i.e.,
+    /// excluding those linked to variables in other scopes.  At the global scope, there
+    /// are no local variables.
+    pub fn vars_local(&self) -> MoltList {
+        self.scopes.vars_local()
+    }
+
+    /// Gets a vector of the names of the variables in the global scope.
+    pub fn vars_global(&self) -> MoltList {
+        self.scopes.vars_global()
+    }
+
+    /// Pushes a variable scope on to the scope stack.
+    /// Procs use this to define their local scope.
+    pub fn push_scope(&mut self) {
+        self.push_proc_scope(&[]);
+    }
+
+    /// Pushes a variable scope on to the scope stack on behalf of the given command,
+    /// which `info level` will report for the new scope level.
+    pub(crate) fn push_proc_scope(&mut self, argv: &[Value]) {
+        self.scopes.push();
+        self.scope_argvs.push(argv.to_vec());
+    }
+
+    /// Pops a variable scope off of the scope stack, first calling the unset traces on
+    /// its variables.
+    pub fn pop_scope(&mut self) {
+        for name in self.scopes.traced_locals() {
+            let _ = self.var_traces(&name, None, TraceOp::Unset);
+        }
+
+        self.scopes.pop();
+        self.scope_argvs.pop();
+    }
+
+    /// Return the current scope level
+    pub fn scope_level(&self) -> usize {
+        self.scopes.current()
+    }
+
+    /// Gets the command that pushed the given scope level, as a list, or None if the
+    /// level is the global scope or doesn't exist.  The command is empty if the scope was
+    /// pushed by `push_scope`.
+    pub fn scope_argv(&self, level: usize) -> Option<MoltList> {
+        if level == 0 {
+            None
+        } else {
+            self.scope_argvs.get(level - 1).cloned()
+        }
+    }
+
+    /// Determines whether commands are being evaluated in the body of a proc or lambda,
+    /// i.e., in a scope pushed by a proc call, rather than in the global scope, a
+    /// namespace's scope, or a scope pushed by `push_scope`.
+    pub(crate) fn in_proc(&self) -> bool {
+        let pushed_by_proc = match self.scope_argv(self.scope_level()) {
+            Some(argv) => !argv.is_empty(),
+            None => false,
+        };
+
+        pushed_by_proc && !self.in_namespace_scope()
+    }
+
+    /// Evaluates a script in the variable scope at the given level, as for `uplevel`,
+    /// returning whatever MoltResult arises.  While the script is being evaluated,
+    /// the given level is the current scope level, and procs called by the script push
+    /// their scopes just above it, and the script is evaluated in the namespace that was
+    /// current at that level.  Panics if the level doesn't exist.
+    pub fn eval_at_level(&mut self, level: usize, script: &str) -> MoltResult {
+        assert!(level <= self.scopes.current(), "Invalid scope level");
+        let context = self
+            .ns_stack
+            .iter()
+            .rev()
+            .find(|ctx| ctx.level <= level)
+            .cloned()
+            .unwrap_or(NsContext {
+                name: "::".into(),
+                level: 0,
+                var_level: Some(0),
+            });
+
+        self.scopes.uplevel(level);
+        self.ns_stack.push(context);
+        let mut hidden_argvs = self.scope_argvs.split_off(level);
+
+        let result = self.eval_body(script);
+
+        self.scope_argvs.append(&mut hidden_argvs);
+        self.ns_stack.pop();
+        self.scopes.downlevel();
+
+        result
+    }
+
+    /// Evaluates a script at the global scope, in the global namespace, as for the event
+    /// loop and `interp eval`.  As at the top level of `eval`, a return returns from the
+    /// script, and a break or continue is an error.
+    pub fn eval_global(&mut self, script: &str) -> MoltResult {
+        self.ns_stack.push(NsContext {
+            name: "::".into(),
+            level: 0,
+            var_level: Some(0),
+        });
+        let result = script_result(self.eval_at_level(0, script));
+        self.ns_stack.pop();
+
+        result
+    }
+
+    /// Links the variable name in the current scope to the given scope.
+    pub fn upvar(&mut self, level: usize, name: &str) {
+        assert!(level <= self.scopes.current(), "Invalid scope level");
+        self.scopes.upvar(level, name);
+    }
+
+    /// Links the variable `name` in the current scope to the variable `other_name` in the
+    /// given scope, which may be the current scope, as for the `upvar` command.  Reads,
+    /// writes, and unsets of `name` then affect `other_name`; the latter is created on
+    /// the first write if it doesn't exist.  The `other_name` may be a scalar or an array
+    /// variable, but not an array element.
+    ///
+    /// It's an error if `name` already exists as a variable in the current scope, and if
+    /// it would be linked to itself.
+    pub fn upvar2(&mut self, level: usize, other_name: &str, name: &str) -> MoltResult {
+        assert!(level <= self.scopes.current(), "Invalid scope level");
+
+        if parse_var_name(name).1.is_some() {
+            return molt_err!(
+                "bad variable name \"{}\": upvar won't create a scalar variable that looks like an array element",
+                name
+            );
+        }
+
+        if parse_var_name(other_name).1.is_some() {
+            return molt_err!("can't upvar to array element \"{}\"", other_name);
+        }
+
+        let other_key = if other_name.contains("::") {
+            self.var_key(other_name)
+        } else {
+            Cow::Borrowed(other_name)
+        };
+
+        if self.scopes.is_local(name) {
+            return molt_err!("variable \"{}\" already exists", name);
+        }
+
+        if !self.scopes.link(level, &other_key, name) {
+            return molt_err!("can't upvar from variable to itself");
+        }
+
+        molt_ok!()
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Namespaces
+
+    /// Gets the fully qualified name of the current namespace, e.g., "::" for the global
+    /// namespace or "::foo::bar".
+    pub fn current_namespace(&self) -> &str {
+        match self.ns_stack.last() {
+            Some(ctx) => &ctx.name,
+            None => "::",
+        }
+    }
+
+    /// Gets the fully qualified name of the namespace with the given name, which is
+    /// relative to the current namespace unless it begins with "::".
+    pub fn qualify_namespace(&self, name: &str) -> String {
+        let name = name.trim_end_matches(':');
+
+        if name.is_empty() {
+            "::".into()
+        } else {
+            qualify(self.current_namespace(), name)
+        }
+    }
+
+    /// Determines whether the named namespace exists.
+    pub fn namespace_exists(&self, name: &str) -> bool {
+        let name = self.qualify_namespace(name);
+        name == "::" || self.namespaces.contains(&name)
+    }
+
+    /// Creates the named namespace, and any parent namespaces, if they don't already
+    /// exist, and returns its fully qualified name.
+    pub fn add_namespace(&mut self, name: &str) -> String {
+        let name = self.qualify_namespace(name);
+        let mut parent = name.as_str();
+
+        while parent != "::" {
+            self.namespaces.insert(parent.to_string());
+            parent = parent_namespace(parent);
+        }
+
+        name
+    }
+
+    /// Evaluates a script in the named namespace, creating the namespace if necessary, and
+    /// returns whatever MoltResult arises.  Commands defined by the script are defined
+    /// in the namespace, and unqualified variable names refer to the namespace's
+    /// variables.  No new variable scope is pushed.
+    pub fn namespace_eval(&mut self, name: &str, script: &str) -> MoltResult {
+        let name = self.add_namespace(name);
+
+        self.ns_stack.push(NsContext {
+            name: name.into(),
+            level: self.scopes.current(),
+            var_level: Some(self.scopes.current()),
+        });
+        let result = self.eval_body(script);
+        self.ns_stack.pop();
+
+        result
+    }
+
+    /// Deletes the named namespace and its child namespaces, along with the commands
+    /// and variables they contain.  It's an error if the namespace doesn't exist.
+    pub fn delete_namespace(&mut self, name: &str) -> MoltResult {
+        let name = self.qualify_namespace(name);
+
+        if !self.namespaces.contains(&name) {
+            if name == "::" {
+                return molt_err!("can't delete the global namespace");
+            }
+            return molt_err!("unknown namespace \"{}\" in namespace delete command", name);
+        }
+
+        let prefix = format!("{}::", name);
+        self.namespaces.retain(|ns| *ns != name && !ns.starts_with(&prefix));
+        self.commands.retain(|key, _| !key.starts_with(&prefix[2..]));
+        self.exec_traces.retain(|key, _| !key.starts_with(&prefix[2..]));
+        self.scopes.unset_namespace(&prefix[2..]);
+
+        molt_ok!()
+    }
+
+    /// Gets a vector of the fully qualified names of the children of the named namespace.
+    /// It's an error if the namespace doesn't exist.
+    pub fn namespace_children(&self, name: &str) -> Result<MoltList, ResultCode> {
+        let name = self.qualify_namespace(name);
+
+        if !self.namespace_exists(&name) {
+            return molt_err!("namespace \"{}\" not found in \"{}\"", name, self.current_namespace());
+        }
+
+        Ok(self
+            .namespaces
+            .iter()
+            .filter(|ns| parent_namespace(ns) == name)
+            .map(Value::from)
+            .collect())
+    }
+
+    /// Gets a vector of the names of the commands defined in the named namespace, excluding
+    /// those in its child namespaces.  The names are unqualified.
+    pub fn namespace_commands(&self, name: &str) -> MoltList {
+        let name = self.qualify_namespace(name);
+
+        if name == "::" {
+            return self.command_names();
+        }
+
+        let prefix = format!("{}::", &name[2..]);
+
+        self.commands
+            .keys()
+            .filter_map(|key| key.strip_prefix(&prefix))
+            .filter(|tail| !tail.contains("::"))
+            .map(Value::from)
+            .collect()
+    }
+
+    /// Gets the fully qualified name of the namespace variable with the given name in
+    /// the current namespace, e.g., "::foo::x"; the `variable` command links procs' local
+    /// variables to it.
+    pub fn namespace_var_name(&self, name: &str) -> String {
+        qualify(self.current_namespace(), name)
+    }
+
+    /// Determines whether unqualified variable names refer to namespace variables, i.e.,
+    /// whether the current scope is the global scope or the body of a `namespace eval`
+    /// rather than a proc.
+    pub fn in_namespace_scope(&self) -> bool {
+        match self.ns_stack.last() {
+            Some(ctx) => ctx.var_level == Some(self.scopes.current()),
+            None => self.scopes.current() == 0,
+        }
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Script and Expression Evaluation
+
+    /// Evaluates a script one command at a time, returning the
+    /// value of the last command in the script, the value of an explicit
+    /// `return` command, or an error.
+    ///
+    /// `break` and `continue` results are converted to errors.  A `return` result is
+    /// returned one level, as described for [`ReturnData`]; thus, `return -code break`
+    /// or `return -level 2` can yield a `Break` or `Return` result, except at the top
+    /// level, i.e., when `eval` is not called from within another `eval`.  At the top level,
+    /// the error info and error code of an error are saved in the global `errorInfo` and
+    /// `errorCode` variables.
+    ///
+    /// [`ReturnData`]: ../types/struct.ReturnData.html
+    ///
+    /// This is the method to use when evaluating an entire script.
+    pub fn eval(&mut self, script: &str) -> MoltResult {
+        // FIRST, check the number of nesting levels
+        self.num_levels += 1;
+
+        if self.num_levels > self.recursion_limit {
+            self.num_levels -= 1;
+            return molt_err!("too many nested calls to Interp::eval (infinite loop?)");
+        }
+
+        // NEXT, evaluate the script and translate the result to Ok or Error
+        let mut ctx = Context::new(script);
+
+        let result = self.eval_context(&mut ctx);
+
+        // NEXT, decrement the number of nesting levels.
+        self.num_levels -= 1;
+
+        // NEXT, a return returns one level.  Below the top level, that's the result;
+        // at the top level, there's nowhere for a returned break or continue to go.
+        // Similarly, a tail call goes to the enclosing proc, if any, and an application-
+        // defined result code to whoever handles it.
+        let result = match result {
+            Err(ResultCode::Return(data)) if self.num_levels > 0 => return data.return_level(),
+            Err(ResultCode::TailCall(_)) if self.num_levels > 0 => return result,
+            Err(ResultCode::Other(..)) if self.num_levels > 0 => return result,
+            _ => script_result(result),
+        };
+
+        // NEXT, at the top level, save the error info and error code of any error.
+        if self.num_levels == 0 {
+            if let Err(ResultCode::Error(data)) = &result {
+                self.set_error_vars(data);
+            }
+        }
+
+        result
+    }
+
+    /// Saves the error info and error code of an error in the global `errorInfo` and
+    /// `errorCode` variables, as for an error caught by `catch`.
+    pub(crate) fn set_error_vars(&mut self, data: &ErrorData) {
+        // Errors are ignored; there's nothing to be done if the variables are arrays.
+        let _ = self.set_var2("::errorInfo", Value::from(data.error_info()));
+        let _ = self.set_var2("::errorCode", data.error_code().clone());
+    }
+
+    /// Evaluates a script one command at a time, returning whatever
+    /// MoltResult arises.
+    ///
+    /// This is the method to use when evaluating a control structure's
+    /// script body; the control structure must handle the special
+    /// result codes appropriately.
+    pub fn eval_body(&mut self, script: &str) -> MoltResult {
+        let mut ctx = Context::new(script);
+
+        self.eval_context(&mut ctx)
+    }
+
+    /// Determines whether or not the script is syntactically complete,
+    /// e.g., has no unmatched quotes, brackets, or braces.
+    ///
+    /// REPLs use this to determine whether or not to ask for another line of
+    /// input.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::types::*;
+    /// # use molt::interp::Interp;
+    /// let mut interp = Interp::new();
+    /// assert!(interp.complete("set a [expr {1+1}]"));
+    /// assert!(!interp.complete("set a [expr {1+1"));
+    /// ```
+
+    pub fn complete(&mut self, script: &str) -> bool {
         let mut ctx = Context::new(script);
         ctx.set_no_eval(true);
 
-        self.eval_context(&mut ctx).is_ok()
+        self.eval_context(&mut ctx).is_ok()
+    }
+
+    /// Seeds the interpreter's pseudo-random number generator, as the `srand()` math
+    /// function does.  Interpreters seeded alike produce the same sequence of numbers.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::interp::Interp;
+    /// let mut interp = Interp::new();
+    /// interp.srand(42);
+    /// let first = interp.rand();
+    ///
+    /// assert!((0.0..1.0).contains(&first));
+    /// assert_eq!(interp.eval("expr {srand(42)}").unwrap().as_float(), Ok(first));
+    /// ```
+    pub fn srand(&mut self, seed: MoltInt) {
+        // As in Standard TCL, only the low 31 bits count, and the generator's fixed
+        // points are avoided.
+        let mut seed = seed & 0x7fff_ffff;
+
+        if seed == 0 || seed == 0x7fff_ffff {
+            seed ^= RAND_MASK;
+        }
+
+        self.rand_seed = Some(seed);
+    }
+
+    /// Gets the next number in [0, 1) from the interpreter's pseudo-random number
+    /// generator, as the `rand()` math function does.  If the generator hasn't been seeded,
+    /// it's seeded from the clock and the process ID.
+    pub fn rand(&mut self) -> MoltFloat {
+        let seed = match self.rand_seed {
+            Some(seed) => seed,
+            None => {
+                let nanos = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.subsec_nanos())
+                    .unwrap_or(0);
+                self.srand(MoltInt::from(nanos) + (MoltInt::from(std::process::id()) << 12));
+                self.rand_seed.unwrap()
+            }
+        };
+
+        // The Park-Miller "minimal standard" generator, computed per Schrage's method so
+        // as not to overflow, as in Standard TCL.
+        let tmp = seed / RAND_IQ;
+        let mut seed = RAND_IA * (seed - tmp * RAND_IQ) - RAND_IR * tmp;
+
+        if seed < 0 {
+            seed += RAND_IM;
+        }
+
+        self.rand_seed = Some(seed);
+        seed as MoltFloat / RAND_IM as MoltFloat
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Coroutines
+    //
+    // A coroutine's command runs on a thread of its own; see the `coroutine` module.  When
+    // a coroutine is resumed, the caller's call stack, i.e., its scopes above the global
+    // scope and its namespace contexts, is hidden and replaced by the coroutine's; when the
+    // coroutine yields, the coroutine's call stack is saved and the caller's is restored.
+    // Thus the coroutine's command runs at the global level, whoever resumes it.
+
+    /// Creates a coroutine command with the given name, as for the `coroutine` command,
+    /// and runs the coroutine until it first yields, returning the yielded value, or until
+    /// its command returns, returning the result.  The command is resolved in the current
+    /// namespace.  It's an error if the name's namespace doesn't exist, or if a command
+    /// with that name already exists.
+    pub(crate) fn add_coroutine(&mut self, name: &str, cmd: &[Value]) -> MoltResult {
+        let key = match self.definition_key(name) {
+            Some(key) => key,
+            None => return molt_err!("can't create coroutine \"{}\": unknown namespace", name),
+        };
+
+        if self.commands.contains_key(&key) {
+            return molt_err!("command \"{}\" already exists", name);
+        }
+
+        let co = Rc::new(Coroutine::new(self.tail_command(cmd)));
+        self.commands.insert(key, CommandEntry::Coroutine(Rc::clone(&co)));
+        self.resume_coroutine(&co, Resume::Value(Value::empty()))
+    }
+
+    /// Calls a coroutine command: resumes the coroutine with the argument, if any.
+    fn call_coroutine(&mut self, co: &Rc<Coroutine>, argv: &[Value]) -> MoltResult {
+        crate::check_args(1, argv, 1, 2, "?arg?")?;
+
+        if co.is_running() {
+            return molt_err!("coroutine \"{}\" is already running", argv[0]);
+        }
+
+        let value = argv.get(1).cloned().unwrap_or_else(Value::empty);
+        self.resume_coroutine(co, Resume::Value(value))
+    }
+
+    /// Resumes a coroutine that isn't running, swapping its call stack in while it runs.
+    /// When its command returns, the coroutine command is deleted.
+    fn resume_coroutine(&mut self, co: &Rc<Coroutine>, resume: Resume) -> MoltResult {
+        let mut stack = co.take_stack();
+        let mark = self.scopes.resume_frames(std::mem::take(&mut stack.frames));
+        self.swap_call_stack(&mut stack);
+        self.coroutines.push(Rc::clone(co));
+
+        let outcome = co.transfer(self, resume);
+
+        self.coroutines.pop();
+        self.swap_call_stack(&mut stack);
+        stack.frames = self.scopes.suspend_frames(mark);
+
+        match outcome {
+            Outcome::Yield(value) => {
+                co.save_stack(stack);
+                molt_ok!(value)
+            }
+            Outcome::Done(result) => {
+                if let Some(key) = self.coroutine_key(co) {
+                    self.commands.remove(&key);
+                    self.exec_traces.remove(&key);
+                }
+                result
+            }
+        }
+    }
+
+    /// Swaps the interpreter's call stack state, other than its scopes, with the saved
+    /// state.
+    fn swap_call_stack(&mut self, stack: &mut CallStack) {
+        std::mem::swap(&mut self.scope_argvs, &mut stack.scope_argvs);
+        std::mem::swap(&mut self.ns_stack, &mut stack.ns_stack);
+        std::mem::swap(&mut self.num_levels, &mut stack.num_levels);
+    }
+
+    /// Suspends the current coroutine, as for the `yield` command, returning the value
+    /// from the call that resumed it, and returns the argument of the call that next
+    /// resumes it.  It's an error if there's no current coroutine, or if the coroutine's
+    /// command has been deleted.
+    pub(crate) fn yield_coroutine(&mut self, value: Value) -> MoltResult {
+        let co = match self.coroutines.last() {
+            Some(co) => Rc::clone(co),
+            None => return molt_err!("yield can only be called in a coroutine"),
+        };
+
+        if co.is_killed() {
+            return molt_err!("coroutine has been deleted");
+        }
+
+        match co.suspend(value) {
+            Resume::Value(value) => molt_ok!(value),
+            Resume::Kill => molt_err!("coroutine has been deleted"),
+        }
+    }
+
+    /// Gets the fully qualified name of the current coroutine's command, as for `info
+    /// coroutine`, or None if there's no current coroutine.
+    pub fn coroutine_name(&self) -> Option<String> {
+        let co = self.coroutines.last()?;
+        self.coroutine_key(co).map(|key| format!("::{}", key))
+    }
+
+    /// Gets the command table key of the coroutine's command, if it still exists.
+    fn coroutine_key(&self, co: &Rc<Coroutine>) -> Option<String> {
+        self.commands.iter().find_map(|(key, cmd)| match cmd {
+            CommandEntry::Coroutine(other) if Rc::ptr_eq(co, other) => Some(key.clone()),
+            _ => None,
+        })
+    }
+
+    /// Disposes of a command that has been removed from the command table: a coroutine
+    /// that isn't running is resumed so that `yield` returns an error and it runs to
+    /// completion; a running coroutine's next `yield` returns an error.
+    fn discard_command(&mut self, cmd: CommandEntry) {
+        if let CommandEntry::Coroutine(co) = cmd {
+            co.kill();
+
+            if !co.is_running() {
+                let _ = self.resume_coroutine(&co, Resume::Kill);
+            }
+        }
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Event Processing
+    //
+    // These methods evaluate the scripts scheduled by the `after` command and the channel
+    // handlers registered by `fileevent`, and accept the connections to server sockets.  An
+    // application that uses these must process events, either by calling `do_one_event` as
+    // part of its own main loop, or by calling `run_event_loop`.  The channels are polled
+    // for readiness at short intervals while waiting.
+
+    /// Schedules the script to be evaluated at the global scope after the given delay,
+    /// as for `after ms script`, returning the event's ID.
+    pub fn after(&mut self, delay: Duration, script: Value) -> String {
+        self.events.add_timer(delay, script)
+    }
+
+    /// Schedules the script to be evaluated at the global scope when no other events are
+    /// pending, as for `after idle script`, returning the event's ID.
+    pub fn after_idle(&mut self, script: Value) -> String {
+        self.events.add_idle(script)
+    }
+
+    /// Cancels the pending event with the given ID, if any.
+    pub fn cancel_event(&mut self, id: &str) {
+        self.events.cancel(id);
+    }
+
+    /// Cancels the first pending event whose script is the given script, if any.
+    pub(crate) fn cancel_event_script(&mut self, script: &str) {
+        self.events.cancel_script(script);
+    }
+
+    /// Gets the IDs of the pending events.
+    pub fn event_ids(&self) -> MoltList {
+        self.events.ids()
+    }
+
+    /// Gets the script and type, "timer" or "idle", of the pending event with the given ID.
+    pub fn event_info(&self, id: &str) -> Option<(Value, &'static str)> {
+        self.events
+            .get(id)
+            .map(|event| (event.script.clone(), if event.due.is_some() { "timer" } else { "idle" }))
+    }
+
+    /// Determines whether there are any pending events.
+    pub fn has_pending_events(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    /// Processes a single event: a pending connection to a server socket, if any; otherwise
+    /// a `fileevent` handler whose channel is ready, if any; otherwise a timer event that
+    /// is due, if any; otherwise an idle event, if any; otherwise the next of these, waiting
+    /// until it is due or ready.  Returns false if there were no events to process, and no
+    /// channels awaiting events.
+    ///
+    /// Errors in the event's script are background errors; see `background_error`.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::Interp;
+    /// # use molt::molt_ok;
+    /// # use molt::types::*;
+    /// let mut interp = Interp::new();
+    /// interp.eval("after 10 {set done 1}").unwrap();
+    /// assert!(interp.do_one_event());
+    /// assert_eq!(interp.var("done"), molt_ok!("1"));
+    /// assert!(!interp.do_one_event());
+    /// ```
+    ///
+    /// Waiting for an event ends early, returning false, if the interpreter is
+    /// interrupted; see `interrupt_handle`.
+    pub fn do_one_event(&mut self) -> bool {
+        // Sleep in short intervals, so that connections and interrupts are noticed promptly.
+        const INTERVAL: Duration = Duration::from_millis(10);
+
+        loop {
+            if self.accept_connection() || self.call_file_handler() {
+                return true;
+            }
+
+            let now = Instant::now();
+
+            if let Some(event) = self.events.pop_timer(now) {
+                self.run_event(event);
+                return true;
+            }
+
+            if let Some(event) = self.events.pop_idle() {
+                self.run_event(event);
+                return true;
+            }
+
+            let wait = match self.events.next_due() {
+                Some(due) => (due - now).min(INTERVAL),
+                None if self.channels.awaits_events() => INTERVAL,
+                None => return false,
+            };
+
+            if self.is_interrupted() {
+                return false;
+            }
+
+            thread::sleep(wait);
+        }
+    }
+
+    /// Accepts a pending connection to a server socket, if any, and calls the server's
+    /// command with the new channel and the client's address and port, as an event.
+    /// Returns false if there was no connection.
+    fn accept_connection(&mut self) -> bool {
+        let (mut command, stream, addr) = match self.channels.accept() {
+            Some(connection) => connection,
+            None => return false,
+        };
+
+        let name = self.add_channel("sock", socket::socket_channel(stream));
+        command.push(Value::from(name));
+        command.push(Value::from(addr.ip().to_string()));
+        command.push(Value::from(addr.port() as MoltInt));
+
+        self.run_script(&Value::from(command));
+        true
+    }
+
+    /// Processes events until there are none left; the scripts evaluated may schedule
+    /// more events.  Returns early if the interpreter is interrupted, clearing the
+    /// interrupt.
+    pub fn run_event_loop(&mut self) {
+        while self.do_one_event() {}
+
+        self.interrupted.store(false, Ordering::SeqCst);
+    }
+
+    /// Processes all events that are pending when it is called, without waiting: the
+    /// pending connections to server sockets and the `fileevent` handlers that are ready,
+    /// the timer events that are due, and then the idle events, as for `update`.  Events
+    /// scheduled by the scripts evaluated are left pending.
+    pub fn update(&mut self) {
+        while self.accept_connection() {}
+
+        for _ in 0..self.channels.ready_len() {
+            self.call_file_handler();
+        }
+
+        let now = Instant::now();
+
+        while let Some(event) = self.events.pop_timer(now) {
+            self.run_event(event);
+        }
+
+        self.update_idletasks();
+    }
+
+    /// Processes the idle events that are pending when it is called, as for
+    /// `update idletasks`.  Idle events scheduled by the scripts evaluated are left pending.
+    pub fn update_idletasks(&mut self) {
+        for _ in 0..self.events.idle_len() {
+            match self.events.pop_idle() {
+                Some(event) => self.run_event(event),
+                None => break,
+            }
+        }
+    }
+
+    /// Processes events until the named global variable is written or unset, as for
+    /// `vwait`.  Calls may be nested by the scripts evaluated; each returns only once its
+    /// own variable has been written.
+    ///
+    /// It's an error if there are no events left to process before the variable is
+    /// written, or if the interpreter is interrupted; see `interrupt_handle`.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::Interp;
+    /// # use molt::molt_ok;
+    /// # use molt::types::*;
+    /// let mut interp = Interp::new();
+    /// interp.eval("after 10 {set done 1}").unwrap();
+    /// assert_eq!(interp.vwait("done"), molt_ok!());
+    /// assert_eq!(interp.var("done"), molt_ok!("1"));
+    /// ```
+    pub fn vwait(&mut self, name: &str) -> MoltResult {
+        self.scopes.watch(name);
+
+        let result = loop {
+            if self.scopes.is_written() {
+                break molt_ok!();
+            }
+
+            if self.interrupted.swap(false, Ordering::SeqCst) {
+                break molt_err!("vwait for \"{}\" was interrupted", name);
+            }
+
+            if !self.do_one_event() && !self.is_interrupted() {
+                break molt_err!("can't wait for variable \"{}\": would wait forever", name);
+            }
+        };
+
+        self.scopes.unwatch();
+        result
+    }
+
+    /// Gets a handle that can be used to interrupt the interpreter while it's waiting for
+    /// events, e.g., from another thread or a signal handler.  Setting the flag to true
+    /// makes a pending `vwait` return an error, and `run_event_loop` return; the flag is
+    /// cleared when the interrupt has been handled.
+    ///
+    /// # Example
+    /// ```
+    /// # use molt::Interp;
+    /// # use std::sync::atomic::Ordering;
+    /// let mut interp = Interp::new();
+    /// interp.eval("after 60000 {set done 1}").unwrap();
+    ///
+    /// let handle = interp.interrupt_handle();
+    /// std::thread::spawn(move || handle.store(true, Ordering::SeqCst));
+    ///
+    /// assert!(interp.vwait("done").is_err());
+    /// ```
+    pub fn interrupt_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.interrupted)
+    }
+
+    /// Determines whether the interpreter has been interrupted.
+    fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+
+    /// Calls a `fileevent` handler whose channel is ready, if any.  As in TCL, a handler
+    /// that raises an error is removed, so that it isn't called repeatedly.  Returns false
+    /// if there was no handler ready.
+    fn call_file_handler(&mut self) -> bool {
+        let (name, event, script) = match self.channels.ready_handler() {
+            Some(handler) => handler,
+            None => return false,
+        };
+
+        if !self.run_script(&script) {
+            if let Some(channel) = self.channels.get(&name) {
+                if channel.handler(event) == Some(&script) {
+                    channel.set_handler(event, None);
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Evaluates an event's script at the global scope, in the global namespace.
+    fn run_event(&mut self, event: Event) {
+        self.run_script(&event.script);
+    }
+
+    /// Evaluates a script at the global scope, in the global namespace, as an event,
+    /// returning false if it raised an error.
+    fn run_script(&mut self, script: &Value) -> bool {
+        let result = self.eval_global(&script.as_string());
+
+        if let Err(ResultCode::Error(data)) = result {
+            self.background_error(&data);
+            return false;
+        }
+
+        true
+    }
+
+    /// Handles an error in a script evaluated by the event loop.  The error's info and code
+    /// are saved in `errorInfo` and `errorCode`, and the command `bgerror` is called at the
+    /// global scope with the error message; if there's no `bgerror` command, or it fails,
+    /// the error info is written to standard error.
+    pub fn background_error(&mut self, data: &ErrorData) {
+        self.set_error_vars(data);
+
+        if self.has_command("::bgerror") {
+            let script = list::list_to_string(&[Value::from("::bgerror"), data.message().clone()]);
+
+            if let Err(ResultCode::Error(error)) = self.eval_at_level(0, &script) {
+                self.write_stderr(&format!(
+                    "error in bgerror: {}\n    while handling background error: {}",
+                    error.error_info(),
+                    data.error_info()
+                ));
+            }
+        } else {
+            self.write_stderr(&format!("background error: {}", data.error_info()));
+        }
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Channels
+    //
+    // These methods manage the open channels, which commands like `gets` and `puts` refer
+    // to by name.
+
+    /// Gets the named channel, or the standard error if there is no such open channel.
+    pub(crate) fn channel(&mut self, name: &str) -> Result<&mut Channel, ResultCode> {
+        match self.channels.get(name) {
+            Some(channel) => Ok(channel),
+            None => molt_err!("can not find channel named \"{}\"", name),
+        }
+    }
+
+    /// Adds an open channel, returning its name, the prefix followed by a unique number,
+    /// e.g., "file3".
+    pub(crate) fn add_channel(&mut self, prefix: &str, channel: Channel) -> String {
+        self.channels.add(prefix, channel)
+    }
+
+    /// Removes the named channel, returning it so that the caller can close it.
+    pub(crate) fn remove_channel(&mut self, name: &str) -> Result<Channel, ResultCode> {
+        match self.channels.remove(name) {
+            Some(channel) => Ok(channel),
+            None => molt_err!("can not find channel named \"{}\"", name),
+        }
+    }
+
+    /// Writes the buffered output of all open channels, e.g., before the process exits.
+    pub(crate) fn flush_channels(&mut self) {
+        self.channels.flush_all();
+    }
+
+    /// Directs the `stdout` channel to the writer, so that the client can capture the
+    /// output of `puts` and other commands that write to `stdout`.  Output already buffered
+    /// is first written to the old stream.  If a script has closed `stdout`, it's reopened.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use molt::Interp;
+    /// use std::cell::RefCell;
+    /// use std::io;
+    /// use std::io::Write;
+    /// use std::rc::Rc;
+    ///
+    /// // A writer that appends to a shared buffer.
+    /// struct Capture(Rc<RefCell<Vec<u8>>>);
+    ///
+    /// impl Write for Capture {
+    ///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    ///         self.0.borrow_mut().write(buf)
+    ///     }
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let out = Rc::new(RefCell::new(Vec::new()));
+    /// let err = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.set_stdout(Box::new(Capture(out.clone())));
+    /// interp.set_stderr(Box::new(Capture(err.clone())));
+    /// interp.eval("puts -nonewline Hello; puts \", world!\"; puts stderr Oops").unwrap();
+    ///
+    /// assert_eq!(*out.borrow(), b"Hello, world!\n");
+    /// assert_eq!(*err.borrow(), b"Oops\n");
+    /// ```
+    pub fn set_stdout(&mut self, writer: Box<dyn Write>) {
+        self.channels.set_output("stdout", writer);
+    }
+
+    /// Directs the `stderr` channel to the writer, as [`set_stdout`](#method.set_stdout)
+    /// does the `stdout` channel.  Background errors with no `bgerror` handler are reported
+    /// on the `stderr` channel, and so are captured as well.
+    pub fn set_stderr(&mut self, writer: Box<dyn Write>) {
+        self.channels.set_output("stderr", writer);
+    }
+
+    /// Writes the message and a newline to the `stderr` channel, or to the standard error
+    /// if a script has closed the channel.  Errors are ignored, as there's nowhere to report
+    /// them.
+    fn write_stderr(&mut self, message: &str) {
+        match self.channels.get("stderr") {
+            Some(channel) => {
+                let _ = channel.write_str(&format!("{}\n", message));
+            }
+            None => eprintln!("{}", message),
+        }
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Packages
+    //
+    // These methods manage the packages that are provided, i.e., loaded into the
+    // interpreter, or available to be loaded by `package require`.
+
+    /// Makes a version of a package available: the first time a script requires the
+    /// package, and the version is the latest that satisfies the requirement, the init
+    /// function is called to load it, e.g., by adding its commands.  The package is then
+    /// provided, unless the function returns an error.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    /// use molt::molt_ok;
+    ///
+    /// fn cmd_square(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    ///     let x = argv[1].as_int()?;
+    ///     molt_ok!(x * x)
+    /// }
+    ///
+    /// fn init_mymath(interp: &mut Interp) -> MoltResult {
+    ///     interp.add_command("square", cmd_square);
+    ///     molt_ok!()
+    /// }
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.add_package("mymath", "1.2", init_mymath).unwrap();
+    /// assert!(!interp.has_command("square"));
+    /// assert_eq!(interp.eval("package require mymath 1"), molt_ok!("1.2"));
+    /// assert_eq!(interp.eval("square 5"), molt_ok!(25));
+    /// ```
+    pub fn add_package(&mut self, name: &str, version: &str, init: PackageInit) -> MoltResult {
+        package::parse_version(version)?;
+        self.packages.set_loader(name, version, Loader::Native(init));
+        molt_ok!()
+    }
+
+    /// Makes a version of a package available, to be loaded by evaluating the script at
+    /// the global scope, as for `package ifneeded`.
+    pub(crate) fn add_package_script(&mut self, name: &str, version: &str, script: Value) -> MoltResult {
+        package::parse_version(version)?;
+        self.packages.set_loader(name, version, Loader::Script(script));
+        molt_ok!()
+    }
+
+    /// Gets the script that loads a version of a package, if it was made available by
+    /// `package ifneeded`.
+    pub(crate) fn package_script(&self, name: &str, version: &str) -> Option<Value> {
+        match self.packages.loader(name, version) {
+            Some(Loader::Script(script)) => Some(script.clone()),
+            _ => None,
+        }
+    }
+
+    /// Notes that a version of a package is provided, as for `package provide`.  It's an
+    /// error if a different version is already provided.
+    pub fn provide_package(&mut self, name: &str, version: &str) -> MoltResult {
+        package::parse_version(version)?;
+
+        if let Some(provided) = self.packages.provided(name) {
+            if package::equal(provided, version) {
+                return molt_ok!();
+            }
+
+            return molt_err!(
+                "conflicting versions provided for package \"{}\": {}, then {}",
+                name,
+                provided,
+                version
+            );
+        }
+
+        self.packages.provide(name, version);
+        molt_ok!()
+    }
+
+    /// Gets the provided version of a package, if any.
+    pub fn package_provided(&self, name: &str) -> Option<String> {
+        self.packages.provided(name).map(String::from)
+    }
+
+    /// Requires a package, loading it if it isn't already provided, and returns the version
+    /// that's provided.  If a requirement is given, the version must satisfy it:
+    /// be equal to it, if `exact`, or else as for `package vsatisfies`; otherwise, the latest
+    /// available version is loaded.  It's an error if no acceptable version is available,
+    /// or if loading it fails to provide it.
+    pub fn require_package(&mut self, name: &str, requirement: Option<&str>, exact: bool) -> MoltResult {
+        if let Some(requirement) = requirement {
+            if exact {
+                package::parse_version(requirement)?;
+            } else {
+                package::check_requirement(requirement)?;
+            }
+        }
+
+        let acceptable = |version: &str| match requirement {
+            Some(req) if exact => package::equal(version, req),
+            Some(req) => package::satisfies(version, req),
+            None => true,
+        };
+
+        let need = match requirement {
+            Some(req) if exact => format!(" -exact {}", req),
+            Some(req) => format!(" {}", req),
+            None => String::new(),
+        };
+
+        // FIRST, if the package is provided, its version must be acceptable.
+        if let Some(provided) = self.packages.provided(name) {
+            if acceptable(provided) {
+                return molt_ok!(provided);
+            }
+
+            return molt_err!(
+                "version conflict for package \"{}\": have {}, need{}",
+                name,
+                provided,
+                need
+            );
+        }
+
+        // NEXT, load the latest acceptable version, taking care that a package that
+        // requires itself doesn't recurse forever.
+        let (version, loader) = match self.packages.latest(name, acceptable) {
+            Some(found) => found,
+            None => return molt_err!("can't find package {}{}", name, need),
+        };
+
+        if !self.packages.start_loading(name) {
+            return molt_err!(
+                "circular package dependency: attempt to provide {} {} requires {}",
+                name,
+                version,
+                name
+            );
+        }
+
+        let result = match loader {
+            Loader::Script(script) => self.eval_global(&script.as_string()),
+            Loader::Native(init) => init(self).and_then(|_| {
+                if self.packages.provided(name).is_none() {
+                    self.packages.provide(name, &version);
+                }
+                molt_ok!()
+            }),
+        };
+
+        self.packages.stop_loading(name);
+        result?;
+
+        // NEXT, loading the version must have provided it.
+        match self.packages.provided(name) {
+            Some(provided) if package::equal(provided, &version) => {
+                molt_ok!(provided)
+            }
+            Some(provided) => molt_err!(
+                "attempt to provide package {} {} failed: package {} {} provided instead",
+                name,
+                version,
+                name,
+                provided
+            ),
+            None => molt_err!(
+                "attempt to provide package {} {} failed: no version of package {} provided",
+                name,
+                version,
+                name
+            ),
+        }
+    }
+
+    /// Forgets a package, so that it's neither provided nor available.
+    pub fn forget_package(&mut self, name: &str) {
+        self.packages.forget(name);
+    }
+
+    /// Gets the names of the packages that are provided or available, in sorted order.
+    pub fn package_names(&self) -> MoltList {
+        self.packages.names().iter().map(Value::from).collect()
+    }
+
+    /// Gets the available versions of a package.
+    pub fn package_versions(&self, name: &str) -> MoltList {
+        self.packages.versions(name).iter().map(Value::from).collect()
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Command History
+    //
+    // The history is a list of events, e.g., the commands entered in an interactive shell,
+    // as managed by the `history` command.  Events are numbered from 1 in the order in
+    // which they are added; only the most recent are retained.
+
+    /// Replaces the store for the command history, e.g., with one backed by a line
+    /// editor's history, so that the `history` command and the line editor share the same
+    /// events.  Any events already in the store are numbered from 1, and the store is
+    /// trimmed to the current `history keep` limit.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    ///
+    /// struct Lines(Vec<String>);
+    ///
+    /// impl HistoryStore for Lines {
+    ///     fn add(&mut self, event: &str) -> bool {
+    ///         self.0.push(event.to_string());
+    ///         true
+    ///     }
+    ///     fn get(&self, index: usize) -> Option<String> {
+    ///         self.0.get(index).cloned()
+    ///     }
+    ///     fn len(&self) -> usize {
+    ///         self.0.len()
+    ///     }
+    ///     fn set_max_len(&mut self, len: usize) {
+    ///         let excess = self.0.len().saturating_sub(len);
+    ///         self.0.drain(..excess);
+    ///     }
+    ///     fn clear(&mut self) {
+    ///         self.0.clear();
+    ///     }
+    /// }
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.set_history_store(Box::new(Lines(vec!["set a 1".into()])));
+    /// interp.add_history("set b 2");
+    /// assert_eq!(interp.eval("history event 1"), Ok(Value::from("set a 1")));
+    /// assert_eq!(interp.eval("history nextid"), Ok(Value::from(3)));
+    /// ```
+    pub fn set_history_store(&mut self, store: Box<dyn HistoryStore>) {
+        self.history.set_store(store);
+    }
+
+    /// Adds an event to the command history, if the history store accepts it.
+    pub fn add_history(&mut self, event: &str) {
+        self.history.add(event);
+    }
+
+    /// Gets the event with the given id, if it's retained.
+    pub fn history_event(&self, id: usize) -> Option<String> {
+        self.history.event(id)
+    }
+
+    /// Gets the id of the oldest event retained.  If there are none, it's the id the next
+    /// event will have.
+    pub fn history_first_id(&self) -> usize {
+        self.history.first_id()
+    }
+
+    /// Gets the id of the most recent event, or 0 if there have been none.
+    pub fn history_id(&self) -> usize {
+        self.history.id()
+    }
+
+    /// Gets the maximum number of events the command history retains.
+    pub fn history_keep(&self) -> usize {
+        self.history.keep()
+    }
+
+    /// Sets the maximum number of events the command history retains, dropping the oldest
+    /// events if there are more than that.
+    pub fn set_history_keep(&mut self, keep: usize) {
+        self.history.set_keep(keep);
+    }
+
+    /// Clears the command history.  The next event's id will be 1.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Slave Interpreters
+    //
+    // These methods manage the interpreter's slaves: separate interpreters, each with its
+    // own commands, variables, and channels, that it owns and refers to by name, as
+    // created by `interp create`.  A slave's state is dropped when it's deleted.
+
+    /// Creates a slave interpreter with the standard Molt commands, as for `Interp::new`,
+    /// or, if this is a safe interpreter, a safe slave, as for `Interp::new_safe`.  It's an
+    /// error if the interpreter already has a slave with the name.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.create_slave("child").unwrap();
+    /// interp.slave_eval("child", "set a 1").unwrap();
+    /// assert_eq!(interp.slave_eval("child", "set a"), Ok(Value::from(1)));
+    /// assert!(interp.eval("set a").is_err());
+    /// ```
+    pub fn create_slave(&mut self, name: &str) -> MoltResult {
+        let slave = if self.safe { Interp::new_safe() } else { Interp::new() };
+        self.add_slave(name, slave)
+    }
+
+    /// Creates a safe slave interpreter, as for `Interp::new_safe`.  It's an error if the
+    /// interpreter already has a slave with the name.
+    pub fn create_safe_slave(&mut self, name: &str) -> MoltResult {
+        self.add_slave(name, Interp::new_safe())
+    }
+
+    /// Adds a slave interpreter, returning its name.
+    fn add_slave(&mut self, name: &str, slave: Interp) -> MoltResult {
+        if self.slaves.contains_key(name) {
+            return molt_err!("interpreter named \"{}\" already exists, cannot create", name);
+        }
+
+        self.slaves.insert(name.into(), Box::new(slave));
+        molt_ok!(name)
+    }
+
+    /// Deletes the named slave interpreter, dropping all of its state.  Returns false if
+    /// there is no such slave.
+    pub fn delete_slave(&mut self, name: &str) -> bool {
+        self.slaves.remove(name).is_some()
+    }
+
+    /// Determines whether the interpreter has a slave with the given name.
+    pub fn has_slave(&self, name: &str) -> bool {
+        self.slaves.contains_key(name)
+    }
+
+    /// Gets the named slave interpreter, if any.
+    pub fn slave(&mut self, name: &str) -> Option<&mut Interp> {
+        self.slaves.get_mut(name).map(|slave| &mut **slave)
+    }
+
+    /// Gets a vector of the names of the slave interpreters.
+    pub fn slave_names(&self) -> MoltList {
+        self.slaves.keys().map(Value::from).collect()
+    }
+
+    /// Evaluates a script in the named slave interpreter, at its global scope.  The result,
+    /// including any error, is the slave's result.
+    pub fn slave_eval(&mut self, name: &str, script: &str) -> MoltResult {
+        match self.slaves.get_mut(name) {
+            Some(slave) => slave.eval_global(script),
+            None => molt_err!("could not find interpreter \"{}\"", name),
+        }
     }
 
     //--------------------------------------------------------------------------------------------
@@ -390,6 +2635,92 @@ impl Interp {
         subst_backslashes(str)
     }
 
+    /// Performs backslash, command, and variable substitution on the string, as the
+    /// parser does within a quoted word, returning the result.  Each kind of substitution
+    /// can be disabled independently.
+    ///
+    /// If a command substitution returns `break`, the result is the string as substituted
+    /// up to the beginning of that command substitution; if it returns `continue`, the
+    /// command substitution is replaced by the empty string; and if it returns `return`,
+    /// it is replaced by the returned value.  Errors are returned as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use molt::types::*;
+    /// # use molt::interp::Interp;
+    /// # use molt::molt_ok;
+    /// # fn dummy() -> MoltResult {
+    /// let mut interp = Interp::new();
+    /// interp.set_var("x", "1")?;
+    /// assert_eq!(interp.subst("$x+[expr {$x + 1}]\\n", true, true, true)?.as_string().as_str(),
+    ///     "1+2\n");
+    /// assert_eq!(interp.subst("$x+[expr {$x + 1}]", true, false, true)?.as_string().as_str(),
+    ///     "1+[expr {1 + 1}]");
+    /// # molt_ok!()
+    /// # }
+    /// ```
+    pub fn subst(
+        &mut self,
+        str: &str,
+        backslashes: bool,
+        commands: bool,
+        variables: bool,
+    ) -> MoltResult {
+        let mut ctx = Context::new(str);
+        let mut result = String::new();
+
+        while !ctx.at_end() {
+            if commands && ctx.next_is('[') {
+                // FIRST, evaluate the script up to the matching ']'.
+                ctx.skip_char('[');
+                ctx.set_bracket_term(true);
+                let value = self.eval_context(&mut ctx);
+
+                // NEXT, after a continue or return, skip the rest of the script.
+                let value = match value {
+                    Ok(value) => value,
+                    Err(ResultCode::Break) => break,
+                    Err(ResultCode::Continue) => {
+                        self.skip_script(&mut ctx)?;
+                        Value::empty()
+                    }
+                    Err(ResultCode::Return(data)) => {
+                        self.skip_script(&mut ctx)?;
+                        data.value().clone()
+                    }
+                    Err(code) => return Err(code),
+                };
+                ctx.set_bracket_term(false);
+
+                // NEXT, make sure there's a closing bracket.
+                if !ctx.next_is(']') {
+                    return molt_err!("missing close-bracket");
+                }
+                ctx.next();
+
+                result.push_str(&value.as_string());
+            } else if variables && ctx.next_is('$') {
+                result.push_str(&self.parse_variable(&mut ctx)?.as_string());
+            } else if backslashes && ctx.next_is('\\') {
+                subst_backslash(&mut ctx, &mut result);
+            } else {
+                result.push(ctx.next().unwrap());
+            }
+        }
+
+        molt_ok!(result)
+    }
+
+    /// Skips the remainder of a script, parsing but not evaluating its commands.
+    fn skip_script(&mut self, ctx: &mut Context) -> Result<(), ResultCode> {
+        ctx.set_no_eval(true);
+        let result = self.eval_context(ctx);
+        ctx.set_no_eval(false);
+
+        result.map(|_| ())
+    }
+
     //--------------------------------------------------------------------------------------------
     // The Molt Parser
     //
@@ -402,7 +2733,24 @@ impl Interp {
         let mut result_value = Value::empty();
 
         while !ctx.at_end_of_script() {
-            let words = self.parse_command(ctx)?;
+            // FIRST, find the beginning of the next command, noting its line number and
+            // position for use in error reporting.
+            self.skip_to_command(ctx);
+            let line = ctx.line();
+            let start = ctx.pos();
+
+            let words = match self.parse_command(ctx) {
+                Ok(words) => words,
+                Err(code) => {
+                    // The error arose while parsing the command, perhaps in a command
+                    // substitution; if so, only the beginning of the command is known.
+                    let mut command = ctx.text(start, ctx.pos()).unwrap_or_default().to_string();
+                    if !ctx.at_end() {
+                        command.push_str("...");
+                    }
+                    return Err(log_command(code, line, &command));
+                }
+            };
 
             if words.is_empty() {
                 break;
@@ -413,25 +2761,140 @@ impl Interp {
                 continue;
             }
 
-            // FIRST, convert to Vec<&str>
-            let name = &*words[0].as_string();
-            if let Some(cmd) = self.commands.get(name) {
-                let cmd = Rc::clone(cmd);
-                let result = cmd.execute(self, words.as_slice());
-                match result {
-                    Ok(v) => result_value = v,
-                    _ => return result,
+            let end = ctx.pos();
+
+            match self.invoke(words.as_slice()) {
+                Ok(v) => result_value = v,
+                Err(code) => {
+                    let command = match ctx.text(start, end) {
+                        Some(text) => Cow::from(text),
+                        None => Cow::from(list::list_to_string(&words)),
+                    };
+                    return Err(log_command(code, line, &command));
                 }
+            }
+        }
+
+        Ok(result_value)
+    }
+
+    /// Invokes the command named by the first word of `argv` with the remaining words as
+    /// its arguments, as though it were a command in a script, and returns its result.
+    pub(crate) fn invoke(&mut self, argv: &[Value]) -> MoltResult {
+        let name = &*argv[0].as_string();
+
+        if let Some(cmd) = self.find_command(name) {
+            let cmd = cmd.clone();
+            self.cmd_count += 1;
+
+            if self.is_exec_traced(name) {
+                self.execute_traced(&cmd, argv)
             } else {
-                return molt_err!("invalid command name \"{}\"", name);
+                cmd.execute(self, argv)
+            }
+        } else {
+            self.call_unknown(argv)
+        }
+    }
+
+    /// Handles a command that wasn't found: calls the unknown handler, if one is set, with
+    /// the command's words, or else the global `unknown` command, if it exists, with the
+    /// words as its arguments, returning its result in place of the command's.  Otherwise,
+    /// it's an error.  Calls made while handling an unknown command count toward a limit,
+    /// so that a handler that calls a still-missing command can't loop forever.
+    fn call_unknown(&mut self, argv: &[Value]) -> MoltResult {
+        if self.unknown_handler.is_none() && !self.has_command("::unknown") {
+            return molt_err!("invalid command name \"{}\"", argv[0]);
+        }
+
+        if self.unknown_depth >= MAX_UNKNOWN_DEPTH {
+            return molt_err!(
+                "too many nested calls to unknown (infinite loop?) looking for \"{}\"",
+                argv[0]
+            );
+        }
+
+        self.unknown_depth += 1;
+
+        let result = match self.unknown_handler {
+            Some(func) => func(self, argv),
+            None => {
+                let mut cmd = vec![Value::from("::unknown")];
+                cmd.extend_from_slice(argv);
+                self.invoke(&cmd)
+            }
+        };
+
+        self.unknown_depth -= 1;
+        result
+    }
+
+    /// Sets or, given `None`, clears the function to call when a command isn't found.
+    /// The function is called with the command's words, the first being the name of the
+    /// missing command, and its result is used in place of the command's.  While the
+    /// handler is set, it's called instead of the `unknown` command.
+    ///
+    /// # Example
+    /// ```
+    /// use molt::types::*;
+    /// use molt::Interp;
+    /// use molt::molt_ok;
+    ///
+    /// fn echo_unknown(_interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    ///     molt_ok!(Value::from(argv.to_vec()))
+    /// }
+    ///
+    /// let mut interp = Interp::new();
+    /// interp.set_unknown_handler(Some(echo_unknown));
+    /// assert_eq!(interp.eval("nonesuch a b"), molt_ok!("nonesuch a b"));
+    /// ```
+    pub fn set_unknown_handler(&mut self, handler: Option<CommandFunc>) {
+        self.unknown_handler = handler;
+    }
+
+    /// Invokes a command on behalf of a proc that called `tailcall`, in the proc's caller's
+    /// scope.  If the command is itself a proc that calls `tailcall`, the next command is
+    /// invoked in turn, and so on, without growing the Rust call stack.
+    fn tail_call(&mut self, mut argv: MoltList) -> MoltResult {
+        loop {
+            // Traced commands are invoked in the usual way, so that their traces are called.
+            if self.is_exec_traced(&argv[0].as_string()) {
+                return self.invoke(&argv);
+            }
+
+            let cmd = match self.find_command(&argv[0].as_string()) {
+                Some(cmd) => cmd.clone(),
+                None => return self.call_unknown(&argv),
+            };
+
+            self.cmd_count += 1;
+
+            match cmd {
+                CommandEntry::Proc(proc) => match proc.call_frame(self, &argv, CallKind::Proc) {
+                    Err(ResultCode::TailCall(next)) => argv = next,
+                    result => return result,
+                },
+                CommandEntry::Native(cmd) => return cmd.execute(self, &argv),
+                CommandEntry::Alias(target) => return self.call_alias(&target, &argv),
+                CommandEntry::Coroutine(co) => return self.call_coroutine(&co, &argv),
             }
         }
+    }
+
+    /// Gets the command to be invoked by the `tailcall` command: the given command and its
+    /// arguments, with the command name resolved in the current namespace.
+    pub(crate) fn tail_command(&self, argv: &[Value]) -> MoltList {
+        let mut cmd = argv.to_vec();
+
+        if let Some(key) = self.command_key(&argv[0].as_string()) {
+            cmd[0] = Value::from(format!("::{}", key));
+        }
 
-        Ok(result_value)
+        cmd
     }
 
-    fn parse_command(&mut self, ctx: &mut Context) -> Result<MoltList, ResultCode> {
-        // FIRST, deal with whitespace and comments between "here" and the next command.
+    /// Skips the whitespace and comments between "here" and the next command.
+    fn skip_to_command(&mut self, ctx: &mut Context) {
         while !ctx.at_end_of_script() {
             ctx.skip_block_white();
 
@@ -442,7 +2905,9 @@ impl Interp {
                 break;
             }
         }
+    }
 
+    fn parse_command(&mut self, ctx: &mut Context) -> Result<MoltList, ResultCode> {
         let mut words = Vec::new();
 
         // Read words until we get to the end of the line or hit an error
@@ -604,16 +3069,30 @@ impl Interp {
 
         // NEXT, make sure this is really a variable reference.  If it isn't
         // just return a "$".
-        if !ctx.next_is_varname_char() && !ctx.next_is('{') {
+        if !ctx.next_is_varname_char() && !ctx.next_is('{') && !ctx.next_is_namespace_sep() {
             return Ok(Value::from("$"));
         }
 
-        // NEXT, get the variable name
+        // NEXT, get the variable name.  It may contain namespace separators.
         let mut varname = String::new();
 
-        if ctx.next_is_varname_char() {
-            while ctx.next_is_varname_char() {
-                varname.push(ctx.next().unwrap());
+        if !ctx.next_is('{') {
+            loop {
+                if ctx.next_is_varname_char() {
+                    varname.push(ctx.next().unwrap());
+                } else if ctx.next_is_namespace_sep() {
+                    while ctx.next_is(':') {
+                        varname.push(ctx.next().unwrap());
+                    }
+                } else {
+                    break;
+                }
+            }
+
+            // NEXT, if there's an array index, get it.
+            if ctx.next_is('(') {
+                let index = self.parse_var_index(ctx)?;
+                return self.element(&varname, &index.as_string());
             }
         } else if ctx.next_is('{') {
             ctx.skip_char('{');
@@ -623,6 +3102,34 @@ impl Interp {
         Ok(self.var(&varname)?)
     }
 
+    /// Parses an array index in a variable reference, e.g., the "(x)" in "$a(x)", doing
+    /// the usual variable, command, and backslash substitutions.
+    fn parse_var_index(&mut self, ctx: &mut Context) -> MoltResult {
+        // FIRST, skip the '('
+        ctx.skip_char('(');
+
+        // NEXT, add characters to the index until we reach the close paren.
+        let mut index = String::new();
+
+        while !ctx.at_end() {
+            // Note: the while condition ensures that there's a character.
+            if ctx.next_is('[') {
+                index.push_str(&self.parse_script(ctx)?.as_string());
+            } else if ctx.next_is('$') {
+                index.push_str(&self.parse_variable(ctx)?.as_string());
+            } else if ctx.next_is('\\') {
+                subst_backslash(ctx, &mut index);
+            } else if !ctx.next_is(')') {
+                index.push(ctx.next().unwrap());
+            } else {
+                ctx.skip_char(')');
+                return Ok(Value::from(index));
+            }
+        }
+
+        molt_err!("missing )")
+    }
+
     fn parse_braced_varname(&self, ctx: &mut Context) -> MoltResult {
         let mut string = String::new();
 
@@ -640,6 +3147,32 @@ impl Interp {
     }
 }
 
+impl Drop for Interp {
+    // Kills the suspended coroutines, so that `yield` returns an error and they run to
+    // completion while the interpreter still exists.
+    fn drop(&mut self) {
+        loop {
+            let key = self.commands.iter().find_map(|(key, cmd)| match cmd {
+                CommandEntry::Coroutine(_) => Some(key.clone()),
+                _ => None,
+            });
+
+            match key.and_then(|key| self.commands.remove(&key)) {
+                Some(cmd) => self.discard_command(cmd),
+                None => break,
+            }
+        }
+
+        let hidden: Vec<String> = self.hidden.keys().cloned().collect();
+
+        for name in hidden {
+            if let Some(cmd) = self.hidden.remove(&name) {
+                self.discard_command(cmd);
+            }
+        }
+    }
+}
+
 /// A struct that wraps a CommandFunc and implements the Command trait.
 struct CommandFuncWrapper {
     func: CommandFunc,
@@ -657,23 +3190,163 @@ impl Command for CommandFuncWrapper {
     }
 }
 
+/// An entry in the command table.  Procs and aliases are distinguished from other commands
+/// so that they can be introspected.
+#[derive(Clone)]
+enum CommandEntry {
+    Native(Rc<dyn Command>),
+    Proc(Rc<CommandProc>),
+    Alias(Rc<MoltList>),
+    Coroutine(Rc<Coroutine>),
+}
+
+impl CommandEntry {
+    fn execute(&self, interp: &mut Interp, argv: &[Value]) -> MoltResult {
+        match self {
+            CommandEntry::Native(cmd) => cmd.execute(interp, argv),
+            CommandEntry::Proc(cmd) => cmd.execute(interp, argv),
+            CommandEntry::Alias(target) => interp.call_alias(target, argv),
+            CommandEntry::Coroutine(co) => interp.call_coroutine(co, argv),
+        }
+    }
+}
+
+/// An operation on a command that can be traced by an execution trace.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum ExecOp {
+    Enter,
+    Leave,
+}
+
+impl ExecOp {
+    /// The operations, in the order in which `trace info execution` reports them.
+    pub const ALL: [ExecOp; 2] = [ExecOp::Enter, ExecOp::Leave];
+
+    /// Gets the operation's name, as passed to trace commands.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExecOp::Enter => "enter",
+            ExecOp::Leave => "leave",
+        }
+    }
+}
+
+/// An execution trace on a command: a command prefix to call before or after the command
+/// executes.
+#[derive(Debug, Clone)]
+pub(crate) struct ExecTrace {
+    pub ops: Vec<ExecOp>,
+    pub command: Value,
+}
+
+impl ExecTrace {
+    /// Determines whether the trace has the same operations and command as another.
+    fn matches(&self, ops: &[ExecOp], command: &str) -> bool {
+        ExecOp::ALL.iter().all(|op| self.ops.contains(op) == ops.contains(op))
+            && *self.command.as_string() == command
+    }
+}
+
+/// A namespace context: the namespace in which commands are being evaluated, the scope
+/// level at which the context was pushed, and the scope level at which unqualified
+/// variable names refer to the namespace's variables, if any.  Procs push contexts with
+/// no such level: their unqualified variable names refer to local variables.
+#[derive(Clone)]
+pub(crate) struct NsContext {
+    name: Rc<str>,
+    level: usize,
+    var_level: Option<usize>,
+}
+
 // Context structure for a proc.
+#[derive(Debug)]
 struct CommandProc {
     args: MoltList,
-    body: String
+    body: String,
+    // The fully qualified name of the namespace in which the proc is defined.
+    namespace: Rc<str>,
+}
+
+/// How a `CommandProc` is called: as a named proc, `name ?arg ...?`, or as the lambda
+/// expression of the `apply` command, `apply lambdaExpr ?arg ...?`.
+#[derive(Clone, Copy, PartialEq)]
+enum CallKind {
+    Proc,
+    Lambda,
 }
 
 // TODO: Need to work out how we're going to store the CommandProc details for
 // best efficiency.
 impl Command for CommandProc {
     fn execute(&self, interp: &mut Interp, argv: &[Value]) -> MoltResult {
-        let name = &*argv[0].as_string();
+        self.call(interp, argv, CallKind::Proc)
+    }
+}
 
-        // FIRST, push the proc's local scope onto the stack.
-        interp.push_scope();
+impl CommandProc {
+    /// Calls the proc with the given arguments.  If the proc calls `tailcall`, the
+    /// tail-called command's result is the proc's result.
+    fn call(&self, interp: &mut Interp, argv: &[Value], kind: CallKind) -> MoltResult {
+        match self.call_frame(interp, argv, kind) {
+            Err(ResultCode::TailCall(cmd)) => interp.tail_call(cmd),
+            result => result,
+        }
+    }
+
+    /// Calls the proc with the given arguments in a new stack frame, returning the result
+    /// of the proc's body.  If the body calls `tailcall`, the result is a `TailCall`, to be
+    /// invoked once the frame has been popped.
+    fn call_frame(&self, interp: &mut Interp, argv: &[Value], kind: CallKind) -> MoltResult {
+        // FIRST, push the proc's local scope onto the stack, and make its namespace
+        // current.
+        interp.push_proc_scope(argv);
+        interp.ns_stack.push(NsContext {
+            name: Rc::clone(&self.namespace),
+            level: interp.scopes.current(),
+            var_level: None,
+        });
+
+        // NEXT, assign the arguments to the parameters and evaluate the proc's body,
+        // getting the result.
+        // If the body throws an error, note the proc in the error info.
+        let result = match self.bind_args(interp, argv, kind) {
+            Ok(()) => match interp.eval(&self.body) {
+                Err(ResultCode::Error(mut data)) => {
+                    let line = data.error_line();
+                    let context = match kind {
+                        CallKind::Proc => format!("procedure \"{}\"", argv[0]),
+                        CallKind::Lambda => format!("lambda term \"{}\"", truncate(&argv[1].as_string(), MAX_LOGGED_LAMBDA)),
+                    };
+                    data.add_error_info(&format!("\n    ({} line {})", context, line));
+                    Err(ResultCode::Error(data))
+                }
+                result => result,
+            },
+            Err(code) => Err(code),
+        };
 
-        // NEXT, process the proc's argument list.
-        let mut argi = 1; // Skip the proc's name
+        // NEXT, pop the scope and namespace off of the stacks; we're done with them.
+        interp.ns_stack.pop();
+        interp.pop_scope();
+
+        // NEXT, return the computed result.
+        // Note: no need for special handling for return, break, continue;
+        // interp.eval() has already returned one level, and converted bare break and
+        // continue results to errors.
+        result
+    }
+
+    /// Assigns the proc's arguments to its parameters in the current scope.
+    fn bind_args(&self, interp: &mut Interp, argv: &[Value], kind: CallKind) -> Result<(), ResultCode> {
+        // The command as it appears in a wrong # args message.
+        let usage = || match kind {
+            CallKind::Proc => argv[0].to_string(),
+            CallKind::Lambda => format!("{} lambdaExpr", argv[0]),
+        };
+
+        // FIRST, process the proc's argument list.
+        // Skip the proc's name, or the apply command and its lambda.
+        let mut argi = if kind == CallKind::Proc { 1 } else { 2 };
 
         // TODO: Ugly; need better tools.
         let str_args: Vec<String> = self.args.iter().map(|v| v.to_string()).collect();
@@ -695,7 +3368,7 @@ impl Command for CommandProc {
                 } else {
                     "".into()
                 };
-                interp.set_var("args", &args);
+                interp.set_var("args", &args)?;
 
                 // We've processed all of the args
                 argi = argv.len();
@@ -705,41 +3378,205 @@ impl Command for CommandProc {
             // NEXT, do we have a matching argument?
              if argi < argv.len() {
                 // Pair them up
-                interp.set_var3(&*vec[0].as_string(), &argv[argi]);
+                interp.set_var3(&*vec[0].as_string(), &argv[argi])?;
                 argi += 1;
                 continue;
             }
 
             // NEXT, do we have a default value?
             if vec.len() == 2 {
-                interp.set_var3(&*vec[0].as_string(), &vec[1]);
+                interp.set_var3(&*vec[0].as_string(), &vec[1])?;
             } else {
                 // We don't; we're missing a required argument.
-                return wrong_num_args_for_proc(name, &str_args);
+                return wrong_num_args_for_proc(&usage(), &str_args);
             }
         }
 
         // NEXT, do we have any arguments left over?
 
         if argi != argv.len() {
-            return wrong_num_args_for_proc(name, &str_args);
+            return wrong_num_args_for_proc(&usage(), &str_args);
         }
 
-        // NEXT, evaluate the proc's body, getting the result.
-        let result = interp.eval(&self.body);
+        Ok(())
+    }
+}
 
-        // NEXT, pop the scope off of the stack; we're done with it.
-        interp.pop_scope();
+/// A lambda expression, as used by the `apply` command: a list of two or three elements,
+/// the argument list, the body, and optionally the namespace in which the body is to be
+/// evaluated, relative to the global namespace.  The parsed form is saved in the lambda
+/// expression's `Value`, so that the lambda needn't be parsed each time it is applied.
+#[derive(Debug)]
+pub(crate) struct Lambda(CommandProc);
 
-        // NEXT, return the computed result.
-        // Note: no need for special handling for return, break, continue;
-        // interp.eval() returns only Ok or a real error.
-        result
+impl Lambda {
+    /// Parses a lambda expression.
+    fn parse(str: &str) -> Result<Self, ResultCode> {
+        let vec = list::get_list(str)?;
+
+        if vec.len() != 2 && vec.len() != 3 {
+            return molt_err!("can't interpret \"{}\" as a lambda expression", str);
+        }
+
+        let args = vec[0].as_list()?.to_vec();
+        check_arg_specs(&args)?;
+
+        let namespace = match vec.get(2) {
+            Some(ns) => qualify("::", &ns.as_string()),
+            None => "::".into(),
+        };
+
+        Ok(Lambda(CommandProc {
+            args,
+            body: vec[1].to_string(),
+            namespace: namespace.into(),
+        }))
+    }
+}
+
+impl FromStr for Lambda {
+    type Err = ResultCode;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        Lambda::parse(str)
+    }
+}
+
+impl fmt::Display for Lambda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let vec = vec![
+            Value::from(self.0.args.clone()),
+            Value::from(&self.0.body),
+            Value::from(&*self.0.namespace),
+        ];
+        write!(f, "{}", list::list_to_string(&vec))
+    }
+}
+
+/// Qualifies the name relative to the fully qualified namespace, unless it's already
+/// fully qualified.
+fn qualify(namespace: &str, name: &str) -> String {
+    if name.starts_with("::") {
+        name.to_string()
+    } else if namespace == "::" {
+        format!("::{}", name)
+    } else {
+        format!("{}::{}", namespace, name)
+    }
+}
+
+/// Gets the fully qualified name of the parent of the fully qualified namespace, or
+/// "::" if none.
+fn parent_namespace(namespace: &str) -> &str {
+    match namespace.rfind("::") {
+        Some(0) | None => "::",
+        Some(i) => &namespace[..i],
+    }
+}
+
+/// Gets the fully qualified name of the namespace of the command with the given command
+/// table key, e.g., "::foo" for "foo::bar" or "::" for "bar".
+fn namespace_of(key: &str) -> String {
+    match key.rfind("::") {
+        Some(i) => format!("::{}", &key[..i]),
+        None => "::".into(),
+    }
+}
+
+/// The maximum number of nested calls to the unknown handler or command.
+const MAX_UNKNOWN_DEPTH: usize = 100;
+
+/// The maximum number of bytes of a command's text to include in the error info.
+const MAX_LOGGED_COMMAND: usize = 150;
+
+/// The maximum number of bytes of a lambda expression to include in the error info.
+const MAX_LOGGED_LAMBDA: usize = 60;
+
+/// Truncates text to at most the given number of bytes, adding an ellipsis if anything
+/// was removed.
+fn truncate(text: &str, max: usize) -> Cow<'_, str> {
+    if text.len() <= max {
+        return Cow::from(text);
+    }
+
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Cow::from(format!("{}...", &text[..end]))
+}
+
+/// Records the command that produced an error, and its line number, in the error data.  Each
+/// enclosing script overwrites the line number as the error propagates, so that whoever
+/// catches the error sees the line number within the script it evaluated.
+fn log_command(mut code: ResultCode, line: usize, command: &str) -> ResultCode {
+    if let ResultCode::Error(data) = &mut code {
+        data.set_error_line(line);
+
+        let command = command.trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+        data.log_command(&truncate(command, MAX_LOGGED_COMMAND));
+    }
+
+    code
+}
+
+/// Translates the result of evaluating a script to the result of the script as a whole:
+/// a return returns one level, and there's nowhere for a break, continue, tail call, or
+/// application-defined result code to go.
+fn script_result(result: MoltResult) -> MoltResult {
+    let result = match result {
+        Err(ResultCode::Return(data)) => data.return_level(),
+        _ => result,
+    };
+
+    match result {
+        Err(ResultCode::Break) => {
+            molt_err!("invoked \"break\" outside of a loop")
+        }
+        Err(ResultCode::Continue) => {
+            molt_err!("invoked \"continue\" outside of a loop")
+        }
+        Err(ResultCode::TailCall(_)) => {
+            molt_err!("tailcall can only be called from a proc or lambda")
+        }
+        Err(ResultCode::Other(code, _)) => {
+            molt_err!("command returned bad code: {}", code)
+        }
+        _ => result
+    }
+}
+
+/// Splits a variable name of the form "*arrayName*(*index*)" into the array name and
+/// the index.  Any other name is returned as is, with no index.
+pub(crate) fn parse_var_name(name: &str) -> (&str, Option<&str>) {
+    if name.ends_with(')') {
+        if let Some(open) = name.find('(') {
+            return (&name[..open], Some(&name[open + 1..name.len() - 1]));
+        }
+    }
+
+    (name, None)
+}
+
+/// Validates a proc's argument specifications: each must be a list of one or two
+/// elements, the argument name and its default value.
+pub(crate) fn check_arg_specs(args: &[Value]) -> Result<(), ResultCode> {
+    for arg in args {
+        let vec = arg.as_list()?;
+
+        if vec.is_empty() {
+            return molt_err!("argument with no name");
+        } else if vec.len() > 2 {
+            return molt_err!("too many fields in argument specifier \"{}\"", arg);
+        }
     }
+
+    Ok(())
 }
 
 // TODO: This almost certainly needs to be refactored to use Values.
-fn wrong_num_args_for_proc(name: &str, args: &[String]) -> MoltResult {
+fn wrong_num_args_for_proc(name: &str, args: &[String]) -> Result<(), ResultCode> {
     let mut msg = String::new();
     msg.push_str("wrong # args: should be \"");
     msg.push_str(name);
@@ -886,6 +3723,344 @@ mod tests {
             molt_err!("too many nested calls to Interp::eval (infinite loop?)"));
     }
 
+    #[test]
+    fn test_cmd_count() {
+        let mut interp = Interp::new();
+        assert_eq!(interp.cmd_count(), 0);
+
+        assert!(interp.eval("proc myproc {} { set a 1; set b 2 }").is_ok());
+        assert_eq!(interp.cmd_count(), 1);
+
+        // The proc call, the two commands in its body, and the command substitution.
+        assert!(interp.eval("set c [myproc]").is_ok());
+        assert_eq!(interp.cmd_count(), 5);
+
+        // Invalid commands aren't counted.
+        assert!(interp.eval("nonesuch").is_err());
+        assert_eq!(interp.cmd_count(), 5);
+    }
+
+    #[test]
+    fn test_eval_return() {
+        let mut interp = Interp::new();
+
+        assert_eq!(interp.eval("return foo"), molt_ok!("foo"));
+        assert_eq!(interp.eval("return -code break"),
+            molt_err!("invoked \"break\" outside of a loop"));
+        assert_eq!(interp.eval("return -code error foo"), molt_err!("foo"));
+
+        match interp.eval("return -level 2 foo") {
+            Err(ResultCode::Return(data)) => {
+                assert_eq!(data.level(), 1);
+                assert_eq!(data.value(), &Value::from("foo"));
+                assert_eq!(data.code(), None);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_do_one_event() {
+        let mut interp = Interp::new();
+        assert!(!interp.has_pending_events());
+        assert!(!interp.do_one_event());
+
+        // Timers are processed in order of time due, and then idle events.
+        interp.eval("after idle {lappend log idle}").unwrap();
+        interp.eval("after 20 {lappend log timer2}").unwrap();
+        interp.eval("after 0 {lappend log timer1}").unwrap();
+        assert!(interp.has_pending_events());
+
+        assert!(interp.do_one_event());
+        assert_eq!(interp.var("log"), molt_ok!("timer1"));
+        assert!(interp.do_one_event());
+        assert_eq!(interp.var("log"), molt_ok!("timer1 idle"));
+        assert!(interp.do_one_event());
+        assert_eq!(interp.var("log"), molt_ok!("timer1 idle timer2"));
+        assert!(!interp.do_one_event());
+        assert!(!interp.has_pending_events());
+    }
+
+    #[test]
+    fn test_run_event_loop() {
+        let mut interp = Interp::new();
+
+        // Events run at the global scope, and may schedule further events.
+        interp.eval("proc tick {n} { if {$n > 0} { after 1 [list tick [expr {$n - 1}]] }; lappend ::log $n }").unwrap();
+        interp.eval("proc sched {} { set log local; after idle {lappend log idle} }").unwrap();
+        interp.eval("sched; tick 3").unwrap();
+
+        interp.run_event_loop();
+        assert_eq!(interp.var("log"), molt_ok!("3 idle 2 1 0"));
+        assert!(!interp.has_pending_events());
+    }
+
+    #[test]
+    fn test_background_error() {
+        let mut interp = Interp::new();
+        interp.eval("proc bgerror {msg} { lappend ::errors $msg }").unwrap();
+        interp.eval("after 0 {error first}; after 0 {set x ok}; after 0 {break}").unwrap();
+
+        // Errors are background errors; processing continues.
+        interp.run_event_loop();
+        assert_eq!(interp.var("x"), molt_ok!("ok"));
+        assert_eq!(interp.var("errors"), molt_ok!("first {invoked \"break\" outside of a loop}"));
+        assert_eq!(interp.var("errorCode"), molt_ok!("NONE"));
+    }
+
+    #[test]
+    fn test_rand() {
+        let mut interp1 = Interp::new();
+        let mut interp2 = Interp::new();
+        interp1.srand(7);
+        interp2.srand(7);
+
+        for _ in 0..100 {
+            let value = interp1.rand();
+            assert!((0.0..1.0).contains(&value));
+            assert_eq!(interp2.rand().to_bits(), value.to_bits());
+        }
+
+        // The generator's fixed points are avoided.
+        interp1.srand(0);
+        assert!(interp1.rand() > 0.0);
+        interp1.srand(0x7fff_ffff);
+        assert!(interp1.rand() > 0.0);
+    }
+
+    #[test]
+    fn test_set_output() {
+        use std::cell::RefCell;
+        use std::io;
+
+        struct Capture(Rc<RefCell<Vec<u8>>>);
+
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let out = Rc::new(RefCell::new(Vec::new()));
+        let err = Rc::new(RefCell::new(Vec::new()));
+        let mut interp = Interp::new();
+        interp.set_stderr(Box::new(Capture(err.clone())));
+
+        // Background errors are reported on the stderr channel.
+        interp.eval("after 0 {error oops}").unwrap();
+        interp.run_event_loop();
+        assert!(String::from_utf8_lossy(&err.borrow()).starts_with("background error: oops\n"));
+
+        // A closed stdout is reopened, with its standard configuration.
+        interp.eval("close stdout").unwrap();
+        interp.set_stdout(Box::new(Capture(out.clone())));
+        interp.eval("puts -nonewline a; puts b; puts stdout c").unwrap();
+        assert_eq!(*out.borrow(), b"ab\nc\n");
+        assert_eq!(interp.eval("fconfigure stdout -buffering"), molt_ok!("line"));
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut interp = Interp::new();
+        let argv = vec![
+            Value::from("apply"),
+            Value::from("{x {y 2}} {expr {$x * $y}}"),
+            Value::from(3),
+        ];
+
+        assert_eq!(interp.apply(&argv), molt_ok!(6));
+
+        // The parsed lambda is saved in the lambda's value.
+        let lambda = argv[1].as_other::<Lambda>().unwrap();
+        assert_eq!(interp.apply(&argv), molt_ok!(6));
+        assert!(Rc::ptr_eq(&lambda, &argv[1].as_other::<Lambda>().unwrap()));
+
+        let argv = vec![Value::from("apply"), Value::from("x")];
+        assert_eq!(interp.apply(&argv),
+            molt_err!("can't interpret \"x\" as a lambda expression"));
+    }
+
+    #[test]
+    fn test_slaves() {
+        let mut interp = Interp::new();
+        assert_eq!(interp.create_slave("child"), molt_ok!("child"));
+        assert!(interp.create_slave("child").is_err());
+        assert!(interp.has_slave("child"));
+        assert_eq!(interp.slave_names(), vec![Value::from("child")]);
+
+        // The slave's state is its own, and its errors are returned as is.
+        interp.set_var("a", "master").unwrap();
+        assert_eq!(interp.slave_eval("child", "set a slave"), molt_ok!("slave"));
+        assert_eq!(interp.var("a"), molt_ok!("master"));
+        assert_eq!(interp.slave_eval("child", "error oops"), molt_err!("oops"));
+        assert_eq!(
+            interp.slave_eval("nonesuch", "set a"),
+            molt_err!("could not find interpreter \"nonesuch\"")
+        );
+
+        assert!(interp.delete_slave("child"));
+        assert!(!interp.delete_slave("child"));
+        assert!(!interp.has_slave("child"));
+    }
+
+    #[test]
+    fn test_coroutines_killed_on_drop() {
+        use std::cell::RefCell;
+        use std::io;
+
+        struct Capture(Rc<RefCell<Vec<u8>>>);
+
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let out = Rc::new(RefCell::new(Vec::new()));
+
+        // The interpreter is dropped in place: one that has moved can't resume its
+        // coroutines.
+        {
+            let mut interp = Interp::new();
+            interp.set_stdout(Box::new(Capture(out.clone())));
+            interp.eval("proc gen {} {catch {yield} msg; puts $msg}; coroutine co gen").unwrap();
+        }
+
+        assert_eq!(*out.borrow(), b"coroutine has been deleted\n");
+    }
+
+    #[test]
+    fn test_coroutine_in_moved_interp() {
+        let mut interp = Interp::new();
+        interp.eval("proc gen {} {while 1 {yield}}; coroutine co gen").unwrap();
+
+        let mut moved = Box::new(interp);
+        assert_eq!(moved.eval("co"), molt_err!("can't resume coroutine: its interpreter has moved"));
+        assert!(!moved.has_command("co"));
+    }
+
+    #[test]
+    fn test_hidden_commands() {
+        let mut interp = Interp::new();
+        let argv = [Value::from("mylist"), Value::from("a")];
+
+        interp.hide_command("list", "mylist").unwrap();
+        assert!(!interp.has_command("list"));
+        assert_eq!(interp.hidden_names(), vec![Value::from("mylist")]);
+        assert_eq!(interp.invoke_hidden(&argv), molt_ok!("a"));
+        assert!(interp.hide_command("nonesuch", "x").is_err());
+        assert!(interp.expose_command("nonesuch", "x").is_err());
+
+        interp.expose_command("mylist", "list").unwrap();
+        assert_eq!(interp.eval("list a"), molt_ok!("a"));
+        assert!(interp.invoke_hidden(&argv).is_err());
+    }
+
+    #[test]
+    fn test_new_safe() {
+        let mut interp = Interp::new_safe();
+        assert!(interp.is_safe());
+        assert!(interp.has_command("list"));
+
+        for name in &["cd", "exec", "exit", "glob", "open", "pwd", "socket", "source"] {
+            assert!(!interp.has_command(name), "{} is exposed", name);
+        }
+
+        assert!(interp.eval("file exists .").is_err());
+        assert!(!interp.var_exists("env"));
+
+        interp.create_slave("child").unwrap();
+        assert!(interp.slave("child").unwrap().is_safe());
+    }
+
+    #[test]
+    fn test_var_traces() {
+        let mut interp = Interp::new();
+        interp.eval("proc log {args} { lappend ::log $args }").unwrap();
+        interp.eval("trace add variable x {read write} log").unwrap();
+
+        // Writes and reads from Rust call the traces.
+        interp.set_var("x", "1").unwrap();
+        assert_eq!(interp.var("x"), molt_ok!("1"));
+        assert_eq!(interp.eval("set ::log"), molt_ok!("{x {} write} {x {} read}"));
+
+        // Errors in write traces are errors for the set.
+        interp.eval("proc fail {args} { error nope }").unwrap();
+        interp.eval("trace add variable y write fail").unwrap();
+        assert_eq!(interp.set_var("y", "1"), molt_err!("can't set \"y\": nope"));
+    }
+
+    #[test]
+    fn test_eval_at_level() {
+        let mut interp = Interp::new();
+        interp.set_var("a", "global").unwrap();
+        interp.push_scope();
+        interp.set_var("a", "local").unwrap();
+
+        assert_eq!(interp.eval_at_level(0, "set a"), molt_ok!("global"));
+        assert_eq!(interp.eval_at_level(0, "info level"), molt_ok!(0));
+        assert_eq!(interp.eval_at_level(1, "set a"), molt_ok!("local"));
+        assert_eq!(interp.eval_at_level(0, "break"), Err(ResultCode::Break));
+
+        assert_eq!(interp.scope_level(), 1);
+        assert_eq!(interp.var("a"), molt_ok!("local"));
+        interp.pop_scope();
+    }
+
+    #[test]
+    fn test_upvar2() {
+        let mut interp = Interp::new();
+        interp.set_var("a", "1").unwrap();
+        interp.push_scope();
+
+        assert!(interp.upvar2(0, "a", "b").is_ok());
+        assert_eq!(interp.var("b"), molt_ok!("1"));
+        interp.set_var("b", "2").unwrap();
+        assert!(interp.var_exists("b"));
+        interp.unset_var("b");
+        assert!(!interp.var_exists("b"));
+
+        interp.set_var("c", "3").unwrap();
+        assert_eq!(interp.upvar2(0, "a", "c"), molt_err!("variable \"c\" already exists"));
+        assert_eq!(interp.upvar2(1, "d", "d"), molt_err!("can't upvar from variable to itself"));
+        assert_eq!(interp.upvar2(0, "a(1)", "e"),
+            molt_err!("can't upvar to array element \"a(1)\""));
+
+        interp.pop_scope();
+        assert!(!interp.var_exists("a"));
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let mut interp = Interp::new();
+        assert_eq!(interp.current_namespace(), "::");
+        assert!(interp.namespace_exists("::"));
+        assert!(!interp.namespace_exists("a"));
+
+        assert_eq!(interp.add_namespace("a::b"), "::a::b");
+        assert!(interp.namespace_exists("::a"));
+        assert_eq!(interp.namespace_children("a"), Ok(vec![Value::from("::a::b")]));
+
+        assert_eq!(interp.namespace_eval("a", "namespace current"), molt_ok!("::a"));
+        assert_eq!(interp.namespace_eval("a", "set x 1; proc p {} {return p}"), molt_ok!());
+        assert_eq!(interp.var("a::x"), molt_ok!("1"));
+        assert_eq!(interp.var("x"), molt_err!("can't read \"x\": no such variable"));
+        assert!(interp.has_command("::a::p"));
+        assert!(!interp.has_command("p"));
+
+        assert!(interp.delete_namespace("a").is_ok());
+        assert!(!interp.namespace_exists("a::b"));
+        assert!(!interp.var_exists("a::x"));
+        assert!(!interp.has_command("a::p"));
+        assert_eq!(interp.delete_namespace("::"), molt_err!("can't delete the global namespace"));
+    }
+
     #[test]
     fn test_complete() {
         // This function tests the function by testing the Interp method
@@ -899,6 +4074,79 @@ mod tests {
         assert!(!interp.complete("a \"bc"));
     }
 
+    #[test]
+    fn test_parse_var_name() {
+        assert_eq!(parse_var_name("a"), ("a", None));
+        assert_eq!(parse_var_name("a(x)"), ("a", Some("x")));
+        assert_eq!(parse_var_name("a()"), ("a", Some("")));
+        assert_eq!(parse_var_name("a(x(y))"), ("a", Some("x(y)")));
+        assert_eq!(parse_var_name("a(x"), ("a(x", None));
+        assert_eq!(parse_var_name("ax)"), ("ax)", None));
+    }
+
+    #[test]
+    fn test_array_vars() {
+        let mut interp = Interp::new();
+
+        assert!(interp.set_var2("a(x)", Value::from("1")).is_ok());
+        assert!(interp.array_exists("a"));
+        assert_eq!(interp.var("a(x)"), Ok(Value::from("1")));
+        assert_eq!(interp.element("a", "x"), Ok(Value::from("1")));
+        assert_eq!(interp.var("a"), molt_err!("can't read \"a\": variable is array"));
+        assert_eq!(interp.set_var2("a", Value::from("1")),
+            molt_err!("can't set \"a\": variable is array"));
+
+        interp.unset_var("a(x)");
+        assert_eq!(interp.var("a(x)"),
+            molt_err!("can't read \"a(x)\": no such element in array"));
+
+        assert!(interp.set_var2("b", Value::from("1")).is_ok());
+        assert_eq!(interp.set_var2("b(x)", Value::from("1")),
+            molt_err!("can't set \"b(x)\": variable isn't array"));
+    }
+
+    #[test]
+    fn test_var_exists() {
+        let mut interp = Interp::new();
+
+        assert!(!interp.var_exists("a"));
+        assert!(interp.set_var2("a", Value::from("1")).is_ok());
+        assert!(interp.var_exists("a"));
+        assert!(!interp.var_exists("a(x)"));
+
+        assert!(interp.set_var2("b(x)", Value::from("1")).is_ok());
+        assert!(interp.var_exists("b"));
+        assert!(interp.var_exists("b(x)"));
+        assert!(!interp.var_exists("b(y)"));
+
+        interp.unset_var("a");
+        assert!(!interp.var_exists("a"));
+    }
+
+    #[test]
+    fn test_unset_var_checked() {
+        let mut interp = Interp::new();
+
+        assert!(interp.set_var2("a(x)", Value::from("1")).is_ok());
+        assert!(interp.set_var2("b", Value::from("1")).is_ok());
+
+        assert_eq!(interp.unset_var_checked("a(y)"),
+            molt_err!("can't unset \"a(y)\": no such element in array"));
+        assert_eq!(interp.unset_var_checked("b(x)"),
+            molt_err!("can't unset \"b(x)\": variable isn't array"));
+        assert_eq!(interp.unset_var_checked("c(x)"),
+            molt_err!("can't unset \"c(x)\": no such variable"));
+        assert_eq!(interp.unset_var_checked("c"),
+            molt_err!("can't unset \"c\": no such variable"));
+
+        assert!(interp.unset_var_checked("a(x)").is_ok());
+        assert!(interp.array_exists("a"));
+        assert!(interp.unset_var_checked("a").is_ok());
+        assert!(!interp.array_exists("a"));
+        assert!(interp.unset_var_checked("b").is_ok());
+        assert!(interp.var("b").is_err());
+    }
+
     #[test]
     fn test_subst_backslashes() {
         // This function tests the function by testing the Interp method
@@ -8,17 +8,31 @@ pub use crate::types::*;
 pub use crate::list::list_to_string;
 pub use crate::interp::Interp;
 
+mod binary;
 #[allow(dead_code)] // Temporary
+mod channel;
 mod char_ptr;
+mod clock;
 mod commands;
 mod context;
+mod coroutine;
+mod dict;
+mod ensemble;
+mod event;
 #[allow(dead_code)] // Temporary
 mod expr;
+mod file;
+mod format;
+mod history;
 pub mod interp;
 mod list;
 #[macro_use]
 mod macros;
+mod package;
+mod process;
+mod scan;
 mod scope;
+mod socket;
 pub mod types;
 pub mod value;
 mod util;
@@ -43,12 +57,11 @@ pub fn check_args(
     if argv.len() < min || (max > 0 && argv.len() > max) {
         // TODO: Need an easy way to join the values in a &[&Value] into a string.
         // This is a stopgap.
-        let vec: Vec<String> = argv[0..namec].iter().map(|v| v.to_string()).collect();
-        molt_err!("wrong # args: should be \"{} {}\"",
-            // argv[0..namec].join(" "),
-            vec.join(" "),
-            argsig
-        )
+        let mut vec: Vec<String> = argv[0..namec].iter().map(|v| v.to_string()).collect();
+        if !argsig.is_empty() {
+            vec.push(argsig.to_string());
+        }
+        molt_err!("wrong # args: should be \"{}\"", vec.join(" "))
     } else {
         molt_ok!()
     }
@@ -85,6 +98,10 @@ mod tests {
             &check_args(1, &mklist(vec!["mycmd", "val1", "val2"].as_slice()), 2, 2, "arg1"),
             "wrong # args: should be \"mycmd arg1\"",
         );
+        assert_err(
+            &check_args(1, &mklist(vec!["mycmd", "val1"].as_slice()), 1, 1, ""),
+            "wrong # args: should be \"mycmd\"",
+        );
     }
 
     // TODO: stopgap until we have finalized the MoltList API.
@@ -199,7 +199,7 @@ fn escape_item(hash: bool, item: &str) -> String {
         }
 
         match ch {
-            '{' | ';' | '$' | '[' | ']' | '\\' => {
+            '{' | '}' | '"' | ';' | '$' | '[' | ']' | '\\' => {
                 word.push('\\');
                 word.push(ch);
             }
@@ -223,11 +223,12 @@ fn get_mode(word: &str) -> Mode {
         return Mode::Brace;
     }
 
-    // NEXT, inspect the content.
-    let mut mode = Mode::AsIs;
+    // NEXT, inspect the content.  A word that begins with a double quote would be
+    // parsed as a quoted item, so it must at least be braced.
+    let mut mode = if word.starts_with('"') { Mode::Brace } else { Mode::AsIs };
     let mut brace_count = 0;
 
-    let mut iter = word.chars().peekable();
+    let mut iter = word.chars();
 
     while let Some(ch) = iter.next() {
         if ch.is_whitespace() {
@@ -239,12 +240,18 @@ fn get_mode(word: &str) -> Mode {
                 mode = Mode::Brace;
             }
             '{' => brace_count += 1,
-            '}' => brace_count -= 1,
-            '\\' => {
-                if iter.peek() == Some(&'\n') {
+            '}' => {
+                // A close brace with no matching open brace can't be braced.
+                brace_count -= 1;
+                if brace_count < 0 {
                     return Mode::Escape;
-                } else {
-                    mode = Mode::Brace;
+                }
+            }
+            '\\' => {
+                // A backslash at the end of the word would escape the close brace.
+                match iter.next() {
+                    None | Some('\n') => return Mode::Escape,
+                    _ => mode = Mode::Brace,
                 }
             }
             _ => ()
@@ -275,5 +282,10 @@ mod tests {
         assert_eq!(list_to_string(&[Value::from("a]b")]), "{a]b}");
         assert_eq!(list_to_string(&[Value::from("a\\nb")]), "{a\\nb}");
         assert_eq!(list_to_string(&[Value::from("{ "), Value::from("abc")]), r#"\{\  abc"#);
+        assert_eq!(list_to_string(&[Value::from("b\\"), Value::from("c")]), r#"b\\ c"#);
+        assert_eq!(list_to_string(&[Value::from("a b\\")]), r#"a\ b\\"#);
+        assert_eq!(list_to_string(&[Value::from("b\\\\")]), r#"{b\\}"#);
+        assert_eq!(list_to_string(&[Value::from("}{")]), r#"\}\{"#);
+        assert_eq!(list_to_string(&[Value::from("\"a")]), r#"{"a}"#);
     }
 }
@@ -26,9 +26,9 @@ macro_rules! molt_ok {
 #[macro_export]
 macro_rules! molt_err {
     ($arg:expr) => (
-        Err(ResultCode::Error(Value::from($arg)))
+        Err(ResultCode::error(Value::from($arg)))
     );
     ($($arg:tt)*) => (
-        Err(ResultCode::Error(Value::from(format!($($arg)*))))
+        Err(ResultCode::error(Value::from(format!($($arg)*))))
     )
 }
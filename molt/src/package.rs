@@ -0,0 +1,255 @@
+//! Packages
+//!
+//! This module defines the interpreter's table of packages, as managed by the `package`
+//! command.  A package is *provided* once it has been loaded into the interpreter.  Before
+//! that, one or more versions of it may be *available*, each with a loader: a script,
+//! registered by `package ifneeded`, or a Rust function, registered by
+//! `Interp::add_package`, that loads that version, e.g., by adding its commands.
+//!
+//! Version numbers are sequences of non-negative integers separated by dots, e.g., `1.2.3`,
+//! and are compared component by component, so that `1.10` is later than `1.9`.  A missing
+//! component counts as 0, so that `1` and `1.0` are the same version.
+
+use crate::types::*;
+use crate::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The means of loading a version of a package.
+#[derive(Clone)]
+pub(crate) enum Loader {
+    /// A script, as registered by `package ifneeded`.
+    Script(Value),
+
+    /// A Rust function, as registered by `Interp::add_package`.
+    Native(PackageInit),
+}
+
+/// The interpreter's packages.
+#[derive(Default)]
+pub(crate) struct PackageTable {
+    /// The versions of the provided packages, by name.
+    provided: HashMap<String, String>,
+
+    /// The available versions of the packages and their loaders, by name.
+    available: HashMap<String, Vec<(String, Loader)>>,
+
+    /// The names of the packages currently being loaded.
+    loading: HashSet<String>,
+}
+
+impl PackageTable {
+    /// Creates an empty package table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the provided version of the package, if any.
+    pub fn provided(&self, name: &str) -> Option<&str> {
+        self.provided.get(name).map(String::as_str)
+    }
+
+    /// Notes that the version of the package is provided.
+    pub fn provide(&mut self, name: &str, version: &str) {
+        self.provided.insert(name.into(), version.into());
+    }
+
+    /// Forgets the package: it's neither provided nor available.
+    pub fn forget(&mut self, name: &str) {
+        self.provided.remove(name);
+        self.available.remove(name);
+    }
+
+    /// Gets the loader of the version of the package, if it's available.
+    pub fn loader(&self, name: &str, version: &str) -> Option<&Loader> {
+        self.available
+            .get(name)?
+            .iter()
+            .find(|(v, _)| compare(v, version) == Ordering::Equal)
+            .map(|(_, loader)| loader)
+    }
+
+    /// Sets the loader of the version of the package, making it available.
+    pub fn set_loader(&mut self, name: &str, version: &str, loader: Loader) {
+        let versions = self.available.entry(name.into()).or_default();
+        versions.retain(|(v, _)| compare(v, version) != Ordering::Equal);
+        versions.push((version.into(), loader));
+    }
+
+    /// Gets the available versions of the package, in the order in which they were made
+    /// available.
+    pub fn versions(&self, name: &str) -> Vec<String> {
+        match self.available.get(name) {
+            Some(versions) => versions.iter().map(|(v, _)| v.clone()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the names of the packages that are provided or available, in sorted order.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> =
+            self.provided.keys().chain(self.available.keys()).cloned().collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Gets the latest available version of the package that's acceptable, with its
+    /// loader.
+    pub fn latest<F>(&self, name: &str, acceptable: F) -> Option<(String, Loader)>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.available
+            .get(name)?
+            .iter()
+            .filter(|(v, _)| acceptable(v))
+            .max_by(|(a, _), (b, _)| compare(a, b))
+            .cloned()
+    }
+
+    /// Notes that the package is being loaded, returning false if it already was.
+    pub fn start_loading(&mut self, name: &str) -> bool {
+        self.loading.insert(name.into())
+    }
+
+    /// Notes that the package is no longer being loaded.
+    pub fn stop_loading(&mut self, name: &str) {
+        self.loading.remove(name);
+    }
+}
+
+/// Parses a version number into its components, or returns the usual error.
+pub(crate) fn parse_version(version: &str) -> Result<Vec<u64>, ResultCode> {
+    let components: Option<Vec<u64>> = version
+        .split('.')
+        .map(|c| {
+            if !c.is_empty() && c.bytes().all(|b| b.is_ascii_digit()) {
+                c.parse().ok()
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    match components {
+        Some(components) => Ok(components),
+        None => molt_err!("expected version number but got \"{}\"", version),
+    }
+}
+
+/// Checks a version requirement, which is a version number, *min*, or a range, *min*- or
+/// *min*-*max*.
+pub(crate) fn check_requirement(requirement: &str) -> Result<(), ResultCode> {
+    match requirement.split_once('-') {
+        Some((min, "")) => parse_version(min).map(|_| ()),
+        Some((min, max)) => parse_version(min).and(parse_version(max)).map(|_| ()),
+        None => parse_version(requirement).map(|_| ()),
+    }
+}
+
+/// Compares two valid version numbers.
+pub(crate) fn compare(a: &str, b: &str) -> Ordering {
+    let a = parse_version(a).unwrap_or_default();
+    let b = parse_version(b).unwrap_or_default();
+    let len = a.len().max(b.len());
+
+    for i in 0..len {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Determines whether two valid version numbers are the same version.
+pub(crate) fn equal(a: &str, b: &str) -> bool {
+    compare(a, b) == Ordering::Equal
+}
+
+/// Determines whether a valid version number satisfies a valid requirement.  A version
+/// satisfies *min* if it's no earlier than *min* and has the same major version, i.e.,
+/// first component; *min*- if it's no earlier than *min*; and *min*-*max* if it's no
+/// earlier than *min* and earlier than *max*, or equal to both.
+pub(crate) fn satisfies(version: &str, requirement: &str) -> bool {
+    match requirement.split_once('-') {
+        Some((min, "")) => compare(version, min) != Ordering::Less,
+        Some((min, max)) => {
+            compare(version, min) != Ordering::Less
+                && (compare(version, max) == Ordering::Less
+                    || (compare(min, max) == Ordering::Equal
+                        && compare(version, min) == Ordering::Equal))
+        }
+        None => {
+            compare(version, requirement) != Ordering::Less
+                && major(version) == major(requirement)
+        }
+    }
+}
+
+/// Gets the major version of a valid version number.
+fn major(version: &str) -> u64 {
+    parse_version(version).map_or(0, |components| components[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1"), Ok(vec![1]));
+        assert_eq!(parse_version("1.10.0"), Ok(vec![1, 10, 0]));
+        assert_eq!(parse_version("1.x"), molt_err!("expected version number but got \"1.x\""));
+        assert!(parse_version("").is_err());
+        assert!(parse_version("1..2").is_err());
+        assert!(parse_version("-1").is_err());
+        assert!(check_requirement("1.2-").is_ok());
+        assert!(check_requirement("1.2-2").is_ok());
+        assert!(check_requirement("1.2-x").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare("1.9", "1.10"), Ordering::Less);
+        assert_eq!(compare("1", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare("2", "1.99"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_satisfies() {
+        assert!(satisfies("1.2", "1.2"));
+        assert!(satisfies("1.10", "1.9"));
+        assert!(!satisfies("1.1", "1.2"));
+        assert!(!satisfies("2.0", "1.2"));
+        assert!(satisfies("2.0", "1.2-"));
+        assert!(satisfies("1.5", "1.2-2"));
+        assert!(!satisfies("2.0", "1.2-2"));
+        assert!(satisfies("1.2", "1.2-1.2"));
+    }
+
+    #[test]
+    fn test_package_table() {
+        let mut table = PackageTable::new();
+        table.set_loader("a", "1.9", Loader::Script(Value::from("9")));
+        table.set_loader("a", "1.10", Loader::Script(Value::from("10")));
+        table.set_loader("a", "2.0", Loader::Script(Value::from("20")));
+        table.provide("b", "1.0");
+
+        assert_eq!(table.names(), vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(table.versions("a"), vec!["1.9", "1.10", "2.0"]);
+        assert_eq!(table.latest("a", |v| satisfies(v, "1")).unwrap().0, "1.10");
+        assert!(table.latest("a", |v| satisfies(v, "3")).is_none());
+        assert!(table.loader("a", "1.9.0").is_some());
+        assert_eq!(table.provided("b"), Some("1.0"));
+
+        table.forget("a");
+        assert!(table.versions("a").is_empty());
+        assert!(table.provided("a").is_none());
+    }
+}
@@ -0,0 +1,789 @@
+//! Process Commands
+//!
+//! This module defines the commands that run other programs, i.e., `exec`.  Like the file
+//! system commands, they are kept apart from the standard commands, so that an
+//! application can create an interpreter that can't run programs: `Interp::new` adds them
+//! by calling `Interp::add_process_commands`, and an interpreter built up from
+//! `Interp::empty` need not.
+//!
+//! `exec` runs a pipeline: one or more programs, each of whose standard output is the
+//! standard input of the next, along with the redirection of the pipeline's standard
+//! input, output, and error.  The stages are connected by OS pipes, so that the programs
+//! run concurrently.  `open` runs a pipeline in the same way, as a channel that writes to
+//! the pipeline's standard input and reads its standard output; an interpreter without
+//! these commands can't open command pipelines.
+
+use crate::channel;
+use crate::channel::Channel;
+use crate::channel::FileEvent;
+use crate::channel::Stream;
+use crate::commands::output_channel;
+use crate::interp::Interp;
+use crate::types::*;
+use crate::util::posix_error;
+use crate::*;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::process::Child;
+use std::process::Command;
+use std::process::ExitStatus;
+use std::process::Stdio;
+use std::thread;
+use std::thread::JoinHandle;
+
+/// # exec ?*switches*? *arg* ?*arg* ...? ?&?
+///
+/// Runs the pipeline of programs given by the arguments, and waits for it to complete.
+/// Returns the standard output of the last program, less any trailing newline.  It's an
+/// error if any of the programs exits abnormally or writes to its standard error.  With
+/// a trailing `&`, runs the pipeline in the background and returns the process IDs.  See
+/// molt-book for full semantics.
+pub fn cmd_exec(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut ignore_stderr = false;
+    let mut keep_newline = false;
+    let mut i = 1;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        match opt.as_str() {
+            "-ignorestderr" => ignore_stderr = true,
+            "-keepnewline" => keep_newline = true,
+            "--" => {
+                i += 1;
+                break;
+            }
+            _ => {
+                return molt_err!(
+                    "bad option \"{}\": must be -ignorestderr, -keepnewline, or --",
+                    opt
+                )
+            }
+        }
+
+        i += 1;
+    }
+
+    if i == argv.len() {
+        return molt_err!("wrong # args: should be \"exec ?-option ...? arg ?arg ...?\"");
+    }
+
+    let words: Vec<String> = argv[i..].iter().map(|arg| arg.as_string().to_string()).collect();
+    let mut pipeline = Pipeline::parse(&words)?;
+
+    if ignore_stderr && pipeline.stderr == Sink::Capture {
+        pipeline.stderr = Sink::Inherit;
+    }
+
+    for sink in &[&pipeline.stdout, &pipeline.stderr] {
+        if let Sink::Channel(name) = sink {
+            output_channel(interp, name)?;
+        }
+    }
+
+    // So that output the script has written precedes the programs' output.
+    interp.flush_channels();
+
+    if pipeline.background {
+        let pids = pipeline.spawn_background()?;
+        return molt_ok!(pids.into_iter().map(|pid| Value::from(pid as MoltInt)).collect::<MoltList>());
+    }
+
+    let Outcome { mut stdout, mut stderr, statuses } = pipeline.run()?;
+
+    if let Sink::Channel(name) = &pipeline.stdout {
+        write_channel(interp, name, &stdout)?;
+        stdout.clear();
+    }
+
+    if let Sink::Channel(name) = &pipeline.stderr {
+        write_channel(interp, name, &stderr)?;
+        stderr.clear();
+    }
+
+    pipeline_result(stdout, stderr, &statuses, keep_newline)
+}
+
+/// Computes the result of a pipeline that has completed, given its captured standard
+/// output and standard error and its programs' exit statuses: the output, followed by the
+/// standard error, less any trailing newline unless it's kept.  It's an error if any of
+/// the programs exited abnormally or wrote to the standard error.
+fn pipeline_result(
+    stdout: String,
+    stderr: String,
+    statuses: &[(u32, ExitStatus)],
+    keep_newline: bool,
+) -> MoltResult {
+    let mut result = stdout;
+
+    if !stderr.is_empty() {
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(&stderr);
+    }
+
+    if !keep_newline && result.ends_with('\n') {
+        result.pop();
+    }
+
+    // As in TCL, the error code describes the last of the programs that failed.
+    match statuses.iter().rev().find_map(|(pid, status)| child_status(*pid, *status)) {
+        Some((message, code)) => {
+            if stderr.is_empty() {
+                if !result.is_empty() {
+                    result.push('\n');
+                }
+                result.push_str(message);
+            }
+
+            let mut data = ErrorData::new(Value::from(result));
+            data.set_error_code(Value::from(code));
+            Err(ResultCode::Error(data))
+        }
+        None if !stderr.is_empty() => molt_err!("{}", result),
+        None => molt_ok!(result),
+    }
+}
+
+/// Opens a command pipeline as a channel, as for `open |command`: runs the pipeline, a
+/// list of words as given to `exec`, in the background, returning the name of a channel
+/// that writes to the first program's standard input and reads from the last program's
+/// standard output, as the access mode allows.  The standard input and output are
+/// otherwise the application's, unless redirected, and may not be redirected if the
+/// channel uses them.  The standard error, unless redirected, is captured, and `close`
+/// reports it as an error, along with any program that exits abnormally, as `exec` does.
+pub(crate) fn open_pipeline(
+    interp: &mut Interp,
+    command: &str,
+    readable: bool,
+    writable: bool,
+) -> MoltResult {
+    if !interp.runs_programs() {
+        return molt_err!("couldn't open \"|{}\": this interpreter can't run programs", command);
+    }
+
+    let words: Vec<String> =
+        Value::from(command).as_list()?.iter().map(|word| word.as_string().to_string()).collect();
+    let mut pipeline = Pipeline::parse(&words)?;
+
+    if pipeline.background {
+        return molt_err!("can't run a command pipeline opened as a channel in the background");
+    }
+
+    for sink in &[&pipeline.stdout, &pipeline.stderr] {
+        if let Sink::Channel(name) = sink {
+            return molt_err!("can't redirect a command pipeline to channel \"{}\"", name);
+        }
+    }
+
+    if readable && pipeline.stdout != Sink::Capture {
+        return molt_err!("can't read output from command: standard output was redirected");
+    }
+
+    if writable && pipeline.stdin != Source::Inherit {
+        return molt_err!("can't write input to command: standard input was redirected");
+    }
+
+    if !readable && pipeline.stdout == Sink::Capture {
+        pipeline.stdout = Sink::Inherit;
+    }
+
+    // So that output the script has written precedes the programs' output.
+    interp.flush_channels();
+
+    let (input, stdin) = if writable {
+        let (reader, writer) = io::pipe().map_err(pipe_error)?;
+        (Input { stdio: reader.into(), feed: None }, Some(writer))
+    } else {
+        (open_source(&pipeline.stdin)?, None)
+    };
+
+    let (stdout, stdout_reader) = open_sink(&pipeline.stdout)?;
+    let (stderr, stderr_reader) = match pipeline.stderr {
+        Sink::Stdout => (stdout.try_clone().map_err(pipe_error)?, None),
+        _ => open_sink(&pipeline.stderr)?,
+    };
+
+    let children = pipeline.spawn(input, stdout, stderr)?;
+    let pids = children.iter().map(Child::id).collect();
+
+    let stream = PipelineStream {
+        stdin,
+        stdout: stdout_reader,
+        stderr: stderr_reader.map(|reader| thread::spawn(move || read_all(reader))),
+        children,
+    };
+
+    let mut channel = Channel::new(Box::new(stream), readable, writable);
+    channel.set_pids(pids);
+    molt_ok!(interp.add_channel("file", channel))
+}
+
+/// The stream of a channel opened on a command pipeline.
+struct PipelineStream {
+    /// The pipe to the first program's standard input, if the channel is writable.
+    stdin: Option<io::PipeWriter>,
+
+    /// The pipe from the last program's standard output, if the channel is readable.
+    stdout: Option<io::PipeReader>,
+
+    /// The thread reading the pipeline's standard error, if it's captured.
+    stderr: Option<JoinHandle<String>>,
+
+    /// The programs, until they've been waited for.
+    children: Vec<Child>,
+}
+
+impl Stream for PipelineStream {
+    fn reader(&mut self) -> Option<&mut dyn Read> {
+        self.stdout.as_mut().map(|reader| reader as &mut dyn Read)
+    }
+
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        self.stdin.as_mut().map(|writer| writer as &mut dyn Write)
+    }
+
+    #[cfg(unix)]
+    fn is_ready(&mut self, event: FileEvent) -> bool {
+        use std::os::unix::io::AsRawFd;
+
+        let fd = match event {
+            FileEvent::Readable => self.stdout.as_ref().map(|reader| reader.as_raw_fd()),
+            FileEvent::Writable => self.stdin.as_ref().map(|writer| writer.as_raw_fd()),
+        };
+
+        fd.is_none_or(|fd| channel::poll_fd(fd, event))
+    }
+
+    /// Closes the pipes, so that the programs see the end of their input, and waits for
+    /// the programs to exit.
+    fn finish(&mut self) -> Result<(), ResultCode> {
+        self.stdin = None;
+        self.stdout = None;
+
+        let mut statuses = Vec::new();
+
+        for mut child in std::mem::take(&mut self.children) {
+            match child.wait() {
+                Ok(status) => statuses.push((child.id(), status)),
+                Err(e) => return molt_err!("error waiting for process to exit: {}", posix_error(&e)),
+            }
+        }
+
+        let stderr = self.stderr.take().map(|t| t.join().unwrap_or_default()).unwrap_or_default();
+        pipeline_result(String::new(), stderr, &statuses, false).map(|_| ())
+    }
+}
+
+impl Drop for PipelineStream {
+    /// If the channel wasn't closed, e.g., because the interpreter was dropped, the
+    /// programs are waited for on another thread, as for `exec &`.
+    fn drop(&mut self) {
+        self.stdin = None;
+        self.stdout = None;
+
+        let children = std::mem::take(&mut self.children);
+
+        if !children.is_empty() {
+            thread::spawn(move || {
+                for mut child in children {
+                    let _ = child.wait();
+                }
+            });
+        }
+    }
+}
+
+/// Where a pipeline's standard input comes from.
+#[derive(Debug, PartialEq)]
+enum Source {
+    /// The application's standard input.
+    Inherit,
+
+    /// The named file, as for `< fileName`.
+    File(String),
+
+    /// The given string, as for `<< value`.
+    Literal(String),
+}
+
+/// Where a pipeline's standard output or standard error goes.
+#[derive(Debug, PartialEq)]
+enum Sink {
+    /// To `exec`, which returns it.
+    Capture,
+
+    /// To the application's standard output or standard error.
+    Inherit,
+
+    /// To the named file, appending if the flag is set, as for `> fileName` or
+    /// `>> fileName`.
+    File(String, bool),
+
+    /// For the standard error, to wherever the standard output goes, as for `2>@1`.
+    Stdout,
+
+    /// To the named channel, as for `>@ chanId`.  The output is captured, and written to
+    /// the channel when the pipeline completes.
+    Channel(String),
+}
+
+/// A program in a pipeline.
+#[derive(Debug, Default, PartialEq)]
+struct Stage {
+    /// The program and its arguments.
+    words: Vec<String>,
+
+    /// Whether the program's standard error goes to the next program along with its
+    /// standard output, as for `|&`.
+    pipe_stderr: bool,
+}
+
+/// A pipeline of programs, as given to `exec`.
+#[derive(Debug, PartialEq)]
+struct Pipeline {
+    stages: Vec<Stage>,
+    stdin: Source,
+    stdout: Sink,
+    stderr: Sink,
+
+    /// Whether to run the pipeline in the background, as for a trailing `&`.
+    background: bool,
+}
+
+/// The outcome of running a pipeline: its captured standard output and standard error,
+/// and the process ID and exit status of each program.
+struct Outcome {
+    stdout: String,
+    stderr: String,
+    statuses: Vec<(u32, ExitStatus)>,
+}
+
+/// The redirection operators, longest first, so that each word is matched against the
+/// operator it begins with.
+const REDIRECTIONS: [&str; 10] = ["2>>", "2>@", "2>", ">>&", ">>", ">&", ">@", ">", "<<", "<"];
+
+impl Pipeline {
+    /// Parses the words of an `exec` command.  A redirection operator may be followed by
+    /// its target in the same word, e.g., `>out.txt`, or in the next word.
+    fn parse(words: &[String]) -> Result<Self, ResultCode> {
+        let mut pipeline = Pipeline {
+            stages: vec![Stage::default()],
+            stdin: Source::Inherit,
+            stdout: Sink::Capture,
+            stderr: Sink::Capture,
+            background: false,
+        };
+
+        let mut i = 0;
+
+        while i < words.len() {
+            let word = words[i].as_str();
+            i += 1;
+
+            if word == "|" || word == "|&" {
+                let stage = pipeline.stages.last_mut().unwrap();
+
+                if stage.words.is_empty() {
+                    return molt_err!("illegal use of | or |& in command");
+                }
+
+                stage.pipe_stderr = word == "|&";
+                pipeline.stages.push(Stage::default());
+                continue;
+            }
+
+            if word == "&" && i == words.len() {
+                pipeline.background = true;
+                continue;
+            }
+
+            if word == "2>@1" {
+                pipeline.stderr = Sink::Stdout;
+                continue;
+            }
+
+            let op = match REDIRECTIONS.iter().find(|op| word.starts_with(*op)) {
+                Some(op) => *op,
+                None => {
+                    pipeline.stages.last_mut().unwrap().words.push(word.into());
+                    continue;
+                }
+            };
+
+            let target = if word.len() > op.len() {
+                word[op.len()..].to_string()
+            } else if i < words.len() {
+                i += 1;
+                words[i - 1].clone()
+            } else {
+                return molt_err!("can't specify \"{}\" as last word in command", op);
+            };
+
+            match op {
+                "<" => pipeline.stdin = Source::File(target),
+                "<<" => pipeline.stdin = Source::Literal(target),
+                ">" => pipeline.stdout = Sink::File(target, false),
+                ">>" => pipeline.stdout = Sink::File(target, true),
+                ">@" => pipeline.stdout = Sink::Channel(target),
+                ">&" | ">>&" => {
+                    pipeline.stdout = Sink::File(target, op == ">>&");
+                    pipeline.stderr = Sink::Stdout;
+                }
+                "2>" => pipeline.stderr = Sink::File(target, false),
+                "2>@" => pipeline.stderr = Sink::Channel(target),
+                _ => pipeline.stderr = Sink::File(target, true),
+            }
+        }
+
+        if pipeline.stages.last().unwrap().words.is_empty() {
+            if pipeline.stages.len() > 1 {
+                return molt_err!("illegal use of | or |& in command");
+            } else {
+                return molt_err!("didn't specify command to execute");
+            }
+        }
+
+        Ok(pipeline)
+    }
+
+    /// Runs the pipeline and waits for it to complete.
+    fn run(&self) -> Result<Outcome, ResultCode> {
+        let input = open_source(&self.stdin)?;
+        let (stdout, stdout_reader) = open_sink(&self.stdout)?;
+        let (stderr, stderr_reader) = match self.stderr {
+            Sink::Stdout => (stdout.try_clone().map_err(pipe_error)?, None),
+            _ => open_sink(&self.stderr)?,
+        };
+
+        let children = self.spawn(input, stdout, stderr)?;
+
+        // The standard error is read on another thread, so that a program that writes a
+        // lot to it doesn't block while the standard output is read.
+        let stderr_thread = stderr_reader.map(|reader| thread::spawn(move || read_all(reader)));
+        let stdout = stdout_reader.map(read_all).unwrap_or_default();
+        let stderr = stderr_thread.map(|t| t.join().unwrap_or_default()).unwrap_or_default();
+
+        let mut statuses = Vec::new();
+
+        for mut child in children {
+            match child.wait() {
+                Ok(status) => statuses.push((child.id(), status)),
+                Err(e) => return molt_err!("error waiting for process to exit: {}", posix_error(&e)),
+            }
+        }
+
+        Ok(Outcome { stdout, stderr, statuses })
+    }
+
+    /// Starts the pipeline in the background, returning the process IDs of its programs.
+    /// Output that isn't redirected goes to the application's standard output and
+    /// standard error.  It's an error to redirect the output to a channel, as there's no
+    /// one to write it there.
+    fn spawn_background(mut self) -> Result<Vec<u32>, ResultCode> {
+        for sink in &[&self.stdout, &self.stderr] {
+            if let Sink::Channel(name) = sink {
+                return molt_err!("can't redirect a background pipeline to channel \"{}\"", name);
+            }
+        }
+
+        if self.stdout == Sink::Capture {
+            self.stdout = Sink::Inherit;
+        }
+
+        if self.stderr == Sink::Capture {
+            self.stderr = Sink::Inherit;
+        }
+
+        let input = open_source(&self.stdin)?;
+        let (stdout, _) = open_sink(&self.stdout)?;
+        let (stderr, _) = match self.stderr {
+            Sink::Stdout => (stdout.try_clone().map_err(pipe_error)?, None),
+            _ => open_sink(&self.stderr)?,
+        };
+
+        let children = self.spawn(input, stdout, stderr)?;
+        let pids = children.iter().map(|child| child.id()).collect();
+
+        // The programs are waited for on another thread, so that they don't linger as
+        // zombies when they exit.
+        thread::spawn(move || {
+            for mut child in children {
+                let _ = child.wait();
+            }
+        });
+
+        Ok(pids)
+    }
+
+    /// Starts the programs, connecting the first one's standard input to the given input,
+    /// each one's standard output to the next one's standard input, and the last one's to
+    /// the given output.  If a program can't be started, the ones already started are
+    /// killed.
+    fn spawn(&self, input: Input, stdout: Output, stderr: Output) -> Result<Vec<Child>, ResultCode> {
+        let Input { stdio, feed } = input;
+        let mut stdin = Some(stdio);
+        let mut children: Vec<Child> = Vec::new();
+
+        for (index, stage) in self.stages.iter().enumerate() {
+            let mut command = Command::new(&stage.words[0]);
+            command.args(&stage.words[1..]);
+            command.stdin(stdin.take().unwrap());
+
+            if index + 1 == self.stages.len() {
+                command.stdout(stdout.stdio().map_err(pipe_error)?);
+                command.stderr(stderr.stdio().map_err(pipe_error)?);
+            } else {
+                let (reader, writer) = io::pipe().map_err(pipe_error)?;
+
+                if stage.pipe_stderr {
+                    command.stderr(writer.try_clone().map_err(pipe_error)?);
+                } else {
+                    command.stderr(stderr.stdio().map_err(pipe_error)?);
+                }
+
+                command.stdout(writer);
+                stdin = Some(reader.into());
+            }
+
+            // The command is dropped once the program has started, closing this process's
+            // copies of the pipes, so that the programs see the end of their input.
+            match command.spawn() {
+                Ok(child) => children.push(child),
+                Err(e) => {
+                    for mut child in children {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+
+                    return molt_err!("couldn't execute \"{}\": {}", stage.words[0], posix_error(&e));
+                }
+            }
+        }
+
+        // The literal input is written on another thread, so that a large input doesn't
+        // block while the program's output fills its pipe.
+        if let Some((mut writer, text)) = feed {
+            thread::spawn(move || {
+                let _ = writer.write_all(text.as_bytes());
+            });
+        }
+
+        Ok(children)
+    }
+}
+
+/// The standard input of a program in a pipeline, and, for `<<`, the pipe to write the
+/// literal input to and the input.
+struct Input {
+    stdio: Stdio,
+    feed: Option<(io::PipeWriter, String)>,
+}
+
+/// Opens the source of a pipeline's standard input.
+fn open_source(source: &Source) -> Result<Input, ResultCode> {
+    match source {
+        Source::Inherit => Ok(Input { stdio: Stdio::inherit(), feed: None }),
+        Source::File(name) => match File::open(name) {
+            Ok(file) => Ok(Input { stdio: file.into(), feed: None }),
+            Err(e) => molt_err!("couldn't read file \"{}\": {}", name, posix_error(&e)),
+        },
+        Source::Literal(text) => {
+            let (reader, writer) = io::pipe().map_err(pipe_error)?;
+            Ok(Input { stdio: reader.into(), feed: Some((writer, text.clone())) })
+        }
+    }
+}
+
+/// The standard output or standard error of a pipeline, which may be shared by several
+/// of its programs.
+enum Output {
+    Inherit,
+    Pipe(io::PipeWriter),
+    File(File),
+}
+
+impl Output {
+    /// Gets a handle to the output for a program.
+    fn stdio(&self) -> io::Result<Stdio> {
+        match self {
+            Output::Inherit => Ok(Stdio::inherit()),
+            Output::Pipe(writer) => Ok(writer.try_clone()?.into()),
+            Output::File(file) => Ok(file.try_clone()?.into()),
+        }
+    }
+
+    /// Gets another handle to the same output.
+    fn try_clone(&self) -> io::Result<Output> {
+        match self {
+            Output::Inherit => Ok(Output::Inherit),
+            Output::Pipe(writer) => Ok(Output::Pipe(writer.try_clone()?)),
+            Output::File(file) => Ok(Output::File(file.try_clone()?)),
+        }
+    }
+}
+
+/// Opens the destination of a pipeline's standard output or standard error, returning
+/// it and, if the output is to be captured, the pipe to read it from.
+fn open_sink(sink: &Sink) -> Result<(Output, Option<io::PipeReader>), ResultCode> {
+    match sink {
+        Sink::Capture | Sink::Channel(_) => {
+            let (reader, writer) = io::pipe().map_err(pipe_error)?;
+            Ok((Output::Pipe(writer), Some(reader)))
+        }
+        Sink::File(name, append) => {
+            let mut options = OpenOptions::new();
+            options.create(true);
+
+            if *append {
+                options.append(true);
+            } else {
+                options.write(true).truncate(true);
+            }
+
+            match options.open(name) {
+                Ok(file) => Ok((Output::File(file), None)),
+                Err(e) => molt_err!("couldn't write file \"{}\": {}", name, posix_error(&e)),
+            }
+        }
+        _ => Ok((Output::Inherit, None)),
+    }
+}
+
+/// Writes output captured for the named channel to the channel.
+fn write_channel(interp: &mut Interp, name: &str, text: &str) -> Result<(), ResultCode> {
+    match output_channel(interp, name)?.write_str(text) {
+        Ok(_) => Ok(()),
+        Err(e) => molt_err!("error writing \"{}\": {}", name, posix_error(&e)),
+    }
+}
+
+/// Reads the pipe to its end, decoding it as UTF-8.
+fn read_all(mut reader: io::PipeReader) -> String {
+    let mut bytes = Vec::new();
+    let _ = reader.read_to_end(&mut bytes);
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// The error for failing to create a pipe.
+fn pipe_error(err: io::Error) -> ResultCode {
+    ResultCode::error(Value::from(format!("couldn't create pipe: {}", posix_error(&err))))
+}
+
+/// Describes how the child process with the given ID ended abnormally, if it did, as an
+/// error message and error code: `CHILDSTATUS` with the exit status if it exited with a
+/// nonzero status, or `CHILDKILLED` with the signal number if it was killed by a signal.
+fn child_status(pid: u32, status: ExitStatus) -> Option<(&'static str, MoltList)> {
+    let pid = Value::from(pid as MoltInt);
+
+    if let Some(code) = status.code() {
+        if code == 0 {
+            return None;
+        }
+
+        let code = vec![Value::from("CHILDSTATUS"), pid, Value::from(code as MoltInt)];
+        return Some(("child process exited abnormally", code));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if let Some(signal) = status.signal() {
+            let code = vec![Value::from("CHILDKILLED"), pid, Value::from(signal as MoltInt)];
+            return Some(("child killed", code));
+        }
+    }
+
+    let code = vec![Value::from("CHILDSTATUS"), pid, Value::from(-1)];
+    Some(("child process exited abnormally", code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(words: &[&str]) -> Result<Pipeline, ResultCode> {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        Pipeline::parse(&words)
+    }
+
+    fn words(stage: &Stage) -> Vec<&str> {
+        stage.words.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn test_parse_pipeline() {
+        let pipeline = parse(&["ls", "-l", "|", "grep", "x", "|&", "wc"]).unwrap();
+        assert_eq!(pipeline.stages.len(), 3);
+        assert_eq!(words(&pipeline.stages[0]), vec!["ls", "-l"]);
+        assert_eq!(words(&pipeline.stages[1]), vec!["grep", "x"]);
+        assert!(!pipeline.stages[0].pipe_stderr);
+        assert!(pipeline.stages[1].pipe_stderr);
+        assert!(!pipeline.background);
+
+        assert!(parse(&["|", "wc"]).is_err());
+        assert!(parse(&["ls", "|"]).is_err());
+        assert!(parse(&["ls", "|", "|", "wc"]).is_err());
+        assert!(parse(&["<", "in.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_redirections() {
+        let pipeline = parse(&["cat", "<", "in.txt", ">>out.txt", "2>", "err.txt", "&"]).unwrap();
+        assert_eq!(words(&pipeline.stages[0]), vec!["cat"]);
+        assert_eq!(pipeline.stdin, Source::File("in.txt".into()));
+        assert_eq!(pipeline.stdout, Sink::File("out.txt".into(), true));
+        assert_eq!(pipeline.stderr, Sink::File("err.txt".into(), false));
+        assert!(pipeline.background);
+
+        let pipeline = parse(&["cat", "<<", "text", ">&", "out.txt"]).unwrap();
+        assert_eq!(pipeline.stdin, Source::Literal("text".into()));
+        assert_eq!(pipeline.stdout, Sink::File("out.txt".into(), false));
+        assert_eq!(pipeline.stderr, Sink::Stdout);
+
+        let pipeline = parse(&["cat", "2>@1", "2>>err.txt"]).unwrap();
+        assert_eq!(pipeline.stdout, Sink::Capture);
+        assert_eq!(pipeline.stderr, Sink::File("err.txt".into(), true));
+
+        let pipeline = parse(&["cat", ">@", "file3", "2>@stderr"]).unwrap();
+        assert_eq!(pipeline.stdout, Sink::Channel("file3".into()));
+        assert_eq!(pipeline.stderr, Sink::Channel("stderr".into()));
+
+        let pipeline = parse(&["cat", ">@file3", "2>@", "stdout"]).unwrap();
+        assert_eq!(pipeline.stdout, Sink::Channel("file3".into()));
+        assert_eq!(pipeline.stderr, Sink::Channel("stdout".into()));
+
+        let pipeline = parse(&["cat", "2>@1"]).unwrap();
+        assert_eq!(pipeline.stderr, Sink::Stdout);
+
+        // A "&" that isn't the last word is an ordinary argument.
+        let pipeline = parse(&["echo", "&", "x"]).unwrap();
+        assert_eq!(words(&pipeline.stages[0]), vec!["echo", "&", "x"]);
+
+        assert!(parse(&["cat", ">"]).is_err());
+    }
+
+    #[test]
+    fn test_open_pipeline_needs_process_commands() {
+        let mut interp = Interp::empty();
+        interp.add_standard_commands();
+        interp.add_filesystem_commands();
+
+        assert_eq!(
+            interp.eval("open {|echo hi}"),
+            molt_err!("couldn't open \"|echo hi\": this interpreter can't run programs")
+        );
+
+        interp.add_process_commands();
+        assert_eq!(interp.eval("set f [open {|echo hi}]; list [gets $f] [close $f]"), molt_ok!("hi {}"));
+    }
+}
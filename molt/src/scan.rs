@@ -0,0 +1,586 @@
+//! The Scan Command's Scanner
+//!
+//! This module implements the parsing logic for the `scan` command, which parses an input
+//! string according to a format string in the style of the C `sscanf` function.
+
+use crate::*;
+
+//--------------------------------------------------------------------------
+// Format Items
+
+/// An item in a parsed format string.
+enum Item {
+    /// A run of whitespace, which matches zero or more whitespace characters.
+    Space,
+
+    /// A literal character, which must match exactly.
+    Char(char),
+
+    /// A conversion specifier.
+    Conv(Conv),
+}
+
+/// A parsed conversion specifier.
+struct Conv {
+    /// The index of the variable (or result list element) that receives the value, or
+    /// None if assignment is suppressed.
+    slot: Option<usize>,
+
+    /// The maximum field width, if any.
+    width: Option<usize>,
+
+    /// The kind of conversion.
+    kind: Kind,
+}
+
+/// The values produced by a scan, one per variable; a value is None if scanning stopped
+/// before its conversion.
+type Values = Vec<Option<Value>>;
+
+/// The kinds of conversion.
+enum Kind {
+    /// An integer in the given radix; radix 0 means the radix is determined by the
+    /// integer's prefix, as for `%i`.
+    Int(u32),
+
+    /// A floating-point number.
+    Float,
+
+    /// A string of non-whitespace characters.
+    Str,
+
+    /// A single character, converted to its code point.
+    Char,
+
+    /// A string of characters from a set of ranges, or not from the set if negated.
+    Set(bool, Vec<(char, char)>),
+
+    /// The number of characters scanned so far.
+    Count,
+}
+
+//--------------------------------------------------------------------------
+// Scanning
+
+/// Scans the input according to the format string, returning the converted values, one
+/// per variable, or None if the input ran out before the first conversion.
+///
+/// The format string may contain the conversions `%d`, `%i`, `%u`, `%o`, `%x`, `%b`,
+/// `%f`, `%e`, `%g`, `%s`, `%c`, `%[chars]`, `%n`, and `%%`, with optional maximum
+/// field widths.  A `*` after the `%` suppresses assignment, and an XPG positional
+/// specifier, e.g., `%2$d`, assigns the value to the given variable.  If `num_vars` is
+/// 0, the number of values is determined by the format string; otherwise it must match.
+pub(crate) fn scan(
+    input: &str,
+    fmt: &str,
+    num_vars: usize,
+) -> Result<Option<Values>, ResultCode> {
+    let (items, num_slots) = parse_format(fmt, num_vars)?;
+    let input: Vec<char> = input.chars().collect();
+    let mut values: Values = vec![None; num_slots];
+    let mut converted = false;
+    let mut underflow = false;
+    let mut p = 0;
+
+    for item in &items {
+        match item {
+            Item::Space => {
+                p += count_spaces(&input[p..]);
+                continue;
+            }
+            Item::Char(ch) => {
+                if p == input.len() {
+                    underflow = true;
+                    break;
+                }
+                if input[p] != *ch {
+                    break;
+                }
+                p += 1;
+                continue;
+            }
+            Item::Conv(conv) => {
+                // FIRST, all conversions but %c, %[, and %n skip leading whitespace.
+                if !matches!(conv.kind, Kind::Char | Kind::Set(..) | Kind::Count) {
+                    p += count_spaces(&input[p..]);
+                }
+
+                let field = &input[p..];
+                let field = &field[..conv.width.unwrap_or(field.len()).min(field.len())];
+
+                if field.is_empty() && !matches!(conv.kind, Kind::Count) {
+                    underflow = p == input.len();
+                    break;
+                }
+
+                // NEXT, convert the field.
+                let (value, len) = match &conv.kind {
+                    Kind::Int(radix) => match scan_int(field, *radix)? {
+                        Some((int, len)) => (Value::from(int), len),
+                        None => break,
+                    },
+                    Kind::Float => match scan_float(field) {
+                        Some((flt, len)) => (Value::from(flt), len),
+                        None => break,
+                    },
+                    Kind::Str => {
+                        let len = field.iter().take_while(|c| !c.is_whitespace()).count();
+                        (Value::from(field[..len].iter().collect::<String>()), len)
+                    }
+                    Kind::Char => (Value::from(field[0] as MoltInt), 1),
+                    Kind::Set(negate, ranges) => {
+                        let len = field
+                            .iter()
+                            .take_while(|c| in_set(**c, ranges) != *negate)
+                            .count();
+
+                        if len == 0 {
+                            break;
+                        }
+                        (Value::from(field[..len].iter().collect::<String>()), len)
+                    }
+                    Kind::Count => (Value::from(p as MoltInt), 0),
+                };
+
+                p += len;
+                converted = true;
+
+                if let Some(slot) = conv.slot {
+                    values[slot] = Some(value);
+                }
+            }
+        }
+    }
+
+    if underflow && !converted {
+        Ok(None)
+    } else {
+        Ok(Some(values))
+    }
+}
+
+/// Parses the format string into a list of items, returning the items and the number
+/// of values the conversions produce.
+fn parse_format(fmt: &str, num_vars: usize) -> Result<(Vec<Item>, usize), ResultCode> {
+    let chars: Vec<char> = fmt.chars().collect();
+    let mut items = Vec::new();
+
+    // The slot to assign next, whether positional specifiers are in use, and which
+    // slots have been assigned.
+    let mut next_slot = 0;
+    let mut xpg: Option<bool> = None;
+    let mut assigned: Vec<bool> = vec![false; num_vars];
+
+    // Inline, every value must be assigned, so there are no more values than conversions,
+    // nor conversions than "%" characters.
+    let max_values = chars.iter().filter(|&&c| c == '%').count();
+
+    let mut i = 0;
+
+    while i < chars.len() {
+        // FIRST, handle whitespace and ordinary characters.
+        if chars[i].is_whitespace() {
+            i += count_spaces(&chars[i..]);
+            items.push(Item::Space);
+            continue;
+        }
+
+        if chars[i] != '%' {
+            items.push(Item::Char(chars[i]));
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+
+        if i < chars.len() && chars[i] == '%' {
+            items.push(Item::Char('%'));
+            i += 1;
+            continue;
+        }
+
+        // NEXT, look for assignment suppression or an XPG positional specifier.
+        let digits = count_digits(&chars[i..]);
+        let mut slot = None;
+
+        if i < chars.len() && chars[i] == '*' {
+            i += 1;
+        } else {
+            let is_xpg = digits > 0 && chars.get(i + digits) == Some(&'$');
+
+            if *xpg.get_or_insert(is_xpg) != is_xpg {
+                return molt_err!("cannot mix \"%\" and \"%n$\" conversion specifiers");
+            }
+
+            if is_xpg {
+                let n = parse_digits(&chars[i..i + digits]).unwrap_or(usize::MAX);
+
+                if n == 0 || (num_vars > 0 && n > num_vars) {
+                    return molt_err!("\"%n$\" argument index out of range");
+                }
+
+                if n > max_values {
+                    return molt_err!("variable is not assigned by any conversion specifiers");
+                }
+
+                slot = Some(n - 1);
+                i += digits + 1;
+            } else {
+                slot = Some(next_slot);
+                next_slot += 1;
+            }
+        }
+
+        // NEXT, get the maximum field width.
+        let digits = count_digits(&chars[i..]);
+        let width = match parse_digits(&chars[i..i + digits]) {
+            Some(0) => None,
+            Some(n) => Some(n),
+            None => return molt_err!("field width too large"),
+        };
+        i += digits;
+
+        // NEXT, skip any size modifiers; all integers are 64-bit.
+        while i < chars.len() && matches!(chars[i], 'h' | 'l' | 'L' | 'j' | 'z' | 't') {
+            i += 1;
+        }
+
+        // NEXT, get the conversion character.
+        if i == chars.len() {
+            return molt_err!("format string ended in middle of field specifier");
+        }
+
+        let conv = chars[i];
+        i += 1;
+
+        let kind = match conv {
+            'd' | 'u' => Kind::Int(10),
+            'i' => Kind::Int(0),
+            'o' => Kind::Int(8),
+            'x' | 'X' => Kind::Int(16),
+            'b' => Kind::Int(2),
+            'f' | 'e' | 'E' | 'g' | 'G' => Kind::Float,
+            's' => Kind::Str,
+            'c' => {
+                if width.is_some() {
+                    return molt_err!("field width may not be specified in %c conversion");
+                }
+                Kind::Char
+            }
+            'n' => Kind::Count,
+            '[' => {
+                let (set, len) = parse_set(&chars[i..])?;
+                i += len;
+                set
+            }
+            _ => return molt_err!("bad scan conversion character \"{}\"", conv),
+        };
+
+        // NEXT, record the slot's assignment.
+        if let Some(slot) = slot {
+            if slot >= assigned.len() {
+                assigned.resize(slot + 1, false);
+            }
+
+            if assigned[slot] && xpg == Some(true) {
+                return molt_err!(
+                    "variable is assigned by multiple \"%n$\" conversion specifiers"
+                );
+            }
+            assigned[slot] = true;
+        }
+
+        items.push(Item::Conv(Conv { slot, width, kind }));
+    }
+
+    // NEXT, make sure every variable gets a value.
+    if xpg != Some(true) && num_vars > 0 && next_slot != num_vars {
+        return molt_err!("different numbers of variable names and field specifiers");
+    }
+
+    if assigned.contains(&false) {
+        return molt_err!("variable is not assigned by any conversion specifiers");
+    }
+
+    Ok((items, assigned.len()))
+}
+
+/// Parses the body of a `%[...]` character set, which follows the `[`.  Returns the
+/// set and the number of characters parsed, including the closing `]`.  A `]` at the
+/// beginning of the set is a member of it.
+fn parse_set(chars: &[char]) -> Result<(Kind, usize), ResultCode> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+
+    let negate = chars.first() == Some(&'^');
+
+    if negate {
+        i += 1;
+    }
+
+    if chars.get(i) == Some(&']') {
+        ranges.push((']', ']'));
+        i += 1;
+    }
+
+    while i < chars.len() && chars[i] != ']' {
+        if i + 2 < chars.len() && chars[i + 1] == '-' && chars[i + 2] != ']' {
+            let (first, last) = (chars[i], chars[i + 2]);
+            ranges.push((first.min(last), first.max(last)));
+            i += 3;
+        } else {
+            ranges.push((chars[i], chars[i]));
+            i += 1;
+        }
+    }
+
+    if i == chars.len() {
+        return molt_err!("unmatched [ in format string");
+    }
+
+    Ok((Kind::Set(negate, ranges), i + 1))
+}
+
+/// Determines whether the character is in one of the ranges.
+fn in_set(ch: char, ranges: &[(char, char)]) -> bool {
+    ranges.iter().any(|(first, last)| *first <= ch && ch <= *last)
+}
+
+/// Scans an integer in the given radix from the head of the field, returning the
+/// integer and the number of characters consumed, or None if there is no integer.
+/// Radix 0 determines the radix from the prefix: `0x` for hex, `0` for octal.
+fn scan_int(field: &[char], radix: u32) -> Result<Option<(MoltInt, usize)>, ResultCode> {
+    let mut i = 0;
+    let mut radix = radix;
+
+    // FIRST, get the sign.
+    let negative = field[0] == '-';
+
+    if field[0] == '-' || field[0] == '+' {
+        i += 1;
+    }
+
+    // NEXT, get any radix prefix.
+    let has_hex_prefix = i + 2 < field.len()
+        && field[i] == '0'
+        && (field[i + 1] == 'x' || field[i + 1] == 'X')
+        && field[i + 2].is_ascii_hexdigit();
+
+    if (radix == 0 || radix == 16) && has_hex_prefix {
+        radix = 16;
+        i += 2;
+    } else if radix == 0 {
+        radix = if field.get(i) == Some(&'0') { 8 } else { 10 };
+    }
+
+    // NEXT, get the digits.
+    let digits = field[i..].iter().take_while(|c| c.is_digit(radix)).count();
+
+    if digits == 0 {
+        return Ok(None);
+    }
+
+    let text: String = field[i..i + digits].iter().collect();
+
+    // Decimal integers must fit in a MoltInt; other radices may use all 64 bits.
+    let magnitude = match u64::from_str_radix(&text, radix) {
+        Ok(mag) if radix != 10 || mag <= MoltInt::MAX as u64 + negative as u64 => mag,
+        _ => return molt_err!("integer value too large to represent"),
+    };
+
+    let int = if negative {
+        (magnitude as MoltInt).wrapping_neg()
+    } else {
+        magnitude as MoltInt
+    };
+
+    Ok(Some((int, i + digits)))
+}
+
+/// Scans a floating-point number from the head of the field, returning the number and
+/// the number of characters consumed, or None if there is no number.
+fn scan_float(field: &[char]) -> Option<(MoltFloat, usize)> {
+    let mut i = 0;
+
+    // FIRST, get the sign.
+    if field[0] == '-' || field[0] == '+' {
+        i += 1;
+    }
+
+    // NEXT, get the mantissa.
+    let int_digits = count_digits(&field[i..]);
+    i += int_digits;
+
+    let mut frac_digits = 0;
+
+    if field.get(i) == Some(&'.') {
+        frac_digits = count_digits(&field[i + 1..]);
+        i += 1 + frac_digits;
+    }
+
+    if int_digits + frac_digits == 0 {
+        return None;
+    }
+
+    // NEXT, get the exponent, if it has any digits.
+    if i < field.len() && (field[i] == 'e' || field[i] == 'E') {
+        let mut j = i + 1;
+
+        if j < field.len() && (field[j] == '-' || field[j] == '+') {
+            j += 1;
+        }
+
+        let exp_digits = count_digits(&field[j..]);
+
+        if exp_digits > 0 {
+            i = j + exp_digits;
+        }
+    }
+
+    let text: String = field[..i].iter().collect();
+
+    text.parse().ok().map(|flt| (flt, i))
+}
+
+/// Counts the whitespace characters at the head of the slice.
+fn count_spaces(chars: &[char]) -> usize {
+    chars.iter().take_while(|c| c.is_whitespace()).count()
+}
+
+/// Counts the decimal digits at the head of the slice.
+fn count_digits(chars: &[char]) -> usize {
+    chars.iter().take_while(|c| c.is_ascii_digit()).count()
+}
+
+/// Parses a slice of decimal digits; the empty slice is 0.  Returns None if the number
+/// is too large for a `usize`.
+fn parse_digits(chars: &[char]) -> Option<usize> {
+    chars.iter().try_fold(0usize, |n, c| {
+        n.checked_mul(10)?.checked_add(c.to_digit(10).unwrap() as usize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scans in inline mode, returning the values as strings; an unconverted value is
+    /// "-".
+    fn sc(input: &str, fmt: &str) -> Option<Vec<String>> {
+        scan(input, fmt, 0).unwrap().map(|values| {
+            values
+                .iter()
+                .map(|v| match v {
+                    Some(v) => v.to_string(),
+                    None => "-".to_string(),
+                })
+                .collect()
+        })
+    }
+
+    fn sc_err(input: &str, fmt: &str, num_vars: usize) -> String {
+        match scan(input, fmt, num_vars) {
+            Err(ResultCode::Error(msg)) => msg.to_string(),
+            _ => panic!("expected error"),
+        }
+    }
+
+    fn v(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(sc("42", "%d"), v(&["42"]));
+        assert_eq!(sc("  -42 +7", "%d %d"), v(&["-42", "7"]));
+        assert_eq!(sc("12345", "%3d%d"), v(&["123", "45"]));
+        assert_eq!(sc("ff 0x1F 17 101", "%x %x %o %b"), v(&["255", "31", "15", "5"]));
+        assert_eq!(sc("0x10 010 10", "%i %i %i"), v(&["16", "8", "10"]));
+        assert_eq!(sc("ffffffffffffffff", "%x"), v(&["-1"]));
+        assert_eq!(sc("-9223372036854775808", "%d"), v(&["-9223372036854775808"]));
+        assert_eq!(sc("12abc", "%d%s"), v(&["12", "abc"]));
+        assert_eq!(sc("-", "%d"), v(&["-"]));
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(sc("3.5", "%f"), v(&["3.5"]));
+        assert_eq!(sc("-1.5e3 .25 7", "%e %g %f"), v(&["-1500", "0.25", "7"]));
+        assert_eq!(sc("1.5e", "%f%s"), v(&["1.5", "e"]));
+        assert_eq!(sc("1.2345", "%4f"), v(&["1.23"]));
+        assert_eq!(sc(".", "%f"), v(&["-"]));
+    }
+
+    #[test]
+    fn test_strings_and_chars() {
+        assert_eq!(sc("  hello world", "%s%s"), v(&["hello", "world"]));
+        assert_eq!(sc("abcdef", "%3s%s"), v(&["abc", "def"]));
+        assert_eq!(sc(" a", "%c%c"), v(&["32", "97"]));
+        assert_eq!(sc("é", "%c"), v(&["233"]));
+    }
+
+    #[test]
+    fn test_sets() {
+        assert_eq!(sc("abc123", "%[a-z]%[0-9]"), v(&["abc", "123"]));
+        assert_eq!(sc("key=value", "%[^=]=%s"), v(&["key", "value"]));
+        assert_eq!(sc("]]x", "%[]]%s"), v(&["]]", "x"]));
+        assert_eq!(sc("a-b", "%[a-]"), v(&["a-"]));
+        assert_eq!(sc("abcdef", "%2[a-z]"), v(&["ab"]));
+        assert_eq!(sc("123", "%[a-z]"), v(&["-"]));
+    }
+
+    #[test]
+    fn test_literals_and_suppression() {
+        assert_eq!(sc("10:20", "%d:%d"), v(&["10", "20"]));
+        assert_eq!(sc("10-20", "%d:%d"), v(&["10", "-"]));
+        assert_eq!(sc("100%", "%d%%"), v(&["100"]));
+        assert_eq!(sc("1 2 3", "%d %*d %d"), v(&["1", "3"]));
+        assert_eq!(sc("abc", "%*s%n"), v(&["3"]));
+    }
+
+    #[test]
+    fn test_xpg() {
+        assert_eq!(sc("1 2", "%2$d %1$d"), v(&["2", "1"]));
+        assert_eq!(
+            sc_err("1 2", "%1$d %1$d", 0),
+            "variable is assigned by multiple \"%n$\" conversion specifiers"
+        );
+        assert_eq!(
+            sc_err("1 2", "%2$d", 0),
+            "variable is not assigned by any conversion specifiers"
+        );
+        assert_eq!(sc_err("1", "%3$d", 2), "\"%n$\" argument index out of range");
+        assert_eq!(
+            sc_err("1 2", "%1$d %d", 0),
+            "cannot mix \"%\" and \"%n$\" conversion specifiers"
+        );
+    }
+
+    #[test]
+    fn test_underflow() {
+        assert_eq!(sc("", "%d"), None);
+        assert_eq!(sc("   ", "%d %s"), None);
+        assert_eq!(sc("", "abc%d"), None);
+        assert_eq!(sc("12", "%d %d"), v(&["12", "-"]));
+        assert_eq!(sc("", "%n"), v(&["0"]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(sc_err("1", "%q", 0), "bad scan conversion character \"q\"");
+        assert_eq!(sc_err("1", "%[abc", 0), "unmatched [ in format string");
+        assert_eq!(sc_err("1", "%", 0), "format string ended in middle of field specifier");
+        assert_eq!(
+            sc_err("1", "%2c", 0),
+            "field width may not be specified in %c conversion"
+        );
+        assert_eq!(
+            sc_err("1 2", "%d %d", 1),
+            "different numbers of variable names and field specifiers"
+        );
+        assert_eq!(
+            sc_err("99999999999999999999", "%d", 0),
+            "integer value too large to represent"
+        );
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! A scope contains the variables for a given level in the call stack.  New scopes are pushed
 //! onto the stack by procedure on entry and popped on exit.  Variables in the current scope
-//! can be mapped to variables in lower scopes (e.g., scope #0, the `global` scope) using
-//! the `upvar` method.
+//! can be linked to variables in higher scopes (e.g., scope #0, the `global` scope) or
+//! to other variables in the current scope using the `upvar` and `link` methods.
 //!
 //! Scopes are numbered starting at `0`, the `global` scope.  Scopes with lower indices than
 //! the current are said to be higher in the stack, following Standard TCL practice (e.g.,
@@ -11,38 +11,114 @@
 //!
 //! Molt clients do not interact with this mechanism directly, but via the
 //! `Interp` (or the Molt language itself).
+//!
+//! If linked to the environment by `link_env`, the global array `env` mirrors the process's
+//! environment variables: it's populated when linked, and setting or unsetting one of its
+//! elements sets or removes the environment variable, so that programs run by `exec`
+//! see the change.
+//!
+//! Variables and array elements may have traces, commands to call when they are read,
+//! written, or unset, as added by `trace add variable`.  The scope stack keeps the traces
+//! with the variables they trace, following links; the `Interp` calls them.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use crate::value::Value;
 use crate::types::MoltList;
 
-/// A variable in a `Scope`.  If the variable is defined in the `Scope`, it has a
-/// `Value`; if it is a reference to a variable in a higher scope (e.g., a global) then
-/// the `Level` gives the referenced scope.
+/// A variable in a `Scope`.  If the variable is defined in the `Scope`, it is either a
+/// scalar with a `Value` or an array with a hash table of element `Value`s by element
+/// name; if it is a reference to a variable in a higher scope (e.g., a global) then
+/// the `Link` gives the referenced scope and the name of the variable in that scope.
 enum Var {
-    Value(Value),
-    Level(usize)
+    Scalar(Value),
+    Array(HashMap<String,Value>),
+    Link(usize, String),
 }
 
 /// A scope: a level in the `ScopeStack`.  It contains a hash table of `Var`'s by name.
 #[derive(Default)]
 struct Scope {
     /// Vars in this scope by name.
-    map: HashMap<String,Var>
+    map: HashMap<String,Var>,
+
+    /// The traces on vars in this scope, most recent first, by var name or, for traces on
+    /// array elements, "*name*(*index*)".
+    traces: HashMap<String, Vec<VarTrace>>,
 }
 
 impl Scope {
     /// Create a new empty scope.
     pub fn new() -> Self {
-        Scope { map: HashMap::new() }
+        Scope { map: HashMap::new(), traces: HashMap::new() }
+    }
+}
+
+/// An operation on a variable that can be traced.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum TraceOp {
+    Read,
+    Write,
+    Unset,
+}
+
+impl TraceOp {
+    /// The operations, in the order in which `trace info variable` reports them.
+    pub const ALL: [TraceOp; 3] = [TraceOp::Read, TraceOp::Write, TraceOp::Unset];
+
+    /// Gets the operation's name, as passed to trace commands.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TraceOp::Read => "read",
+            TraceOp::Write => "write",
+            TraceOp::Unset => "unset",
+        }
     }
 }
 
+/// A trace on a variable or array element: a command prefix to call when it's accessed
+/// by one of the operations.
+#[derive(Debug, Clone)]
+pub(crate) struct VarTrace {
+    pub ops: Vec<TraceOp>,
+    pub command: Value,
+}
+
+impl VarTrace {
+    /// Determines whether the trace has the same operations and command as another.
+    fn matches(&self, ops: &[TraceOp], command: &str) -> bool {
+        TraceOp::ALL.iter().all(|op| self.ops.contains(op) == ops.contains(op))
+            && *self.command.as_string() == command
+    }
+}
+
+/// The scopes above the global scope that belong to a suspended coroutine, including
+/// any scopes hidden by an `uplevel` within it, as saved by `ScopeStack::suspend_frames`.
+#[derive(Default)]
+pub(crate) struct Frames {
+    stack: Vec<Scope>,
+    hidden: Vec<Vec<Scope>>,
+}
+
 /// The scope stack: a stack of variable scopes corresponding to the Molt `proc`
 /// call stack.
 #[derive(Default)]
 pub(crate) struct ScopeStack {
     stack: Vec<Scope>,
+
+    /// Scopes hidden by `uplevel`, most recent last.
+    hidden: Vec<Vec<Scope>>,
+
+    /// The global variables being watched for writes, e.g., by `vwait`, most recent
+    /// last, each with a flag that is set when the variable is written or unset.
+    watches: Vec<(String, bool)>,
+
+    /// Whether the global array `env` mirrors the process's environment.
+    env: bool,
+
+    /// The number of traces on variables in all scopes, so that untraced variables can be
+    /// accessed without looking for traces.
+    num_traces: usize,
 }
 
 impl ScopeStack {
@@ -50,6 +126,10 @@ impl ScopeStack {
     pub fn new() -> Self {
         let mut ss = Self {
             stack: Vec::new(),
+            hidden: Vec::new(),
+            watches: Vec::new(),
+            env: false,
+            num_traces: 0,
         };
 
         ss.stack.push(Scope::new());
@@ -57,77 +137,242 @@ impl ScopeStack {
         ss
     }
 
-    /// Sets a variable to a value in the current scope.  If the variable is linked to
+    /// Sets a scalar variable to a value in the current scope.  If the variable is linked to
     /// another scope, the value is set there instead.  The variable is created if it does
     /// not already exist.
+    ///
+    /// If the variable is an array, it is replaced by the scalar; it is up to the caller
+    /// to check `is_array` first if that's an error.
     pub fn set(&mut self, name: &str, value: Value) -> Value {
-        let top = self.stack.len() - 1;
+        let (level, name) = self.true_var(self.current(), name);
 
-        self.set_at(top, name, value.clone());
+        self.notify(level, &name, None);
+        self.stack[level].map.insert(name.into_owned(), Var::Scalar(value.clone()));
         value
     }
 
-    /// Gets the value of the named variable in the current scope, if present.
+    /// Gets the value of the named scalar variable in the current scope, if present.
+    /// Returns `None` if the variable is an array.
     pub fn get(&self, name: &str) -> Option<Value> {
-        let top = self.stack.len() - 1;
+        let (level, name) = self.true_var(self.current(), name);
 
-        self.get_at(top, name)
+        match self.stack[level].map.get(&*name) {
+            Some(Var::Scalar(value)) => Some(value.clone()),
+            _ =>  None,
+        }
     }
 
     /// Unsets a variable in the current scope, i.e., removes it from the scope.
-    /// If the variable is a reference to another scope, the variable is removed from that
-    /// scope as well.
+    /// If the variable is linked to a variable in another scope, the linked variable is
+    /// removed instead; the link remains, so that setting the variable again sets the
+    /// linked variable.  If the variable is an array, the entire array is removed.
     pub fn unset(&mut self, name: &str) {
-        let top = self.stack.len() - 1;
-        self.unset_at(top, name);
+        let (level, name) = self.true_var(self.current(), name);
+        self.notify(level, &name, None);
+        self.stack[level].map.remove(&*name);
     }
 
-    /// Gets the value at the given level, recursing up the stack as needed.
-    fn get_at(&self, level: usize, name: &str) -> Option<Value> {
-        match self.stack[level].map.get(name) {
-            Some(Var::Value(value)) => Some(value.clone()),
-            Some(Var::Level(at)) => self.get_at(*at, name),
-            _ =>  None,
+    /// Determines whether the named variable exists in the current scope, as either a
+    /// scalar or an array.
+    pub fn exists(&self, name: &str) -> bool {
+        let (level, name) = self.true_var(self.current(), name);
+
+        matches!(self.stack[level].map.get(&*name), Some(Var::Scalar(_)) | Some(Var::Array(_)))
+    }
+
+    /// Determines whether the named variable is an array variable.
+    pub fn is_array(&self, name: &str) -> bool {
+        let (level, name) = self.true_var(self.current(), name);
+
+        matches!(self.stack[level].map.get(&*name), Some(Var::Array(_)))
+    }
+
+    /// Sets an element of the named array variable in the current scope, creating the
+    /// array if it does not already exist.  If the variable is a scalar it is replaced by
+    /// the array; it is up to the caller to check for that if it's an error.
+    pub fn set_elem(&mut self, name: &str, index: &str, value: Value) -> Value {
+        let (level, name) = self.true_var(self.current(), name);
+        self.notify(level, &name, Some(index));
+        let map = &mut self.stack[level].map;
+
+        if let Some(Var::Array(array)) = map.get_mut(&*name) {
+            array.insert(index.into(), value.clone());
+        } else {
+            let mut array = HashMap::new();
+            array.insert(index.into(), value.clone());
+            map.insert(name.to_string(), Var::Array(array));
+        }
+
+        if self.is_env(level, &name) {
+            set_env(index, &value.as_string());
         }
+
+        value
     }
 
-    /// Set a variable to a value at a given level in the stack.  If the variable at that level
-    /// is linked to a higher level, sets it at that level instead.
-    fn set_at(&mut self, level:usize, name: &str, value: Value) {
-        match self.stack[level].map.get(name) {
-            Some(Var::Level(at)) => {
-                let true_level = *at;
-                self.set_at(true_level, name, value);
+    /// Gets the value of an element of the named array variable, if present.  An element
+    /// of `env` that isn't present is read from the environment, in case the variable
+    /// was set since `env` was populated.
+    pub fn get_elem(&self, name: &str, index: &str) -> Option<Value> {
+        let (level, name) = self.true_var(self.current(), name);
+
+        match self.stack[level].map.get(&*name) {
+            Some(Var::Array(array)) => array.get(index).cloned().or_else(|| {
+                if self.is_env(level, &name) && is_env_name(index) {
+                    std::env::var(index).ok().map(Value::from)
+                } else {
+                    None
+                }
+            }),
+            _ => None,
+        }
+    }
+
+    /// Unsets an element of the named array variable.  The array itself remains, even
+    /// if it is now empty.
+    pub fn unset_elem(&mut self, name: &str, index: &str) {
+        let (level, name) = self.true_var(self.current(), name);
+        self.notify(level, &name, Some(index));
+
+        if let Some(Var::Array(array)) = self.stack[level].map.get_mut(&*name) {
+            array.remove(index);
+        }
+
+        if self.is_env(level, &name) && is_env_name(index) {
+            std::env::remove_var(index);
+        }
+    }
+
+    /// Gets the names of the elements of the named array variable.  The list is empty
+    /// if the variable isn't an array.
+    pub fn array_names(&self, name: &str) -> MoltList {
+        let (level, name) = self.true_var(self.current(), name);
+
+        match self.stack[level].map.get(&*name) {
+            Some(Var::Array(array)) => array.keys().map(Value::from).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Gets a flat list of the element names and values of the named array variable.
+    /// The list is empty if the variable isn't an array.
+    pub fn array_get(&self, name: &str) -> MoltList {
+        let (level, name) = self.true_var(self.current(), name);
+        let mut list = Vec::new();
+
+        if let Some(Var::Array(array)) = self.stack[level].map.get(&*name) {
+            for (key, value) in array {
+                list.push(Value::from(key));
+                list.push(value.clone());
             }
-            _ => {
-                self.stack[level].map.insert(name.into(), Var::Value(value));
+        }
+
+        list
+    }
+
+    /// Merges a flat list of element names and values into the named array variable,
+    /// creating the array if it does not already exist.  If the variable is a scalar it is
+    /// replaced by the array; it is up to the caller to check for that if it's an error.
+    pub fn array_set(&mut self, name: &str, list: &[Value]) {
+        assert!(list.len() % 2 != 1, "array_set requires an even-length list");
+        let (level, name) = self.true_var(self.current(), name);
+        for pair in list.chunks(2) {
+            self.notify(level, &name, Some(&pair[0].as_string()));
+        }
+        let map = &mut self.stack[level].map;
+
+        if let Some(Var::Array(_)) = map.get(&*name) {
+            // Already an array
+        } else {
+            map.insert(name.to_string(), Var::Array(HashMap::new()));
+        }
+
+        if let Some(Var::Array(array)) = map.get_mut(&*name) {
+            for pair in list.chunks(2) {
+                array.insert(pair[0].to_string(), pair[1].clone());
+            }
+        }
+
+        if self.is_env(level, &name) {
+            for pair in list.chunks(2) {
+                set_env(&pair[0].as_string(), &pair[1].as_string());
             }
         }
     }
 
-    /// Unset a variable at a given level in the stack.  If the variable at that level
-    /// is linked to a higher level, follows the chain down, unsetting as it goes.
-    fn unset_at(&mut self, level: usize, name: &str) {
-        // FIRST, if the variable at this level links to a lower level, follow the chain.
-        if let Some(Var::Level(at)) = self.stack[level].map.get(name) {
-            let true_level = *at;
-            self.unset_at(true_level, name);
+    /// Links the global array `env` to the process's environment, populating it with the
+    /// environment variables.  Variables whose names or values aren't valid UTF-8 are
+    /// skipped.
+    pub fn link_env(&mut self) {
+        let array = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, Value::from(value.into_string().ok()?))))
+            .collect();
+
+        self.stack[0].map.insert("env".into(), Var::Array(array));
+        self.env = true;
+    }
+
+    /// Determines whether the variable with the given true level and name is the global
+    /// array `env`, linked to the environment.
+    fn is_env(&self, level: usize, name: &str) -> bool {
+        self.env && level == 0 && name == "env"
+    }
+
+    /// Returns the level and name of the variable the named variable at the given level
+    /// actually refers to, following any links to other variables.
+    ///
+    /// Fully qualified names refer to the global scope: "::x" is the global variable `x`,
+    /// and "::foo::x" is the variable `x` in namespace `::foo`, stored in the global scope
+    /// as `foo::x`.
+    fn true_var<'a>(&self, level: usize, name: &'a str) -> (usize, Cow<'a, str>) {
+        if let Some(global) = name.strip_prefix("::") {
+            return self.true_var(0, global);
         }
 
-        // NEXT, remove the link at this level.
-        self.stack[level].map.remove(name);
+        match self.stack[level].map.get(name) {
+            Some(Var::Link(at, other)) => {
+                let (level, other) = self.true_var(*at, other);
+                (level, Cow::Owned(other.into_owned()))
+            }
+            _ => (level, Cow::Borrowed(name)),
+        }
     }
 
-    /// Links a variable in the current scope to variable at the given level, counting
-    /// from `0`, the global scope.
+    /// Links a variable in the current scope to the variable with the same name at the
+    /// given level, counting from `0`, the global scope, as for `global`.
     ///
     /// **Note:** does not try to create the variable at the referenced scope level, if it
     /// does not exist; the variable will be created on the first `set`, if any.  This is
     /// consistent with standard TCL behavior.
     pub fn upvar(&mut self, level: usize, name: &str) {
         assert!(level < self.current(), "Can't upvar to current stack level");
+        self.link(level, name, name);
+    }
+
+    /// Links the variable `name` in the current scope to the variable `other` at the given
+    /// level, which may be the current level.  Any existing variable called `name` in the
+    /// current scope is replaced.  Returns false, creating no link, if the link would
+    /// refer to the variable itself.  As for `upvar`, the variable at the referenced scope
+    /// level is created on the first `set`, if any.
+    pub fn link(&mut self, level: usize, other: &str, name: &str) -> bool {
+        assert!(level <= self.current(), "Can't link to lower stack level");
+        let top = self.current();
+        let (level, other) = self.true_var(level, other);
+
+        if level == top && other == name {
+            return false;
+        }
+
+        let link = Var::Link(level, other.into_owned());
+        self.stack[top].map.insert(name.into(), link);
+        true
+    }
+
+    /// Determines whether the named variable is defined in the current scope itself, i.e.,
+    /// exists as a scalar or array and isn't linked to another variable.
+    pub fn is_local(&self, name: &str) -> bool {
         let top = self.current();
-        self.stack[top].map.insert(name.into(), Var::Level(level));
+        matches!(self.stack[top].map.get(name), Some(Var::Scalar(_)) | Some(Var::Array(_)))
     }
 
     /// Returns the index of the current stack level, counting from 0, the global scope.
@@ -146,18 +391,300 @@ impl ScopeStack {
     /// Pops the current scope from the stack. Panics if we're at the global scope; this implies an
     /// coding error at the Rust level.
     pub fn pop(&mut self) {
-        self.stack.pop();
+        if let Some(scope) = self.stack.pop() {
+            self.num_traces -= scope.traces.values().map(Vec::len).sum::<usize>();
+        }
         assert!(!self.stack.is_empty(), "Popped global scope!");
     }
 
-    /// Gets the names of the variables defined in the current scope.
-    /// TODO: Should return a MoltList.
+    /// Makes the given level the current scope temporarily by hiding the scopes below it,
+    /// e.g., for `uplevel`.  Scopes pushed while the level is current are pushed above it.
+    /// Every call to `uplevel` must be matched by a call to `downlevel`.
+    pub fn uplevel(&mut self, level: usize) {
+        assert!(level <= self.current(), "Can't uplevel to a lower stack level");
+        let hidden = self.stack.split_off(level + 1);
+        self.hidden.push(hidden);
+    }
+
+    /// Restores the scopes hidden by the most recent call to `uplevel`.  Any scopes pushed
+    /// since then must already have been popped.
+    pub fn downlevel(&mut self) {
+        let mut hidden = self.hidden.pop().expect("downlevel without uplevel");
+        self.stack.append(&mut hidden);
+    }
+
+    /// Hides the scopes above the global scope, as for `uplevel #0`, and pushes the given
+    /// frames in their place, e.g., to resume a coroutine.  Returns a mark to pass to
+    /// `suspend_frames`, which must be called to restore the hidden scopes.
+    pub fn resume_frames(&mut self, frames: Frames) -> usize {
+        self.uplevel(0);
+        let mark = self.hidden.len();
+        self.hidden.extend(frames.hidden);
+        self.stack.extend(frames.stack);
+        mark
+    }
+
+    /// Removes the frames pushed since the matching call to `resume_frames`, returning
+    /// them, and restores the scopes hidden by it, e.g., when a coroutine yields.
+    pub fn suspend_frames(&mut self, mark: usize) -> Frames {
+        let frames = Frames {
+            stack: self.stack.split_off(1),
+            hidden: self.hidden.split_off(mark),
+        };
+        self.downlevel();
+        frames
+    }
+
+    /// Gets the names of the variables visible in the current scope, including variables
+    /// linked to other scopes, whether or not they exist there.
     pub fn vars_in_scope(&self) -> MoltList {
-        let top = self.stack.len() - 1;
-        // let vec: MoltList = self.stack[top].map.keys().cloned().map(|x| Value::new(&x)).collect();
-        //
-        // vec
-        self.stack[top].map.keys().cloned().map(|x| Value::from(&x)).collect()
+        let top = self.current();
+
+        self.stack[top]
+            .map
+            .keys()
+            .filter(|name| !name.contains("::"))
+            .map(Value::from)
+            .collect()
+    }
+
+    /// Gets the names of the variables local to the current scope, excluding variables
+    /// linked to other scopes.  The global scope has no local variables.
+    pub fn vars_local(&self) -> MoltList {
+        let top = self.current();
+
+        if top == 0 {
+            return Vec::new();
+        }
+
+        self.stack[top]
+            .map
+            .iter()
+            .filter(|(_, var)| !matches!(var, Var::Link(..)))
+            .map(|(name, _)| Value::from(name))
+            .collect()
+    }
+
+    /// Gets the names of the variables in the global scope, excluding namespace variables.
+    pub fn vars_global(&self) -> MoltList {
+        self.stack[0]
+            .map
+            .keys()
+            .filter(|name| !name.contains("::"))
+            .map(Value::from)
+            .collect()
+    }
+
+    /// Starts watching the named global variable for writes, as for `vwait`.  The name
+    /// may be an array element, e.g., "a(x)"; watching an array notes writes to any of its
+    /// elements.  Watches nest: each call to `watch` must be matched by a call to `unwatch`.
+    pub fn watch(&mut self, name: &str) {
+        let name = name.strip_prefix("::").unwrap_or(name);
+        self.watches.push((name.to_string(), false));
+    }
+
+    /// Determines whether the variable watched by the most recent call to `watch` has been
+    /// written or unset since then.
+    pub fn is_written(&self) -> bool {
+        matches!(self.watches.last(), Some((_, true)))
+    }
+
+    /// Stops watching the variable watched by the most recent call to `watch`.
+    pub fn unwatch(&mut self) {
+        self.watches.pop().expect("unwatch without watch");
+    }
+
+    /// Notes a write to or unset of the named variable or array element at the given level
+    /// for any watches on it.
+    fn notify(&mut self, level: usize, name: &str, index: Option<&str>) {
+        if level != 0 || self.watches.is_empty() {
+            return;
+        }
+
+        let elem = index.map(|index| format!("{}({})", name, index));
+
+        for (watched, written) in &mut self.watches {
+            if watched == name || Some(&*watched) == elem.as_ref() {
+                *written = true;
+            }
+        }
+    }
+
+    /// Removes the variables of a namespace and its child namespaces from the global
+    /// scope, given the prefix of their keys, e.g., "foo::" for the namespace `::foo`.
+    pub fn unset_namespace(&mut self, prefix: &str) {
+        self.stack[0].map.retain(|name, _| !name.starts_with(prefix));
+
+        let num_traces = &mut self.num_traces;
+        self.stack[0].traces.retain(|name, traces| {
+            let keep = !name.starts_with(prefix);
+            if !keep {
+                *num_traces -= traces.len();
+            }
+            keep
+        });
+    }
+
+    /// Determines whether any variable in any scope has traces.
+    pub fn has_traces(&self) -> bool {
+        self.num_traces > 0
+    }
+
+    /// Gets the level and name of the variable the named variable in the current scope
+    /// refers to, following links, e.g., to tell whether two names refer to the same
+    /// variable.
+    pub fn var_id(&self, name: &str) -> (usize, String) {
+        let (level, name) = self.true_var(self.current(), name);
+        (level, name.into_owned())
+    }
+
+    /// Adds a trace to the named variable in the current scope, or to the given element of
+    /// the named array, following links.  The variable needn't exist.
+    pub fn add_trace(&mut self, name: &str, index: Option<&str>, trace: VarTrace) {
+        let (level, name) = self.true_var(self.current(), name);
+        let key = trace_key(&name, index);
+
+        self.stack[level].traces.entry(key).or_default().insert(0, trace);
+        self.num_traces += 1;
+    }
+
+    /// Removes the most recent trace with the given operations and command from the named
+    /// variable or array element, if there is one.
+    pub fn remove_trace(&mut self, name: &str, index: Option<&str>, ops: &[TraceOp], command: &str) {
+        let (level, name) = self.true_var(self.current(), name);
+        let key = trace_key(&name, index);
+        let traces = &mut self.stack[level].traces;
+
+        if let Some(list) = traces.get_mut(&key) {
+            if let Some(i) = list.iter().position(|trace| trace.matches(ops, command)) {
+                list.remove(i);
+                self.num_traces -= 1;
+
+                if list.is_empty() {
+                    traces.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Gets the traces on the named variable, most recent first.  Given an index, gets the
+    /// traces on the array as a whole followed by the traces on the element.  Each trace
+    /// is paired with the element index to pass to it, or the empty string for a scalar.
+    pub fn traces(&self, name: &str, index: Option<&str>) -> Vec<(String, VarTrace)> {
+        if self.num_traces == 0 {
+            return Vec::new();
+        }
+
+        let (level, name) = self.true_var(self.current(), name);
+        let traces = &self.stack[level].traces;
+        let elem = index.unwrap_or("");
+        let mut result: Vec<(String, VarTrace)> = Vec::new();
+
+        if let Some(list) = traces.get(&*name) {
+            result.extend(list.iter().map(|trace| (elem.to_string(), trace.clone())));
+        }
+
+        if index.is_some() {
+            if let Some(list) = traces.get(&trace_key(&name, index)) {
+                result.extend(list.iter().map(|trace| (elem.to_string(), trace.clone())));
+            }
+        }
+
+        result
+    }
+
+    /// Gets the traces on the named variable, or on just the given element of the named
+    /// array, most recent first.
+    pub fn trace_info(&self, name: &str, index: Option<&str>) -> Vec<VarTrace> {
+        let (level, name) = self.true_var(self.current(), name);
+
+        match self.stack[level].traces.get(&trace_key(&name, index)) {
+            Some(list) => list.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Gets the traces on the named variable or array element to call when it's unset, as
+    /// for `traces`, removing those that go away with it: the traces on the variable and
+    /// all of its elements, or on the element.
+    pub fn take_traces(&mut self, name: &str, index: Option<&str>) -> Vec<(String, VarTrace)> {
+        if self.num_traces == 0 {
+            return Vec::new();
+        }
+
+        let (level, name) = self.true_var(self.current(), name);
+        let traces = &mut self.stack[level].traces;
+        let mut result: Vec<(String, VarTrace)> = Vec::new();
+        let mut taken = 0;
+
+        if let Some(index) = index {
+            if let Some(list) = traces.get(&*name) {
+                result.extend(list.iter().map(|trace| (index.to_string(), trace.clone())));
+            }
+
+            if let Some(list) = traces.remove(&trace_key(&name, Some(index))) {
+                taken += list.len();
+                result.extend(list.into_iter().map(|trace| (index.to_string(), trace)));
+            }
+        } else {
+            if let Some(list) = traces.remove(&*name) {
+                taken += list.len();
+                result.extend(list.into_iter().map(|trace| (String::new(), trace)));
+            }
+
+            let prefix = format!("{}(", name);
+            let keys: Vec<String> =
+                traces.keys().filter(|key| key.starts_with(&prefix)).cloned().collect();
+
+            for key in keys {
+                let index = key[prefix.len()..key.len() - 1].to_string();
+                let list = traces.remove(&key).unwrap_or_default();
+                taken += list.len();
+                result.extend(list.into_iter().map(|trace| (index.clone(), trace)));
+            }
+        }
+
+        self.num_traces -= taken;
+        result
+    }
+
+    /// Gets the names of the variables in the current scope that have traces on them or on
+    /// their elements, e.g., to call their unset traces before the scope is popped.
+    pub fn traced_locals(&self) -> Vec<String> {
+        if self.num_traces == 0 {
+            return Vec::new();
+        }
+
+        let top = self.current();
+        let mut names: Vec<String> = self.stack[top]
+            .traces
+            .keys()
+            .map(|key| key.split('(').next().unwrap_or(key).to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// Gets the key of the traces on the named variable or array element in a scope.
+fn trace_key(name: &str, index: Option<&str>) -> String {
+    match index {
+        Some(index) => format!("{}({})", name, index),
+        None => name.to_string(),
+    }
+}
+
+/// Determines whether the string is a valid environment variable name, as required by
+/// `std::env::set_var`.
+fn is_env_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains('=') && !name.contains('\0')
+}
+
+/// Sets the environment variable, if its name and value are valid.
+fn set_env(name: &str, value: &str) {
+    if is_env_name(name) && !value.contains('\0') {
+        std::env::set_var(name, value);
     }
 }
 
@@ -326,8 +853,37 @@ mod tests {
         ss.unset("a");
 
         assert!(ss.get("a").is_none());
+
+        // The link remains; setting it sets a@0.
+        ss.set("a", Value::from("2"));
         ss.pop();
-        assert!(ss.get("a").is_none());
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "2");
+    }
+
+    #[test]
+    fn test_link() {
+        let mut ss = ScopeStack::new();
+        ss.set("a", Value::from("1"));
+        ss.push();
+
+        // Link b@1 to a@0, and c@1 to b@1, i.e., to a@0.
+        assert!(ss.link(0, "a", "b"));
+        assert!(ss.link(1, "b", "c"));
+        assert_eq!(&*ss.get("b").unwrap().as_string(), "1");
+        assert_eq!(&*ss.get("c").unwrap().as_string(), "1");
+        assert!(!ss.is_local("b"));
+
+        ss.set("c", Value::from("2"));
+        assert_eq!(&*ss.get("b").unwrap().as_string(), "2");
+
+        // Links to the variable itself are rejected.
+        assert!(!ss.link(1, "d", "d"));
+        assert!(ss.link(1, "d", "e"));
+        assert!(!ss.link(1, "e", "d"));
+
+        ss.pop();
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "2");
+        assert!(ss.get("b").is_none());
     }
 
     #[test]
@@ -367,4 +923,284 @@ mod tests {
         assert_eq!(ss.vars_in_scope().len(), 1);
         assert!(!ss.vars_in_scope().contains(&Value::from("b")));
     }
+
+    #[test]
+    fn test_vars_local_and_global() {
+        let mut ss = ScopeStack::new();
+        ss.set("a", Value::from("1"));
+
+        // At the global scope, there are no locals.
+        assert_eq!(ss.vars_local().len(), 0);
+        assert_eq!(ss.vars_global(), vec![Value::from("a")]);
+
+        // Linked vars are visible, even if they don't exist, but not local.
+        ss.push();
+        ss.set("b", Value::from("2"));
+        ss.upvar(0, "a");
+        ss.upvar(0, "nonesuch");
+
+        assert_eq!(ss.vars_local(), vec![Value::from("b")]);
+        assert_eq!(ss.vars_global(), vec![Value::from("a")]);
+        assert_eq!(ss.vars_in_scope().len(), 3);
+        assert!(ss.vars_in_scope().contains(&Value::from("nonesuch")));
+    }
+
+    #[test]
+    fn test_set_get_elem() {
+        let mut ss = ScopeStack::new();
+
+        assert!(ss.get_elem("a", "x").is_none());
+
+        ss.set_elem("a", "x", Value::from("1"));
+        ss.set_elem("a", "y", Value::from("2"));
+        assert!(ss.is_array("a"));
+        assert!(ss.exists("a"));
+        assert_eq!(&*ss.get_elem("a", "x").unwrap().as_string(), "1");
+        assert_eq!(&*ss.get_elem("a", "y").unwrap().as_string(), "2");
+        assert!(ss.get_elem("a", "z").is_none());
+
+        // An array has no scalar value.
+        assert!(ss.get("a").is_none());
+
+        // A scalar has no elements.
+        ss.set("b", Value::from("3"));
+        assert!(!ss.is_array("b"));
+        assert!(ss.get_elem("b", "x").is_none());
+    }
+
+    #[test]
+    fn test_unset_elem() {
+        let mut ss = ScopeStack::new();
+
+        ss.set_elem("a", "x", Value::from("1"));
+        ss.set_elem("a", "y", Value::from("2"));
+        ss.unset_elem("a", "x");
+        assert!(ss.get_elem("a", "x").is_none());
+        assert!(ss.get_elem("a", "y").is_some());
+
+        // The array remains even when it's empty.
+        ss.unset_elem("a", "y");
+        assert!(ss.is_array("a"));
+        assert_eq!(ss.array_names("a").len(), 0);
+
+        ss.unset("a");
+        assert!(!ss.exists("a"));
+    }
+
+    #[test]
+    fn test_array_names_get() {
+        let mut ss = ScopeStack::new();
+        assert_eq!(ss.array_names("a").len(), 0);
+        assert_eq!(ss.array_get("a").len(), 0);
+
+        ss.set_elem("a", "x", Value::from("1"));
+        ss.set_elem("a", "y", Value::from("2"));
+
+        let names = ss.array_names("a");
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&Value::from("x")));
+        assert!(names.contains(&Value::from("y")));
+
+        let list = ss.array_get("a");
+        assert_eq!(list.len(), 4);
+        let i = list.iter().position(|v| v == &Value::from("x")).unwrap();
+        assert_eq!(list[i + 1], Value::from("1"));
+        let i = list.iter().position(|v| v == &Value::from("y")).unwrap();
+        assert_eq!(list[i + 1], Value::from("2"));
+    }
+
+    #[test]
+    fn test_array_set() {
+        let mut ss = ScopeStack::new();
+
+        // Creates an empty array
+        ss.array_set("a", &[]);
+        assert!(ss.is_array("a"));
+        assert_eq!(ss.array_names("a").len(), 0);
+
+        // Merges into an existing array
+        ss.set_elem("a", "x", Value::from("1"));
+        ss.array_set("a", &[Value::from("x"), Value::from("2"), Value::from("y"), Value::from("3")]);
+        assert_eq!(ss.array_names("a").len(), 2);
+        assert_eq!(&*ss.get_elem("a", "x").unwrap().as_string(), "2");
+        assert_eq!(&*ss.get_elem("a", "y").unwrap().as_string(), "3");
+    }
+
+    #[test]
+    fn test_elem_upvar() {
+        let mut ss = ScopeStack::new();
+
+        ss.set_elem("a", "x", Value::from("1"));
+        ss.push();
+        ss.upvar(0, "a");
+        assert!(ss.is_array("a"));
+        assert_eq!(&*ss.get_elem("a", "x").unwrap().as_string(), "1");
+
+        ss.set_elem("a", "y", Value::from("2"));
+        ss.pop();
+        assert_eq!(&*ss.get_elem("a", "y").unwrap().as_string(), "2");
+    }
+
+    #[test]
+    fn test_watch() {
+        let mut ss = ScopeStack::new();
+
+        ss.watch("a");
+        ss.set("b", Value::from(1));
+        assert!(!ss.is_written());
+        ss.set("a", Value::from(1));
+        assert!(ss.is_written());
+        ss.unwatch();
+
+        // Writes through links and with qualified names are noted; writes to local
+        // variables of the same name are not.
+        ss.watch("::a");
+        ss.push();
+        ss.set("a", Value::from(2));
+        assert!(!ss.is_written());
+        ss.upvar(0, "b");
+        ss.set("b", Value::from(2));
+        assert!(!ss.is_written());
+        ss.set("::a", Value::from(3));
+        assert!(ss.is_written());
+        ss.pop();
+        ss.unwatch();
+
+        // Watching an array notes writes to its elements.
+        ss.watch("arr");
+        ss.watch("arr(x)");
+        ss.set_elem("arr", "y", Value::from(1));
+        assert!(!ss.is_written());
+        ss.unset_elem("arr", "x");
+        assert!(ss.is_written());
+        ss.unwatch();
+        assert!(ss.is_written());
+        ss.unwatch();
+        assert!(!ss.is_written());
+    }
+
+    #[test]
+    fn test_link_env() {
+        std::env::set_var("MOLT_SCOPE_ENV_A", "a");
+        let mut ss = ScopeStack::new();
+        ss.link_env();
+        assert_eq!(&*ss.get_elem("env", "MOLT_SCOPE_ENV_A").unwrap().as_string(), "a");
+
+        // Setting and unsetting elements sets and removes the environment variables.
+        ss.set_elem("env", "MOLT_SCOPE_ENV_B", Value::from("b"));
+        assert_eq!(std::env::var("MOLT_SCOPE_ENV_B").unwrap(), "b");
+        ss.unset_elem("env", "MOLT_SCOPE_ENV_A");
+        assert!(std::env::var("MOLT_SCOPE_ENV_A").is_err());
+
+        // Variables set since linking are read from the environment.
+        std::env::set_var("MOLT_SCOPE_ENV_C", "c");
+        assert_eq!(&*ss.get_elem("env", "MOLT_SCOPE_ENV_C").unwrap().as_string(), "c");
+
+        // A local array named env is not linked.
+        ss.push();
+        ss.set_elem("env", "MOLT_SCOPE_ENV_D", Value::from("d"));
+        assert!(std::env::var("MOLT_SCOPE_ENV_D").is_err());
+        ss.pop();
+
+        std::env::remove_var("MOLT_SCOPE_ENV_B");
+        std::env::remove_var("MOLT_SCOPE_ENV_C");
+    }
+
+    #[test]
+    fn test_traces() {
+        let mut ss = ScopeStack::new();
+        assert!(!ss.has_traces());
+        let trace = |cmd: &str| VarTrace { ops: vec![TraceOp::Write], command: Value::from(cmd) };
+
+        // Traces follow links to the variables they trace.
+        ss.add_trace("a", None, trace("cmd1"));
+        ss.add_trace("a", Some("x"), trace("cmd2"));
+        ss.push();
+        ss.upvar(0, "a");
+        assert!(ss.has_traces());
+
+        let traces = ss.traces("a", Some("x"));
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].0, "x");
+        assert_eq!(&*traces[0].1.command.as_string(), "cmd1");
+        assert_eq!(&*traces[1].1.command.as_string(), "cmd2");
+        assert_eq!(ss.traces("a", None).len(), 1);
+
+        // Only matching traces are removed.
+        ss.remove_trace("a", None, &[TraceOp::Read], "cmd1");
+        assert_eq!(ss.traces("a", None).len(), 1);
+        ss.remove_trace("a", None, &[TraceOp::Write], "cmd1");
+        assert!(ss.traces("a", None).is_empty());
+
+        // Taking a variable's traces takes its elements' traces.
+        ss.add_trace("a", None, trace("cmd3"));
+        let taken = ss.take_traces("a", None);
+        assert_eq!(taken.len(), 2);
+        assert!(!ss.has_traces());
+
+        // Popping a scope drops its traces.
+        ss.add_trace("b", None, trace("cmd4"));
+        assert_eq!(ss.traced_locals(), vec!["b".to_string()]);
+        ss.pop();
+        assert!(!ss.has_traces());
+    }
+
+    #[test]
+    fn test_uplevel_downlevel() {
+        let mut ss = ScopeStack::new();
+        ss.set("a", Value::from("global"));
+        ss.push();
+        ss.set("a", Value::from("one"));
+        ss.push();
+        ss.set("a", Value::from("two"));
+
+        ss.uplevel(1);
+        assert_eq!(ss.current(), 1);
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "one");
+        ss.set("b", Value::from("new"));
+
+        // Scopes pushed while uplevel'd go above the new current level.
+        ss.push();
+        assert_eq!(ss.current(), 2);
+        assert!(ss.get("a").is_none());
+        ss.pop();
+
+        ss.uplevel(0);
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "global");
+        ss.downlevel();
+
+        ss.downlevel();
+        assert_eq!(ss.current(), 2);
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "two");
+        ss.pop();
+        assert_eq!(&*ss.get("b").unwrap().as_string(), "new");
+    }
+
+    #[test]
+    fn test_resume_suspend_frames() {
+        let mut ss = ScopeStack::new();
+        ss.push();
+        ss.set("a", Value::from("caller"));
+
+        // A coroutine's first resumption has no frames.
+        let mark = ss.resume_frames(Frames::default());
+        assert_eq!(ss.current(), 0);
+        ss.push();
+        ss.set("a", Value::from("coroutine"));
+        ss.uplevel(0);
+        let frames = ss.suspend_frames(mark);
+        assert_eq!(ss.current(), 1);
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "caller");
+
+        // The caller's scopes are hidden again, and the coroutine's restored.
+        let mark = ss.resume_frames(frames);
+        assert_eq!(ss.current(), 0);
+        ss.downlevel();
+        assert_eq!(ss.current(), 1);
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "coroutine");
+        ss.pop();
+        let frames = ss.suspend_frames(mark);
+        assert!(frames.stack.is_empty() && frames.hidden.is_empty());
+        assert_eq!(&*ss.get("a").unwrap().as_string(), "caller");
+    }
 }
@@ -0,0 +1,196 @@
+//! Socket Commands
+//!
+//! This module defines the `socket` command, which opens TCP connections as channels.  A
+//! client socket is a channel that can be read and written like any other.  A server
+//! socket is a channel that can only be configured and closed; while it's open, the event
+//! loop accepts connections on it, opening a channel for each and passing it to the
+//! server's command.  Like the file system commands, `socket` is kept apart from the
+//! standard commands; `Interp::new` adds it by calling `Interp::add_socket_commands`.
+
+use crate::channel;
+use crate::channel::Channel;
+use crate::channel::FileEvent;
+use crate::channel::Stream;
+use crate::channel::Translation;
+use crate::interp::Interp;
+use crate::types::*;
+use crate::util::posix_error;
+use crate::*;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+
+/// A server socket: a listener, and the command prefix to call with each connection.
+pub(crate) struct Server {
+    /// The listener, which is non-blocking so that the event loop can poll it.
+    listener: TcpListener,
+
+    /// The command prefix.
+    command: MoltList,
+}
+
+impl Server {
+    /// Gets the server's command prefix.
+    pub fn command(&self) -> &MoltList {
+        &self.command
+    }
+
+    /// Accepts a pending connection, if there is one, returning it in blocking mode along
+    /// with the address of its peer.  Failed connections are ignored.
+    pub fn accept(&mut self) -> Option<(TcpStream, SocketAddr)> {
+        let (stream, addr) = self.listener.accept().ok()?;
+        stream.set_nonblocking(false).ok()?;
+        Some((stream, addr))
+    }
+}
+
+impl Stream for Server {
+    fn server(&mut self) -> Option<&mut Server> {
+        Some(self)
+    }
+
+    fn sockname(&self) -> Option<SocketAddr> {
+        self.listener.local_addr().ok()
+    }
+}
+
+impl Stream for TcpStream {
+    fn reader(&mut self) -> Option<&mut dyn Read> {
+        Some(self)
+    }
+
+    fn writer(&mut self) -> Option<&mut dyn Write> {
+        Some(self)
+    }
+
+    fn set_blocking(&mut self, blocking: bool) -> io::Result<()> {
+        self.set_nonblocking(!blocking)
+    }
+
+    #[cfg(unix)]
+    fn is_ready(&mut self, event: FileEvent) -> bool {
+        use std::os::unix::io::AsRawFd;
+        channel::poll_fd(self.as_raw_fd(), event)
+    }
+
+    fn sockname(&self) -> Option<SocketAddr> {
+        self.local_addr().ok()
+    }
+
+    fn peername(&self) -> Option<SocketAddr> {
+        self.peer_addr().ok()
+    }
+}
+
+/// Creates a channel for a connected socket.  As in TCL, lines are written with `\r\n`,
+/// the line ending of most Internet protocols.
+pub(crate) fn socket_channel(stream: TcpStream) -> Channel {
+    let mut channel = Channel::new(Box::new(stream), true, true);
+    channel.set_translation(Translation::Auto, Translation::Crlf);
+    channel
+}
+
+/// # socket *host* *port*
+/// # socket -server *command* ?-myaddr *addr*? *port*
+///
+/// Opens a TCP connection to the *port* on the *host*, returning the name of a new
+/// channel, e.g., `sock3`.
+///
+/// With `-server`, opens a server socket listening on the *port*, on the address *addr*,
+/// by default all of the host's addresses, and returns its channel.  As each connection
+/// is accepted by the event loop, the *command* prefix is called with the name of a new
+/// channel for the connection, and the address and port of the client.
+pub fn cmd_socket(interp: &mut Interp, argv: &[Value]) -> MoltResult {
+    let mut command = None;
+    let mut myaddr = None;
+    let mut i = 1;
+
+    while i < argv.len() {
+        let opt = argv[i].as_string();
+
+        if !opt.starts_with('-') {
+            break;
+        }
+
+        let value = match argv.get(i + 1) {
+            Some(value) => value,
+            None => return molt_err!("no argument given for \"{}\" option", opt),
+        };
+
+        match opt.as_str() {
+            "-server" => command = Some(value.as_list()?.to_vec()),
+            "-myaddr" => myaddr = Some(value.as_string()),
+            _ => return molt_err!("bad option \"{}\": must be -myaddr or -server", opt),
+        }
+
+        i += 2;
+    }
+
+    let args = &argv[i..];
+
+    match command {
+        Some(command) => {
+            if args.len() != 1 {
+                return molt_err!(
+                    "wrong # args: should be \"socket -server command ?-myaddr addr? port\""
+                );
+            }
+
+            let port = socket_port(&args[0])?;
+            let host = myaddr.as_deref().map(String::as_str).unwrap_or("0.0.0.0");
+
+            match listen(host, port) {
+                Ok(listener) => {
+                    let server = Server { listener, command };
+                    molt_ok!(interp.add_channel("sock", Channel::new(Box::new(server), false, false)))
+                }
+                Err(e) => molt_err!("couldn't open socket: {}", posix_error(&e)),
+            }
+        }
+        None => {
+            if args.len() != 2 || myaddr.is_some() {
+                return molt_err!(
+                    "wrong # args: should be \"socket host port\" or \"socket -server command \
+                     ?-myaddr addr? port\""
+                );
+            }
+
+            let host = args[0].as_string();
+            let port = socket_port(&args[1])?;
+
+            match TcpStream::connect((host.as_str(), port)) {
+                Ok(stream) => molt_ok!(interp.add_channel("sock", socket_channel(stream))),
+                Err(e) => molt_err!("couldn't open socket: {}", posix_error(&e)),
+            }
+        }
+    }
+}
+
+/// Gets a port number.
+fn socket_port(value: &Value) -> Result<u16, ResultCode> {
+    let port = value.as_int()?;
+
+    if (0..=0xFFFF).contains(&port) {
+        Ok(port as u16)
+    } else {
+        molt_err!("expected port number between 0 and 65535 but got \"{}\"", value)
+    }
+}
+
+/// Opens a non-blocking listener on the host and port.
+fn listen(host: &str, port: u16) -> io::Result<TcpListener> {
+    let listener = TcpListener::bind((host, port))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Formats a socket address as for `fconfigure -sockname` and `-peername`: a list of the
+/// address, the host name, and the port.  Molt doesn't look up host names, so the address
+/// is given for both.
+pub(crate) fn addr_list(addr: SocketAddr) -> Value {
+    let ip = Value::from(addr.ip().to_string());
+    Value::from(vec![ip.clone(), ip, Value::from(addr.port() as MoltInt)])
+}
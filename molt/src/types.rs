@@ -1,7 +1,10 @@
 //! Public Type Declarations
 
 use crate::interp::Interp;
+use crate::util::unknown_subcommand;
 pub use crate::value::Value;
+use indexmap::IndexMap;
+use std::fmt;
 
 // Molt Numeric Types
 
@@ -27,11 +30,17 @@ pub type MoltFloat = f64;
 /// that implements and works with Molt commands.  A list is a list of `Value`s.
 pub type MoltList = Vec<Value>;
 
+/// The standard dictionary type for Molt code.
+///
+/// A dictionary is a mapping from keys to values that preserves the order in which the
+/// keys were added.  Its string representation is a list of alternating keys and values.
+pub type MoltDict = IndexMap<Value, Value>;
+
 /// Molt's standard `Result<T,E>` type.
 ///
 /// This is the most common result value returned by Molt code.  The
 /// `Ok` type is `Value`, the standard Molt value type; the `Err` type is
-/// [`ResultCode`], which encompasses the four exceptional Molt return values.
+/// [`ResultCode`], which encompasses the exceptional Molt return values.
 ///
 /// [`ResultCode`]: enum.ResultCode.html
 pub type MoltResult = Result<Value, ResultCode>;
@@ -42,15 +51,17 @@ pub type MoltResult = Result<Value, ResultCode>;
 /// [`MoltResult`], or it can return one of a number of exceptional results, which
 /// will bubble up the call stack in the usual way until caught.
 ///
-/// * `Error(Value)`: This code indicates a Molt error; the `Value` is the error message
-///   for display to the user.
+/// * `Error(ErrorData)`: This code indicates a Molt error; the [`ErrorData`] contains the
+///   error message for display to the user, along with the error code and error info that
+///   describe the error to Molt code.
 ///
-/// * `Return(Value)`: This code indicates that a Molt procedure called the
-///   `return` command.  The `Value` is the returned value, or the empty value if
-///   no value was returned.  This result will bubble up until it reaches the top-level
-///   of the procedure, which will then return the value as a normal `Ok` result.  If
-///   it is received when evaluating an arbitrary script, i.e., if `return` is called outside
-///   of any procedure, the interpreter will convert it into a normal `Ok` result.
+/// * `Return(ReturnData)`: This code indicates that a Molt procedure called the
+///   `return` command.  The [`ReturnData`] contains the returned value, or the empty value
+///   if no value was returned, along with the number of levels to return and the result
+///   code to return at that level.  This result will bubble up until it reaches the top-level
+///   of the procedure, which by default will then return the value as a normal `Ok` result.
+///   If it is received when evaluating an arbitrary script, i.e., if `return` is called
+///   outside of any procedure, the interpreter will handle it in the same way.
 ///
 /// * `Break`: This code indicates that the Molt `break` command was called.  It will
 ///   break out of the inmost enclosing loop in the usual way.  When returned outside a
@@ -62,33 +73,267 @@ pub type MoltResult = Result<Value, ResultCode>;
 ///   When returned outside a loop (or some user-defined control structure that supports
 ///   `continue`), the interpreter will convert it into an error.
 ///
-/// Client code will usually see only the `Error` code; the others will most often be caught
-/// and handled within the interpreter.
+/// * `TailCall(MoltList)`: This code indicates that the Molt `tailcall` command was called.
+///   The list contains the command to be invoked, with its command name already resolved;
+///   the procedure that called `tailcall` returns, and the command is invoked in its place
+///   in the caller's scope.
 ///
-/// # Future Work
+/// * `Other(MoltInt, Value)`: This code indicates an application-defined result code, i.e.,
+///   an integer other than 0 to 4, as returned by `return -code`, along with the value
+///   returned with it.  It propagates like `Break` and `Continue`, allowing applications to
+///   define new kinds of control structures; when returned from the top level of a script,
+///   the interpreter will convert it into an error.
 ///
-/// * Standard TCL allows for an arbitrary number of result codes, which in turn allows the
-///   application to define an arbitrary number of new kinds of control structures that are
-///   distinct from the standard ones.  At some point we might wish to add one or more
-///   generic result codes, parallel to `Break` and `Continue`, for this purpose.
+/// Client code will usually see only the `Error` code; the others will most often be caught
+/// and handled within the interpreter.
 ///
 /// [`MoltResult`]: type.MoltResult.html
+/// [`ErrorData`]: struct.ErrorData.html
+/// [`ReturnData`]: struct.ReturnData.html
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum ResultCode {
-    Error(Value),
-    Return(Value),
+    Error(ErrorData),
+    Return(ReturnData),
     Break,
     Continue,
+    TailCall(MoltList),
+    Other(MoltInt, Value),
 }
 
 impl ResultCode {
-    /// Indicates whether the result code is an `Error(String)`.
+    /// Creates an `Error` result code with the given error message, an error code of
+    /// `NONE`, and the message as its error info.
+    pub fn error(message: Value) -> Self {
+        ResultCode::Error(ErrorData::new(message))
+    }
+
+    /// Creates a `Return` result code with the given value, as returned by a plain
+    /// `return` command.
+    pub fn ret(value: Value) -> Self {
+        ResultCode::Return(ReturnData::new(value, 1, None))
+    }
+
+    /// Indicates whether the result code is an `Error(ErrorData)`.
     pub fn is_error(&self) -> bool {
         match self {
             ResultCode::Error(_) => true,
             _ => false,
         }
     }
+
+    /// Gets the result code's numeric value, as returned by the `catch` command: 1 for
+    /// `Error`, 2 for `Return`, 3 for `Break`, and 4 for `Continue`.  (0 is a normal
+    /// `Ok` result.)  A `TailCall` is a kind of return, and so is 2; `Other` has its own
+    /// code.
+    pub fn as_int(&self) -> MoltInt {
+        match self {
+            ResultCode::Error(_) => 1,
+            ResultCode::Return(_) => 2,
+            ResultCode::Break => 3,
+            ResultCode::Continue => 4,
+            ResultCode::TailCall(_) => 2,
+            ResultCode::Other(code, _) => *code,
+        }
+    }
+}
+
+/// The details of an error: the error message, plus the error code, error info, and error
+/// line that Molt code can retrieve using `catch`.
+///
+/// * The error message is the human-readable description of the error.
+///
+/// * The error code is a list describing the error in machine-readable form, e.g.,
+///   `{ARITH DIVZERO {divide by zero}}`; it defaults to `NONE`.
+///
+/// * The error info is a human-readable trace of the error, beginning with the error message
+///   and extended by the interpreter with each command and procedure through which the
+///   error propagates.
+///
+/// * The error line is the line number, within the script being evaluated, of the command
+///   that produced the error.
+///
+/// Two `ErrorData` values are equal if they have the same message and error code; the
+/// error info and error line describe where the error happened rather than what it is.
+#[derive(Clone, Debug)]
+pub struct ErrorData(Box<ErrorFields>);
+
+// The fields are boxed to keep the size of `ResultCode`, and hence of `MoltResult`, small.
+#[derive(Clone, Debug)]
+struct ErrorFields {
+    message: Value,
+    error_code: Value,
+    error_info: String,
+    error_line: usize,
+    // Whether the interpreter has yet to add anything to the error info.
+    is_new: bool,
+    // Whether the command that produced the error is already described by the error info.
+    is_logged: bool,
+}
+
+impl ErrorData {
+    /// Creates a new `ErrorData` with the given error message, an error code of `NONE`,
+    /// and the message as its error info.
+    pub fn new(message: Value) -> Self {
+        Self(Box::new(ErrorFields {
+            error_info: message.to_string(),
+            message,
+            error_code: Value::from("NONE"),
+            error_line: 1,
+            is_new: true,
+            is_logged: false,
+        }))
+    }
+
+    /// Gets the error message.
+    pub fn message(&self) -> &Value {
+        &self.0.message
+    }
+
+    /// Gets the error code.
+    pub fn error_code(&self) -> &Value {
+        &self.0.error_code
+    }
+
+    /// Sets the error code.
+    pub fn set_error_code(&mut self, code: Value) {
+        self.0.error_code = code;
+    }
+
+    /// Gets the error info.
+    pub fn error_info(&self) -> &str {
+        &self.0.error_info
+    }
+
+    /// Sets the error info.  The interpreter will extend the error info as the error
+    /// propagates, as though it had built the given info itself.
+    pub fn set_error_info(&mut self, info: &str) {
+        self.0.error_info = info.to_string();
+        self.0.is_new = false;
+    }
+
+    /// Marks the error info as already describing the command that produced the error, so
+    /// that the interpreter doesn't add that command to it, as for `error` with an
+    /// explicit error info.  Enclosing commands are added as usual.
+    pub fn set_logged(&mut self) {
+        self.0.is_logged = true;
+    }
+
+    /// Appends text to the error info, e.g., a line describing the context in which
+    /// the error occurred.
+    pub fn add_error_info(&mut self, text: &str) {
+        self.0.error_info.push_str(text);
+        self.0.is_new = false;
+    }
+
+    /// Adds the text of the command that produced the error to the error info: "while
+    /// executing" the command in which the error arose, and "invoked from within" each
+    /// enclosing command as the error propagates.
+    pub(crate) fn log_command(&mut self, command: &str) {
+        if self.0.is_logged {
+            self.0.is_logged = false;
+            return;
+        }
+
+        let context = if self.0.is_new {
+            "while executing"
+        } else {
+            "invoked from within"
+        };
+
+        self.add_error_info(&format!("\n    {}\n\"{}\"", context, command));
+    }
+
+    /// Gets the error line.
+    pub fn error_line(&self) -> usize {
+        self.0.error_line
+    }
+
+    /// Sets the error line.
+    pub fn set_error_line(&mut self, line: usize) {
+        self.0.error_line = line;
+    }
+}
+
+impl PartialEq for ErrorData {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.message == other.0.message && self.0.error_code == other.0.error_code
+    }
+}
+
+impl Eq for ErrorData {}
+
+impl fmt::Display for ErrorData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.message)
+    }
+}
+
+/// The details of an explicit `return`: the returned value, the number of levels to
+/// return, and the result code to return at that level.
+///
+/// Each procedure call (or top-level script evaluation) that receives a `Return` result
+/// decrements the level; when the level reaches zero, the `Return` is replaced by the
+/// pending result code: a normal `Ok` result with the returned value if there is none,
+/// and otherwise an `Error`, `Break`, or `Continue`.  Thus, `return -code break` in a
+/// procedure breaks out of the caller's loop.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ReturnData(Box<ReturnFields>);
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct ReturnFields {
+    value: Value,
+    level: usize,
+    code: Option<ResultCode>,
+}
+
+impl ReturnData {
+    /// Creates a new `ReturnData` that returns the given value through the given number of
+    /// levels, after which it becomes the given result code, or a normal result if `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level is zero, or if the code is itself a `Return` or a `TailCall`.
+    pub fn new(value: Value, level: usize, code: Option<ResultCode>) -> Self {
+        assert!(level > 0, "return level must be positive");
+        assert!(
+            !matches!(code, Some(ResultCode::Return(_)) | Some(ResultCode::TailCall(_))),
+            "pending result code cannot be a return or tail call"
+        );
+
+        Self(Box::new(ReturnFields { value, level, code }))
+    }
+
+    /// Gets the returned value.
+    pub fn value(&self) -> &Value {
+        &self.0.value
+    }
+
+    /// Gets the number of levels remaining to return.
+    pub fn level(&self) -> usize {
+        self.0.level
+    }
+
+    /// Gets the result code to return when the level reaches zero, or `None` for a
+    /// normal result.
+    pub fn code(&self) -> Option<&ResultCode> {
+        self.0.code.as_ref()
+    }
+
+    /// Returns one level: decrements the level, returning the pending result if it
+    /// has reached zero and the decremented `Return` otherwise.
+    pub fn return_level(mut self) -> MoltResult {
+        if self.0.level > 1 {
+            self.0.level -= 1;
+            return Err(ResultCode::Return(self));
+        }
+
+        let fields = *self.0;
+
+        match fields.code {
+            None => Ok(fields.value),
+            Some(code) => Err(code),
+        }
+    }
 }
 
 /// A trait defining a Molt command object: a struct that implements a command (and may also
@@ -119,6 +364,42 @@ pub trait Command {
 /// [`Interp`]: ../interp/struct.Interp.html
 pub type CommandFunc = fn(&mut Interp, &[Value]) -> MoltResult;
 
+/// A function that loads a package into the interpreter, e.g., by adding its commands, as
+/// registered by [`Interp::add_package`].
+///
+/// [`Interp::add_package`]: ../interp/struct.Interp.html#method.add_package
+pub type PackageInit = fn(&mut Interp) -> MoltResult;
+
+/// A store for the events in the interpreter's command history, as managed by the
+/// `history` command.  The interpreter has a simple store of its own; an application may
+/// supply another with [`Interp::set_history_store`], e.g., one backed by its line editor's
+/// history, so that the two share the same events.
+///
+/// [`Interp::set_history_store`]: ../interp/struct.Interp.html#method.set_history_store
+pub trait HistoryStore {
+    /// Adds an event to the end of the history, dropping the oldest event if the history
+    /// is full, and returns whether the event was added.
+    fn add(&mut self, event: &str) -> bool;
+
+    /// Gets the event at the index, counting from 0, the oldest event retained.
+    fn get(&self, index: usize) -> Option<String>;
+
+    /// Gets the number of events retained.
+    fn len(&self) -> usize;
+
+    /// Determines whether no events are retained.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sets the maximum number of events to retain, dropping the oldest events if there
+    /// are more than that.
+    fn set_max_len(&mut self, len: usize);
+
+    /// Drops all of the events.
+    fn clear(&mut self);
+}
+
 /// Used for defining subcommands of ensemble commands.
 ///
 /// The tuple fields are the subcommand's name and [`CommandFunc`].
@@ -148,24 +429,7 @@ impl Subcommand {
             }
         }
 
-        let mut names = String::new();
-        names.push_str(subs[0].0);
-        let last = subs.len() - 1;
-
-        if subs.len() > 1 {
-            names.push_str(", ");
-        }
-
-        if subs.len() > 2 {
-            let vec: Vec<&str> = subs[1..last].iter().map(|x| x.0).collect();
-            names.push_str(&vec.join(", "));
-        }
-
-        if subs.len() > 1 {
-            names.push_str(", or ");
-            names.push_str(subs[last].0);
-        }
-
-        molt_err!("unknown or ambiguous subcommand \"{}\": must be {}", sub, &names)
+        let names: Vec<&str> = subs.iter().map(|x| x.0).collect();
+        unknown_subcommand(&names, sub)
     }
 }
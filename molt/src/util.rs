@@ -3,6 +3,8 @@
 //! This module contains function for use by molt only.
 
 use crate::char_ptr::CharPtr;
+use crate::types::*;
+use std::io;
 
 /// Reads the integer string from the head of the input.  If the function returns `Some`,
 /// the value is the integer string that was read, and the `ptr` points to the following
@@ -144,6 +146,230 @@ pub fn read_float(ptr: &mut CharPtr) -> Option<String> {
     }
 }
 
+/// Determines whether the string matches the glob pattern, using the same rules as the
+/// standard TCL `string match` command.
+///
+/// The pattern may contain the following special sequences:
+///
+/// * `*` matches any sequence of characters, including the empty sequence.
+/// * `?` matches any single character.
+/// * `[chars]` matches any character in the set.  If *chars* contains a sequence of the
+///   form *x*-*y*, any character between *x* and *y*, inclusive, will match.
+/// * `\x` matches the single character *x*, so as to avoid any special interpretation
+///   of the characters `*?[]\` in the pattern.
+pub fn glob_match(pattern: &str, string: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let string: Vec<char> = string.chars().collect();
+
+    glob_match_chars(&pattern, &string)
+}
+
+/// Matches a string against a glob pattern, as for `glob_match`, but without regard to
+/// case.
+pub fn glob_match_nocase(pattern: &str, string: &str) -> bool {
+    glob_match(&pattern.to_lowercase(), &string.to_lowercase())
+}
+
+fn glob_match_chars(pattern: &[char], string: &[char]) -> bool {
+    let mut p = 0;
+    let mut s = 0;
+
+    while p < pattern.len() {
+        match pattern[p] {
+            '*' => {
+                // Skip successive stars; then try to match the rest of the pattern
+                // at every remaining position in the string.
+                while p < pattern.len() && pattern[p] == '*' {
+                    p += 1;
+                }
+
+                if p == pattern.len() {
+                    return true;
+                }
+
+                while s <= string.len() {
+                    if glob_match_chars(&pattern[p..], &string[s..]) {
+                        return true;
+                    }
+                    s += 1;
+                }
+
+                return false;
+            }
+            '?' => {
+                if s == string.len() {
+                    return false;
+                }
+            }
+            '[' => {
+                if s == string.len() {
+                    return false;
+                }
+
+                let ch = string[s];
+                let mut matched = false;
+                p += 1;
+
+                while p < pattern.len() && pattern[p] != ']' {
+                    let start = pattern[p];
+
+                    if p + 2 < pattern.len() && pattern[p + 1] == '-' && pattern[p + 2] != ']' {
+                        let end = pattern[p + 2];
+                        let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+                        matched = matched || (lo <= ch && ch <= hi);
+                        p += 3;
+                    } else {
+                        matched = matched || start == ch;
+                        p += 1;
+                    }
+                }
+
+                // An unterminated set can't match.
+                if !matched || p == pattern.len() {
+                    return false;
+                }
+            }
+            '\\' if p + 1 < pattern.len() => {
+                p += 1;
+
+                if s == string.len() || pattern[p] != string[s] {
+                    return false;
+                }
+            }
+            c => {
+                if s == string.len() || c != string[s] {
+                    return false;
+                }
+            }
+        }
+
+        p += 1;
+        s += 1;
+    }
+
+    s == string.len()
+}
+
+/// Converts a list index into an offset into a list of the given length.  The index may
+/// be an integer, the word `end` (the last element), `end-`*N* or `end+`*N*, or an
+/// integer plus or minus another integer, e.g., `2+1`.  The result might be out of range
+/// for the list; it's up to the caller to decide what to do about that.  Sums and
+/// differences that overflow saturate at the bounds of `MoltInt`.
+pub fn get_index(index: &Value, len: usize) -> Result<MoltInt, ResultCode> {
+    let string = &*index.as_string();
+    let s = string.trim();
+
+    let (base, rest) = if let Some(rest) = s.strip_prefix("end") {
+        ((len as MoltInt).saturating_sub(1), rest)
+    } else {
+        // Find the end of the leading integer, skipping any sign.
+        let start = if s.starts_with('+') || s.starts_with('-') { 1 } else { 0 };
+        let split = s[start..].find(['+', '-']).map_or(s.len(), |i| i + start);
+
+        match s[..split].parse::<MoltInt>() {
+            Ok(base) => (base, &s[split..]),
+            Err(_) => return bad_index(string),
+        }
+    };
+
+    if rest.is_empty() {
+        return Ok(base);
+    }
+
+    // The rest must be a signed integer offset.
+    if !rest.starts_with('+') && !rest.starts_with('-') {
+        return bad_index(string);
+    }
+
+    match rest[1..].parse::<MoltInt>() {
+        Ok(offset) if !rest[1..].starts_with('+') && !rest[1..].starts_with('-') => {
+            if rest.starts_with('+') {
+                Ok(base.saturating_add(offset))
+            } else {
+                Ok(base.saturating_sub(offset))
+            }
+        }
+        _ => bad_index(string),
+    }
+}
+
+fn bad_index(index: &str) -> Result<MoltInt, ResultCode> {
+    molt_err!("bad index \"{}\": must be integer?[+-]integer? or end?[+-]integer?", index)
+}
+
+/// Looks up a subcommand name in a list of the valid names, returning the matching name.
+/// If `prefixes` is true, any unambiguous prefix of a valid name matches it.  Otherwise,
+/// returns the usual error, listing the valid names in order.  Ensemble commands use this
+/// to dispatch to their subcommands.
+pub fn find_subcommand<'a>(
+    names: &[&'a str],
+    sub: &str,
+    prefixes: bool,
+) -> Result<&'a str, ResultCode> {
+    if let Some(name) = names.iter().find(|name| **name == sub) {
+        return Ok(name);
+    }
+
+    if prefixes && !sub.is_empty() {
+        let mut matches = names.iter().filter(|name| name.starts_with(sub));
+
+        if let (Some(name), None) = (matches.next(), matches.next()) {
+            return Ok(name);
+        }
+    }
+
+    unknown_subcommand(names, sub)
+}
+
+/// Returns the usual error for an unknown subcommand of an ensemble command, listing the
+/// valid names in order.
+pub fn unknown_subcommand<T>(names: &[&str], sub: &str) -> Result<T, ResultCode> {
+    let names = match names.len() {
+        0 => String::new(),
+        1 => names[0].to_string(),
+        2 => format!("{} or {}", names[0], names[1]),
+        n => format!("{}, or {}", names[..n - 1].join(", "), names[n - 1]),
+    };
+
+    molt_err!("unknown or ambiguous subcommand \"{}\": must be {}", sub, names)
+}
+
+/// Formats an I/O error the way TCL does, e.g., "no such file or directory", without
+/// Rust's "(os error N)" suffix.
+pub fn posix_error(err: &io::Error) -> String {
+    let msg = err.to_string();
+    let msg = match msg.find(" (os error") {
+        Some(index) => &msg[..index],
+        None => &msg,
+    };
+
+    let mut chars = msg.chars();
+    match chars.next() {
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The largest value a command will build: the length in bytes of a string or in
+/// elements of a list.  This is TCL's limit, and keeps absurd sizes from aborting the
+/// process when the allocation fails.
+pub const MAX_VALUE_SIZE: usize = i32::MAX as usize;
+
+/// Returns the size, or an error if it exceeds `MAX_VALUE_SIZE`.
+pub fn value_size(size: usize) -> Result<usize, ResultCode> {
+    if size <= MAX_VALUE_SIZE {
+        Ok(size)
+    } else {
+        molt_err!("max size for a Tcl value exceeded")
+    }
+}
+
+/// Returns the size of *count* repetitions of something of the given size, or an error if
+/// it would exceed `MAX_VALUE_SIZE`.
+pub fn repeated_size(size: usize, count: usize) -> Result<usize, ResultCode> {
+    value_size(size.saturating_mul(count))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,4 +464,121 @@ mod tests {
         assert_eq!(Some("-123".into()), read_float(&mut p));
         assert_eq!(Some('a'), p.peek());
     }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("", ""));
+        assert!(glob_match("abc", "abc"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(!glob_match("abcd", "abc"));
+
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "abc"));
+        assert!(glob_match("a*", "abc"));
+        assert!(glob_match("*c", "abc"));
+        assert!(glob_match("a*c", "abbbc"));
+        assert!(glob_match("a**c", "ac"));
+        assert!(!glob_match("a*c", "abd"));
+
+        assert!(glob_match("a?c", "abc"));
+        assert!(!glob_match("a?c", "ac"));
+
+        assert!(glob_match("[abc]x", "bx"));
+        assert!(!glob_match("[abc]x", "dx"));
+        assert!(glob_match("[a-c]x", "bx"));
+        assert!(glob_match("[c-a]x", "bx"));
+        assert!(!glob_match("[a-c]x", "dx"));
+        assert!(!glob_match("[abc", "a"));
+
+        assert!(glob_match("a\\*c", "a*c"));
+        assert!(!glob_match("a\\*c", "abc"));
+        assert!(glob_match("a\\", "a\\"));
+    }
+
+    #[test]
+    fn test_glob_match_nocase() {
+        assert!(glob_match_nocase("ABC", "abc"));
+        assert!(glob_match_nocase("a*", "ABC"));
+        assert!(glob_match_nocase("[A-C]x", "bX"));
+        assert!(!glob_match_nocase("a?c", "ABD"));
+    }
+
+    #[test]
+    fn test_find_subcommand() {
+        let names = ["create", "current", "delete"];
+        let msg = "must be create, current, or delete";
+
+        assert_eq!(find_subcommand(&names, "current", false), Ok("current"));
+        assert_eq!(find_subcommand(&names, "cur", false),
+            molt_err!("unknown or ambiguous subcommand \"cur\": {}", msg));
+        assert_eq!(find_subcommand(&names, "cur", true), Ok("current"));
+        assert_eq!(find_subcommand(&names, "d", true), Ok("delete"));
+        assert_eq!(find_subcommand(&names, "c", true),
+            molt_err!("unknown or ambiguous subcommand \"c\": {}", msg));
+        assert_eq!(find_subcommand(&names, "", true),
+            molt_err!("unknown or ambiguous subcommand \"\": {}", msg));
+        assert_eq!(find_subcommand(&names[..2], "x", true),
+            molt_err!("unknown or ambiguous subcommand \"x\": must be create or current"));
+    }
+
+    #[test]
+    fn test_get_index() {
+        assert_eq!(get_index(&Value::from("0"), 5), Ok(0));
+        assert_eq!(get_index(&Value::from("3"), 5), Ok(3));
+        assert_eq!(get_index(&Value::from("-1"), 5), Ok(-1));
+        assert_eq!(get_index(&Value::from("7"), 5), Ok(7));
+        assert_eq!(get_index(&Value::from("end"), 5), Ok(4));
+        assert_eq!(get_index(&Value::from("end-1"), 5), Ok(3));
+        assert_eq!(get_index(&Value::from("end+1"), 5), Ok(5));
+        assert_eq!(get_index(&Value::from("end"), 0), Ok(-1));
+        assert_eq!(get_index(&Value::from("1+2"), 5), Ok(3));
+        assert_eq!(get_index(&Value::from("1-2"), 5), Ok(-1));
+        assert_eq!(get_index(&Value::from("-1+2"), 5), Ok(1));
+        assert_eq!(get_index(&Value::from(" 2 "), 5), Ok(2));
+
+        // Overflowing sums and differences saturate.
+        assert_eq!(get_index(&Value::from("end+9223372036854775807"), 5), Ok(MoltInt::MAX));
+        assert_eq!(get_index(&Value::from("9223372036854775807+1"), 5), Ok(MoltInt::MAX));
+        assert_eq!(get_index(&Value::from("-9223372036854775808-1"), 5), Ok(MoltInt::MIN));
+        assert_eq!(get_index(&Value::from("end-9223372036854775807"), 0), Ok(MoltInt::MIN));
+
+        let msg = "must be integer?[+-]integer? or end?[+-]integer?";
+        assert_eq!(get_index(&Value::from("x"), 5),
+            molt_err!("bad index \"x\": {}", msg));
+        assert_eq!(get_index(&Value::from("endx"), 5),
+            molt_err!("bad index \"endx\": {}", msg));
+        assert_eq!(get_index(&Value::from("end-"), 5),
+            molt_err!("bad index \"end-\": {}", msg));
+        assert_eq!(get_index(&Value::from("end--1"), 5),
+            molt_err!("bad index \"end--1\": {}", msg));
+        assert_eq!(get_index(&Value::from("1+"), 5),
+            molt_err!("bad index \"1+\": {}", msg));
+        assert_eq!(get_index(&Value::from(""), 5),
+            molt_err!("bad index \"\": {}", msg));
+    }
+
+    #[test]
+    fn test_posix_error() {
+        let err = io::Error::from_raw_os_error(libc::ENOENT);
+        assert_eq!(posix_error(&err), "no such file or directory");
+
+        let err = io::Error::other("Some error");
+        assert_eq!(posix_error(&err), "some error");
+    }
+
+    #[test]
+    fn test_repeated_size() {
+        assert_eq!(repeated_size(3, 4), Ok(12));
+        assert_eq!(repeated_size(0, usize::MAX), Ok(0));
+        assert_eq!(repeated_size(MAX_VALUE_SIZE, 1), Ok(MAX_VALUE_SIZE));
+        assert!(repeated_size(MAX_VALUE_SIZE, 2).is_err());
+        assert!(repeated_size(2, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_value_size() {
+        assert_eq!(value_size(0), Ok(0));
+        assert_eq!(value_size(MAX_VALUE_SIZE), Ok(MAX_VALUE_SIZE));
+        assert!(value_size(MAX_VALUE_SIZE + 1).is_err());
+    }
 }
@@ -64,6 +64,15 @@
 //! In addition, a `Value` can also contain _external types_: Rust types that meet
 //! certain requirements.
 //!
+//! # Byte Arrays
+//!
+//! A `Value` can also hold binary data, e.g., as produced by the `binary format` command,
+//! as a byte array.  Its string rep contains one character per byte, the character with
+//! the byte's code, U+0000 to U+00FF; conversely, a string is converted to a byte array by
+//! taking the low eight bits of each character's code.  Thus bytes of 0x80 and above
+//! survive the round trip through the string rep, which is UTF-8 encoded, as do the
+//! strings that are read from and written to a channel whose encoding is `binary`.
+//!
 //! # External Types
 //!
 //! Any type that implements the `std::fmt::Display`, `std::fmt::Debug`,
@@ -131,7 +140,7 @@
 //!         if let Some(x) = value.as_copy::<Flavor>() {
 //!             Ok(x)
 //!         } else {
-//!             Err(ResultCode::Error(Value::from("Not a flavor string")))
+//!             Err(ResultCode::error(Value::from("Not a flavor string")))
 //!         }
 //!     }
 //! }
@@ -145,10 +154,15 @@ use std::any::TypeId;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::rc::Rc;
 use std::str::FromStr;
+use crate::dict::dict_to_string;
+use crate::dict::get_dict;
 use crate::list::get_list;
 use crate::list::list_to_string;
+use crate::types::MoltDict;
 use crate::types::MoltList;
 use crate::types::MoltInt;
 use crate::types::MoltFloat;
@@ -179,6 +193,13 @@ impl PartialEq for Value {
 
 impl Eq for Value {}
 
+impl Hash for Value {
+    // Values hash on their string representations, consistent with equality.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_string().hash(state);
+    }
+}
+
 impl From<String> for Value {
     /// Creates a new `Value` from the given String.
     ///
@@ -324,6 +345,28 @@ impl From<MoltList> for Value {
     }
 }
 
+impl From<MoltDict> for Value {
+    /// Creates a new `Value` whose data representation is a `MoltDict`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use molt::types::Value;
+    /// use molt::types::MoltDict;
+    ///
+    /// let mut dict = MoltDict::new();
+    /// dict.insert(Value::from("abc"), Value::from(1234));
+    /// let value = Value::from(dict);
+    /// assert_eq!(&*value.as_string(), "abc 1234");
+    /// ```
+    fn from(dict: MoltDict) -> Self {
+        Self {
+            string_rep: RefCell::new(None),
+            data_rep: RefCell::new(DataRep::Dict(Rc::new(dict))),
+        }
+    }
+}
+
 impl Value {
     /// Returns the empty `Value`, a value whose string representation is the empty
     /// string.
@@ -382,6 +425,16 @@ impl Value {
         new_string
     }
 
+    /// Consumes the `Value`, returning its string representation as an owned `String`.
+    /// The string is moved rather than copied if no other `Value` shares it, so that
+    /// commands like `append` can extend a variable's string in place.
+    pub(crate) fn into_string(self) -> String {
+        self.as_string();
+
+        let string = self.string_rep.into_inner().unwrap();
+        Rc::try_unwrap(string).unwrap_or_else(|string| (*string).clone())
+    }
+
     /// Tries to return the `Value` as a `bool`, parsing the
     /// value's string representation if necessary.
     ///
@@ -718,6 +771,102 @@ impl Value {
         Ok(list)
     }
 
+    /// Tries to return the `Value` as a `MoltDict`, parsing the
+    /// value's string representation if necessary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use molt::types::Value;
+    /// use molt::types::ResultCode;
+    /// # fn dummy() -> Result<String,ResultCode> {
+    ///
+    /// let value = Value::from("abc 1234 def 5678");
+    /// let dict = value.as_dict()?;
+    /// assert_eq!(dict.len(), 2);
+    ///
+    /// assert_eq!(dict.get(&Value::from("abc")), Some(&Value::from("1234")));
+    /// assert_eq!(dict.get(&Value::from("def")), Some(&Value::from("5678")));
+    ///
+    /// # Ok("dummy".to_string())
+    /// # }
+    /// ```
+    pub fn as_dict(&self) -> Result<Rc<MoltDict>, ResultCode> {
+        let mut string_ref = self.string_rep.borrow_mut();
+        let mut data_ref = self.data_rep.borrow_mut();
+
+        // FIRST, if we have the desired type, return it.
+        if let DataRep::Dict(dict) = &*data_ref {
+            return Ok(dict.clone());
+        }
+
+        // NEXT, if we don't have a string_rep, get one from the current
+        // data rep.
+        if (*string_ref).is_none() {
+            *string_ref = Some(Rc::new(data_ref.to_string()));
+        }
+
+        // NEXT, try to parse the string_rep as a dictionary.
+        let str = string_ref.as_ref().unwrap();
+        let dict = Rc::new(get_dict(str)?);
+        *data_ref = DataRep::Dict(dict.clone());
+
+        Ok(dict)
+    }
+
+    /// Creates a new `Value` whose data representation is a byte array.  Its string rep
+    /// contains one character per byte, the character with the byte's code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use molt::types::Value;
+    ///
+    /// let value = Value::from_bytes(vec![0x41, 0xE9]);
+    /// assert_eq!(&*value.as_string(), "A\u{E9}");
+    /// ```
+    pub fn from_bytes(bytes: Vec<u8>) -> Value {
+        Self {
+            string_rep: RefCell::new(None),
+            data_rep: RefCell::new(DataRep::Bytes(Rc::new(bytes))),
+        }
+    }
+
+    /// Returns the `Value` as a byte array, converting the value's string representation
+    /// if necessary: each character becomes the byte given by the low eight bits of its
+    /// code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use molt::types::Value;
+    ///
+    /// let value = Value::from("A\u{E9}");
+    /// assert_eq!(*value.as_bytes(), vec![0x41, 0xE9]);
+    /// ```
+    pub fn as_bytes(&self) -> Rc<Vec<u8>> {
+        let mut string_ref = self.string_rep.borrow_mut();
+        let mut data_ref = self.data_rep.borrow_mut();
+
+        // FIRST, if we have the desired type, return it.
+        if let DataRep::Bytes(bytes) = &*data_ref {
+            return bytes.clone();
+        }
+
+        // NEXT, if we don't have a string_rep, get one from the current
+        // data rep.
+        if (*string_ref).is_none() {
+            *string_ref = Some(Rc::new(data_ref.to_string()));
+        }
+
+        // NEXT, convert the string_rep to bytes.
+        let str = string_ref.as_ref().unwrap();
+        let bytes = Rc::new(str.chars().map(|c| c as u32 as u8).collect::<Vec<u8>>());
+        *data_ref = DataRep::Bytes(bytes.clone());
+
+        bytes
+    }
+
     /// Creates a new `Value` containing the given value of some user type.
     ///
     /// The user type must meet certain constraints; see the
@@ -816,7 +965,7 @@ impl Value {
             if let Ok(tval) = str.parse::<T>() {
                 let tval = Rc::new(tval);
                 let out = tval.clone();
-                *data_ref = DataRep::Other(Rc::new(tval));
+                *data_ref = DataRep::Other(tval);
                 return Some(out);
             }
         }
@@ -886,9 +1035,9 @@ impl Value {
         if let Some(str) = &*string_ref {
             if let Ok(tval) = str.parse::<T>() {
                 let tval = Rc::new(tval);
-                let out = tval.clone();
-                *data_ref = DataRep::Other(Rc::new(tval));
-                return Some(*out);
+                let out = *tval;
+                *data_ref = DataRep::Other(tval);
+                return Some(out);
             }
         }
 
@@ -965,6 +1114,12 @@ enum DataRep {
     /// A Molt List
     List(Rc<MoltList>),
 
+    /// A Molt Dictionary
+    Dict(Rc<MoltDict>),
+
+    /// A byte array
+    Bytes(Rc<Vec<u8>>),
+
     /// An external data type
     Other(Rc<dyn MoltAny>),
 
@@ -979,6 +1134,10 @@ impl Display for DataRep {
             DataRep::Int(int) => write!(f, "{}", int),
             DataRep::Flt(flt) => Value::fmt_float(f, *flt),
             DataRep::List(list) => write!(f, "{}", list_to_string(&*list)),
+            DataRep::Dict(dict) => write!(f, "{}", dict_to_string(dict)),
+            DataRep::Bytes(bytes) => {
+                write!(f, "{}", bytes.iter().map(|b| *b as char).collect::<String>())
+            }
             DataRep::Other(other) => write!(f, "{}", other),
             DataRep::None => write!(f, ""),
         }
@@ -1030,6 +1189,19 @@ mod tests {
         assert_eq!(*val.as_string(), *val2.to_string());
     }
 
+    #[test]
+    fn as_bytes() {
+        // Bytes of 0x80 and above survive the round trip through the string rep.
+        let val = Value::from_bytes(vec![0x00, 0x7F, 0x80, 0xFF]);
+        assert_eq!(val.as_string().chars().count(), 4);
+        let val2 = Value::from(val.to_string());
+        assert_eq!(*val2.as_bytes(), vec![0x00, 0x7F, 0x80, 0xFF]);
+
+        // Characters above U+00FF are truncated to their low eight bits.
+        let val = Value::from("\u{141}");
+        assert_eq!(*val.as_bytes(), vec![0x41]);
+    }
+
     #[test]
     fn compare() {
         let val = Value::from("123");
@@ -1194,6 +1366,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_as_dict() {
+        // NOTE: we aren't testing dict formatting and parsing here; that's done in dict.rs.
+        let mut dict = MoltDict::new();
+        dict.insert(Value::from("abc"), Value::from("def"));
+        let dictval = Value::from(dict);
+        assert_eq!(&*dictval.as_string(), "abc def");
+
+        let dictval = Value::from("qrs xyz");
+        let result = dictval.as_dict();
+
+        assert!(result.is_ok());
+
+        if let Ok(rcdict) = result {
+            assert_eq!(rcdict.len(), 1);
+            assert_eq!(rcdict.get(&Value::from("qrs")), Some(&Value::from("xyz")));
+        }
+
+        let dictval = Value::from("qrs");
+        assert_eq!(dictval.as_dict(), molt_err!("missing value to go with key"));
+    }
+
     #[test]
     fn from_to_flavor() {
         // Give a Flavor, get an Rc<Flavor> back.
@@ -1210,6 +1404,10 @@ mod tests {
         let out = result.unwrap();
         assert_eq!(*out, Flavor::SWEET);
 
+        // The parsed Flavor is saved in the Value.
+        let again = myval.as_other::<Flavor>().unwrap();
+        assert!(Rc::ptr_eq(&out, &again));
+
         // Flavor is Copy, so get a Flavor back
         let myval = Value::from_other(Flavor::SALTY);
         let result = myval.as_copy::<Flavor>();
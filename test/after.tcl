@@ -0,0 +1,133 @@
+# Test Script: after
+#
+# See vwait.tcl and update.tcl for the processing of scheduled events.
+
+test after-1.1 {after errors} {
+    after
+} -error {wrong # args: should be "after option ?arg ...?"}
+
+test after-1.2 {after errors} {
+    after nonesuch
+} -error {bad argument "nonesuch": must be cancel, idle, info, or an integer}
+
+test after-1.3 {after errors} {
+    after cancel
+} -error {wrong # args: should be "after cancel id|command"}
+
+test after-1.4 {after errors} {
+    after idle
+} -error {wrong # args: should be "after idle script ?script ...?"}
+
+test after-1.5 {after errors} {
+    after info a b
+} -error {wrong # args: should be "after info ?id?"}
+
+test after-1.6 {after errors} {
+    after info nonesuch
+} -error {event "nonesuch" doesn't exist}
+
+test after-2.1 {after ms sleeps} {
+    set start [clock milliseconds]
+    after 20
+    expr {[clock milliseconds] - $start >= 20}
+} -ok {1}
+
+test after-2.2 {after ms returns nothing} {
+    after 0
+} -ok {}
+
+test after-2.3 {after ms script schedules a timer} -body {
+    set id [after 1000 {set x 1}]
+    list [string match "after#*" $id] [after info $id]
+} -cleanup {
+    after cancel $id
+} -ok {1 {{set x 1} timer}}
+
+test after-2.4 {after concatenates its script arguments} -body {
+    set id [after 1000 set x " 1 "]
+    lindex [after info $id] 0
+} -cleanup {
+    after cancel $id
+} -ok {set x 1}
+
+test after-2.5 {after idle schedules an idle event} -body {
+    set id [after idle {set x 1}]
+    after info $id
+} -cleanup {
+    after cancel $id
+} -ok {{set x 1} idle}
+
+test after-2.6 {event ids are unique} -body {
+    set a [after 1000 {set x 1}]
+    set b [after 1000 {set x 1}]
+    set c [after idle {set x 1}]
+    list [expr {$a ne $b}] [expr {$b ne $c}] [expr {$a ne $c}]
+} -cleanup {
+    after cancel $a
+    after cancel $b
+    after cancel $c
+} -ok {1 1 1}
+
+test after-3.1 {after info lists pending events} -body {
+    set a [after 2000 {set x 1}]
+    set b [after 1000 {set x 2}]
+    set c [after idle {set x 3}]
+    expr {[after info] eq [list $b $a $c]}
+} -cleanup {
+    after cancel $a
+    after cancel $b
+    after cancel $c
+} -ok {1}
+
+test after-4.1 {after cancel by id} {
+    set id [after 1000 {set x 1}]
+    after cancel $id
+    list [lsearch [after info] $id] [catch {after info $id}]
+} -ok {-1 1}
+
+test after-4.2 {after cancel by script} {
+    set id [after 1000 set x 1]
+    after cancel set x 1
+    lsearch [after info] $id
+} -ok {-1}
+
+test after-4.3 {after cancel of an unknown event is ignored} {
+    after cancel nonesuch
+} -ok {}
+
+test after-5.1 {after scripts run at the global scope} -setup {
+    proc afproc {} {
+        set x "local"
+        after 0 {set ::af_level [info level]; set x "global"}
+        update
+        list $x $::x $::af_level
+    }
+} -body {
+    afproc
+} -cleanup {
+    rename afproc ""
+    unset ::x ::af_level
+} -ok {local global 0}
+
+test after-5.2 {after scripts in order of time due} -body {
+    set ::af_log {}
+    after 20 {lappend ::af_log 20}
+    after 10 {lappend ::af_log 10}
+    after 10 {lappend ::af_log 10b}
+    after 30 {set ::af_done 1}
+    vwait ::af_done
+    set ::af_log
+} -cleanup {
+    unset ::af_log ::af_done
+} -ok {10 10b 20}
+
+test after-5.3 {cancelled scripts don't run} -body {
+    set ::af_log {}
+    set id [after 0 {lappend ::af_log cancelled}]
+    after 0 {lappend ::af_log kept}
+    after cancel $id
+    update
+    set ::af_log
+} -cleanup {
+    unset ::af_log
+} -ok {kept}
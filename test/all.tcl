@@ -2,18 +2,76 @@
 #
 # If I add the "glob" command, I'll use that to pick up the tests.
 
+source after.tcl
 source append.tcl
+source apply.tcl
+source array.tcl
 source assert_eq.tcl
+source binary.tcl
 source break.tcl
+source catch.tcl
+source cd.tcl
+source clock.tcl
+source concat.tcl
 source continue.tcl
+source coroutine.tcl
+source dict.tcl
+source encoding.tcl
+source env.tcl
 source error.tcl
+source exec.tcl
 source exit.tcl
 source expr.tcl
+source fconfigure.tcl
+source file.tcl
+source fileevent.tcl
 source for.tcl
+source foreach.tcl
+source format.tcl
+source glob.tcl
+source history.tcl
+source info.tcl
 source interp.tcl
+source join.tcl
 source lappend.tcl
+source lassign.tcl
+source lindex.tcl
+source linsert.tcl
+source lmap.tcl
+source lrange.tcl
+source lrepeat.tcl
+source lreplace.tcl
+source lreverse.tcl
+source lsearch.tcl
+source lset.tcl
+source lsort.tcl
+source namespace.tcl
+source open.tcl
+source package.tcl
 source parser.tcl
+source pid.tcl
+source regexp.tcl
+source regsub.tcl
 source rename.tcl
+source scan.tcl
+source seek.tcl
+source socket.tcl
+source source.tcl
+source split.tcl
+source string.tcl
+source subst.tcl
+source switch.tcl
+source tailcall.tcl
+source throw.tcl
+source time.tcl
+source trace.tcl
+source try.tcl
+source unknown.tcl
+source update.tcl
+source uplevel.tcl
+source upvar.tcl
+source variable.tcl
+source vwait.tcl
 source while.tcl
 
 # Done last: these will be broken out into individual scripts.
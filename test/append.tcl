@@ -34,10 +34,65 @@ test append-3.1 {append errors} {
    append
 } -error {wrong # args: should be "append varName ?value value ...?"}
 
-# Need arrays
-if 0 {
 test append-3.2 {append errors} {
     set x ""
     list [catch {append x(0) 44} msg] $msg
 } -ok {1 {can't set "x(0)": variable isn't array}}
-}
+
+test append-4.1 {append doesn't change values shared with other variables} {
+    set a abc
+    set b $a
+    append a def
+    list $a $b
+} -ok {abcdef abc}
+
+test append-4.2 {append onto its own value} {
+    set a abc
+    append a $a $a
+} -ok {abcabcabc}
+
+test append-4.3 {append to a global in a proc} -setup {
+    proc appendproc {} {
+        global append_g
+        append append_g " more"
+    }
+} -body {
+    global append_g
+    set append_g start
+    appendproc
+    set append_g
+} -cleanup {
+    rename appendproc ""
+    global append_g
+    unset append_g
+} -ok {start more}
+
+test append-4.4 {append to a local in a proc} -setup {
+    proc appendproc {} {
+        for {set i 0} {$i < 3} {incr i} {
+            append s $i
+        }
+        set s
+    }
+} -body {
+    appendproc
+} -cleanup {
+    rename appendproc ""
+} -ok {012}
+
+test append-4.5 {append to a non-string value} {
+    set a [list 1 2]
+    append a " 3"
+    list $a [llength $a]
+} -ok {{1 2 3} 3}
+
+test append-4.6 {append to an array element} {
+    set arr(x) abc
+    append arr(x) def
+    set arr(x)
+} -ok {abcdef}
+
+test append-4.7 {append errors, array variable} {
+    array set app_arr {a 1}
+    append app_arr x
+} -error {can't set "app_arr": variable is array}
@@ -0,0 +1,89 @@
+# Test Script: apply
+
+test apply-1.1 {apply errors} {
+    apply
+} -error {wrong # args: should be "apply lambdaExpr ?arg ...?"}
+
+test apply-1.2 {apply errors} {
+    apply {x}
+} -error {can't interpret "x" as a lambda expression}
+
+test apply-1.3 {apply errors} {
+    apply {a b c d}
+} -error {can't interpret "a b c d" as a lambda expression}
+
+test apply-1.4 {apply errors} {
+    apply {{{}} {}}
+} -error {argument with no name}
+
+test apply-1.5 {apply errors} {
+    apply {{{a b c}} {}}
+} -error {too many fields in argument specifier "a b c"}
+
+test apply-1.6 {apply errors} {
+    apply {{x y} {}} 1
+} -error {wrong # args: should be "apply lambdaExpr x y"}
+
+test apply-1.7 {apply errors} {
+    apply {{x {y 2}} {}} 1 2 3
+} -error {wrong # args: should be "apply lambdaExpr x ?y?"}
+
+test apply-1.8 {apply errors} {
+    apply {{} {} nonesuch}
+} -error {namespace "::nonesuch" not found}
+
+test apply-2.1 {apply with no arguments} {
+    apply {{} {set a "value"}}
+} -ok {value}
+
+test apply-2.2 {apply with arguments} {
+    apply {{x y} {list $y $x}} 1 2
+} -ok {2 1}
+
+test apply-2.3 {apply with default values} {
+    list [apply {{x {y 2}} {list $x $y}} 1] [apply {{x {y 2}} {list $x $y}} 1 3]
+} -ok {{1 2} {1 3}}
+
+test apply-2.4 {apply with args} {
+    apply {{x args} {list $x $args}} 1 2 3
+} -ok {1 {2 3}}
+
+test apply-2.5 {return is a normal result} {
+    apply {{x} {return [expr {$x * 2}]; error "not reached"}} 21
+} -ok {42}
+
+test apply-2.6 {apply has its own scope} {
+    set x "outer"
+    list [apply {{} {set x "inner"}}] $x
+} -ok {inner outer}
+
+test apply-2.7 {apply as a command prefix} {
+    lsort -command {apply {{a b} {expr {$b - $a}}}} {3 1 2}
+} -ok {3 2 1}
+
+test apply-2.8 {apply in a namespace} -setup {
+    namespace eval ::applyns {
+        variable v "ns value"
+    }
+} -body {
+    apply {{} {
+        variable v
+        list [namespace current] $v
+    } applyns}
+} -cleanup {
+    namespace delete ::applyns
+} -ok {::applyns {ns value}}
+
+test apply-2.9 {info level} {
+    apply {{x} {info level 0}} 1
+} -ok {apply {{x} {info level 0}} 1}
+
+test apply-3.1 {lambda in error info} {
+    catch {apply {{} {error "simulated error"}}} msg opts
+    dict get $opts -errorinfo
+} -ok {simulated error
+    while executing
+"error "simulated error""
+    (lambda term "{} {error "simulated error"}" line 1)
+    invoked from within
+"apply {{} {error "simulated error"}}"}
@@ -0,0 +1,212 @@
+# Test Script: array command and array variables.
+
+test array-1.1 {array errors} {
+    array
+} -error {wrong # args: should be "array subcommand ?arg ...?"}
+
+test array-1.2 {array errors} {
+    array frob a
+} -error {unknown or ambiguous subcommand "frob": must be exists, get, names, set, size, or unset}
+
+test array-2.1 {array set and element access} {
+    array set a {x 1 y 2}
+    list $a(x) $a(y)
+} -ok {1 2}
+
+test array-2.2 {set and read elements} {
+    set a(x) 1
+    set a(y) [expr {$a(x) + 1}]
+    list [set a(x)] $a(y)
+} -ok {1 2}
+
+test array-2.3 {element index substitution} {
+    set a(1) one
+    set a(one.two) three
+    set i 1
+    set j two
+    list $a($i) $a([set a($i)].$j) ${a(1)}
+} -ok {one three one}
+
+test array-2.4 {element index substitution in quotes} {
+    set "a(x y)" 1
+    set i x
+    set b "<$a($i y)>"
+} -ok {<1>}
+
+test array-2.5 {array set merges} {
+    array set a {x 1 y 2}
+    array set a {y 3 z 4}
+    list [array size a] $a(x) $a(y) $a(z)
+} -ok {3 1 3 4}
+
+test array-2.6 {array set creates empty array} {
+    array set a {}
+    list [array exists a] [array size a]
+} -ok {1 0}
+
+test array-3.1 {reading array as scalar} {
+    set a(x) 1
+    set a
+} -error {can't read "a": variable is array}
+
+test array-3.2 {reading array as scalar} {
+    set a(x) 1
+    set b $a
+} -error {can't read "a": variable is array}
+
+test array-3.3 {setting array as scalar} {
+    set a(x) 1
+    set a 2
+} -error {can't set "a": variable is array}
+
+test array-3.4 {reading missing element} {
+    set a(x) 1
+    set a(y)
+} -error {can't read "a(y)": no such element in array}
+
+test array-3.5 {reading element of missing array} {
+    set b $a(y)
+} -error {can't read "a(y)": no such variable}
+
+test array-3.6 {reading element of scalar} {
+    set a 1
+    set a(y)
+} -error {can't read "a(y)": variable isn't array}
+
+test array-3.7 {setting element of scalar} {
+    set a 1
+    set a(y) 2
+} -error {can't set "a(y)": variable isn't array}
+
+test array-3.8 {missing close paren} {
+    set a(x) 1
+    set b $a(x
+} -error {missing )}
+
+test array-4.1 {array exists} {
+    set s 1
+    set a(x) 1
+    list [array exists a] [array exists s] [array exists nonesuch]
+} -ok {1 0 0}
+
+test array-4.2 {array exists errors} {
+    array exists
+} -error {wrong # args: should be "array exists arrayName"}
+
+test array-5.1 {array get} {
+    set a(x) 1
+    array get a
+} -ok {x 1}
+
+test array-5.2 {array get, pattern} {
+    array set a {x 1 y 2 zz 3}
+    array get a z*
+} -ok {zz 3}
+
+test array-5.3 {array get, not an array} {
+    set s 1
+    list [array get s] [array get nonesuch]
+} -ok {{} {}}
+
+test array-5.4 {array get, round trip} {
+    array set a {x 1 y 2 z 3}
+    array set b [array get a]
+    list [array size b] $b(x) $b(y) $b(z)
+} -ok {3 1 2 3}
+
+test array-5.5 {array get errors} {
+    array get
+} -error {wrong # args: should be "array get arrayName ?pattern?"}
+
+test array-6.1 {array names} {
+    array set a {x 1}
+    array names a
+} -ok {x}
+
+test array-6.2 {array names, pattern} {
+    array set a {x 1 y 2 zz 3}
+    list [array names a z*] [llength [array names a ?]] [llength [array names a]]
+} -ok {zz 2 3}
+
+test array-6.3 {array names, not an array} {
+    set s 1
+    list [array names s] [array names nonesuch]
+} -ok {{} {}}
+
+test array-6.4 {array names errors} {
+    array names
+} -error {wrong # args: should be "array names arrayName ?pattern?"}
+
+test array-7.1 {array set errors} {
+    array set a {x 1 y}
+} -error {list must have an even number of elements}
+
+test array-7.2 {array set errors} {
+    set s 1
+    array set s {x 1}
+} -error {can't array set "s": variable isn't array}
+
+test array-7.3 {array set errors} {
+    array set a
+} -error {wrong # args: should be "array set arrayName list"}
+
+test array-8.1 {array size} {
+    set s 1
+    array set a {x 1 y 2}
+    list [array size a] [array size s] [array size nonesuch]
+} -ok {2 0 0}
+
+test array-8.2 {array size errors} {
+    array size
+} -error {wrong # args: should be "array size arrayName"}
+
+test array-9.1 {array unset} {
+    array set a {x 1 y 2}
+    array unset a
+    array exists a
+} -ok {0}
+
+test array-9.2 {array unset, pattern} {
+    array set a {x 1 y 2 zz 3}
+    array unset a z*
+    list [array exists a] [array size a] [catch {set a(zz)}]
+} -ok {1 2 1}
+
+test array-9.3 {array unset, not an array} {
+    set s 1
+    array unset s
+    array unset nonesuch
+    set s
+} -ok {1}
+
+test array-9.4 {array unset errors} {
+    array unset
+} -error {wrong # args: should be "array unset arrayName ?pattern?"}
+
+test array-10.1 {unset element} {
+    array set a {x 1 y 2}
+    unset a(x)
+    array get a
+} -ok {y 2}
+
+test array-10.2 {append, lappend, incr on elements} {
+    append a(x) abc def
+    lappend a(y) abc def
+    incr a(z) 5
+    list $a(x) $a(y) $a(z)
+} -ok {abcdef {abc def} 5}
+
+test array-10.3 {global arrays} -setup {
+    proc foo {} {
+        global ga
+        set ga(x) 1
+    }
+} -body {
+    foo
+    global ga
+    set result $ga(x)
+    unset ga
+    set result
+} -cleanup {
+    rename foo ""
+} -ok {1}
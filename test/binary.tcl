@@ -0,0 +1,384 @@
+# Test Script: binary
+
+proc bytes {data} {
+    binary scan $data cu* codes
+    return $codes
+}
+
+test binary-1.1 {binary errors} {
+    binary
+} -error {wrong # args: should be "binary subcommand ?arg ...?"}
+
+test binary-1.2 {binary errors} {
+    binary nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be format or scan}
+
+test binary-2.1 {binary format errors} {
+    binary format
+} -error {wrong # args: should be "binary format formatString ?arg ...?"}
+
+test binary-2.2 {binary format errors} {
+    binary format q 1
+} -error {bad field specifier "q"}
+
+test binary-2.3 {binary format errors} {
+    binary format cc 1
+} -error {not enough arguments for all format specifiers}
+
+test binary-2.4 {binary format errors} {
+    binary format c3 {1 2}
+} -error {number of elements in list does not match count}
+
+test binary-2.5 {binary format errors} {
+    binary format c foo
+} -error {expected integer but got "foo"}
+
+test binary-2.6 {binary format errors} {
+    binary format d foo
+} -error {expected floating-point number but got "foo"}
+
+test binary-2.7 {binary format errors} {
+    binary format x*
+} -error {cannot use "*" in format string with "x"}
+
+test binary-2.8 {binary format errors} {
+    binary format @
+} -error {missing count for "@" field specifier}
+
+test binary-2.9 {binary format errors, fields too large} {
+    set a [catch {binary format x9223372036854775807} msg]
+    lappend a $msg [catch {binary format a9223372036854775807 abc} msg] $msg
+    lappend a [catch {binary format A2147483648 abc} msg] $msg
+} -ok {1 {max size for a Tcl value exceeded} 1 {max size for a Tcl value exceeded} 1 {max size for a Tcl value exceeded}}
+
+test binary-2.10 {binary format errors, position too large} {
+    binary format @9223372036854775807
+} -error {max size for a Tcl value exceeded}
+
+test binary-2.11 {binary format errors, fields too large in a safe interpreter} -setup {
+    interp create -safe binsafe
+} -body {
+    interp eval binsafe {binary format x9223372036854775807}
+} -cleanup {
+    interp delete binsafe
+} -error {max size for a Tcl value exceeded}
+
+test binary-3.1 {binary format a} {
+    binary format a abc
+} -ok {a}
+
+test binary-3.2 {binary format a with count} {
+    binary format a3 abcdef
+} -ok {abc}
+
+test binary-3.3 {binary format a pads with nulls} {
+    bytes [binary format a5 ab]
+} -ok {97 98 0 0 0}
+
+test binary-3.4 {binary format A pads with spaces} {
+    binary format A5 ab
+} -ok {ab   }
+
+test binary-3.5 {binary format a*} {
+    binary format a*a* abc de
+} -ok {abcde}
+
+test binary-3.6 {binary format a with count 0} {
+    binary format a0 abc
+} -ok {}
+
+test binary-3.7 {binary format a takes bytes} {
+    bytes [binary format a* [binary format c2 {200 201}]]
+} -ok {200 201}
+
+test binary-4.1 {binary format c} {
+    binary format c 65
+} -ok {A}
+
+test binary-4.2 {binary format c truncates} {
+    bytes [binary format ccc 256 -1 0x141]
+} -ok {0 255 65}
+
+test binary-4.3 {binary format c with count} {
+    bytes [binary format c3 {1 2 3 4}]
+} -ok {1 2 3}
+
+test binary-4.4 {binary format c*} {
+    bytes [binary format c* {1 2 3 4}]
+} -ok {1 2 3 4}
+
+test binary-4.5 {binary format c1 takes a list} {
+    bytes [binary format c1 {7 8}]
+} -ok {7}
+
+test binary-4.6 {binary format s is little-endian} {
+    bytes [binary format s 0x1234]
+} -ok {52 18}
+
+test binary-4.7 {binary format S is big-endian} {
+    bytes [binary format S 0x1234]
+} -ok {18 52}
+
+test binary-4.8 {binary format s2} {
+    bytes [binary format s2 {1 -2}]
+} -ok {1 0 254 255}
+
+test binary-4.9 {binary format i is little-endian} {
+    bytes [binary format i 0x01020304]
+} -ok {4 3 2 1}
+
+test binary-4.10 {binary format I is big-endian} {
+    bytes [binary format I 0x01020304]
+} -ok {1 2 3 4}
+
+test binary-4.11 {binary format i truncates} {
+    bytes [binary format i 0x100000001]
+} -ok {1 0 0 0}
+
+test binary-4.12 {binary format w is little-endian} {
+    bytes [binary format w 0x0102030405060708]
+} -ok {8 7 6 5 4 3 2 1}
+
+test binary-4.13 {binary format W is big-endian} {
+    bytes [binary format W 0x0102030405060708]
+} -ok {1 2 3 4 5 6 7 8}
+
+test binary-4.14 {binary format w negative} {
+    bytes [binary format w -1]
+} -ok {255 255 255 255 255 255 255 255}
+
+test binary-4.15 {binary format f and d sizes} {
+    list [string length [binary format f 1.5]] [string length [binary format d2 {1.5 2}]]
+} -ok {4 16}
+
+test binary-5.1 {binary format x} {
+    bytes [binary format x]
+} -ok {0}
+
+test binary-5.2 {binary format x with count} {
+    bytes [binary format cx3c 1 2]
+} -ok {1 0 0 0 2}
+
+test binary-5.3 {binary format X} {
+    binary format a3Xa abc d
+} -ok {abd}
+
+test binary-5.4 {binary format X with count} {
+    binary format a3X2a abc d
+} -ok {adc}
+
+test binary-5.5 {binary format X past the start} {
+    binary format a3X5a abc d
+} -ok {dbc}
+
+test binary-5.6 {binary format X*} {
+    binary format a3X*a abc d
+} -ok {dbc}
+
+test binary-5.7 {binary format @} {
+    binary format a5@2a abcde x
+} -ok {abxde}
+
+test binary-5.8 {binary format @ past the end} {
+    bytes [binary format a@3a a b]
+} -ok {97 0 0 98}
+
+test binary-5.9 {binary format @*} {
+    binary format a5@1a@*a abcde x y
+} -ok {axcdey}
+
+test binary-5.10 {binary format whitespace} {
+    binary format "a2 a1\ta" ab c d
+} -ok {abcd}
+
+test binary-6.1 {binary scan errors} {
+    binary scan abc
+} -error {wrong # args: should be "binary scan value formatString ?varName ...?"}
+
+test binary-6.2 {binary scan errors} {
+    binary scan abc a
+} -error {not enough arguments for all format specifiers}
+
+test binary-6.3 {binary scan errors} {
+    binary scan abc q x
+} -error {bad field specifier "q"}
+
+test binary-6.4 {binary scan errors} {
+    binary scan abc @ x
+} -error {missing count for "@" field specifier}
+
+test binary-7.1 {binary scan a} -body {
+    list [binary scan abc a x] $x
+} -ok {1 a}
+
+test binary-7.2 {binary scan a with count} -body {
+    list [binary scan abcdef a2a3 x y] $x $y
+} -ok {2 ab cde}
+
+test binary-7.3 {binary scan a*} -body {
+    list [binary scan abc a* x] $x
+} -ok {1 abc}
+
+test binary-7.4 {binary scan a keeps nulls} -body {
+    binary scan [binary format a4 ab] a* x
+    bytes $x
+} -ok {97 98 0 0}
+
+test binary-7.5 {binary scan A strips nulls and spaces} -body {
+    binary scan "ab \x00 " A* x
+    set x
+} -ok {ab}
+
+test binary-7.6 {binary scan stops when the data runs out} -body {
+    set y unset
+    list [binary scan abc a2a2 x y] $x $y
+} -ok {1 ab unset}
+
+test binary-8.1 {binary scan c is signed} -body {
+    binary scan [binary format c2 {127 -128}] c2 x
+    set x
+} -ok {127 -128}
+
+test binary-8.2 {binary scan cu is unsigned} -body {
+    binary scan [binary format c2 {127 -128}] cu2 x
+    set x
+} -ok {127 128}
+
+test binary-8.3 {binary scan c with no count} -body {
+    list [binary scan [binary format c2 {-1 2}] cc x y] $x $y
+} -ok {2 -1 2}
+
+test binary-8.4 {binary scan c1 is a list} -body {
+    binary scan {{} x} c1 x
+    list $x [llength $x]
+} -ok {123 1}
+
+test binary-8.5 {binary scan c*} -body {
+    binary scan ABC c* x
+    set x
+} -ok {65 66 67}
+
+test binary-8.6 {binary scan s and S} -body {
+    binary scan [binary format c4 {0x34 0x12 0x12 0x34}] sS x y
+    list [format %x $x] [format %x $y]
+} -ok {1234 1234}
+
+test binary-8.7 {binary scan s is signed} -body {
+    binary scan [binary format c2 {0xfe 0xff}] s x
+    set x
+} -ok {-2}
+
+test binary-8.8 {binary scan su is unsigned} -body {
+    binary scan [binary format c2 {0xfe 0xff}] su x
+    set x
+} -ok {65534}
+
+test binary-8.9 {binary scan i and I} -body {
+    binary scan [binary format c4 {1 2 3 4}] i x
+    binary scan [binary format c4 {1 2 3 4}] I y
+    list [format %x $x] [format %x $y]
+} -ok {4030201 1020304}
+
+test binary-8.10 {binary scan i is signed} -body {
+    binary scan [binary format c4 {0xff 0xff 0xff 0xff}] i x
+    binary scan [binary format c4 {0xff 0xff 0xff 0xff}] iu y
+    list $x $y
+} -ok {-1 4294967295}
+
+test binary-8.11 {binary scan w and W} -body {
+    set data [binary format c8 {1 2 3 4 5 6 7 8}]
+    binary scan $data w x
+    binary scan $data W y
+    list [format %x $x] [format %x $y]
+} -ok {807060504030201 102030405060708}
+
+test binary-8.12 {binary scan i*} -body {
+    binary scan [binary format i3 {1 -2 3}] i* x
+    set x
+} -ok {1 -2 3}
+
+test binary-8.13 {binary scan i* with a partial integer} -body {
+    binary scan [binary format i2c {1 2} 3] i* x
+    set x
+} -ok {1 2}
+
+test binary-8.14 {binary scan i with too little data} -body {
+    binary scan abc i x
+} -ok {0}
+
+test binary-8.15 {binary scan f and d} -body {
+    binary scan [binary format fd 0.25 -1.5] fd x y
+    list $x $y
+} -ok {0.25 -1.5}
+
+test binary-8.16 {binary scan d*} -body {
+    binary scan [binary format d* {1.5 2.5}] d* x
+    set x
+} -ok {1.5 2.5}
+
+test binary-9.1 {binary scan x} -body {
+    binary scan abcd xa x
+    set x
+} -ok {b}
+
+test binary-9.2 {binary scan x with count} -body {
+    binary scan abcd x2a* x
+    set x
+} -ok {cd}
+
+test binary-9.3 {binary scan x past the end} -body {
+    list [binary scan abcd x9a* x] $x
+} -ok {1 {}}
+
+test binary-9.4 {binary scan x*} -body {
+    list [binary scan abcd x*a* x] $x
+} -ok {1 {}}
+
+test binary-9.5 {binary scan X} -body {
+    binary scan abcd a2Xa x y
+    list $x $y
+} -ok {ab b}
+
+test binary-9.6 {binary scan X past the start} -body {
+    binary scan abcd a2X9a x y
+    list $x $y
+} -ok {ab a}
+
+test binary-9.7 {binary scan X*} -body {
+    binary scan abcd a3X*a2 x y
+    list $x $y
+} -ok {abc ab}
+
+test binary-9.8 {binary scan @} -body {
+    binary scan abcd @1a2 x
+    set x
+} -ok {bc}
+
+test binary-9.9 {binary scan @ past the end} -body {
+    list [binary scan abcd @9a* x] $x
+} -ok {1 {}}
+
+test binary-9.10 {binary scan @*} -body {
+    binary scan abcd a@*X2a2 x y
+    list $x $y
+} -ok {a cd}
+
+test binary-10.1 {bytes above 0x7f survive the string rep} -body {
+    set data [binary format c* {0 127 128 200 255}]
+    set copy "[string range $data 0 end]"
+    list [string length $copy] [bytes $copy]
+} -ok {5 {0 127 128 200 255}}
+
+test binary-10.2 {strings are scanned as bytes} -body {
+    binary scan "éÿ" cu* x
+    set x
+} -ok {233 255}
+
+test binary-10.3 {binary data through a list} -body {
+    set data [binary format S 0xabcd]
+    set list [list $data x]
+    binary scan [lindex $list 0] S x
+    format %x [expr {$x & 0xffff}]
+} -ok {abcd}
+
+rename bytes ""
@@ -0,0 +1,80 @@
+# Test Script: catch
+
+test catch-1.1 {catch errors} {
+    catch
+} -error {wrong # args: should be "catch script ?resultVarName? ?optionsVarName?"}
+
+test catch-1.2 {catch errors} {
+    catch a b c d
+} -error {wrong # args: should be "catch script ?resultVarName? ?optionsVarName?"}
+
+test catch-2.1 {catch codes} {
+    list [catch {set a 1} result] $result
+} -ok {0 1}
+
+test catch-2.2 {catch codes} {
+    list [catch {error "simulated error"} result] $result
+} -ok {1 {simulated error}}
+
+test catch-2.3 {catch codes} {
+    list [catch {return "value"} result] $result
+} -ok {2 value}
+
+test catch-2.4 {catch codes} {
+    list [catch break result] $result
+} -ok {3 {}}
+
+test catch-2.5 {catch codes} {
+    list [catch continue result] $result
+} -ok {4 {}}
+
+test catch-3.1 {catch options: ok} {
+    catch {set a 1} result opts
+    set opts
+} -ok {-code 0 -level 0}
+
+test catch-3.2 {catch options: error} {
+    catch {error "simulated error"} result opts
+    set opts
+} -ok {-code 1 -level 0 -errorcode NONE -errorinfo {simulated error
+    while executing
+"error "simulated error""} -errorline 1}
+
+test catch-3.3 {catch options: return} {
+    catch {return "value"} result opts
+    set opts
+} -ok {-code 0 -level 1}
+
+test catch-3.4 {catch options: break} {
+    catch break result opts
+    set opts
+} -ok {-code 3 -level 0}
+
+test catch-3.5 {catch options: continue} {
+    catch continue result opts
+    set opts
+} -ok {-code 4 -level 0}
+
+test catch-4.1 {catch options: error line} {
+    catch {
+        set a 1
+
+        error "simulated error"
+    } result opts
+    dict get $opts -errorline
+} -ok {4}
+
+test catch-4.2 {catch options: error line in proc} -setup {
+    proc myproc {} {
+        set a 1
+        error "simulated error"
+    }
+} -body {
+    catch {
+        set a 1
+        myproc
+    } result opts
+    dict get $opts -errorline
+} -cleanup {
+    rename myproc ""
+} -ok {3}
@@ -0,0 +1,50 @@
+# Test Script: cd, pwd
+
+test cd-1.1 {cd errors} {
+    cd a b
+} -error {wrong # args: should be "cd ?dirName?"}
+
+test cd-1.2 {cd, nonexistent directory} {
+    cd nonesuch
+} -error {couldn't change working directory to "nonesuch": no such file or directory}
+
+test cd-1.3 {cd, not a directory} {
+    cd cd.tcl
+} -error {couldn't change working directory to "cd.tcl": not a directory}
+
+test cd-2.1 {cd} -setup {
+    set old [pwd]
+} -body {
+    cd ..
+    list [expr {[pwd] eq [file dirname $old]}] [file exists test/cd.tcl]
+} -cleanup {
+    cd $old
+} -ok {1 1}
+
+test cd-2.2 {cd, result} -setup {
+    set old [pwd]
+} -body {
+    cd .
+} -cleanup {
+    cd $old
+} -ok {}
+
+test cd-2.3 {cd, home directory} -setup {
+    set old [pwd]
+    set oldhome $::env(HOME)
+    set ::env(HOME) [file dirname $old]
+} -body {
+    cd
+    expr {[pwd] eq [file dirname $old]}
+} -cleanup {
+    cd $old
+    set ::env(HOME) $oldhome
+} -ok {1}
+
+test pwd-1.1 {pwd errors} {
+    pwd a
+} -error {wrong # args: should be "pwd"}
+
+test pwd-1.2 {pwd} {
+    list [file exists cd.tcl] [expr {[file normalize cd.tcl] eq [file join [pwd] cd.tcl]}]
+} -ok {1 1}
@@ -0,0 +1,186 @@
+# Test Script: clock
+
+test clock-1.1 {clock errors} {
+    clock
+} -error {wrong # args: should be "clock subcommand ?arg ...?"}
+
+test clock-1.2 {clock errors} {
+    clock nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be format, microseconds, milliseconds, scan, or seconds}
+
+test clock-2.1 {clock seconds} {
+    string is integer -strict [clock seconds]
+} -ok {1}
+
+test clock-2.2 {clock seconds errors} {
+    clock seconds 1
+} -error {wrong # args: should be "clock seconds"}
+
+test clock-2.3 {clock milliseconds} {
+    set s [clock seconds]
+    set ms [clock milliseconds]
+    expr {$ms / 1000 - $s <= 1}
+} -ok {1}
+
+test clock-2.4 {clock microseconds} {
+    set ms [clock milliseconds]
+    set us [clock microseconds]
+    expr {$us / 1000 - $ms <= 1000}
+} -ok {1}
+
+test clock-3.1 {clock format errors} {
+    clock format
+} -error {wrong # args: should be "clock format clockValue ?-format string? ?-gmt boolean?"}
+
+test clock-3.2 {clock format errors} {
+    clock format x
+} -error {expected integer but got "x"}
+
+test clock-3.3 {clock format errors} {
+    clock format 0 -format
+} -error {missing value for option "-format"}
+
+test clock-3.4 {clock format errors} {
+    clock format 0 -nonesuch 1
+} -error {bad option "-nonesuch": must be -format or -gmt}
+
+test clock-3.5 {clock format errors} {
+    clock format 0 -gmt x
+} -error {expected boolean but got "x"}
+
+test clock-3.6 {clock format errors} {
+    clock format 0 -format "%Q" -gmt 1
+} -error {bad format field "%Q"}
+
+test clock-3.7 {clock format errors} {
+    clock format 0 -format "%Y%" -gmt 1
+} -error {bad format string "%Y%": ends with "%"}
+
+test clock-4.1 {clock format default format} {
+    clock format 951827696 -gmt 1
+} -ok {Tue Feb 29 12:34:56 GMT 2000}
+
+test clock-4.2 {clock format ISO-8601} {
+    clock format 951827696 -format "%Y-%m-%dT%H:%M:%S" -gmt 1
+} -ok {2000-02-29T12:34:56}
+
+test clock-4.3 {clock format names} {
+    clock format 951827696 -format "%a %A %b %B %h" -gmt 1
+} -ok {Tue Tuesday Feb February Feb}
+
+test clock-4.4 {clock format days} {
+    clock format 0 -format "%d|%e|%j|%u|%w" -gmt 1
+} -ok {01| 1|001|4|4}
+
+test clock-4.5 {clock format hours} {
+    list [clock format 0 -format "%H %I %k %l %p" -gmt 1] [clock format 82800 -format "%H %I %k %l %p" -gmt 1]
+} -ok {{00 12  0 12 AM} {23 11 23 11 PM}}
+
+test clock-4.6 {clock format compound fields} {
+    clock format 951827696 -format "%c|%D|%R|%T" -gmt 1
+} -ok {Tue Feb 29 12:34:56 2000|02/29/00|12:34|12:34:56}
+
+test clock-4.7 {clock format other fields} {
+    clock format 951827696 -format "%C %y %s %z %Z %%" -gmt 1
+} -ok {20 00 951827696 +0000 GMT %}
+
+test clock-4.8 {clock format times before the epoch} {
+    clock format -1 -format "%Y-%m-%d %H:%M:%S" -gmt 1
+} -ok {1969-12-31 23:59:59}
+
+test clock-4.9 {clock format without -gmt} {
+    string length [clock format 0 -format "%Y-%m-%d"]
+} -ok {10}
+
+test clock-4.10 {clock format literal text} {
+    clock format 0 -format "day %d of %Y\n" -gmt yes
+} -ok {day 01 of 1970
+}
+
+test clock-5.1 {clock scan errors} {
+    clock scan
+} -error {wrong # args: should be "clock scan dateString ?-format string? ?-gmt boolean?"}
+
+test clock-5.2 {clock scan errors} {
+    clock scan "2000-02-29" -nonesuch 1
+} -error {bad option "-nonesuch": must be -format or -gmt}
+
+test clock-5.3 {clock scan errors} {
+    clock scan "2000-02-29" -format "%Y-%m-%d %H"
+} -error {input string does not match supplied format}
+
+test clock-5.4 {clock scan errors} {
+    clock scan "next tuesday"
+} -error {unable to convert date-time string "next tuesday"}
+
+test clock-5.5 {clock scan out-of-range fields} {
+    clock scan "2000-13-01" -format "%Y-%m-%d" -gmt 1
+} -error {bad month "13": must be from 1 to 12}
+
+test clock-5.6 {clock scan out-of-range fields} {
+    clock scan "2001-02-29" -format "%Y-%m-%d" -gmt 1
+} -error {bad day "29": must be from 1 to 28}
+
+test clock-5.7 {clock scan out-of-range fields} {
+    clock scan "2000-02-29T25:00:00" -gmt 1
+} -error {bad hour "25": must be from 0 to 23}
+
+test clock-5.8 {clock scan out-of-range fields} {
+    clock scan "12:60" -format "%H:%M" -gmt 1
+} -error {bad minute "60": must be from 0 to 59}
+
+test clock-5.9 {clock scan out-of-range fields} {
+    clock scan "13 PM" -format "%I %p" -gmt 1
+} -error {bad hour "13": must be from 1 to 12}
+
+test clock-6.1 {clock scan ISO-8601} {
+    clock scan "2000-02-29T12:34:56" -format "%Y-%m-%dT%H:%M:%S" -gmt 1
+} -ok {951827696}
+
+test clock-6.2 {clock scan ISO-8601 without a format} {
+    list [clock scan "2000-02-29T12:34:56" -gmt 1] [clock scan "2000-02-29 12:34:56" -gmt 1] [clock scan "20000229T123456" -gmt 1]
+} -ok {951827696 951827696 951827696}
+
+test clock-6.3 {clock scan dates} {
+    list [clock scan "2000-02-29" -gmt 1] [clock scan "02/29/2000" -gmt 1] [clock scan "20000229" -gmt 1]
+} -ok {951782400 951782400 951782400}
+
+test clock-6.4 {clock scan time zone offsets} {
+    list [clock scan "2000-02-29T12:34:56Z"] [clock scan "2000-02-29T07:34:56-0500"] [clock scan "2000-02-29T13:34:56+01:00"]
+} -ok {951827696 951827696 951827696}
+
+test clock-6.5 {clock scan names} {
+    clock scan "Tuesday, 29 february 2000" -format "%A, %d %B %Y" -gmt 1
+} -ok {951782400}
+
+test clock-6.6 {clock scan 12-hour clock, defaulting to today} {
+    set pm [clock scan "12:34:56 PM" -format "%I:%M:%S %p" -gmt 1]
+    set am [clock scan "12:34:56 am" -format "%I:%M:%S %p" -gmt 1]
+    list [expr {$pm % 86400}] [expr {$am % 86400}]
+} -ok {45296 2096}
+
+test clock-6.7 {clock scan two-digit years} {
+    list [clock scan "69-01-01" -format "%y-%m-%d" -gmt 1] [clock scan "68-01-01" -format "%y-%m-%d" -gmt 1]
+} -ok {-31536000 3092601600}
+
+test clock-6.8 {clock scan day of year} {
+    clock scan "2000 060" -format "%Y %j" -gmt 1
+} -ok {951782400}
+
+test clock-6.9 {clock scan seconds} {
+    clock scan "951827696" -format "%s"
+} -ok {951827696}
+
+test clock-6.10 {clock scan round trip} {
+    set t 951827696
+    list [clock scan [clock format $t -gmt 1] -format "%a %b %d %H:%M:%S %Z %Y"] [clock scan [clock format $t] -format "%a %b %d %H:%M:%S %Z %Y"]
+} -ok {951827696 951827696}
+
+test clock-6.11 {clock scan local time} {
+    set t [clock scan "2000-02-29 12:34:56"]
+    clock format $t -format "%Y-%m-%d %H:%M:%S"
+} -ok {2000-02-29 12:34:56}
+
+test clock-6.12 {clock scan literal text and whitespace} {
+    clock scan "date:2000-02-29   at 12:34" -format "date:%Y-%m-%d at %H:%M" -gmt 1
+} -ok {951827640}
@@ -8,7 +8,7 @@
 
 test foreach-1.1 {foreach errors} {
     foreach
-} -error {wrong # args: should be "foreach varList list body"}
+} -error {wrong # args: should be "foreach varList list ?varList list ...? body"}
 
 # Doesn't execute if there's no list data.
 test foreach-2.1 {foreach command} {
@@ -212,78 +212,6 @@ test if-4.1 {if command} {
     list [doit 1] [doit 0]
 } -ok {then else}
 
-#-------------------------------------------------------------------------
-# info
-
-test info-1.1 {info errors} {
-    info
-} -error {wrong # args: should be "info subcommand ?arg ...?"}
-
-# TODO: really need glob matching or something; as it is, this won't
-# pass with tclsh.  Or, I need a way to limit tests to the right
-# context, as with tcltest.
-test info-1.2 {info errors} {
-    info nonesuch
-} -error {unknown or ambiguous subcommand "nonesuch": must be commands, complete, or vars}
-
-test info-2.1 {info complete errors} {
-    info complete
-} -error {wrong # args: should be "info complete command"}
-
-test info-2.2 {info complete errors} {
-    info complete foo bar
-} -error {wrong # args: should be "info complete command"}
-
-test info-2.3 {info complete command} {
-    info complete cmd
-} -ok {1}
-
-test info-2.4 {info complete command} {
-    info complete "\{cmd"
-} -ok {0}
-
-test info-3.1 {info vars command} -setup {
-    proc myproc {} {
-        info vars
-    }
-} -body {
-    myproc
-} -cleanup {
-    rename myproc ""
-} -ok {}
-
-test info-3.2 {info vars command} -setup {
-    proc myproc {a} {
-        info vars
-    }
-} -body {
-    myproc a
-} -cleanup {
-    rename myproc ""
-} -ok {a}
-
-test info-3.2 {info vars command} -setup {
-    proc myproc {} {
-        set v 1
-        info vars
-    }
-} -body {
-    myproc
-} -cleanup {
-    rename myproc ""
-} -ok {v}
-
-test info-3.3 {info vars command} -setup {
-    proc myproc {} {
-        global x
-        info vars
-    }
-} -body {
-    myproc
-} -cleanup {
-    rename myproc ""
-} -ok {x}
-
 #-------------------------------------------------------------------------
 # join
 
@@ -311,17 +239,17 @@ test lappend-1.1 {lappend errors} {
 } -error {wrong # args: should be "lappend varName ?value ...?"}
 
 test lappend-2.1 {lappend command} {
-    unset x
+    unset -nocomplain x
     lappend x
 } -ok {}
 
 test lappend-2.2 {lappend command} {
-    unset x
+    unset -nocomplain x
     lappend x a b c
 } -ok {a b c}
 
 test lappend-2.3 {lappend command} {
-    unset x
+    unset -nocomplain x
     lappend x a b c
     lappend x d e f
 } -ok {a b c d e f}
@@ -534,11 +462,22 @@ test proc-4.4 {defined proc} -body {
 #
 # NOTE: The semantics of return are a subset of those of standard TCL.
 
-# Test syntax.  Note: TCL doesn't work this way, but until I implement
-# the full return syntax, it doesn't matter.
+# Test syntax.  Note: TCL allows arbitrary options, but Molt doesn't.
 test return-1.1 {result errors} {
     return foo bar
-} -error {wrong # args: should be "return ?value?"}
+} -error {bad option "foo": must be -code, -errorcode, -errorinfo, or -level}
+
+test return-1.2 {result errors} {
+    return -code nonesuch foo
+} -error {bad completion code "nonesuch": must be ok, error, return, break, continue, or an integer}
+
+test return-1.3 {result errors} {
+    return -code 5.5 foo
+} -error {bad completion code "5.5": must be ok, error, return, break, continue, or an integer}
+
+test return-1.4 {result errors} {
+    return -level -1 foo
+} -error {bad -level value: expected non-negative integer but got "-1"}
 
 # return the empty string
 test return-2.1 {result command} -setup {
@@ -562,6 +501,152 @@ test return-2.2 {result command} -setup {
     rename a ""
 } -ok {howdy}
 
+# return options
+test return-3.1 {return -code error} -setup {
+    proc a {} {
+        return -code error "simulated error"
+    }
+} -body {
+    list [catch a result] $result
+} -cleanup {
+    rename a ""
+} -ok {1 {simulated error}}
+
+test return-3.2 {return -code error -errorcode} -setup {
+    proc a {} {
+        return -code error -errorcode {MY CODE} "simulated error"
+    }
+} -body {
+    catch a result opts
+    dict get $opts -errorcode
+} -cleanup {
+    rename a ""
+} -ok {MY CODE}
+
+test return-3.3 {return -code break} -setup {
+    proc a {} {
+        return -code break
+    }
+} -body {
+    set result {}
+    foreach i {1 2 3} {
+        lappend result $i
+        a
+    }
+    set result
+} -cleanup {
+    rename a ""
+} -ok {1}
+
+test return-3.4 {return -code continue} -setup {
+    proc a {} {
+        return -code continue
+    }
+} -body {
+    set result {}
+    foreach i {1 2 3} {
+        a
+        lappend result $i
+    }
+    set result
+} -cleanup {
+    rename a ""
+} -ok {}
+
+test return-3.5 {return -code return} -setup {
+    proc a {} {
+        return -code return "from a"
+    }
+    proc b {} {
+        a
+        return "from b"
+    }
+} -body {
+    b
+} -cleanup {
+    rename a ""
+    rename b ""
+} -ok {from a}
+
+test return-3.6 {return -level 2} -setup {
+    proc a {} {
+        return -level 2 "from a"
+    }
+    proc b {} {
+        a
+        return "from b"
+    }
+} -body {
+    b
+} -cleanup {
+    rename a ""
+    rename b ""
+} -ok {from a}
+
+test return-3.7 {return -level 0} -setup {
+    proc a {} {
+        set x [return -level 0 "value"]
+        return "got $x"
+    }
+} -body {
+    a
+} -cleanup {
+    rename a ""
+} -ok {got value}
+
+test return-3.8 {return -level 0 -code error in catch} {
+    list [catch {return -level 0 -code error "simulated error"} result] $result
+} -ok {1 {simulated error}}
+
+test return-3.9 {return -code error in catch} {
+    set code [catch {return -code error "simulated error"} result opts]
+    list $code $result [dict get $opts -code] [dict get $opts -level]
+} -ok {2 {simulated error} 1 1}
+
+test return-3.10 {return -code return in catch} {
+    catch {return -code return "value"} result opts
+    list $result [dict get $opts -code] [dict get $opts -level]
+} -ok {value 0 2}
+
+test return-3.11 {return -code by number} {
+    list [catch {return -level 0 -code 3} result] $result
+} -ok {3 {}}
+
+test return-3.12 {return -code with an application-defined code} -setup {
+    proc a {} {
+        return -code 7 "custom"
+    }
+} -body {
+    list [catch a result opts] $result [dict get $opts -code] [dict get $opts -level]
+} -cleanup {
+    rename a ""
+} -ok {7 custom 7 0}
+
+test return-3.13 {application-defined codes propagate through procs and loops} -setup {
+    proc a {} {
+        return -code 7 "custom"
+    }
+    proc b {} {
+        foreach i {1 2 3} {
+            a
+        }
+        return "done"
+    }
+} -body {
+    list [catch b result] $result
+} -cleanup {
+    rename a ""
+    rename b ""
+} -ok {7 custom}
+
+test return-3.14 {application-defined codes at the top level} -setup {
+    interp create return_slave
+} -body {
+    interp eval return_slave {return -level 0 -code 7 "custom"}
+} -cleanup {
+    interp delete return_slave
+} -error {command returned bad code: 7}
+
 #-------------------------------------------------------------------------
 # set
 
@@ -590,17 +675,90 @@ test set-2.2 {set command} {
 #-------------------------------------------------------------------------
 # unset
 
-test unset-1.1 {unset errors} {
+test unset-1.1 {unset, no variables} {
     unset
-} -error {wrong # args: should be "unset varName"}
+} -ok {}
 
 test unset-2.1 {unset command} {
-    # In standard TCL, this is an error; use -nocomplain.
     unset nonesuch
-} -ok {}
+} -error {can't unset "nonesuch": no such variable}
 
 test unset-2.2 {unset command} {
     set x 1
     unset x
     set x
 } -error {can't read "x": no such variable}
+
+test unset-2.3 {unset multiple variables} {
+    set a 1
+    set b 2
+    unset a b
+    list [catch {set a}] [catch {set b}]
+} -ok {1 1}
+
+test unset-2.4 {unset stops at the first error} {
+    set a 1
+    set c 3
+    list [catch {unset a b c} msg] $msg [catch {set a}] [catch {set c}]
+} -ok {1 {can't unset "b": no such variable} 1 0}
+
+test unset-2.5 {unset -nocomplain} {
+    set a 1
+    unset -nocomplain nonesuch a other
+    catch {set a}
+} -ok {1}
+
+test unset-2.6 {unset --} {
+    set -nocomplain 1
+    unset -- -nocomplain
+    catch {set -nocomplain}
+} -ok {1}
+
+test unset-2.7 {unset -nocomplain --} {
+    set -- 1
+    unset -nocomplain -- -- nonesuch
+    catch {set --}
+} -ok {1}
+
+test unset-3.1 {unset array element} {
+    set a(x) 1
+    set a(y) 2
+    unset a(x)
+    list [array names a] [catch {set a(x)}]
+} -ok {y 1}
+
+test unset-3.2 {unset whole array} {
+    set a(x) 1
+    set a(y) 2
+    unset a
+    array exists a
+} -ok {0}
+
+test unset-3.3 {unset errors, missing element} {
+    set a(x) 1
+    unset a(y)
+} -error {can't unset "a(y)": no such element in array}
+
+test unset-3.4 {unset errors, element of scalar} {
+    set a 1
+    unset a(y)
+} -error {can't unset "a(y)": variable isn't array}
+
+test unset-3.5 {unset errors, element of missing variable} {
+    unset a(y)
+} -error {can't unset "a(y)": no such variable}
+
+test unset-3.6 {unset in a proc} -setup {
+    proc unsetproc {} {
+        global unset_g
+        set local 1
+        unset local unset_g
+        list [catch {set local}] [catch {set unset_g}]
+    }
+} -body {
+    global unset_g
+    set unset_g 1
+    list [unsetproc] [catch {set unset_g}]
+} -cleanup {
+    rename unsetproc ""
+} -ok {{1 1} 1}
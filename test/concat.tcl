@@ -0,0 +1,45 @@
+# Test Script: concat command.
+
+test concat-1.1 {concat, no arguments} {
+    concat
+} -ok {}
+
+test concat-1.2 {concat, one argument} {
+    concat "  a b  "
+} -ok {a b}
+
+test concat-1.3 {concat, several arguments} {
+    concat a {b c} d
+} -ok {a b c d}
+
+test concat-1.4 {concat, trims whitespace} {
+    concat "  a  " "\tb\t" " c"
+} -ok {a b c}
+
+test concat-1.5 {concat, internal whitespace retained} {
+    concat "a  b" "c\td"
+} -ok "a  b c\td"
+
+test concat-2.1 {concat, empty arguments dropped} {
+    concat a {} b "" c
+} -ok {a b c}
+
+test concat-2.2 {concat, whitespace-only arguments dropped} {
+    concat a "   " b "\n\t" c
+} -ok {a b c}
+
+test concat-2.3 {concat, all empty} {
+    concat {} "  " ""
+} -ok {}
+
+test concat-3.1 {concat, embedded newlines} {
+    concat "a\nb\n" "\nc"
+} -ok "a\nb c"
+
+test concat-3.2 {concat, no list quoting} {
+    concat "\{" "a b" "\$x"
+} -ok "\{ a b \$x"
+
+test concat-3.3 {concat, splicing lists} {
+    llength [concat {a b} {c {d e}}]
+} -ok {4}
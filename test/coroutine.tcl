@@ -0,0 +1,391 @@
+# Test Script: coroutine, yield
+
+test coroutine-1.1 {coroutine errors} {
+    coroutine
+} -error {wrong # args: should be "coroutine name command ?arg ...?"}
+
+test coroutine-1.2 {coroutine errors} {
+    coroutine co
+} -error {wrong # args: should be "coroutine name command ?arg ...?"}
+
+test coroutine-1.3 {coroutine with an existing name} {
+    coroutine set list a
+} -error {command "set" already exists}
+
+test coroutine-1.4 {coroutine in an unknown namespace} {
+    coroutine ::nonesuch::co list a
+} -error {can't create coroutine "::nonesuch::co": unknown namespace}
+
+test coroutine-1.5 {yield errors} {
+    yield a b
+} -error {wrong # args: should be "yield ?value?"}
+
+test coroutine-1.6 {yield outside a coroutine} {
+    yield
+} -error {yield can only be called in a coroutine}
+
+test coroutine-2.1 {coroutine that doesn't yield} {
+    list [coroutine co list a b] [info commands co]
+} -ok {{a b} {}}
+
+test coroutine-2.2 {coroutine returns the first yielded value} -setup {
+    proc coproc {} {
+        yield first
+        error "not reached"
+    }
+} -body {
+    coroutine co coproc
+} -cleanup {
+    rename co ""
+    rename coproc ""
+} -ok {first}
+
+test coroutine-2.3 {generator} -setup {
+    proc coproc {n} {
+        yield
+        for {set i 1} {$i <= $n} {incr i} {
+            yield $i
+        }
+        return done
+    }
+} -body {
+    coroutine co coproc 3
+    list [co] [co] [co] [co] [info commands co]
+} -cleanup {
+    rename coproc ""
+} -ok {1 2 3 done {}}
+
+test coroutine-2.4 {yield returns the resumption argument} -setup {
+    proc coproc {} {
+        set total 0
+        while 1 {
+            incr total [yield $total]
+        }
+    }
+} -body {
+    coroutine co coproc
+    list [co 1] [co 2] [co 3]
+} -cleanup {
+    rename co ""
+    rename coproc ""
+} -ok {1 3 6}
+
+test coroutine-2.5 {yield with no value} -setup {
+    proc coproc {} {
+        set a [yield]
+        set b [yield]
+        list $a $b
+    }
+} -body {
+    list [coroutine co coproc] [co] [co x]
+} -cleanup {
+    rename coproc ""
+} -ok {{} {} {{} x}}
+
+test coroutine-2.6 {coroutine resumption errors} -setup {
+    proc coproc {} {
+        yield
+    }
+} -body {
+    coroutine co coproc
+    co a b
+} -cleanup {
+    rename co ""
+    rename coproc ""
+} -error {wrong # args: should be "co ?arg?"}
+
+test coroutine-2.7 {coroutine error} -setup {
+    proc coproc {} {
+        yield a
+        error "oops"
+    }
+} -body {
+    coroutine co coproc
+    list [catch {co} msg] $msg [info commands co]
+} -cleanup {
+    rename coproc ""
+} -ok {1 oops {}}
+
+test coroutine-2.8 {coroutine with a lambda} {
+    coroutine co apply {{x} {yield [expr {$x * 2}]; expr {$x * 3}}} 5
+    list [info commands co] [co]
+} -ok {co 15}
+
+test coroutine-3.1 {yield in a nested proc} -setup {
+    proc coinner {} {
+        set x [yield inner]
+        return "got $x"
+    }
+    proc coproc {} {
+        set result [coinner]
+        yield $result
+        return end
+    }
+} -body {
+    list [coroutine co coproc] [co hello] [co]
+} -cleanup {
+    rename coinner ""
+    rename coproc ""
+} -ok {inner {got hello} end}
+
+test coroutine-3.2 {yield from deep recursion} -setup {
+    proc coproc {n} {
+        if {$n == 0} {
+            return [yield bottom]
+        }
+        coproc [expr {$n - 1}]
+    }
+} -body {
+    list [coroutine co coproc 100] [co top]
+} -cleanup {
+    rename coproc ""
+} -ok {bottom top}
+
+test coroutine-3.3 {coroutine locals persist across yields} -setup {
+    proc coproc {} {
+        set x 1
+        yield
+        incr x
+        yield
+        incr x
+    }
+} -body {
+    coroutine co coproc
+    co
+    co
+} -cleanup {
+    rename coproc ""
+} -ok {3}
+
+test coroutine-3.4 {coroutine runs at the global level} -setup {
+    proc coproc {} {
+        yield [info level]
+        info level
+    }
+    proc cocaller {} {
+        set a [coroutine co coproc]
+        list $a [cocaller2]
+    }
+    proc cocaller2 {} {
+        co
+    }
+} -body {
+    cocaller
+} -cleanup {
+    rename coproc ""
+    rename cocaller ""
+    rename cocaller2 ""
+} -ok {1 1}
+
+test coroutine-3.5 {caller's variables are intact} -setup {
+    proc coproc {} {
+        set x coroutine
+        yield $x
+        set x
+    }
+    proc cocaller {} {
+        set x caller
+        set a [coroutine co coproc]
+        set b [co]
+        list $x $a $b
+    }
+} -body {
+    cocaller
+} -cleanup {
+    rename coproc ""
+    rename cocaller ""
+} -ok {caller coroutine coroutine}
+
+test coroutine-3.6 {yield within uplevel} -setup {
+    proc coinner {} {
+        uplevel 1 {set y [yield $x]}
+    }
+    proc coproc {} {
+        set x 7
+        coinner
+        list $x $y
+    }
+} -body {
+    list [coroutine co coproc] [co 9]
+} -cleanup {
+    rename coinner ""
+    rename coproc ""
+} -ok {7 {7 9}}
+
+test coroutine-3.7 {coroutine globals} -setup {
+    set ::cogv 5
+    proc coproc {} {
+        global cogv
+        yield
+        incr cogv
+    }
+} -body {
+    coroutine co coproc
+    set ::cogv 10
+    list [co] $::cogv
+} -cleanup {
+    unset ::cogv
+    rename coproc ""
+} -ok {11 11}
+
+test coroutine-3.8 {coroutine command resolved in the current namespace} -setup {
+    namespace eval ::cons {
+        proc coproc {} {
+            yield [namespace current]
+            return [namespace current]
+        }
+    }
+} -body {
+    namespace eval ::cons { coroutine ::co coproc }
+    co
+} -cleanup {
+    namespace delete ::cons
+} -ok {::cons}
+
+test coroutine-4.1 {info coroutine} -setup {
+    proc coproc {} {
+        yield [info coroutine]
+    }
+} -body {
+    list [info coroutine] [coroutine co coproc] [info coroutine] [co]
+} -cleanup {
+    rename coproc ""
+} -ok {{} ::co {} {}}
+
+test coroutine-4.2 {info coroutine after rename} -setup {
+    proc coproc {} {
+        yield
+        info coroutine
+    }
+} -body {
+    coroutine co coproc
+    rename co co2
+    list [co2] [info commands co2]
+} -cleanup {
+    rename coproc ""
+} -ok {::co2 {}}
+
+test coroutine-4.3 {coroutine already running} -setup {
+    proc coproc {} {
+        yield
+        co
+    }
+} -body {
+    coroutine co coproc
+    co
+} -cleanup {
+    rename coproc ""
+} -error {coroutine "co" is already running}
+
+test coroutine-4.4 {nested coroutines} -setup {
+    proc coinner {} {
+        yield a
+        yield b
+    }
+    proc coproc {} {
+        yield [coroutine coi coinner]
+        yield [coi]
+    }
+} -body {
+    list [coroutine co coproc] [co] [info commands coi]
+} -cleanup {
+    rename co ""
+    rename coi ""
+    rename coinner ""
+    rename coproc ""
+} -ok {a b coi}
+
+test coroutine-5.1 {deleting a suspended coroutine} -setup {
+    proc coproc {} {
+        try {
+            yield 1
+            yield 2
+        } finally {
+            set ::coresult "cleaned up"
+        }
+    }
+} -body {
+    coroutine co coproc
+    rename co ""
+    list [info commands co] $::coresult
+} -cleanup {
+    unset ::coresult
+    rename coproc ""
+} -ok {{} {cleaned up}}
+
+test coroutine-5.2 {yield after deletion} -setup {
+    proc coproc {} {
+        catch {yield} msg
+        lappend ::coresult $msg
+        catch {yield} msg
+        lappend ::coresult $msg
+    }
+} -body {
+    coroutine co coproc
+    rename co ""
+    set ::coresult
+} -cleanup {
+    unset ::coresult
+    rename coproc ""
+} -ok {{coroutine has been deleted} {coroutine has been deleted}}
+
+test coroutine-5.3 {redefining a coroutine command} -setup {
+    proc coproc {} {
+        catch {yield} msg
+        set ::coresult $msg
+    }
+} -body {
+    coroutine co coproc
+    proc co {} { return proc }
+    list [co] $::coresult
+} -cleanup {
+    unset ::coresult
+    rename co ""
+    rename coproc ""
+} -ok {proc {coroutine has been deleted}}
+
+test coroutine-5.4 {coroutine in a slave, after the slave table grows} -setup {
+    interp create coslave
+    interp eval coslave {
+        proc coproc {} {
+            set i 0
+            while 1 {
+                yield [incr i]
+            }
+        }
+        coroutine co coproc
+    }
+    for {set i 0} {$i < 40} {incr i} {
+        interp create copad$i
+    }
+} -body {
+    list [interp eval coslave co] [interp eval coslave co]
+} -cleanup {
+    interp delete coslave
+    for {set i 0} {$i < 40} {incr i} {
+        interp delete copad$i
+    }
+} -ok {2 3}
+
+test coroutine-5.5 {deleting a slave kills its coroutines} -setup {
+    set f [open coroutine_tmp.txt w]
+    close $f
+    interp create coslave
+    interp eval coslave {
+        proc coproc {} {
+            catch {yield} msg
+            set f [open coroutine_tmp.txt w]
+            puts -nonewline $f $msg
+            close $f
+        }
+        coroutine co coproc
+    }
+} -body {
+    interp delete coslave
+    set f [open coroutine_tmp.txt]
+    set result [read $f]
+    close $f
+    set result
+} -cleanup {
+    file delete coroutine_tmp.txt
+} -ok {coroutine has been deleted}
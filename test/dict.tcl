@@ -0,0 +1,433 @@
+# Test Script: dict command.
+
+test dict-1.1 {dict errors} {
+    dict
+} -error {wrong # args: should be "dict subcommand ?arg ...?"}
+
+test dict-1.2 {dict errors} {
+    dict frob
+} -error {unknown or ambiguous subcommand "frob": must be create, exists, filter, for, get, keys, map, merge, set, size, unset, update, values, or with}
+
+test dict-2.1 {dict create} {
+    dict create
+} -ok {}
+
+test dict-2.2 {dict create} {
+    dict create a 1 b {x y}
+} -ok {a 1 b {x y}}
+
+test dict-2.3 {dict create, duplicate keys} {
+    dict create a 1 b 2 a 3
+} -ok {a 3 b 2}
+
+test dict-2.4 {dict create errors} {
+    dict create a
+} -error {wrong # args: should be "dict create ?key value ...?"}
+
+test dict-3.1 {dict get} {
+    dict get {a 1 b 2} b
+} -ok {2}
+
+test dict-3.2 {dict get, no keys} {
+    dict get {a 1 b 2}
+} -ok {a 1 b 2}
+
+test dict-3.3 {dict get, nested} {
+    dict get {a 1 b {c 2 d {e 3}}} b d e
+} -ok {3}
+
+test dict-3.4 {dict get, missing key} {
+    dict get {a 1 b 2} c
+} -error {key "c" not known in dictionary}
+
+test dict-3.5 {dict get, missing nested key} {
+    dict get {a 1 b {c 2}} b x
+} -error {key "x" not known in dictionary}
+
+test dict-3.6 {dict get, not a dictionary} {
+    dict get {a 1 b} a
+} -error {missing value to go with key}
+
+test dict-3.7 {dict get errors} {
+    dict get
+} -error {wrong # args: should be "dict get dictionary ?key ...?"}
+
+test dict-4.1 {dict set} {
+    set d {a 1}
+    list [dict set d b 2] $d
+} -ok {{a 1 b 2} {a 1 b 2}}
+
+test dict-4.2 {dict set, replace} {
+    set d {a 1 b 2}
+    dict set d a 3
+} -ok {a 3 b 2}
+
+test dict-4.3 {dict set, new variable} {
+    dict set d a 1
+} -ok {a 1}
+
+test dict-4.4 {dict set, nested} {
+    set d {a 1}
+    dict set d b c 2
+    dict set d b d 3
+} -ok {a 1 b {c 2 d 3}}
+
+test dict-4.5 {dict set, not a dictionary} {
+    set d {a 1 b}
+    dict set d c 2
+} -error {missing value to go with key}
+
+test dict-4.6 {dict set errors} {
+    dict set d a
+} -error {wrong # args: should be "dict set dictVarName key ?key ...? value"}
+
+test dict-5.1 {dict unset} {
+    set d {a 1 b 2 c 3}
+    list [dict unset d b] $d
+} -ok {{a 1 c 3} {a 1 c 3}}
+
+test dict-5.2 {dict unset, missing key} {
+    set d {a 1}
+    dict unset d b
+} -ok {a 1}
+
+test dict-5.3 {dict unset, nested} {
+    set d {a 1 b {c 2 d 3}}
+    dict unset d b c
+} -ok {a 1 b {d 3}}
+
+test dict-5.4 {dict unset, missing nested key} {
+    set d {a 1}
+    dict unset d b c
+} -error {key "b" not known in dictionary}
+
+test dict-5.5 {dict unset errors} {
+    dict unset d
+} -error {wrong # args: should be "dict unset dictVarName key ?key ...?"}
+
+test dict-6.1 {dict exists} {
+    set d {a 1 b {c 2}}
+    list [dict exists $d a] [dict exists $d x] [dict exists $d b c] [dict exists $d b x]
+} -ok {1 0 1 0}
+
+test dict-6.2 {dict exists, not a dictionary} {
+    list [dict exists {a 1} a b] [dict exists {a} a]
+} -ok {0 0}
+
+test dict-6.3 {dict exists errors} {
+    dict exists {a 1}
+} -error {wrong # args: should be "dict exists dictionary key ?key ...?"}
+
+test dict-7.1 {dict keys} {
+    dict keys {a 1 b 2 bb 3}
+} -ok {a b bb}
+
+test dict-7.2 {dict keys, pattern} {
+    dict keys {a 1 b 2 bb 3} b*
+} -ok {b bb}
+
+test dict-7.3 {dict keys errors} {
+    dict keys
+} -error {wrong # args: should be "dict keys dictionary ?pattern?"}
+
+test dict-8.1 {dict values} {
+    dict values {a 1 b 2 c 12}
+} -ok {1 2 12}
+
+test dict-8.2 {dict values, pattern} {
+    dict values {a 1 b 2 c 12} 1*
+} -ok {1 12}
+
+test dict-8.3 {dict values errors} {
+    dict values
+} -error {wrong # args: should be "dict values dictionary ?pattern?"}
+
+test dict-9.1 {dict size} {
+    list [dict size {}] [dict size {a 1 b 2}]
+} -ok {0 2}
+
+test dict-9.2 {dict size, not a dictionary} {
+    dict size {a 1 b}
+} -error {missing value to go with key}
+
+test dict-9.3 {dict size errors} {
+    dict size
+} -error {wrong # args: should be "dict size dictionary"}
+
+test dict-10.1 {dict for} {
+    set result ""
+    dict for {k v} {a 1 b 2 c 3} {
+        append result "$k=$v;"
+    }
+    set result
+} -ok {a=1;b=2;c=3;}
+
+test dict-10.2 {dict for, break} {
+    set result ""
+    dict for {k v} {a 1 b 2 c 3} {
+        if {$k eq "b"} { break }
+        append result "$k=$v;"
+    }
+    set result
+} -ok {a=1;}
+
+test dict-10.3 {dict for, continue} {
+    set result ""
+    dict for {k v} {a 1 b 2 c 3} {
+        if {$k eq "b"} { continue }
+        append result "$k=$v;"
+    }
+    set result
+} -ok {a=1;c=3;}
+
+test dict-10.4 {dict for, error} {
+    dict for {k v} {a 1 b 2} {
+        error "Simulated error"
+    }
+} -error {Simulated error}
+
+test dict-10.5 {dict for, returns empty} {
+    dict for {k v} {a 1} { set k }
+} -ok {}
+
+test dict-10.6 {dict for errors} {
+    dict for {k} {a 1} {}
+} -error {must have exactly two variable names}
+
+test dict-10.7 {dict for errors} {
+    dict for {k v} {a 1 b} {}
+} -error {missing value to go with key}
+
+test dict-10.8 {dict for errors} {
+    dict for {k v} {a 1}
+} -error {wrong # args: should be "dict for {keyVar valueVar} dictionary script"}
+
+test dict-11.1 {dict merge} {
+    dict merge
+} -ok {}
+
+test dict-11.2 {dict merge} {
+    dict merge {a 1 b 2} {b 3 c 4} {c 5}
+} -ok {a 1 b 3 c 5}
+
+test dict-11.3 {dict merge, not a dictionary} {
+    dict merge {a 1} {b}
+} -error {missing value to go with key}
+
+test dict-12.1 {dict filter key} {
+    dict filter {a 1 b 2 bb 3 c 4} key b* c
+} -ok {b 2 bb 3 c 4}
+
+test dict-12.2 {dict filter value} {
+    dict filter {a 1 b 2 c 12} value 1*
+} -ok {a 1 c 12}
+
+test dict-12.3 {dict filter script} {
+    dict filter {a 1 b 2 c 3 d 4} script {k v} {expr {$v % 2 == 0}}
+} -ok {b 2 d 4}
+
+test dict-12.4 {dict filter script, break and continue} {
+    dict filter {a 1 b 2 c 3 d 4} script {k v} {
+        if {$k eq "a"} { continue }
+        if {$k eq "d"} { break }
+        expr {1}
+    }
+} -ok {b 2 c 3}
+
+test dict-12.5 {dict filter script, error} {
+    dict filter {a 1} script {k v} { error "Simulated error" }
+} -error {Simulated error}
+
+test dict-12.6 {dict filter, no patterns} {
+    dict filter {a 1 b 2} key
+} -ok {}
+
+test dict-12.7 {dict filter errors} {
+    dict filter {a 1} frob
+} -error {bad filterType "frob": must be key, script, or value}
+
+test dict-12.8 {dict filter errors} {
+    dict filter {a 1} script {k v}
+} -error {wrong # args: should be "dict filter dictionary script {keyVarName valueVarName} filterScript"}
+
+test dict-12.9 {dict filter errors} {
+    dict filter {a 1} script {k} {expr 1}
+} -error {must have exactly two variable names}
+
+test dict-13.1 {dict map} {
+    dict map {k v} {a 1 b 2} { expr {$v * 10} }
+} -ok {a 10 b 20}
+
+test dict-13.2 {dict map, new keys} {
+    dict map {k v} {a 1 b 2} { set k "$k$k"; set v }
+} -ok {aa 1 bb 2}
+
+test dict-13.3 {dict map, break and continue} {
+    dict map {k v} {a 1 b 2 c 3} {
+        if {$k eq "a"} { continue }
+        if {$k eq "c"} { break }
+        set v
+    }
+} -ok {b 2}
+
+test dict-13.4 {dict map errors} {
+    dict map {k} {a 1} {}
+} -error {must have exactly two variable names}
+
+test dict-14.1 {dict with} {
+    set d {a 1 b 2}
+    dict with d {
+        set a 10
+        incr b
+    }
+    set d
+} -ok {a 10 b 3}
+
+test dict-14.2 {dict with, result and locals} {
+    set d {a 1 b 2}
+    list [dict with d { expr {$a + $b} }] $a $b
+} -ok {3 1 2}
+
+test dict-14.3 {dict with, unset removes key} {
+    set d {a 1 b 2}
+    dict with d { unset a }
+    set d
+} -ok {b 2}
+
+test dict-14.4 {dict with, new variables aren't added} {
+    set d {a 1}
+    dict with d { set c 3 }
+    set d
+} -ok {a 1}
+
+test dict-14.5 {dict with, nested} {
+    set d {x {a 1 b 2} y 3}
+    dict with d x { set a 10 }
+    set d
+} -ok {x {a 10 b 2} y 3}
+
+test dict-14.6 {dict with, write back on error} {
+    set d {a 1}
+    set code [catch {
+        dict with d {
+            set a 2
+            error "Simulated error"
+        }
+    } msg]
+    list $code $msg $d
+} -ok {1 {Simulated error} {a 2}}
+
+test dict-14.7 {dict with, write back on return} -setup {
+    proc myproc {} {
+        set d {a 1}
+        dict with d {
+            set a 2
+            return $d
+        }
+        return "not reached"
+    }
+    proc myproc2 {} {
+        set d {a 1}
+        catch {
+            dict with d {
+                set a 2
+                return "early"
+            }
+        }
+        return $d
+    }
+} -body {
+    list [myproc] [myproc2]
+} -cleanup {
+    rename myproc ""
+    rename myproc2 ""
+} -ok {{a 1} {a 2}}
+
+test dict-14.8 {dict with, write back on break} {
+    set d {a 1}
+    foreach i {1 2 3} {
+        dict with d {
+            incr a
+            break
+        }
+    }
+    set d
+} -ok {a 2}
+
+test dict-14.9 {dict with, variable unset in body} {
+    set d {a 1}
+    dict with d { unset d }
+    catch {set d} msg
+    set msg
+} -ok {can't read "d": no such variable}
+
+test dict-14.10 {dict with errors} {
+    dict with d {}
+} -error {can't read "d": no such variable}
+
+test dict-14.11 {dict with errors} {
+    dict with d
+} -error {wrong # args: should be "dict with dictVarName ?key ...? script"}
+
+test dict-15.1 {dict update} {
+    set d {a 1 b 2 c 3}
+    dict update d a x b y {
+        set x 10
+        set y 20
+    }
+    set d
+} -ok {a 10 b 20 c 3}
+
+test dict-15.2 {dict update, missing key} {
+    set d {a 1}
+    set y "old"
+    dict update d a x b y {
+        set r [list $x [catch {set y}]]
+        set y 2
+    }
+    list $r $d
+} -ok {{1 1} {a 1 b 2}}
+
+test dict-15.3 {dict update, unset removes key} {
+    set d {a 1 b 2}
+    dict update d a x { unset x }
+    set d
+} -ok {b 2}
+
+test dict-15.4 {dict update, result} {
+    set d {a 1}
+    dict update d a x { expr {$x + 1} }
+} -ok {2}
+
+test dict-15.5 {dict update, write back on error} {
+    set d {a 1}
+    set code [catch {
+        dict update d a x {
+            set x 2
+            error "Simulated error"
+        }
+    } msg]
+    list $code $msg $d
+} -ok {1 {Simulated error} {a 2}}
+
+test dict-15.6 {dict update, write back on return} -setup {
+    proc myproc {} {
+        set d {a 1}
+        catch {
+            dict update d a x {
+                set x 2
+                return "early"
+            }
+        }
+        return $d
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {a 2}
+
+test dict-15.7 {dict update errors} {
+    set d {a 1}
+    dict update d a {}
+} -error {wrong # args: should be "dict update dictVarName key varName ?key varName ...? script"}
@@ -0,0 +1,125 @@
+# Test Script: encoding
+
+proc bytes {data} {
+    binary scan $data cu* codes
+    return $codes
+}
+
+test encoding-1.1 {encoding errors} {
+    encoding
+} -error {wrong # args: should be "encoding subcommand ?arg ...?"}
+
+test encoding-1.2 {encoding errors} {
+    encoding nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be convertfrom, convertto, names, or system}
+
+test encoding-2.1 {encoding names} {
+    encoding names
+} -ok {utf-8 iso8859-1 ascii utf-16le utf-16be}
+
+test encoding-2.2 {encoding names errors} {
+    encoding names x
+} -error {wrong # args: should be "encoding names"}
+
+test encoding-3.1 {encoding system} {
+    encoding system
+} -ok {utf-8}
+
+test encoding-3.2 {encoding system errors} {
+    encoding system utf-8
+} -error {wrong # args: should be "encoding system"}
+
+test encoding-4.1 {encoding convertto errors} {
+    encoding convertto
+} -error {wrong # args: should be "encoding convertto ?encoding? string"}
+
+test encoding-4.2 {encoding convertto unknown encoding} {
+    encoding convertto nonesuch abc
+} -error {unknown encoding "nonesuch"}
+
+test encoding-4.3 {encoding convertto utf-8} {
+    bytes [encoding convertto utf-8 "aé€"]
+} -ok {97 195 169 226 130 172}
+
+test encoding-4.4 {encoding convertto uses the system encoding} {
+    bytes [encoding convertto "é"]
+} -ok {195 169}
+
+test encoding-4.5 {encoding convertto iso8859-1} {
+    bytes [encoding convertto iso8859-1 "aé€"]
+} -ok {97 233 63}
+
+test encoding-4.6 {encoding convertto ascii} {
+    bytes [encoding convertto ascii "aé"]
+} -ok {97 63}
+
+test encoding-4.7 {encoding convertto utf-16le} {
+    bytes [encoding convertto utf-16le "a€"]
+} -ok {97 0 172 32}
+
+test encoding-4.8 {encoding convertto utf-16be} {
+    bytes [encoding convertto utf-16be "a€"]
+} -ok {0 97 32 172}
+
+test encoding-4.9 {encoding convertto utf-16 surrogate pair} {
+    bytes [encoding convertto utf-16be [encoding convertfrom utf-8 [binary format c4 {0xf0 0x9f 0x98 0x80}]]]
+} -ok {216 61 222 0}
+
+test encoding-5.1 {encoding convertfrom errors} {
+    encoding convertfrom a b c
+} -error {wrong # args: should be "encoding convertfrom ?encoding? data"}
+
+test encoding-5.2 {encoding convertfrom unknown encoding} {
+    encoding convertfrom nonesuch abc
+} -error {unknown encoding "nonesuch"}
+
+test encoding-5.3 {encoding convertfrom utf-8} {
+    encoding convertfrom utf-8 [binary format c* {97 195 169 226 130 172}]
+} -ok "aé€"
+
+test encoding-5.4 {encoding convertfrom uses the system encoding} {
+    encoding convertfrom [binary format c2 {195 169}]
+} -ok "é"
+
+test encoding-5.5 {encoding convertfrom iso8859-1} {
+    encoding convertfrom iso8859-1 [binary format c2 {97 233}]
+} -ok "aé"
+
+test encoding-5.6 {encoding convertfrom utf-16le} {
+    encoding convertfrom utf-16le [binary format s2 {97 0x20ac}]
+} -ok "a€"
+
+test encoding-5.7 {encoding convertfrom utf-16be} {
+    encoding convertfrom utf-16be [binary format S2 {97 0x20ac}]
+} -ok "a€"
+
+test encoding-5.8 {encoding convertfrom utf-16 surrogate pair} {
+    bytes [encoding convertto utf-8 [encoding convertfrom utf-16be [binary format S2 {0xd83d 0xde00}]]]
+} -ok {240 159 152 128}
+
+test encoding-6.1 {invalid utf-8 is decoded as the replacement character} {
+    encoding convertfrom utf-8 [binary format c3 {97 0xff 98}]
+} -ok "a�b"
+
+test encoding-6.2 {incomplete utf-8 is decoded as the replacement character} {
+    encoding convertfrom utf-8 [binary format c3 {97 0xe2 0x82}]
+} -ok "a�"
+
+test encoding-6.3 {invalid ascii is decoded as the replacement character} {
+    encoding convertfrom ascii [binary format c2 {97 0xe9}]
+} -ok "a�"
+
+test encoding-6.4 {unpaired surrogates are decoded as the replacement character} {
+    encoding convertfrom utf-16le [binary format s3 {0xdc00 0xd800 97}]
+} -ok "��a"
+
+test encoding-6.5 {odd utf-16 byte is decoded as the replacement character} {
+    encoding convertfrom utf-16le [binary format sc 97 98]
+} -ok "a�"
+
+test encoding-7.1 {round trip} {
+    set s "héllo 世界"
+    expr {[encoding convertfrom utf-16le [encoding convertto utf-16le $s]] eq $s}
+} -ok {1}
+
+rename bytes ""
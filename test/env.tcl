@@ -0,0 +1,58 @@
+# Test Script: env
+
+test env-1.1 {env is a global array} {
+    array exists ::env
+} -ok {1}
+
+test env-1.2 {env holds the environment} {
+    expr {[exec sh -c {echo $HOME}] eq $::env(HOME)}
+} -ok {1}
+
+test env-1.3 {env, set} -body {
+    set ::env(MOLT_ENV_TEST) "hello world"
+    exec sh -c {echo $MOLT_ENV_TEST}
+} -cleanup {
+    unset ::env(MOLT_ENV_TEST)
+} -ok {hello world}
+
+test env-1.4 {env, unset} -setup {
+    set ::env(MOLT_ENV_TEST) hello
+} -body {
+    unset ::env(MOLT_ENV_TEST)
+    list [info exists ::env(MOLT_ENV_TEST)] [exec sh -c {echo "<$MOLT_ENV_TEST>"}]
+} -ok {0 <>}
+
+test env-1.5 {env, array set} -body {
+    array set ::env {MOLT_ENV_A a MOLT_ENV_B b}
+    exec sh -c {echo $MOLT_ENV_A $MOLT_ENV_B}
+} -cleanup {
+    unset ::env(MOLT_ENV_A) ::env(MOLT_ENV_B)
+} -ok {a b}
+
+test env-1.6 {env, global in a proc} -setup {
+    proc myproc {} {
+        global env
+        set env(MOLT_ENV_TEST) proc
+    }
+} -body {
+    myproc
+    exec sh -c {echo $MOLT_ENV_TEST}
+} -cleanup {
+    rename myproc ""
+    unset ::env(MOLT_ENV_TEST)
+} -ok {proc}
+
+test env-1.7 {env, local array is not linked} -setup {
+    proc myproc {} {
+        set env(MOLT_ENV_TEST) local
+        exec sh -c {echo "<$MOLT_ENV_TEST>"}
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {<>}
+
+test env-1.8 {env, missing variable} {
+    set ::env(MOLT_NONESUCH)
+} -error {can't read "::env(MOLT_NONESUCH)": no such element in array}
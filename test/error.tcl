@@ -2,7 +2,7 @@
 
 test error-1.1 {error error} {
     error
-} -error {wrong # args: should be "error message"}
+} -error {wrong # args: should be "error message ?errorInfo? ?errorCode?"}
 
 test error-2.1 {error returns error} {
     error "simulated error"
@@ -22,3 +22,91 @@ test error-2.2 {error exits proc} -setup {
 } -cleanup {
     rename myproc ""
 } -ok {before 1 {simulated error}}
+
+test error-3.1 {error info and code} {
+    catch {error "simulated error"} result opts
+    list [dict get $opts -errorinfo] [dict get $opts -errorcode]
+} -ok {{simulated error
+    while executing
+"error "simulated error""} NONE}
+
+test error-3.2 {error with errorInfo} {
+    catch {error "simulated error" "my info"} result opts
+    list $result [dict get $opts -errorinfo]
+} -ok {{simulated error} {my info}}
+
+test error-3.3 {error with empty errorInfo} {
+    catch {error "simulated error" ""} result opts
+    dict get $opts -errorinfo
+} -ok {simulated error
+    while executing
+"error "simulated error" """}
+
+test error-3.4 {error with errorCode} {
+    catch {error "simulated error" "" {MY CODE}} result opts
+    dict get $opts -errorcode
+} -ok {MY CODE}
+
+test error-4.1 {stack trace through procs} -setup {
+    proc myproc1 {} {
+        set x 1
+        error "simulated error"
+    }
+    proc myproc2 {} {
+        if {1} {
+            myproc1
+        }
+    }
+} -body {
+    catch myproc2 result opts
+    dict get $opts -errorinfo
+} -cleanup {
+    rename myproc1 ""
+    rename myproc2 ""
+} -ok {simulated error
+    while executing
+"error "simulated error""
+    (procedure "myproc1" line 3)
+    invoked from within
+"myproc1"
+    invoked from within
+"if {1} {
+            myproc1
+        }"
+    (procedure "myproc2" line 2)
+    invoked from within
+"myproc2"}
+
+test error-4.2 {stack trace through command substitution} {
+    catch {set a [nonesuch]} result opts
+    dict get $opts -errorinfo
+} -ok {invalid command name "nonesuch"
+    while executing
+"nonesuch"
+    invoked from within
+"set a [nonesuch..."}
+
+test error-4.3 {errors in long commands are truncated} {
+    set script [list error [string repeat x 200]]
+    catch {uplevel 0 $script} result opts
+    string length [lindex [split [dict get $opts -errorinfo] \n] 2]
+} -ok {155}
+
+test error-4.4 {stack trace through procs with errorInfo} -setup {
+    proc myproc {} {
+        error "simulated error" "my info"
+    }
+} -body {
+    catch myproc result opts
+    dict get $opts -errorinfo
+} -cleanup {
+    rename myproc ""
+} -ok {my info
+    (procedure "myproc" line 2)
+    invoked from within
+"myproc"}
+
+test error-5.1 {catch sets errorInfo and errorCode} {
+    catch {error "simulated error" "my info" {MY CODE}}
+    list [uplevel #0 {set errorInfo}] [uplevel #0 {set errorCode}]
+} -ok {{my info} {MY CODE}}
@@ -0,0 +1,213 @@
+# Test Script: exec
+#
+# These tests assume the standard Unix programs cat, echo, printf, sh, sleep, tr, true,
+# and wc.  The redirection tests work in the scratch directory exec_tmp, which each test
+# creates and deletes.
+
+proc exec_setup {} {
+    file delete -force exec_tmp
+    file mkdir exec_tmp
+}
+
+proc exec_cleanup {} {
+    file delete -force exec_tmp
+}
+
+proc exec_read {name} {
+    set f [open exec_tmp/$name]
+    set text [read $f]
+    close $f
+    return $text
+}
+
+test exec-1.1 {exec errors} {
+    exec
+} -error {wrong # args: should be "exec ?-option ...? arg ?arg ...?"}
+
+test exec-1.2 {exec errors} {
+    exec -nonesuch echo
+} -error {bad option "-nonesuch": must be -ignorestderr, -keepnewline, or --}
+
+test exec-1.3 {exec, nonexistent program} {
+    exec nonesuch_program_xyz arg
+} -error {couldn't execute "nonesuch_program_xyz": no such file or directory}
+
+test exec-2.1 {exec, output} {
+    exec echo hello world
+} -ok {hello world}
+
+test exec-2.2 {exec, no output} {
+    exec true
+} -ok {}
+
+test exec-2.3 {exec, arguments are verbatim} {
+    exec printf {%s|} "a b" {*.tcl} {$x} {}
+} -ok {a b|*.tcl|$x||}
+
+test exec-2.4 {exec, only one trailing newline is stripped} {
+    exec printf "a\n\n"
+} -ok "a\n"
+
+test exec-2.5 {exec -keepnewline} {
+    exec -keepnewline echo hello
+} -ok "hello\n"
+
+test exec-2.6 {exec --} {
+    exec -- echo -keepnewline
+} -ok {-keepnewline}
+
+test exec-3.1 {exec, nonzero exit status} -body {
+    list [catch {exec sh -c {echo out; exit 3}} msg] $msg [lindex $::errorCode 0] [lindex $::errorCode 2]
+} -ok {1 {out
+child process exited abnormally} CHILDSTATUS 3}
+
+test exec-3.2 {exec, nonzero exit status with standard error} -body {
+    list [catch {exec sh -c {echo out; echo oops >&2; exit 1}} msg] $msg [lindex $::errorCode 2]
+} -ok {1 {out
+oops} 1}
+
+test exec-3.3 {exec, standard error} -body {
+    list [catch {exec sh -c {echo oops >&2}} msg] $msg $::errorCode
+} -ok {1 oops NONE}
+
+test exec-3.4 {exec -ignorestderr} -body {
+    exec -ignorestderr -keepnewline echo out
+} -ok "out\n"
+
+test exec-3.5 {exec, killed by a signal} -body {
+    list [catch {exec sh -c {kill -9 $$}} msg] $msg [lindex $::errorCode 0] [lindex $::errorCode 2]
+} -ok {1 {child killed} CHILDKILLED 9}
+
+test exec-3.6 {exec, working directory} -body {
+    string equal [exec pwd] [pwd]
+} -ok {1}
+
+test exec-4.1 {exec, pipeline} {
+    exec printf "b\na\nc\n" | sort | tr a-z A-Z
+} -ok "A\nB\nC"
+
+test exec-4.2 {exec, pipeline errors} {
+    exec | cat
+} -error {illegal use of | or |& in command}
+
+test exec-4.3 {exec, pipeline errors} {
+    exec echo |
+} -error {illegal use of | or |& in command}
+
+test exec-4.4 {exec, pipeline errors} {
+    exec echo | | cat
+} -error {illegal use of | or |& in command}
+
+test exec-4.5 {exec, pipeline with a nonexistent program} {
+    exec echo | nonesuch_program_xyz
+} -error {couldn't execute "nonesuch_program_xyz": no such file or directory}
+
+test exec-4.6 {exec, failing middle stage} -body {
+    list [catch {exec echo a | sh -c {cat; exit 4} | cat} msg] $msg [lindex $::errorCode 2]
+} -ok {1 {a
+child process exited abnormally} 4}
+
+test exec-4.7 {exec, |& pipes standard error} {
+    exec sh -c {echo out; echo err >&2} |& sort
+} -ok "err\nout"
+
+test exec-5.1 {exec, redirection errors} {
+    exec cat <
+} -error {can't specify "<" as last word in command}
+
+test exec-5.2 {exec, redirection errors} {
+    exec < exec_nonesuch.txt
+} -error {didn't specify command to execute}
+
+test exec-5.3 {exec, redirection errors} {
+    exec cat < exec_nonesuch.txt
+} -error {couldn't read file "exec_nonesuch.txt": no such file or directory}
+
+test exec-5.4 {exec, < and >} -setup exec_setup -body {
+    exec echo "hello" > exec_tmp/a.txt
+    list [exec tr a-z A-Z < exec_tmp/a.txt] [exec_read a.txt]
+} -cleanup exec_cleanup -ok "HELLO {hello\n}"
+
+test exec-5.5 {exec, >> appends} -setup exec_setup -body {
+    exec echo one >exec_tmp/a.txt
+    exec echo two >>exec_tmp/a.txt
+    exec_read a.txt
+} -cleanup exec_cleanup -ok "one\ntwo\n"
+
+test exec-5.6 {exec, << feeds a string} {
+    exec wc -c << "abcde"
+} -ok {5}
+
+test exec-5.7 {exec, << with a pipeline} {
+    exec cat << "one two" | tr " " "\n"
+} -ok "one\ntwo"
+
+test exec-5.8 {exec, 2> redirects standard error} -setup exec_setup -body {
+    set result [exec sh -c {echo out; echo err >&2} 2> exec_tmp/err.txt]
+    list $result [exec_read err.txt]
+} -cleanup exec_cleanup -ok "out {err\n}"
+
+test exec-5.9 {exec, 2>@1 merges standard error} {
+    exec sh -c {echo out; echo err >&2} 2>@1
+} -ok "out\nerr"
+
+test exec-5.10 {exec, >& redirects both} -setup exec_setup -body {
+    set result [exec sh -c {echo out; echo err >&2} >& exec_tmp/a.txt]
+    list $result [exec_read a.txt]
+} -cleanup exec_cleanup -ok "{} {out\nerr\n}"
+
+test exec-5.11 {exec, output to an unwritable file} -setup exec_setup -body {
+    exec echo > exec_tmp/nonesuch/a.txt
+} -cleanup exec_cleanup -error {couldn't write file "exec_tmp/nonesuch/a.txt": no such file or directory}
+
+test exec-5.12 {exec, >@ writes the standard output to a channel} -setup exec_setup -body {
+    set f [open exec_tmp/a.txt w]
+    puts $f before
+    set result [list [exec echo separate >@ $f] [exec echo attached >@$f]]
+    puts $f after
+    close $f
+    list $result [exec_read a.txt]
+} -cleanup exec_cleanup -ok "{{} {}} {before\nseparate\nattached\nafter\n}"
+
+test exec-5.13 {exec, 2>@ writes the standard error to a channel} -setup exec_setup -body {
+    set f [open exec_tmp/err.txt w]
+    set result [list [exec sh -c {echo out; echo separate >&2} 2>@ $f] [exec sh -c {echo attached >&2} 2>@$f]]
+    close $f
+    list $result [exec_read err.txt]
+} -cleanup exec_cleanup -ok "{out {}} {separate\nattached\n}"
+
+test exec-5.14 {exec, >@ with 2>@1} -setup exec_setup -body {
+    set f [open exec_tmp/a.txt w]
+    set result [exec sh -c {echo out; echo err >&2} >@ $f 2>@1]
+    close $f
+    list $result [exec_read a.txt]
+} -cleanup exec_cleanup -ok "{} {out\nerr\n}"
+
+test exec-5.15 {exec, redirection to a channel errors} {
+    list [catch {exec echo >@ nonesuch} msg] $msg [catch {exec echo 2>@ stdin} msg] $msg
+} -ok {1 {can not find channel named "nonesuch"} 1 {channel "stdin" wasn't opened for writing}}
+
+test exec-6.1 {exec, background} -setup exec_setup -body {
+    set pids [exec sh -c {echo done > exec_tmp/a.txt} &]
+    set result [list [llength $pids] [string is integer [lindex $pids 0]]]
+    after 200
+    lappend result [exec_read a.txt]
+} -cleanup exec_cleanup -ok "1 1 {done\n}"
+
+test exec-6.2 {exec, background pipeline returns immediately} -setup exec_setup -body {
+    set start [clock milliseconds]
+    set pids [exec sleep 1 | cat > exec_tmp/a.txt &]
+    list [llength $pids] [expr {[clock milliseconds] - $start < 500}]
+} -cleanup exec_cleanup -ok {2 1}
+
+test exec-6.3 {exec, & needn't be last} {
+    exec echo a & b
+} -ok {a & b}
+
+test exec-6.4 {exec, background pipeline can't write to a channel} {
+    exec echo >@ stdout &
+} -error {can't redirect a background pipeline to channel "stdout"}
+
+rename exec_setup ""
+rename exec_cleanup ""
+rename exec_read ""
@@ -231,6 +231,50 @@ test expr-4.7 {comparisons} {
     lexpr {1.1 == 1.1} {1.1 != 1.1} {1.1 < 1.1} {1.1 <= 1.1} {1.1 > 1.1} {1.1 >= 1.1}
 } -ok {1 0 0 1 0 1}
 
+test expr-4.8 {string comparisons} {
+    lexpr {"a" eq "a"} {"a" eq "b"} {"a" ne "a"} {"a" ne "b"} {"" eq ""}
+} -ok {1 0 0 1 1}
+
+test expr-4.9 {string comparisons, numbers compare as strings} {
+    lexpr {"0x10" eq "16"} {0x10 == 16} {0x10 eq 16} {1.0 eq 1} {1.0 == 1} {"1.0" ne "1"}
+} -ok {0 1 0 0 1 1}
+
+test expr-4.10 {string ordering} {
+    lexpr {"a" lt "b"} {"b" lt "a"} {"a" lt "a"} {"a" gt "b"} {"b" gt "a"} {"" lt "a"}
+} -ok {1 0 0 0 1 1}
+
+test expr-4.11 {string ordering} {
+    lexpr {"a" le "a"} {"b" le "a"} {"abc" ge "abd"} {"abd" ge "abd"} {"ab" le "abc"}
+} -ok {1 0 0 1 1}
+
+test expr-4.12 {string ordering, numbers compare as strings} {
+    lexpr {10 lt 9} {10 < 9} {2 gt 10} {2 > 10} {1e1 ge 10} {1e1 >= 10}
+} -ok {1 0 1 0 1 1}
+
+test expr-4.13 {string comparisons, precedence} {
+    lexpr {"a" eq "a" == 1} {1 == 1 ne 0} {1 < 2 eq 1} {"a" lt "b" == 1} {"a" lt "b" < 2} {2 + 3 eq 5}
+} -ok {1 1 1 1 1 1}
+
+test expr-4.14 {string comparisons, precedence} {
+    lexpr {"b" in {a b} eq 1} {"b" eq "b" && "c" ne "b"} {"a" lt "b" ? "x" : "y"}
+} -ok {0 1 x}
+
+test expr-4.15 {string comparisons, operators and barewords} {
+    lexpr {"a"eq"a"} {"b"lt"c"} {max(1,2) eq 2}
+} -ok {1 1 1}
+
+test expr-4.16 {string comparison errors} {
+    expr {"a" eq}
+} -error {syntax error in expression ""a" eq"}
+
+test expr-4.17 {string comparison errors} {
+    expr {lt "a"}
+} -error {syntax error in expression "lt "a""}
+
+test expr-4.18 {string comparison errors} {
+    expr {1 eqq 1}
+} -error {unknown math function "eqq"}
+
 # expr-5.*: bitwise operators
 
 test expr-5.1 {bit-wise} {
@@ -284,3 +328,284 @@ test expr-6.3 {questy} {
     # $b will be 2.
     list $result $a $b
 } -ok {1 1 0}
+
+test expr-6.4 {questy, nested} {
+    lexpr {1 ? 2 : 3 ? 4 : 5} {0 ? 2 : 0 ? 4 : 5} {0 ? 2 : 1 ? 4 : 5} {1 ? 0 ? 6 : 7 : 8}
+} -ok {2 5 4 7}
+
+test expr-6.5 {questy, lowest precedence} {
+    set ::x -5
+    lexpr {$::x > 0 ? $::x : -$::x} {1 ? 2 : 3 + 4} {1 || 0 ? 5 : 6} {0 ? 1 : 2 * 3}
+} -ok {5 2 5 6}
+
+test expr-6.6 {questy, unselected branch isn't evaluated} {
+    lexpr {0 ? [error boom] : 9} {1 ? 9 : [error boom]} {1 ? 9 : 0**-1}
+} -ok {9 9 9}
+
+test expr-6.7 {questy, floating-point condition} {
+    lexpr {1.5 ? 1 : 2} {0.0 ? 1 : 2}
+} -ok {1 2}
+
+test expr-6.8 {questy errors} {
+    expr {1 ? 2}
+} -error {syntax error in expression "1 ? 2": missing ":" for "?" at character 2}
+
+test expr-6.9 {questy errors} {
+    expr {0 ? 2}
+} -error {syntax error in expression "0 ? 2": missing ":" for "?" at character 2}
+
+test expr-6.10 {questy errors, nested} {
+    expr {1 ? 0 ? 6 : 8}
+} -error {syntax error in expression "1 ? 0 ? 6 : 8": missing ":" for "?" at character 2}
+
+test expr-6.11 {questy errors} {
+    expr {1 ? 2 : }
+} -error {syntax error in expression "1 ? 2 : "}
+
+test expr-6.12 {questy errors} {
+    expr {"a" ? 1 : 2}
+} -error {can't use non-numeric string as operand of "?"}
+
+# expr-7.*: math functions
+
+test expr-7.1 {math function errors} {
+    expr {nosuch(1)}
+} -error {unknown math function "nosuch"}
+
+test expr-7.2 {math function errors} {
+    expr {pow(1)}
+} -error {not enough arguments for math function "pow"}
+
+test expr-7.3 {math function errors} {
+    expr {sqrt(1,2)}
+} -error {too many arguments for math function "sqrt"}
+
+test expr-7.4 {math function errors} {
+    expr {sqrt("x")}
+} -error {argument to math function didn't have numeric value}
+
+test expr-7.5 {math function errors} {
+    expr {sqrt 2}
+} -error {syntax error in expression "sqrt 2"}
+
+test expr-7.6 {math function domain errors} {
+    set result {}
+    foreach e {{sqrt(-1)} {log(0)} {log10(-1)} {acos(2)} {fmod(1, 0)}} {
+        lappend result [catch {expr $e} msg] $msg
+    }
+    set result
+} -ok {1 {domain error: argument not in valid range} 1 {domain error: argument not in valid range} 1 {domain error: argument not in valid range} 1 {domain error: argument not in valid range} 1 {domain error: argument not in valid range}}
+
+test expr-7.7 {abs} {
+    lexpr {abs(-3)} {abs(3)} {abs(-2.5)} {abs(-3) / 2} {abs(-3.0) / 2}
+} -ok {3 3 2.5 1 1.5}
+
+test expr-7.8 {abs, overflow} {
+    expr {abs(-9223372036854775807 - 1)}
+} -error {integer overflow}
+
+test expr-7.9 {rounding functions} {
+    lexpr {ceil(1.2)} {floor(1.8)} {ceil(-1.2)} {floor(-1.8)} {ceil(3) / 2}
+} -ok {2 1 -1 -2 1.5}
+
+test expr-7.10 {conversion functions} {
+    lexpr {int(3.7)} {int(-3.7)} {double(3) / 2} {round(2.5)} {round(-2.5)}
+} -ok {3 -3 1.5 3 -3}
+
+test expr-7.11 {exponential functions} {
+    lexpr {sqrt(16)} {exp(0)} {log(1)} {log10(1000)} {pow(2, 10)} {pow(2, 0.5) == sqrt(2)}
+} -ok {4 1 0 3 1024 1}
+
+test expr-7.12 {fmod and hypot} {
+    lexpr {fmod(7, 3)} {fmod(-7, 3)} {fmod(7.5, 2)} {hypot(3, 4)}
+} -ok {1 -1 1.5 5}
+
+test expr-7.13 {trigonometric functions} {
+    lexpr {sin(0)} {cos(0)} {tan(0)} {asin(0)} {acos(1)} {atan(0)} {atan2(0, 1)} {round(1000 * atan2(1, 1))}
+} -ok {0 1 0 0 0 0 0 785}
+
+test expr-7.14 {hyperbolic functions} {
+    lexpr {sinh(0)} {cosh(0)} {tanh(0)}
+} -ok {0 1 0}
+
+test expr-7.15 {max and min} {
+    lexpr {max(1)} {max(1, 3, 2)} {min(3, 1, 2)} {max(-1, -2)} {min(2.5, 3)} {max(1, 2.5)}
+} -ok {1 3 1 -1 2.5 2.5}
+
+test expr-7.16 {max and min, integer-ness} {
+    lexpr {max(1, 3) / 2} {max(1, 3.0) / 2} {min(3, 5, 7.0) / 2}
+} -ok {1 1.5 1.5}
+
+test expr-7.17 {math functions, nested expressions} {
+    set x 9
+    expr {max(sqrt($x), abs(-2) + 2) * 2}
+} -ok {8}
+
+test expr-7.18 {math functions, not evaluated} {
+    lexpr {0 && sqrt(-1)} {1 || max(1, "x")} {1 ? 2 : log(0)}
+} -ok {0 1 2}
+
+test expr-7.19 {math function errors, empty argument list} {
+    expr {sqrt()}
+} -error {not enough arguments for math function "sqrt"}
+
+test expr-7.20 {math function errors, empty argument list, variadic} {
+    expr {max( )}
+} -error {not enough arguments for math function "max"}
+
+test expr-7.21 {math functions, empty argument list} {
+    expr {rand( ) < 1.0}
+} -ok {1}
+
+# expr-8.*: rand() and srand()
+
+test expr-8.1 {rand errors} {
+    expr {rand(1)}
+} -error {too many arguments for math function "rand"}
+
+test expr-8.2 {srand errors} {
+    expr {srand()}
+} -error {not enough arguments for math function "srand"}
+
+test expr-8.3 {srand errors} {
+    expr {srand(1.5)}
+} -error {can't use floating-point value as argument to srand}
+
+test expr-8.4 {srand errors} {
+    expr {srand(1, 2)}
+} -error {too many arguments for math function "srand"}
+
+test expr-8.5 {rand, range} {
+    set result 1
+    for {set i 0} {$i < 100} {incr i} {
+        set r [expr {rand()}]
+        if {$r < 0.0 || $r >= 1.0} {
+            set result $r
+        }
+    }
+    set result
+} -ok {1}
+
+test expr-8.6 {srand returns the first value} {
+    set first [expr {srand(1)}]
+    set second [expr {rand()}]
+    list [expr {$first == srand(1)}] [expr {$second == rand()}] [expr {$first == $second}]
+} -ok {1 1 0}
+
+test expr-8.7 {srand, Standard TCL's sequence} {
+    expr {srand(1)}
+    expr {round(1000000 * rand())}
+} -ok {131538}
+
+test expr-8.8 {srand, per interpreter} -body {
+    interp create rand_slave
+    interp eval rand_slave {expr {srand(99)}}
+    expr {srand(99)}
+    set same [expr {rand() == [interp eval rand_slave {expr {rand()}}]}]
+    set different [expr {rand() == [interp eval rand_slave {expr {srand(5)}}]}]
+    list $same $different
+} -cleanup {
+    interp delete rand_slave
+} -ok {1 0}
+
+# expr-9.*: ** operator
+
+test expr-9.1 {exponentiation, integers} {
+    lexpr {2 ** 10} {2**0} {0**0} {0**3} {(-2)**3} {10**18}
+} -ok {1024 1 1 0 -8 1000000000000000000}
+
+test expr-9.2 {exponentiation, right-associative} {
+    lexpr {2**3**2} {(2**3)**2} {2 ** 3 ** 0}
+} -ok {512 64 2}
+
+test expr-9.3 {exponentiation, precedence} {
+    lexpr {-2**2} {-2**2 + 1} {3*2**2} {2**2*3} {2**-1 + 1} {1 ? 2**2 : 3}
+} -ok {-4 -3 12 12 1 4}
+
+test expr-9.4 {exponentiation, negative integer powers} {
+    lexpr {2**-1} {1**-5} {(-1)**-3} {(-1)**-4} {-1**-4}
+} -ok {0 1 -1 1 -1}
+
+test expr-9.5 {exponentiation, zero by negative power} {
+    expr {0**-1}
+} -error {exponentiation of zero by negative power}
+
+test expr-9.6 {exponentiation, zero by negative power} {
+    expr {0.0**-1}
+} -error {exponentiation of zero by negative power}
+
+test expr-9.7 {exponentiation, integer overflow} {
+    expr {2**63}
+} -error {integer overflow}
+
+test expr-9.8 {exponentiation, limits} {
+    lexpr {2**62} {(-2)**63} {1**1000000000000} {(-1)**1000000000001}
+} -ok {4611686018427387904 -9223372036854775808 1 -1}
+
+test expr-9.9 {exponentiation, floating point} {
+    lexpr {2.0**-1} {2**0.5 == sqrt(2)} {4**0.5} {2.5**2}
+} -ok {0.5 1 2 6.25}
+
+test expr-9.10 {exponentiation, domain error} {
+    expr {(-8)**(1.0/3)}
+} -error {domain error: argument not in valid range}
+
+test expr-9.11 {exponentiation errors} {
+    expr {"a"**2}
+} -error {can't use non-numeric string as operand of "**"}
+
+test expr-9.12 {exponentiation errors} {
+    expr {2**}
+} -error {syntax error in expression "2**"}
+
+test expr-9.13 {exponentiation errors} {
+    expr {** 2}
+} -error {syntax error in expression "** 2"}
+
+test expr-9.14 {exponentiation, not evaluated} {
+    lexpr {0 && 2**100} {1 || 0**-1}
+} -ok {0 1}
+
+# expr-10.*: in and ni operators
+
+test expr-10.1 {list membership} {
+    lexpr {"a" in {a b c}} {"d" in {a b c}} {"a" ni {a b c}} {"d" ni {a b c}}
+} -ok {1 0 0 1}
+
+test expr-10.2 {list membership, empty list} {
+    lexpr {"" in {}} {1 in {}} {1 ni {}}
+} -ok {0 0 1}
+
+test expr-10.3 {list membership, string equality} {
+    lexpr {1 in {1.0 2}} {1.0 in {1.0 2}} {"a b" in {{a b} c}} {"a" in {{a b} c}} {0x1 in {1}}
+} -ok {0 1 1 0 0}
+
+test expr-10.4 {list membership, computed operands} {
+    set ::values {2 4 6}
+    lexpr {1+1 in $::values} {3 in $::values} {[lindex $::values 1] ni $::values}
+} -ok {1 0 0}
+
+test expr-10.5 {list membership, precedence} {
+    lexpr {1 < 2 in {1}} {"b" in {a b} == 1} {"b" in {a b} && "c" ni {a b}} {"a" in {a} ? 5 : 6}
+} -ok {1 0 1 5}
+
+test expr-10.6 {list membership, malformed list} {
+    set ::bad "\{a"
+    expr {"a" in $::bad}
+} -error {unmatched open brace in list}
+
+test expr-10.7 {list membership, malformed list} {
+    set ::bad "\{a"
+    expr {"a" ni $::bad}
+} -error {unmatched open brace in list}
+
+test expr-10.8 {list membership, not evaluated} {
+    set ::bad "\{a"
+    expr {0 && "a" in $::bad}
+} -ok {0}
+
+test expr-10.9 {list membership, numbers compare as given} {
+    set ::f 1.0
+    expr {$::f + 0}
+    lexpr {$::f in {1.0}} {$::f in {1}} {(0x10 + 0) in {16}} {(1 ? 0x10 : 2) in {0x10}}
+} -ok {1 0 1 1}
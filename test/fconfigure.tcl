@@ -0,0 +1,165 @@
+# Test Script: fconfigure
+
+# The tests work in the scratch directory fconfigure_tmp, which each test creates and
+# deletes.
+proc fc_setup {} {
+    file delete -force fconfigure_tmp
+    file mkdir fconfigure_tmp
+}
+
+proc fc_cleanup {} {
+    file delete -force fconfigure_tmp
+}
+
+# Writes the text to the file with the given fconfigure options.
+proc fc_write {name text args} {
+    set f [open fconfigure_tmp/$name w]
+    foreach {option value} $args {
+        fconfigure $f $option $value
+    }
+    puts -nonewline $f $text
+    close $f
+}
+
+# Reads the file with the given fconfigure options.
+proc fc_read {name args} {
+    set f [open fconfigure_tmp/$name]
+    foreach {option value} $args {
+        fconfigure $f $option $value
+    }
+    set text [read $f]
+    close $f
+    return $text
+}
+
+test fconfigure-1.1 {fconfigure errors} {
+    fconfigure
+} -error {wrong # args: should be "fconfigure channelId ?optionName? ?value optionName value ...?"}
+
+test fconfigure-1.2 {fconfigure errors} {
+    fconfigure nonesuch
+} -error {can not find channel named "nonesuch"}
+
+test fconfigure-1.3 {fconfigure errors} {
+    fconfigure stdout -nonesuch
+} -error {bad option "-nonesuch": should be one of -blocking, -buffering, -buffersize, -encoding, or -translation}
+
+test fconfigure-1.4 {fconfigure errors} {
+    fconfigure stdout -buffering line -blocking
+} -error {value for "-blocking" missing}
+
+test fconfigure-1.5 {fconfigure errors} {
+    fconfigure stdout -buffering some
+} -error {bad value for -buffering: must be one of full, line, or none}
+
+test fconfigure-1.6 {fconfigure errors} {
+    fconfigure stdout -translation {lf lf lf}
+} -error {bad value for -translation: must be one of auto, binary, cr, lf, crlf, or platform}
+
+test fconfigure-1.7 {fconfigure errors} {
+    fconfigure stdout -encoding klingon
+} -error {unknown encoding "klingon"}
+
+test fconfigure-2.1 {fconfigure, all options} {
+    fconfigure stderr
+} -ok {-blocking 1 -buffering none -buffersize 4096 -encoding utf-8 -translation lf}
+
+test fconfigure-2.2 {fconfigure, one option} {
+    list [fconfigure stdin -buffering] [fconfigure stdin -translation] [fconfigure stdout -buffering]
+} -ok {line auto line}
+
+test fconfigure-2.3 {fconfigure, read-write channel} -setup fc_setup -body {
+    set f [open fconfigure_tmp/a.txt w+]
+    set result [list [fconfigure $f -translation] [fconfigure $f -buffering]]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {{auto lf} full}
+
+test fconfigure-2.4 {fconfigure, set options} -setup fc_setup -body {
+    set f [open fconfigure_tmp/a.txt w+]
+    set result [fconfigure $f -buffering line -buffersize 100 -translation {crlf cr} -blocking 0]
+    lappend result [fconfigure $f]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {{-blocking 0 -buffering line -buffersize 100 -encoding utf-8 -translation {crlf cr}}}
+
+test fconfigure-3.1 {-translation crlf, output} -setup fc_setup -body {
+    fc_write a.txt "a\nb\n" -translation crlf
+    list [file size fconfigure_tmp/a.txt] [string map {\r <CR> \n <LF>} [fc_read a.txt -translation lf]]
+} -cleanup fc_cleanup -ok {6 a<CR><LF>b<CR><LF>}
+
+test fconfigure-3.2 {-translation cr, output} -setup fc_setup -body {
+    fc_write a.txt "a\nb\n" -translation cr
+    string map {\r <CR> \n <LF>} [fc_read a.txt -translation lf]
+} -cleanup fc_cleanup -ok {a<CR>b<CR>}
+
+test fconfigure-3.3 {-translation auto, input} -setup fc_setup -body {
+    fc_write a.txt "a\r\nb\rc\nd" -translation lf
+    fc_read a.txt
+} -cleanup fc_cleanup -ok "a\nb\nc\nd"
+
+test fconfigure-3.4 {-translation crlf, input} -setup fc_setup -body {
+    fc_write a.txt "a\r\nb\rc\n" -translation lf
+    string map {\r <CR> \n <LF>} [fc_read a.txt -translation crlf]
+} -cleanup fc_cleanup -ok {a<LF>b<CR>c<LF>}
+
+test fconfigure-3.5 {-translation, gets on CRLF lines} -setup fc_setup -body {
+    fc_write a.txt "one\r\ntwo\r\n" -translation lf
+    set f [open fconfigure_tmp/a.txt]
+    set result [list [gets $f] [gets $f] [gets $f] [eof $f]]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {one two {} 1}
+
+test fconfigure-3.6 {-translation, CRLF split across buffers} -setup fc_setup -body {
+    fc_write a.txt "abc\r\ndef\r\n" -translation lf
+    set f [open fconfigure_tmp/a.txt]
+    fconfigure $f -buffersize 4
+    set result [list [gets $f] [gets $f] [gets $f]]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {abc def {}}
+
+test fconfigure-4.1 {-encoding binary} -setup fc_setup -body {
+    fc_write a.txt "é" -encoding binary
+    list [file size fconfigure_tmp/a.txt] [fc_read a.txt -encoding iso8859-1]
+} -cleanup fc_cleanup -ok {1 é}
+
+test fconfigure-4.2 {-encoding utf-8} -setup fc_setup -body {
+    fc_write a.txt "é"
+    list [file size fconfigure_tmp/a.txt] [string length [fc_read a.txt -encoding binary]] [fc_read a.txt]
+} -cleanup fc_cleanup -ok {2 2 é}
+
+test fconfigure-4.3 {-translation binary} -setup fc_setup -body {
+    fc_write a.txt "é\r\n" -translation binary
+    set f [open fconfigure_tmp/a.txt]
+    fconfigure $f -translation binary
+    set result [list [fconfigure $f -encoding] [fconfigure $f -translation] [string length [read $f]]]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {binary lf 3}
+
+test fconfigure-4.4 {-encoding ascii} -setup fc_setup -body {
+    fc_write a.txt "aé" -encoding ascii
+    fc_read a.txt
+} -cleanup fc_cleanup -ok {a?}
+
+test fconfigure-4.5 {-encoding utf-16le} -setup fc_setup -body {
+    fc_write a.txt "a€\n" -encoding utf-16le -translation crlf
+    set f [open fconfigure_tmp/a.txt]
+    fconfigure $f -encoding utf-16le
+    set result [list [file size fconfigure_tmp/a.txt] [gets $f] [gets $f] [eof $f]]
+    close $f
+    set result
+} -cleanup fc_cleanup -ok {8 a€ {} 1}
+
+test fconfigure-4.6 {-encoding utf-16be} -setup fc_setup -body {
+    fc_write a.txt "a€" -encoding utf-16be
+    binary scan [fc_read a.txt -translation binary] S* units
+    set units
+} -cleanup fc_cleanup -ok {97 8364}
+
+rename fc_setup ""
+rename fc_cleanup ""
+rename fc_write ""
+rename fc_read ""
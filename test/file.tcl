@@ -0,0 +1,316 @@
+# Test Script: file
+
+test file-1.1 {file errors} {
+    file
+} -error {wrong # args: should be "file subcommand ?arg ...?"}
+
+test file-1.2 {file errors} {
+    file nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be atime, copy, delete, dirname, executable, exists, extension, isdirectory, isfile, join, mkdir, mtime, nativename, normalize, readable, rename, rootname, size, split, tail, type, or writable}
+
+test file-2.1 {file exists errors} {
+    file exists
+} -error {wrong # args: should be "file exists name"}
+
+test file-2.2 {file exists} {
+    list [file exists file.tcl] [file exists .] [file exists nonesuch.txt]
+} -ok {1 1 0}
+
+test file-3.1 {file isfile errors} {
+    file isfile
+} -error {wrong # args: should be "file isfile name"}
+
+test file-3.2 {file isfile} {
+    list [file isfile file.tcl] [file isfile .] [file isfile nonesuch.txt]
+} -ok {1 0 0}
+
+test file-4.1 {file isdirectory errors} {
+    file isdirectory a b
+} -error {wrong # args: should be "file isdirectory name"}
+
+test file-4.2 {file isdirectory} {
+    list [file isdirectory file.tcl] [file isdirectory .] [file isdirectory nonesuch.txt]
+} -ok {0 1 0}
+
+test file-5.1 {file readable errors} {
+    file readable
+} -error {wrong # args: should be "file readable name"}
+
+test file-5.2 {file readable} {
+    list [file readable file.tcl] [file readable nonesuch.txt]
+} -ok {1 0}
+
+test file-5.3 {file writable errors} {
+    file writable
+} -error {wrong # args: should be "file writable name"}
+
+test file-5.4 {file writable} {
+    list [file writable .] [file writable nonesuch.txt]
+} -ok {1 0}
+
+test file-5.5 {file executable errors} {
+    file executable
+} -error {wrong # args: should be "file executable name"}
+
+test file-5.6 {file executable} {
+    list [file executable .] [file executable nonesuch.txt]
+} -ok {1 0}
+
+test file-6.1 {file size errors} {
+    file size
+} -error {wrong # args: should be "file size name"}
+
+test file-6.2 {file size} {
+    set size [file size file.tcl]
+    list [string is integer -strict $size] [expr {$size > 0}]
+} -ok {1 1}
+
+test file-6.3 {file size, nonexistent file} {
+    file size nonesuch.txt
+} -error {could not read "nonesuch.txt": no such file or directory}
+
+test file-7.1 {file mtime errors} {
+    file mtime
+} -error {wrong # args: should be "file mtime name"}
+
+test file-7.2 {file mtime} {
+    set mtime [file mtime file.tcl]
+    list [string is integer -strict $mtime] [expr {$mtime <= [clock seconds]}]
+} -ok {1 1}
+
+test file-7.3 {file mtime, nonexistent file} {
+    file mtime nonesuch.txt
+} -error {could not read "nonesuch.txt": no such file or directory}
+
+test file-7.4 {file atime errors} {
+    file atime
+} -error {wrong # args: should be "file atime name"}
+
+test file-7.5 {file atime} {
+    string is integer -strict [file atime file.tcl]
+} -ok {1}
+
+test file-7.6 {file atime, nonexistent file} {
+    file atime nonesuch.txt
+} -error {could not read "nonesuch.txt": no such file or directory}
+
+test file-8.1 {file type errors} {
+    file type
+} -error {wrong # args: should be "file type name"}
+
+test file-8.2 {file type} {
+    list [file type file.tcl] [file type .]
+} -ok {file directory}
+
+test file-8.3 {file type, nonexistent file} {
+    file type nonesuch.txt
+} -error {could not read "nonesuch.txt": no such file or directory}
+
+test file-8.4 {file dirname errors} {
+    file dirname
+} -error {wrong # args: should be "file dirname name"}
+
+test file-8.5 {file dirname} {
+    list [file dirname a/b/c] [file dirname a] [file dirname /a] [file dirname /] [file dirname a/b/] [file dirname C:/a] [file dirname {a\b\c}]
+} -ok {a/b . / / a C:/ a/b}
+
+test file-8.6 {file tail} {
+    list [file tail a/b/c] [file tail a] [file tail /] [file tail a/b/] [file tail {a\b}]
+} -ok {c a {} b b}
+
+test file-8.7 {file tail errors} {
+    file tail
+} -error {wrong # args: should be "file tail name"}
+
+test file-8.8 {file split} {
+    list [file split a/b/c] [file split /a//b/] [file split /] [file split {C:\a\b}] [file split C:a] [file split //server/share/a]
+} -ok {{a b c} {/ a b} / {C:/ a b} {C: a} {//server/share a}}
+
+test file-8.9 {file split errors} {
+    file split
+} -error {wrong # args: should be "file split name"}
+
+test file-8.10 {file join} {
+    list [file join a] [file join a b/c d] [file join a /b c] [file join a {b\c}] [file join a C:/b c] [file join / a] [file join a/ b/]
+} -ok {a a/b/c/d /b/c a/b/c C:/b/c /a a/b}
+
+test file-8.11 {file join errors} {
+    file join
+} -error {wrong # args: should be "file join name ?name ...?"}
+
+test file-8.12 {file extension} {
+    list [file extension a/b.txt] [file extension a.b/c] [file extension b.tar.gz] [file extension .tclrc] [file extension ..] [file extension {a.b\c}]
+} -ok {.txt {} .gz .tclrc {} {}}
+
+test file-8.13 {file extension errors} {
+    file extension
+} -error {wrong # args: should be "file extension name"}
+
+test file-8.14 {file rootname} {
+    list [file rootname a/b.txt] [file rootname a.b/c] [file rootname b.tar.gz] [file rootname ..]
+} -ok {a/b a.b/c b.tar ..}
+
+test file-8.15 {file rootname errors} {
+    file rootname
+} -error {wrong # args: should be "file rootname name"}
+
+test file-8.16 {file normalize} {
+    list [file normalize /a/./b/../c] [file normalize /..] [file normalize {/a\b}]
+} -ok {/a/c / /a/b}
+
+test file-8.17 {file normalize, relative name} {
+    expr {[file normalize a/../b] eq [file join [file normalize .] b]}
+} -ok {1}
+
+test file-8.18 {file normalize errors} {
+    file normalize
+} -error {wrong # args: should be "file normalize name"}
+
+test file-8.19 {file nativename} {
+    set name [file nativename {a/b\c}]
+    expr {$name eq {a/b/c} || $name eq {a\b\c}}
+} -ok {1}
+
+test file-8.20 {file nativename errors} {
+    file nativename
+} -error {wrong # args: should be "file nativename name"}
+
+# The remaining tests work in the scratch directory file_tmp, which each test creates
+# and deletes.
+proc file_setup {} {
+    file delete -force file_tmp
+    file mkdir file_tmp
+    file copy file.tcl file_tmp/a.txt
+}
+
+proc file_cleanup {} {
+    file delete -force file_tmp
+}
+
+test file-9.1 {file mkdir} -setup file_setup -body {
+    file mkdir file_tmp/x/y/z file_tmp/w
+    list [file isdirectory file_tmp/x/y/z] [file isdirectory file_tmp/w]
+} -cleanup file_cleanup -ok {1 1}
+
+test file-9.2 {file mkdir, existing directory} -setup file_setup -body {
+    file mkdir file_tmp
+    file isdirectory file_tmp
+} -cleanup file_cleanup -ok {1}
+
+test file-9.3 {file mkdir, existing file} -setup file_setup -body {
+    file mkdir file_tmp/a.txt
+} -cleanup file_cleanup -error {can't create directory "file_tmp/a.txt": file already exists}
+
+test file-9.4 {file mkdir, no names} {
+    file mkdir
+} -ok {}
+
+test file-10.1 {file delete, file} -setup file_setup -body {
+    file delete file_tmp/a.txt
+    file exists file_tmp/a.txt
+} -cleanup file_cleanup -ok {0}
+
+test file-10.2 {file delete, nonexistent files are ignored} -setup file_setup -body {
+    file delete file_tmp/nonesuch file_tmp/a.txt
+    file exists file_tmp/a.txt
+} -cleanup file_cleanup -ok {0}
+
+test file-10.3 {file delete, empty directory} -setup file_setup -body {
+    file mkdir file_tmp/x
+    file delete file_tmp/x
+    file exists file_tmp/x
+} -cleanup file_cleanup -ok {0}
+
+test file-10.4 {file delete, non-empty directory} -setup file_setup -body {
+    file delete file_tmp
+} -cleanup file_cleanup -error {error deleting "file_tmp": directory not empty}
+
+test file-10.5 {file delete -force, non-empty directory} -setup file_setup -body {
+    file mkdir file_tmp/x/y
+    file delete -force file_tmp
+    file exists file_tmp
+} -cleanup file_cleanup -ok {0}
+
+test file-10.6 {file delete, options} -setup file_setup -body {
+    file delete -- -force
+    file delete -nonesuch
+} -cleanup file_cleanup -error {bad option "-nonesuch": must be -force or --}
+
+test file-11.1 {file copy errors} {
+    file copy a
+} -error {wrong # args: should be "file copy ?-force? ?--? source ?source ...? target"}
+
+test file-11.2 {file copy, file} -setup file_setup -body {
+    file copy file_tmp/a.txt file_tmp/b.txt
+    list [file exists file_tmp/a.txt] [expr {[file size file_tmp/b.txt] == [file size file.tcl]}]
+} -cleanup file_cleanup -ok {1 1}
+
+test file-11.3 {file copy, existing target} -setup file_setup -body {
+    file copy file_tmp/a.txt file_tmp/b.txt
+    file copy file_tmp/a.txt file_tmp/b.txt
+} -cleanup file_cleanup -error {error copying "file_tmp/a.txt" to "file_tmp/b.txt": file already exists}
+
+test file-11.4 {file copy -force, existing target} -setup file_setup -body {
+    file copy file_tmp/a.txt file_tmp/b.txt
+    file copy -force file_tmp/a.txt file_tmp/b.txt
+    file isfile file_tmp/b.txt
+} -cleanup file_cleanup -ok {1}
+
+test file-11.5 {file copy, into directory} -setup file_setup -body {
+    file mkdir file_tmp/x
+    file copy file_tmp/a.txt file.tcl file_tmp/x
+    list [file isfile file_tmp/x/a.txt] [file isfile file_tmp/x/file.tcl]
+} -cleanup file_cleanup -ok {1 1}
+
+test file-11.6 {file copy, multiple sources need a directory} -setup file_setup -body {
+    file copy file_tmp/a.txt file.tcl file_tmp/b.txt
+} -cleanup file_cleanup -error {error copying: target "file_tmp/b.txt" is not a directory}
+
+test file-11.7 {file copy, directory} -setup file_setup -body {
+    file mkdir file_tmp/x/y
+    file copy file_tmp/a.txt file_tmp/x/y
+    file copy file_tmp/x file_tmp/z
+    list [file isdirectory file_tmp/x] [file isfile file_tmp/z/y/a.txt]
+} -cleanup file_cleanup -ok {1 1}
+
+test file-11.8 {file copy, nonexistent source} -setup file_setup -body {
+    file copy file_tmp/nonesuch file_tmp/b.txt
+} -cleanup file_cleanup -error {error copying "file_tmp/nonesuch": no such file or directory}
+
+test file-11.9 {file copy -force, directory onto file} -setup file_setup -body {
+    file mkdir file_tmp/x
+    file copy -force file_tmp/x file_tmp/a.txt
+} -cleanup file_cleanup -error {can't overwrite file "file_tmp/a.txt" with directory "file_tmp/x"}
+
+test file-12.1 {file rename errors} {
+    file rename a
+} -error {wrong # args: should be "file rename ?-force? ?--? source ?source ...? target"}
+
+test file-12.2 {file rename, file} -setup file_setup -body {
+    file rename file_tmp/a.txt file_tmp/b.txt
+    list [file exists file_tmp/a.txt] [file isfile file_tmp/b.txt]
+} -cleanup file_cleanup -ok {0 1}
+
+test file-12.3 {file rename, existing target} -setup file_setup -body {
+    file copy file_tmp/a.txt file_tmp/b.txt
+    file rename file_tmp/a.txt file_tmp/b.txt
+} -cleanup file_cleanup -error {error renaming "file_tmp/a.txt" to "file_tmp/b.txt": file already exists}
+
+test file-12.4 {file rename -force, existing target} -setup file_setup -body {
+    file copy file_tmp/a.txt file_tmp/b.txt
+    file rename -force file_tmp/a.txt file_tmp/b.txt
+    list [file exists file_tmp/a.txt] [file isfile file_tmp/b.txt]
+} -cleanup file_cleanup -ok {0 1}
+
+test file-12.5 {file rename, into directory} -setup file_setup -body {
+    file mkdir file_tmp/x file_tmp/y
+    file rename file_tmp/a.txt file_tmp/y file_tmp/x
+    list [file isfile file_tmp/x/a.txt] [file isdirectory file_tmp/x/y] [file exists file_tmp/y]
+} -cleanup file_cleanup -ok {1 1 0}
+
+test file-12.6 {file rename, nonexistent source} -setup file_setup -body {
+    file rename file_tmp/nonesuch file_tmp/b.txt
+} -cleanup file_cleanup -error {error renaming "file_tmp/nonesuch": no such file or directory}
+
+rename file_setup ""
+rename file_cleanup ""
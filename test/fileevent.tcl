@@ -0,0 +1,197 @@
+# Test Script: fileevent
+
+# Each socket test opens a server socket on a free port on the loopback address, whose
+# command saves the connection's channel in ::fe_conn.  fe_connect opens a client
+# connection to the server and waits for it to be accepted, returning the client channel.
+proc fe_setup {} {
+    set ::fe_server [socket -server fe_accept -myaddr 127.0.0.1 0]
+    set ::fe_port [lindex [fconfigure $::fe_server -sockname] 2]
+    file delete -force fileevent_tmp
+    file mkdir fileevent_tmp
+}
+
+proc fe_accept {chan addr port} {
+    set ::fe_conn $chan
+}
+
+proc fe_connect {} {
+    set client [socket 127.0.0.1 $::fe_port]
+    vwait ::fe_conn
+    return $client
+}
+
+proc fe_cleanup {} {
+    close $::fe_server
+
+    if {[info exists ::fe_conn]} {
+        catch {close $::fe_conn}
+        unset ::fe_conn
+    }
+
+    unset ::fe_server ::fe_port
+    file delete -force fileevent_tmp
+}
+
+test fileevent-1.1 {fileevent errors} {
+    fileevent stdin
+} -error {wrong # args: should be "fileevent channelId event ?script?"}
+
+test fileevent-1.2 {fileevent errors} {
+    fileevent nonesuch readable
+} -error {can not find channel named "nonesuch"}
+
+test fileevent-1.3 {fileevent errors} {
+    fileevent stdin exception
+} -error {bad event name "exception": must be readable or writable}
+
+test fileevent-1.4 {fileevent errors} {
+    fileevent stdout readable {set x 1}
+} -error {channel is not readable}
+
+test fileevent-1.5 {fileevent errors} {
+    fileevent stdin writable {set x 1}
+} -error {channel is not writable}
+
+test fileevent-2.1 {fileevent, no handler} {
+    list [fileevent stdin readable] [fileevent stdout writable]
+} -ok {{} {}}
+
+test fileevent-2.2 {fileevent, setting and removing handlers} -setup fe_setup -body {
+    set f [open fileevent_tmp/a.txt w+]
+    fileevent $f readable {set ::fe_read 1}
+    fileevent $f writable {set ::fe_written 1}
+    set result [list [fileevent $f readable] [fileevent $f writable]]
+    fileevent $f readable {}
+    lappend result [fileevent $f readable] [fileevent $f writable]
+    close $f
+    set result
+} -cleanup fe_cleanup -ok {{set ::fe_read 1} {set ::fe_written 1} {} {set ::fe_written 1}}
+
+test fileevent-2.3 {fileevent, files are always writable} -setup fe_setup -body {
+    set f [open fileevent_tmp/a.txt w]
+    fileevent $f writable {
+        puts $::fe_file "written"
+        fileevent $::fe_file writable {}
+        set ::fe_done 1
+    }
+    set ::fe_file $f
+    vwait ::fe_done
+    close $f
+    unset ::fe_done ::fe_file
+    file size fileevent_tmp/a.txt
+} -cleanup fe_cleanup -ok {8}
+
+test fileevent-2.4 {fileevent, closing removes handlers} -setup {
+    file mkdir fileevent_tmp
+} -body {
+    set f [open fileevent_tmp/a.txt w]
+    fileevent $f writable {set ::fe_done 1}
+    close $f
+    vwait ::fe_done
+} -cleanup {
+    file delete -force fileevent_tmp
+} -error {can't wait for variable "::fe_done": would wait forever}
+
+test fileevent-2.5 {fileevent, update calls ready handlers once} -setup {
+    file mkdir fileevent_tmp
+} -body {
+    set ::fe_count 0
+    set f [open fileevent_tmp/a.txt w]
+    fileevent $f writable {incr ::fe_count}
+    update
+    update
+    close $f
+    set ::fe_count
+} -cleanup {
+    file delete -force fileevent_tmp
+    unset ::fe_count
+} -ok {2}
+
+test fileevent-3.1 {fileevent, readable socket} -setup fe_setup -body {
+    set client [fe_connect]
+    fileevent $::fe_conn readable {set ::fe_line [gets $::fe_conn]}
+    after 20 [list puts $client "hello"]
+    after 20 [list flush $client]
+    vwait ::fe_line
+    close $client
+    set ::fe_line
+} -cleanup {
+    fe_cleanup
+    unset ::fe_line
+} -ok {hello}
+
+test fileevent-3.2 {fileevent, event-driven echo} -setup fe_setup -body {
+    proc fe_echo {chan} {
+        if {[gets $chan line] >= 0} {
+            puts $chan "echo $line"
+            flush $chan
+        } elseif {[eof $chan]} {
+            close $chan
+            unset ::fe_conn
+        }
+    }
+    proc fe_reply {chan} {
+        if {[gets $chan line] >= 0} {
+            lappend ::fe_replies $line
+        }
+    }
+    set ::fe_replies {}
+    set client [fe_connect]
+    fconfigure $::fe_conn -blocking 0
+    fconfigure $client -blocking 0
+    fileevent $::fe_conn readable [list fe_echo $::fe_conn]
+    fileevent $client readable [list fe_reply $client]
+    puts $client "one"
+    puts $client "two"
+    flush $client
+    while {[llength $::fe_replies] < 2} {
+        vwait ::fe_replies
+    }
+    close $client
+    set ::fe_replies
+} -cleanup {
+    fe_cleanup
+    rename fe_echo ""
+    rename fe_reply ""
+    unset ::fe_replies
+} -ok {{echo one} {echo two}}
+
+test fileevent-3.3 {fileevent, readable at eof} -setup fe_setup -body {
+    set client [fe_connect]
+    fileevent $::fe_conn readable {
+        set ::fe_eof [list [read $::fe_conn] [eof $::fe_conn]]
+        close $::fe_conn
+        unset ::fe_conn
+    }
+    puts -nonewline $client "bye"
+    close $client
+    vwait ::fe_eof
+    list $::fe_eof [info exists ::fe_conn]
+} -cleanup {
+    fe_cleanup
+    unset ::fe_eof
+} -ok {{bye 1} 0}
+
+test fileevent-3.4 {fileevent, errors remove the handler} -setup {
+    fe_setup
+    proc bgerror {msg} {
+        set ::fe_error $msg
+    }
+} -body {
+    set client [fe_connect]
+    fileevent $::fe_conn readable {error "failed"}
+    puts $client "data"
+    flush $client
+    vwait ::fe_error
+    close $client
+    list $::fe_error [fileevent $::fe_conn readable]
+} -cleanup {
+    fe_cleanup
+    rename bgerror ""
+    unset ::fe_error
+} -ok {failed {}}
+
+rename fe_setup ""
+rename fe_accept ""
+rename fe_connect ""
+rename fe_cleanup ""
@@ -2,13 +2,25 @@
 
 test foreach-1.1 {foreach argument error} {
     foreach
-} -error {wrong # args: should be "foreach varList list body"}
+} -error {wrong # args: should be "foreach varList list ?varList list ...? body"}
 
 test foreach-1.2 {error in body} {
     foreach x {1 2 3} {
-        unset
+        unset nonesuch
     }
-} -error {wrong # args: should be "unset varName"}
+} -error {can't unset "nonesuch": no such variable}
+
+test foreach-1.3 {empty varList} {
+    foreach {} {1 2 3} {}
+} -error {foreach varlist is empty}
+
+test foreach-1.4 {foreach argument error} {
+    foreach x {1 2} y {}
+} -error {wrong # args: should be "foreach varList list ?varList list ...? body"}
+
+test foreach-1.5 {empty second varList} {
+    foreach x {1 2 3} {} {4 5} {}
+} -error {foreach varlist is empty}
 
 test foreach-2.1 {normal foreach loop} {
     foreach x {1 2 3} {
@@ -16,3 +28,65 @@ test foreach-2.1 {normal foreach loop} {
     }
     set o
 } -ok {123}
+
+test foreach-3.1 {multiple lists} {
+    set o {}
+    foreach x {a b c} y {1 2 3} {
+        lappend o $x$y
+    }
+    set o
+} -ok {a1 b2 c3}
+
+test foreach-3.2 {multiple lists, shorter lists padded} {
+    set o {}
+    foreach x {a b c} y {1} {
+        lappend o "$x,$y"
+    }
+    set o
+} -ok {a,1 b, c,}
+
+test foreach-3.3 {multiple varLists with different strides} {
+    set o {}
+    foreach {a b} {1 2 3 4 5} c {x y} {
+        lappend o [list $a $b $c]
+    }
+    set o
+} -ok {{1 2 x} {3 4 y} {5 {} {}}}
+
+test foreach-3.4 {pairs from a flat list} {
+    set o {}
+    foreach {key value} [dict create a 1 b 2] {
+        lappend o $value $key
+    }
+    set o
+} -ok {1 a 2 b}
+
+test foreach-3.5 {multiple lists, all empty} {
+    set o none
+    foreach x {} y {} {
+        set o some
+    }
+    set o
+} -ok {none}
+
+test foreach-3.6 {multiple lists, break} {
+    set o {}
+    foreach x {1 2 3} y {a b c} {
+        if {$x == 2} break
+        lappend o $x$y
+    }
+    set o
+} -ok {1a}
+
+test foreach-3.7 {multiple lists, continue} {
+    set o {}
+    foreach x {1 2 3} y {a b c} {
+        if {$x == 2} continue
+        lappend o $x$y
+    }
+    set o
+} -ok {1a 3c}
+
+test foreach-3.8 {multiple lists, returns empty} {
+    foreach x {1 2} y {3 4} {}
+} -ok {}
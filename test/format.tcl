@@ -0,0 +1,112 @@
+# Test Script: format command.
+#
+# The formatter itself is tested in detail by the unit tests in format.rs; these tests
+# check the command and the most common cases against Tcl 8.6 output.
+
+test format-1.1 {format errors} {
+    format
+} -error {wrong # args: should be "format formatString ?arg ...?"}
+
+test format-1.2 {format errors, too few arguments} {
+    format "%s %s" a
+} -error {not enough arguments for all format specifiers}
+
+test format-1.3 {format errors, bad integer} {
+    format %d abc
+} -error {expected integer but got "abc"}
+
+test format-1.4 {format errors, bad float} {
+    format %f abc
+} -error {expected floating-point number but got "abc"}
+
+test format-1.5 {format errors, bad specifier} {
+    format %y 1
+} -error {bad field specifier "y"}
+
+test format-1.6 {format errors, incomplete specifier} {
+    format "abc %-5"
+} -error {format string ended in middle of field specifier}
+
+test format-1.7 {format errors, mixed XPG} {
+    format {%1$s %s} a b
+} -error {cannot mix "%" and "%n$" conversion specifiers}
+
+test format-1.8 {format errors, XPG index} {
+    format {%2$s} a
+} -error {"%n$" argument index out of range}
+
+test format-2.1 {format, plain strings} {
+    list [format ""] [format abc] [format "100%%"] [format "%s" ""]
+} -ok {{} abc 100% {}}
+
+test format-2.2 {format, extra arguments ignored} {
+    format %s a b c
+} -ok {a}
+
+test format-3.1 {format, integers} {
+    format "%d|%5d|%-5d|%05d|%+d|% d" 42 42 42 42 42 42
+} -ok {42|   42|42   |00042|+42| 42}
+
+test format-3.2 {format, integer precision} {
+    format "%.3d|%6.3d" 7 -7
+} -ok {007|  -007}
+
+test format-3.3 {format, unsigned, octal, and hex} {
+    format "%u|%o|%#o|%x|%X|%#x|%08x" 42 8 8 255 255 255 255
+} -ok {42|10|010|ff|FF|0xff|000000ff}
+
+test format-3.4 {format, character codes} {
+    format "%c%c%c" 72 105 233
+} -ok {Hié}
+
+test format-4.1 {format, strings} {
+    format "%s|%8s|%-8s|%.2s" hello hello hello hello
+} -ok {hello|   hello|hello   |he}
+
+test format-4.2 {format, strings count characters} {
+    format "%6s|%.3s" héllo héllo
+} -ok { héllo|hél}
+
+test format-5.1 {format, fixed point} {
+    format "%f|%.2f|%8.3f|%-8.1f|%08.2f" 1.5 3.14159 3.14159 2.5 -2.5
+} -ok {1.500000|3.14|   3.142|2.5     |-0002.50}
+
+test format-5.2 {format, exponential} {
+    format "%e|%.2E|%.1e" 12345.678 0.000123 1e100
+} -ok {1.234568e+04|1.23E-04|1.0e+100}
+
+test format-5.3 {format, general} {
+    format "%g|%g|%g|%g|%.3g|%G" 100000 1000000 0.0001 0.00001 3.14159 1e-10
+} -ok {100000|1e+06|0.0001|1e-05|3.14|1E-10}
+
+test format-5.4 {format, integers as floats} {
+    format "%.1f|%g" 3 42
+} -ok {3.0|42}
+
+test format-5.5 {format, large precisions} {
+    list [string length [format %.70000f 1]] [string length [format %.70000e 1]] [format %.70000g 1] [format %.70000g 0.1] [string trimright [format %.70000f 0.5] 0]
+} -ok {70002 70006 1 0.1000000000000000055511151231257827021181583404541015625 0.5}
+
+test format-6.1 {format, star width and precision} {
+    format "%*d|%-*d|%.*f|%*.*s|" 5 42 5 42 2 3.14159 5 2 abc
+} -ok {   42|42   |3.14|   ab|}
+
+test format-6.2 {format, negative star width left-justifies} {
+    format "%*s|" -5 ab
+} -ok {ab   |}
+
+test format-7.1 {format, XPG positional specifiers} {
+    format {%2$s %1$s %2$s} a b
+} -ok {b a b}
+
+test format-7.2 {format, XPG with width} {
+    format {%1$5d|%1$-5d|} 42
+} -ok {   42|42   |}
+
+test format-8.1 {format, aligned table} {
+    set out ""
+    foreach {name qty} {apple 3 kiwi 12} {
+        append out [format "%-6s%4d\n" $name $qty]
+    }
+    set out
+} -ok "apple    3\nkiwi    12\n"
@@ -0,0 +1,121 @@
+# Test Script: glob
+
+# The tests work in the scratch directory glob_tmp, which each test creates and deletes.
+proc glob_setup {} {
+    file delete -force glob_tmp
+    file mkdir glob_tmp/sub/deep glob_tmp/other
+    foreach name {a.txt b.txt c.tcl .hidden sub/d.txt sub/deep/e.txt other/f.txt} {
+        file copy glob.tcl glob_tmp/$name
+    }
+}
+
+proc glob_cleanup {} {
+    file delete -force glob_tmp
+}
+
+test glob-1.1 {glob errors} {
+    glob
+} -error {wrong # args: should be "glob ?switches? name ?name ...?"}
+
+test glob-1.2 {glob errors} {
+    glob -nonesuch *
+} -error {bad option "-nonesuch": must be -directory, -nocomplain, -types, or --}
+
+test glob-1.3 {glob errors} {
+    glob -directory
+} -error {missing argument to "-directory"}
+
+test glob-1.4 {glob errors} {
+    glob -types q *
+} -error {bad argument to "-types": q}
+
+test glob-1.5 {glob errors} {
+    glob "glob_tmp/\{a,b"
+} -error {unmatched open-brace in file name}
+
+test glob-2.1 {glob, no match} {
+    glob nonesuch*
+} -error {no files matched glob pattern "nonesuch*"}
+
+test glob-2.2 {glob, no match} {
+    glob nonesuch* nosuch*
+} -error {no files matched glob patterns "nonesuch* nosuch*"}
+
+test glob-2.3 {glob -nocomplain} {
+    glob -nocomplain nonesuch*
+} -ok {}
+
+test glob-3.1 {glob, star} -setup glob_setup -body {
+    glob glob_tmp/*.txt
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt glob_tmp/b.txt}
+
+test glob-3.2 {glob, question mark} -setup glob_setup -body {
+    glob glob_tmp/?.t*
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt glob_tmp/b.txt glob_tmp/c.tcl}
+
+test glob-3.3 {glob, character set} -setup glob_setup -body {
+    glob glob_tmp/\[bc\].*
+} -cleanup glob_cleanup -ok {glob_tmp/b.txt glob_tmp/c.tcl}
+
+test glob-3.4 {glob, alternation} -setup glob_setup -body {
+    glob glob_tmp/{c,a}.*
+} -cleanup glob_cleanup -ok {glob_tmp/c.tcl glob_tmp/a.txt}
+
+test glob-3.5 {glob, nested alternation} -setup glob_setup -body {
+    glob glob_tmp/{a.{txt,tcl},c.{txt,tcl}}
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt glob_tmp/c.tcl}
+
+test glob-3.6 {glob, literal name} -setup glob_setup -body {
+    list [glob glob_tmp/a.txt] [glob -nocomplain glob_tmp/z.txt]
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt {}}
+
+test glob-3.7 {glob, multiple patterns} -setup glob_setup -body {
+    glob glob_tmp/*.tcl glob_tmp/a*
+} -cleanup glob_cleanup -ok {glob_tmp/c.tcl glob_tmp/a.txt}
+
+test glob-3.8 {glob, patterns in directory components} -setup glob_setup -body {
+    glob glob_tmp/*/*.txt
+} -cleanup glob_cleanup -ok {glob_tmp/other/f.txt glob_tmp/sub/d.txt}
+
+test glob-3.9 {glob, trailing slash matches directories} -setup glob_setup -body {
+    glob glob_tmp/*/
+} -cleanup glob_cleanup -ok {glob_tmp/other/ glob_tmp/sub/}
+
+test glob-4.1 {glob, hidden files} -setup glob_setup -body {
+    list [glob glob_tmp/*] [glob glob_tmp/.*]
+} -cleanup glob_cleanup -ok {{glob_tmp/a.txt glob_tmp/b.txt glob_tmp/c.tcl glob_tmp/other glob_tmp/sub} glob_tmp/.hidden}
+
+test glob-5.1 {glob -types f} -setup glob_setup -body {
+    glob -types f glob_tmp/*
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt glob_tmp/b.txt glob_tmp/c.tcl}
+
+test glob-5.2 {glob -types d} -setup glob_setup -body {
+    glob -types d glob_tmp/*
+} -cleanup glob_cleanup -ok {glob_tmp/other glob_tmp/sub}
+
+test glob-5.3 {glob -types, any type} -setup glob_setup -body {
+    glob -types {d f} glob_tmp/s* glob_tmp/a*
+} -cleanup glob_cleanup -ok {glob_tmp/sub glob_tmp/a.txt}
+
+test glob-5.4 {glob -types r} -setup glob_setup -body {
+    glob -types {f r} glob_tmp/a*
+} -cleanup glob_cleanup -ok {glob_tmp/a.txt}
+
+test glob-6.1 {glob -directory} -setup glob_setup -body {
+    glob -directory glob_tmp/sub *
+} -cleanup glob_cleanup -ok {glob_tmp/sub/d.txt glob_tmp/sub/deep}
+
+test glob-6.2 {glob -directory, nested pattern} -setup glob_setup -body {
+    glob -directory glob_tmp */*/*.txt
+} -cleanup glob_cleanup -ok {glob_tmp/sub/deep/e.txt}
+
+test glob-6.3 {glob --} -setup glob_setup -body {
+    glob -nocomplain -- -*
+} -cleanup glob_cleanup -ok {}
+
+test glob-7.1 {glob, result is a list} -setup glob_setup -body {
+    llength [glob glob_tmp/*.txt]
+} -cleanup glob_cleanup -ok {2}
+
+rename glob_setup ""
+rename glob_cleanup ""
@@ -0,0 +1,152 @@
+# Test Script: history
+
+test history-1.1 {history errors} {
+    history nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be add, clear, event, info, keep, nextid, or redo}
+
+test history-1.2 {history add, errors} {
+    history add
+} -error {wrong # args: should be "history add command ?exec?"}
+
+test history-1.3 {history add, bad argument} {
+    history add {set a 1} nonesuch
+} -error {bad argument "nonesuch": should be "exec"}
+
+test history-1.4 {history keep, bad count} {
+    history keep -1
+} -error {illegal keep count "-1"}
+
+test history-2.1 {history add, nextid} -setup {
+    history clear
+} -body {
+    set a [history nextid]
+    history add {set x 1}
+    history add {set y 2}
+    list $a [history nextid]
+} -cleanup {
+    history clear
+} -ok {1 3}
+
+test history-2.2 {history add exec} -setup {
+    history clear
+} -body {
+    list [history add {set ::histVar 5} exec] $::histVar [history event 1]
+} -cleanup {
+    history clear
+    unset ::histVar
+} -ok {5 5 {set ::histVar 5}}
+
+test history-3.1 {history event} -setup {
+    history clear
+    history add {set x 1}
+    history add {set y 2}
+    history add {history event}
+} -body {
+    list [history event 1] [history event] [history event -2] [history event 0]
+} -cleanup {
+    history clear
+} -ok {{set x 1} {set y 2} {set x 1} {history event}}
+
+test history-3.2 {history event, by prefix or pattern} -setup {
+    history clear
+    history add {set x 1}
+    history add {puts hello}
+    history add {set y 2}
+} -body {
+    list [history event set] [history event pu] [history event {*x*}]
+} -cleanup {
+    history clear
+} -ok {{set y 2} {puts hello} {set x 1}}
+
+test history-3.3 {history event, errors} -setup {
+    history clear
+    history add {set x 1}
+} -body {
+    list [catch {history event 5} msg1] $msg1 [catch {history event nope} msg2] $msg2
+} -cleanup {
+    history clear
+} -ok {1 {event "5" hasn't occurred yet} 1 {no event matches "nope"}}
+
+test history-4.1 {history info} -setup {
+    history clear
+    history add {set x 1}
+    history add "set y \{\n2\n\}"
+} -body {
+    history info
+} -cleanup {
+    history clear
+} -ok "     1  set x 1\n     2  set y \{\n\t2\n\t\}"
+
+test history-4.2 {history info, count} -setup {
+    history clear
+    history add {set x 1}
+    history add {set y 2}
+    history add {set z 3}
+} -body {
+    list [history info 2] [history]
+} -cleanup {
+    history clear
+} -ok [list "     2  set y 2\n     3  set z 3" "     1  set x 1\n     2  set y 2\n     3  set z 3"]
+
+test history-5.1 {history keep} -setup {
+    history clear
+} -body {
+    set old [history keep]
+    history keep 2
+    history add {set x 1}
+    history add {set y 2}
+    history add {set z 3}
+    list $old [history keep] [history info] [catch {history event 1} msg] $msg
+} -cleanup {
+    history keep 20
+    history clear
+} -ok [list 20 2 "     2  set y 2\n     3  set z 3" 1 {event "1" is too far in the past}]
+
+test history-5.2 {history keep, trims existing events} -setup {
+    history clear
+    history add {set x 1}
+    history add {set y 2}
+    history add {set z 3}
+} -body {
+    history keep 1
+    history info
+} -cleanup {
+    history keep 20
+    history clear
+} -ok {     3  set z 3}
+
+test history-6.1 {history redo} -setup {
+    history clear
+    history add {incr ::histCount}
+    history add {history redo}
+    set ::histCount 10
+} -body {
+    list [history redo] [history redo 1] $::histCount
+} -cleanup {
+    history clear
+    unset ::histCount
+} -ok {11 12 12}
+
+test history-6.2 {history redo, global scope} -setup {
+    history clear
+    history add {set histLocal 1}
+    proc redoIt {} {
+        history redo 1
+        info exists histLocal
+    }
+} -body {
+    list [redoIt] [info exists ::histLocal]
+} -cleanup {
+    history clear
+    rename redoIt ""
+    unset ::histLocal
+} -ok {0 1}
+
+test history-6.3 {history redo, error} -setup {
+    history clear
+    history add {error boom}
+} -body {
+    history redo 1
+} -cleanup {
+    history clear
+} -error {boom}
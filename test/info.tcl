@@ -0,0 +1,411 @@
+# Test Script: info command.
+
+test info-1.1 {info errors} {
+    info
+} -error {wrong # args: should be "info subcommand ?arg ...?"}
+
+# TODO: really need glob matching or something; as it is, this won't
+# pass with tclsh.  Or, I need a way to limit tests to the right
+# context, as with tcltest.
+test info-1.2 {info errors} {
+    info nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be args, body, cmdcount, commands, complete, coroutine, default, exists, globals, level, locals, nameofexecutable, patchlevel, procs, script, tclversion, or vars}
+
+test info-2.1 {info complete errors} {
+    info complete
+} -error {wrong # args: should be "info complete command"}
+
+test info-2.2 {info complete errors} {
+    info complete foo bar
+} -error {wrong # args: should be "info complete command"}
+
+test info-2.3 {info complete command} {
+    info complete cmd
+} -ok {1}
+
+test info-2.4 {info complete command} {
+    info complete "\{cmd"
+} -ok {0}
+
+test info-3.1 {info vars command} -setup {
+    proc myproc {} {
+        info vars
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {}
+
+test info-3.2 {info vars command} -setup {
+    proc myproc {a} {
+        info vars
+    }
+} -body {
+    myproc a
+} -cleanup {
+    rename myproc ""
+} -ok {a}
+
+test info-3.2 {info vars command} -setup {
+    proc myproc {} {
+        set v 1
+        info vars
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {v}
+
+test info-3.3 {info vars command} -setup {
+    proc myproc {} {
+        global x
+        info vars
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {x}
+
+test info-4.1 {info exists errors} {
+    info exists
+} -error {wrong # args: should be "info exists varName"}
+
+test info-4.2 {info exists errors} {
+    info exists a b
+} -error {wrong # args: should be "info exists varName"}
+
+test info-4.3 {info exists, scalars} {
+    set a 1
+    list [info exists a] [info exists nonesuch]
+} -ok {1 0}
+
+test info-4.4 {info exists, arrays} {
+    set arr(x) 1
+    set s 1
+    list [info exists arr] [info exists arr(x)] [info exists arr(y)] [info exists s(x)] [info exists no(x)]
+} -ok {1 1 0 0 0}
+
+test info-4.5 {info exists, unset variables} {
+    set a 1
+    set arr(x) 1
+    unset a arr(x)
+    list [info exists a] [info exists arr(x)] [info exists arr]
+} -ok {0 0 1}
+
+test info-4.6 {info exists, globals} -setup {
+    proc myproc {} {
+        set before [info exists info_g]
+        global info_g
+        list $before [info exists info_g]
+    }
+} -body {
+    global info_g
+    set info_g 1
+    myproc
+} -cleanup {
+    rename myproc ""
+    global info_g
+    unset info_g
+} -ok {0 1}
+
+test info-4.7 {info exists, linked but undefined global} -setup {
+    proc myproc {} {
+        global info_nonesuch
+        info exists info_nonesuch
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {0}
+
+test info-4.8 {info exists, unset earlier in the same proc} -setup {
+    proc myproc {} {
+        set a 1
+        set before [info exists a]
+        unset a
+        list $before [info exists a]
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {1 0}
+
+test info-5.1 {info commands errors} {
+    info commands a b
+} -error {wrong # args: should be "info commands ?pattern?"}
+
+test info-5.2 {info commands} {
+    set names [info commands]
+    list [expr {[lsearch $names set] >= 0}] [expr {[lsearch $names info] >= 0}]
+} -ok {1 1}
+
+test info-5.3 {info commands, pattern} {
+    lsort [info commands lr*]
+} -ok {lrange lrepeat lreplace lreverse}
+
+test info-5.4 {info commands, includes procs} -setup {
+    proc info_proc {} {}
+} -body {
+    info commands info_pr*
+} -cleanup {
+    rename info_proc ""
+} -ok {info_proc}
+
+test info-5.5 {info commands, no match} {
+    info commands nonesuch*
+} -ok {}
+
+test info-6.1 {info procs errors} {
+    info procs a b
+} -error {wrong # args: should be "info procs ?pattern?"}
+
+test info-6.2 {info procs} -setup {
+    proc info_proc1 {} {}
+    proc info_proc2 {} {}
+} -body {
+    list [lsort [info procs info_proc*]] [info procs set] [info procs info_proc1]
+} -cleanup {
+    rename info_proc1 ""
+    rename info_proc2 ""
+} -ok {{info_proc1 info_proc2} {} info_proc1}
+
+test info-6.3 {info procs, renamed and removed procs} -setup {
+    proc info_proc1 {} {}
+} -body {
+    rename info_proc1 info_proc2
+    set a [info procs info_proc*]
+    rename info_proc2 ""
+    list $a [info procs info_proc*]
+} -ok {info_proc2 {}}
+
+test info-7.1 {info vars errors} {
+    info vars a b
+} -error {wrong # args: should be "info vars ?pattern?"}
+
+test info-7.2 {info vars, pattern} -setup {
+    proc myproc {} {
+        set apple 1
+        set avocado 2
+        set banana 3
+        lsort [info vars a*]
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -ok {apple avocado}
+
+test info-7.3 {info vars, includes linked globals, even unset ones} -setup {
+    proc myproc {} {
+        global info_g info_nonesuch
+        set local 1
+        lsort [info vars]
+    }
+} -body {
+    global info_g
+    set info_g 1
+    myproc
+} -cleanup {
+    rename myproc ""
+    global info_g
+    unset info_g
+} -ok {info_g info_nonesuch local}
+
+test info-8.1 {info locals errors} {
+    info locals a b
+} -error {wrong # args: should be "info locals ?pattern?"}
+
+test info-8.2 {info locals} -setup {
+    proc myproc {arg} {
+        global info_g
+        set local 1
+        list [lsort [info locals]] [info locals l*]
+    }
+} -body {
+    global info_g
+    set info_g 1
+    myproc x
+} -cleanup {
+    rename myproc ""
+    global info_g
+    unset info_g
+} -ok {{arg local} local}
+
+test info-9.1 {info globals errors} {
+    info globals a b
+} -error {wrong # args: should be "info globals ?pattern?"}
+
+test info-9.2 {info globals} -setup {
+    proc myproc {} {
+        set info_local 1
+        info globals info_*
+    }
+} -body {
+    global info_g
+    set info_g 1
+    myproc
+} -cleanup {
+    rename myproc ""
+    global info_g
+    unset info_g
+} -ok {info_g}
+
+test info-10.1 {info level errors} {
+    info level 1 2
+} -error {wrong # args: should be "info level ?number?"}
+
+test info-10.2 {info level errors, bad level} {
+    info level [expr {[info level] + 1}]
+} -error {bad level "2"}
+
+test info-10.3 {info level errors, bad relative level} -setup {
+    proc myproc {} { info level [expr {-[info level]}] }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+} -error {bad level "-2"}
+
+test info-10.4 {info level errors, not an integer} {
+    info level x
+} -error {expected integer but got "x"}
+
+# Note: the test harness evaluates each test body in its own scope, one level above
+# the global scope.
+test info-10.5 {info level, test body} {
+    info level
+} -ok {1}
+
+test info-10.6 {info level, nested procs} -setup {
+    proc outer {a b} { inner $a }
+    proc inner {x} {
+        set base [expr {[info level] - 2}]
+        list [expr {[info level] - $base}] [info level 0] [info level -1] [info level [expr {$base + 1}]] [info level [expr {$base + 2}]]
+    }
+} -body {
+    outer 1 {2 3}
+} -cleanup {
+    rename outer ""
+    rename inner ""
+} -ok {2 {inner 1} {outer 1 {2 3}} {outer 1 {2 3}} {inner 1}}
+
+test info-10.7 {info level, after a proc returns an error} -setup {
+    proc myproc {a} { error oops }
+} -body {
+    set before [info level]
+    catch {myproc}
+    catch {myproc 1}
+    expr {[info level] == $before}
+} -cleanup {
+    rename myproc ""
+} -ok {1}
+
+test info-11.1 {info args errors} {
+    info args
+} -error {wrong # args: should be "info args procname"}
+
+test info-11.2 {info args errors, not a proc} {
+    info args set
+} -error {"set" isn't a procedure}
+
+test info-11.3 {info args} -setup {
+    proc myproc {a {b 2} args} {}
+    proc noargs {} {}
+} -body {
+    list [info args myproc] [info args noargs]
+} -cleanup {
+    rename myproc ""
+    rename noargs ""
+} -ok {{a b args} {}}
+
+test info-12.1 {info body errors} {
+    info body
+} -error {wrong # args: should be "info body procname"}
+
+test info-12.2 {info body errors, not a proc} {
+    info body nonesuch
+} -error {"nonesuch" isn't a procedure}
+
+test info-12.3 {info body} -setup {
+    proc myproc {a} {return [expr {$a + 1}]}
+} -body {
+    info body myproc
+} -cleanup {
+    rename myproc ""
+} -ok {return [expr {$a + 1}]}
+
+test info-13.1 {info default errors} {
+    info default a b
+} -error {wrong # args: should be "info default procname arg varname"}
+
+test info-13.2 {info default errors, not a proc} {
+    info default set a v
+} -error {"set" isn't a procedure}
+
+test info-13.3 {info default errors, no such argument} -setup {
+    proc myproc {a} {}
+} -body {
+    info default myproc b v
+} -cleanup {
+    rename myproc ""
+} -error {procedure "myproc" doesn't have an argument "b"}
+
+test info-13.4 {info default} -setup {
+    proc myproc {a {b "two words"}} {}
+} -body {
+    set v unchanged
+    set r1 [info default myproc a v]
+    set v1 $v
+    set r2 [info default myproc b v]
+    list $r1 $v1 $r2 $v
+} -cleanup {
+    rename myproc ""
+} -ok {0 {} 1 {two words}}
+
+test info-14.1 {info script errors} {
+    info script a b
+} -error {wrong # args: should be "info script ?filename?"}
+
+test info-14.2 {info script, set and restore} -body {
+    set old [info script]
+    set new [info script other.tcl]
+    set mid [info script]
+    info script $old
+    list $new $mid [expr {[info script] eq $old}]
+} -ok {other.tcl other.tcl 1}
+
+test info-14.3 {info script, sourced file} {
+    regexp {info\.tcl$} [info script]
+} -ok {1}
+
+test info-14.4 {info script, nested source} -body {
+    set inner [source info_script.tcl]
+    list $inner [regexp {info\.tcl$} [info script]]
+} -ok {info_script.tcl 1}
+
+test info-15.1 {info nameofexecutable} {
+    expr {[info nameofexecutable] ne ""}
+} -ok {1}
+
+test info-16.1 {info patchlevel} {
+    info patchlevel
+} -ok {0.1.0}
+
+test info-17.1 {info tclversion} {
+    info tclversion
+} -ok {0.1}
+
+test info-18.1 {info cmdcount errors} {
+    info cmdcount a
+} -error {wrong # args: should be "info cmdcount"}
+
+test info-18.2 {info cmdcount} {
+    set before [info cmdcount]
+    set a [expr {1 + 1}]
+    expr {[info cmdcount] - $before}
+} -ok {5}
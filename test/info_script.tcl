@@ -0,0 +1,2 @@
+# Helper script for the "info script" tests in info.tcl; returns its own name.
+info script
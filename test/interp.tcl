@@ -1,4 +1,4 @@
-# Test Script: Molt Interpreter
+# Test Script: Molt Interpreter, interp
 
 test interp-1.1 {stack level checking} -setup {
     proc bad_recursion {} { bad_recursion }
@@ -7,3 +7,609 @@ test interp-1.1 {stack level checking} -setup {
 } -cleanup {
     rename bad_recursion {}
 } -error {too many nested calls to Interp::eval (infinite loop?)}
+
+test interp-2.1 {interp errors} {
+    interp
+} -error {wrong # args: should be "interp subcommand ?arg ...?"}
+
+test interp-2.2 {interp, bad subcommand} {
+    catch {interp nonesuch} result
+} -ok {1}
+
+test interp-3.1 {interp alias errors} {
+    interp alias {}
+} -error {wrong # args: should be "interp alias slavePath slaveCmd ?masterPath masterCmd? ?arg ...?"}
+
+test interp-3.2 {interp alias, unknown interpreter} {
+    interp alias nonesuch myalias {} list
+} -error {could not find interpreter "nonesuch"}
+
+test interp-3.3 {interp alias, unknown target interpreter} {
+    interp alias {} myalias nonesuch list
+} -error {could not find interpreter "nonesuch"}
+
+test interp-3.4 {interp alias, not an alias} {
+    interp alias {} list
+} -error {alias "list" not found}
+
+test interp-3.5 {interp alias, missing target command} {
+    interp alias {} myalias x
+} -error {wrong # args: should be "interp alias slavePath slaveCmd ?masterPath masterCmd? ?arg ...?"}
+
+test interp-4.1 {interp alias, result} -body {
+    interp alias {} myalias {} list a
+} -cleanup {
+    rename myalias ""
+} -ok {myalias}
+
+test interp-4.2 {interp alias, curried arguments} -setup {
+    interp alias {} myalias {} list a b
+} -body {
+    list [myalias] [myalias c d]
+} -cleanup {
+    rename myalias ""
+} -ok {{a b} {a b c d}}
+
+test interp-4.3 {interp alias, target} -setup {
+    interp alias {} myalias {} list a {b c}
+} -body {
+    interp alias {} myalias
+} -cleanup {
+    rename myalias ""
+} -ok {list a {b c}}
+
+test interp-4.4 {interp alias, delete} -setup {
+    interp alias {} myalias {} list a
+} -body {
+    list [interp alias {} myalias {}] [info commands myalias]
+} -ok {{} {}}
+
+test interp-4.5 {interp alias, target looked up when called} -setup {
+    proc mytarget {args} { return "one $args" }
+    interp alias {} myalias {} mytarget x
+} -body {
+    set a [myalias y]
+    proc mytarget {args} { return "two $args" }
+    list $a [myalias y]
+} -cleanup {
+    rename myalias ""
+    rename mytarget ""
+} -ok {{one x y} {two x y}}
+
+test interp-4.6 {interp alias, missing target} -setup {
+    interp alias {} myalias {} nonesuch
+} -body {
+    myalias
+} -cleanup {
+    rename myalias ""
+} -error {invalid command name "nonesuch"}
+
+test interp-4.7 {interp alias, errors pass through} -setup {
+    interp alias {} myalias {} error
+} -body {
+    myalias oops
+} -cleanup {
+    rename myalias ""
+} -error {oops}
+
+test interp-4.8 {interp alias, loop} -setup {
+    interp alias {} myalias {} myalias
+} -body {
+    myalias
+} -cleanup {
+    rename myalias ""
+} -error {too many nested calls to Interp::eval (infinite loop?)}
+
+test interp-4.9 {interp alias, renamed} -setup {
+    interp alias {} myalias {} list a
+} -body {
+    rename myalias youralias
+    list [youralias b] [interp alias {} youralias]
+} -cleanup {
+    rename youralias ""
+} -ok {{a b} {list a}}
+
+test interp-4.10 {interp alias, in a namespace} -setup {
+    namespace eval ::myns {}
+    interp alias {} ::myns::myalias {} list a
+} -body {
+    namespace eval ::myns { myalias b }
+} -cleanup {
+    namespace delete ::myns
+} -ok {a b}
+
+test interp-4.11 {interp alias, replaces a command} -setup {
+    proc myproc {} { return proc }
+    interp alias {} myproc {} list alias
+} -body {
+    list [myproc] [info procs myproc]
+} -cleanup {
+    rename myproc ""
+} -ok {alias {}}
+
+test interp-5.1 {interp aliases errors} {
+    interp aliases a b
+} -error {wrong # args: should be "interp aliases ?path?"}
+
+test interp-5.2 {interp aliases} -setup {
+    interp alias {} myalias1 {} list
+    interp alias {} myalias2 {} list
+} -body {
+    lsort [interp aliases {}]
+} -cleanup {
+    rename myalias1 ""
+    rename myalias2 ""
+} -ok {myalias1 myalias2}
+
+test interp-6.1 {interp create errors} {
+    interp create a b
+} -error {wrong # args: should be "interp create ?-safe? ?--? ?path?"}
+
+test interp-6.2 {interp create, bad option} {
+    interp create -nonesuch
+} -error {bad option "-nonesuch": must be -safe or --}
+
+test interp-6.3 {interp create, existing} -setup {
+    interp create myslave
+} -body {
+    interp create myslave
+} -cleanup {
+    interp delete myslave
+} -error {interpreter named "myslave" already exists, cannot create}
+
+test interp-6.4 {interp create, current interpreter} {
+    interp create {}
+} -error {interpreter named "" already exists, cannot create}
+
+test interp-6.5 {interp create, missing parent} {
+    interp create {nonesuch myslave}
+} -error {could not find interpreter "nonesuch myslave"}
+
+test interp-6.6 {interp create, name} -body {
+    interp create myslave
+} -cleanup {
+    interp delete myslave
+} -ok {myslave}
+
+test interp-6.7 {interp create, generated name} -body {
+    set a [interp create]
+    set b [interp create]
+    list [regexp {^interp[0-9]+$} $a] [expr {$a ne $b}]
+} -cleanup {
+    interp delete $a $b
+} -ok {1 1}
+
+test interp-6.8 {interp create, --} -body {
+    interp create -- -myslave
+} -cleanup {
+    interp delete -myslave
+} -ok {-myslave}
+
+test interp-6.9 {interp create, nested} -setup {
+    interp create myslave
+} -body {
+    interp create {myslave child}
+    list [interp slaves myslave] [interp eval myslave {interp slaves}]
+} -cleanup {
+    interp delete myslave
+} -ok {child child}
+
+test interp-7.1 {interp eval errors} {
+    interp eval {}
+} -error {wrong # args: should be "interp eval path arg ?arg ...?"}
+
+test interp-7.2 {interp eval, unknown interpreter} {
+    interp eval nonesuch {set a 1}
+} -error {could not find interpreter "nonesuch"}
+
+test interp-7.3 {interp eval, result} -setup {
+    interp create myslave
+} -body {
+    interp eval myslave {expr {1 + 2}}
+} -cleanup {
+    interp delete myslave
+} -ok {3}
+
+test interp-7.4 {interp eval, concatenated arguments} -setup {
+    interp create myslave
+} -body {
+    interp eval myslave set a {{b c}}
+} -cleanup {
+    interp delete myslave
+} -ok {b c}
+
+test interp-7.5 {interp eval, separate variables and commands} -setup {
+    interp create myslave
+    set a master
+    proc myproc {} { return master }
+} -body {
+    interp eval myslave {
+        set a slave
+        proc myproc {} { return slave }
+    }
+    list $a [myproc] [interp eval myslave {list $a [myproc]}]
+} -cleanup {
+    interp delete myslave
+    rename myproc ""
+} -ok {master master {slave slave}}
+
+test interp-7.6 {interp eval, global scope} -setup {
+    interp create myslave
+    interp eval myslave {
+        set a global
+        proc myproc {} {
+            set a local
+            interp eval {} {set a}
+        }
+    }
+} -body {
+    interp eval myslave myproc
+} -cleanup {
+    interp delete myslave
+} -ok {global}
+
+test interp-7.7 {interp eval, error} -setup {
+    interp create myslave
+} -body {
+    interp eval myslave {error oops}
+} -cleanup {
+    interp delete myslave
+} -error {oops}
+
+test interp-7.8 {interp eval, error code and info} -setup {
+    interp create myslave
+} -body {
+    catch {interp eval myslave {throw {MY CODE} oops}} result opts
+    set info [dict get $opts -errorinfo]
+    list $result [dict get $opts -errorcode] [string match "*throw {MY CODE} oops*" $info]
+} -cleanup {
+    interp delete myslave
+} -ok {oops {MY CODE} 1}
+
+test interp-7.9 {interp eval, return} -setup {
+    interp create myslave
+} -body {
+    interp eval myslave {return early; set a late}
+} -cleanup {
+    interp delete myslave
+} -ok {early}
+
+test interp-7.10 {interp eval, break} -setup {
+    interp create myslave
+} -body {
+    interp eval myslave break
+} -cleanup {
+    interp delete myslave
+} -error {invoked "break" outside of a loop}
+
+test interp-7.11 {interp eval, nested path} -setup {
+    interp create myslave
+    interp create {myslave child}
+} -body {
+    interp eval {myslave child} {set a 1}
+    list [interp eval {myslave child} {set a}] [interp eval myslave {info exists a}]
+} -cleanup {
+    interp delete myslave
+} -ok {1 0}
+
+test interp-7.12 {interp eval, aliases within a slave} -setup {
+    interp create myslave
+    interp alias myslave myalias myslave list a
+} -body {
+    set target [interp alias myslave myalias]
+    list [interp eval myslave {myalias b}] $target [interp aliases myslave]
+} -cleanup {
+    interp delete myslave
+} -ok {{a b} {list a} myalias}
+
+test interp-7.13 {interp alias, cross-interpreter} -setup {
+    interp create myslave
+} -body {
+    interp alias myslave myalias {} list
+} -cleanup {
+    interp delete myslave
+} -error {cross-interpreter aliases are not supported}
+
+test interp-8.1 {interp delete, current interpreter} {
+    interp delete {}
+} -error {cannot delete the current interpreter}
+
+test interp-8.2 {interp delete, unknown interpreter} {
+    interp delete nonesuch
+} -error {could not find interpreter "nonesuch"}
+
+test interp-8.3 {interp delete} -setup {
+    interp create myslave
+    interp create yourslave
+} -body {
+    interp delete myslave yourslave
+    list [interp exists myslave] [interp exists yourslave]
+} -ok {0 0}
+
+test interp-8.4 {interp delete, drops state} -setup {
+    interp create myslave
+    interp eval myslave {set a 1}
+} -body {
+    interp delete myslave
+    interp create myslave
+    interp eval myslave {info exists a}
+} -cleanup {
+    interp delete myslave
+} -ok {0}
+
+test interp-8.5 {interp delete, nested} -setup {
+    interp create myslave
+    interp create {myslave child}
+} -body {
+    interp delete {myslave child}
+    list [interp exists myslave] [interp exists {myslave child}]
+} -cleanup {
+    interp delete myslave
+} -ok {1 0}
+
+test interp-9.1 {interp exists errors} {
+    interp exists a b
+} -error {wrong # args: should be "interp exists ?path?"}
+
+test interp-9.2 {interp exists} -setup {
+    interp create myslave
+    interp create {myslave child}
+} -body {
+    set a [list [interp exists] [interp exists {}] [interp exists myslave]]
+    lappend a [interp exists {myslave child}] [interp exists nonesuch]
+} -cleanup {
+    interp delete myslave
+} -ok {1 1 1 1 0}
+
+test interp-10.1 {interp slaves errors} {
+    interp slaves a b
+} -error {wrong # args: should be "interp slaves ?path?"}
+
+test interp-10.2 {interp slaves} -setup {
+    interp create yourslave
+    interp create myslave
+} -body {
+    list [interp slaves] [interp slaves myslave]
+} -cleanup {
+    interp delete myslave yourslave
+} -ok {{myslave yourslave} {}}
+
+test interp-11.1 {interp create -safe} -body {
+    interp create -safe mysafe
+    list [interp issafe mysafe] [interp issafe]
+} -cleanup {
+    interp delete mysafe
+} -ok {1 0}
+
+test interp-11.2 {interp create -safe, generated name} -body {
+    set a [interp create -safe]
+    list [regexp {^interp[0-9]+$} $a] [interp issafe $a]
+} -cleanup {
+    interp delete $a
+} -ok {1 1}
+
+test interp-11.3 {safe interpreter, unsafe commands are hidden} -setup {
+    interp create -safe mysafe
+} -body {
+    set a [interp hidden mysafe]
+    list $a [interp eval mysafe {info commands open}] [interp eval mysafe {info exists env}]
+} -cleanup {
+    interp delete mysafe
+} -ok {{cd exec exit file glob open pwd socket source} {} 0}
+
+test interp-11.4 {safe interpreter, can't open files} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {open interp.tcl}
+} -cleanup {
+    interp delete mysafe
+} -error {invalid command name "open"}
+
+test interp-11.5 {safe interpreter, file manipulates path names only} -setup {
+    interp create -safe mysafe
+} -body {
+    set a [interp eval mysafe {file join a b}]
+    list $a [catch {interp eval mysafe {file exists interp.tcl}}]
+} -cleanup {
+    interp delete mysafe
+} -ok {a/b 1}
+
+test interp-11.6 {safe interpreter, slaves are safe} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {
+        interp create child
+        interp issafe child
+    }
+} -cleanup {
+    interp delete mysafe
+} -ok {1}
+
+test interp-11.7 {safe interpreter, standard commands} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {
+        proc double {x} { expr {$x * 2} }
+        lmap x {1 2 3} { double $x }
+    }
+} -cleanup {
+    interp delete mysafe
+} -ok {2 4 6}
+
+test interp-12.1 {interp hide errors} {
+    interp hide {}
+} -error {wrong # args: should be "interp hide path cmdName ?hiddenCmdName?"}
+
+test interp-12.2 {interp hide, unknown command} -setup {
+    interp create myslave
+} -body {
+    interp hide myslave nonesuch
+} -cleanup {
+    interp delete myslave
+} -error {unknown command "nonesuch"}
+
+test interp-12.3 {interp hide} -setup {
+    interp create myslave
+} -body {
+    interp hide myslave pwd
+    list [interp hidden myslave] [catch {interp eval myslave pwd}]
+} -cleanup {
+    interp delete myslave
+} -ok {pwd 1}
+
+test interp-12.4 {interp hide, hidden name} -setup {
+    interp create myslave
+} -body {
+    interp hide myslave pwd mypwd
+    interp hidden myslave
+} -cleanup {
+    interp delete myslave
+} -ok {mypwd}
+
+test interp-12.5 {interp hide, existing hidden name} -setup {
+    interp create -safe mysafe
+} -body {
+    interp hide mysafe list open
+} -cleanup {
+    interp delete mysafe
+} -error {hidden command named "open" already exists}
+
+test interp-12.6 {interp hide, qualified hidden name} -setup {
+    interp create myslave
+} -body {
+    interp hide myslave pwd ::a::pwd
+} -cleanup {
+    interp delete myslave
+} -error {cannot use namespace qualifiers in hidden command token (rename)}
+
+test interp-12.7 {interp hide, from a safe interpreter} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {interp hide {} list}
+} -cleanup {
+    interp delete mysafe
+} -error {permission denied: safe interpreter cannot hide commands}
+
+test interp-13.1 {interp expose errors} {
+    interp expose {}
+} -error {wrong # args: should be "interp expose path hiddenCmdName ?cmdName?"}
+
+test interp-13.2 {interp expose, unknown hidden command} -setup {
+    interp create -safe mysafe
+} -body {
+    interp expose mysafe nonesuch
+} -cleanup {
+    interp delete mysafe
+} -error {unknown hidden command "nonesuch"}
+
+test interp-13.3 {interp expose} -setup {
+    interp create -safe mysafe
+} -body {
+    interp expose mysafe pwd
+    set a [expr {[interp eval mysafe pwd] eq [pwd]}]
+    list $a [lsearch [interp hidden mysafe] pwd]
+} -cleanup {
+    interp delete mysafe
+} -ok {1 -1}
+
+test interp-13.4 {interp expose, exposed name} -setup {
+    interp create -safe mysafe
+} -body {
+    interp expose mysafe pwd mypwd
+    expr {[interp eval mysafe mypwd] eq [pwd]}
+} -cleanup {
+    interp delete mysafe
+} -ok {1}
+
+test interp-13.5 {interp expose, existing command} -setup {
+    interp create -safe mysafe
+} -body {
+    interp expose mysafe file
+} -cleanup {
+    interp delete mysafe
+} -error {exposed command "file" already exists}
+
+test interp-13.6 {interp expose, replacing the safe file command} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {rename file ""}
+    interp expose mysafe file
+    interp eval mysafe {file exists interp.tcl}
+} -cleanup {
+    interp delete mysafe
+} -ok {1}
+
+test interp-13.7 {interp expose, qualified name} -setup {
+    interp create -safe mysafe
+} -body {
+    interp expose mysafe pwd ::a::pwd
+} -cleanup {
+    interp delete mysafe
+} -error {cannot expose to a namespace (use expose to toplevel, then rename)}
+
+test interp-13.8 {interp expose, from a safe interpreter} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {interp expose {} open}
+} -cleanup {
+    interp delete mysafe
+} -error {permission denied: safe interpreter cannot expose commands}
+
+test interp-14.1 {interp hidden errors} {
+    interp hidden a b
+} -error {wrong # args: should be "interp hidden ?path?"}
+
+test interp-14.2 {interp hidden, none} -setup {
+    interp create myslave
+} -body {
+    list [interp hidden] [interp hidden myslave]
+} -cleanup {
+    interp delete myslave
+} -ok {{} {}}
+
+test interp-15.1 {interp invokehidden errors} {
+    interp invokehidden {}
+} -error {wrong # args: should be "interp invokehidden path ?--? cmd ?arg ...?"}
+
+test interp-15.2 {interp invokehidden, unknown hidden command} -setup {
+    interp create -safe mysafe
+} -body {
+    interp invokehidden mysafe list a
+} -cleanup {
+    interp delete mysafe
+} -error {invalid hidden command name "list"}
+
+test interp-15.3 {interp invokehidden} -setup {
+    interp create -safe mysafe
+} -body {
+    set a [interp invokehidden mysafe file exists interp.tcl]
+    list $a [interp invokehidden mysafe -- file isdirectory interp.tcl]
+} -cleanup {
+    interp delete mysafe
+} -ok {1 0}
+
+test interp-15.4 {interp invokehidden, hidden proc} -setup {
+    interp create myslave
+    interp eval myslave {proc myproc {a} { return "<$a>" }}
+    interp hide myslave myproc
+} -body {
+    interp invokehidden myslave myproc x
+} -cleanup {
+    interp delete myslave
+} -ok {<x>}
+
+test interp-15.5 {interp invokehidden, from a safe interpreter} -setup {
+    interp create -safe mysafe
+} -body {
+    interp eval mysafe {interp invokehidden {} pwd}
+} -cleanup {
+    interp delete mysafe
+} -error {not allowed to invoke hidden commands from safe interpreter}
+
+test interp-16.1 {interp issafe errors} {
+    interp issafe a b
+} -error {wrong # args: should be "interp issafe ?path?"}
+
+test interp-16.2 {interp issafe, unknown interpreter} {
+    interp issafe nonesuch
+} -error {could not find interpreter "nonesuch"}
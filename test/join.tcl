@@ -0,0 +1,37 @@
+# Test Script: join command.
+
+test join-1.1 {join errors} {
+    join
+} -error {wrong # args: should be "join list ?joinString?"}
+
+test join-1.2 {join errors} {
+    join {a b} , x
+} -error {wrong # args: should be "join list ?joinString?"}
+
+test join-1.3 {join errors, bad list} {
+    join "a \{b"
+} -error {unmatched open brace in list}
+
+test join-2.1 {join, default join string} {
+    join {a b c}
+} -ok {a b c}
+
+test join-2.2 {join, join string} {
+    list [join {a b c} ,] [join {a b c} ", "] [join {a b c} {}]
+} -ok {a,b,c {a, b, c} abc}
+
+test join-2.3 {join, empty list} {
+    list [join {}] [join {} ,]
+} -ok {{} {}}
+
+test join-2.4 {join, one element is returned verbatim} {
+    join {{a b}} ,
+} -ok {a b}
+
+test join-2.5 {join, no list quoting} {
+    join [list "a \{" "\$b" {c d}] |
+} -ok "a \{|\$b|c d"
+
+test join-2.6 {join, empty elements} {
+    join {a {} b {}} ,
+} -ok {a,,b,}
@@ -98,17 +98,14 @@ test lappend-1.18 {lappend command} {
     lappend x {}
 } -ok {{}}
 
-if 0 {
-    # Need arrays
-    test lappend-1.19 {lappend command} {
-        lappend x(0)
-    } -ok {}
-
-    test lappend-1.20 {lappend command} {
-        unset -nocomplain x
-        lappend x(0) abc
-    } -ok {abc}
-}
+test lappend-1.19 {lappend command} {
+    lappend x(0)
+} -ok {}
+
+test lappend-1.20 {lappend command} {
+    unset -nocomplain x
+    lappend x(0) abc
+} -ok {abc}
 
 test lappend-1.21 {lappend command} {
     set x \"
@@ -120,6 +117,32 @@ test lappend-1.22 {lappend command} {
     lappend x abc
 } -error {unmatched open quote in list}
 
+test lappend-1.23 {lappend command, no values creates the variable} {
+    lappend x
+    list [set x] [llength $x]
+} -ok {{} 0}
+
+test lappend-1.24 {lappend command, values round-trip} {
+    lappend x "a \{" "b\\" {c d} "\}"
+    set first [expr {[lindex $x 0] eq "a \{"}]
+    set second [expr {[lindex $x 1] eq "b\\"}]
+    set last [expr {[lindex $x 3] eq "\}"}]
+    list [llength $x] $first $second [lindex $x 2] $last
+} -ok {4 1 1 {c d} 1}
+
+test lappend-1.25 {lappend command, global variable} -setup {
+    proc foo {} {
+        global lappend_g
+        lappend lappend_g b c
+    }
+} -body {
+    global lappend_g
+    set lappend_g a
+    list [foo] $lappend_g
+} -cleanup {
+    rename foo ""
+} -ok {{a b c} {a b c}}
+
 test lappend-2.1 {long lappends} -setup {
     proc check {var size} {
         set l [llength $var]
@@ -148,10 +171,7 @@ test lappend-3.1 {lappend errors} {
     lappend
 } -error {wrong # args: should be "lappend varName ?value ...?"}
 
-if 0 {
-    # Need arrays
-    test lappend-3.2 {lappend errors} {
-        set x ""
-        lappend x(0) 44
-    } -error {can't set "x(0)": variable isn't array}
-}
+test lappend-3.2 {lappend errors} {
+    set x ""
+    lappend x(0) 44
+} -error {can't set "x(0)": variable isn't array}
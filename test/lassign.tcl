@@ -0,0 +1,57 @@
+# Test Script: lassign command.
+
+test lassign-1.1 {lassign errors} {
+    lassign {a b}
+} -error {wrong # args: should be "lassign list varName ?varName ...?"}
+
+test lassign-1.2 {lassign errors} {
+    set a(1) x
+    lassign {1 2} a
+} -error {can't set "a": variable is array}
+
+test lassign-2.1 {lassign, exact fit} {
+    list [lassign {1 2 3} a b c] $a $b $c
+} -ok {{} 1 2 3}
+
+test lassign-2.2 {lassign, extra variables set to empty} {
+    set c old
+    list [lassign {1 2} a b c] $a $b $c
+} -ok {{} 1 2 {}}
+
+test lassign-2.3 {lassign, empty list} {
+    list [lassign {} a b] $a $b
+} -ok {{} {} {}}
+
+test lassign-3.1 {lassign, returns remainder} {
+    list [lassign {1 2 3 4} a b] $a $b
+} -ok {{3 4} 1 2}
+
+test lassign-3.2 {lassign, remainder is a list} {
+    set rest [lassign {1 {2 3} {4 5}} a]
+    list [llength $rest] [lindex $rest 0] [lindex $rest 1]
+} -ok {2 {2 3} {4 5}}
+
+test lassign-3.3 {lassign, shift idiom} {
+    set args {-foo 1 -bar 2}
+    set args [lassign $args opt val]
+    list $opt $val $args
+} -ok {-foo 1 {-bar 2}}
+
+test lassign-4.1 {lassign, in a proc} {
+    proc lassign_test {list} {
+        set rest [lassign $list x y]
+        list $x $y $rest
+    }
+    set x global
+    list [lassign_test {a b c}] $x
+} -ok {{a b c} global}
+
+test lassign-4.2 {lassign, global variables} {
+    proc lassign_test2 {} {
+        global lassign_g
+        lassign {a b} lassign_g
+    }
+    global lassign_g
+    set result [lassign_test2]
+    list $result $lassign_g
+} -ok {b a}
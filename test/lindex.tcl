@@ -0,0 +1,56 @@
+# Test Script: lindex command.
+
+test lindex-1.1 {lindex errors} {
+    lindex
+} -error {wrong # args: should be "lindex list ?index ...?"}
+
+test lindex-1.2 {lindex errors} {
+    lindex {a b c} x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lindex-1.3 {lindex errors, intermediate element not a list} {
+    lindex [list a "b \{c" d] 1 0
+} -error {unmatched open brace in list}
+
+test lindex-2.1 {lindex, no indices} {
+    list [lindex {a b c}] [lindex {a {b c}} {}]
+} -ok {{a b c} {a {b c}}}
+
+test lindex-2.2 {lindex, single index} {
+    list [lindex {a b c} 0] [lindex {a b c} 2] [lindex {a {b c}} 1]
+} -ok {a c {b c}}
+
+test lindex-2.3 {lindex, out of range} {
+    list [lindex {a b c} -1] [lindex {a b c} 3] [lindex {} 0]
+} -ok {{} {} {}}
+
+test lindex-2.4 {lindex, end and end-N} {
+    list [lindex {a b c} end] [lindex {a b c} end-1] [lindex {a b c} end-3]
+} -ok {c b {}}
+
+test lindex-2.5 {lindex, M+N} {
+    lindex {a b c} 0+1
+} -ok {b}
+
+test lindex-3.1 {lindex, nested index arguments} {
+    set m {{1 2 3} {4 5 6} {7 8 9}}
+    list [lindex $m 1 2] [lindex $m 2 0] [lindex $m end end-1]
+} -ok {6 7 8}
+
+test lindex-3.2 {lindex, nested index list} {
+    set m {{1 2 3} {4 5 6} {7 8 9}}
+    list [lindex $m {1 2}] [lindex $m {end 0}] [lindex $m {0}]
+} -ok {6 7 {1 2 3}}
+
+test lindex-3.3 {lindex, nested out of range} {
+    set m {{1 2 3} {4 5 6}}
+    list [lindex $m 1 5] [lindex $m 5 0] [lindex $m {0 -1}]
+} -ok {{} {} {}}
+
+test lindex-3.4 {lindex, deeply nested} {
+    lindex {a {b {c d} e} f} 1 1 0
+} -ok {c}
+
+test lindex-3.5 {lindex, overflowing indices} {
+    list [lindex {a b} 9223372036854775807+1] [lindex {a b} -9223372036854775808-1] [lindex {a b} end-9223372036854775807]
+} -ok {{} {} {}}
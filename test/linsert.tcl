@@ -0,0 +1,46 @@
+# Test Script: linsert command.
+
+test linsert-1.1 {linsert errors} {
+    linsert {a b c} 1
+} -error {wrong # args: should be "linsert list index value ?value ...?"}
+
+test linsert-1.2 {linsert errors} {
+    linsert {a b c} x X
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test linsert-2.1 {linsert, before index} {
+    list [linsert {a b c} 0 X] [linsert {a b c} 1 X] [linsert {a b c} 3 X]
+} -ok {{X a b c} {a X b c} {a b c X}}
+
+test linsert-2.2 {linsert, multiple values} {
+    linsert {a b c} 1 X Y Z
+} -ok {a X Y Z b c}
+
+test linsert-2.3 {linsert, empty list} {
+    list [linsert {} 0 X] [linsert {} end X Y] [linsert {} 5 X]
+} -ok {X {X Y} X}
+
+test linsert-2.4 {linsert, original value unchanged} {
+    set x {a b}
+    set y [linsert $x 0 Z]
+    list $x $y
+} -ok {{a b} {Z a b}}
+
+test linsert-3.1 {linsert, end appends} {
+    linsert {a b c} end X
+} -ok {a b c X}
+
+test linsert-3.2 {linsert, end-N} {
+    list [linsert {a b c} end-1 X] [linsert {a b c} end-3 X]
+} -ok {{a b X c} {X a b c}}
+
+test linsert-3.3 {linsert, out of range} {
+    list [linsert {a b c} -5 X] [linsert {a b c} 10 X] [linsert {a b c} end+2 X]
+} -ok {{X a b c} {a b c X} {a b c X}}
+
+test linsert-4.1 {linsert, special characters} {
+    set x [linsert {a b} 1 {x y} "\{" "c\\d" ""]
+    set brace [expr {[lindex $x 2] eq "\{"}]
+    set backslash [expr {[lindex $x 3] eq "c\\d"}]
+    list [llength $x] [lindex $x 1] $brace $backslash [lindex $x 4] [lindex $x 5]
+} -ok {6 {x y} 1 1 {} b}
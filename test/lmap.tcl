@@ -0,0 +1,79 @@
+# Test Script: lmap command.
+
+test lmap-1.1 {lmap errors} {
+    lmap x {1 2}
+} -error {wrong # args: should be "lmap varList list ?varList list ...? body"}
+
+test lmap-1.2 {lmap errors} {
+    lmap x {1 2} y {}
+} -error {wrong # args: should be "lmap varList list ?varList list ...? body"}
+
+test lmap-1.3 {lmap errors} {
+    lmap {} {1 2} {}
+} -error {lmap varlist is empty}
+
+test lmap-1.4 {lmap, error in body} {
+    lmap x {1 2 3} {error "oops $x"}
+} -error {oops 1}
+
+test lmap-2.1 {lmap, basic} {
+    lmap x {1 2 3} {expr {$x * 2}}
+} -ok {2 4 6}
+
+test lmap-2.2 {lmap, empty list} {
+    lmap x {} {set x}
+} -ok {}
+
+test lmap-2.3 {lmap, results are list elements} {
+    set result [lmap x {a b} {list $x $x}]
+    list [llength $result] [lindex $result 0]
+} -ok {2 {a a}}
+
+test lmap-3.1 {lmap, multiple variables} {
+    lmap {a b} {1 2 3 4 5} {list $a $b}
+} -ok {{1 2} {3 4} {5 {}}}
+
+test lmap-3.2 {lmap, multiple lists} {
+    lmap x {a b c} y {1 2 3} {set _ $x$y}
+} -ok {a1 b2 c3}
+
+test lmap-3.3 {lmap, lists of different lengths} {
+    lmap x {a b c} y {1} {set _ <$x$y>}
+} -ok {<a1> <b> <c>}
+
+test lmap-3.4 {lmap, multiple variables and lists} {
+    lmap {a b} {1 2 3 4} c {x y z} {set _ $a$b$c}
+} -ok {12x 34y z}
+
+test lmap-4.1 {lmap, continue skips the iteration} {
+    lmap x {1 2 3 4 5 6} {
+        if {$x % 2 == 1} { continue }
+        set x
+    }
+} -ok {2 4 6}
+
+test lmap-4.2 {lmap, break returns what was collected} {
+    lmap x {1 2 3 4 5 6} {
+        if {$x == 4} { break }
+        set x
+    }
+} -ok {1 2 3}
+
+test lmap-4.3 {lmap, break on the first iteration} {
+    lmap x {1 2 3} { break }
+} -ok {}
+
+test lmap-4.4 {lmap, return from a proc} {
+    proc lmap_test {} {
+        lmap x {1 2 3} {
+            if {$x == 2} { return "returned $x" }
+            set x
+        }
+    }
+    lmap_test
+} -ok {returned 2}
+
+test lmap-4.5 {lmap, variables keep their last values} {
+    lmap x {1 2 3} {set x}
+    set x
+} -ok {3}
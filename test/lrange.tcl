@@ -0,0 +1,66 @@
+# Test Script: lrange command.
+
+test lrange-1.1 {lrange errors} {
+    lrange {a b c}
+} -error {wrong # args: should be "lrange list first last"}
+
+test lrange-1.2 {lrange errors} {
+    lrange {a b c} x 1
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lrange-1.3 {lrange errors} {
+    lrange "a \{b" 0 1
+} -error {unmatched open brace in list}
+
+test lrange-2.1 {lrange, basic} {
+    lrange {a b c d e} 1 3
+} -ok {b c d}
+
+test lrange-2.2 {lrange, single element} {
+    lrange {a b c d e} 2 2
+} -ok {c}
+
+test lrange-2.3 {lrange, empty list} {
+    lrange {} 0 end
+} -ok {}
+
+test lrange-2.4 {lrange, first > last} {
+    lrange {a b c d e} 3 1
+} -ok {}
+
+test lrange-3.1 {lrange, end} {
+    lrange {a b c d e} 2 end
+} -ok {c d e}
+
+test lrange-3.2 {lrange, end-N} {
+    list [lrange {a b c d e} end-2 end-1] [lrange {a b c d e} end end]
+} -ok {{c d} e}
+
+test lrange-3.3 {lrange, end-N before start} {
+    lrange {a b c} end-5 end-1
+} -ok {a b}
+
+test lrange-3.4 {lrange, M+N} {
+    lrange {a b c d e} 0+1 4-1
+} -ok {b c d}
+
+test lrange-4.1 {lrange, negative first} {
+    lrange {a b c} -3 1
+} -ok {a b}
+
+test lrange-4.2 {lrange, last past end} {
+    lrange {a b c} 1 10
+} -ok {b c}
+
+test lrange-4.3 {lrange, both out of range} {
+    list [lrange {a b c} -5 -1] [lrange {a b c} 5 10] [lrange {a b c} -5 10]
+} -ok {{} {} {a b c}}
+
+test lrange-4.4 {lrange, overflowing indices} {
+    list [lrange {a b c} 0 end+9223372036854775807] [lrange {a b c} -9223372036854775808-1 0]
+} -ok {{a b c} a}
+
+test lrange-5.1 {lrange, elements round-trip} {
+    set x [lrange {a {b c} "d \{" {e\\} f} 1 3]
+    list [lindex $x 0] [expr {[lindex $x 1] eq "d \{"}] [lindex $x 2] [llength $x]
+} -ok {{b c} 1 {e\\} 3}
@@ -0,0 +1,42 @@
+# Test Script: lrepeat command.
+
+test lrepeat-1.1 {lrepeat errors} {
+    lrepeat
+} -error {wrong # args: should be "lrepeat count ?value ...?"}
+
+test lrepeat-1.2 {lrepeat errors} {
+    lrepeat x a
+} -error {expected integer but got "x"}
+
+test lrepeat-1.3 {lrepeat errors} {
+    lrepeat -1 a
+} -error {bad count "-1": must be integer >= 0}
+
+test lrepeat-1.4 {lrepeat errors, result too large} {
+    list [catch {lrepeat 9223372036854775807 a b} msg] $msg [catch {lrepeat 3000000000 a} msg] $msg
+} -ok {1 {max size for a Tcl value exceeded} 1 {max size for a Tcl value exceeded}}
+
+test lrepeat-2.1 {lrepeat, basic} {
+    list [lrepeat 3 a] [lrepeat 2 a b]
+} -ok {{a a a} {a b a b}}
+
+test lrepeat-2.2 {lrepeat, zero count} {
+    list [lrepeat 0 a b] [lrepeat 0]
+} -ok {{} {}}
+
+test lrepeat-2.3 {lrepeat, no values} {
+    lrepeat 3
+} -ok {}
+
+test lrepeat-2.4 {lrepeat, values are list elements} {
+    set x [lrepeat 2 {a b}]
+    list [llength $x] [lindex $x 0] [lindex $x 1]
+} -ok {2 {a b} {a b}}
+
+test lrepeat-2.5 {lrepeat, empty value} {
+    llength [lrepeat 3 {}]
+} -ok {3}
+
+test lrepeat-2.6 {lrepeat, huge count and no values} {
+    lrepeat 9223372036854775807
+} -ok {}
@@ -0,0 +1,63 @@
+# Test Script: lreplace command.
+
+test lreplace-1.1 {lreplace errors} {
+    lreplace {a b c} 1
+} -error {wrong # args: should be "lreplace list first last ?value ...?"}
+
+test lreplace-1.2 {lreplace errors} {
+    lreplace {a b c} 1 x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lreplace-2.1 {lreplace, replace one} {
+    lreplace {a b c d} 1 1 X
+} -ok {a X c d}
+
+test lreplace-2.2 {lreplace, replace range with more values} {
+    lreplace {a b c d} 1 2 X Y Z
+} -ok {a X Y Z d}
+
+test lreplace-2.3 {lreplace, pure deletion} {
+    list [lreplace {a b c d} 1 2] [lreplace {a b c d} 0 end]
+} -ok {{a d} {}}
+
+test lreplace-2.4 {lreplace, values are list elements} {
+    lreplace {a b c} 1 1 {x y} {}
+} -ok {a {x y} {} c}
+
+test lreplace-2.5 {lreplace, original value unchanged} {
+    set x {a b c}
+    set y [lreplace $x 0 0 Z]
+    list $x $y
+} -ok {{a b c} {Z b c}}
+
+test lreplace-3.1 {lreplace, end and end-N} {
+    list [lreplace {a b c d} end end X] [lreplace {a b c d} end-2 end-1]
+} -ok {{a b c X} {a d}}
+
+test lreplace-3.2 {lreplace, last past end} {
+    lreplace {a b c d} 2 10 X
+} -ok {a b X}
+
+test lreplace-4.1 {lreplace, last < first inserts at first} {
+    lreplace {a b c d} 2 1 X Y
+} -ok {a b X Y c d}
+
+test lreplace-4.2 {lreplace, first before the list} {
+    list [lreplace {a b c} -2 -1 X] [lreplace {a b c} -2 0 X]
+} -ok {{X a b c} {X b c}}
+
+test lreplace-4.3 {lreplace, first one past the end appends} {
+    list [lreplace {a b c} 3 3 X] [lreplace {a b c} end+1 end+1 X Y]
+} -ok {{a b c X} {a b c X Y}}
+
+test lreplace-4.4 {lreplace, first far past the end appends} {
+    lreplace {a b c} 10 10 X
+} -ok {a b c X}
+
+test lreplace-4.5 {lreplace, empty list} {
+    list [lreplace {} 0 0] [lreplace {} 0 0 X] [lreplace {} end end X]
+} -ok {{} X X}
+
+test lreplace-4.6 {lreplace, overflowing indices} {
+    list [lreplace {a b c} 9223372036854775807+1 end+9223372036854775807 x] [lreplace {a b c} -9223372036854775808-1 0 x]
+} -ok {{a b c x} {x b c}}
@@ -0,0 +1,28 @@
+# Test Script: lreverse command.
+
+test lreverse-1.1 {lreverse errors} {
+    lreverse
+} -error {wrong # args: should be "lreverse list"}
+
+test lreverse-1.2 {lreverse errors} {
+    lreverse {a b} {c d}
+} -error {wrong # args: should be "lreverse list"}
+
+test lreverse-2.1 {lreverse, basic} {
+    lreverse {a b c d}
+} -ok {d c b a}
+
+test lreverse-2.2 {lreverse, empty and single element lists} {
+    list [lreverse {}] [lreverse {a}] [lreverse {{a b}}]
+} -ok {{} a {{a b}}}
+
+test lreverse-2.3 {lreverse, sublists and special characters} {
+    set x [lreverse [list {a b} "\{" "c\\d" {}]]
+    set brace [expr {[lindex $x 2] eq "\{"}]
+    set backslash [expr {[lindex $x 1] eq "c\\d"}]
+    list [llength $x] [lindex $x 0] $backslash $brace [lindex $x 3]
+} -ok {4 {} 1 1 {a b}}
+
+test lreverse-2.4 {lreverse, canonical form} {
+    lreverse "  a   \{b c\}  d "
+} -ok {d {b c} a}
@@ -0,0 +1,108 @@
+# Test Script: lsearch command.
+
+test lsearch-1.1 {lsearch errors} {
+    lsearch {a b}
+} -error {wrong # args: should be "lsearch ?-option value ...? list pattern"}
+
+test lsearch-1.2 {lsearch errors} {
+    lsearch -frob {a b} a
+} -error {bad option "-frob": must be -all, -exact, -glob, -inline, -not, -regexp, or -start}
+
+test lsearch-1.3 {lsearch errors} {
+    lsearch -start {a b} a
+} -error {missing starting index}
+
+test lsearch-1.4 {lsearch errors} {
+    lsearch -start x {a b} a
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lsearch-1.5 {lsearch errors} {
+    lsearch -regexp {a b} "a("
+} -error {couldn't compile regular expression pattern: regex parse error:
+    a(
+     ^
+error: unclosed group}
+
+test lsearch-2.1 {lsearch, default glob} {
+    list [lsearch {apple banana cherry} b*] [lsearch {apple banana cherry} z*]
+} -ok {1 -1}
+
+test lsearch-2.2 {lsearch, first match} {
+    lsearch {a b a b} b
+} -ok {1}
+
+test lsearch-2.3 {lsearch, empty list} {
+    lsearch {} a
+} -ok {-1}
+
+test lsearch-2.4 {lsearch -glob} {
+    lsearch -glob {x1 a* ab} a*
+} -ok {1}
+
+test lsearch-2.5 {lsearch -exact} {
+    list [lsearch -exact {x1 ab a*} a*] [lsearch -exact {x1 ab} a*]
+} -ok {2 -1}
+
+test lsearch-2.6 {lsearch -regexp} {
+    lsearch -regexp {abc x12 y} {^[a-z][0-9]+$}
+} -ok {1}
+
+test lsearch-3.1 {lsearch -all} {
+    list [lsearch -all {a b a c a} a] [lsearch -all {a b} z]
+} -ok {{0 2 4} {}}
+
+test lsearch-3.2 {lsearch -inline} {
+    list [lsearch -inline {apple banana} b*] [lsearch -inline {apple banana} z*]
+} -ok {banana {}}
+
+test lsearch-3.3 {lsearch -not} {
+    lsearch -not {a a b a} a
+} -ok {2}
+
+test lsearch-3.4 {lsearch -all -inline} {
+    lsearch -all -inline {apple banana blueberry cherry} b*
+} -ok {banana blueberry}
+
+test lsearch-3.5 {lsearch -all -not} {
+    lsearch -all -not {apple banana blueberry cherry} b*
+} -ok {0 3}
+
+test lsearch-3.6 {lsearch -inline -not} {
+    lsearch -inline -not {banana apple cherry} b*
+} -ok {apple}
+
+test lsearch-3.7 {lsearch -all -inline -not} {
+    lsearch -all -inline -not {apple banana blueberry cherry} b*
+} -ok {apple cherry}
+
+test lsearch-3.8 {lsearch -all -inline -not, no matches} {
+    lsearch -all -inline -not {a a} a
+} -ok {}
+
+test lsearch-3.9 {lsearch -inline, list element} {
+    lsearch -inline {a {b c} d} {b*}
+} -ok {b c}
+
+test lsearch-4.1 {lsearch -start} {
+    list [lsearch -start 1 {a b a b} a] [lsearch -start 3 {a b a b} a]
+} -ok {2 -1}
+
+test lsearch-4.2 {lsearch -start end} {
+    list [lsearch -start end {a b a b} b] [lsearch -start end-1 {a b a b} a]
+} -ok {3 2}
+
+test lsearch-4.3 {lsearch -start before the list} {
+    lsearch -start -5 {a b} a
+} -ok {0}
+
+test lsearch-4.4 {lsearch -start after the list} {
+    lsearch -start 10 {a b} a
+} -ok {-1}
+
+test lsearch-4.5 {lsearch -start -all -inline} {
+    lsearch -all -inline -start 2 {a1 b a2 a3} a*
+} -ok {a2 a3}
+
+test lsearch-4.6 {lsearch -start, overflowing indices} {
+    list [lsearch -start 9223372036854775807+1 {a b} b] [lsearch -start -9223372036854775808-1 {a b} b]
+} -ok {-1 1}
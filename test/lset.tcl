@@ -0,0 +1,110 @@
+# Test Script: lset command.
+
+test lset-1.1 {lset errors} {
+    lset x
+} -error {wrong # args: should be "lset listVar ?index? ?index ...? value"}
+
+test lset-1.2 {lset errors} {
+    lset nonesuch 0 a
+} -error {can't read "nonesuch": no such variable}
+
+test lset-1.3 {lset errors} {
+    set x {a b c}
+    lset x 3+1 z
+} -error {list index out of range}
+
+test lset-1.4 {lset errors} {
+    set x {a b c}
+    lset x -1 z
+} -error {list index out of range}
+
+test lset-1.5 {lset errors} {
+    set x {a b c}
+    lset x foo z
+} -error {bad index "foo": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lset-1.6 {lset errors, intermediate element not a list} {
+    set x [list a "b \{c" d]
+    lset x 1 0 z
+} -error {unmatched open brace in list}
+
+test lset-1.7 {lset errors, bad list is unchanged} {
+    set x {a b c}
+    catch {lset x 5 z}
+    set x
+} -ok {a b c}
+
+test lset-2.1 {lset, basic} {
+    set x {a b c}
+    list [lset x 1 B] $x
+} -ok {{a B c} {a B c}}
+
+test lset-2.2 {lset, end and end-N} {
+    set x {a b c}
+    lset x end C
+    lset x end-2 A
+    set x
+} -ok {A b C}
+
+test lset-2.3 {lset, index at the end appends} {
+    set x {a b c}
+    lset x end+1 d
+    lset x 4 e
+} -ok {a b c d e}
+
+test lset-2.4 {lset, no indices replaces the value} {
+    set x {a b c}
+    list [lset x {x y}] [lset x {} z] $x
+} -ok {{x y} z z}
+
+test lset-2.5 {lset, value with spaces is one element} {
+    set x {a b c}
+    lset x 1 {p q}
+    list [llength $x] [lindex $x 1]
+} -ok {3 {p q}}
+
+test lset-2.6 {lset, original value unchanged} {
+    set x {a b c}
+    set y $x
+    lset x 0 z
+    list $x $y
+} -ok {{z b c} {a b c}}
+
+test lset-3.1 {lset, nested indices} {
+    set m {{1 2 3} {4 5 6} {7 8 9}}
+    lset m 1 2 X
+} -ok {{1 2 3} {4 5 X} {7 8 9}}
+
+test lset-3.2 {lset, nested index list} {
+    set m {{1 2 3} {4 5 6} {7 8 9}}
+    lset m {2 0} X
+} -ok {{1 2 3} {4 5 6} {X 8 9}}
+
+test lset-3.3 {lset, nested end-N} {
+    set m {{1 2 3} {4 5 6} {7 8 9}}
+    lset m end end-1 X
+} -ok {{1 2 3} {4 5 6} {7 X 9}}
+
+test lset-3.4 {lset, nested append} {
+    set m {{1 2} {3 4}}
+    lset m 0 end+1 X
+} -ok {{1 2 X} {3 4}}
+
+test lset-3.5 {lset, nested out of range} {
+    set m {{1 2} {3 4}}
+    lset m 1 5 X
+} -error {list index out of range}
+
+test lset-3.6 {lset, deeply nested} {
+    set m {a {b {c d} e} f}
+    lset m 1 1 0 X
+} -ok {a {b {X d} e} f}
+
+test lset-3.7 {lset, in a proc} {
+    proc lset_test {} {
+        set m {{1 2} {3 4}}
+        lset m 1 0 X
+        set m
+    }
+    lset_test
+} -ok {{1 2} {X 4}}
@@ -0,0 +1,205 @@
+# Test Script: lsort command.
+
+test lsort-1.1 {lsort errors} {
+    lsort
+} -error {wrong # args: should be "lsort ?-option value ...? list"}
+
+test lsort-1.2 {lsort errors} {
+    lsort -frob {a b}
+} -error {bad option "-frob": must be -ascii, -command, -decreasing, -dictionary, -increasing, -index, -integer, -nocase, -real, or -unique}
+
+test lsort-1.3 {lsort errors} {
+    lsort -integer {1 2 x}
+} -error {expected integer but got "x"}
+
+test lsort-1.4 {lsort errors} {
+    lsort -real {1.0 x 2.0}
+} -error {expected floating-point number but got "x"}
+
+test lsort-1.5 {lsort errors} {
+    lsort "a \{b"
+} -error {unmatched open brace in list}
+
+test lsort-2.1 {lsort, trivial lists} {
+    list [lsort {}] [lsort {a}]
+} -ok {{} a}
+
+test lsort-2.2 {lsort, ascii} {
+    lsort {d b c a B}
+} -ok {B a b c d}
+
+test lsort-2.3 {lsort, ascii} {
+    lsort -ascii {10 9 1 100}
+} -ok {1 10 100 9}
+
+test lsort-2.4 {lsort, increasing and decreasing} {
+    list [lsort -increasing {c a b}] [lsort -decreasing {c a b}]
+} -ok {{a b c} {c b a}}
+
+test lsort-2.5 {lsort, last option wins} {
+    lsort -decreasing -increasing {c a b}
+} -ok {a b c}
+
+test lsort-3.1 {lsort -integer} {
+    lsort -integer {10 9 1 100 -5}
+} -ok {-5 1 9 10 100}
+
+test lsort-3.2 {lsort -integer -decreasing} {
+    lsort -integer -decreasing {10 9 1 100 -5}
+} -ok {100 10 9 1 -5}
+
+test lsort-3.3 {lsort -real} {
+    lsort -real {1.5 -2 10 1e1 0.25}
+} -ok {-2 0.25 1.5 10 1e1}
+
+test lsort-4.1 {lsort -nocase} {
+    lsort -nocase {b A c a B}
+} -ok {A a b B c}
+
+test lsort-5.1 {lsort -dictionary} {
+    lsort -dictionary {x10 x9 X1 x1 x011 abc ABC Abc}
+} -ok {ABC Abc abc X1 x1 x9 x10 x011}
+
+test lsort-5.2 {lsort -dictionary, leading zeros} {
+    lsort -dictionary {a01 a1 a001}
+} -ok {a1 a01 a001}
+
+test lsort-6.1 {lsort -unique} {
+    lsort -unique {c a b a c}
+} -ok {a b c}
+
+test lsort-6.2 {lsort -unique keeps last duplicate} {
+    lsort -unique -nocase {b A a B}
+} -ok {a B}
+
+test lsort-6.3 {lsort -unique -integer} {
+    lsort -unique -integer {3 03 1 2 0x3}
+} -ok {1 2 0x3}
+
+test lsort-7.1 {lsort stability} {
+    lsort -nocase {b A B a}
+} -ok {A a b B}
+
+test lsort-8.1 {lsort -index} {
+    lsort -index 1 {{a 3} {b 1} {c 2}}
+} -ok {{b 1} {c 2} {a 3}}
+
+test lsort-8.2 {lsort -index end} {
+    lsort -index end {{a x 3} {b 1} {c y z 2}}
+} -ok {{b 1} {c y z 2} {a x 3}}
+
+test lsort-8.3 {lsort -index -integer -decreasing} {
+    lsort -integer -decreasing -index 0 {{10 a} {9 b} {100 c}}
+} -ok {{100 c} {10 a} {9 b}}
+
+test lsort-8.4 {lsort -index stability} {
+    lsort -index 1 {{a 2} {b 1} {c 2} {d 1}}
+} -ok {{b 1} {d 1} {a 2} {c 2}}
+
+test lsort-8.5 {lsort -index -unique} {
+    lsort -unique -index 1 {{a 2} {b 1} {c 2} {d 1}}
+} -ok {{d 1} {c 2}}
+
+test lsort-8.6 {lsort -index errors} {
+    lsort -index 2 {{a 1} {b 2}}
+} -error {element 2 missing from sublist "a 1"}
+
+test lsort-8.7 {lsort -index errors} {
+    lsort -index 0 [list a "b \{c"]
+} -error {unmatched open brace in list}
+
+test lsort-8.8 {lsort -index errors} {
+    lsort -index x {{a 1} {b 2}}
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test lsort-8.9 {lsort -index errors} {
+    lsort -index {{a 1} {b 2}}
+} -error {"-index" option must be followed by list index}
+
+test lsort-9.1 {lsort -command} -setup {
+    proc bylength {a b} {
+        expr {[llength $a] - [llength $b]}
+    }
+} -body {
+    lsort -command bylength {{a b c} {d} {e f}}
+} -cleanup {
+    rename bylength ""
+} -ok {d {e f} {a b c}}
+
+test lsort-9.2 {lsort -command -decreasing} -setup {
+    proc numcmp {a b} {
+        expr {$a - $b}
+    }
+} -body {
+    lsort -decreasing -command numcmp {3 10 1 2}
+} -cleanup {
+    rename numcmp ""
+} -ok {10 3 2 1}
+
+test lsort-9.3 {lsort -command with prefix} -setup {
+    proc modcmp {m a b} {
+        expr {$a % $m - $b % $m}
+    }
+} -body {
+    lsort -command {modcmp 3} {5 3 4}
+} -cleanup {
+    rename modcmp ""
+} -ok {3 4 5}
+
+test lsort-9.4 {lsort -command -index} -setup {
+    proc numcmp {a b} {
+        expr {$a - $b}
+    }
+} -body {
+    lsort -index 1 -command numcmp {{a 3} {b 10} {c 1}}
+} -cleanup {
+    rename numcmp ""
+} -ok {{c 1} {a 3} {b 10}}
+
+test lsort-9.5 {lsort -command -unique} -setup {
+    proc nocasecmp {a b} {
+        if {[lsort -nocase [list $a $b]] eq [list $a $b]} {
+            if {[lsort -nocase [list $b $a]] eq [list $b $a]} {
+                return 0
+            }
+            return -1
+        }
+        return 1
+    }
+} -body {
+    lsort -unique -command nocasecmp {b A a B}
+} -cleanup {
+    rename nocasecmp ""
+} -ok {a B}
+
+test lsort-9.6 {lsort -command errors} -setup {
+    proc badcmp {a b} {
+        error "Simulated error"
+    }
+} -body {
+    lsort -command badcmp {1 2 3}
+} -cleanup {
+    rename badcmp ""
+} -error {Simulated error}
+
+test lsort-9.7 {lsort -command errors} -setup {
+    proc badcmp {a b} {
+        return "abc"
+    }
+} -body {
+    lsort -command badcmp {1 2 3}
+} -cleanup {
+    rename badcmp ""
+} -error {-compare command returned non-integer result}
+
+test lsort-9.8 {lsort -command errors} {
+    lsort -command nonesuch {1 2 3}
+} -error {invalid command name "nonesuch"}
+
+test lsort-9.9 {lsort -command, single element} {
+    lsort -command nonesuch {1}
+} -ok {1}
+
+test lsort-9.10 {lsort -command errors} {
+    lsort -command {1 2}
+} -error {"-command" option must be followed by comparison command}
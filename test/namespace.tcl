@@ -0,0 +1,451 @@
+# Test Script: namespace
+#
+# Note: test bodies are evaluated in their own scope at level 1.
+
+test namespace-1.1 {namespace errors} {
+    namespace
+} -error {wrong # args: should be "namespace subcommand ?arg ...?"}
+
+test namespace-1.2 {namespace errors, bad subcommand} {
+    namespace nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be children, current, delete, ensemble, eval, or exists}
+
+test namespace-2.1 {namespace current errors} {
+    namespace current a
+} -error {wrong # args: should be "namespace current"}
+
+test namespace-2.2 {namespace current, global} {
+    namespace current
+} -ok {::}
+
+test namespace-2.3 {namespace current, nested} -body {
+    namespace eval ns_a {
+        namespace eval b {
+            namespace current
+        }
+    }
+} -cleanup {
+    namespace delete ns_a
+} -ok {::ns_a::b}
+
+test namespace-3.1 {namespace eval errors} {
+    namespace eval ns_a
+} -error {wrong # args: should be "namespace eval name arg ?arg...?"}
+
+test namespace-3.2 {namespace eval creates the namespace} -body {
+    set r1 [namespace exists ns_a]
+    namespace eval ns_a {}
+    list $r1 [namespace exists ns_a] [namespace exists ::ns_a]
+} -cleanup {
+    namespace delete ns_a
+} -ok {0 1 1}
+
+test namespace-3.3 {namespace eval creates parent namespaces} -body {
+    namespace eval ns_a::b::c {}
+    list [namespace exists ns_a] [namespace exists ns_a::b] [namespace exists ::ns_a::b::c]
+} -cleanup {
+    namespace delete ns_a
+} -ok {1 1 1}
+
+test namespace-3.4 {namespace eval concatenates its arguments} -body {
+    namespace eval ns_a set x 1
+    set ns_a::x
+} -cleanup {
+    namespace delete ns_a
+} -ok {1}
+
+test namespace-3.5 {namespace eval returns errors} -body {
+    namespace eval ns_a {error "simulated error"}
+} -cleanup {
+    namespace delete ns_a
+} -error {simulated error}
+
+test namespace-3.6 {namespace eval doesn't push a call frame} -body {
+    namespace eval ns_a {info level}
+} -cleanup {
+    namespace delete ns_a
+} -ok {1}
+
+test namespace-3.7 {namespace eval, namespace restored after error} -body {
+    catch {namespace eval ns_a {error oops}}
+    namespace current
+} -cleanup {
+    namespace delete ns_a
+} -ok {::}
+
+test namespace-4.1 {namespace exists errors} {
+    namespace exists
+} -error {wrong # args: should be "namespace exists name"}
+
+test namespace-4.2 {namespace exists, global} {
+    list [namespace exists ::] [namespace exists nonesuch]
+} -ok {1 0}
+
+test namespace-5.1 {namespace delete errors} {
+    namespace delete nonesuch
+} -error {unknown namespace "::nonesuch" in namespace delete command}
+
+test namespace-5.2 {namespace delete removes children, commands, and variables} -setup {
+    namespace eval ns_a {
+        proc p {} { return p }
+        set x 1
+        namespace eval b {
+            proc q {} { return q }
+            set y 2
+        }
+    }
+} -body {
+    namespace delete ns_a
+    set r1 [list [namespace exists ns_a] [namespace exists ns_a::b]]
+    set r2 [list [info exists ns_a::x] [info exists ::ns_a::b::y]]
+    concat $r1 $r2 [catch {ns_a::p}] [catch {ns_a::b::q}]
+} -ok {0 0 0 0 1 1}
+
+test namespace-5.3 {namespace delete multiple} -setup {
+    namespace eval ns_a {}
+    namespace eval ns_b {}
+} -body {
+    namespace delete ns_a ns_b
+    list [namespace exists ns_a] [namespace exists ns_b]
+} -ok {0 0}
+
+test namespace-6.1 {namespace children errors} {
+    namespace children nonesuch
+} -error {namespace "::nonesuch" not found in "::"}
+
+test namespace-6.2 {namespace children} -setup {
+    namespace eval ns_a::b {}
+    namespace eval ns_a::c {}
+    namespace eval ns_a::b::d {}
+} -body {
+    list [lsort [namespace children ns_a]] [namespace children ::ns_a::b] [namespace children ns_a::c]
+} -cleanup {
+    namespace delete ns_a
+} -ok {{::ns_a::b ::ns_a::c} ::ns_a::b::d {}}
+
+test namespace-6.3 {namespace children, current namespace} -setup {
+    namespace eval ns_a::b {}
+} -body {
+    namespace eval ns_a {namespace children}
+} -cleanup {
+    namespace delete ns_a
+} -ok {::ns_a::b}
+
+test namespace-6.4 {namespace children, pattern} -setup {
+    namespace eval ns_a::b {}
+    namespace eval ns_a::c {}
+} -body {
+    list [namespace children ns_a b*] [namespace children ns_a ::ns_a::c*]
+} -cleanup {
+    namespace delete ns_a
+} -ok {::ns_a::b ::ns_a::c}
+
+test namespace-7.1 {procs defined in namespaces} -setup {
+    namespace eval ns_a {
+        proc p {} { return "in [namespace current]" }
+    }
+    proc ::ns_a::q {} { return q }
+} -body {
+    list [ns_a::p] [::ns_a::p] [ns_a::q] [namespace eval ns_a {p}] [info procs p]
+} -cleanup {
+    namespace delete ns_a
+} -ok {{in ::ns_a} {in ::ns_a} q {in ::ns_a} {}}
+
+test namespace-7.2 {proc in a nonexistent namespace} {
+    proc ::nonesuch::p {} {}
+} -error {can't create procedure "::nonesuch::p": unknown namespace}
+
+test namespace-7.3 {command lookup tries the current namespace, then the global namespace} -setup {
+    proc ns_p {} { return global }
+    namespace eval ns_a {
+        proc ns_q {} { return local }
+        proc r {} { list [ns_p] [ns_q] }
+    }
+} -body {
+    ns_a::r
+} -cleanup {
+    rename ns_p ""
+    namespace delete ns_a
+} -ok {global local}
+
+test namespace-7.4 {namespace commands shadow global commands} -setup {
+    namespace eval ns_a {
+        proc list {args} { return shadowed }
+        proc r {} { list a b }
+    }
+} -body {
+    list [ns_a::r] [list a b]
+} -cleanup {
+    namespace delete ns_a
+} -ok {shadowed {a b}}
+
+test namespace-7.5 {rename qualified commands} -setup {
+    namespace eval ns_a {
+        proc p {} { return p }
+    }
+} -body {
+    rename ns_a::p ::ns_a::q
+    list [catch {ns_a::p}] [ns_a::q] [info body ns_a::q]
+} -cleanup {
+    namespace delete ns_a
+} -ok {1 p { return p }}
+
+test namespace-7.6 {rename into a nonexistent namespace} -setup {
+    proc ns_p {} {}
+} -body {
+    rename ns_p ::nonesuch::ns_p
+} -cleanup {
+    rename ns_p ""
+} -error {can't rename to "::nonesuch::ns_p": unknown namespace}
+
+test namespace-8.1 {namespace variables} -setup {
+    namespace eval ns_a {
+        set x 1
+    }
+} -body {
+    list $ns_a::x $::ns_a::x [namespace eval ns_a {set x}] [info exists x]
+} -cleanup {
+    namespace delete ns_a
+} -ok {1 1 1 0}
+
+test namespace-8.2 {qualified global variables} -setup {
+    uplevel #0 {set ns_g global}
+    proc p {} { set ::ns_g }
+} -body {
+    set ::ns_g2 new
+    list [p] [uplevel #0 {set ns_g2}] [info exists ns_g]
+} -cleanup {
+    rename p ""
+    uplevel #0 {unset ns_g ns_g2}
+} -ok {global new 0}
+
+test namespace-8.3 {procs use variable to reach namespace variables} -setup {
+    namespace eval ns_a {
+        variable count 0
+        proc incr_count {} {
+            variable count
+            incr count
+        }
+    }
+} -body {
+    ns_a::incr_count
+    ns_a::incr_count
+    set ns_a::count
+} -cleanup {
+    namespace delete ns_a
+} -ok {2}
+
+test namespace-8.4 {proc locals aren't namespace variables} -setup {
+    namespace eval ns_a {
+        proc p {} { set x local }
+    }
+} -body {
+    ns_a::p
+    info exists ns_a::x
+} -cleanup {
+    namespace delete ns_a
+} -ok {0}
+
+test namespace-8.5 {namespace variables don't fall back to globals} -setup {
+    uplevel #0 {set ns_g global}
+} -body {
+    namespace eval ns_a {set ns_g local}
+    list $::ns_g $ns_a::ns_g
+} -cleanup {
+    namespace delete ns_a
+    uplevel #0 {unset ns_g}
+} -ok {global local}
+
+test namespace-8.6 {namespace arrays} -setup {
+    namespace eval ns_a {
+        set arr(a) 1
+    }
+} -body {
+    set ns_a::arr(b) 2
+    list [lsort [array names ns_a::arr]] $::ns_a::arr(a)
+} -cleanup {
+    namespace delete ns_a
+} -ok {{a b} 1}
+
+test namespace-8.7 {namespace variables aren't globals} -setup {
+    namespace eval ns_a {
+        set x 1
+    }
+} -body {
+    uplevel #0 {info globals ns_a*}
+} -cleanup {
+    namespace delete ns_a
+} -ok {}
+
+test namespace-8.8 {namespace variable reads fall back to globals} -setup {
+    uplevel #0 {set ns_g global; array set ns_ga {a 1}}
+} -body {
+    namespace eval ns_a {
+        list $ns_g [set ns_g] [info exists ns_g] $ns_ga(a) [array names ns_ga] [info exists nonesuch]
+    }
+} -cleanup {
+    namespace delete ns_a
+    uplevel #0 {unset ns_g ns_ga}
+} -ok {global global 1 1 a 0}
+
+test namespace-8.9 {namespace variable writes stay in the namespace} -setup {
+    uplevel #0 {set ns_g global}
+} -body {
+    namespace eval ns_a {
+        set before $ns_g
+        set ns_g local
+        list $before $ns_g
+    }
+    list $::ns_g $ns_a::ns_g [namespace eval ns_a {set ns_g}]
+} -cleanup {
+    namespace delete ns_a
+    uplevel #0 {unset ns_g}
+} -ok {global local local}
+
+test namespace-9.1 {namespace ensemble errors} {
+    namespace ensemble
+} -error {wrong # args: should be "namespace ensemble subcommand ?arg ...?"}
+
+test namespace-9.2 {namespace ensemble create errors, bad option} -body {
+    namespace eval ns_e {namespace ensemble create -nonesuch 1}
+} -cleanup {
+    namespace delete ns_e
+} -error {bad option "-nonesuch": must be -command, -map, or -subcommands}
+
+test namespace-9.3 {namespace ensemble create errors, missing value} -body {
+    namespace eval ns_e {namespace ensemble create -map}
+} -cleanup {
+    namespace delete ns_e
+} -error {missing value for option "-map"}
+
+test namespace-9.4 {namespace ensemble create errors, global namespace} {
+    namespace ensemble create
+} -error {can't create an ensemble for the global namespace}
+
+test namespace-9.5 {namespace ensemble dispatch} -setup {
+    namespace eval ns_e {
+        proc add {a b} { expr {$a + $b} }
+        proc sub {a b} { expr {$a - $b} }
+        namespace ensemble create
+    }
+} -body {
+    list [ns_e add 5 3] [ns_e sub 5 3] [::ns_e add 1 1]
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {8 2 2}
+
+test namespace-9.6 {namespace ensemble create returns the command name} -body {
+    namespace eval ns_e {namespace ensemble create}
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {::ns_e}
+
+test namespace-9.7 {namespace ensemble unknown subcommand} -setup {
+    namespace eval ns_e {
+        proc add {a b} {}
+        proc append {a b} {}
+        proc sub {a b} {}
+        namespace ensemble create
+    }
+} -body {
+    ns_e mul 1 2
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -error {unknown or ambiguous subcommand "mul": must be add, append, or sub}
+
+test namespace-9.8 {namespace ensemble unique prefixes} -setup {
+    namespace eval ns_e {
+        proc add {a b} { expr {$a + $b} }
+        proc append {a b} { return $a$b }
+        proc sub {a b} { expr {$a - $b} }
+        namespace ensemble create
+    }
+} -body {
+    list [ns_e s 5 3] [ns_e ad 5 3] [ns_e app 5 3] [catch {ns_e a 5 3} msg] $msg
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {2 8 53 1 {unknown or ambiguous subcommand "a": must be add, append, or sub}}
+
+test namespace-9.9 {namespace ensemble without subcommand} -setup {
+    namespace eval ns_e {
+        proc add {a b} {}
+        namespace ensemble create
+    }
+} -body {
+    ns_e
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -error {wrong # args: should be "ns_e subcommand ?arg ...?"}
+
+test namespace-9.10 {namespace ensemble with no commands} -setup {
+    namespace eval ns_e {namespace ensemble create}
+} -body {
+    ns_e foo
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -error {unknown subcommand "foo": namespace ::ns_e does not export any commands}
+
+test namespace-9.11 {namespace ensemble -command} -setup {
+    namespace eval ns_e {
+        proc hello {} { return hello }
+        namespace ensemble create -command ::ns_greet
+    }
+} -body {
+    ns_greet hello
+} -cleanup {
+    namespace delete ns_e
+    rename ns_greet ""
+} -ok {hello}
+
+test namespace-9.12 {namespace ensemble -map} -setup {
+    namespace eval ns_e {
+        proc add {a b} { expr {$a + $b} }
+        namespace ensemble create -map {plus add length {string length} twice {add 2}}
+    }
+} -body {
+    list [ns_e plus 1 2] [ns_e len abcd] [ns_e twice 3] [catch {ns_e add 1 2} msg] $msg
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {3 4 5 1 {unknown or ambiguous subcommand "add": must be length, plus, or twice}}
+
+test namespace-9.13 {namespace ensemble -subcommands} -setup {
+    namespace eval ns_e {
+        proc add {a b} { expr {$a + $b} }
+        proc secret {} { return secret }
+        namespace ensemble create -subcommands {add}
+    }
+} -body {
+    list [ns_e add 1 2] [catch {ns_e secret} msg] $msg
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {3 1 {unknown or ambiguous subcommand "secret": must be add}}
+
+test namespace-9.14 {namespace ensemble sees commands defined later} -setup {
+    namespace eval ns_e {namespace ensemble create}
+    proc ns_e::later {} { return later }
+} -body {
+    ns_e later
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -ok {later}
+
+test namespace-9.15 {namespace ensemble subcommand errors propagate} -setup {
+    namespace eval ns_e {
+        proc fail {} { error "simulated error" }
+        namespace ensemble create
+    }
+} -body {
+    ns_e fail
+} -cleanup {
+    namespace delete ns_e
+    rename ns_e ""
+} -error {simulated error}
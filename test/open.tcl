@@ -0,0 +1,305 @@
+# Test Script: open, close, gets, read, eof, puts
+
+# The tests work in the scratch directory open_tmp, which each test creates and deletes.
+# The file lines.txt contains three lines; the last has no newline.
+proc open_setup {} {
+    file delete -force open_tmp
+    file mkdir open_tmp
+    set f [open open_tmp/lines.txt w]
+    puts $f "one"
+    puts $f "two"
+    puts -nonewline $f "thrée"
+    close $f
+}
+
+proc open_cleanup {} {
+    file delete -force open_tmp
+}
+
+test open-1.1 {open errors} {
+    open
+} -error {wrong # args: should be "open fileName ?access? ?permissions?"}
+
+test open-1.2 {open, nonexistent file} {
+    open nonesuch.txt
+} -error {couldn't open "nonesuch.txt": no such file or directory}
+
+test open-1.3 {open, bad access mode} -setup open_setup -body {
+    open open_tmp/lines.txt rw
+} -cleanup open_cleanup -error {illegal access mode "rw"}
+
+test open-2.1 {open, channel name} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    set result [regexp {^file[0-9]+$} $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok {1}
+
+test open-2.2 {open w, truncates} -setup open_setup -body {
+    close [open open_tmp/lines.txt w]
+    file size open_tmp/lines.txt
+} -cleanup open_cleanup -ok {0}
+
+test open-2.3 {open w, creates} -setup open_setup -body {
+    set f [open open_tmp/new.txt w]
+    puts $f "new"
+    close $f
+    file size open_tmp/new.txt
+} -cleanup open_cleanup -ok {4}
+
+test open-2.4 {open a, appends} -setup open_setup -body {
+    set f [open open_tmp/lines.txt a]
+    puts $f "!"
+    close $f
+    set f [open open_tmp/lines.txt]
+    set result [read $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "one\ntwo\nthrée!\n"
+
+test open-2.5 {open r+, reads and writes} -setup open_setup -body {
+    set f [open open_tmp/lines.txt r+]
+    gets $f
+    puts -nonewline $f "TWO"
+    close $f
+    set f [open open_tmp/lines.txt]
+    set result [read $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "one\nTWO\nthrée"
+
+test open-2.6 {open w+, reads and writes} -setup open_setup -body {
+    set f [open open_tmp/lines.txt w+]
+    puts $f "abc"
+    set result [list [gets $f] [eof $f]]
+    close $f
+    set result
+} -cleanup open_cleanup -ok {{} 1}
+
+test open-2.7 {open r, can't write} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    catch {puts $f "x"} result
+    close $f
+    string equal $result "channel \"$f\" wasn't opened for writing"
+} -cleanup open_cleanup -ok {1}
+
+test open-2.8 {open w, can't read} -setup open_setup -body {
+    set f [open open_tmp/lines.txt w]
+    set result [catch {gets $f}]
+    close $f
+    set result
+} -cleanup open_cleanup -ok {1}
+
+test open-2.9 {open, permissions} -setup open_setup -body {
+    close [open open_tmp/perm.txt w 0600]
+    file exists open_tmp/perm.txt
+} -cleanup open_cleanup -ok {1}
+
+test open-3.1 {open, command pipeline for reading} {
+    set f [open "|echo hello"]
+    set result [list [gets $f] [gets $f] [eof $f]]
+    close $f
+    set result
+} -ok {hello {} 1}
+
+test open-3.2 {open, command pipeline for writing} -setup open_setup -body {
+    set f [open "|tr a-z A-Z > open_tmp/out.txt" w]
+    puts $f "hello"
+    close $f
+    set f [open open_tmp/out.txt]
+    set result [read $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "HELLO\n"
+
+test open-3.3 {open, command pipeline for reading and writing} {
+    set f [open "|cat" r+]
+    puts $f "one"
+    flush $f
+    set result [gets $f]
+    puts $f "two"
+    flush $f
+    lappend result [gets $f]
+    close $f
+    set result
+} -ok {one two}
+
+test open-3.4 {open, command pipeline errors} -setup open_setup -body {
+    set result [list [catch {open "|echo hi > open_tmp/out.txt"} msg] $msg]
+    lappend result [catch {open "|cat < open_tmp/lines.txt" w} msg] $msg
+    lappend result [catch {open "|cat >@ stdout" w} msg] $msg
+    lappend result [catch {open "|sleep 1 &"} msg] $msg
+    lappend result [catch {open "|nonesuch_program"} msg] $msg
+} -cleanup open_cleanup -ok {1 {can't read output from command: standard output was redirected} 1 {can't write input to command: standard input was redirected} 1 {can't redirect a command pipeline to channel "stdout"} 1 {can't run a command pipeline opened as a channel in the background} 1 {couldn't execute "nonesuch_program": no such file or directory}}
+
+test open-3.5 {open, command pipeline with a literal input} {
+    set f [open {|cat << "one two" | wc -w}]
+    set result [string trim [read $f]]
+    close $f
+    set result
+} -ok {2}
+
+test close-1.1 {close errors} {
+    close
+} -error {wrong # args: should be "close channelId"}
+
+test close-1.2 {close, unknown channel} {
+    close nonesuch
+} -error {can not find channel named "nonesuch"}
+
+test close-1.3 {close, closed channel} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    close $f
+    catch {gets $f} result
+    string equal $result "can not find channel named \"$f\""
+} -cleanup open_cleanup -ok {1}
+
+test close-2.1 {close, command pipeline that fails} {
+    set f [open "|sh -c {exit 3}"]
+    set result [list [catch {close $f} msg] $msg [lindex $::errorCode 0] [lindex $::errorCode 2]]
+} -ok {1 {child process exited abnormally} CHILDSTATUS 3}
+
+test close-2.2 {close, command pipeline that writes to its standard error} {
+    set f [open "|sh -c {echo out; echo oops >&2}"]
+    set result [list [gets $f] [catch {close $f} msg] $msg]
+} -ok {out 1 oops}
+
+test close-2.3 {close, command pipeline whose standard error is redirected} {
+    set f [open "|sh -c {echo oops >&2} 2>@1"]
+    set result [list [gets $f] [catch {close $f} msg] $msg]
+} -ok {oops 0 {}}
+
+test gets-1.1 {gets errors} {
+    gets
+} -error {wrong # args: should be "gets channelId ?varName?"}
+
+test gets-1.2 {gets, unknown channel} {
+    gets nonesuch
+} -error {can not find channel named "nonesuch"}
+
+test gets-2.1 {gets, lines} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    set result [list [gets $f] [gets $f] [gets $f] [gets $f]]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "one two thrée {}"
+
+test gets-2.2 {gets, variable} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    set result {}
+    while {[gets $f line] >= 0} {
+        lappend result $line [string length $line]
+    }
+    lappend result [gets $f line] $line
+    close $f
+    set result
+} -cleanup open_cleanup -ok "one 3 two 3 thrée 5 -1 {}"
+
+# The gets stdin tests run a child molt with the given standard input, which reads it
+# with the script in stdin.tcl and writes the results.
+proc gets_stdin {script input} {
+    set f [open open_tmp/stdin.tcl w]
+    puts $f $script
+    close $f
+    exec [info nameofexecutable] shell open_tmp/stdin.tcl << $input
+}
+
+test gets-3.1 {gets stdin} -setup open_setup -body {
+    gets_stdin {
+        puts [list [gets stdin] [eof stdin] [gets stdin] [gets stdin] [eof stdin]]
+    } "one\ntwo"
+} -cleanup open_cleanup -ok {one 0 two {} 1}
+
+test gets-3.2 {gets stdin, variable} -setup open_setup -body {
+    gets_stdin {
+        set result {}
+        while {[gets stdin line] >= 0} {
+            lappend result $line [string length $line]
+        }
+        lappend result [gets stdin line] $line [eof stdin]
+        puts $result
+    } "one\n\nthrée\n"
+} -cleanup open_cleanup -ok "one 3 {} 0 thrée 5 -1 {} 1"
+
+test gets-3.3 {gets stdin, empty input} -setup open_setup -body {
+    gets_stdin {
+        puts [list [gets stdin line] $line [gets stdin] [eof stdin]]
+    } ""
+} -cleanup open_cleanup -ok {-1 {} {} 1}
+
+test read-1.1 {read errors} {
+    read
+} -error {wrong # args: should be "read channelId ?numChars?" or "read ?-nonewline? channelId"}
+
+test read-1.2 {read errors} {
+    read stdin -1
+} -error {expected non-negative integer but got "-1"}
+
+test read-2.1 {read, all} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    gets $f
+    set result [read $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "two\nthrée"
+
+test read-2.2 {read, count} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    set result [list [read $f 2] [read $f 0] [read $f 8] [read $f 2]]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "on {} {e\ntwo\nth} ré"
+
+test read-2.3 {read -nonewline} -setup open_setup -body {
+    set f [open open_tmp/lines.txt w]
+    puts $f "a\nb\n"
+    close $f
+    set f [open open_tmp/lines.txt]
+    set result [read -nonewline $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "a\nb\n"
+
+test eof-1.1 {eof errors} {
+    eof
+} -error {wrong # args: should be "eof channelId"}
+
+test eof-1.2 {eof} -setup open_setup -body {
+    set f [open open_tmp/lines.txt]
+    set result [eof $f]
+    read $f
+    lappend result [eof $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok {0 1}
+
+test puts-1.1 {puts errors} {
+    puts
+} -error {wrong # args: should be "puts ?-nonewline? ?channelId? string"}
+
+test puts-1.2 {puts errors} {
+    puts a b c
+} -error {bad argument "c": should be "nonewline"}
+
+test puts-1.3 {puts, unknown channel} {
+    puts nonesuch "text"
+} -error {can not find channel named "nonesuch"}
+
+test puts-1.4 {puts, read-only channel} {
+    puts stdin "text"
+} -error {channel "stdin" wasn't opened for writing}
+
+test puts-2.1 {puts -nonewline} -setup open_setup -body {
+    set f [open open_tmp/lines.txt w]
+    puts -nonewline $f "a"
+    puts $f "b"
+    puts $f "c" nonewline
+    close $f
+    set f [open open_tmp/lines.txt]
+    set result [read $f]
+    close $f
+    set result
+} -cleanup open_cleanup -ok "ab\nc"
+
+rename open_setup ""
+rename open_cleanup ""
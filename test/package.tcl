@@ -0,0 +1,213 @@
+# Test Script: package
+
+test package-1.1 {package errors} {
+    package
+} -error {wrong # args: should be "package subcommand ?arg ...?"}
+
+test package-1.2 {package errors} {
+    package nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be forget, ifneeded, names, present, provide, require, vcompare, versions, or vsatisfies}
+
+test package-2.1 {package provide, errors} {
+    package provide
+} -error {wrong # args: should be "package provide package ?version?"}
+
+test package-2.2 {package provide, bad version} {
+    package provide pkg-2.2 1.x
+} -error {expected version number but got "1.x"}
+
+test package-2.3 {package provide} -body {
+    set a [package provide pkg-2.3]
+    package provide pkg-2.3 1.2
+    list $a [package provide pkg-2.3]
+} -cleanup {
+    package forget pkg-2.3
+} -ok {{} 1.2}
+
+test package-2.4 {package provide, conflicting versions} -body {
+    package provide pkg-2.4 1.2
+    package provide pkg-2.4 1.2.0
+    package provide pkg-2.4 1.3
+} -cleanup {
+    package forget pkg-2.4
+} -error {conflicting versions provided for package "pkg-2.4": 1.2, then 1.3}
+
+test package-3.1 {package require, errors} {
+    package require
+} -error {wrong # args: should be "package require ?-exact? package ?requirement?"}
+
+test package-3.2 {package require, errors} {
+    package require -exact pkg-3.2
+} -error {wrong # args: should be "package require ?-exact? package ?requirement?"}
+
+test package-3.3 {package require, bad requirement} {
+    package require pkg-3.3 1.x
+} -error {expected version number but got "1.x"}
+
+test package-3.4 {package require, no such package} {
+    package require pkg-3.4
+} -error {can't find package pkg-3.4}
+
+test package-3.5 {package require, no such version} -body {
+    package ifneeded pkg-3.5 1.0 {package provide pkg-3.5 1.0}
+    package require pkg-3.5 2.0
+} -cleanup {
+    package forget pkg-3.5
+} -error {can't find package pkg-3.5 2.0}
+
+test package-3.6 {package require, loads latest version} -body {
+    package ifneeded pkg-3.6 1.9 {package provide pkg-3.6 1.9}
+    package ifneeded pkg-3.6 1.10 {package provide pkg-3.6 1.10}
+    package ifneeded pkg-3.6 2.0 {package provide pkg-3.6 2.0}
+    package require pkg-3.6
+} -cleanup {
+    package forget pkg-3.6
+} -ok {2.0}
+
+test package-3.7 {package require, loads latest satisfying version} -body {
+    package ifneeded pkg-3.7 1.9 {package provide pkg-3.7 1.9}
+    package ifneeded pkg-3.7 1.10 {package provide pkg-3.7 1.10}
+    package ifneeded pkg-3.7 2.0 {package provide pkg-3.7 2.0}
+    package require pkg-3.7 1.2
+} -cleanup {
+    package forget pkg-3.7
+} -ok {1.10}
+
+test package-3.8 {package require, -exact} -body {
+    package ifneeded pkg-3.8 1.9 {package provide pkg-3.8 1.9}
+    package ifneeded pkg-3.8 1.10 {package provide pkg-3.8 1.10}
+    package require -exact pkg-3.8 1.9
+} -cleanup {
+    package forget pkg-3.8
+} -ok {1.9}
+
+test package-3.9 {package require, loads once} -body {
+    set ::pkgCount 0
+    package ifneeded pkg-3.9 1.0 {incr ::pkgCount; package provide pkg-3.9 1.0}
+    package require pkg-3.9
+    package require pkg-3.9 1.0
+    set ::pkgCount
+} -cleanup {
+    package forget pkg-3.9
+    unset ::pkgCount
+} -ok {1}
+
+test package-3.10 {package require, provided version conflicts} -body {
+    package provide pkg-3.10 1.5
+    package require pkg-3.10 2.0
+} -cleanup {
+    package forget pkg-3.10
+} -error {version conflict for package "pkg-3.10": have 1.5, need 2.0}
+
+test package-3.11 {package require, -exact conflicts} -body {
+    package provide pkg-3.11 1.5
+    package require -exact pkg-3.11 1.6
+} -cleanup {
+    package forget pkg-3.11
+} -error {version conflict for package "pkg-3.11": have 1.5, need -exact 1.6}
+
+test package-3.12 {package require, script doesn't provide} -body {
+    package ifneeded pkg-3.12 1.0 {set x 1}
+    package require pkg-3.12
+} -cleanup {
+    package forget pkg-3.12
+} -error {attempt to provide package pkg-3.12 1.0 failed: no version of package pkg-3.12 provided}
+
+test package-3.13 {package require, script provides wrong version} -body {
+    package ifneeded pkg-3.13 1.0 {package provide pkg-3.13 1.1}
+    package require pkg-3.13
+} -cleanup {
+    package forget pkg-3.13
+} -error {attempt to provide package pkg-3.13 1.0 failed: package pkg-3.13 1.1 provided instead}
+
+test package-3.14 {package require, script error} -body {
+    package ifneeded pkg-3.14 1.0 {error "load failed"}
+    package require pkg-3.14
+} -cleanup {
+    package forget pkg-3.14
+} -error {load failed}
+
+test package-3.15 {package require, circular dependency} -body {
+    package ifneeded pkg-3.15 1.0 {package require pkg-3.15; package provide pkg-3.15 1.0}
+    package require pkg-3.15
+} -cleanup {
+    package forget pkg-3.15
+} -error {circular package dependency: attempt to provide pkg-3.15 1.0 requires pkg-3.15}
+
+test package-3.16 {package require, script runs at global scope} -body {
+    package ifneeded pkg-3.16 1.0 {set pkgGlobal 1; package provide pkg-3.16 1.0}
+    proc pkgProc {} {
+        package require pkg-3.16
+    }
+    pkgProc
+    set ::pkgGlobal
+} -cleanup {
+    package forget pkg-3.16
+    rename pkgProc ""
+    unset ::pkgGlobal
+} -ok {1}
+
+test package-4.1 {package present} -body {
+    package ifneeded pkg-4.1 1.0 {package provide pkg-4.1 1.0}
+    package present pkg-4.1
+} -cleanup {
+    package forget pkg-4.1
+} -error {package pkg-4.1 is not present}
+
+test package-4.2 {package present} -body {
+    package provide pkg-4.2 1.3
+    list [package present pkg-4.2] [package present pkg-4.2 1.2] [package present -exact pkg-4.2 1.3]
+} -cleanup {
+    package forget pkg-4.2
+} -ok {1.3 1.3 1.3}
+
+test package-5.1 {package ifneeded, errors} {
+    package ifneeded pkg-5.1
+} -error {wrong # args: should be "package ifneeded package version ?script?"}
+
+test package-5.2 {package ifneeded, bad version} {
+    package ifneeded pkg-5.2 x {}
+} -error {expected version number but got "x"}
+
+test package-5.3 {package ifneeded, query} -body {
+    package ifneeded pkg-5.3 1.0 {package provide pkg-5.3 1.0}
+    list [package ifneeded pkg-5.3 1.0] [package ifneeded pkg-5.3 2.0]
+} -cleanup {
+    package forget pkg-5.3
+} -ok {{package provide pkg-5.3 1.0} {}}
+
+test package-6.1 {package names, versions} -body {
+    package ifneeded pkg-6.1a 1.0 {}
+    package ifneeded pkg-6.1a 1.1 {}
+    package provide pkg-6.1b 2.0
+    set names [package names]
+    list [expr {[lsearch $names pkg-6.1a] >= 0}] [expr {[lsearch $names pkg-6.1b] >= 0}] [package versions pkg-6.1a] [package versions pkg-6.1b]
+} -cleanup {
+    package forget pkg-6.1a pkg-6.1b
+} -ok {1 1 {1.0 1.1} {}}
+
+test package-6.2 {package forget} {
+    package provide pkg-6.2 1.0
+    package forget pkg-6.2
+    package provide pkg-6.2
+} -ok {}
+
+test package-7.1 {package vcompare} {
+    list [package vcompare 1.10 1.9] [package vcompare 1.9 1.10] [package vcompare 1 1.0.0]
+} -ok {1 -1 0}
+
+test package-7.2 {package vcompare, bad version} {
+    package vcompare 1.0 a
+} -error {expected version number but got "a"}
+
+test package-8.1 {package vsatisfies} {
+    list [package vsatisfies 1.10 1.9] [package vsatisfies 2.0 1.9] [package vsatisfies 1.1 1.2]
+} -ok {1 0 0}
+
+test package-8.2 {package vsatisfies, ranges} {
+    list [package vsatisfies 3.0 1.2-] [package vsatisfies 1.5 1.2-2] [package vsatisfies 2.0 1.2-2] [package vsatisfies 1.2 1.2-1.2]
+} -ok {1 1 0 1}
+
+test package-8.3 {package vsatisfies, bad requirement} {
+    package vsatisfies 1.0 1.0-x
+} -error {expected version number but got "x"}
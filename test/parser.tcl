@@ -7,3 +7,11 @@ test parser-1.1 {parser bug fix} {
 
 # Tests for "not consuming ';' at end of command bug."
 test parser-1.2 {parser bug fix} {set a 1; set b 2} -ok 2
+
+test parser-2.1 {variable names with namespace separators} -setup {
+    namespace eval parser_ns {set x 1; set y(a) 2}
+} -body {
+    list $parser_ns::x $::parser_ns::x $parser_ns::y(a) $parser_ns::x:a
+} -cleanup {
+    namespace delete parser_ns
+} -ok {1 1 2 1:a}
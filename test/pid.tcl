@@ -0,0 +1,28 @@
+# Test Script: pid
+
+test pid-1.1 {pid errors} {
+    pid a b
+} -error {wrong # args: should be "pid ?channelId?"}
+
+test pid-1.2 {pid, nonexistent channel} {
+    pid nonesuch
+} -error {can not find channel named "nonesuch"}
+
+test pid-2.1 {pid, current process} {
+    expr {[pid] == [exec sh -c {echo $PPID}]}
+} -ok {1}
+
+test pid-2.2 {pid, integer} {
+    string is integer -strict [pid]
+} -ok {1}
+
+test pid-2.3 {pid, channel that isn't a pipeline} {
+    pid stdout
+} -ok {}
+
+test pid-2.4 {pid, command pipeline} {
+    set f [open "|cat | cat" r+]
+    set pids [pid $f]
+    close $f
+    list [llength $pids] [string is integer -strict [lindex $pids 0]] [expr {[lindex $pids 0] != [lindex $pids 1]}]
+} -ok {2 1 1}
@@ -0,0 +1,101 @@
+# Test Script: regexp command.
+
+test regexp-1.1 {regexp errors} {
+    regexp abc
+} -error {wrong # args: should be "regexp ?-option ...? exp string ?matchVar? ?subMatchVar ...?"}
+
+test regexp-1.2 {regexp errors, bad option} {
+    regexp -foo abc abc
+} -error {bad option "-foo": must be -all, -indices, -inline, -expanded, -line, -linestop, -lineanchor, -nocase, -start, or --}
+
+test regexp-1.3 {regexp errors, bad pattern} {
+    catch {regexp {a(} abc} result
+    string match "couldn't compile regular expression pattern: *" $result
+} -ok {1}
+
+test regexp-1.4 {regexp errors, -inline with variables} {
+    regexp -inline a abc x
+} -error {regexp match variables not allowed when using -inline}
+
+test regexp-1.5 {regexp errors, bad start index} {
+    regexp -start x a abc
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test regexp-2.1 {regexp, simple matches} {
+    list [regexp {b+} abbbc] [regexp {x} abc] [regexp {^a.c$} abc]
+} -ok {1 0 1}
+
+test regexp-2.2 {regexp, match variables} {
+    set result [regexp {(\w+)@(\w+)\.com} "mail joe@example.com now" all user host]
+    list $result $all $user $host
+} -ok {1 joe@example.com joe example}
+
+test regexp-2.3 {regexp, non-participating group is empty} {
+    set b unset
+    list [regexp {(a)|(b)} a all x b] $all $x $b
+} -ok {1 a a {}}
+
+test regexp-2.4 {regexp, extra variables are empty} {
+    list [regexp {a} abc all x] $all $x
+} -ok {1 a {}}
+
+test regexp-2.5 {regexp, no match leaves variables unchanged} {
+    set all before
+    list [regexp {x} abc all] $all
+} -ok {0 before}
+
+test regexp-3.1 {regexp -nocase} {
+    list [regexp {ABC} abc] [regexp -nocase {ABC} abc]
+} -ok {0 1}
+
+test regexp-3.2 {regexp -all} {
+    list [regexp -all {\d+} "1 22 333" last] $last
+} -ok {3 333}
+
+test regexp-3.3 {regexp -all, empty matches} {
+    list [regexp -all {a*} baaa] [regexp -all {x*} ""]
+} -ok {2 1}
+
+test regexp-3.4 {regexp -inline} {
+    regexp -inline {(\d+)-(\d+)} "call 555-1234 now"
+} -ok {555-1234 555 1234}
+
+test regexp-3.5 {regexp -all -inline} {
+    regexp -all -inline {\w+} "the quick fox"
+} -ok {the quick fox}
+
+test regexp-3.6 {regexp -inline, no match} {
+    regexp -inline {\d} abc
+} -ok {}
+
+test regexp-3.7 {regexp -indices} {
+    list [regexp -indices {(b+)(x)?} abbbc all sub none] $all $sub $none
+} -ok {1 {1 3} {1 3} {-1 -1}}
+
+test regexp-3.8 {regexp -start} {
+    list [regexp -start 2 -inline {\w} abcd] [regexp -start end -inline {\w} abcd]
+} -ok {c d}
+
+test regexp-3.9 {regexp -start, ^ doesn't match at the start index} {
+    regexp -start 1 {^b} abc
+} -ok {0}
+
+test regexp-3.10 {regexp, dot matches newline by default} {
+    list [regexp {a.b} "a\nb"] [regexp -linestop {a.b} "a\nb"]
+} -ok {1 0}
+
+test regexp-3.11 {regexp -lineanchor and -line} {
+    list [regexp {^b} "a\nb"] [regexp -lineanchor {^b} "a\nb"] [regexp -line {^b$} "a\nb"]
+} -ok {0 1 1}
+
+test regexp-3.12 {regexp -expanded} {
+    regexp -expanded -inline { (\d+) \s* - \s* (\d+) } "10 - 20"
+} -ok {{10 - 20} 10 20}
+
+test regexp-3.13 {regexp --} {
+    regexp -inline -- {-a} "x-a"
+} -ok {-a}
+
+test regexp-3.14 {regexp, non-ASCII indices} {
+    regexp -indices -inline {ö+} "größe"
+} -ok {{2 2}}
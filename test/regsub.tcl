@@ -0,0 +1,82 @@
+# Test Script: regsub command.
+
+test regsub-1.1 {regsub errors} {
+    regsub a b
+} -error {wrong # args: should be "regsub ?-option ...? exp string subSpec ?varName?"}
+
+test regsub-1.2 {regsub errors, too many args} {
+    regsub a b c d e
+} -error {wrong # args: should be "regsub ?-option ...? exp string subSpec ?varName?"}
+
+test regsub-1.3 {regsub errors, bad option} {
+    regsub -inline a b c
+} -error {bad option "-inline": must be -all, -expanded, -line, -linestop, -lineanchor, -nocase, -start, or --}
+
+test regsub-1.4 {regsub errors, bad pattern} {
+    catch {regsub {a(} abc x} result
+    string match "couldn't compile regular expression pattern: *" $result
+} -ok {1}
+
+test regsub-2.1 {regsub, first match} {
+    list [regsub {o} "foo boo" 0] [regsub {x} "foo" y]
+} -ok {{f0o boo} foo}
+
+test regsub-2.2 {regsub -all} {
+    regsub -all {o} "foo boo" 0
+} -ok {f00 b00}
+
+test regsub-2.3 {regsub, variable} {
+    list [regsub -all {o} "foo boo" 0 result] $result
+} -ok {4 {f00 b00}}
+
+test regsub-2.4 {regsub, variable, no match} {
+    list [regsub {x} "foo" y result] $result
+} -ok {0 foo}
+
+test regsub-2.5 {regsub -nocase} {
+    regsub -all -nocase {o} "fOo" 0
+} -ok {f00}
+
+test regsub-3.1 {regsub, & and \0} {
+    list [regsub {b+} abbbc <&>] [regsub {b+} abbbc {<\0>}]
+} -ok {a<bbb>c a<bbb>c}
+
+test regsub-3.2 {regsub, group references} {
+    regsub -all {(\w+)=(\w+)} "a=1 b=2" {\2=\1}
+} -ok {1=a 2=b}
+
+test regsub-3.3 {regsub, missing and non-participating groups are empty} {
+    list [regsub {(a)|(b)} b {[\1\2]}] [regsub {a} a {[\5]}]
+} -ok {{[b]} {[]}}
+
+test regsub-3.4 {regsub, escaped & and backslash} {
+    regsub {b} abc {\&\\&}
+} -ok {a&\bc}
+
+test regsub-3.5 {regsub, other backslashes are literal} {
+    regsub {b} abc {\n\x}
+} -ok {a\n\xc}
+
+test regsub-4.1 {regsub -all, empty matches} {
+    list [regsub -all {x*} abc -] [regsub -all {a*} baaa -] [regsub -all {x*} "" -]
+} -ok {-a-b-c- -b-- -}
+
+test regsub-4.2 {regsub -all, anchored} {
+    regsub -all {^} "a\nb" >
+} -ok ">a\nb"
+
+test regsub-4.3 {regsub -all -line, anchored} {
+    regsub -all -line {^} "a\nb" >
+} -ok ">a\n>b"
+
+test regsub-4.4 {regsub -start} {
+    regsub -all -start 3 {o} "foo boo" 0
+} -ok {foo b00}
+
+test regsub-4.5 {regsub --} {
+    regsub -- {-} "a-b" +
+} -ok {a+b}
+
+test regsub-4.6 {regsub, non-ASCII} {
+    regsub -all {ö} "größe öl" oe
+} -ok {groeße oel}
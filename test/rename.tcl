@@ -8,6 +8,20 @@ test rename-1.2 {rename no such command} {
     rename nonesuch newname
 } -error {can't rename "nonesuch": command doesn't exist}
 
+test rename-1.3 {rename, delete no such command} {
+    rename nonesuch ""
+} -error {can't delete "nonesuch": command doesn't exist}
+
+test rename-1.4 {rename onto existing command} -setup {
+    proc hello {} { return "hello" }
+    proc howdy {} { return "howdy" }
+} -body {
+    list [catch {rename hello howdy} result] $result [hello] [howdy]
+} -cleanup {
+    rename hello ""
+    rename howdy ""
+} -ok {1 {can't rename to "howdy": command already exists} hello howdy}
+
 test rename-2.1 {rename command to ""} -setup {
     proc hello {} { return "hello" }
 } -body {
@@ -23,3 +37,43 @@ test rename-2.2 {rename command} -setup {
 } -cleanup {
     rename howdy ""
 } -ok hello
+
+test rename-2.3 {rename built-in command} -body {
+    rename llength _llength
+    _llength {a b c}
+} -cleanup {
+    rename _llength llength
+} -ok {3}
+
+test rename-2.4 {rename, wrap and delegate} -setup {
+    rename llength _llength
+    proc llength {list} {
+        expr {[_llength $list] * 10}
+    }
+} -body {
+    llength {a b c}
+} -cleanup {
+    rename llength ""
+    rename _llength llength
+} -ok {30}
+
+test rename-2.5 {rename, proc keeps its arguments and body} -setup {
+    proc hello {name {greeting hello}} { return "$greeting, $name" }
+} -body {
+    rename hello howdy
+    list [howdy Fred] [howdy Fred howdy] [info commands hello] [info procs howdy]
+} -cleanup {
+    rename howdy ""
+} -ok {{hello, Fred} {howdy, Fred} {} howdy}
+
+test rename-2.6 {rename, recursive proc calls itself by its new name} -setup {
+    proc fact {n} {
+        if {$n <= 1} { return 1 }
+        expr {$n * [[lindex [info level 0] 0] [expr {$n - 1}]]}
+    }
+} -body {
+    rename fact factorial
+    factorial 5
+} -cleanup {
+    rename factorial ""
+} -ok {120}
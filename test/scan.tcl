@@ -0,0 +1,118 @@
+# Test Script: scan command.
+#
+# The scanner itself is tested in detail by the unit tests in scan.rs; these tests
+# check the command and the most common cases against Tcl 8.6 output.
+
+test scan-1.1 {scan errors} {
+    scan abc
+} -error {wrong # args: should be "scan string format ?varName ...?"}
+
+test scan-1.2 {scan errors, bad conversion} {
+    scan abc %q
+} -error {bad scan conversion character "q"}
+
+test scan-1.3 {scan errors, unmatched bracket} {
+    scan abc {%[abc}
+} -error {unmatched [ in format string}
+
+test scan-1.4 {scan errors, variable count} {
+    scan "1 2" "%d %d" a
+} -error {different numbers of variable names and field specifiers}
+
+test scan-1.5 {scan errors, unassigned variable} {
+    scan "1 2" {%2$d} a b
+} -error {variable is not assigned by any conversion specifiers}
+
+test scan-1.6 {scan errors, %c width} {
+    scan abc %2c
+} -error {field width may not be specified in %c conversion}
+
+test scan-1.7 {scan errors, huge field width} {
+    scan abc %99999999999999999999s
+} -error {field width too large}
+
+test scan-1.8 {scan errors, huge XPG indices} {
+    list [catch {scan abc {%99999999999999999999$s}} msg] $msg [catch {scan abc {%99999999999$s}} msg] $msg
+} -ok {1 {variable is not assigned by any conversion specifiers} 1 {variable is not assigned by any conversion specifiers}}
+
+test scan-1.9 {scan errors, huge XPG index with variables} {
+    scan abc {%99999999999999999999$s} a
+} -error {"%n$" argument index out of range}
+
+test scan-2.1 {scan, inline} {
+    scan "12 abc 3.5" "%d %s %f"
+} -ok {12 abc 3.5}
+
+test scan-2.2 {scan, inline, partial match} {
+    scan "12 abc" "%d %d"
+} -ok {12 {}}
+
+test scan-2.3 {scan, inline, empty input} {
+    scan "" "%d"
+} -ok {}
+
+test scan-3.1 {scan, variables} {
+    list [scan "12 abc 3.5" "%d %s %f" a b c] $a $b $c
+} -ok {3 12 abc 3.5}
+
+test scan-3.2 {scan, partial match stops} {
+    set b unchanged
+    list [scan "12 abc" "%d %d" a b] $a $b
+} -ok {1 12 unchanged}
+
+test scan-3.3 {scan, no match} {
+    scan "abc" "%d" a
+} -ok {0}
+
+test scan-3.4 {scan, empty input} {
+    scan "   " "%d" a
+} -ok {-1}
+
+test scan-4.1 {scan, integers} {
+    scan "-42 ff 17 0x10 010" "%d %x %o %i %i"
+} -ok {-42 255 15 16 8}
+
+test scan-4.2 {scan, field widths} {
+    scan "12345abcdef" "%3d%d%2s%s"
+} -ok {123 45 ab cdef}
+
+test scan-4.3 {scan, floats} {
+    scan "1.5 -2e3 .25" "%f %e %g"
+} -ok {1.5 -2000 0.25}
+
+test scan-4.4 {scan, characters} {
+    scan "Hi there" "%c%c%c"
+} -ok {72 105 32}
+
+test scan-5.1 {scan, character sets} {
+    scan "abc123def" {%[a-z]%[0-9]%s}
+} -ok {abc 123 def}
+
+test scan-5.2 {scan, negated character sets} {
+    scan "key = some value" {%[^=]= %[^z]}
+} -ok {{key } {some value}}
+
+test scan-5.3 {scan, bracket in character set} {
+    scan "\]\]x" {%[]]%s} a b
+    set a
+} -ok {]]}
+
+test scan-6.1 {scan, whitespace in format} {
+    scan "1\n\t  2" "%d %d"
+} -ok {1 2}
+
+test scan-6.2 {scan, literals} {
+    scan "10:20:30" "%d:%d:%d" h m s
+} -ok {3}
+
+test scan-6.3 {scan, literal mismatch} {
+    scan "10-20" "%d:%d"
+} -ok {10 {}}
+
+test scan-6.4 {scan, suppression and %n} {
+    scan "1 2 3" "%d %*d %d%n"
+} -ok {1 3 5}
+
+test scan-6.5 {scan, XPG specifiers} {
+    list [scan "1 2" {%2$d %1$d} a b] $a $b
+} -ok {2 2 1}
@@ -0,0 +1,148 @@
+# Test Script: seek, tell, flush
+
+# The tests work in the scratch directory seek_tmp, which each test creates and deletes.
+# The file data.txt contains "0123456789".
+proc seek_setup {} {
+    file delete -force seek_tmp
+    file mkdir seek_tmp
+    set f [open seek_tmp/data.txt w]
+    puts -nonewline $f "0123456789"
+    close $f
+}
+
+proc seek_cleanup {} {
+    file delete -force seek_tmp
+}
+
+test seek-1.1 {seek errors} {
+    seek
+} -error {wrong # args: should be "seek channelId offset ?origin?"}
+
+test seek-1.2 {seek errors} {
+    seek nonesuch 0
+} -error {can not find channel named "nonesuch"}
+
+test seek-1.3 {seek errors} {
+    seek stdin abc
+} -error {expected integer but got "abc"}
+
+test seek-1.4 {seek errors} {
+    seek stdin 0 middle
+} -error {bad origin "middle": must be start, current, or end}
+
+test seek-1.5 {seek, non-seekable channel} {
+    seek stdin 0
+} -error {error during seek on "stdin": illegal seek}
+
+test seek-1.6 {seek, negative offset} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt]
+    set result [catch {seek $f -1} msg]
+    close $f
+    list $result [string equal $msg "error during seek on \"$f\": invalid argument"]
+} -cleanup seek_cleanup -ok {1 1}
+
+test seek-2.1 {seek, default origin} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt]
+    set result [list [seek $f 4] [read $f 2]]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {{} 45}
+
+test seek-2.2 {seek, origins} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt]
+    set result [list [read $f 2]]
+    seek $f 3 current
+    lappend result [read $f 1]
+    seek $f -2 end
+    lappend result [read $f]
+    seek $f 1 start
+    lappend result [read $f 1]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {01 5 89 1}
+
+test seek-2.3 {seek, clears eof} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt]
+    read $f
+    set result [list [eof $f]]
+    seek $f 0
+    lappend result [eof $f] [read $f 3]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {1 0 012}
+
+test seek-2.4 {seek, writes buffered output} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt r+]
+    puts -nonewline $f "ab"
+    seek $f 8
+    puts -nonewline $f "yz"
+    seek $f 0
+    set result [read $f]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {ab234567yz}
+
+test tell-1.1 {tell errors} {
+    tell
+} -error {wrong # args: should be "tell channelId"}
+
+test tell-1.2 {tell errors} {
+    tell nonesuch
+} -error {can not find channel named "nonesuch"}
+
+test tell-2.1 {tell, non-seekable channel} {
+    tell stdin
+} -ok {-1}
+
+test tell-2.2 {tell, after reading} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt]
+    set result [list [tell $f]]
+    read $f 3
+    lappend result [tell $f]
+    read $f
+    lappend result [tell $f]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {0 3 10}
+
+test tell-2.3 {tell, after buffered writing} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt a]
+    puts -nonewline $f "abc"
+    set result [tell $f]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {13}
+
+test tell-2.4 {tell, multibyte characters} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt w+]
+    puts -nonewline $f "éé"
+    set result [list [tell $f]]
+    seek $f 2
+    lappend result [read $f] [tell $f]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {4 é 4}
+
+test flush-1.1 {flush errors} {
+    flush
+} -error {wrong # args: should be "flush channelId"}
+
+test flush-1.2 {flush errors} {
+    flush stdin
+} -error {channel "stdin" wasn't opened for writing}
+
+test flush-2.1 {flush} -setup seek_setup -body {
+    set f [open seek_tmp/data.txt w]
+    puts -nonewline $f "abc"
+    set result [list [file size seek_tmp/data.txt]]
+    lappend result [flush $f] [file size seek_tmp/data.txt]
+    close $f
+    set result
+} -cleanup seek_cleanup -ok {0 {} 3}
+
+test flush-2.2 {flush stdout} {
+    flush stdout
+} -ok {}
+
+rename seek_setup ""
+rename seek_cleanup ""
@@ -0,0 +1,163 @@
+# Test Script: socket
+
+# Each test opens a server socket on a free port on the loopback address; its command
+# saves the connection's channel and details in ::sock_conn.  sock_connect opens a client
+# connection to the server and waits for it to be accepted, returning the client channel.
+proc sock_setup {} {
+    set ::sock_server [socket -server sock_accept -myaddr 127.0.0.1 0]
+    set ::sock_port [lindex [fconfigure $::sock_server -sockname] 2]
+}
+
+proc sock_accept {chan addr port} {
+    set ::sock_conn [list $chan $addr $port]
+}
+
+proc sock_connect {} {
+    set client [socket 127.0.0.1 $::sock_port]
+    vwait ::sock_conn
+    return $client
+}
+
+proc sock_cleanup {} {
+    close $::sock_server
+
+    if {[info exists ::sock_conn]} {
+        catch {close [lindex $::sock_conn 0]}
+        unset ::sock_conn
+    }
+
+    unset ::sock_server ::sock_port
+}
+
+test socket-1.1 {socket errors} {
+    socket
+} -error {wrong # args: should be "socket host port" or "socket -server command ?-myaddr addr? port"}
+
+test socket-1.2 {socket errors} {
+    socket -server
+} -error {no argument given for "-server" option}
+
+test socket-1.3 {socket errors} {
+    socket -async localhost 80
+} -error {bad option "-async": must be -myaddr or -server}
+
+test socket-1.4 {socket errors} {
+    socket -server foo
+} -error {wrong # args: should be "socket -server command ?-myaddr addr? port"}
+
+test socket-1.5 {socket errors} {
+    socket localhost 70000
+} -error {expected port number between 0 and 65535 but got "70000"}
+
+test socket-1.6 {socket errors} {
+    socket localhost http
+} -error {expected integer but got "http"}
+
+test socket-1.7 {socket, connection refused} -body {
+    set server [socket -server sock_accept -myaddr 127.0.0.1 0]
+    set port [lindex [fconfigure $server -sockname] 2]
+    close $server
+    socket 127.0.0.1 $port
+} -error {couldn't open socket: connection refused}
+
+test socket-1.8 {socket, port in use} -setup sock_setup -body {
+    socket -server sock_accept -myaddr 127.0.0.1 $::sock_port
+} -cleanup sock_cleanup -error {couldn't open socket: address already in use}
+
+test socket-2.1 {socket -server, accepting a connection} -setup sock_setup -body {
+    set client [sock_connect]
+    lassign $::sock_conn chan addr port
+    set result [list [string match sock* $chan] $addr]
+    lappend result [expr {$port == [lindex [fconfigure $client -sockname] 2]}]
+    lappend result [expr {[lindex [fconfigure $chan -peername] 2] == $port}]
+    close $client
+    set result
+} -cleanup sock_cleanup -ok {1 127.0.0.1 1 1}
+
+test socket-2.2 {socket, puts and gets} -setup sock_setup -body {
+    set client [sock_connect]
+    set chan [lindex $::sock_conn 0]
+    puts $client "hello"
+    flush $client
+    set result [list [gets $chan]]
+    puts $chan "goodbye"
+    flush $chan
+    lappend result [gets $client]
+    close $client
+    set result
+} -cleanup sock_cleanup -ok {hello goodbye}
+
+test socket-2.3 {socket, default translation} -setup sock_setup -body {
+    set client [sock_connect]
+    set chan [lindex $::sock_conn 0]
+    fconfigure $chan -translation lf
+    set result [list [fconfigure $client -translation]]
+    puts $client "line"
+    close $client
+    lappend result [string map {\r <CR> \n <LF>} [read $chan]]
+} -cleanup sock_cleanup -ok {{auto crlf} line<CR><LF>}
+
+test socket-2.4 {socket, eof on remote close} -setup sock_setup -body {
+    set client [sock_connect]
+    set chan [lindex $::sock_conn 0]
+    puts $client "last"
+    close $client
+    list [gets $chan] [eof $chan] [gets $chan] [eof $chan]
+} -cleanup sock_cleanup -ok {last 0 {} 1}
+
+test socket-2.5 {socket, non-blocking reads} -setup sock_setup -body {
+    set client [sock_connect]
+    set chan [lindex $::sock_conn 0]
+    fconfigure $chan -blocking 0
+    set result [list [fconfigure $chan -blocking] [gets $chan] [eof $chan]]
+    puts -nonewline $client "ab"
+    flush $client
+    after 50
+    lappend result [gets $chan line] [read $chan]
+    puts $client "c"
+    flush $client
+    after 50
+    lappend result [gets $chan line] $line
+    close $client
+    set result
+} -cleanup sock_cleanup -ok {0 {} 0 -1 ab 1 c}
+
+test socket-2.6 {socket, server channel} -setup sock_setup -body {
+    set result [list [lsort [dict keys [fconfigure $::sock_server]]]]
+    lappend result [catch {gets $::sock_server} msg]
+    lappend result [string equal $msg "channel \"$::sock_server\" wasn't opened for reading"]
+} -cleanup sock_cleanup -ok {{-blocking -buffering -buffersize -encoding -sockname -translation} 1 1}
+
+test socket-2.7 {socket, read-only options} -setup sock_setup -body {
+    set client [sock_connect]
+    set result [llength [fconfigure $client -peername]]
+    lappend result [catch {fconfigure $client -peername foo} msg] $msg
+    close $client
+    set result
+} -cleanup sock_cleanup -ok {3 1 {bad option "-peername": should be one of -blocking, -buffering, -buffersize, -encoding, or -translation}}
+
+test socket-2.8 {socket, errors in the server command} -setup {
+    sock_setup
+    proc bgerror {msg} {
+        set ::sock_conn [list {} $msg]
+    }
+    proc sock_accept {chan addr port} {
+        close $chan
+        error "rejected"
+    }
+} -body {
+    set client [sock_connect]
+    close $client
+    lindex $::sock_conn 1
+} -cleanup {
+    sock_cleanup
+    rename bgerror ""
+    proc sock_accept {chan addr port} {
+        set ::sock_conn [list $chan $addr $port]
+    }
+} -ok {rejected}
+
+rename sock_setup ""
+rename sock_accept ""
+rename sock_connect ""
+rename sock_cleanup ""
@@ -0,0 +1,103 @@
+# Test Script: source
+
+# The tests work in the scratch directory source_tmp, which each test creates and deletes.
+# source_write writes a script to a file in it.
+proc source_setup {} {
+    file delete -force source_tmp
+    file mkdir source_tmp
+}
+
+proc source_cleanup {} {
+    file delete -force source_tmp
+}
+
+proc source_write {name script} {
+    set f [open source_tmp/$name w]
+    puts $f $script
+    close $f
+}
+
+test source-1.1 {source errors} {
+    source
+} -error {wrong # args: should be "source filename"}
+
+test source-1.2 {source, nonexistent file} {
+    source nonesuch.tcl
+} -error {couldn't read file "nonesuch.tcl": no such file or directory}
+
+test source-1.3 {source, directory} -setup source_setup -body {
+    source source_tmp
+} -cleanup source_cleanup -error {couldn't read file "source_tmp": is a directory}
+
+test source-2.1 {source, result} -setup source_setup -body {
+    source_write a.tcl {set x 1; expr {$x + 1}}
+    source source_tmp/a.tcl
+} -cleanup source_cleanup -ok {2}
+
+test source-2.2 {source, empty file} -setup source_setup -body {
+    source_write a.tcl ""
+    source source_tmp/a.tcl
+} -cleanup source_cleanup -ok {}
+
+test source-2.3 {source, current scope level} -setup {
+    source_setup
+    source_write a.tcl {set y [expr {$x * 2}]}
+    proc myproc {} {
+        set x 21
+        source source_tmp/a.tcl
+        set y
+    }
+} -body {
+    list [myproc] [info exists y]
+} -cleanup {
+    rename myproc ""
+    source_cleanup
+} -ok {42 0}
+
+test source-2.4 {source, return ends only the file} -setup {
+    source_setup
+    source_write a.tcl "set z before\nreturn early\nset z after"
+    proc myproc {} {
+        set result [source source_tmp/a.tcl]
+        list $result $z continued
+    }
+} -body {
+    myproc
+} -cleanup {
+    rename myproc ""
+    source_cleanup
+} -ok {early before continued}
+
+test source-2.5 {source, error} -setup source_setup -body {
+    source_write a.tcl "set a 1\nerror oops"
+    source source_tmp/a.tcl
+} -cleanup source_cleanup -error {oops}
+
+test source-2.6 {source, error info} -setup source_setup -body {
+    source_write a.tcl "set a 1\nerror oops"
+    catch {source source_tmp/a.tcl} result opts
+    string match {*(file "source_tmp/a.tcl" line 2)*} [dict get $opts -errorinfo]
+} -cleanup source_cleanup -ok {1}
+
+test source-2.7 {source, error code} -setup source_setup -body {
+    source_write a.tcl {throw {MY CODE} oops}
+    catch {source source_tmp/a.tcl} result opts
+    dict get $opts -errorcode
+} -cleanup source_cleanup -ok {MY CODE}
+
+test source-3.1 {source, info script} -setup source_setup -body {
+    source_write a.tcl {info script}
+    list [source source_tmp/a.tcl] [regexp {source\.tcl$} [info script]]
+} -cleanup source_cleanup -ok {source_tmp/a.tcl 1}
+
+test source-3.2 {source, nested info script} -setup source_setup -body {
+    source_write a.tcl {list [info script] [source source_tmp/b.tcl] [info script]}
+    source_write b.tcl {info script}
+    source source_tmp/a.tcl
+} -cleanup source_cleanup -ok {source_tmp/a.tcl source_tmp/b.tcl source_tmp/a.tcl}
+
+test source-3.3 {source, info script restored after error} -setup source_setup -body {
+    source_write a.tcl {error oops}
+    catch {source source_tmp/a.tcl}
+    regexp {source\.tcl$} [info script]
+} -cleanup source_cleanup -ok {1}
@@ -0,0 +1,58 @@
+# Test Script: split command.
+
+test split-1.1 {split errors} {
+    split
+} -error {wrong # args: should be "split string ?splitChars?"}
+
+test split-1.2 {split errors} {
+    split a b c
+} -error {wrong # args: should be "split string ?splitChars?"}
+
+test split-2.1 {split, default whitespace} {
+    split "a b\tc\nd"
+} -ok {a b c d}
+
+test split-2.2 {split, each whitespace character is a delimiter} {
+    split "a  b "
+} -ok {a {} b {}}
+
+test split-2.3 {split, leading delimiter} {
+    split " a"
+} -ok {{} a}
+
+test split-3.1 {split, split characters} {
+    split "a,b;c" ",;"
+} -ok {a b c}
+
+test split-3.2 {split, adjacent split characters} {
+    split "a,,b," ,
+} -ok {a {} b {}}
+
+test split-3.3 {split, only split characters} {
+    split ",," ,
+} -ok {{} {} {}}
+
+test split-3.4 {split, no split characters in string} {
+    split "a b" ,
+} -ok {{a b}}
+
+test split-4.1 {split, empty splitChars} {
+    split "abc" {}
+} -ok {a b c}
+
+test split-4.2 {split, empty splitChars with spaces} {
+    split "a b" {}
+} -ok {a { } b}
+
+test split-5.1 {split, empty string} {
+    list [split ""] [split "" ,] [split "" {}]
+} -ok {{} {} {}}
+
+test split-5.2 {split, elements are quoted} {
+    set x [split "a\{b,c d,\$e" ,]
+    list [llength $x] [expr {[lindex $x 0] eq "a\{b"}] [lindex $x 1] [expr {[lindex $x 2] eq "\$e"}]
+} -ok {3 1 {c d} 1}
+
+test split-5.3 {split, round-trip with join} {
+    join [split "a:b::c" :] :
+} -ok {a:b::c}
@@ -0,0 +1,732 @@
+# Test Script: string command.
+
+test string-1.1 {string errors} {
+    string
+} -error {wrong # args: should be "string subcommand ?arg ...?"}
+
+test string-1.2 {string errors} {
+    string nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be cat, compare, equal, first, index, is, last, length, map, match, range, repeat, replace, reverse, tolower, totitle, toupper, trim, trimleft, trimright, wordend, or wordstart}
+
+#-------------------------------------------------------------------------
+# string length
+
+test string-length-1.1 {string length errors} {
+    string length
+} -error {wrong # args: should be "string length string"}
+
+test string-length-2.1 {string length} {
+    list [string length ""] [string length abc] [string length "a b c"]
+} -ok {0 3 5}
+
+test string-length-2.2 {string length, characters not bytes} {
+    string length "héllo"
+} -ok {5}
+
+#-------------------------------------------------------------------------
+# string map
+
+test string-map-1.1 {string map errors} {
+    string map {a b}
+} -error {wrong # args: should be "string map ?-nocase? charMap string"}
+
+test string-map-1.2 {string map errors} {
+    string map -frob {a b} abc
+} -error {bad option "-frob": must be -nocase}
+
+test string-map-1.3 {string map errors} {
+    string map {a b c} abc
+} -error {char map list unbalanced}
+
+test string-map-2.1 {string map, basic} {
+    string map {a 1 b 2} "abcab"
+} -ok {12c12}
+
+test string-map-2.2 {string map, empty map and string} {
+    list [string map {} abc] [string map {a b} ""]
+} -ok {abc {}}
+
+test string-map-2.3 {string map, empty replacement deletes} {
+    string map {- {}} "a-b-c"
+} -ok {abc}
+
+test string-map-2.4 {string map, empty keys are ignored} {
+    string map {{} X a 1} "abc"
+} -ok {1bc}
+
+test string-map-2.5 {string map, multicharacter keys and values} {
+    string map {foo bar. hello hi} "hello foo!"
+} -ok {hi bar.!}
+
+test string-map-3.1 {string map, earlier pairs win} {
+    list [string map {ab X abc Y} "abcd"] [string map {abc Y ab X} "abcd"]
+} -ok {Xcd Yd}
+
+test string-map-3.2 {string map, no rescan of replacements} {
+    string map {a b b a} "aabb"
+} -ok {bbaa}
+
+test string-map-3.3 {string map, no restart inside the replacement} {
+    string map {ab abab} "abab"
+} -ok {abababab}
+
+test string-map-3.4 {string map, overlapping occurrences} {
+    string map {aa X} "aaaaa"
+} -ok {XXa}
+
+test string-map-4.1 {string map -nocase} {
+    string map -nocase {ab X} "AbaBab"
+} -ok {XXX}
+
+test string-map-4.2 {string map -nocase, replacement is verbatim} {
+    string map -nocase {hello Hi} "HELLO hello"
+} -ok {Hi Hi}
+
+test string-map-4.3 {string map, case-sensitive by default} {
+    string map {ab X} "AbaBab"
+} -ok {AbaBX}
+
+test string-map-4.4 {string map, non-ASCII} {
+    string map {é e ü ue} "café München"
+} -ok {cafe Muenchen}
+
+#-------------------------------------------------------------------------
+# string match
+
+test string-match-1.1 {string match errors} {
+    string match a
+} -error {wrong # args: should be "string match ?-nocase? pattern string"}
+
+test string-match-1.2 {string match errors} {
+    string match -frob a a
+} -error {bad option "-frob": must be -nocase}
+
+test string-match-2.1 {string match, literal} {
+    list [string match abc abc] [string match abc abd] [string match "" ""] [string match a ""]
+} -ok {1 0 1 0}
+
+test string-match-2.2 {string match, star} {
+    list [string match a* abc] [string match *c abc] [string match a*c abbbc] [string match a*c abd]
+} -ok {1 1 1 0}
+
+test string-match-2.3 {string match, pattern ending in star} {
+    list [string match abc* abc] [string match abc* abcdef] [string match abc* ab] [string match * ""]
+} -ok {1 1 0 1}
+
+test string-match-2.4 {string match, question mark} {
+    list [string match a?c abc] [string match a?c ac] [string match ??? abc]
+} -ok {1 0 1}
+
+test string-match-3.1 {string match, character classes} {
+    list [string match {[abc]x} bx] [string match {[abc]x} dx]
+} -ok {1 0}
+
+test string-match-3.2 {string match, character ranges} {
+    list [string match {[a-z]*} hello] [string match {[a-z]*} Hello] [string match {[0-9][0-9]} 42]
+} -ok {1 0 1}
+
+test string-match-3.3 {string match, several ranges in a class} {
+    list [string match {[a-zA-Z_]} Q] [string match {[a-zA-Z_]} _] [string match {[a-zA-Z_]} 5]
+} -ok {1 1 0}
+
+test string-match-4.1 {string match, escaped star is literal} {
+    list [string match {a\*c} a*c] [string match {a\*c} abc] [string match {a\*c} ac]
+} -ok {1 0 0}
+
+test string-match-4.2 {string match, escaped specials} {
+    list [string match {\?} ?] [string match {\?} x] [string match {\[a\]} {[a]}]
+} -ok {1 0 1}
+
+test string-match-5.1 {string match -nocase} {
+    list [string match -nocase ABC abc] [string match -nocase {[a-c]*} Bob] [string match ABC abc]
+} -ok {1 1 0}
+
+test string-match-5.2 {string match, non-ASCII} {
+    list [string match h?llo héllo] [string match -nocase É* école]
+} -ok {1 1}
+
+#-------------------------------------------------------------------------
+# string repeat
+
+test string-repeat-1.1 {string repeat errors} {
+    string repeat abc
+} -error {wrong # args: should be "string repeat string count"}
+
+test string-repeat-1.2 {string repeat errors} {
+    string repeat abc x
+} -error {expected integer but got "x"}
+
+test string-repeat-1.3 {string repeat errors} {
+    string repeat abc -1
+} -error {bad count "-1": must be integer >= 0}
+
+test string-repeat-1.4 {string repeat errors, result too large} {
+    list [catch {string repeat abc 9223372036854775807} msg] $msg [catch {string repeat ab 2000000000} msg] $msg
+} -ok {1 {max size for a Tcl value exceeded} 1 {max size for a Tcl value exceeded}}
+
+test string-repeat-2.1 {string repeat} {
+    list [string repeat abc 3] [string repeat - 1] [string repeat "a b" 2]
+} -ok {abcabcabc - {a ba b}}
+
+test string-repeat-2.2 {string repeat, zero count and empty string} {
+    list [string repeat abc 0] [string repeat "" 5]
+} -ok {{} {}}
+
+test string-repeat-2.3 {string repeat, long result} {
+    string length [string repeat "0123456789" 1000]
+} -ok {10000}
+
+test string-repeat-2.4 {string repeat, non-ASCII} {
+    set x [string repeat "héé" 3]
+    list $x [string length $x]
+} -ok {hééhééhéé 9}
+
+#-------------------------------------------------------------------------
+# string reverse
+
+test string-reverse-1.1 {string reverse errors} {
+    string reverse
+} -error {wrong # args: should be "string reverse string"}
+
+test string-reverse-1.2 {string reverse errors} {
+    string reverse a b
+} -error {wrong # args: should be "string reverse string"}
+
+test string-reverse-2.1 {string reverse} {
+    list [string reverse abc] [string reverse a] [string reverse ""] [string reverse "a b "]
+} -ok {cba a {} { b a}}
+
+test string-reverse-2.2 {string reverse, non-ASCII} {
+    set x [string reverse "héllo wörld"]
+    list $x [string length $x]
+} -ok {{dlröw olléh} 11}
+
+test string-reverse-2.3 {string reverse, round-trip} {
+    string reverse [string reverse "日本語 text"]
+} -ok {日本語 text}
+
+#-------------------------------------------------------------------------
+# string trim, trimleft, trimright
+
+test string-trim-1.1 {string trim errors} {
+    string trim
+} -error {wrong # args: should be "string trim string ?chars?"}
+
+test string-trim-1.2 {string trim errors} {
+    list [catch {string trimleft a b c} msg] $msg
+} -ok {1 {wrong # args: should be "string trimleft string ?chars?"}}
+
+test string-trim-1.3 {string trim errors} {
+    list [catch {string trimright} msg] $msg
+} -ok {1 {wrong # args: should be "string trimright string ?chars?"}}
+
+test string-trim-2.1 {string trim, default whitespace} {
+    string trim " \t\n\r\v\f abc \t\n\r\v\f "
+} -ok {abc}
+
+test string-trim-2.2 {string trim, internal whitespace retained} {
+    string trim "  a  b  "
+} -ok {a  b}
+
+test string-trim-2.3 {string trimleft and trimright, default whitespace} {
+    list [string trimleft "  abc  "] [string trimright "  abc  "]
+} -ok {{abc  } {  abc}}
+
+test string-trim-3.1 {string trim, custom chars} {
+    list [string trim "xxabcxy" xy] [string trimleft "xxabcxy" xy] [string trimright "xxabcxy" xy]
+} -ok {abc abcxy xxabc}
+
+test string-trim-3.2 {string trim, chars match individually} {
+    string trim "abcba" ab
+} -ok {c}
+
+test string-trim-3.3 {string trim, empty chars trims nothing} {
+    string trim "  abc  " ""
+} -ok {  abc  }
+
+test string-trim-4.1 {string trim, everything trimmed} {
+    list [string trim "   "] [string trim "xyx" xy] [string trimleft "xyz" zyx] [string trimright "" x]
+} -ok {{} {} {} {}}
+
+test string-trim-4.2 {string trim, non-ASCII} {
+    list [string trim "ééaéé" é] [string trimleft "«abc»" «»] [string trimright "«abc»" «»]
+} -ok {a abc» «abc}
+
+#-------------------------------------------------------------------------
+# string tolower, toupper, totitle
+
+test string-case-1.1 {string tolower errors} {
+    string tolower
+} -error {wrong # args: should be "string tolower string ?first? ?last?"}
+
+test string-case-1.2 {string toupper errors} {
+    string toupper abc 1 2 3
+} -error {wrong # args: should be "string toupper string ?first? ?last?"}
+
+test string-case-1.3 {string totitle errors} {
+    string totitle abc x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-case-2.1 {string tolower} {
+    list [string tolower "Hello WORLD"] [string tolower ""] [string tolower 123]
+} -ok {{hello world} {} 123}
+
+test string-case-2.2 {string toupper} {
+    list [string toupper "Hello world"] [string toupper ""]
+} -ok {{HELLO WORLD} {}}
+
+test string-case-2.3 {string totitle} {
+    list [string totitle "hELLO wORLD"] [string totitle ""] [string totitle a]
+} -ok {{Hello world} {} A}
+
+test string-case-3.1 {string case, first only} {
+    list [string toupper "abcde" 1] [string tolower "ABCDE" end] [string totitle "abcde" 2]
+} -ok {aBcde ABCDe abCde}
+
+test string-case-3.2 {string case, first and last} {
+    list [string toupper "abcde" 1 3] [string tolower "ABCDE" 0 end-2] [string totitle "hELLO wORLD" 6 end]
+} -ok {aBCDe abcDE {hELLO World}}
+
+test string-case-3.3 {string case, out of range indices clamp} {
+    list [string toupper "abc" -5 1] [string toupper "abc" 1 10] [string toupper "abc" 10 20]
+} -ok {ABc aBC abc}
+
+test string-case-3.4 {string case, first > last} {
+    string toupper "abc" 2 1
+} -ok {abc}
+
+test string-case-4.1 {string case, non-ASCII} {
+    list [string toupper "héllo"] [string tolower "ÉCOLE"] [string totitle "élan"]
+} -ok {HÉLLO école Élan}
+
+test string-case-4.2 {string case, non-ASCII ranges are character-based} {
+    list [string toupper "ééé" 1 1] [string toupper "日本é" end]
+} -ok {éÉé 日本É}
+
+#-------------------------------------------------------------------------
+# string compare, equal
+
+test string-compare-1.1 {string compare errors} {
+    string compare a
+} -error {wrong # args: should be "string compare ?-nocase? ?-length int? string1 string2"}
+
+test string-compare-1.2 {string compare errors} {
+    string compare -frob a b
+} -error {bad option "-frob": must be -nocase or -length}
+
+test string-compare-1.3 {string compare errors} {
+    string compare -length x a b
+} -error {expected integer but got "x"}
+
+test string-compare-1.4 {string compare errors} {
+    string equal -length a b
+} -error {wrong # args: should be "string equal ?-nocase? ?-length int? string1 string2"}
+
+test string-compare-1.5 {string equal errors} {
+    string equal -nocase -length 1 -frob a b
+} -error {bad option "-frob": must be -nocase or -length}
+
+test string-compare-2.1 {string compare} {
+    list [string compare abc abd] [string compare abc abc] [string compare abd abc]
+} -ok {-1 0 1}
+
+test string-compare-2.2 {string compare, prefixes} {
+    list [string compare ab abc] [string compare abc ab] [string compare "" a] [string compare "" ""]
+} -ok {-1 1 -1 0}
+
+test string-compare-2.3 {string compare, case-sensitive} {
+    list [string compare ABC abc] [string compare -nocase ABC abc] [string compare -nocase ABD abc]
+} -ok {-1 0 1}
+
+test string-compare-2.4 {string compare -length} {
+    list [string compare -length 2 abc abd] [string compare -length 3 abc abd] [string compare -length 5 ab abc]
+} -ok {0 -1 -1}
+
+test string-compare-2.5 {string compare -length 0 and negative} {
+    list [string compare -length 0 abc xyz] [string compare -length -1 abc abd]
+} -ok {0 -1}
+
+test string-compare-2.6 {string compare -nocase -length} {
+    string compare -nocase -length 3 ABCx abcy
+} -ok {0}
+
+test string-compare-2.7 {string compare, by character} {
+    list [string compare é z] [string compare -nocase É é] [string compare -length 1 éa éb]
+} -ok {1 0 0}
+
+test string-compare-3.1 {string equal} {
+    list [string equal abc abc] [string equal abc abd] [string equal "" ""] [string equal a ""]
+} -ok {1 0 1 0}
+
+test string-compare-3.2 {string equal -nocase} {
+    list [string equal -nocase HeLLo hello] [string equal HeLLo hello]
+} -ok {1 0}
+
+test string-compare-3.3 {string equal -length} {
+    list [string equal -length 3 abcx abcy] [string equal -length 4 abcx abcy] [string equal -length 0 a b]
+} -ok {1 0 1}
+
+test string-compare-3.4 {string equal -length negative} {
+    string equal -length -5 abcx abcy
+} -ok {0}
+
+#-------------------------------------------------------------------------
+# string first, last
+
+test string-first-1.1 {string first errors} {
+    string first a
+} -error {wrong # args: should be "string first needleString haystackString ?startIndex?"}
+
+test string-first-1.2 {string first errors} {
+    string first a abc x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-first-1.3 {string last errors} {
+    string last a b c d
+} -error {wrong # args: should be "string last needleString haystackString ?lastIndex?"}
+
+test string-first-2.1 {string first} {
+    list [string first b abcabc] [string first bc abcabc] [string first x abc] [string first abcd abc]
+} -ok {1 1 -1 -1}
+
+test string-first-2.2 {string first, empty needle or haystack} {
+    list [string first "" abc] [string first a ""] [string first "" ""]
+} -ok {-1 -1 -1}
+
+test string-first-2.3 {string first, start index} {
+    list [string first b abcabc 2] [string first b abcabc 5] [string first b abcabc -5]
+} -ok {4 -1 1}
+
+test string-first-2.4 {string first, end-N start index} {
+    list [string first c abcabc end] [string first b abcabc end-3] [string first a abcabc end+1]
+} -ok {5 4 -1}
+
+test string-first-2.5 {string first, character indices} {
+    list [string first wö "héllo wörld"] [string first ö "héllo wörld"]
+} -ok {6 7}
+
+test string-first-2.6 {string first, multibyte start index} {
+    string first l "ééélll" 4
+} -ok {4}
+
+test string-last-2.1 {string last} {
+    list [string last b abcabc] [string last bc abcabc] [string last x abc] [string last abcd abc]
+} -ok {4 4 -1 -1}
+
+test string-last-2.2 {string last, empty needle or haystack} {
+    list [string last "" abc] [string last a ""]
+} -ok {-1 -1}
+
+test string-last-2.3 {string last, last index} {
+    list [string last b abcabc 3] [string last b abcabc 4] [string last b abcabc 0] [string last a abcabc -1]
+} -ok {1 4 -1 -1}
+
+test string-last-2.4 {string last, end-N last index} {
+    list [string last c abcabc end-1] [string last abc abcabc end] [string last c abcabc 100]
+} -ok {2 3 5}
+
+test string-last-2.5 {string last, match must begin at or before last index} {
+    string last bc abcabc 3
+} -ok {1}
+
+test string-last-2.6 {string last, character indices} {
+    list [string last €l "h€llo h€llo"] [string last o "h€llo h€llo"]
+} -ok {7 10}
+
+test string-last-2.7 {string last, overflowing indices} {
+    list [string last b abc end+9223372036854775807] [string last b abc -9223372036854775808-1]
+} -ok {1 -1}
+
+#-------------------------------------------------------------------------
+# string index
+
+test string-index-1.1 {string index errors} {
+    string index abc
+} -error {wrong # args: should be "string index string charIndex"}
+
+test string-index-1.2 {string index errors} {
+    string index abc x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-index-2.1 {string index} {
+    list [string index abc 0] [string index abc 2] [string index "a b" 1]
+} -ok {a c { }}
+
+test string-index-2.2 {string index, out of range} {
+    list [string index abc -1] [string index abc 3] [string index "" 0]
+} -ok {{} {} {}}
+
+test string-index-2.3 {string index, end and end-N} {
+    list [string index abc end] [string index abc end-2] [string index abc end-3] [string index abc end+1]
+} -ok {c a {} {}}
+
+test string-index-2.4 {string index, non-ASCII} {
+    list [string index "héllo" 1] [string index "héllo" end] [string index "日本語" end-1]
+} -ok {é o 本}
+
+test string-index-2.5 {string index, overflowing indices} {
+    list [string index abc end+9223372036854775807] [string index abc -9223372036854775808-1]
+} -ok {{} {}}
+
+#-------------------------------------------------------------------------
+# string range
+
+test string-range-1.1 {string range errors} {
+    string range abc 1
+} -error {wrong # args: should be "string range string first last"}
+
+test string-range-1.2 {string range errors} {
+    string range abc 0 x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-range-2.1 {string range} {
+    list [string range abcde 1 3] [string range abcde 0 0] [string range abcde 0 end]
+} -ok {bcd a abcde}
+
+test string-range-2.2 {string range, first > last} {
+    list [string range abcde 3 1] [string range "" 0 end]
+} -ok {{} {}}
+
+test string-range-2.3 {string range, clamping} {
+    list [string range abcde -5 1] [string range abcde 3 100] [string range abcde 10 20] [string range abcde -5 -1]
+} -ok {ab de {} {}}
+
+test string-range-3.1 {string range, end-N} {
+    list [string range abcde end-1 end] [string range abcde 0 end-1] [string range abcde end-0 end]
+} -ok {de abcd e}
+
+test string-range-3.2 {string range, end-N past the start} {
+    list [string range abcde end-10 end-3] [string range abcde end-10 end-10]
+} -ok {ab {}}
+
+test string-range-3.3 {string range, end+N} {
+    string range abcde 2 end+5
+} -ok {cde}
+
+test string-range-4.1 {string range, non-ASCII} {
+    list [string range "héllo wörld" 1 4] [string range "日本語" end-1 end]
+} -ok {éllo 本語}
+
+#-------------------------------------------------------------------------
+# string replace
+
+test string-replace-1.1 {string replace errors} {
+    string replace abc 1
+} -error {wrong # args: should be "string replace string first last ?string?"}
+
+test string-replace-1.2 {string replace errors} {
+    string replace abc x 1
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-replace-2.1 {string replace, deletion} {
+    list [string replace abcde 1 3] [string replace abcde 0 end] [string replace abcde 2 2]
+} -ok {ae {} abde}
+
+test string-replace-2.2 {string replace, same length} {
+    string replace abcde 1 3 XYZ
+} -ok {aXYZe}
+
+test string-replace-2.3 {string replace, longer replacement} {
+    string replace abcde 1 1 "long string"
+} -ok {along stringcde}
+
+test string-replace-2.4 {string replace, shorter replacement} {
+    string replace abcde 1 3 X
+} -ok {aXe}
+
+test string-replace-3.1 {string replace, end-N} {
+    list [string replace abcde end end X] [string replace abcde end-1 end] [string replace abcde 0 end-3 X]
+} -ok {abcdX abc Xcde}
+
+test string-replace-3.2 {string replace, clamping} {
+    list [string replace abcde -5 1 X] [string replace abcde 3 100 X]
+} -ok {Xcde abcX}
+
+test string-replace-3.4 {string replace, overflowing indices} {
+    list [string replace abcde -9223372036854775808-1 1 X] [string replace abcde 3 end+9223372036854775807 X]
+} -ok {Xcde abcX}
+
+test string-replace-3.3 {string replace, unchanged} {
+    list [string replace abcde 3 1 X] [string replace abcde 5 6 X] [string replace abcde -3 -1 X] [string replace "" 0 0 X]
+} -ok {abcde abcde abcde {}}
+
+test string-replace-4.1 {string replace, non-ASCII} {
+    list [string replace "héllo" 1 1 e] [string replace "日本語" 1 end 本]
+} -ok {hello 日本}
+
+#-------------------------------------------------------------------------
+# string is
+
+test string-is-1.1 {string is errors} {
+    string is integer
+} -error {wrong # args: should be "string is class ?-strict? ?-failindex var? str"}
+
+test string-is-1.2 {string is errors} {
+    string is frob x
+} -error {bad class "frob": must be alnum, alpha, ascii, boolean, digit, double, false, integer, list, lower, space, true, or upper}
+
+test string-is-1.3 {string is errors} {
+    string is integer -frob x
+} -error {bad option "-frob": must be -strict or -failindex}
+
+test string-is-1.4 {string is errors} {
+    string is integer -failindex x
+} -error {wrong # args: should be "string is class ?-strict? ?-failindex var? str"}
+
+test string-is-2.1 {string is, empty string} {
+    list [string is integer ""] [string is alpha ""] [string is integer -strict ""] [string is list -strict ""]
+} -ok {1 1 0 0}
+
+test string-is-3.1 {string is integer} {
+    list [string is integer 123] [string is integer -45] [string is integer +6] [string is integer 0xff] [string is integer " 7 "]
+} -ok {1 1 1 1 1}
+
+test string-is-3.2 {string is integer, failures} {
+    list [string is integer 12a] [string is integer 1.5] [string is integer abc] [string is integer 99999999999999999999]
+} -ok {0 0 0 0}
+
+test string-is-3.3 {string is integer, agrees with expr} {
+    set x 0x1F
+    list [string is integer $x] [expr {$x + 1}]
+} -ok {1 32}
+
+test string-is-3.4 {string is double} {
+    list [string is double 1.5] [string is double 1e10] [string is double -2] [string is double .5] [string is double 0x10]
+} -ok {1 1 1 1 1}
+
+test string-is-3.5 {string is double, failures} {
+    list [string is double 1.5x] [string is double abc] [string is double 1e]
+} -ok {0 0 0}
+
+test string-is-4.1 {string is boolean} {
+    list [string is boolean true] [string is boolean no] [string is boolean 1] [string is boolean ON] [string is boolean maybe]
+} -ok {1 1 1 1 0}
+
+test string-is-4.2 {string is true and false} {
+    list [string is true yes] [string is true no] [string is false off] [string is false 1] [string is true xyz]
+} -ok {1 0 1 0 0}
+
+test string-is-5.1 {string is alpha, alnum, digit} {
+    list [string is alpha abcDEF] [string is alpha abc1] [string is alnum abc123] [string is alnum "abc 1"] [string is digit 0123] [string is digit 12.3]
+} -ok {1 0 1 0 1 0}
+
+test string-is-5.2 {string is space, upper, lower} {
+    list [string is space " \t\n"] [string is space " x "] [string is upper ABC] [string is upper AbC] [string is lower abc] [string is lower abC]
+} -ok {1 0 1 0 1 0}
+
+test string-is-5.3 {string is ascii} {
+    list [string is ascii "abc 123"] [string is ascii "héllo"]
+} -ok {1 0}
+
+test string-is-5.4 {string is, non-ASCII classes} {
+    list [string is alpha "héllo"] [string is upper "ÉCOLE"] [string is lower "école"]
+} -ok {1 1 1}
+
+test string-is-5.5 {string is list} {
+    list [string is list {a b {c d}}] [string is list "a \{b"] [string is list "a \"b"]
+} -ok {1 0 0}
+
+test string-is-6.1 {string is -failindex, character classes} {
+    list [string is alpha -failindex i abc1d] $i
+} -ok {0 3}
+
+test string-is-6.2 {string is -failindex, not set on success} {
+    set i unchanged
+    list [string is alpha -failindex i abc] $i
+} -ok {1 unchanged}
+
+test string-is-6.3 {string is -failindex, is a character index} {
+    list [string is ascii -failindex i "ééé€"] $i [string is alpha -failindex j "éé1"] $j
+} -ok {0 0 0 2}
+
+test string-is-6.4 {string is -failindex, integer} {
+    list [string is integer -failindex i 12a] $i [string is integer -failindex j "  x"] $j
+} -ok {0 2 0 2}
+
+test string-is-6.5 {string is -failindex, integer overflow} {
+    list [string is integer -failindex i 99999999999999999999] $i
+} -ok {0 -1}
+
+test string-is-6.6 {string is -failindex, double} {
+    list [string is double -failindex i 1.5x] $i
+} -ok {0 3}
+
+test string-is-6.7 {string is -strict -failindex, empty string} {
+    list [string is integer -strict -failindex i ""] $i
+} -ok {0 0}
+
+test string-is-6.8 {string is, options in either order} {
+    list [string is digit -failindex i -strict 12x] $i
+} -ok {0 2}
+
+#-------------------------------------------------------------------------
+# string cat
+
+test string-cat-1.1 {string cat, no arguments} {
+    string cat
+} -ok {}
+
+test string-cat-1.2 {string cat} {
+    list [string cat a] [string cat a b c] [string cat "a " " b"]
+} -ok {a abc {a  b}}
+
+test string-cat-1.3 {string cat, empty arguments} {
+    string cat "" a "" b ""
+} -ok {ab}
+
+test string-cat-1.4 {string cat, no list quoting} {
+    string cat "\{" "a b" "\$"
+} -ok "\{a b\$"
+
+test string-cat-1.5 {string cat, large arguments} {
+    set x [string repeat abcdefghij 10000]
+    string length [string cat $x $x $x]
+} -ok {300000}
+
+#-------------------------------------------------------------------------
+# string wordend, wordstart
+
+test string-word-1.1 {string wordend errors} {
+    string wordend abc
+} -error {wrong # args: should be "string wordend string index"}
+
+test string-word-1.2 {string wordstart errors} {
+    string wordstart abc x
+} -error {bad index "x": must be integer?[+-]integer? or end?[+-]integer?}
+
+test string-word-2.1 {string wordend} {
+    set s "hello world_2 ok"
+    list [string wordend $s 0] [string wordend $s 3] [string wordend $s 6] [string wordend $s 12]
+} -ok {5 5 13 13}
+
+test string-word-2.2 {string wordend, non-word characters} {
+    set s "ab, cd"
+    list [string wordend $s 2] [string wordend $s 3] [string wordend "a--b" 1]
+} -ok {3 4 2}
+
+test string-word-2.3 {string wordend, clamping and end} {
+    list [string wordend "abc def" -5] [string wordend "abc def" 100] [string wordend "abc def" end] [string wordend "" 0]
+} -ok {3 7 7 0}
+
+test string-word-2.4 {string wordend, end-N} {
+    string wordend "abc def" end-5
+} -ok {3}
+
+test string-word-3.1 {string wordstart} {
+    set s "hello world_2 ok"
+    list [string wordstart $s 0] [string wordstart $s 3] [string wordstart $s 12] [string wordstart $s 15]
+} -ok {0 0 6 14}
+
+test string-word-3.2 {string wordstart, non-word characters} {
+    set s "ab, cd"
+    list [string wordstart $s 2] [string wordstart $s 3] [string wordstart "a--b" 2]
+} -ok {2 3 2}
+
+test string-word-3.3 {string wordstart, clamping and end} {
+    list [string wordstart "abc def" -5] [string wordstart "abc def" 100] [string wordstart "abc def" end-1] [string wordstart "" 0]
+} -ok {0 4 4 0}
+
+test string-word-4.1 {string word, non-ASCII} {
+    set s "café olé"
+    list [string wordend $s 1] [string wordstart $s 7] [string wordend $s 4]
+} -ok {4 5 5}
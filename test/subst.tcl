@@ -0,0 +1,71 @@
+# Test Script: subst
+
+test subst-1.1 {subst errors} {
+    subst
+} -error {wrong # args: should be "subst ?-nobackslashes? ?-nocommands? ?-novariables? string"}
+
+test subst-1.2 {subst errors} {
+    subst -nonesuch {abc}
+} -error {bad option "-nonesuch": must be -nobackslashes, -nocommands, or -novariables}
+
+test subst-1.3 {subst errors} {
+    subst {abc [list x}
+} -error {missing close-bracket}
+
+test subst-1.4 {subst errors} {
+    subst {abc $nonesuch}
+} -error {can't read "nonesuch": no such variable}
+
+test subst-2.1 {all substitutions} {
+    set x 1
+    subst {x=$x, y=[expr {$x + 1}]\t.}
+} -ok "x=1, y=2\t."
+
+test subst-2.2 {plain text} {
+    subst {abc def}
+} -ok {abc def}
+
+test subst-2.3 {not a command} {
+    subst {a "quoted" {braced} ;string}
+} -ok {a "quoted" {braced} ;string}
+
+test subst-2.4 {array elements} {
+    set a(1) one
+    set i 1
+    subst {$a($i)}
+} -ok {one}
+
+test subst-2.5 {lone dollar sign} {
+    subst {cost: $ 5}
+} -ok {cost: $ 5}
+
+test subst-3.1 {-nobackslashes} {
+    set x 1
+    subst -nobackslashes {$x\t[set x]}
+} -ok {1\t1}
+
+test subst-3.2 {-nocommands} {
+    set x 1
+    subst -nocommands {$x\t[set x]}
+} -ok "1\t\[set x\]"
+
+test subst-3.3 {-novariables} {
+    set x 1
+    subst -novariables {$x\t[set x]}
+} -ok "\$x\t1"
+
+test subst-3.4 {all options} {
+    subst -nobackslashes -nocommands -novariables {$x\t[set x]}
+} -ok {$x\t[set x]}
+
+test subst-4.1 {break truncates the result} {
+    subst {abc[break]def}
+} -ok {abc}
+
+test subst-4.2 {continue substitutes the empty string} {
+    subst {abc[continue; set x 1]def}
+} -ok {abcdef}
+
+test subst-4.3 {return substitutes the returned value} {
+    subst {abc[return xyz; set x 1]def}
+} -ok {abcxyzdef}
@@ -0,0 +1,132 @@
+# Test Script: switch command.
+
+test switch-1.1 {switch errors} {
+    switch a
+} -error {wrong # args: should be "switch ?-option ...? string ?pattern body ...? ?default body?"}
+
+test switch-1.2 {switch errors, empty clause list} {
+    switch a {}
+} -error {wrong # args: should be "switch ?-option ...? string {?pattern body ...? ?default body?}"}
+
+test switch-1.3 {switch errors, odd clauses, braced} {
+    switch a {a {set x 1} b}
+} -error {extra switch pattern with no body}
+
+test switch-1.4 {switch errors, odd clauses, flat} {
+    switch a a {set x 1} b
+} -error {extra switch pattern with no body}
+
+test switch-1.5 {switch errors, bad option} {
+    switch -foo a {a {set x 1}}
+} -error {bad option "-foo": must be -exact, -glob, -indexvar, -matchvar, -nocase, -regexp, or --}
+
+test switch-1.6 {switch errors, fall through at end} {
+    switch a {a - b -}
+} -error {no body specified for pattern "b"}
+
+test switch-1.7 {switch errors, -matchvar without -regexp} {
+    switch -matchvar m a {a {set x 1}}
+} -error {-matchvar option requires -regexp option}
+
+test switch-1.8 {switch errors, bad regexp} {
+    catch {switch -regexp a {( {set x 1}}} result
+    string match "couldn't compile regular expression pattern: *" $result
+} -ok {1}
+
+test switch-2.1 {switch, braced form} {
+    list [switch b {a {set x 1} b {set x 2} c {set x 3}}] [switch c {a {set x 1} b {set x 2} c {set x 3}}]
+} -ok {2 3}
+
+test switch-2.2 {switch, flat form} {
+    list [switch b a {set x 1} b {set x 2}] [switch a a {set x 1} b {set x 2}]
+} -ok {2 1}
+
+test switch-2.3 {switch, flat form with substitution} {
+    set p b
+    switch b a {set x 1} $p {set x 2}
+} -ok {2}
+
+test switch-2.4 {switch, braced form has no substitution} {
+    set p b
+    switch b {a {set x 1} $p {set x 2} default {set x 3}}
+} -ok {3}
+
+test switch-2.5 {switch, no match} {
+    switch z {a {set x 1} b {set x 2}}
+} -ok {}
+
+test switch-2.6 {switch, first match wins} {
+    switch a {a {set x 1} a {set x 2}}
+} -ok {1}
+
+test switch-3.1 {switch, default} {
+    list [switch z {a {set x 1} default {set x 2}}] [switch z a {set x 1} default {set x 2}]
+} -ok {2 2}
+
+test switch-3.2 {switch, default only special when last} {
+    list [switch z {default {set x 1} a {set x 2}}] [switch default {default {set x 1} a {set x 2}}]
+} -ok {{} 1}
+
+test switch-3.3 {switch, fall through} {
+    list [switch b {a - b - c {set x 1} d {set x 2}}] [switch a a - b {set x 3}]
+} -ok {1 3}
+
+test switch-4.1 {switch -exact is the default} {
+    list [switch a* {a* {set x 1} default {set x 2}}] [switch abc {a* {set x 1} default {set x 2}}]
+} -ok {1 2}
+
+test switch-4.2 {switch -glob} {
+    list [switch -glob abc {x* {set x 1} a* {set x 2}}] [switch -glob abc {a?c {set x 3}}]
+} -ok {2 3}
+
+test switch-4.3 {switch -regexp} {
+    switch -regexp abc123 {{^[a-z]+$} {set x 1} {^[a-z]+\d+$} {set x 2}}
+} -ok {2}
+
+test switch-4.4 {switch -nocase} {
+    list [switch -nocase ABC {abc {set x 1}}] [switch -glob -nocase ABC {a* {set x 2}}] [switch -regexp -nocase ABC {^a {set x 3}}]
+} -ok {1 2 3}
+
+test switch-4.5 {switch --} {
+    switch -- -a {-a {set x 1} default {set x 2}}
+} -ok {1}
+
+test switch-4.6 {switch, string beginning with dash} {
+    switch -a {-a {set x 1} default {set x 2}}
+} -ok {1}
+
+test switch-4.7 {switch -regexp -matchvar -indexvar} {
+    switch -regexp -matchvar m -indexvar i "key=val" {{(\w+)=(\w+)} {list $m $i}}
+} -ok {{key=val key val} {{0 6} {0 2} {4 6}}}
+
+test switch-5.1 {switch, break propagates} {
+    set out {}
+    foreach x {a b c} {
+        switch $x {b break default {lappend out $x}}
+    }
+    set out
+} -ok {a}
+
+test switch-5.2 {switch, continue propagates} {
+    set out {}
+    foreach x {a b c} {
+        switch $x {b continue}
+        lappend out $x
+    }
+    set out
+} -ok {a c}
+
+test switch-5.3 {switch, return propagates} -setup {
+    proc switchproc {x} {
+        switch $x {a {return A}}
+        return other
+    }
+} -body {
+    list [switchproc a] [switchproc b]
+} -cleanup {
+    rename switchproc ""
+} -ok {A other}
+
+test switch-5.4 {switch, errors propagate} {
+    switch a {a {error oops}}
+} -error {oops}
@@ -0,0 +1,129 @@
+# Test Script: tailcall
+
+test tailcall-1.1 {tailcall errors} {
+    tailcall
+} -error {wrong # args: should be "tailcall command ?arg ...?"}
+
+test tailcall-1.2 {tailcall outside a proc} {
+    tailcall list a b
+} -error {tailcall can only be called from a proc or lambda}
+
+test tailcall-1.3 {tailcall outside a proc} -body {
+    namespace eval ::tcns { tailcall list a b }
+} -cleanup {
+    namespace delete ::tcns
+} -error {tailcall can only be called from a proc or lambda}
+
+test tailcall-2.1 {tailcall result} -setup {
+    proc tcproc {} {
+        tailcall list a b
+        error "not reached"
+    }
+} -body {
+    tcproc
+} -cleanup {
+    rename tcproc ""
+} -ok {a b}
+
+test tailcall-2.2 {tailcall uses the caller's scope} -setup {
+    proc tccaller {} {
+        set x "caller"
+        tcproc
+    }
+    proc tcproc {} {
+        set x "proc"
+        tailcall set x
+    }
+} -body {
+    tccaller
+} -cleanup {
+    rename tccaller ""
+    rename tcproc ""
+} -ok {caller}
+
+test tailcall-2.3 {tailcall replaces the proc's frame} -setup {
+    proc tcproc {} {
+        tailcall info level
+    }
+} -body {
+    expr {[tcproc] == [info level]}
+} -cleanup {
+    rename tcproc ""
+} -ok {1}
+
+test tailcall-2.4 {tailcall doesn't grow the stack} -setup {
+    proc tccount {n acc} {
+        if {$n == 0} {
+            return $acc
+        }
+        tailcall tccount [expr {$n - 1}] [expr {$acc + 1}]
+    }
+} -body {
+    tccount 5000 0
+} -cleanup {
+    rename tccount ""
+} -ok {5000}
+
+test tailcall-2.5 {tailcall resolves the command in the proc's namespace} -setup {
+    namespace eval ::tcns {
+        proc helper {} { return "helper" }
+        proc tcproc {} { tailcall helper }
+    }
+} -body {
+    ::tcns::tcproc
+} -cleanup {
+    namespace delete ::tcns
+} -ok {helper}
+
+test tailcall-2.6 {tailcall from a lambda} {
+    apply {{x} {tailcall list $x $x}} a
+} -ok {a a}
+
+test tailcall-2.7 {tailcall ends the proc within catch} -setup {
+    proc tcproc {} {
+        catch {tailcall list a}
+        return "not reached"
+    }
+} -body {
+    tcproc
+} -cleanup {
+    rename tcproc ""
+} -ok {a}
+
+test tailcall-2.8 {tailcall evaluates try's finally clause} -setup {
+    proc tcproc {} {
+        try {
+            tailcall list a
+        } on return {} {
+            error "not reached"
+        } finally {
+            lappend ::tclog "finally"
+        }
+    }
+} -body {
+    set ::tclog {}
+    list [tcproc] $::tclog
+} -cleanup {
+    rename tcproc ""
+    unset ::tclog
+} -ok {a finally}
+
+test tailcall-3.1 {tail-called errors propagate} -setup {
+    proc tcproc {} {
+        tailcall error "simulated error"
+    }
+} -body {
+    tcproc
+} -cleanup {
+    rename tcproc ""
+} -error {simulated error}
+
+test tailcall-3.2 {tail-called command doesn't exist} -setup {
+    proc tcproc {} {
+        tailcall nonesuch
+    }
+} -body {
+    tcproc
+} -cleanup {
+    rename tcproc ""
+} -error {invalid command name "nonesuch"}
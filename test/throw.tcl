@@ -0,0 +1,42 @@
+# Test Script: throw
+
+test throw-1.1 {throw errors} {
+    throw
+} -error {wrong # args: should be "throw type message"}
+
+test throw-1.2 {throw errors} {
+    throw {} "simulated error"
+} -error {type must be non-empty list}
+
+test throw-2.1 {throw an error} {
+    throw {MY CODE} "simulated error"
+} -error {simulated error}
+
+test throw-2.2 {throw sets the error code} {
+    catch {throw {MY CODE} "simulated error"} msg opts
+    list $msg [dict get $opts -errorcode]
+} -ok {{simulated error} {MY CODE}}
+
+test throw-2.3 {throw is like return -code error} {
+    catch {throw {MY CODE} "simulated error"} msg opts1
+    catch {return -level 0 -code error -errorcode {MY CODE} "simulated error"} msg opts2
+    expr {[dict get $opts1 -errorcode] eq [dict get $opts2 -errorcode]}
+} -ok {1}
+
+test throw-3.1 {trap a throw from a nested proc} -setup {
+    proc inner {} {
+        throw {MY ERROR DETAIL} "simulated error"
+    }
+    proc middle {} {
+        inner
+    }
+} -body {
+    try {
+        middle
+    } trap {MY ERROR} {msg opts} {
+        list trapped $msg [dict get $opts -errorcode]
+    }
+} -cleanup {
+    rename inner ""
+    rename middle ""
+} -ok {trapped {simulated error} {MY ERROR DETAIL}}
@@ -0,0 +1,46 @@
+# Test Script: time
+
+test time-1.1 {time errors} {
+    time
+} -error {wrong # args: should be "time command ?count?"}
+
+test time-1.2 {time errors} {
+    time {set a 1} 1 2
+} -error {wrong # args: should be "time command ?count?"}
+
+test time-1.3 {time errors} {
+    time {set a 1} x
+} -error {expected integer but got "x"}
+
+test time-2.1 {time result} {
+    regexp {^\d+ microseconds per iteration$} [time {set a 1}]
+} -ok {1}
+
+test time-2.2 {time result with count} {
+    regexp {^[\d.e+-]+ microseconds per iteration$} [time {set a 1} 10]
+} -ok {1}
+
+test time-2.3 {time evaluates the command count times} {
+    set a 0
+    time {incr a} 5
+    set a
+} -ok {5}
+
+test time-2.4 {time with a count of zero} {
+    set a 0
+    list [time {incr a} 0] $a
+} -ok {{0 microseconds per iteration} 0}
+
+test time-2.5 {time with a negative count} {
+    set a 0
+    list [time {incr a} -1] $a
+} -ok {{0 microseconds per iteration} 0}
+
+test time-2.6 {time result is a list} {
+    lindex [time {set a 1}] 1
+} -ok {microseconds}
+
+test time-3.1 {time propagates errors immediately} {
+    set a 0
+    list [catch {time {incr a; error "simulated error"} 5} msg] $msg $a
+} -ok {1 {simulated error} 1}
@@ -0,0 +1,458 @@
+# Test Script: trace
+
+proc traceLog {args} {
+    global traceLog
+    lappend traceLog $args
+}
+
+test trace-1.1 {trace errors} {
+    trace
+} -error {wrong # args: should be "trace subcommand ?arg ...?"}
+
+test trace-1.2 {trace errors} {
+    trace nonesuch
+} -error {unknown or ambiguous subcommand "nonesuch": must be add, info, or remove}
+
+test trace-1.3 {trace add, bad type} {
+    trace add nonesuch x write traceLog
+} -error {bad option "nonesuch": must be execution or variable}
+
+test trace-1.4 {trace add variable, errors} {
+    trace add variable x write
+} -error {wrong # args: should be "trace add variable name opList command"}
+
+test trace-1.5 {trace add variable, bad operation} {
+    trace add variable x {write nonesuch} traceLog
+} -error {bad operation "nonesuch": must be read, unset, or write}
+
+test trace-1.6 {trace add variable, empty operation list} {
+    trace add variable x {} traceLog
+} -error {bad operation list "": must be one or more of read, unset, or write}
+
+test trace-1.7 {trace info variable, errors} {
+    trace info variable
+} -error {wrong # args: should be "trace info variable name"}
+
+test trace-2.1 {write trace} -setup {
+    set ::traceLog {}
+} -body {
+    set x 1
+    trace add variable x write traceLog
+    set x 2
+    set x 3
+    set ::traceLog
+} -ok {{x {} write} {x {} write}}
+
+test trace-2.2 {write trace, returns value after traces} -setup {
+    proc double {name1 name2 op} {
+        upvar 1 $name1 var
+        set var [expr {$var * 2}]
+    }
+} -body {
+    trace add variable x write double
+    list [set x 5] $x
+} -cleanup {
+    rename double ""
+} -ok {10 10}
+
+test trace-2.3 {write trace, error aborts the set} -setup {
+    proc readonly {name1 name2 op} {
+        error "$name1 is read-only"
+    }
+} -body {
+    trace add variable x write readonly
+    set x 1
+} -cleanup {
+    rename readonly ""
+} -error {can't set "x": x is read-only}
+
+test trace-2.4 {write trace, Rust set via incr, append, lappend} -setup {
+    set ::traceLog {}
+} -body {
+    set x 1
+    trace add variable x write traceLog
+    incr x
+    append x 0
+    lappend x a
+    list $x [llength $::traceLog]
+} -ok {{20 a} 3}
+
+test trace-2.5 {write trace on array} -setup {
+    set ::traceLog {}
+} -body {
+    trace add variable a write traceLog
+    set a(x) 1
+    array set a {y 2}
+    set ::traceLog
+} -ok {{a x write} {a y write}}
+
+test trace-2.6 {write trace on array element} -setup {
+    set ::traceLog {}
+} -body {
+    trace add variable a(x) write traceLog
+    set a(x) 1
+    set a(y) 2
+    set ::traceLog
+} -ok {{a x write}}
+
+test trace-2.7 {write trace, through upvar} -setup {
+    set ::traceLog {}
+    proc setit {} {
+        upvar 1 x y
+        set y 2
+    }
+} -body {
+    set x 1
+    trace add variable x write traceLog
+    setit
+    list $x $::traceLog
+} -cleanup {
+    rename setit ""
+} -ok {2 {{y {} write}}}
+
+test trace-2.8 {write trace doesn't recurse} -setup {
+    set ::count 0
+    proc bump {name1 name2 op} {
+        upvar 1 $name1 var
+        incr ::count
+        set var [expr {$var + 1}]
+    }
+} -body {
+    trace add variable x write bump
+    set x 1
+    list $x $::count
+} -cleanup {
+    rename bump ""
+    unset ::count
+} -ok {2 1}
+
+test trace-3.1 {read trace} -setup {
+    set ::traceLog {}
+} -body {
+    set x 1
+    trace add variable x read traceLog
+    set y $x
+    set y [set x]
+    set ::traceLog
+} -ok {{x {} read} {x {} read}}
+
+test trace-3.2 {read trace, can supply value} -setup {
+    proc supply {name1 name2 op} {
+        upvar 1 $name1 var
+        set var($name2) "value of $name2"
+    }
+} -body {
+    array set a {}
+    trace add variable a read supply
+    set a(foo)
+} -cleanup {
+    rename supply ""
+} -ok {value of foo}
+
+test trace-3.3 {read trace, error} -setup {
+    proc noread {name1 name2 op} {
+        error "no reading"
+    }
+} -body {
+    set x 1
+    trace add variable x read noread
+    set x
+} -cleanup {
+    rename noread ""
+} -error {can't read "x": no reading}
+
+test trace-3.4 {read trace, nonexistent variable} -setup {
+    set ::traceLog {}
+} -body {
+    trace add variable x read traceLog
+    catch {set x}
+    set ::traceLog
+} -ok {{x {} read}}
+
+test trace-4.1 {unset trace} -setup {
+    set ::traceLog {}
+} -body {
+    set x 1
+    trace add variable x unset traceLog
+    unset x
+    list $::traceLog [trace info variable x]
+} -ok {{{x {} unset}} {}}
+
+test trace-4.2 {unset trace, errors are ignored} -setup {
+    proc failing {args} {
+        error "failed"
+    }
+} -body {
+    set x 1
+    trace add variable x unset failing
+    unset x
+    info exists x
+} -cleanup {
+    rename failing ""
+} -ok {0}
+
+test trace-4.3 {unset trace, array element} -setup {
+    set ::traceLog {}
+} -body {
+    array set a {x 1 y 2}
+    trace add variable a unset traceLog
+    unset a(x)
+    unset a
+    set ::traceLog
+} -ok {{a x unset} {a {} unset}}
+
+test trace-4.4 {unset trace, proc locals on return} -setup {
+    set ::traceLog {}
+    proc local {} {
+        set x 1
+        trace add variable x unset traceLog
+    }
+} -body {
+    local
+    set ::traceLog
+} -cleanup {
+    rename local ""
+} -ok {{x {} unset}}
+
+test trace-5.1 {trace info variable} {
+    trace add variable x {write read} cmd1
+    trace add variable x unset {cmd2 arg}
+    trace info variable x
+} -ok {{unset {cmd2 arg}} {{read write} cmd1}}
+
+test trace-5.2 {trace info variable, no traces} {
+    trace info variable x
+} -ok {}
+
+test trace-5.3 {trace info variable, array element} {
+    trace add variable a write cmd1
+    trace add variable a(x) read cmd2
+    list [trace info variable a] [trace info variable a(x)]
+} -ok {{{write cmd1}} {{read cmd2}}}
+
+test trace-6.1 {trace remove variable} -setup {
+    set ::traceLog {}
+} -body {
+    trace add variable x write traceLog
+    trace add variable x read traceLog
+    trace remove variable x write traceLog
+    set x 1
+    list [trace info variable x] $::traceLog
+} -ok {{{read traceLog}} {}}
+
+test trace-6.2 {trace remove variable, ops must match} {
+    trace add variable x {read write} cmd1
+    trace remove variable x write cmd1
+    trace remove variable x {write read} cmd1
+    trace info variable x
+} -ok {}
+
+test trace-6.3 {trace remove variable, no such trace} {
+    trace remove variable x write cmd1
+} -ok {}
+
+test trace-7.1 {trace on global variable} -setup {
+    set ::traceLog {}
+    proc setglobal {} {
+        global g
+        set g 2
+    }
+} -body {
+    set ::g 1
+    trace add variable ::g write traceLog
+    setglobal
+    set ::traceLog
+} -cleanup {
+    trace remove variable ::g write traceLog
+    unset ::g
+    rename setglobal ""
+} -ok {{g {} write}}
+
+test trace-8.1 {trace add execution, errors} {
+    trace add execution nonesuch enter traceLog
+} -error {unknown command "nonesuch"}
+
+test trace-8.2 {trace add execution, bad operation} {
+    trace add execution list {enter nonesuch} traceLog
+} -error {bad operation "nonesuch": must be enter or leave}
+
+test trace-8.3 {trace add execution, empty operation list} {
+    trace add execution list {} traceLog
+} -error {bad operation list "": must be one or more of enter or leave}
+
+test trace-8.4 {trace info execution, errors} {
+    trace info execution nonesuch
+} -error {unknown command "nonesuch"}
+
+test trace-9.1 {enter trace} -setup {
+    set ::traceLog {}
+    proc traced {a b} { return "$a+$b" }
+} -body {
+    trace add execution traced enter traceLog
+    traced 1 {2 3}
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+} -ok {{{traced 1 {2 3}} enter}}
+
+test trace-9.2 {leave trace} -setup {
+    set ::traceLog {}
+    proc traced {a} { return "<$a>" }
+} -body {
+    trace add execution traced leave traceLog
+    traced x
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+} -ok {{{traced x} 0 <x> leave}}
+
+test trace-9.3 {leave trace, error result} -setup {
+    set ::traceLog {}
+    proc traced {} { error "oops" }
+} -body {
+    trace add execution traced leave traceLog
+    catch {traced}
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+} -ok {{traced 1 oops leave}}
+
+test trace-9.4 {enter trace error aborts the command} -setup {
+    set ::ran 0
+    proc traced {} { set ::ran 1 }
+    proc deny {args} { error "denied" }
+} -body {
+    trace add execution traced enter deny
+    list [catch {traced} msg] $msg $::ran
+} -cleanup {
+    rename traced ""
+    rename deny ""
+    unset ::ran
+} -ok {1 denied 0}
+
+test trace-9.5 {leave trace error replaces the result} -setup {
+    proc traced {} { return ok }
+    proc deny {args} { error "denied" }
+} -body {
+    trace add execution traced leave deny
+    traced
+} -cleanup {
+    rename traced ""
+    rename deny ""
+} -error {denied}
+
+test trace-9.6 {execution trace order} -setup {
+    set ::traceLog {}
+    proc traced {} {}
+    proc tr {name args} { lappend ::traceLog "$name [lindex $args end]" }
+} -body {
+    trace add execution traced {enter leave} {tr first}
+    trace add execution traced {enter leave} {tr second}
+    traced
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+    rename tr ""
+} -ok {{second enter} {first enter} {first leave} {second leave}}
+
+test trace-9.7 {execution trace doesn't trace itself} -setup {
+    set ::traceLog {}
+    proc traced {args} { return $args }
+    proc tr {args} {
+        lappend ::traceLog [lindex $args end]
+        traced from trace
+    }
+} -body {
+    trace add execution traced enter tr
+    traced
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+    rename tr ""
+} -ok {enter}
+
+test trace-9.8 {execution trace, recursive calls are traced} -setup {
+    set ::traceLog {}
+    proc countdown {n} {
+        if {$n > 0} { countdown [expr {$n - 1}] }
+    }
+} -body {
+    trace add execution countdown enter traceLog
+    countdown 2
+    set ::traceLog
+} -cleanup {
+    rename countdown ""
+} -ok {{{countdown 2} enter} {{countdown 1} enter} {{countdown 0} enter}}
+
+test trace-9.9 {execution trace, native command} -setup {
+    set ::traceLog {}
+    proc tr {cmd code result op} { lappend ::traceLog $result }
+} -body {
+    trace add execution llength leave tr
+    llength {a b c}
+    trace remove execution llength leave tr
+    llength {a b}
+    set ::traceLog
+} -cleanup {
+    rename tr ""
+} -ok {3}
+
+test trace-9.10 {execution trace follows rename} -setup {
+    set ::traceLog {}
+    proc traced {} {}
+} -body {
+    trace add execution traced enter traceLog
+    rename traced renamed
+    renamed
+    list [trace info execution renamed] $::traceLog
+} -cleanup {
+    rename renamed ""
+} -ok {{{enter traceLog}} {{renamed enter}}}
+
+test trace-9.11 {execution trace removed with command} -setup {
+    proc traced {} {}
+} -body {
+    trace add execution traced enter traceLog
+    rename traced ""
+    proc traced {} {}
+    trace info execution traced
+} -cleanup {
+    rename traced ""
+} -ok {}
+
+test trace-9.12 {execution trace, tailcall} -setup {
+    set ::traceLog {}
+    proc traced {} { return done }
+    proc caller {} { tailcall traced }
+} -body {
+    trace add execution traced leave traceLog
+    caller
+    set ::traceLog
+} -cleanup {
+    rename traced ""
+    rename caller ""
+} -ok {{::traced 0 done leave}}
+
+test trace-10.1 {trace info execution} -setup {
+    proc traced {} {}
+} -body {
+    trace add execution traced {leave enter} cmd1
+    trace add execution traced leave {cmd2 arg}
+    trace info execution traced
+} -cleanup {
+    rename traced ""
+} -ok {{leave {cmd2 arg}} {{enter leave} cmd1}}
+
+test trace-10.2 {trace remove execution} -setup {
+    proc traced {} {}
+} -body {
+    trace add execution traced {enter leave} cmd1
+    trace add execution traced enter cmd1
+    trace remove execution traced {leave enter} cmd1
+    trace info execution traced
+} -cleanup {
+    rename traced ""
+} -ok {{enter cmd1}}
+
+rename traceLog ""
+unset -nocomplain traceLog
@@ -0,0 +1,196 @@
+# Test Script: try
+
+test try-1.1 {try errors} {
+    try
+} -error {wrong # args: should be "try body ?handler ...? ?finally script?"}
+
+test try-1.2 {try errors} {
+    try {set a 1} on error
+} -error {wrong # args to on clause: must be "... on code variableList script"}
+
+test try-1.3 {try errors} {
+    try {set a 1} trap {A B} {msg}
+} -error {wrong # args to trap clause: must be "... trap pattern variableList script"}
+
+test try-1.4 {try errors} {
+    try {set a 1} finally
+} -error {wrong # args to finally clause: must be "... finally script"}
+
+test try-1.5 {try errors} {
+    try {set a 1} finally {set b 2} on ok {} {}
+} -error {wrong # args to finally clause: must be "... finally script"}
+
+test try-1.6 {try errors} {
+    try {set a 1} nonesuch
+} -error {bad handler type "nonesuch": must be finally, on, or trap}
+
+test try-1.7 {try errors} {
+    try {set a 1} on nonesuch {} {}
+} -error {bad completion code "nonesuch": must be ok, error, return, break, continue, or an integer}
+
+test try-1.8 {try errors} {
+    try {set a 1} on ok {a b c} {}
+} -error {bad variable list "a b c": must name at most a result variable and an options variable}
+
+test try-1.9 {try errors} {
+    try {set a 1} on ok {} -
+} -error {last non-finally clause must not have a body of "-"}
+
+test try-2.1 {try with no handlers} {
+    try {set a 1}
+} -ok {1}
+
+test try-2.2 {try with no handlers} {
+    try {error "simulated error"}
+} -error {simulated error}
+
+test try-2.3 {no handler matches} {
+    try {set a 1} on error {msg} {set b "handled"}
+} -ok {1}
+
+test try-3.1 {on ok} {
+    try {set a 1} on ok {result} {list handled $result}
+} -ok {handled 1}
+
+test try-3.2 {on error} {
+    try {error "simulated error"} on error {msg} {list handled $msg}
+} -ok {handled {simulated error}}
+
+test try-3.3 {on error with options} {
+    try {
+        error "simulated error" "" {MY CODE}
+    } on error {msg opts} {
+        list $msg [dict get $opts -code] [dict get $opts -errorcode]
+    }
+} -ok {{simulated error} 1 {MY CODE}}
+
+test try-3.4 {on return} {
+    try {return "value"} on return {result} {list handled $result}
+} -ok {handled value}
+
+test try-3.5 {on break} {
+    try {break} on break {} {set a "handled"}
+} -ok {handled}
+
+test try-3.6 {on continue} {
+    try {continue} on continue {} {set a "handled"}
+} -ok {handled}
+
+test try-3.7 {numeric codes} {
+    set result {}
+    lappend result [try {set a 1} on 0 {} {set a ok}]
+    lappend result [try {error x} on 1 {} {set a error}]
+    lappend result [try {return x} on 2 {} {set a return}]
+    lappend result [try {break} on 3 {} {set a break}]
+    lappend result [try {continue} on 4 {} {set a continue}]
+} -ok {ok error return break continue}
+
+test try-3.8 {first matching handler wins} {
+    try {
+        error "simulated error"
+    } on ok {} {
+        set a ok
+    } on error {} {
+        set a first
+    } on error {} {
+        set a second
+    }
+} -ok {first}
+
+test try-3.9 {handler result is try result} {
+    catch {try {error "first"} on error {} {error "second"}} msg
+    set msg
+} -ok {second}
+
+test try-3.10 {fall-through handlers} {
+    set result {}
+    foreach script {{error x} {break}} {
+        lappend result [try $script on error {} - on break {} {set a "handled"}]
+    }
+    set result
+} -ok {handled handled}
+
+test try-3.11 {variables are set before the handler runs} {
+    try {error "simulated error"} on error {msg opts} {
+        list [info exists msg] [info exists opts]
+    }
+} -ok {1 1}
+
+test try-3.12 {empty variable list} {
+    try {error "simulated error"} on error {} {info exists msg}
+} -ok {0}
+
+test try-3.13 {application-defined codes} {
+    try {return -level 0 -code 7 "custom"} on 7 {result opts} {list $result [dict get $opts -code]}
+} -ok {custom 7}
+
+test try-4.1 {trap matches error code prefix} {
+    try {
+        error "simulated error" "" {MY CODE DETAIL}
+    } trap {MY CODE} {msg} {
+        list trapped $msg
+    }
+} -ok {trapped {simulated error}}
+
+test try-4.2 {trap doesn't match other codes} {
+    catch {
+        try {
+            error "simulated error" "" {MY OTHER}
+        } trap {MY CODE} {msg} {
+            list trapped $msg
+        }
+    } msg
+    set msg
+} -ok {simulated error}
+
+test try-4.3 {trap doesn't match longer patterns} {
+    catch {
+        try {
+            error "simulated error" "" {MY}
+        } trap {MY CODE} {msg} {
+            list trapped $msg
+        }
+    } msg
+    set msg
+} -ok {simulated error}
+
+test try-4.4 {empty trap pattern matches any error} {
+    try {error "simulated error"} trap {} {msg} {list trapped $msg}
+} -ok {trapped {simulated error}}
+
+test try-4.5 {trap doesn't match non-errors} {
+    try {set a 1} trap {} {msg} {list trapped $msg}
+} -ok {1}
+
+test try-5.1 {finally} {
+    set a {}
+    list [try {set b 1} finally {lappend a "finally"}] $a
+} -ok {1 finally}
+
+test try-5.2 {finally after error} {
+    set a {}
+    list [catch {try {error "simulated error"} finally {lappend a "finally"}} msg] $msg $a
+} -ok {1 {simulated error} finally}
+
+test try-5.3 {finally after handler} {
+    set a {}
+    set b [try {
+        error "simulated error"
+    } on error {msg} {
+        lappend a "handler"
+        set b "handled"
+    } finally {
+        lappend a "finally"
+    }]
+    list $b $a
+} -ok {handled {handler finally}}
+
+test try-5.4 {finally errors take precedence} {
+    catch {try {error "first"} finally {error "second"}} msg
+    set msg
+} -ok {second}
+
+test try-5.5 {finally errors take precedence over handler results} {
+    catch {try {set a 1} on ok {} {set b 2} finally {error "finally"}} msg
+    set msg
+} -ok {finally}
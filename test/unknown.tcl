@@ -0,0 +1,87 @@
+# Test Script: unknown
+
+proc unknown_cleanup {} {
+    rename unknown ""
+}
+
+test unknown-1.1 {no unknown command} {
+    nonesuch a b
+} -error {invalid command name "nonesuch"}
+
+test unknown-1.2 {unknown, called with the words} -setup {
+    proc unknown {args} { return "unknown: $args" }
+} -body {
+    nonesuch a {b c}
+} -cleanup unknown_cleanup -ok {unknown: nonesuch a {b c}}
+
+test unknown-1.3 {unknown, result used in place of the command} -setup {
+    proc unknown {name args} { return [llength $args] }
+} -body {
+    expr {[nonesuch a b c] + 1}
+} -cleanup unknown_cleanup -ok {4}
+
+test unknown-1.4 {unknown, error} -setup {
+    proc unknown {name args} { error "no such command: $name" }
+} -body {
+    nonesuch
+} -cleanup unknown_cleanup -error {no such command: nonesuch}
+
+test unknown-1.5 {unknown, abbreviation} -setup {
+    proc unknown {name args} {
+        set matches [info commands $name*]
+        if {[llength $matches] != 1} {
+            error "invalid command name \"$name\""
+        }
+        uplevel 1 [linsert $args 0 [lindex $matches 0]]
+    }
+} -body {
+    llen {a b c}
+} -cleanup unknown_cleanup -ok {3}
+
+test unknown-1.6 {unknown, auto-loading} -setup {
+    proc unknown {name args} {
+        proc $name {args} { return "loaded $args" }
+        uplevel 1 [linsert $args 0 $name]
+    }
+} -body {
+    list [myloaded a] [info procs myloaded] [myloaded b]
+} -cleanup {
+    unknown_cleanup
+    rename myloaded ""
+} -ok {{loaded a} myloaded {loaded b}}
+
+test unknown-1.7 {unknown, calls a missing command} -setup {
+    proc unknown {args} { nonesuch2 }
+} -body {
+    nonesuch
+} -cleanup unknown_cleanup -error {too many nested calls to unknown (infinite loop?) looking for "nonesuch2"}
+
+test unknown-1.8 {unknown, in a namespace} -setup {
+    proc unknown {args} { return "unknown: $args" }
+    namespace eval ::myns {}
+} -body {
+    namespace eval ::myns { nonesuch a }
+} -cleanup {
+    unknown_cleanup
+    namespace delete ::myns
+} -ok {unknown: nonesuch a}
+
+test unknown-1.9 {unknown, tail call} -setup {
+    proc unknown {args} { return "unknown: $args" }
+    proc myproc {} { tailcall nonesuch a }
+} -body {
+    myproc
+} -cleanup {
+    unknown_cleanup
+    rename myproc ""
+} -ok {unknown: nonesuch a}
+
+test unknown-1.10 {unknown, namespace unknown proc isn't used} -setup {
+    namespace eval ::myns {
+        proc unknown {args} { return "myns unknown" }
+    }
+} -body {
+    namespace eval ::myns { nonesuch }
+} -cleanup {
+    namespace delete ::myns
+} -error {invalid command name "nonesuch"}
@@ -0,0 +1,73 @@
+# Test Script: update
+
+test update-1.1 {update errors} {
+    update a b
+} -error {wrong # args: should be "update ?idletasks?"}
+
+test update-1.2 {update errors} {
+    update nonesuch
+} -error {bad option "nonesuch": must be idletasks}
+
+test update-2.1 {update with no events} {
+    update
+} -ok {}
+
+test update-2.2 {update processes due timers, then idle events} -body {
+    set ::up_log {}
+    after idle {lappend ::up_log idle}
+    after 0 {lappend ::up_log timer1}
+    after 0 {lappend ::up_log timer2}
+    update
+    set ::up_log
+} -cleanup {
+    unset ::up_log
+} -ok {timer1 timer2 idle}
+
+test update-2.3 {update doesn't wait for timers} -body {
+    set ::up_log {}
+    set id [after 10000 {lappend ::up_log late}]
+    update
+    list $::up_log [after info $id]
+} -cleanup {
+    after cancel $id
+    unset ::up_log
+} -ok {{} {{lappend ::up_log late} timer}}
+
+test update-2.4 {update leaves newly scheduled events pending} -body {
+    set ::up_log {}
+    after idle {
+        lappend ::up_log first
+        set ::up_id [after idle {lappend ::up_log second}]
+    }
+    update
+    list $::up_log [after info $::up_id]
+} -cleanup {
+    after cancel $::up_id
+    unset ::up_log ::up_id
+} -ok {first {{lappend ::up_log second} idle}}
+
+test update-3.1 {update idletasks processes only idle events} -body {
+    set ::up_log {}
+    set id [after 0 {lappend ::up_log timer}]
+    after idle {lappend ::up_log idle}
+    update idletasks
+    list $::up_log [after info $id]
+} -cleanup {
+    after cancel $id
+    unset ::up_log
+} -ok {idle {{lappend ::up_log timer} timer}}
+
+test update-4.1 {update background errors} -setup {
+    proc bgerror {msg} {
+        lappend ::up_log "bgerror: $msg"
+    }
+} -body {
+    set ::up_log {}
+    after 0 {error "simulated error"}
+    after 0 {lappend ::up_log after}
+    update
+    set ::up_log
+} -cleanup {
+    rename bgerror ""
+    unset ::up_log
+} -ok {{bgerror: simulated error} after}
@@ -0,0 +1,166 @@
+# Test Script: uplevel
+#
+# Note: test bodies are evaluated in their own scope at level 1.
+
+test uplevel-1.1 {uplevel errors} {
+    uplevel
+} -error {wrong # args: should be "uplevel ?level? command ?arg ...?"}
+
+test uplevel-1.2 {uplevel errors, bad relative level} {
+    uplevel 5 {set a}
+} -error {bad level "5"}
+
+test uplevel-1.3 {uplevel errors, bad absolute level} {
+    uplevel #5 {set a}
+} -error {bad level "#5"}
+
+test uplevel-1.4 {uplevel errors, bad level syntax} {
+    uplevel #x {set a}
+} -error {bad level "#x"}
+
+test uplevel-1.5 {uplevel errors, script error} {
+    uplevel 0 {error "simulated error"}
+} -error {simulated error}
+
+test uplevel-2.1 {uplevel default level} -setup {
+    proc setter {} { uplevel {set a inner} }
+} -body {
+    set a outer
+    setter
+    set a
+} -cleanup {
+    rename setter ""
+} -ok {inner}
+
+test uplevel-2.2 {uplevel relative level} -setup {
+    proc reader {} { uplevel 1 {set a} }
+} -body {
+    set a outer
+    reader
+} -cleanup {
+    rename reader ""
+} -ok {outer}
+
+test uplevel-2.3 {uplevel absolute level} -setup {
+    proc reader {} { uplevel #1 {set a} }
+    proc caller {} { set a caller; reader }
+} -body {
+    set a body
+    caller
+} -cleanup {
+    rename reader ""
+    rename caller ""
+} -ok {body}
+
+test uplevel-2.4 {uplevel #0 is the global scope} -setup {
+    uplevel #0 {set uplevel_g global}
+    proc reader {} { uplevel #0 {set uplevel_g} }
+} -body {
+    set uplevel_g local
+    reader
+} -cleanup {
+    rename reader ""
+    uplevel #0 {unset uplevel_g}
+} -ok {global}
+
+test uplevel-2.5 {uplevel 0 is the current scope} {
+    set a here
+    uplevel 0 {set a}
+} -ok {here}
+
+test uplevel-2.6 {uplevel concatenates its arguments} -setup {
+    proc setter {name value} { uplevel 1 set $name [list $value] }
+} -body {
+    setter a "two words"
+    set a
+} -cleanup {
+    rename setter ""
+} -ok {two words}
+
+test uplevel-2.7 {uplevel returns the script's result} -setup {
+    proc caller {} { uplevel 1 {expr {1 + 1}} }
+} -body {
+    caller
+} -ok {2}
+
+test uplevel-2.8 {info level within uplevel} -setup {
+    proc inner {} { uplevel 1 {info level} }
+    proc outer {} { list [inner] [uplevel 1 {info level}] [uplevel #0 {info level}] }
+} -body {
+    outer
+} -cleanup {
+    rename inner ""
+    rename outer ""
+} -ok {2 1 0}
+
+test uplevel-2.9 {procs called within uplevel get new scopes} -setup {
+    proc inner {} { set a inner; info level }
+    proc outer {} { set a outer; uplevel #0 inner }
+} -body {
+    set a body
+    list [outer] $a
+} -cleanup {
+    rename inner ""
+    rename outer ""
+} -ok {1 body}
+
+test uplevel-3.1 {uplevel control structure: repeat} -setup {
+    proc repeat {count body} {
+        for {set i 0} {$i < $count} {incr i} {
+            uplevel 1 $body
+        }
+    }
+} -body {
+    set list {}
+    repeat 3 {lappend list x}
+    set list
+} -cleanup {
+    rename repeat ""
+} -ok {x x x}
+
+test uplevel-3.2 {uplevel propagates break} {
+    set list {}
+    foreach i {1 2 3 4} {
+        if {$i == 3} { uplevel 0 break }
+        lappend list $i
+    }
+    list $list [catch {uplevel 0 break}]
+} -ok {{1 2} 3}
+
+test uplevel-3.3 {uplevel propagates continue} {
+    set list {}
+    foreach i {1 2 3 4} {
+        if {$i == 3} { uplevel 0 continue }
+        lappend list $i
+    }
+    list $list [catch {uplevel 0 continue}]
+} -ok {{1 2 4} 4}
+
+test uplevel-3.4 {uplevel propagates return} -setup {
+    proc returner {} { uplevel 1 {return early}; return late }
+} -body {
+    returner
+} -cleanup {
+    rename returner ""
+} -ok {early}
+
+test uplevel-3.5 {scopes are restored after an error} -setup {
+    proc failer {} { uplevel #0 {error oops} }
+} -body {
+    set a local
+    catch {failer}
+    list [info level] $a
+} -cleanup {
+    rename failer ""
+} -ok {1 local}
+
+test uplevel-2.10 {uplevel evaluates in the namespace of the level} -setup {
+    namespace eval uplevel_ns {
+        proc global_ns {} { uplevel #0 {namespace current} }
+        proc caller_ns {} { uplevel 1 {namespace current} }
+    }
+} -body {
+    list [uplevel_ns::global_ns] [namespace eval uplevel_ns2 {::uplevel_ns::caller_ns}]
+} -cleanup {
+    namespace delete uplevel_ns uplevel_ns2
+} -ok {:: ::uplevel_ns2}
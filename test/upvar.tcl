@@ -0,0 +1,170 @@
+# Test Script: upvar
+#
+# Note: test bodies are evaluated in their own scope at level 1.
+
+test upvar-1.1 {upvar errors} {
+    upvar a
+} -error {wrong # args: should be "upvar ?level? otherVar localVar ?otherVar localVar ...?"}
+
+test upvar-1.2 {upvar errors, level without pairs} {
+    upvar 1 a
+} -error {wrong # args: should be "upvar ?level? otherVar localVar ?otherVar localVar ...?"}
+
+test upvar-1.3 {upvar errors, odd number of names} {
+    upvar a b c
+} -error {wrong # args: should be "upvar ?level? otherVar localVar ?otherVar localVar ...?"}
+
+test upvar-1.4 {upvar errors, bad level} {
+    upvar 5 a b
+} -error {bad level "5"}
+
+test upvar-1.5 {upvar errors, local variable exists} {
+    set b 1
+    upvar a b
+} -error {variable "b" already exists}
+
+test upvar-1.6 {upvar errors, link to itself} {
+    upvar 0 a a
+} -error {can't upvar from variable to itself}
+
+test upvar-1.7 {upvar errors, local array element} {
+    upvar a b(1)
+} -error {bad variable name "b(1)": upvar won't create a scalar variable that looks like an array element}
+
+test upvar-2.1 {upvar reads and writes the caller's variable} -setup {
+    proc double {name} {
+        upvar $name x
+        set x [expr {$x * 2}]
+    }
+} -body {
+    set a 5
+    double a
+    set a
+} -cleanup {
+    rename double ""
+} -ok {10}
+
+test upvar-2.2 {upvar with relative level} -setup {
+    proc inner {} { upvar 2 a x; set x inner }
+    proc outer {} { set a outer; inner; set a }
+} -body {
+    set a body
+    list [outer] $a
+} -cleanup {
+    rename inner ""
+    rename outer ""
+} -ok {outer inner}
+
+test upvar-2.3 {upvar with absolute level} -setup {
+    proc inner {} { upvar #1 a x; set x inner }
+    proc outer {} { set a outer; inner; set a }
+} -body {
+    set a body
+    list [outer] $a
+} -cleanup {
+    rename inner ""
+    rename outer ""
+} -ok {outer inner}
+
+test upvar-2.4 {upvar #0 links to a global} -setup {
+    proc setter {} { upvar #0 upvar_g x; set x global }
+} -body {
+    setter
+    uplevel #0 {set upvar_g}
+} -cleanup {
+    rename setter ""
+    uplevel #0 {unset upvar_g}
+} -ok {global}
+
+test upvar-2.5 {upvar creates the linked variable lazily} -setup {
+    proc linker {} { upvar a x; info exists x }
+    proc setter {} { upvar a x; set x new }
+} -body {
+    set r1 [linker]
+    set r2 [info exists a]
+    setter
+    list $r1 $r2 $a
+} -cleanup {
+    rename linker ""
+    rename setter ""
+} -ok {0 0 new}
+
+test upvar-2.6 {upvar and unset} -setup {
+    proc unsetter {} { upvar a x; unset x; info exists x }
+} -body {
+    set a 1
+    list [unsetter] [info exists a]
+} -cleanup {
+    rename unsetter ""
+} -ok {0 0}
+
+test upvar-2.7 {upvar and info exists} -setup {
+    proc exists {} { upvar a x; info exists x }
+} -body {
+    set a 1
+    exists
+} -cleanup {
+    rename exists ""
+} -ok {1}
+
+test upvar-2.8 {upvar multiple pairs} -setup {
+    proc swap {aName bName} {
+        upvar $aName a $bName b
+        set tmp $a
+        set a $b
+        set b $tmp
+        return
+    }
+} -body {
+    set x 1
+    set y 2
+    swap x y
+    list $x $y
+} -cleanup {
+    rename swap ""
+} -ok {2 1}
+
+test upvar-2.9 {upvar to an array} -setup {
+    proc setter {name} { upvar $name arr; set arr(b) 2 }
+} -body {
+    set a(a) 1
+    setter a
+    lsort [array names a]
+} -cleanup {
+    rename setter ""
+} -ok {a b}
+
+test upvar-2.10 {upvar in the same scope} {
+    set a 1
+    upvar 0 a b
+    set b 2
+    set a
+} -ok {2}
+
+test upvar-2.11 {upvar and lappend} -setup {
+    proc collect {listName args} {
+        upvar $listName list
+        foreach item $args {
+            lappend list $item
+        }
+        return
+    }
+} -body {
+    collect result a b
+    collect result c
+    set result
+} -cleanup {
+    rename collect ""
+} -ok {a b c}
+
+test upvar-2.12 {upvar links a variable that's linked by upvar} -setup {
+    proc inner {} { upvar x y; set y inner }
+    proc outer {} { upvar a x; inner }
+} -body {
+    set a body
+    outer
+    set a
+} -cleanup {
+    rename inner ""
+    rename outer ""
+} -ok {inner}
@@ -0,0 +1,86 @@
+# Test Script: variable
+#
+# Note: test bodies are evaluated in their own scope at level 1.
+
+test variable-1.1 {variable errors} {
+    variable
+} -error {wrong # args: should be "variable ?name value...? name ?value?"}
+
+test variable-1.2 {variable errors, array element} {
+    variable a(1) 2
+} -error {can't define "a(1)": name refers to an element in an array}
+
+test variable-1.3 {variable errors, local variable exists} {
+    set a 1
+    variable a
+} -error {variable "a" already exists}
+
+test variable-2.1 {variable links to a global} -setup {
+    uplevel #0 {set variable_g global}
+} -body {
+    variable variable_g
+    set variable_g
+} -cleanup {
+    uplevel #0 {unset variable_g}
+} -ok {global}
+
+test variable-2.2 {variable sets the global} -body {
+    variable variable_g new
+    list $variable_g [uplevel #0 {set variable_g}]
+} -cleanup {
+    uplevel #0 {unset variable_g}
+} -ok {new new}
+
+test variable-2.3 {variable multiple pairs with trailing name} -setup {
+    uplevel #0 {set variable_c three}
+} -body {
+    variable variable_a 1 variable_b 2 variable_c
+    list $variable_a $variable_b $variable_c [info locals variable_*]
+} -cleanup {
+    uplevel #0 {unset variable_a variable_b variable_c}
+} -ok {1 2 three {}}
+
+test variable-2.4 {variable multiple pairs} -body {
+    variable variable_a 1 variable_b 2
+    uplevel #0 {list $variable_a $variable_b}
+} -cleanup {
+    uplevel #0 {unset variable_a variable_b}
+} -ok {1 2}
+
+test variable-2.5 {variable at global scope} -body {
+    uplevel #0 {variable variable_a 1}
+    uplevel #0 {list $variable_a [info level]}
+} -cleanup {
+    uplevel #0 {unset variable_a}
+} -ok {1 0}
+
+test variable-2.6 {variable without value doesn't create the variable} -body {
+    variable variable_a
+    list [info exists variable_a] [uplevel #0 {info exists variable_a}]
+} -ok {0 0}
+
+test variable-2.7 {variable in a proc} -setup {
+    proc counter {} {
+        variable variable_count
+        incr variable_count
+    }
+} -body {
+    counter
+    counter
+    uplevel #0 {set variable_count}
+} -cleanup {
+    rename counter ""
+    uplevel #0 {unset variable_count}
+} -ok {2}
+
+test variable-2.8 {variable in a proc's uplevel #0} -setup {
+    proc setter {} {
+        uplevel #0 {variable variable_a 1}
+    }
+} -body {
+    setter
+    uplevel #0 {set variable_a}
+} -cleanup {
+    rename setter ""
+    uplevel #0 {unset variable_a}
+} -ok {1}
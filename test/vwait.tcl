@@ -0,0 +1,88 @@
+# Test Script: vwait
+
+test vwait-1.1 {vwait errors} {
+    vwait
+} -error {wrong # args: should be "vwait name"}
+
+test vwait-1.2 {vwait with no events} {
+    vwait ::nonesuch
+} -error {can't wait for variable "::nonesuch": would wait forever}
+
+test vwait-1.3 {vwait for a variable that isn't written} -body {
+    after 10 {set ::vw_other 1}
+    vwait ::vw_never
+} -cleanup {
+    unset ::vw_other
+} -error {can't wait for variable "::vw_never": would wait forever}
+
+test vwait-2.1 {vwait processes events until the variable is written} -body {
+    set ::vw_log {}
+    after 30 {set ::vw_done 1}
+    after 10 {lappend ::vw_log 10}
+    after 20 {lappend ::vw_log 20}
+    vwait ::vw_done
+    list $::vw_log $::vw_done
+} -cleanup {
+    unset ::vw_log ::vw_done
+} -ok {{10 20} 1}
+
+test vwait-2.2 {vwait leaves later events pending} -body {
+    after 0 {set ::vw_done 1}
+    set id [after 10000 {set ::vw_done 2}]
+    vwait ::vw_done
+    list $::vw_done [after info $id]
+} -cleanup {
+    after cancel $id
+    unset ::vw_done
+} -ok {1 {{set ::vw_done 2} timer}}
+
+test vwait-2.3 {vwait names global variables} -setup {
+    proc vwproc {} {
+        after 0 {set vw_done "global"}
+        set vw_done "local"
+        vwait vw_done
+        list $vw_done $::vw_done
+    }
+} -body {
+    vwproc
+} -cleanup {
+    rename vwproc ""
+    unset ::vw_done
+} -ok {local global}
+
+test vwait-2.4 {vwait notices unsets} -body {
+    set ::vw_done 1
+    after 0 {unset ::vw_done}
+    vwait ::vw_done
+    info exists ::vw_done
+} -ok {0}
+
+test vwait-2.5 {vwait notices array element writes} -body {
+    after 0 {set ::vw_array(x) 1}
+    vwait ::vw_array
+    array get ::vw_array
+} -cleanup {
+    unset ::vw_array
+} -ok {x 1}
+
+test vwait-2.6 {vwait processes idle events} -body {
+    after idle {set ::vw_done "idle"}
+    vwait ::vw_done
+    set ::vw_done
+} -cleanup {
+    unset ::vw_done
+} -ok {idle}
+
+test vwait-3.1 {nested vwaits unwind in order} -body {
+    set ::vw_log {}
+    after 0 {
+        after 10 {set ::vw_outer 1}
+        vwait ::vw_inner
+        lappend ::vw_log "inner"
+    }
+    after 30 {set ::vw_inner 1}
+    vwait ::vw_outer
+    lappend ::vw_log "outer"
+} -cleanup {
+    unset ::vw_log ::vw_outer ::vw_inner
+} -ok {inner outer}